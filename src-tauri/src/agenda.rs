@@ -0,0 +1,82 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::error::BouldyError;
+use crate::journal;
+use crate::pomodoro::PomodoroSettings;
+use crate::timesheet;
+use crate::todos::{self, TodoItem};
+
+#[derive(Serialize, Clone, Debug)]
+pub struct AgendaFocus {
+    // Work pomodoros finished that day
+    pub completed: usize,
+    #[serde(rename = "focusMinutes")]
+    pub focus_minutes: i64,
+    pub goal: Option<u32>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct AgendaJournal {
+    pub exists: bool,
+    // Where the entry is, or would be created
+    pub path: String,
+    // Has more than the blank template
+    pub written: bool,
+}
+
+/// Everything the Today screen shows for one day.
+#[derive(Serialize, Clone, Debug)]
+pub struct Agenda {
+    pub date: String,
+    // Open todos due that day, timed ones in order before the rest
+    pub due: Vec<TodoItem>,
+    // Open todos due on an earlier day, oldest first
+    pub overdue: Vec<TodoItem>,
+    pub focus: AgendaFocus,
+    pub journal: AgendaJournal,
+}
+
+pub fn get_agenda(
+    vault_path: &str,
+    todos_list: &[TodoItem],
+    pomodoro: &PomodoroSettings,
+    date: NaiveDate,
+) -> Result<Agenda, BouldyError> {
+    let open = todos_list.iter().filter(|todo| !todo.completed);
+    let mut due: Vec<TodoItem> = open
+        .clone()
+        .filter(|todo| todo.due().is_some_and(|due| due.date() == date))
+        .cloned()
+        .collect();
+    let mut overdue: Vec<TodoItem> = open
+        .filter(|todo| todo.due().is_some_and(|due| due.date() < date))
+        .cloned()
+        .collect();
+    todos::sort_by_due(&mut due);
+    todos::sort_by_due(&mut overdue);
+
+    let sessions: Vec<i64> = timesheet::load_pomodoros(vault_path)
+        .into_iter()
+        .filter(|(start, _)| start.date() == date)
+        .map(|(start, end)| (end - start).num_minutes().max(0))
+        .collect();
+
+    let entry = journal::find_entry(vault_path, date);
+
+    Ok(Agenda {
+        date: date.format("%Y-%m-%d").to_string(),
+        due,
+        overdue,
+        focus: AgendaFocus {
+            completed: sessions.len(),
+            focus_minutes: sessions.iter().sum(),
+            goal: pomodoro.daily_goal,
+        },
+        journal: AgendaJournal {
+            exists: entry.day.is_some(),
+            path: entry.path.to_string_lossy().to_string(),
+            written: entry.day.is_some_and(|day| day.is_written()),
+        },
+    })
+}
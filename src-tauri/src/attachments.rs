@@ -0,0 +1,310 @@
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::UNIX_EPOCH;
+
+use crate::error::BouldyError;
+use crate::{
+    boards, canvas, fsutil, habits, inbox, snippets, someday, text, timesheet, todo_store, vault,
+    vault_config, vault_lock,
+};
+
+// Files and folders in the vault root that bouldy keeps its own data in.
+// Obsidian's attachments folder may be the vault root, where none of these
+// must ever be mistaken for attachments.
+const OWNED_FILES: &[&str] = &[
+    "todo.txt",
+    todo_store::CONFLICTS_FILE,
+    someday::SOMEDAY_FILE,
+    inbox::INBOX_FILE,
+    habits::HABITS_FILE,
+    timesheet::TIMESHEET_FILE,
+];
+const OWNED_DIRS: &[&str] = &[
+    canvas::CANVAS_DIR,
+    boards::BOARDS_DIR,
+    snippets::SNIPPETS_DIR,
+    "prompts",
+];
+
+#[derive(Serialize, Clone, Debug)]
+pub struct OrphanedAttachment {
+    pub path: String,
+    pub size: u64,
+    pub modified: u64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct OrphanReport {
+    pub files: Vec<OrphanedAttachment>,
+    #[serde(rename = "totalSize")]
+    pub total_size: u64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct CleanupReport {
+    pub deleted: Vec<String>,
+    // Still referenced, outside the attachments folder, or already gone
+    pub skipped: Vec<String>,
+    #[serde(rename = "freedBytes")]
+    pub freed_bytes: u64,
+}
+
+fn link_target_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    // ](target), ](<target>), ](target "title") and <img src="target">
+    REGEX.get_or_init(|| {
+        Regex::new(r#"\]\(\s*(?:<([^>]+)>|([^)\s]+))|src\s*=\s*["']([^"']+)["']"#).unwrap()
+    })
+}
+
+fn wikilink_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    // [[file.png]] and ![[file.png|300]]
+    REGEX.get_or_init(|| Regex::new(r"\[\[([^\]|#]+)").unwrap())
+}
+
+/// What the vault's notes and canvases point at: canonical paths from
+/// markdown links, `<img>` tags and canvas file nodes, and file names from
+/// wikilinks, which Obsidian resolves by name alone.
+struct References {
+    paths: HashSet<PathBuf>,
+    names: HashSet<String>,
+}
+
+impl References {
+    fn collect(vault_path: &str, files: &[PathBuf]) -> Self {
+        let vault = Path::new(vault_path);
+        let mut references = References {
+            paths: HashSet::new(),
+            names: HashSet::new(),
+        };
+
+        for file in files.iter().filter(|file| canvas::is_canvas_file(file)) {
+            let Some(canvas) = fs::read_to_string(fsutil::long_path(file))
+                .ok()
+                .and_then(|content| serde_json::from_str::<canvas::Canvas>(&content).ok())
+            else {
+                continue;
+            };
+            for node in canvas.nodes {
+                if let Some(Ok(canonical)) =
+                    node.file.map(|target| vault.join(target).canonicalize())
+                {
+                    references.paths.insert(canonical);
+                }
+            }
+        }
+
+        for file in files.iter().filter(|file| vault::is_markdown_file(file)) {
+            let Ok(content) = fs::read_to_string(fsutil::long_path(file)) else {
+                continue;
+            };
+            let note_dir = file.parent().unwrap_or(vault);
+
+            for capture in wikilink_regex().captures_iter(&content) {
+                let target = capture[1].trim();
+                let name = target.rsplit('/').next().unwrap_or(target);
+                references.names.insert(text::fold(name));
+            }
+            for capture in link_target_regex().captures_iter(&content) {
+                let Some(target) = capture.get(1).or(capture.get(2)).or(capture.get(3)) else {
+                    continue;
+                };
+                let target = target.as_str();
+                if target.contains("://") || target.starts_with('#') {
+                    continue;
+                }
+                let target = target.split(['#', '?']).next().unwrap_or(target);
+                let decoded = percent_encoding::percent_decode_str(target).decode_utf8_lossy();
+                // Relative to the note, or to the vault for root-relative links
+                for base in [note_dir, vault] {
+                    let candidate = base.join(decoded.trim_start_matches('/'));
+                    if let Ok(canonical) = candidate.canonicalize() {
+                        references.paths.insert(canonical);
+                    }
+                }
+            }
+        }
+        references
+    }
+
+    fn contains(&self, attachment: &Path) -> bool {
+        let by_name = attachment
+            .file_name()
+            .is_some_and(|name| self.names.contains(&text::fold(&name.to_string_lossy())));
+        by_name
+            || attachment
+                .canonicalize()
+                .is_ok_and(|canonical| self.paths.contains(&canonical))
+    }
+}
+
+// Attachments are anything with an extension other than .md
+fn is_attachment_target(target: &str) -> bool {
+    Path::new(target)
+        .extension()
+        .is_some_and(|extension| !extension.eq_ignore_ascii_case("md"))
+}
+
+/// Attachments `note` links or embeds that don't exist, as written in the
+/// note. The frontend re-checks when an `asset:created` event arrives.
+pub fn broken_attachments(vault_path: &str, note: &Path) -> Result<Vec<String>, BouldyError> {
+    let content = fs::read_to_string(fsutil::long_path(note))
+        .map_err(|e| BouldyError::io("Failed to read note", e))?;
+    let vault = Path::new(vault_path);
+    let note_dir = note.parent().unwrap_or(vault);
+
+    let mut broken = Vec::new();
+    let mut names: Option<HashSet<String>> = None;
+    for capture in wikilink_regex().captures_iter(&content) {
+        let target = capture[1].trim();
+        if !is_attachment_target(target) {
+            continue;
+        }
+        // Wikilinks resolve by name anywhere in the vault
+        let names = names.get_or_insert_with(|| {
+            let mut files = Vec::new();
+            vault::walk_files(vault, &mut files);
+            files
+                .iter()
+                .filter_map(|file| file.file_name())
+                .map(|name| text::fold(&name.to_string_lossy()))
+                .collect()
+        });
+        let name = target.rsplit('/').next().unwrap_or(target);
+        if !names.contains(&text::fold(name)) && !broken.iter().any(|b| b == target) {
+            broken.push(target.to_string());
+        }
+    }
+    for capture in link_target_regex().captures_iter(&content) {
+        let Some(target) = capture.get(1).or(capture.get(2)).or(capture.get(3)) else {
+            continue;
+        };
+        let target = target.as_str();
+        let path = target.split(['#', '?']).next().unwrap_or(target);
+        if target.contains("://") || !is_attachment_target(path) {
+            continue;
+        }
+        let decoded = percent_encoding::percent_decode_str(path).decode_utf8_lossy();
+        let exists = [note_dir, vault]
+            .iter()
+            .any(|base| base.join(decoded.trim_start_matches('/')).exists());
+        if !exists && !broken.iter().any(|b| b == target) {
+            broken.push(target.to_string());
+        }
+    }
+    Ok(broken)
+}
+
+fn is_owned(vault: &Path, path: &Path) -> bool {
+    let Ok(relative) = path.strip_prefix(vault) else {
+        return false;
+    };
+    let mut components = relative.iter().map(|c| c.to_string_lossy());
+    let first = components.next().unwrap_or_default();
+    if components.next().is_none() {
+        OWNED_FILES.contains(&first.as_ref())
+    } else {
+        OWNED_DIRS.contains(&first.as_ref())
+    }
+}
+
+// Non-markdown files in the attachments folder that no note links to
+fn orphans(vault_path: &str) -> Result<Vec<PathBuf>, BouldyError> {
+    let config = vault_config::load(vault_path)?;
+    let attachments_dir = config.attachments_dir(vault_path, None);
+    let Ok(attachments_dir) = attachments_dir.canonicalize() else {
+        return Ok(Vec::new());
+    };
+    let vault = Path::new(vault_path)
+        .canonicalize()
+        .map_err(|e| BouldyError::io("Invalid vault path", e))?;
+
+    // Everything unlinked there would count, notes' own files included
+    let notes_dir = vault::notes_dir(vault_path);
+    let notes_dir = notes_dir.canonicalize().unwrap_or(notes_dir);
+    if attachments_dir == vault || notes_dir.starts_with(&attachments_dir) {
+        return Err(BouldyError::invalid_input("The attachments folder holds the whole vault or its notes; set a folder of its own to find unused attachments"));
+    }
+
+    let mut files = Vec::new();
+    vault::walk_files(Path::new(vault_path), &mut files);
+    let references = References::collect(vault_path, &files);
+
+    let mut attachments = Vec::new();
+    vault::walk_files(&attachments_dir, &mut attachments);
+    Ok(attachments
+        .into_iter()
+        .filter(|path| {
+            !vault::is_markdown_file(path) && !is_owned(&vault, path) && !references.contains(path)
+        })
+        .collect())
+}
+
+/// Files in the attachments folder (`assets/` unless configured otherwise)
+/// not linked or embedded from any note, largest first.
+pub fn find_orphaned(vault_path: &str) -> Result<OrphanReport, BouldyError> {
+    let mut files: Vec<OrphanedAttachment> = orphans(vault_path)?
+        .into_iter()
+        .filter_map(|path| {
+            let metadata = fs::metadata(&path).ok()?;
+            Some(OrphanedAttachment {
+                path: vault::path_to_string(&path).ok()?,
+                size: metadata.len(),
+                modified: metadata
+                    .modified()
+                    .ok()
+                    .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                    .map(|duration| duration.as_secs())
+                    .unwrap_or_default(),
+            })
+        })
+        .collect();
+    files.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+
+    let total_size = files.iter().map(|file| file.size).sum();
+    Ok(OrphanReport { files, total_size })
+}
+
+/// Delete the given attachments, re-checking each is still an orphan so a
+/// link added since the scan keeps its file.
+pub fn delete_orphaned(vault_path: &str, paths: &[String]) -> Result<CleanupReport, BouldyError> {
+    let _lock = vault_lock::acquire(vault_path)?;
+    let orphans: HashSet<PathBuf> = orphans(vault_path)?
+        .into_iter()
+        .filter_map(|path| path.canonicalize().ok())
+        .collect();
+
+    let mut report = CleanupReport {
+        deleted: Vec::new(),
+        skipped: Vec::new(),
+        freed_bytes: 0,
+    };
+    for path in paths {
+        let orphan = Path::new(path)
+            .canonicalize()
+            .ok()
+            .filter(|canonical| orphans.contains(canonical));
+        let Some(orphan) = orphan else {
+            report.skipped.push(path.clone());
+            continue;
+        };
+
+        let size = fs::metadata(&orphan).map(|m| m.len()).unwrap_or_default();
+        match fs::remove_file(fsutil::long_path(&orphan)) {
+            Ok(()) => {
+                report.freed_bytes += size;
+                report.deleted.push(path.clone());
+            }
+            Err(e) => {
+                tracing::warn!("Failed to delete attachment {} - {}", path, e);
+                report.skipped.push(path.clone());
+            }
+        }
+    }
+    Ok(report)
+}
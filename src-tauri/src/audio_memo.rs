@@ -0,0 +1,313 @@
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, Sample, SampleFormat, SizedSample};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
+
+use crate::error::BouldyError;
+use crate::file_drop::unique_destination;
+use crate::llm::{self, LlmProvider};
+use crate::network::{self, NetworkConfig};
+use crate::{fsutil, vault_config, vault_lock};
+
+pub const AUDIO_DIR: &str = "audio";
+
+// The OpenAI transcription endpoint's upload limit
+const MAX_TRANSCRIPTION_BYTES: u64 = 25 * 1024 * 1024;
+
+type Writer = Arc<Mutex<Option<hound::WavWriter<BufWriter<File>>>>>;
+
+/// Speech-to-text for audio memos: OpenAI's Whisper API, or any server with
+/// the same `/audio/transcriptions` endpoint (e.g. a local whisper.cpp).
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct TranscriptionSettings {
+    // Unset uses whisper-1
+    #[serde(default)]
+    pub model: Option<String>,
+    // Unset uses OpenAI with the API key saved for it
+    #[serde(rename = "baseUrl", default)]
+    pub base_url: Option<String>,
+}
+
+impl TranscriptionSettings {
+    pub fn validate(&self) -> Result<(), BouldyError> {
+        if let Some(ref base_url) = self.base_url {
+            let url = tauri::Url::parse(base_url).map_err(|e| {
+                BouldyError::invalid_input(format!(
+                    "Invalid transcription URL '{}': {}",
+                    base_url, e
+                ))
+            })?;
+            if !matches!(url.scheme(), "http" | "https") {
+                return Err(BouldyError::invalid_input(format!(
+                    "Transcription URL must be http or https: '{}'",
+                    base_url
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct AudioMemo {
+    pub path: String,
+    #[serde(rename = "durationSeconds")]
+    pub duration_seconds: u64,
+    pub transcript: Option<String>,
+    // The note the link (and transcript) were appended to
+    #[serde(rename = "notePath")]
+    pub note_path: Option<String>,
+}
+
+struct Recording {
+    path: PathBuf,
+    started: Instant,
+    stop: mpsc::Sender<()>,
+    thread: JoinHandle<()>,
+}
+
+/// Managed state: the memo being recorded, if any.
+#[derive(Default)]
+pub struct AudioRecorder {
+    recording: Mutex<Option<Recording>>,
+}
+
+// Mono 16-bit keeps files small enough to upload for transcription
+fn write_samples<T>(data: &[T], channels: usize, writer: &Writer)
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+{
+    let mut guard = writer.lock().unwrap();
+    let Some(writer) = guard.as_mut() else {
+        return;
+    };
+    for frame in data.chunks(channels) {
+        let sum: f32 = frame.iter().map(|&s| f32::from_sample(s)).sum();
+        let sample = i16::from_sample(sum / frame.len() as f32);
+        if writer.write_sample(sample).is_err() {
+            return;
+        }
+    }
+}
+
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    writer: Writer,
+) -> Result<cpal::Stream, cpal::BuildStreamError>
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+{
+    let channels = usize::from(config.channels);
+    device.build_input_stream(
+        config,
+        move |data: &[T], _: &cpal::InputCallbackInfo| write_samples(data, channels, &writer),
+        |e| tracing::warn!("Audio input error - {}", e),
+        None,
+    )
+}
+
+// Runs on its own thread: cpal streams can't move between threads
+fn record(path: &Path, ready: mpsc::Sender<Result<(), BouldyError>>, stop: mpsc::Receiver<()>) {
+    let setup = || -> Result<(cpal::Stream, Writer), BouldyError> {
+        let device = cpal::default_host()
+            .default_input_device()
+            .ok_or_else(|| BouldyError::not_found("No microphone found"))?;
+        let supported = device
+            .default_input_config()
+            .map_err(|e| format!("Failed to read microphone settings: {}", e))?;
+        let format = supported.sample_format();
+        let config: cpal::StreamConfig = supported.into();
+
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: config.sample_rate.0,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let writer = hound::WavWriter::create(path, spec)
+            .map_err(|e| format!("Failed to create audio file: {}", e))?;
+        let writer: Writer = Arc::new(Mutex::new(Some(writer)));
+
+        let stream = match format {
+            SampleFormat::F32 => build_stream::<f32>(&device, &config, writer.clone()),
+            SampleFormat::I16 => build_stream::<i16>(&device, &config, writer.clone()),
+            SampleFormat::U16 => build_stream::<u16>(&device, &config, writer.clone()),
+            SampleFormat::I32 => build_stream::<i32>(&device, &config, writer.clone()),
+            other => return Err(format!("Unsupported microphone format {:?}", other).into()),
+        }
+        .map_err(|e| format!("Failed to open microphone: {}", e))?;
+        stream
+            .play()
+            .map_err(|e| format!("Failed to start recording: {}", e))?;
+        Ok((stream, writer))
+    };
+
+    let (stream, writer) = match setup() {
+        Ok(started) => started,
+        Err(e) => {
+            let _ = fs::remove_file(path);
+            let _ = ready.send(Err(e));
+            return;
+        }
+    };
+    let _ = ready.send(Ok(()));
+
+    // Also returns if the sender is dropped
+    let _ = stop.recv();
+    drop(stream);
+    let finished = writer.lock().unwrap().take();
+    if let Some(writer) = finished {
+        if let Err(e) = writer.finalize() {
+            tracing::warn!("Failed to finish audio memo - {}", e);
+        }
+    }
+}
+
+impl AudioRecorder {
+    /// Start recording from the default microphone into the vault's
+    /// attachments folder, under `audio/`.
+    pub fn start(&self, vault_path: &str, note: Option<&Path>) -> Result<String, BouldyError> {
+        let mut recording = self.recording.lock().unwrap();
+        if recording.is_some() {
+            return Err(BouldyError::conflict("Already recording"));
+        }
+
+        let dir = vault_config::load(vault_path)?
+            .attachments_dir(vault_path, note)
+            .join(AUDIO_DIR);
+        fs::create_dir_all(&dir)
+            .map_err(|e| BouldyError::io("Failed to create audio folder", e))?;
+        let name = format!(
+            "memo-{}.wav",
+            chrono::Local::now().format("%Y-%m-%d-%H%M%S")
+        );
+        let path = unique_destination(&dir, &name);
+
+        let (ready_tx, ready_rx) = mpsc::channel();
+        let (stop_tx, stop_rx) = mpsc::channel();
+        let thread_path = path.clone();
+        let thread = thread::spawn(move || record(&thread_path, ready_tx, stop_rx));
+        ready_rx
+            .recv()
+            .map_err(|_| "Recorder stopped unexpectedly")??;
+
+        *recording = Some(Recording {
+            path: path.clone(),
+            started: Instant::now(),
+            stop: stop_tx,
+            thread,
+        });
+        Ok(path.to_string_lossy().to_string())
+    }
+
+    /// Stop recording and return the finished file and its length.
+    pub fn stop(&self) -> Result<(PathBuf, u64), BouldyError> {
+        let recording = self
+            .recording
+            .lock()
+            .unwrap()
+            .take()
+            .ok_or_else(|| BouldyError::not_found("Not recording"))?;
+        let _ = recording.stop.send(());
+        recording
+            .thread
+            .join()
+            .map_err(|_| "Recorder stopped unexpectedly")?;
+        Ok((recording.path, recording.started.elapsed().as_secs()))
+    }
+}
+
+/// Send a recording to the transcription service and return its text.
+pub async fn transcribe(
+    network_config: &NetworkConfig,
+    settings: &TranscriptionSettings,
+    path: &Path,
+) -> Result<String, BouldyError> {
+    let audio = fs::read(path).map_err(|e| BouldyError::io("Failed to read audio memo", e))?;
+    if audio.len() as u64 > MAX_TRANSCRIPTION_BYTES {
+        return Err(BouldyError::invalid_input(
+            "Recording is too long to transcribe",
+        ));
+    }
+
+    let base_url = settings
+        .base_url
+        .as_deref()
+        .unwrap_or("https://api.openai.com/v1")
+        .trim_end_matches('/');
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let file = reqwest::multipart::Part::bytes(audio)
+        .file_name(file_name)
+        .mime_str("audio/wav")
+        .map_err(|e| e.to_string())?;
+    let form = reqwest::multipart::Form::new()
+        .text(
+            "model",
+            settings
+                .model
+                .clone()
+                .unwrap_or_else(|| "whisper-1".to_string()),
+        )
+        .part("file", file);
+
+    let mut request = network::http_client(network_config)?
+        .post(format!("{}/audio/transcriptions", base_url))
+        .multipart(form);
+    // Self-hosted servers usually don't need a key
+    match llm::api_key(LlmProvider::OpenAi) {
+        Ok(key) => request = request.bearer_auth(key),
+        Err(e) if settings.base_url.is_none() => return Err(e),
+        Err(_) => {}
+    }
+
+    let response = request
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Transcription failed: {}", e))?;
+    let body: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid transcription response: {}", e))?;
+    body["text"]
+        .as_str()
+        .map(|text| text.trim().to_string())
+        .ok_or_else(|| "Transcription response has no text".into())
+}
+
+/// Append a link to the memo, and its transcript if there is one, to the
+/// end of `note`.
+pub fn append_to_note(
+    vault_path: &str,
+    note: &Path,
+    audio: &Path,
+    transcript: Option<&str>,
+) -> Result<(), BouldyError> {
+    let config = vault_config::load(vault_path)?;
+    let note_dir = note.parent().unwrap_or(Path::new(vault_path));
+    let mut addition = format!("\n{}\n", config.attachment_link(audio, note_dir, false));
+    if let Some(transcript) = transcript.filter(|t| !t.is_empty()) {
+        addition.push_str(&format!("\n> {}\n", transcript.replace('\n', "\n> ")));
+    }
+
+    let _lock = vault_lock::acquire(vault_path)?;
+    let mut content =
+        fs::read_to_string(note).map_err(|e| BouldyError::io("Failed to read note", e))?;
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    content.push_str(&addition);
+    fsutil::write_text_preserving(note, &content)
+        .map_err(|e| BouldyError::io("Failed to write note", e))
+}
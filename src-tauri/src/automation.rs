@@ -0,0 +1,490 @@
+use chrono::{NaiveDate, NaiveTime};
+use rhai::{CallFnOptions, Dynamic, Engine, EvalAltResult, Scope, AST};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::BouldyError;
+use crate::file_drop::unique_destination;
+use crate::todos::TodoItem;
+use crate::{fsutil, markdown, settings, todo_store, todos, vault, vault_lock};
+
+// Rhai scripts in `<vault>/.bouldy/scripts/*.rhai`. A script's top level runs
+// when it's loaded and can schedule functions; hooks are plain functions:
+//
+//   daily("07:30", "tidy_up");
+//
+//   fn tidy_up() { archive_completed_todos(); }
+//
+//   fn on_note_saved(path) {
+//       if read_note(path).contains("meeting") && !note_tags(path).contains("meeting") {
+//           write_note(path, read_note(path) + "\n#meeting\n");
+//       }
+//   }
+//
+//   fn on_todo_completed(todo) { log(`done: ${todo.title}`); }
+//
+// Note paths are relative to the notes folder. Scripts get no filesystem or
+// network access beyond the functions registered below.
+pub const SCRIPTS_DIR: &str = "scripts";
+const SCRIPT_EXTENSION: &str = "rhai";
+const STATE_FILE: &str = "automation.json";
+
+const ON_NOTE_SAVED: &str = "on_note_saved";
+const ON_TODO_COMPLETED: &str = "on_todo_completed";
+
+// Keeps a runaway loop from hanging the backend
+const MAX_OPERATIONS: u64 = 10_000_000;
+const MAX_STRING_BYTES: usize = 16 * 1024 * 1024;
+const MAX_COLLECTION_ITEMS: usize = 100_000;
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Serialize, Clone, Debug)]
+pub struct DailyHook {
+    // HH:MM, local time
+    pub time: String,
+    pub function: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ScriptInfo {
+    pub name: String,
+    pub daily: Vec<DailyHook>,
+    // Which of on_note_saved / on_todo_completed it defines
+    pub hooks: Vec<String>,
+    // Why the script isn't running, if it isn't
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct AutomationState {
+    // "<script>:<function>" -> the last date its daily hook ran
+    #[serde(default, rename = "lastRun")]
+    last_run: HashMap<String, String>,
+}
+
+struct Script {
+    info: ScriptInfo,
+    ast: Option<AST>,
+}
+
+struct Runtime {
+    vault_path: String,
+    engine: Engine,
+    scripts: Vec<Script>,
+}
+
+/// Managed state: the scripts of the open vault.
+#[derive(Default)]
+pub struct ScriptHost {
+    runtime: Mutex<Option<Runtime>>,
+}
+
+pub fn scripts_dir(vault_path: &str) -> PathBuf {
+    Path::new(vault_path).join(".bouldy").join(SCRIPTS_DIR)
+}
+
+fn state_path(vault_path: &str) -> PathBuf {
+    Path::new(vault_path).join(".bouldy").join(STATE_FILE)
+}
+
+fn load_state(vault_path: &str) -> AutomationState {
+    fs::read_to_string(state_path(vault_path))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(vault_path: &str, state: &AutomationState) -> Result<(), BouldyError> {
+    let content = serde_json::to_string_pretty(state)
+        .map_err(|e| format!("Failed to serialize automation state: {}", e))?;
+    let _lock = vault_lock::acquire(vault_path)?;
+    fsutil::write_atomic(&state_path(vault_path), content)
+        .map_err(|e| BouldyError::io("Failed to write automation state", e))
+}
+
+// === Vault API ===
+
+fn script_error(e: impl std::fmt::Display) -> Box<EvalAltResult> {
+    e.to_string().into()
+}
+
+// A note inside the notes folder, given relative to it
+fn note_path(vault_path: &str, path: &str) -> Result<PathBuf, BouldyError> {
+    let notes_dir = vault::notes_dir(vault_path);
+    let file = vault::validate_path_in_vault(vault_path, &notes_dir.join(path).to_string_lossy())?;
+    let inside_notes = notes_dir
+        .canonicalize()
+        .is_ok_and(|notes_dir| file.starts_with(notes_dir));
+    if !inside_notes || !vault::is_markdown_file(&file) {
+        return Err(BouldyError::invalid_path(format!("Not a note: {}", path)));
+    }
+    Ok(file)
+}
+
+fn relative_note_path(vault_path: &str, path: &Path) -> String {
+    let notes_dir = vault::notes_dir(vault_path);
+    let relative = path.strip_prefix(&notes_dir).unwrap_or(path);
+    relative.to_string_lossy().replace('\\', "/")
+}
+
+fn list_notes(vault_path: &str) -> Result<rhai::Array, BouldyError> {
+    Ok(vault::list_notes(vault_path)?
+        .into_iter()
+        .map(|note| {
+            let mut map = rhai::Map::new();
+            map.insert(
+                "path".into(),
+                relative_note_path(vault_path, Path::new(&note.path)).into(),
+            );
+            map.insert("title".into(), note.title.into());
+            map.into()
+        })
+        .collect())
+}
+
+fn write_note(vault_path: &str, path: &str, content: &str) -> Result<(), BouldyError> {
+    let file = note_path(vault_path, path)?;
+    let _lock = vault_lock::acquire(vault_path)?;
+    fsutil::write_text_preserving(&file, content)
+        .map_err(|e| BouldyError::io("Failed to write note", e))
+}
+
+// Move a note into `folder` (created if needed) under the notes folder,
+// returning its new path
+fn move_note(vault_path: &str, path: &str, folder: &str) -> Result<String, BouldyError> {
+    let file = note_path(vault_path, path)?;
+    if Path::new(folder)
+        .components()
+        .any(|c| !matches!(c, std::path::Component::Normal(_)))
+    {
+        return Err(BouldyError::invalid_path(format!(
+            "Invalid folder: {}",
+            folder
+        )));
+    }
+    let dir = vault::notes_dir(vault_path).join(folder);
+    let file_name = file
+        .file_name()
+        .ok_or("Invalid file path")?
+        .to_string_lossy();
+
+    let _lock = vault_lock::acquire(vault_path)?;
+    fs::create_dir_all(&dir).map_err(|e| BouldyError::io("Failed to create folder", e))?;
+    let destination = unique_destination(&dir, &file_name);
+    fs::rename(&file, &destination).map_err(|e| BouldyError::io("Failed to move note", e))?;
+    Ok(relative_note_path(vault_path, &destination))
+}
+
+fn engine(app: &AppHandle, vault_path: &str, scheduled: Arc<Mutex<Vec<DailyHook>>>) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.set_max_string_size(MAX_STRING_BYTES);
+    engine.set_max_array_size(MAX_COLLECTION_ITEMS);
+    engine.set_max_map_size(MAX_COLLECTION_ITEMS);
+    engine.disable_symbol("eval");
+    engine.on_print(|message| tracing::info!("[script] {}", message));
+
+    engine.register_fn("log", |message: &str| {
+        tracing::info!("[script] {}", message)
+    });
+
+    engine.register_fn(
+        "daily",
+        move |time: &str, function: &str| -> Result<(), Box<EvalAltResult>> {
+            let time = NaiveTime::parse_from_str(time.trim(), "%H:%M")
+                .map_err(|_| script_error(format!("Invalid time '{}'. Expected HH:MM", time)))?;
+            scheduled.lock().unwrap().push(DailyHook {
+                time: time.format("%H:%M").to_string(),
+                function: function.to_string(),
+            });
+            Ok(())
+        },
+    );
+
+    let vault = vault_path.to_string();
+    engine.register_fn("list_notes", move || {
+        list_notes(&vault).map_err(script_error)
+    });
+
+    let vault = vault_path.to_string();
+    engine.register_fn("read_note", move |path: &str| {
+        note_path(&vault, path)
+            .and_then(|file| {
+                fs::read_to_string(file).map_err(|e| BouldyError::io("Failed to read note", e))
+            })
+            .map_err(script_error)
+    });
+
+    let vault = vault_path.to_string();
+    engine.register_fn("note_tags", move |path: &str| {
+        note_path(&vault, path)
+            .and_then(|file| {
+                fs::read_to_string(file).map_err(|e| BouldyError::io("Failed to read note", e))
+            })
+            .map(|content| {
+                markdown::extract_tags(&content)
+                    .into_iter()
+                    .map(Dynamic::from)
+                    .collect::<rhai::Array>()
+            })
+            .map_err(script_error)
+    });
+
+    let vault = vault_path.to_string();
+    engine.register_fn("write_note", move |path: &str, content: &str| {
+        write_note(&vault, path, content).map_err(script_error)
+    });
+
+    let vault = vault_path.to_string();
+    engine.register_fn("move_note", move |path: &str, folder: &str| {
+        move_note(&vault, path, folder).map_err(script_error)
+    });
+
+    let (handle, vault) = (app.clone(), vault_path.to_string());
+    engine.register_fn(
+        "todos",
+        move || -> Result<rhai::Array, Box<EvalAltResult>> {
+            let todos = handle
+                .state::<todo_store::TodoStore>()
+                .load(&vault)
+                .map_err(script_error)?;
+            todos.iter().map(rhai::serde::to_dynamic).collect()
+        },
+    );
+
+    let (handle, vault) = (app.clone(), vault_path.to_string());
+    engine.register_fn(
+        "add_todo",
+        move |text: &str| -> Result<(), Box<EvalAltResult>> {
+            handle
+                .state::<todo_store::TodoStore>()
+                .with_file(&vault, || todos::append_todo(&vault, text))
+                .map_err(script_error)?;
+            let _ = handle.emit("todos_changed", ());
+            Ok(())
+        },
+    );
+
+    let (handle, vault) = (app.clone(), vault_path.to_string());
+    engine.register_fn(
+        "archive_completed_todos",
+        move || -> Result<i64, Box<EvalAltResult>> {
+            let count = handle
+                .state::<todo_store::TodoStore>()
+                .with_file(&vault, || todos::archive_completed_todos(&vault))
+                .map_err(script_error)?;
+            let _ = handle.emit("todos_changed", ());
+            Ok(count as i64)
+        },
+    );
+
+    engine
+}
+
+// === Lifecycle ===
+
+fn load_script(
+    engine: &Engine,
+    scheduled: &Mutex<Vec<DailyHook>>,
+    name: &str,
+    path: &Path,
+) -> Script {
+    let mut info = ScriptInfo {
+        name: name.to_string(),
+        daily: Vec::new(),
+        hooks: Vec::new(),
+        error: None,
+    };
+
+    let ast = fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|source| engine.compile(source).map_err(|e| e.to_string()))
+        .and_then(|ast| {
+            scheduled.lock().unwrap().clear();
+            engine
+                .run_ast_with_scope(&mut Scope::new(), &ast)
+                .map_err(|e| e.to_string())?;
+            Ok(ast)
+        });
+    let ast = match ast {
+        Ok(ast) => ast,
+        Err(e) => {
+            info.error = Some(e);
+            return Script { info, ast: None };
+        }
+    };
+
+    info.daily = std::mem::take(&mut *scheduled.lock().unwrap());
+    info.hooks = ast
+        .iter_functions()
+        .map(|function| function.name)
+        .filter(|name| [ON_NOTE_SAVED, ON_TODO_COMPLETED].contains(name))
+        .map(str::to_string)
+        .collect();
+
+    Script {
+        info,
+        ast: Some(ast),
+    }
+}
+
+impl Runtime {
+    fn call(&self, script: &Script, function: &str, args: impl rhai::FuncArgs) {
+        let Some(ast) = &script.ast else {
+            return;
+        };
+        // The top level already ran on load; don't re-run it per call
+        let options = CallFnOptions::new().eval_ast(false);
+        if let Err(e) = self.engine.call_fn_with_options::<Dynamic>(
+            options,
+            &mut Scope::new(),
+            ast,
+            function,
+            args,
+        ) {
+            tracing::warn!("Script {} failed in {} - {}", script.info.name, function, e);
+        }
+    }
+
+    fn run_hook(&self, hook: &str, args: impl rhai::FuncArgs + Clone) {
+        for script in &self.scripts {
+            if script.info.hooks.iter().any(|h| h == hook) {
+                self.call(script, hook, args.clone());
+            }
+        }
+    }
+
+    // Daily hooks whose time has passed today and that haven't run yet
+    fn run_due(&self, today: NaiveDate, now: NaiveTime) -> Result<(), BouldyError> {
+        let mut state = load_state(&self.vault_path);
+        let today_str = today.format("%Y-%m-%d").to_string();
+        let mut ran = false;
+
+        for script in &self.scripts {
+            for hook in &script.info.daily {
+                let key = format!("{}:{}", script.info.name, hook.function);
+                let due = NaiveTime::parse_from_str(&hook.time, "%H:%M").is_ok_and(|t| t <= now);
+                if !due || state.last_run.get(&key) == Some(&today_str) {
+                    continue;
+                }
+                self.call(script, &hook.function, ());
+                state.last_run.insert(key, today_str.clone());
+                ran = true;
+            }
+        }
+
+        if ran {
+            save_state(&self.vault_path, &state)?;
+        }
+        Ok(())
+    }
+}
+
+impl ScriptHost {
+    /// Load the scripts of `vault_path`, replacing any loaded before. A
+    /// script that fails to compile or run is listed with its error.
+    pub fn load(&self, app: &AppHandle, vault_path: &str) -> Result<Vec<ScriptInfo>, BouldyError> {
+        let mut runtime = self.runtime.lock().unwrap();
+        *runtime = None;
+
+        let mut files: Vec<(String, PathBuf)> = match fs::read_dir(scripts_dir(vault_path)) {
+            Ok(entries) => entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.extension()
+                        .is_some_and(|extension| extension == SCRIPT_EXTENSION)
+                })
+                .filter_map(|path| Some((path.file_stem()?.to_str()?.to_string(), path)))
+                .collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(BouldyError::io("Failed to read scripts folder", e)),
+        };
+        files.sort();
+
+        let scheduled = Arc::new(Mutex::new(Vec::new()));
+        let engine = engine(app, vault_path, scheduled.clone());
+        let scripts: Vec<Script> = files
+            .iter()
+            .map(|(name, path)| load_script(&engine, &scheduled, name, path))
+            .collect();
+        for script in &scripts {
+            if let Some(error) = &script.info.error {
+                tracing::warn!("Script {} not loaded - {}", script.info.name, error);
+            }
+        }
+
+        let infos = scripts.iter().map(|script| script.info.clone()).collect();
+        *runtime = Some(Runtime {
+            vault_path: vault_path.to_string(),
+            engine,
+            scripts,
+        });
+        Ok(infos)
+    }
+
+    pub fn list(&self) -> Vec<ScriptInfo> {
+        let runtime = self.runtime.lock().unwrap();
+        runtime
+            .iter()
+            .flat_map(|runtime| runtime.scripts.iter().map(|script| script.info.clone()))
+            .collect()
+    }
+
+    fn with_runtime(&self, vault_path: &str, f: impl FnOnce(&Runtime)) {
+        let runtime = self.runtime.lock().unwrap();
+        if let Some(runtime) = runtime.as_ref().filter(|r| r.vault_path == vault_path) {
+            f(runtime);
+        }
+    }
+}
+
+/// Run `on_note_saved(path)` hooks off the calling thread.
+pub fn note_saved(app: &AppHandle, vault_path: &str, path: &str) {
+    let (app, vault_path) = (app.clone(), vault_path.to_string());
+    let path = relative_note_path(&vault_path, Path::new(path));
+    tauri::async_runtime::spawn_blocking(move || {
+        app.state::<ScriptHost>()
+            .with_runtime(&vault_path, |runtime| {
+                runtime.run_hook(ON_NOTE_SAVED, (path,))
+            });
+    });
+}
+
+/// Run `on_todo_completed(todo)` hooks off the calling thread.
+pub fn todo_completed(app: &AppHandle, vault_path: &str, todo: &TodoItem) {
+    let Ok(todo) = rhai::serde::to_dynamic(todo) else {
+        return;
+    };
+    let (app, vault_path) = (app.clone(), vault_path.to_string());
+    tauri::async_runtime::spawn_blocking(move || {
+        app.state::<ScriptHost>()
+            .with_runtime(&vault_path, |runtime| {
+                runtime.run_hook(ON_TODO_COMPLETED, (todo,))
+            });
+    });
+}
+
+/// Run daily hooks in the background for the configured vault.
+pub fn start(app: AppHandle) {
+    thread::spawn(move || loop {
+        thread::sleep(CHECK_INTERVAL);
+
+        let Ok(Some(vault_path)) = settings::load_settings(&app).map(|s| s.vault_path) else {
+            continue;
+        };
+        let now = chrono::Local::now().naive_local();
+        app.state::<ScriptHost>()
+            .with_runtime(&vault_path, |runtime| {
+                if let Err(e) = runtime.run_due(now.date(), now.time()) {
+                    tracing::warn!("Daily scripts failed - {}", e);
+                }
+            });
+    });
+}
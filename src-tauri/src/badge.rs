@@ -0,0 +1,38 @@
+use tauri::{AppHandle, Manager};
+
+use crate::error::BouldyError;
+use crate::{todos, windows};
+
+/// Show the number of due/overdue todos on the dock/taskbar icon.
+/// Windows has no badge API, so the count goes in the taskbar title instead.
+pub fn set_due_count(app: &AppHandle, count: usize) {
+    let Some(window) = app.get_webview_window(windows::MAIN_WINDOW) else {
+        return;
+    };
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let badge = if count == 0 { None } else { Some(count as i64) };
+        let _ = window.set_badge_count(badge);
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let title = if count == 0 {
+            "Bouldy".to_string()
+        } else {
+            format!("Bouldy ({} due)", count)
+        };
+        let _ = window.set_title(&title);
+    }
+}
+
+/// Recount due todos for the vault and update the badge.
+pub fn refresh(app: &AppHandle, vault_path: &str) -> Result<usize, BouldyError> {
+    let todos_list = todos::load_todos(vault_path)?;
+    let count = todos::due_or_overdue(&todos_list, todos::local_now()).len();
+
+    set_due_count(app, count);
+
+    Ok(count)
+}
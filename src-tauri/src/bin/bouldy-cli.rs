@@ -0,0 +1,164 @@
+//! Command-line companion to the desktop app. Operates directly on the vault
+//! files, so it works whether or not the app is running.
+//!
+//! The vault is taken from `--vault <path>`, then `BOULDY_VAULT`, then the
+//! vault configured in the desktop app.
+
+use bouldy_lib::{todos, vault, vault_lock};
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+// Must match the identifier in tauri.conf.json, which decides where the
+// desktop app keeps settings.json
+const APP_IDENTIFIER: &str = "com.ludeed.bouldy";
+
+const USAGE: &str = "Usage: bouldy-cli [--vault <path>] <command>
+
+Commands:
+  todo add <text>        Add a todo (supports +project @context due:YYYY-MM-DD[THH:MM])
+  todo list              List todos
+  todo done <id>         Toggle a todo's completion
+  note new <title>       Create a note (content read from stdin if piped)
+  search <query>         Search note titles and contents
+  list                   List notes, newest first";
+
+fn configured_vault_path() -> Option<String> {
+    let settings_path: PathBuf = dirs::data_dir()?.join(APP_IDENTIFIER).join("settings.json");
+    let content = std::fs::read_to_string(settings_path).ok()?;
+    let settings: serde_json::Value = serde_json::from_str(&content).ok()?;
+
+    settings.get("vaultPath")?.as_str().map(String::from)
+}
+
+fn resolve_vault(flag: Option<String>) -> Result<String, String> {
+    flag.or_else(|| std::env::var("BOULDY_VAULT").ok())
+        .or_else(configured_vault_path)
+        .ok_or_else(|| "No vault found. Pass --vault <path> or set BOULDY_VAULT".to_string())
+}
+
+fn read_stdin_if_piped() -> Result<String, String> {
+    use std::io::IsTerminal;
+
+    let mut content = String::new();
+    if !std::io::stdin().is_terminal() {
+        std::io::stdin()
+            .read_to_string(&mut content)
+            .map_err(|e| format!("Failed to read stdin: {}", e))?;
+    }
+    Ok(content)
+}
+
+fn print_todo(todo: &todos::TodoItem) {
+    let mut line = format!(
+        "{:>4}  [{}] ",
+        todo.id,
+        if todo.completed { "x" } else { " " }
+    );
+    if let Some(ref priority) = todo.priority {
+        line.push_str(&format!("({}) ", priority));
+    }
+    line.push_str(&todo.title);
+    for project in &todo.projects {
+        line.push_str(&format!(" +{}", project));
+    }
+    for context in &todo.contexts {
+        line.push_str(&format!(" @{}", context));
+    }
+    if let Some(ref due) = todo.due_date {
+        line.push_str(&format!(" due:{}", due));
+    }
+    println!("{}", line);
+}
+
+fn run(args: Vec<String>) -> Result<(), String> {
+    vault_lock::on_contention(|_| eprintln!("Waiting for bouldy to finish writing the vault..."));
+
+    let mut vault_flag = None;
+    let mut rest = Vec::new();
+
+    let mut iter = args.into_iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--vault" => vault_flag = Some(iter.next().ok_or("--vault needs a path")?),
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            _ => rest.push(arg),
+        }
+    }
+
+    let words: Vec<&str> = rest.iter().map(String::as_str).collect();
+
+    match words.as_slice() {
+        ["todo", "add", text @ ..] if !text.is_empty() => {
+            let vault_path = resolve_vault(vault_flag)?;
+            let _lock = vault_lock::acquire(&vault_path)?;
+            let todo = todos::append_todo(&vault_path, &text.join(" "))?;
+            print_todo(&todo);
+        }
+        ["todo", "list"] | ["todo"] => {
+            let vault_path = resolve_vault(vault_flag)?;
+            for todo in todos::load_todos(&vault_path)? {
+                print_todo(&todo);
+            }
+        }
+        ["todo", "done", id] => {
+            let vault_path = resolve_vault(vault_flag)?;
+            let id: usize = id.parse().map_err(|_| format!("Invalid todo id: {}", id))?;
+
+            let _lock = vault_lock::acquire(&vault_path)?;
+            let mut todos_list = todos::load_todos(&vault_path)?;
+            let todo = todos::find_todo_mut(&mut todos_list, id)
+                .ok_or_else(|| format!("Todo not found: {}", id))?;
+            todo.completed = !todo.completed;
+            let result = todo.clone();
+
+            todos::save_todos(&vault_path, &todos_list)?;
+            print_todo(&result);
+        }
+        ["note", "new", title @ ..] if !title.is_empty() => {
+            let vault_path = resolve_vault(vault_flag)?;
+            let content = read_stdin_if_piped()?;
+            let _lock = vault_lock::acquire(&vault_path)?;
+            let path = vault::create_note(&vault_path, &title.join(" "), &content)?;
+            println!("{}", path.display());
+        }
+        ["search", query @ ..] if !query.is_empty() => {
+            let vault_path = resolve_vault(vault_flag)?;
+            for found in vault::search_notes(&vault_path, &query.join(" "))? {
+                if found.line == 0 {
+                    println!("{}", found.title);
+                } else {
+                    println!("{}:{}: {}", found.title, found.line, found.snippet);
+                }
+            }
+        }
+        ["list"] => {
+            let vault_path = resolve_vault(vault_flag)?;
+            for note in vault::list_notes(&vault_path)? {
+                println!("{}", note.title);
+            }
+        }
+        _ => return Err(USAGE.to_string()),
+    }
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    // Library warnings go to stderr, like the app's own
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .without_time()
+        .init();
+
+    match run(std::env::args().skip(1).collect()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,403 @@
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::BouldyError;
+use crate::{fsutil, vault, vault_lock};
+
+// Boards are plain notes, readable anywhere (and by Obsidian's Kanban
+// plugin): `## Column` headings with a list item per card.
+//
+//   ## Doing
+//
+//   - [ ] Write report
+//     Notes on the card are indented under it
+//
+// Anything else in the file is kept as written.
+pub const BOARDS_DIR: &str = "boards";
+
+const DEFAULT_COLUMNS: &[&str] = &["Todo", "Doing", "Done"];
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Card {
+    // Derived from the card's text, so stable while other cards change
+    pub id: String,
+    pub title: String,
+    pub checked: bool,
+    pub description: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Column {
+    pub name: String,
+    pub cards: Vec<Card>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Board {
+    pub name: String,
+    pub path: String,
+    pub columns: Vec<Column>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct BoardSummary {
+    pub name: String,
+    pub path: String,
+    pub columns: usize,
+    pub cards: usize,
+}
+
+// A run of lines from the file, kept verbatim
+enum Block {
+    Card(Vec<String>),
+    Text(String),
+}
+
+struct RawColumn {
+    heading: String,
+    blocks: Vec<Block>,
+}
+
+// The file as parsed; serializing an untouched document gives back the
+// same text
+struct Document {
+    preamble: Vec<String>,
+    columns: Vec<RawColumn>,
+    trailing_newline: bool,
+}
+
+impl RawColumn {
+    fn name(&self) -> &str {
+        self.heading.trim_start_matches('#').trim()
+    }
+
+    fn cards(&self) -> impl Iterator<Item = &Vec<String>> {
+        self.blocks.iter().filter_map(|block| match block {
+            Block::Card(lines) => Some(lines),
+            Block::Text(_) => None,
+        })
+    }
+}
+
+fn is_column_heading(line: &str) -> bool {
+    line.starts_with("## ")
+}
+
+fn list_marker(line: &str) -> Option<&str> {
+    ["- ", "* ", "+ "]
+        .iter()
+        .find_map(|marker| line.strip_prefix(marker))
+}
+
+fn parse(content: &str) -> Document {
+    let mut document = Document {
+        preamble: Vec::new(),
+        columns: Vec::new(),
+        trailing_newline: content.ends_with('\n'),
+    };
+
+    for line in content.lines() {
+        if is_column_heading(line) {
+            document.columns.push(RawColumn {
+                heading: line.to_string(),
+                blocks: Vec::new(),
+            });
+            continue;
+        }
+        let Some(column) = document.columns.last_mut() else {
+            document.preamble.push(line.to_string());
+            continue;
+        };
+
+        let continues_card = (line.starts_with("  ") || line.starts_with('\t'))
+            && !line.trim().is_empty()
+            && matches!(column.blocks.last(), Some(Block::Card(_)));
+        if continues_card {
+            if let Some(Block::Card(lines)) = column.blocks.last_mut() {
+                lines.push(line.to_string());
+            }
+        } else if list_marker(line).is_some() {
+            column.blocks.push(Block::Card(vec![line.to_string()]));
+        } else {
+            column.blocks.push(Block::Text(line.to_string()));
+        }
+    }
+
+    document
+}
+
+fn serialize(document: &Document) -> String {
+    let mut lines: Vec<&str> = document.preamble.iter().map(String::as_str).collect();
+    for column in &document.columns {
+        lines.push(&column.heading);
+        for block in &column.blocks {
+            match block {
+                Block::Card(card) => lines.extend(card.iter().map(String::as_str)),
+                Block::Text(text) => lines.push(text),
+            }
+        }
+    }
+
+    let mut content = lines.join("\n");
+    if document.trailing_newline {
+        content.push('\n');
+    }
+    content
+}
+
+fn card_id(lines: &[String], occurrence: usize) -> String {
+    let digest = Sha256::digest(format!("{}\n{}", occurrence, lines.join("\n")).as_bytes());
+    digest[..6].iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// Ids of every card in document order; identical cards are told apart by
+// how many came before
+fn card_ids(document: &Document) -> Vec<(usize, usize, String)> {
+    let mut seen: HashMap<&Vec<String>, usize> = HashMap::new();
+    let mut ids = Vec::new();
+    for (column_index, column) in document.columns.iter().enumerate() {
+        for (block_index, block) in column.blocks.iter().enumerate() {
+            if let Block::Card(lines) = block {
+                let occurrence = seen.entry(lines).or_default();
+                ids.push((column_index, block_index, card_id(lines, *occurrence)));
+                *occurrence += 1;
+            }
+        }
+    }
+    ids
+}
+
+fn to_card(lines: &[String], id: String) -> Card {
+    let first = list_marker(&lines[0]).unwrap_or(&lines[0]);
+    let (checked, title) = match first.get(..4) {
+        Some("[ ] ") => (false, &first[4..]),
+        Some("[x] ") | Some("[X] ") => (true, &first[4..]),
+        _ => (false, first),
+    };
+    let description: Vec<&str> = lines[1..].iter().map(|line| line.trim()).collect();
+
+    Card {
+        id,
+        title: title.trim().to_string(),
+        checked,
+        description: (!description.is_empty()).then(|| description.join("\n")),
+    }
+}
+
+fn boards_dir(vault_path: &str) -> PathBuf {
+    Path::new(vault_path).join(BOARDS_DIR)
+}
+
+fn board_path(vault_path: &str, name: &str) -> Result<PathBuf, BouldyError> {
+    vault::validate_file_stem(name)?;
+    Ok(boards_dir(vault_path).join(format!("{}.md", name)))
+}
+
+fn read_document(path: &Path, name: &str) -> Result<Document, BouldyError> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(parse(&content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Err(BouldyError::not_found(format!(
+            "Board '{}' not found",
+            name
+        ))),
+        Err(e) => Err(BouldyError::io("Failed to read board", e)),
+    }
+}
+
+fn to_board(name: &str, path: &Path, document: &Document) -> Board {
+    let mut columns: Vec<Column> = document
+        .columns
+        .iter()
+        .map(|column| Column {
+            name: column.name().to_string(),
+            cards: Vec::new(),
+        })
+        .collect();
+    for (column_index, block_index, id) in card_ids(document) {
+        if let Block::Card(lines) = &document.columns[column_index].blocks[block_index] {
+            columns[column_index].cards.push(to_card(lines, id));
+        }
+    }
+
+    Board {
+        name: name.to_string(),
+        path: path.to_string_lossy().to_string(),
+        columns,
+    }
+}
+
+pub fn list_boards(vault_path: &str) -> Result<Vec<BoardSummary>, BouldyError> {
+    let entries = match fs::read_dir(boards_dir(vault_path)) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(BouldyError::io("Failed to read boards folder", e)),
+    };
+
+    let mut boards = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !vault::is_markdown_file(&path) {
+            continue;
+        }
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) => {
+                tracing::warn!("Failed to read {} - {}", path.display(), e);
+                continue;
+            }
+        };
+        let document = parse(&content);
+        boards.push(BoardSummary {
+            name: vault::extract_title_from_filename(&path),
+            path: path.to_string_lossy().to_string(),
+            columns: document.columns.len(),
+            cards: document.columns.iter().map(|c| c.cards().count()).sum(),
+        });
+    }
+    boards.sort_by_key(|board| board.name.to_lowercase());
+    Ok(boards)
+}
+
+pub fn get_board(vault_path: &str, name: &str) -> Result<Board, BouldyError> {
+    let path = board_path(vault_path, name)?;
+    let document = read_document(&path, name)?;
+    Ok(to_board(name, &path, &document))
+}
+
+/// Create a board with the given columns, or Todo/Doing/Done.
+pub fn create_board(
+    vault_path: &str,
+    name: &str,
+    columns: Option<Vec<String>>,
+) -> Result<Board, BouldyError> {
+    let path = board_path(vault_path, name)?;
+    let columns =
+        columns.unwrap_or_else(|| DEFAULT_COLUMNS.iter().map(|c| c.to_string()).collect());
+    if columns.iter().any(|column| column.trim().is_empty()) {
+        return Err(BouldyError::invalid_input("Column names cannot be empty"));
+    }
+
+    let content: String = columns
+        .iter()
+        .map(|column| format!("## {}\n\n", column.trim()))
+        .collect();
+
+    let _lock = vault_lock::acquire(vault_path)?;
+    if path.exists() {
+        return Err(BouldyError::conflict(format!(
+            "Board '{}' already exists",
+            name
+        )));
+    }
+    fs::create_dir_all(boards_dir(vault_path))
+        .map_err(|e| BouldyError::io("Failed to create boards folder", e))?;
+    fsutil::write_atomic(&path, &content)
+        .map_err(|e| BouldyError::io("Failed to write board", e))?;
+
+    Ok(to_board(name, &path, &parse(&content)))
+}
+
+fn find_column(document: &Document, column: &str) -> Result<usize, BouldyError> {
+    document
+        .columns
+        .iter()
+        .position(|c| c.name().eq_ignore_ascii_case(column.trim()))
+        .ok_or_else(|| BouldyError::not_found(format!("No column named '{}'", column)))
+}
+
+// Where the card that ends up `position`th among the column's cards goes;
+// past the end means right after the last card
+fn insert_index(column: &RawColumn, position: Option<usize>) -> usize {
+    let card_blocks: Vec<usize> = column
+        .blocks
+        .iter()
+        .enumerate()
+        .filter(|(_, block)| matches!(block, Block::Card(_)))
+        .map(|(index, _)| index)
+        .collect();
+
+    match position.and_then(|p| card_blocks.get(p)) {
+        Some(&index) => index,
+        None => match card_blocks.last() {
+            Some(&last) => last + 1,
+            // Keep the blank line under the heading
+            None => column
+                .blocks
+                .iter()
+                .position(|block| !matches!(block, Block::Text(text) if text.trim().is_empty()))
+                .unwrap_or(column.blocks.len()),
+        },
+    }
+}
+
+fn insert_card(document: &mut Document, target: usize, position: Option<usize>, card: Block) {
+    let last_column = target + 1 == document.columns.len();
+    let column = &mut document.columns[target];
+    let index = insert_index(column, position);
+    column.blocks.insert(index, card);
+    // Keep a blank line between the cards and the next heading
+    if index + 1 == column.blocks.len() && !last_column {
+        column.blocks.push(Block::Text(String::new()));
+    }
+}
+
+fn update_board<F>(vault_path: &str, name: &str, update: F) -> Result<Board, BouldyError>
+where
+    F: FnOnce(&mut Document) -> Result<(), BouldyError>,
+{
+    let path = board_path(vault_path, name)?;
+    let _lock = vault_lock::acquire(vault_path)?;
+    let mut document = read_document(&path, name)?;
+    update(&mut document)?;
+
+    fsutil::write_atomic(&path, serialize(&document))
+        .map_err(|e| BouldyError::io("Failed to write board", e))?;
+    Ok(to_board(name, &path, &document))
+}
+
+/// Move a card to another column (or within its own), at `position` among
+/// that column's cards; the end if `None`.
+pub fn move_card(
+    vault_path: &str,
+    board: &str,
+    card_id: &str,
+    to_column: &str,
+    position: Option<usize>,
+) -> Result<Board, BouldyError> {
+    update_board(vault_path, board, |document| {
+        let (column_index, block_index, _) = card_ids(document)
+            .into_iter()
+            .find(|(_, _, id)| id == card_id)
+            .ok_or_else(|| BouldyError::not_found(format!("Card '{}' not found", card_id)))?;
+        let target = find_column(document, to_column)?;
+
+        let card = document.columns[column_index].blocks.remove(block_index);
+        insert_card(document, target, position, card);
+        Ok(())
+    })
+}
+
+/// Add a card to the end of a column. Extra lines of `text` become the
+/// card's description.
+pub fn add_card(
+    vault_path: &str,
+    board: &str,
+    column: &str,
+    text: &str,
+) -> Result<Board, BouldyError> {
+    let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+    let title = lines
+        .next()
+        .ok_or_else(|| BouldyError::from("Card text cannot be empty"))?;
+    let mut card = vec![format!("- [ ] {}", title)];
+    card.extend(lines.map(|line| format!("  {}", line)));
+
+    update_board(vault_path, board, |document| {
+        let target = find_column(document, column)?;
+        insert_card(document, target, None, Block::Card(card));
+        Ok(())
+    })
+}
@@ -0,0 +1,496 @@
+use kuchikiki::traits::TendrilSink;
+use regex::Regex;
+use reqwest::{StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::error::BouldyError;
+use crate::network::{self, NetworkConfig};
+use crate::{fsutil, vault_lock};
+
+// The list itself, readable and editable anywhere:
+//
+//   - [The Rust Book](https://doc.rust-lang.org/book/) #rust #docs
+//     Chapter 16 on concurrency is the one to reread
+//
+// Notes are the indented lines under a bookmark.
+pub const BOOKMARKS_FILE: &str = "bookmarks.md";
+
+// What's fetched rather than written: favicons, dates, link checks
+const STATE_FILE: &str = "bookmarks.json";
+
+// Links checked at once
+const CHECK_CONCURRENCY: usize = 8;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LinkStatus {
+    pub ok: bool,
+    // HTTP status, if the server answered
+    pub code: Option<u16>,
+    pub error: Option<String>,
+    pub checked: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+struct BookmarkState {
+    #[serde(default)]
+    favicon: Option<String>,
+    #[serde(default)]
+    added: Option<String>,
+    #[serde(default)]
+    status: Option<LinkStatus>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Bookmark {
+    pub url: String,
+    pub title: String,
+    pub tags: Vec<String>,
+    pub notes: Option<String>,
+    pub favicon: Option<String>,
+    pub added: Option<String>,
+    pub status: Option<LinkStatus>,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct LinkCheckReport {
+    pub checked: usize,
+    pub dead: Vec<Bookmark>,
+}
+
+// One bookmark as written in the file
+struct Entry {
+    url: String,
+    title: String,
+    tags: Vec<String>,
+    notes: Vec<String>,
+}
+
+// Headings and other lines around the bookmarks are kept as written
+enum Block {
+    Bookmark(Entry),
+    Text(String),
+}
+
+struct Document {
+    blocks: Vec<Block>,
+}
+
+impl Document {
+    fn entries(&self) -> impl Iterator<Item = &Entry> {
+        self.blocks.iter().filter_map(|block| match block {
+            Block::Bookmark(entry) => Some(entry),
+            Block::Text(_) => None,
+        })
+    }
+
+    fn contains(&self, url: &str) -> bool {
+        self.entries().any(|entry| entry.url == url)
+    }
+}
+
+fn bookmark_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    // - [title](url) #tags
+    REGEX.get_or_init(|| {
+        Regex::new(r"^[-*] \[((?:[^\]\\]|\\.)*)\]\((<[^>]*>|[^)\s]*)\)(.*)$").unwrap()
+    })
+}
+
+fn bookmarks_path(vault_path: &str) -> PathBuf {
+    Path::new(vault_path).join(BOOKMARKS_FILE)
+}
+
+fn state_path(vault_path: &str) -> PathBuf {
+    Path::new(vault_path).join(".bouldy").join(STATE_FILE)
+}
+
+fn escape_title(title: &str) -> String {
+    title
+        .replace('\\', "\\\\")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+}
+
+fn unescape_title(title: &str) -> String {
+    let mut output = String::with_capacity(title.len());
+    let mut chars = title.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => output.extend(chars.next()),
+            c => output.push(c),
+        }
+    }
+    output
+}
+
+fn parse(content: &str) -> Document {
+    let mut document = Document { blocks: Vec::new() };
+
+    for line in content.lines() {
+        if let Some(captures) = bookmark_regex().captures(line) {
+            let url = captures[2].trim_start_matches('<').trim_end_matches('>');
+            let tags = captures[3]
+                .split_whitespace()
+                .filter_map(|word| word.strip_prefix('#'))
+                .filter(|tag| !tag.is_empty())
+                .map(|tag| tag.to_string())
+                .collect();
+            document.blocks.push(Block::Bookmark(Entry {
+                url: url.to_string(),
+                title: unescape_title(&captures[1]),
+                tags,
+                notes: Vec::new(),
+            }));
+            continue;
+        }
+
+        match document.blocks.last_mut() {
+            Some(Block::Bookmark(entry))
+                if line.starts_with([' ', '\t']) && !line.trim().is_empty() =>
+            {
+                entry.notes.push(line.trim().to_string())
+            }
+            _ => document.blocks.push(Block::Text(line.to_string())),
+        }
+    }
+
+    document
+}
+
+fn serialize(document: &Document) -> String {
+    let mut lines = Vec::new();
+    for block in &document.blocks {
+        let entry = match block {
+            Block::Bookmark(entry) => entry,
+            Block::Text(text) => {
+                lines.push(text.clone());
+                continue;
+            }
+        };
+        let url = if entry.url.contains([' ', '(', ')']) {
+            format!("<{}>", entry.url)
+        } else {
+            entry.url.clone()
+        };
+        let mut line = format!("- [{}]({})", escape_title(&entry.title), url);
+        for tag in &entry.tags {
+            line.push_str(&format!(" #{}", tag));
+        }
+        lines.push(line);
+        lines.extend(entry.notes.iter().map(|note| format!("  {}", note)));
+    }
+
+    let mut content = lines.join("\n");
+    content.push('\n');
+    content
+}
+
+fn load(vault_path: &str) -> Result<Document, BouldyError> {
+    match fs::read_to_string(bookmarks_path(vault_path)) {
+        Ok(content) => Ok(parse(&content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Document {
+            blocks: vec![
+                Block::Text("# Bookmarks".to_string()),
+                Block::Text(String::new()),
+            ],
+        }),
+        Err(e) => Err(BouldyError::io("Failed to read bookmarks", e)),
+    }
+}
+
+fn load_state(vault_path: &str) -> HashMap<String, BookmarkState> {
+    fs::read_to_string(state_path(vault_path))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(vault_path: &str, state: &HashMap<String, BookmarkState>) -> Result<(), BouldyError> {
+    let content = serde_json::to_string_pretty(state)
+        .map_err(|e| format!("Failed to serialize bookmark state: {}", e))?;
+    fsutil::write_atomic(&state_path(vault_path), content)
+        .map_err(|e| BouldyError::io("Failed to write bookmark state", e))
+}
+
+fn to_bookmark(entry: &Entry, state: Option<&BookmarkState>) -> Bookmark {
+    let state = state.cloned().unwrap_or_default();
+    Bookmark {
+        url: entry.url.clone(),
+        title: entry.title.clone(),
+        tags: entry.tags.clone(),
+        notes: (!entry.notes.is_empty()).then(|| entry.notes.join("\n")),
+        favicon: state.favicon,
+        added: state.added,
+        status: state.status,
+    }
+}
+
+fn parse_url(url: &str) -> Result<Url, BouldyError> {
+    Url::parse(url.trim())
+        .ok()
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+        .ok_or_else(|| BouldyError::invalid_path(format!("Not a web page: '{}'", url)))
+}
+
+/// Bookmarks whose title, URL, notes or tags contain every word of
+/// `filter`; `#tag` words must match a tag exactly.
+pub fn list_bookmarks(
+    vault_path: &str,
+    filter: Option<&str>,
+) -> Result<Vec<Bookmark>, BouldyError> {
+    let document = load(vault_path)?;
+    let state = load_state(vault_path);
+    let words: Vec<String> = filter
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(|word| word.to_lowercase())
+        .collect();
+
+    Ok(document
+        .entries()
+        .filter(|entry| {
+            let haystack =
+                format!("{} {} {}", entry.title, entry.url, entry.notes.join(" ")).to_lowercase();
+            words.iter().all(|word| match word.strip_prefix('#') {
+                Some(tag) => entry.tags.iter().any(|t| t.to_lowercase() == tag),
+                None => {
+                    haystack.contains(word.as_str())
+                        || entry
+                            .tags
+                            .iter()
+                            .any(|t| t.to_lowercase().contains(word.as_str()))
+                }
+            })
+        })
+        .map(|entry| to_bookmark(entry, state.get(&entry.url)))
+        .collect())
+}
+
+// Title and favicon from the page, if it can be fetched
+async fn fetch_page_info(
+    client: &reqwest::Client,
+    url: &Url,
+) -> (Option<String>, Option<String>, LinkStatus) {
+    let checked = chrono::Local::now().to_rfc3339();
+    let response = match client.get(url.clone()).send().await {
+        Ok(response) => response,
+        Err(e) => {
+            let status = LinkStatus {
+                ok: false,
+                code: None,
+                error: Some(e.to_string()),
+                checked,
+            };
+            return (None, None, status);
+        }
+    };
+
+    let status = LinkStatus {
+        ok: response.status().is_success(),
+        code: Some(response.status().as_u16()),
+        error: None,
+        checked,
+    };
+    let final_url = response.url().clone();
+    let Ok(html) = response.text().await else {
+        return (None, None, status);
+    };
+
+    let document = kuchikiki::parse_html().one(html.as_str()).document_node;
+    let title = document
+        .select_first("meta[property=\"og:title\"]")
+        .ok()
+        .and_then(|meta| meta.attributes.borrow().get("content").map(str::to_string))
+        .or_else(|| {
+            document
+                .select_first("title")
+                .ok()
+                .map(|t| t.text_contents())
+        })
+        .map(|title| title.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|title| !title.is_empty());
+    let favicon = document
+        .select_first("link[rel~=\"icon\"]")
+        .ok()
+        .and_then(|link| link.attributes.borrow().get("href").map(str::to_string))
+        .and_then(|href| final_url.join(&href).ok())
+        .or_else(|| final_url.join("/favicon.ico").ok())
+        .map(|favicon| favicon.to_string());
+
+    (title, favicon, status)
+}
+
+/// Add a bookmark, fetching its title and favicon. Pages that can't be
+/// fetched are still added, titled by their host and marked dead.
+pub async fn add_bookmark(
+    network_config: &NetworkConfig,
+    vault_path: &str,
+    url: &str,
+    tags: Vec<String>,
+    notes: Option<String>,
+) -> Result<Bookmark, BouldyError> {
+    let url = parse_url(url)?;
+    if load(vault_path)?.contains(url.as_str()) {
+        return Err(BouldyError::conflict(format!(
+            "'{}' is already bookmarked",
+            url
+        )));
+    }
+
+    let client = network::http_client(network_config)?;
+    let (title, favicon, status) = fetch_page_info(&client, &url).await;
+
+    let entry = Entry {
+        url: url.to_string(),
+        title: title.unwrap_or_else(|| url.host_str().unwrap_or(url.as_str()).to_string()),
+        tags: tags
+            .iter()
+            .map(|tag| {
+                tag.trim()
+                    .trim_start_matches('#')
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join("-")
+            })
+            .filter(|tag| !tag.is_empty())
+            .collect(),
+        notes: notes
+            .unwrap_or_default()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect(),
+    };
+    let entry_state = BookmarkState {
+        favicon,
+        added: Some(chrono::Local::now().format("%Y-%m-%d").to_string()),
+        status: Some(status),
+    };
+
+    let _lock = vault_lock::acquire(vault_path)?;
+    // Re-read: the file may have changed while the page was loading
+    let mut document = load(vault_path)?;
+    if document.contains(&entry.url) {
+        return Err(BouldyError::conflict(format!(
+            "'{}' is already bookmarked",
+            url
+        )));
+    }
+    let bookmark = to_bookmark(&entry, Some(&entry_state));
+    document.blocks.push(Block::Bookmark(entry));
+    fsutil::write_atomic(&bookmarks_path(vault_path), serialize(&document))
+        .map_err(|e| BouldyError::io("Failed to write bookmarks", e))?;
+
+    let mut state = load_state(vault_path);
+    state.insert(bookmark.url.clone(), entry_state);
+    save_state(vault_path, &state)?;
+
+    Ok(bookmark)
+}
+
+pub fn remove_bookmark(vault_path: &str, url: &str) -> Result<(), BouldyError> {
+    let _lock = vault_lock::acquire(vault_path)?;
+    let mut document = load(vault_path)?;
+    if !document.contains(url) {
+        return Err(BouldyError::not_found(format!(
+            "'{}' is not bookmarked",
+            url
+        )));
+    }
+    document
+        .blocks
+        .retain(|block| !matches!(block, Block::Bookmark(entry) if entry.url == url));
+    fsutil::write_atomic(&bookmarks_path(vault_path), serialize(&document))
+        .map_err(|e| BouldyError::io("Failed to write bookmarks", e))?;
+
+    let mut state = load_state(vault_path);
+    if state.remove(url).is_some() {
+        save_state(vault_path, &state)?;
+    }
+    Ok(())
+}
+
+async fn check_link(client: reqwest::Client, url: String) -> LinkStatus {
+    let checked = chrono::Local::now().to_rfc3339();
+    // Plenty of servers refuse HEAD; retry those with GET
+    let mut result = client.head(&url).send().await;
+    if result.as_ref().is_ok_and(|response| {
+        matches!(
+            response.status(),
+            StatusCode::METHOD_NOT_ALLOWED | StatusCode::FORBIDDEN | StatusCode::NOT_IMPLEMENTED
+        )
+    }) {
+        result = client.get(&url).send().await;
+    }
+
+    match result {
+        Ok(response) => LinkStatus {
+            // Rate limiting isn't a dead link
+            ok: response.status().is_success()
+                || response.status() == StatusCode::TOO_MANY_REQUESTS,
+            code: Some(response.status().as_u16()),
+            error: None,
+            checked,
+        },
+        Err(e) => LinkStatus {
+            ok: false,
+            code: None,
+            error: Some(e.to_string()),
+            checked,
+        },
+    }
+}
+
+/// Check every bookmark still loads, returning the ones that don't.
+pub async fn check_links(
+    network_config: &NetworkConfig,
+    vault_path: &str,
+) -> Result<LinkCheckReport, BouldyError> {
+    let client = network::http_client(network_config)?;
+    let urls: Vec<String> = load(vault_path)?
+        .entries()
+        .map(|entry| entry.url.clone())
+        .collect();
+
+    let mut results = Vec::new();
+    for chunk in urls.chunks(CHECK_CONCURRENCY) {
+        let handles: Vec<_> = chunk
+            .iter()
+            .map(|url| tauri::async_runtime::spawn(check_link(client.clone(), url.clone())))
+            .collect();
+        for (url, handle) in chunk.iter().zip(handles) {
+            let status = handle
+                .await
+                .map_err(|e| format!("Link check failed: {}", e))?;
+            results.push((url.clone(), status));
+        }
+    }
+
+    let _lock = vault_lock::acquire(vault_path)?;
+    let mut state = load_state(vault_path);
+    for (url, status) in &results {
+        state.entry(url.clone()).or_default().status = Some(status.clone());
+    }
+    save_state(vault_path, &state)?;
+
+    let dead = load(vault_path)?
+        .entries()
+        .filter(|entry| {
+            state
+                .get(&entry.url)
+                .and_then(|s| s.status.as_ref())
+                .is_some_and(|s| !s.ok)
+        })
+        .map(|entry| to_bookmark(entry, state.get(&entry.url)))
+        .collect();
+
+    Ok(LinkCheckReport {
+        checked: results.len(),
+        dead,
+    })
+}
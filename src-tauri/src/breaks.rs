@@ -0,0 +1,152 @@
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+use crate::error::BouldyError;
+use crate::notifications::{self, NotificationCategory};
+use crate::pomodoro::PomodoroSettings;
+use crate::{fsutil, todos};
+
+const STATS_FILE: &str = "break-stats.json";
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BreakKind {
+    #[serde(rename = "short-break")]
+    Short,
+    #[serde(rename = "long-break")]
+    Long,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
+pub struct BreakDay {
+    pub taken: u32,
+    pub skipped: u32,
+}
+
+// Date -> breaks that day
+#[derive(Serialize, Deserialize, Default)]
+struct BreakLog {
+    days: BTreeMap<String, BreakDay>,
+}
+
+/// Sent to the frontend with `break:started`.
+#[derive(Serialize, Clone, Debug)]
+pub struct BreakStart {
+    pub kind: BreakKind,
+    pub activity: Option<String>,
+    pub dim: bool,
+    pub strict: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct BreakStats {
+    pub taken: u32,
+    pub skipped: u32,
+    // By date
+    pub days: BTreeMap<String, BreakDay>,
+}
+
+fn stats_path(vault_path: &str) -> PathBuf {
+    Path::new(vault_path).join(".bouldy").join(STATS_FILE)
+}
+
+fn load_log(vault_path: &str) -> BreakLog {
+    fs::read_to_string(stats_path(vault_path))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn today() -> NaiveDate {
+    todos::local_now().date()
+}
+
+fn record(vault_path: &str, f: impl FnOnce(&mut BreakDay)) -> Result<BreakDay, BouldyError> {
+    let mut log = load_log(vault_path);
+    let day = log
+        .days
+        .entry(today().format(DATE_FORMAT).to_string())
+        .or_default();
+    f(day);
+    let day = *day;
+
+    let content = serde_json::to_string_pretty(&log)
+        .map_err(|e| format!("Failed to serialize break stats: {}", e))?;
+    fsutil::write_atomic(&stats_path(vault_path), content)
+        .map_err(|e| BouldyError::io("Failed to write break stats", e))?;
+    Ok(day)
+}
+
+/// Announce a break: suggest the next activity in the list, notify, and
+/// in strict mode emit `break:enforced` so the frontend locks the timer.
+pub fn start_break(
+    app: &AppHandle,
+    vault_path: &str,
+    settings: &PomodoroSettings,
+    kind: BreakKind,
+) -> Result<BreakStart, BouldyError> {
+    // Rotate through the list over the day
+    let today_count = load_log(vault_path)
+        .days
+        .get(&today().format(DATE_FORMAT).to_string())
+        .map(|day| day.taken + day.skipped)
+        .unwrap_or_default() as usize;
+    let activity = (!settings.break_activities.is_empty())
+        .then(|| settings.break_activities[today_count % settings.break_activities.len()].clone());
+
+    let start = BreakStart {
+        kind,
+        activity,
+        dim: settings.dim_on_break,
+        strict: settings.strict_breaks,
+    };
+
+    if settings.break_notifications {
+        let title = match kind {
+            BreakKind::Short => "Time for a break",
+            BreakKind::Long => "Time for a long break",
+        };
+        let body = start.activity.as_deref().unwrap_or("Step away for a bit");
+        notifications::notify(app, NotificationCategory::Pomodoro, title, body)?;
+    }
+
+    let _ = app.emit("break:started", &start);
+    if start.strict {
+        let _ = app.emit("break:enforced", &start);
+    }
+    Ok(start)
+}
+
+/// Record how a break ended.
+pub fn end_break(vault_path: &str, skipped: bool) -> Result<BreakDay, BouldyError> {
+    record(vault_path, |day| {
+        if skipped {
+            day.skipped += 1;
+        } else {
+            day.taken += 1;
+        }
+    })
+}
+
+/// Breaks taken and skipped over the last `days` days, today included.
+pub fn get_stats(vault_path: &str, days: u32) -> BreakStats {
+    let log = load_log(vault_path);
+    let from = (today() - Duration::days(days.saturating_sub(1) as i64))
+        .format(DATE_FORMAT)
+        .to_string();
+
+    let days: BTreeMap<String, BreakDay> = log
+        .days
+        .into_iter()
+        .filter(|(date, _)| *date >= from)
+        .collect();
+    BreakStats {
+        taken: days.values().map(|day| day.taken).sum(),
+        skipped: days.values().map(|day| day.skipped).sum(),
+        days,
+    }
+}
@@ -0,0 +1,715 @@
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use reqwest::header::{CONTENT_TYPE, ETAG, IF_MATCH, IF_NONE_MATCH};
+use reqwest::{Client, Method, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::BouldyError;
+use crate::fsutil;
+use crate::ics::{escape_text, fold_line};
+use crate::network::{self, NetworkConfig};
+use crate::secrets;
+use crate::todo_store::TodoStore;
+use crate::todos::{self, Due, TodoItem};
+
+pub const SECRET_SERVICE: &str = "bouldy-caldav";
+const STATE_FILE: &str = "caldav-sync.json";
+
+// VTODO properties bouldy writes. Everything else on a remote task
+// (descriptions, alarms, ...) is kept as the server sent it.
+const OWNED_PROPERTIES: &[&str] = &[
+    "DTSTAMP",
+    "LAST-MODIFIED",
+    "SUMMARY",
+    "STATUS",
+    "COMPLETED",
+    "PERCENT-COMPLETE",
+    "DUE",
+    "PRIORITY",
+    "CATEGORIES",
+];
+
+const CALENDAR_QUERY: &str = r#"<?xml version="1.0" encoding="utf-8" ?>
+<c:calendar-query xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:prop><d:getetag/><c:calendar-data/></d:prop>
+  <c:filter>
+    <c:comp-filter name="VCALENDAR"><c:comp-filter name="VTODO"/></c:comp-filter>
+  </c:filter>
+</c:calendar-query>"#;
+
+/// Which side wins when a task changed both locally and on the server
+/// since the last sync.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    #[default]
+    #[serde(rename = "preferLocal")]
+    PreferLocal,
+    #[serde(rename = "preferRemote")]
+    PreferRemote,
+}
+
+/// CalDAV account settings. The password lives in the OS keyring, never in
+/// settings.json.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct CaldavSettings {
+    // The task list's collection URL
+    #[serde(rename = "calendarUrl", default)]
+    pub calendar_url: Option<String>,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(rename = "conflictPolicy", default)]
+    pub conflict_policy: ConflictPolicy,
+}
+
+impl CaldavSettings {
+    pub fn validate(&self) -> Result<(), BouldyError> {
+        if let Some(ref url) = self.calendar_url {
+            let parsed = Url::parse(url).map_err(|e| {
+                BouldyError::invalid_input(format!("Invalid CalDAV URL '{}': {}", url, e))
+            })?;
+            if !matches!(parsed.scheme(), "http" | "https") {
+                return Err(BouldyError::invalid_input(format!(
+                    "CalDAV URL must use http or https: '{}'",
+                    url
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    // Collection URL (with a trailing slash, so task URLs join onto it)
+    // and username, or an error if sync isn't set up
+    fn account(&self) -> Result<(Url, &str), BouldyError> {
+        let (Some(url), Some(username)) = (&self.calendar_url, &self.username) else {
+            return Err("CalDAV sync is not configured".into());
+        };
+        let url = if url.ends_with('/') {
+            url.clone()
+        } else {
+            format!("{}/", url)
+        };
+        let url = Url::parse(&url).map_err(|e| format!("Invalid CalDAV URL: {}", e))?;
+        Ok((url, username))
+    }
+
+    /// The keyring account the password is saved under.
+    pub fn secret_account(&self) -> Result<String, BouldyError> {
+        let (url, username) = self.account()?;
+        Ok(format!("{}@{}", username, url))
+    }
+}
+
+fn password(config: &CaldavSettings) -> Result<String, BouldyError> {
+    secrets::get(SECRET_SERVICE, &config.secret_account()?)?
+        .ok_or_else(|| BouldyError::not_found("No CalDAV password saved"))
+}
+
+/// The parts of a task that are synced, compared against the last synced
+/// copy to tell which side changed.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+struct TaskFields {
+    title: String,
+    completed: bool,
+    due: Option<String>,
+    priority: Option<String>,
+    projects: Vec<String>,
+}
+
+impl TaskFields {
+    fn of(todo: &TodoItem) -> Self {
+        Self {
+            title: todo.title.clone(),
+            completed: todo.completed,
+            due: todo.due_date.clone(),
+            priority: todo.priority.clone(),
+            projects: todo.projects.clone(),
+        }
+    }
+
+    fn apply(&self, todo: &mut TodoItem) {
+        todo.title = self.title.clone();
+        todo.completed = self.completed;
+        todo.due_date = self.due.clone();
+        todo.priority = self.priority.clone();
+        todo.projects = self.projects.clone();
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct SyncedTask {
+    href: String,
+    etag: Option<String>,
+    fields: TaskFields,
+}
+
+// Keyed by todo uid, which is also the VTODO UID
+type SyncState = HashMap<String, SyncedTask>;
+
+fn state_path(vault_path: &str) -> PathBuf {
+    Path::new(vault_path).join(".bouldy").join(STATE_FILE)
+}
+
+fn load_state(vault_path: &str) -> SyncState {
+    fs::read_to_string(state_path(vault_path))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(vault_path: &str, state: &SyncState) -> Result<(), BouldyError> {
+    let content = serde_json::to_string_pretty(state)
+        .map_err(|e| format!("Failed to serialize CalDAV sync state: {}", e))?;
+    fsutil::write_atomic(&state_path(vault_path), content)
+        .map_err(|e| BouldyError::io("Failed to write CalDAV sync state", e))
+}
+
+// === iCalendar ===
+
+// Join folded continuation lines back up
+fn unfold(data: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in data.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(previous)) => previous.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+fn unescape_text(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            result.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => result.push('\n'),
+            Some(other) => result.push(other),
+            None => {}
+        }
+    }
+    result
+}
+
+// "NAME;PARAM=x:value" -> ("NAME", "value")
+fn split_property(line: &str) -> Option<(String, &str)> {
+    let (name, value) = line.split_once(':')?;
+    let name = name.split(';').next().unwrap_or(name).to_uppercase();
+    Some((name, value))
+}
+
+// DUE value to a todo.txt due date. UTC times are shown in local time;
+// floating and TZID times are taken as local.
+fn parse_due(value: &str) -> Option<String> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y%m%d") {
+        return Some(date.format("%Y-%m-%d").to_string());
+    }
+    let at = match value.strip_suffix('Z') {
+        Some(utc) => {
+            let utc = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+            Utc.from_utc_datetime(&utc)
+                .with_timezone(&Local)
+                .naive_local()
+        }
+        None => NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?,
+    };
+    Some(at.format("%Y-%m-%dT%H:%M").to_string())
+}
+
+// The UID and synced fields of the first VTODO in a calendar object
+fn parse_vtodo(data: &str) -> Option<(String, TaskFields)> {
+    let mut uid = None;
+    let mut fields = TaskFields::default();
+    // 0 outside the VTODO, 1 in it, deeper inside nested VALARMs
+    let mut depth = 0;
+
+    for line in unfold(data) {
+        if line.eq_ignore_ascii_case("BEGIN:VTODO") && depth == 0 {
+            depth = 1;
+            continue;
+        }
+        if depth == 0 {
+            continue;
+        }
+        if line.starts_with("BEGIN:") {
+            depth += 1;
+            continue;
+        }
+        if line.starts_with("END:") {
+            depth -= 1;
+            if depth == 0 {
+                break;
+            }
+            continue;
+        }
+        if depth > 1 {
+            continue;
+        }
+
+        let Some((name, value)) = split_property(&line) else {
+            continue;
+        };
+        match name.as_str() {
+            "UID" => uid = Some(value.to_string()),
+            // todo.txt is one line per todo
+            "SUMMARY" => {
+                fields.title = unescape_text(value)
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            }
+            "STATUS" => fields.completed = value.eq_ignore_ascii_case("COMPLETED"),
+            "COMPLETED" => fields.completed = true,
+            "DUE" => fields.due = parse_due(value),
+            // 1 -> (A), 2 -> (B), ... 0 means undefined
+            "PRIORITY" => {
+                fields.priority = value
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|rank| (1..=9).contains(rank))
+                    .map(|rank| ((b'A' + rank - 1) as char).to_string())
+            }
+            "CATEGORIES" => fields.projects.extend(
+                value
+                    .split(',')
+                    .map(|category| unescape_text(category).trim().replace(' ', "-"))
+                    .filter(|category| !category.is_empty()),
+            ),
+            _ => {}
+        }
+    }
+
+    Some((uid?, fields))
+}
+
+fn vtodo_properties(fields: &TaskFields) -> Vec<String> {
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = vec![
+        format!("DTSTAMP:{}", stamp),
+        format!("LAST-MODIFIED:{}", stamp),
+        format!("SUMMARY:{}", escape_text(&fields.title)),
+    ];
+
+    if fields.completed {
+        lines.push("STATUS:COMPLETED".to_string());
+        lines.push(format!("COMPLETED:{}", stamp));
+        lines.push("PERCENT-COMPLETE:100".to_string());
+    } else {
+        lines.push("STATUS:NEEDS-ACTION".to_string());
+    }
+
+    match fields.due.as_deref().and_then(Due::parse) {
+        Some(Due::Date(date)) => lines.push(format!("DUE;VALUE=DATE:{}", date.format("%Y%m%d"))),
+        Some(Due::DateTime(at)) => lines.push(format!("DUE:{}", at.format("%Y%m%dT%H%M%S"))),
+        None => {}
+    }
+
+    if let Some(rank) = fields
+        .priority
+        .as_deref()
+        .and_then(|priority| priority.bytes().next())
+    {
+        lines.push(format!(
+            "PRIORITY:{}",
+            (rank.saturating_sub(b'A') + 1).min(9)
+        ));
+    }
+
+    if !fields.projects.is_empty() {
+        let categories: Vec<String> = fields.projects.iter().map(|p| escape_text(p)).collect();
+        lines.push(format!("CATEGORIES:{}", categories.join(",")));
+    }
+
+    lines
+}
+
+/// Calendar object for a task. Updating an existing remote task replaces
+/// only the properties bouldy owns and keeps the rest.
+fn render_vtodo(uid: &str, fields: &TaskFields, existing: Option<&str>) -> String {
+    let ours = vtodo_properties(fields);
+
+    let lines = match existing {
+        Some(data) => {
+            let mut lines = Vec::new();
+            let mut depth = 0;
+            for line in unfold(data) {
+                if line.eq_ignore_ascii_case("BEGIN:VTODO") && depth == 0 {
+                    depth = 1;
+                } else if depth > 0 && line.starts_with("BEGIN:") {
+                    depth += 1;
+                } else if depth > 0 && line.starts_with("END:") {
+                    depth -= 1;
+                    if depth == 0 {
+                        lines.extend(ours.iter().cloned());
+                    }
+                } else if depth == 1 {
+                    let owned = split_property(&line)
+                        .is_some_and(|(name, _)| OWNED_PROPERTIES.contains(&name.as_str()));
+                    if owned {
+                        continue;
+                    }
+                }
+                lines.push(line);
+            }
+            lines
+        }
+        None => {
+            let mut lines = vec![
+                "BEGIN:VCALENDAR".to_string(),
+                "VERSION:2.0".to_string(),
+                "PRODID:-//bouldy//todos//EN".to_string(),
+                "BEGIN:VTODO".to_string(),
+                format!("UID:{}", uid),
+            ];
+            lines.extend(ours);
+            lines.push("END:VTODO".to_string());
+            lines.push("END:VCALENDAR".to_string());
+            lines
+        }
+    };
+
+    lines.iter().map(|line| fold_line(line)).collect()
+}
+
+// === Server ===
+
+struct RemoteTask {
+    href: String,
+    etag: Option<String>,
+    uid: String,
+    fields: TaskFields,
+    data: String,
+}
+
+struct Server {
+    client: Client,
+    url: Url,
+    username: String,
+    password: String,
+}
+
+impl Server {
+    async fn fetch_tasks(&self) -> Result<Vec<RemoteTask>, BouldyError> {
+        let response = self
+            .client
+            .request(Method::from_bytes(b"REPORT").unwrap(), self.url.clone())
+            .basic_auth(&self.username, Some(&self.password))
+            .header("Depth", "1")
+            .header(CONTENT_TYPE, "application/xml; charset=utf-8")
+            .body(CALENDAR_QUERY)
+            .send()
+            .await
+            .map_err(|e| format!("CalDAV request failed: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("CalDAV server returned {}", response.status()).into());
+        }
+        let body = response
+            .text()
+            .await
+            .map_err(|e| format!("Failed to read CalDAV response: {}", e))?;
+
+        let document = roxmltree::Document::parse(&body)
+            .map_err(|e| format!("Invalid CalDAV response: {}", e))?;
+
+        let text_of = |node: roxmltree::Node, name: &str| {
+            node.descendants()
+                .find(|child| child.tag_name().name() == name)
+                .and_then(|child| child.text())
+                .map(|text| text.trim().to_string())
+        };
+
+        let mut tasks = Vec::new();
+        for response in document
+            .descendants()
+            .filter(|node| node.tag_name().name() == "response")
+        {
+            let (Some(href), Some(data)) = (
+                text_of(response, "href"),
+                text_of(response, "calendar-data"),
+            ) else {
+                continue;
+            };
+            let Some((uid, fields)) = parse_vtodo(&data) else {
+                continue;
+            };
+            tasks.push(RemoteTask {
+                href,
+                etag: text_of(response, "getetag"),
+                uid,
+                fields,
+                data,
+            });
+        }
+
+        Ok(tasks)
+    }
+
+    /// Create or update a task. `etag` makes the write conditional on the
+    /// server copy being unchanged; without one the task must not exist yet.
+    /// Returns the new etag, or None if the precondition failed.
+    async fn put_task(
+        &self,
+        href: &str,
+        etag: Option<&str>,
+        body: String,
+    ) -> Result<Option<Option<String>>, BouldyError> {
+        let url = self
+            .url
+            .join(href)
+            .map_err(|e| format!("Invalid task URL '{}': {}", href, e))?;
+
+        let request = self
+            .client
+            .put(url)
+            .basic_auth(&self.username, Some(&self.password))
+            .header(CONTENT_TYPE, "text/calendar; charset=utf-8")
+            .body(body);
+        let request = match etag {
+            Some(etag) => request.header(IF_MATCH, etag),
+            None => request.header(IF_NONE_MATCH, "*"),
+        };
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("CalDAV request failed: {}", e))?;
+
+        if response.status() == StatusCode::PRECONDITION_FAILED {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(format!("CalDAV server returned {}", response.status()).into());
+        }
+
+        Ok(Some(
+            response
+                .headers()
+                .get(ETAG)
+                .and_then(|etag| etag.to_str().ok())
+                .map(|etag| etag.to_string()),
+        ))
+    }
+}
+
+// === Sync ===
+
+struct Push {
+    uid: String,
+    href: String,
+    etag: Option<String>,
+    fields: TaskFields,
+    body: String,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct CaldavSyncReport {
+    pub pulled: usize,
+    pub pushed: usize,
+    pub removed: usize,
+    pub conflicts: usize,
+    pub errors: Vec<String>,
+}
+
+/// Merge the server's tasks into `todos` and work out what to push.
+/// Whichever side changed since the last sync wins; if both did, `policy`
+/// decides. Todos without a uid get one, since it becomes the VTODO UID.
+fn reconcile(
+    todos: &mut Vec<TodoItem>,
+    remote: &[RemoteTask],
+    state: &mut SyncState,
+    policy: ConflictPolicy,
+    report: &mut CaldavSyncReport,
+) -> (Vec<Push>, bool) {
+    let mut pushes = Vec::new();
+    let mut changed = false;
+
+    for task in remote {
+        let synced = state.get(&task.uid);
+        let remote_changed = synced.is_none_or(|synced| synced.fields != task.fields);
+
+        match todos
+            .iter_mut()
+            .find(|todo| todo.uid.as_deref() == Some(task.uid.as_str()))
+        {
+            Some(todo) => {
+                let local = TaskFields::of(todo);
+                let local_changed = synced.is_none_or(|synced| synced.fields != local);
+
+                if local != task.fields && remote_changed && local_changed {
+                    report.conflicts += 1;
+                }
+
+                if local == task.fields
+                    || (remote_changed
+                        && (!local_changed || policy == ConflictPolicy::PreferRemote))
+                {
+                    if local != task.fields {
+                        task.fields.apply(todo);
+                        report.pulled += 1;
+                        changed = true;
+                    }
+                } else if local_changed {
+                    pushes.push(Push {
+                        uid: task.uid.clone(),
+                        href: task.href.clone(),
+                        etag: task.etag.clone(),
+                        body: render_vtodo(&task.uid, &local, Some(&task.data)),
+                        fields: local,
+                    });
+                    continue;
+                }
+            }
+            // Deleted or archived here since the last sync; the server copy
+            // is left alone unless it changes again
+            None if !remote_changed => continue,
+            None => {
+                let mut todo = TodoItem {
+                    id: 0,
+                    title: String::new(),
+                    completed: false,
+                    due_date: None,
+                    priority: None,
+                    projects: Vec::new(),
+                    contexts: Vec::new(),
+                    created_date: Some(Local::now().format("%Y-%m-%d").to_string()),
+                    uid: Some(task.uid.clone()),
+                    parent: None,
+                    delegated: None,
+                    estimate: None,
+                    description: None,
+                };
+                task.fields.apply(&mut todo);
+                todos::push(todos, todo);
+                report.pulled += 1;
+                changed = true;
+            }
+        }
+
+        state.insert(
+            task.uid.clone(),
+            SyncedTask {
+                href: task.href.clone(),
+                etag: task.etag.clone(),
+                fields: task.fields.clone(),
+            },
+        );
+    }
+
+    // Todos the server doesn't have
+    let remote_uids: HashSet<&str> = remote.iter().map(|task| task.uid.as_str()).collect();
+    let mut removed = HashSet::new();
+    for todo in todos.iter_mut() {
+        if todo
+            .uid
+            .as_deref()
+            .is_some_and(|uid| remote_uids.contains(uid))
+        {
+            continue;
+        }
+
+        let uid = match todo.uid.clone() {
+            Some(uid) => uid,
+            None => {
+                let uid = todos::new_uid();
+                todo.uid = Some(uid.clone());
+                changed = true;
+                uid
+            }
+        };
+        let local = TaskFields::of(todo);
+
+        let href = match state.get(&uid) {
+            // Deleted on the server; follow unless it was edited here since
+            Some(synced) if synced.fields == local => {
+                removed.insert(uid.clone());
+                continue;
+            }
+            Some(synced) => synced.href.clone(),
+            None => format!("{}.ics", uid),
+        };
+
+        pushes.push(Push {
+            body: render_vtodo(&uid, &local, None),
+            uid,
+            href,
+            etag: None,
+            fields: local,
+        });
+    }
+
+    if !removed.is_empty() {
+        todos.retain(|todo| !todo.uid.as_deref().is_some_and(|uid| removed.contains(uid)));
+        for uid in &removed {
+            state.remove(uid);
+        }
+        report.removed += removed.len();
+        changed = true;
+    }
+
+    (pushes, changed)
+}
+
+/// Two-way sync of todo.txt with the configured CalDAV task list: new and
+/// changed server tasks are pulled, local ones pushed, and completion is
+/// mapped to the VTODO status. Per-task failures are reported rather than
+/// aborting the whole sync.
+pub async fn sync(
+    store: &TodoStore,
+    vault_path: &str,
+    config: &CaldavSettings,
+    network_config: &NetworkConfig,
+) -> Result<CaldavSyncReport, BouldyError> {
+    let (url, username) = config.account()?;
+    let server = Server {
+        client: network::http_client(network_config)?,
+        url,
+        username: username.to_string(),
+        password: password(config)?,
+    };
+
+    let remote = server.fetch_tasks().await?;
+
+    let mut state = load_state(vault_path);
+    let mut report = CaldavSyncReport::default();
+    let pushes = store.mutate_if_changed(vault_path, |todos| {
+        Ok(reconcile(
+            todos,
+            &remote,
+            &mut state,
+            config.conflict_policy,
+            &mut report,
+        ))
+    })?;
+
+    for push in pushes {
+        match server
+            .put_task(&push.href, push.etag.as_deref(), push.body)
+            .await
+        {
+            Ok(Some(etag)) => {
+                report.pushed += 1;
+                state.insert(
+                    push.uid,
+                    SyncedTask {
+                        href: push.href,
+                        etag,
+                        fields: push.fields,
+                    },
+                );
+            }
+            // Changed on the server while we were syncing; the next sync
+            // sees both versions
+            Ok(None) => report.conflicts += 1,
+            Err(e) => report.errors.push(format!("{}: {}", push.fields.title, e)),
+        }
+    }
+
+    save_state(vault_path, &state)?;
+    Ok(report)
+}
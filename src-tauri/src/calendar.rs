@@ -0,0 +1,128 @@
+use chrono::{DateTime, Datelike, Local, NaiveDate};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::error::BouldyError;
+use crate::journal::{self, JournalDay};
+use crate::todo_store::TodoStore;
+use crate::todos::TodoItem;
+use crate::{markdown, timesheet, vault};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+#[derive(Serialize, Clone, Debug)]
+pub struct CalendarNote {
+    pub path: String,
+    pub title: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct CalendarDay {
+    pub date: String,
+    // Todos due that day, done or not
+    pub todos: Vec<TodoItem>,
+    pub journal: Option<JournalDay>,
+    // Completed work pomodoros
+    pub pomodoros: usize,
+    #[serde(rename = "focusMinutes")]
+    pub focus_minutes: i64,
+    #[serde(rename = "notesCreated")]
+    pub notes_created: Vec<CalendarNote>,
+}
+
+impl CalendarDay {
+    fn new(date: NaiveDate) -> Self {
+        CalendarDay {
+            date: date.format(DATE_FORMAT).to_string(),
+            todos: Vec::new(),
+            journal: None,
+            pomodoros: 0,
+            focus_minutes: 0,
+            notes_created: Vec::new(),
+        }
+    }
+}
+
+fn day(days: &mut BTreeMap<NaiveDate, CalendarDay>, date: NaiveDate) -> &mut CalendarDay {
+    days.entry(date).or_insert_with(|| CalendarDay::new(date))
+}
+
+// A `created:` frontmatter date wins, since sync tools and copies reset the
+// file's own creation time
+pub fn note_created(path: &str) -> Option<NaiveDate> {
+    let from_frontmatter = fs::read_to_string(path)
+        .ok()
+        .and_then(|content| markdown::parse_frontmatter(&content))
+        .and_then(|frontmatter| {
+            frontmatter
+                .get("created")
+                .and_then(|value| value.as_str().map(str::to_string))
+        })
+        .and_then(|created| {
+            created
+                .get(..10)
+                .and_then(|date| NaiveDate::parse_from_str(date, DATE_FORMAT).ok())
+        });
+
+    from_frontmatter.or_else(|| {
+        fs::metadata(Path::new(path))
+            .and_then(|metadata| metadata.created())
+            .ok()
+            .map(|created| DateTime::<Local>::from(created).date_naive())
+    })
+}
+
+/// Everything that happened or is due in `month` (`YYYY-MM`), one entry per
+/// day that has anything in it.
+pub fn get_calendar(
+    store: &TodoStore,
+    vault_path: &str,
+    month: &str,
+) -> Result<Vec<CalendarDay>, BouldyError> {
+    let first =
+        NaiveDate::parse_from_str(&format!("{}-01", month.trim()), DATE_FORMAT).map_err(|_| {
+            BouldyError::invalid_input(format!("Invalid month '{}'. Expected YYYY-MM", month))
+        })?;
+    let in_month = |date: NaiveDate| date.year() == first.year() && date.month() == first.month();
+
+    let mut days: BTreeMap<NaiveDate, CalendarDay> = BTreeMap::new();
+
+    for todo in store.load(vault_path)? {
+        if let Some(due) = todo.due().map(|due| due.date()).filter(|d| in_month(*d)) {
+            day(&mut days, due).todos.push(todo);
+        }
+    }
+
+    for entry in journal::get_calendar(vault_path, month)? {
+        if let Ok(date) = NaiveDate::parse_from_str(&entry.date, DATE_FORMAT) {
+            day(&mut days, date).journal = Some(entry);
+        }
+    }
+
+    for (start, end) in timesheet::load_pomodoros(vault_path) {
+        if in_month(start.date()) {
+            let day = day(&mut days, start.date());
+            day.pomodoros += 1;
+            day.focus_minutes += (end - start).num_minutes().max(0);
+        }
+    }
+
+    // A vault without a notes folder yet still has todos and pomodoros
+    let notes = if vault::notes_dir(vault_path).exists() {
+        vault::list_notes(vault_path)?
+    } else {
+        Vec::new()
+    };
+    for note in notes {
+        if let Some(created) = note_created(&note.path).filter(|d| in_month(*d)) {
+            day(&mut days, created).notes_created.push(CalendarNote {
+                path: note.path,
+                title: note.title,
+            });
+        }
+    }
+
+    Ok(days.into_values().collect())
+}
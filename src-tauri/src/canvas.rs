@@ -0,0 +1,263 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::error::BouldyError;
+use crate::{fsutil, vault, vault_lock};
+
+// Canvases follow the JSON Canvas format Obsidian uses, so the same files
+// open in both apps. Fields bouldy doesn't know about are kept as written.
+pub const CANVAS_DIR: &str = "canvas";
+pub const CANVAS_EXTENSION: &str = "canvas";
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeKind {
+    #[serde(rename = "text")]
+    Text,
+    // A note or attachment, by path relative to the vault
+    #[serde(rename = "file")]
+    File,
+    #[serde(rename = "link")]
+    Link,
+    #[serde(rename = "group")]
+    Group,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CanvasNode {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: NodeKind,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    // A heading or block in the file, e.g. "#Heading"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subpath: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CanvasEdge {
+    pub id: String,
+    #[serde(rename = "fromNode")]
+    pub from_node: String,
+    #[serde(rename = "toNode")]
+    pub to_node: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    // Sides, arrow ends and color
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Canvas {
+    #[serde(default)]
+    pub nodes: Vec<CanvasNode>,
+    #[serde(default)]
+    pub edges: Vec<CanvasEdge>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct CanvasFile {
+    pub name: String,
+    pub path: String,
+    #[serde(flatten)]
+    pub canvas: Canvas,
+    // File nodes pointing at notes or attachments that don't exist
+    #[serde(rename = "missingFiles")]
+    pub missing_files: Vec<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct CanvasSummary {
+    pub name: String,
+    pub path: String,
+    pub modified: u64,
+}
+
+fn canvas_dir(vault_path: &str) -> PathBuf {
+    Path::new(vault_path).join(CANVAS_DIR)
+}
+
+fn canvas_path(vault_path: &str, name: &str) -> Result<PathBuf, BouldyError> {
+    vault::validate_file_stem(name)?;
+    Ok(canvas_dir(vault_path).join(format!("{}.{}", name, CANVAS_EXTENSION)))
+}
+
+pub fn is_canvas_file(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == CANVAS_EXTENSION)
+}
+
+// Only plain relative paths, so a node can't point outside the vault
+fn is_vault_relative(file: &str) -> bool {
+    !file.is_empty()
+        && Path::new(file)
+            .components()
+            .all(|part| matches!(part, Component::Normal(_)))
+}
+
+impl Canvas {
+    pub fn validate(&self) -> Result<(), BouldyError> {
+        let mut ids = HashSet::new();
+        for node in &self.nodes {
+            if node.id.trim().is_empty() {
+                return Err(BouldyError::invalid_input("Canvas nodes need an id"));
+            }
+            if !ids.insert(node.id.as_str()) {
+                return Err(BouldyError::invalid_input(format!(
+                    "Duplicate canvas node id '{}'",
+                    node.id
+                )));
+            }
+            if !(node.width > 0.0 && node.height > 0.0) {
+                return Err(BouldyError::invalid_input(format!(
+                    "Canvas node '{}' needs a positive size",
+                    node.id
+                )));
+            }
+            match node.kind {
+                NodeKind::Text if node.text.is_none() => {
+                    return Err(BouldyError::invalid_input(format!(
+                        "Text node '{}' has no text",
+                        node.id
+                    )));
+                }
+                NodeKind::File => {
+                    let file = node.file.as_deref().unwrap_or_default();
+                    if !is_vault_relative(file) {
+                        return Err(BouldyError::invalid_input(format!(
+                            "File node '{}' must point to a path inside the vault",
+                            node.id
+                        )));
+                    }
+                }
+                NodeKind::Link if node.url.as_deref().is_none_or(str::is_empty) => {
+                    return Err(BouldyError::invalid_input(format!(
+                        "Link node '{}' has no URL",
+                        node.id
+                    )));
+                }
+                _ => {}
+            }
+        }
+
+        let mut edge_ids = HashSet::new();
+        for edge in &self.edges {
+            if !edge_ids.insert(edge.id.as_str()) {
+                return Err(BouldyError::invalid_input(format!(
+                    "Duplicate canvas edge id '{}'",
+                    edge.id
+                )));
+            }
+            for end in [&edge.from_node, &edge.to_node] {
+                if !ids.contains(end.as_str()) {
+                    return Err(BouldyError::invalid_input(format!(
+                        "Edge '{}' connects to missing node '{}'",
+                        edge.id, end
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn to_file(vault_path: &str, name: &str, path: &Path, canvas: Canvas) -> CanvasFile {
+    let vault = Path::new(vault_path);
+    let missing_files = canvas
+        .nodes
+        .iter()
+        .filter_map(|node| node.file.as_deref())
+        .filter(|file| !vault.join(file).exists())
+        .map(str::to_string)
+        .collect();
+    CanvasFile {
+        name: name.to_string(),
+        path: path.to_string_lossy().to_string(),
+        canvas,
+        missing_files,
+    }
+}
+
+pub fn list_canvases(vault_path: &str) -> Result<Vec<CanvasSummary>, BouldyError> {
+    let entries = match fs::read_dir(canvas_dir(vault_path)) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(BouldyError::io("Failed to read canvas folder", e)),
+    };
+
+    let mut canvases: Vec<CanvasSummary> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| is_canvas_file(path))
+        .filter_map(|path| {
+            let modified = fs::metadata(&path).ok()?.modified().ok()?;
+            Some(CanvasSummary {
+                name: path.file_stem()?.to_str()?.to_string(),
+                path: path.to_str()?.to_string(),
+                modified: vault::unix_secs(modified),
+            })
+        })
+        .collect();
+    canvases.sort_by_key(|canvas| canvas.name.to_lowercase());
+    Ok(canvases)
+}
+
+pub fn read_canvas(vault_path: &str, name: &str) -> Result<CanvasFile, BouldyError> {
+    let path = canvas_path(vault_path, name)?;
+    let content = match fs::read_to_string(fsutil::long_path(&path)) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(BouldyError::not_found(format!(
+                "Canvas '{}' not found",
+                name
+            )))
+        }
+        Err(e) => return Err(BouldyError::io("Failed to read canvas", e)),
+    };
+    // A new canvas in Obsidian is an empty file
+    let canvas: Canvas = if content.trim().is_empty() {
+        Canvas::default()
+    } else {
+        serde_json::from_str(&content).map_err(|e| BouldyError::parse("Invalid canvas", e))?
+    };
+    Ok(to_file(vault_path, name, &path, canvas))
+}
+
+/// Validate and save `canvas`, creating it if needed.
+pub fn write_canvas(
+    vault_path: &str,
+    name: &str,
+    canvas: Canvas,
+) -> Result<CanvasFile, BouldyError> {
+    let path = canvas_path(vault_path, name)?;
+    canvas.validate()?;
+    let content = serde_json::to_string_pretty(&canvas)
+        .map_err(|e| format!("Failed to serialize canvas: {}", e))?;
+
+    let _lock = vault_lock::acquire(vault_path)?;
+    fs::create_dir_all(canvas_dir(vault_path))
+        .map_err(|e| BouldyError::io("Failed to create canvas folder", e))?;
+    fsutil::write_atomic(&path, content)
+        .map_err(|e| BouldyError::io("Failed to write canvas", e))?;
+
+    Ok(to_file(vault_path, name, &path, canvas))
+}
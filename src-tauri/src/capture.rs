@@ -0,0 +1,101 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+
+use crate::error::BouldyError;
+use crate::todo_store::TodoStore;
+use crate::todos;
+use crate::vault_lock;
+
+pub const INBOX_NOTE: &str = "Inbox.md";
+pub const QUICK_CAPTURE_WINDOW: &str = "quick-capture";
+pub const DEFAULT_QUICK_CAPTURE_SHORTCUT: &str = "CommandOrControl+Shift+Space";
+
+/// Append a timestamped list item to `notes/<file_name>`, creating the note
+/// with `heading` if needed. Continuation lines are indented so multi-line
+/// text stays one list item.
+pub fn append_timestamped_entry(
+    vault_path: &str,
+    file_name: &str,
+    heading: &str,
+    text: &str,
+) -> Result<PathBuf, BouldyError> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(BouldyError::invalid_input("Nothing to capture"));
+    }
+
+    let note_path = Path::new(vault_path).join("notes").join(file_name);
+    let _lock = vault_lock::acquire(vault_path)?;
+    let is_new = !note_path.exists();
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&note_path)
+        .map_err(|e| format!("Failed to open {}: {}", file_name, e))?;
+
+    let mut entry = String::new();
+    if is_new {
+        entry.push_str(&format!("# {}\n\n", heading));
+    }
+
+    let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M");
+    let mut lines = text.lines();
+    entry.push_str(&format!(
+        "- [{}] {}\n",
+        timestamp,
+        lines.next().unwrap_or_default()
+    ));
+    for line in lines {
+        entry.push_str(&format!("  {}\n", line));
+    }
+
+    file.write_all(entry.as_bytes())
+        .map_err(|e| format!("Failed to write {}: {}", file_name, e))?;
+
+    Ok(note_path)
+}
+
+/// Append a timestamped entry to the inbox note, creating it if needed.
+pub fn capture_note(vault_path: &str, text: &str) -> Result<PathBuf, BouldyError> {
+    append_timestamped_entry(vault_path, INBOX_NOTE, "Inbox", text)
+}
+
+/// Append a todo parsed from free text to the end of todo.txt.
+pub fn capture_todo(
+    app: &AppHandle,
+    vault_path: &str,
+    text: &str,
+) -> Result<todos::TodoItem, BouldyError> {
+    app.state::<TodoStore>()
+        .with_file(vault_path, || todos::append_todo(vault_path, text))
+}
+
+/// Show the quick-capture window, creating it on first use.
+pub fn show_capture_window(app: &AppHandle) -> Result<(), BouldyError> {
+    if let Some(window) = app.get_webview_window(QUICK_CAPTURE_WINDOW) {
+        window.show().map_err(|e| e.to_string())?;
+        window.set_focus().map_err(|e| e.to_string())?;
+        return Ok(());
+    }
+
+    WebviewWindowBuilder::new(
+        app,
+        QUICK_CAPTURE_WINDOW,
+        WebviewUrl::App("index.html?window=quick-capture".into()),
+    )
+    .title("Quick Capture")
+    .inner_size(480.0, 160.0)
+    .resizable(false)
+    .decorations(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .center()
+    .focused(true)
+    .build()
+    .map_err(|e| format!("Failed to open quick capture window: {}", e))?;
+
+    Ok(())
+}
@@ -0,0 +1,169 @@
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::error::BouldyError;
+use crate::{capture, settings};
+
+pub const CLIPPINGS_NOTE: &str = "Clippings.md";
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// Anything larger is almost certainly not a deliberate clipping
+const MAX_CLIPPING_BYTES: usize = 64 * 1024;
+
+/// Managed state shared between the toggle command and the polling thread.
+#[derive(Default)]
+pub struct ClipboardCapture {
+    pub enabled: AtomicBool,
+    // The last text the app put on the clipboard itself, never captured
+    written: Mutex<Option<String>>,
+}
+
+/// Put `text` on the clipboard without it being captured as a clipping.
+pub fn write_text(app: &AppHandle, text: &str) -> Result<(), BouldyError> {
+    *app.state::<ClipboardCapture>().written.lock().unwrap() = Some(text.to_string());
+    app.clipboard()
+        .write_text(text)
+        .map_err(|e| format!("Failed to write to clipboard: {}", e).into())
+}
+
+/// What was on the clipboard before the app borrowed it for a paste.
+pub enum Saved {
+    Text(String),
+    Image(tauri::image::Image<'static>),
+    Empty,
+}
+
+/// Remember the clipboard's contents so `restore` can put them back. Not for
+/// the main thread, where reading can deadlock on Linux.
+pub fn save(app: &AppHandle) -> Saved {
+    let clipboard = app.clipboard();
+    if let Ok(text) = clipboard.read_text() {
+        return Saved::Text(text);
+    }
+    match clipboard.read_image() {
+        Ok(image) => Saved::Image(image.to_owned()),
+        Err(_) => Saved::Empty,
+    }
+}
+
+pub fn restore(app: &AppHandle, saved: Saved) -> Result<(), BouldyError> {
+    let result = match saved {
+        Saved::Text(text) => return write_text(app, &text),
+        Saved::Image(image) => app.clipboard().write_image(&image),
+        Saved::Empty => app.clipboard().clear(),
+    };
+    result.map_err(|e| format!("Failed to restore clipboard: {}", e).into())
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub enum SourceHint {
+    #[serde(rename = "url")]
+    Url,
+    #[serde(rename = "text")]
+    Text,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ClippingPayload {
+    pub text: String,
+    pub source: SourceHint,
+    pub path: String,
+}
+
+fn source_hint(text: &str) -> SourceHint {
+    let is_url = tauri::Url::parse(text.trim())
+        .map(|url| matches!(url.scheme(), "http" | "https"))
+        .unwrap_or(false);
+
+    if is_url {
+        SourceHint::Url
+    } else {
+        SourceHint::Text
+    }
+}
+
+fn capture_clipping(app: &AppHandle, text: &str) -> Result<(), BouldyError> {
+    let vault_path = settings::require_vault_path(app)?;
+    let source = source_hint(text);
+
+    let entry = match source {
+        SourceHint::Url => format!("(url) <{}>", text.trim()),
+        SourceHint::Text => format!("(text) {}", text.trim()),
+    };
+    let path = capture::append_timestamped_entry(&vault_path, CLIPPINGS_NOTE, "Clippings", &entry)?;
+
+    let _ = app.emit(
+        "clipboard:captured",
+        ClippingPayload {
+            text: text.to_string(),
+            source,
+            path: path.to_string_lossy().to_string(),
+        },
+    );
+
+    Ok(())
+}
+
+/// Start the clipboard polling thread. It idles while capture is disabled and
+/// only records text that changes after capture was switched on.
+pub fn start(app: AppHandle) {
+    thread::spawn(move || {
+        let mut last_seen: Option<String> = None;
+        let mut was_enabled = false;
+
+        loop {
+            thread::sleep(POLL_INTERVAL);
+
+            let enabled = app
+                .state::<ClipboardCapture>()
+                .enabled
+                .load(Ordering::Relaxed);
+            // The clipboard isn't touched at all while capture is off
+            if !enabled {
+                was_enabled = false;
+                last_seen = None;
+                continue;
+            }
+
+            let current = app.clipboard().read_text().ok();
+
+            // Don't capture whatever was already on the clipboard when enabled
+            if !was_enabled {
+                was_enabled = true;
+                last_seen = current;
+                continue;
+            }
+
+            let Some(text) = current else {
+                continue;
+            };
+            if last_seen.as_deref() == Some(text.as_str()) {
+                continue;
+            }
+            last_seen = Some(text.clone());
+
+            let written = app
+                .state::<ClipboardCapture>()
+                .written
+                .lock()
+                .unwrap()
+                .clone();
+            if written.as_deref() == Some(text.as_str()) {
+                continue;
+            }
+            if text.trim().is_empty() || text.len() > MAX_CLIPPING_BYTES {
+                continue;
+            }
+
+            if let Err(e) = capture_clipping(&app, &text) {
+                tracing::warn!("Failed to capture clipping - {}", e);
+            }
+        }
+    });
+}
@@ -0,0 +1,117 @@
+use chrono::NaiveTime;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::error::BouldyError;
+use crate::notifications;
+use crate::todos::TodoItem;
+
+// Times are local "HH:MM"; a range like 22:00-02:00 wraps past midnight
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ContextHours {
+    pub start: String,
+    pub end: String,
+}
+
+impl ContextHours {
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        let (Ok(start), Ok(end)) = (
+            notifications::parse_time(&self.start),
+            notifications::parse_time(&self.end),
+        ) else {
+            return true;
+        };
+
+        if start <= end {
+            time >= start && time < end
+        } else {
+            time >= start || time < end
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ContextSettings {
+    // Where the user is now, e.g. "home"; unset means anywhere
+    #[serde(default)]
+    pub current: Option<String>,
+    // When a context can be acted on, e.g. office 09:00-18:00. Outside these
+    // hours its todos don't send reminders.
+    #[serde(default)]
+    pub hours: HashMap<String, ContextHours>,
+}
+
+impl ContextSettings {
+    pub fn validate(&self) -> Result<(), BouldyError> {
+        if self
+            .current
+            .as_deref()
+            .is_some_and(|context| normalize(context).is_empty())
+        {
+            return Err(BouldyError::invalid_input(
+                "Current context cannot be empty",
+            ));
+        }
+        for hours in self.hours.values() {
+            notifications::parse_time(&hours.start)?;
+            notifications::parse_time(&hours.end)?;
+        }
+        Ok(())
+    }
+
+    // Whether todos in `context` can be done right now
+    fn is_available(&self, context: &str, now: NaiveTime) -> bool {
+        let at_context = self
+            .current
+            .as_deref()
+            .is_none_or(|current| same_context(current, context));
+        let in_hours = self
+            .hours
+            .iter()
+            .find(|(name, _)| same_context(name, context))
+            .is_none_or(|(_, hours)| hours.contains(now));
+        at_context && in_hours
+    }
+
+    /// Todos without a context are always relevant; others when any of
+    /// their contexts is available at `now`.
+    pub fn is_relevant(&self, todo: &TodoItem, now: NaiveTime) -> bool {
+        todo.contexts.is_empty()
+            || todo
+                .contexts
+                .iter()
+                .any(|context| self.is_available(context, now))
+    }
+}
+
+/// "@Home" and "home" are the same context.
+pub fn normalize(context: &str) -> String {
+    context.trim().trim_start_matches('@').to_lowercase()
+}
+
+fn same_context(a: &str, b: &str) -> bool {
+    normalize(a) == normalize(b)
+}
+
+/// Open todos tagged with `context`.
+pub fn todos_for_context(todos: &[TodoItem], context: &str) -> Vec<TodoItem> {
+    todos
+        .iter()
+        .filter(|todo| !todo.completed)
+        .filter(|todo| todo.contexts.iter().any(|c| same_context(c, context)))
+        .cloned()
+        .collect()
+}
+
+/// Every context used by an open todo, alphabetically and without
+/// duplicates.
+pub fn list_contexts(todos: &[TodoItem]) -> Vec<String> {
+    let mut contexts: Vec<String> = todos
+        .iter()
+        .filter(|todo| !todo.completed)
+        .flat_map(|todo| todo.contexts.iter().map(|c| normalize(c)))
+        .collect();
+    contexts.sort();
+    contexts.dedup();
+    contexts
+}
@@ -0,0 +1,135 @@
+use percent_encoding::percent_decode_str;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Url};
+
+use crate::error::BouldyError;
+use crate::{capture, settings, windows};
+
+pub const SCHEME: &str = "bouldy";
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub enum DeepLink {
+    /// bouldy://note/<name>
+    Note { name: String },
+    /// bouldy://todo/new?title=...
+    NewTodo { title: String },
+    /// bouldy://prompt/<id>
+    Prompt { id: String },
+}
+
+/// Links the app was launched with. They arrive before the frontend is
+/// listening, so React collects them with `take_launch_deep_links` once mounted.
+#[derive(Default)]
+pub struct LaunchDeepLinks(pub Mutex<Vec<String>>);
+
+#[derive(Serialize, Clone)]
+pub struct OpenNotePayload {
+    pub path: String,
+    pub name: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct OpenPromptPayload {
+    pub id: String,
+}
+
+// The single path segment after the host, decoded. Anything that could
+// escape the notes/prompts directory is rejected.
+fn single_segment(url: &Url) -> Result<String, BouldyError> {
+    let raw = url.path().trim_start_matches('/');
+    let decoded = percent_decode_str(raw)
+        .decode_utf8()
+        .map_err(|_| BouldyError::invalid_input(format!("Invalid link: {}", url)))?
+        .to_string();
+
+    if decoded.is_empty()
+        || decoded.contains('/')
+        || decoded.contains('\\')
+        || decoded == "."
+        || decoded == ".."
+    {
+        return Err(BouldyError::invalid_input(format!("Invalid link: {}", url)));
+    }
+
+    Ok(decoded)
+}
+
+pub fn parse(url: &Url) -> Result<DeepLink, BouldyError> {
+    if url.scheme() != SCHEME {
+        return Err(BouldyError::invalid_input(format!(
+            "Unsupported link scheme: {}",
+            url.scheme()
+        )));
+    }
+
+    match url.host_str() {
+        Some("note") => Ok(DeepLink::Note {
+            name: single_segment(url)?,
+        }),
+        Some("prompt") => Ok(DeepLink::Prompt {
+            id: single_segment(url)?,
+        }),
+        Some("todo") if url.path().trim_matches('/') == "new" => {
+            let title = url
+                .query_pairs()
+                .find(|(key, _)| key == "title")
+                .map(|(_, value)| value.trim().to_string())
+                .filter(|title| !title.is_empty())
+                .ok_or_else(|| BouldyError::invalid_input("Missing todo title"))?;
+            Ok(DeepLink::NewTodo { title })
+        }
+        _ => Err(BouldyError::invalid_input(format!("Unknown link: {}", url))),
+    }
+}
+
+pub fn handle(app: &AppHandle, url: &Url) -> Result<(), BouldyError> {
+    let link = parse(url)?;
+    let vault_path = settings::require_vault_path(app)?;
+
+    match link {
+        DeepLink::Note { name } => {
+            let file_name = if name.ends_with(".md") {
+                name
+            } else {
+                format!("{}.md", name)
+            };
+            let path = Path::new(&vault_path).join("notes").join(&file_name);
+            if !path.exists() {
+                return Err(BouldyError::not_found(format!(
+                    "Note not found: {}",
+                    file_name
+                )));
+            }
+
+            let _ = app.emit(
+                "navigate:note",
+                OpenNotePayload {
+                    path: path.to_string_lossy().to_string(),
+                    name: file_name,
+                },
+            );
+        }
+        DeepLink::NewTodo { title } => {
+            let todo = capture::capture_todo(app, &vault_path, &title)?;
+            let _ = app.emit("todos_changed", ());
+            let _ = app.emit("navigate:todo", todo);
+        }
+        DeepLink::Prompt { id } => {
+            let _ = app.emit("navigate:prompt", OpenPromptPayload { id });
+        }
+    }
+
+    windows::focus_main_window(app);
+
+    Ok(())
+}
+
+pub fn handle_all(app: &AppHandle, urls: impl IntoIterator<Item = Url>) {
+    for url in urls {
+        if let Err(e) = handle(app, &url) {
+            tracing::warn!("Ignoring deep link {} - {}", url, e);
+        }
+    }
+}
@@ -0,0 +1,375 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::backtrace::Backtrace;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::prelude::*;
+use zip::write::SimpleFileOptions;
+
+use crate::error::BouldyError;
+use crate::network;
+use crate::settings::{self, Settings};
+
+const LOG_PREFIX: &str = "bouldy";
+const LOG_SUFFIX: &str = "log";
+// One file a day, so about a week of history
+const MAX_LOG_FILES: usize = 7;
+const CRASH_DIR: &str = "crashes";
+// Reports already uploaded are renamed to this
+const SENT_SUFFIX: &str = ".sent";
+const DEFAULT_LOG_LINES: usize = 500;
+
+// Settings that would identify the user or their machine
+const REDACTED_KEYS: &[&str] = &[
+    "vaultPath",
+    "lastOpenedNotePath",
+    "calendarExportPath",
+    "url",
+    "username",
+    "host",
+    "clientId",
+    "listId",
+    "proxyUrl",
+    "noProxy",
+    "caCertPath",
+    "baseUrl",
+    "crashReportUrl",
+    "noteWords",
+];
+
+/// What is recorded when the backend panics. Paths under the home folder
+/// are shortened to `~` before anything is uploaded.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CrashReport {
+    pub timestamp: String,
+    pub version: String,
+    pub os: String,
+    pub arch: String,
+    pub thread: String,
+    pub message: String,
+    pub location: Option<String>,
+    pub backtrace: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct CrashUploadReport {
+    pub sent: usize,
+    pub failed: usize,
+}
+
+fn log_dir(app: &AppHandle) -> Result<PathBuf, BouldyError> {
+    let dir = app
+        .path()
+        .app_log_dir()
+        .map_err(|e| format!("Failed to resolve log directory: {}", e))?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create log directory: {}", e))?;
+    Ok(dir)
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "Unknown panic".to_string())
+}
+
+// Logs the panic and saves it as a crash report, then lets the default hook
+// print it as before
+fn install_panic_hook(crash_dir: PathBuf) {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let now = chrono::Local::now();
+        let report = CrashReport {
+            timestamp: now.to_rfc3339(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            thread: std::thread::current()
+                .name()
+                .unwrap_or("unnamed")
+                .to_string(),
+            message: panic_message(info.payload()),
+            location: info.location().map(|location| location.to_string()),
+            backtrace: Backtrace::force_capture().to_string(),
+        };
+        tracing::error!(
+            "Panic in thread {} at {}: {}",
+            report.thread,
+            report.location.as_deref().unwrap_or("unknown location"),
+            report.message
+        );
+
+        let path = crash_dir.join(format!("crash-{}.json", now.format("%Y%m%d-%H%M%S-%3f")));
+        if let Ok(json) = serde_json::to_string_pretty(&report) {
+            let _ = fs::create_dir_all(&crash_dir);
+            let _ = fs::write(path, json);
+        }
+
+        previous(info);
+    }));
+}
+
+/// Send log output to stderr and a daily rolling file in the app's log
+/// folder, and record panics as crash reports next to it.
+pub fn init(app: &AppHandle) {
+    let dir = match log_dir(app) {
+        Ok(dir) => Some(dir),
+        Err(e) => {
+            eprintln!("Warning: Logging to file disabled - {}", e);
+            None
+        }
+    };
+    let file = dir.as_ref().and_then(|dir| {
+        RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix(LOG_PREFIX)
+            .filename_suffix(LOG_SUFFIX)
+            .max_log_files(MAX_LOG_FILES)
+            .build(dir)
+            .map_err(|e| eprintln!("Warning: Logging to file disabled - {}", e))
+            .ok()
+    });
+
+    let result = tracing_subscriber::registry()
+        .with(LevelFilter::INFO)
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+        .with(file.map(|file| {
+            tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(file)
+        }))
+        .try_init();
+    if let Err(e) = result {
+        eprintln!("Warning: Logging already set up - {}", e);
+    }
+
+    if let Some(dir) = dir {
+        install_panic_hook(dir.join(CRASH_DIR));
+    }
+    tracing::info!("Bouldy {} starting", env!("CARGO_PKG_VERSION"));
+}
+
+// Log files oldest first; their names end in the date
+fn log_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(|name| {
+                            name.starts_with(LOG_PREFIX) && name.ends_with(LOG_SUFFIX)
+                        })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+fn crash_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir.join(CRASH_DIR))
+        .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+/// The last `lines` lines logged (500 by default), oldest first, across
+/// the rolling files.
+pub fn recent_logs(app: &AppHandle, lines: Option<usize>) -> Result<Vec<String>, BouldyError> {
+    let wanted = lines.unwrap_or(DEFAULT_LOG_LINES);
+    let mut recent: Vec<String> = Vec::new();
+    for file in log_files(&log_dir(app)?).iter().rev() {
+        if recent.len() >= wanted {
+            break;
+        }
+        let content =
+            fs::read_to_string(file).map_err(|e| BouldyError::io("Failed to read log file", e))?;
+        let mut older: Vec<String> = content
+            .lines()
+            .rev()
+            .take(wanted - recent.len())
+            .map(str::to_string)
+            .collect();
+        older.reverse();
+        older.append(&mut recent);
+        recent = older;
+    }
+    Ok(recent)
+}
+
+fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if REDACTED_KEYS.contains(&key.as_str()) && !value.is_null() {
+                    *value = Value::String("<redacted>".to_string());
+                } else {
+                    redact(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+fn system_info() -> Value {
+    serde_json::json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "os": std::env::consts::OS,
+        "family": std::env::consts::FAMILY,
+        "arch": std::env::consts::ARCH,
+        "createdAt": chrono::Local::now().to_rfc3339(),
+    })
+}
+
+/// Write a zip with the logs, crash reports, startup metrics, system info
+/// and settings (with paths, hosts and accounts redacted) to `output_path`,
+/// for attaching to a bug report.
+pub fn export_bundle(app: &AppHandle, output_path: &Path) -> Result<(), BouldyError> {
+    let dir = log_dir(app)?;
+    let mut settings = serde_json::to_value(settings::load_settings(app)?)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    redact(&mut settings);
+
+    let file =
+        File::create(output_path).map_err(|e| BouldyError::io("Failed to create bundle", e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = SimpleFileOptions::default();
+    let mut add = |name: &str, bytes: &[u8]| -> Result<(), BouldyError> {
+        zip.start_file(name, options)
+            .map_err(|e| format!("Failed to write bundle: {}", e))?;
+        zip.write_all(bytes)
+            .map_err(|e| BouldyError::io("Failed to write bundle", e))
+    };
+
+    add(
+        "system.json",
+        serde_json::to_string_pretty(&system_info())
+            .unwrap_or_default()
+            .as_bytes(),
+    )?;
+    add(
+        "settings.json",
+        serde_json::to_string_pretty(&settings)
+            .unwrap_or_default()
+            .as_bytes(),
+    )?;
+    let files = log_files(&dir)
+        .into_iter()
+        .map(|path| ("logs", path))
+        .chain(crash_files(&dir).into_iter().map(|path| (CRASH_DIR, path)))
+        .chain(std::iter::once(("", dir.join("startup-metrics.jsonl"))));
+    for (folder, path) in files {
+        let (Some(name), Ok(bytes)) = (path.file_name(), fs::read(&path)) else {
+            continue;
+        };
+        let name = name.to_string_lossy();
+        let entry = if folder.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}/{}", folder, name)
+        };
+        add(&entry, &bytes)?;
+    }
+
+    zip.finish()
+        .map_err(|e| format!("Failed to write bundle: {}", e))?;
+    Ok(())
+}
+
+// Home folders usually contain the user's name
+fn anonymize(report: &mut CrashReport) {
+    let Some(home) = dirs::home_dir() else {
+        return;
+    };
+    let home = home.to_string_lossy().to_string();
+    if home.len() > 1 {
+        report.message = report.message.replace(&home, "~");
+        report.backtrace = report.backtrace.replace(&home, "~");
+        if let Some(ref mut location) = report.location {
+            *location = location.replace(&home, "~");
+        }
+    }
+}
+
+/// Upload crash reports not sent yet to the configured endpoint. Nothing
+/// leaves the machine unless `crashReportUrl` is set.
+pub async fn upload_crash_reports(
+    app: &AppHandle,
+    settings: &Settings,
+) -> Result<CrashUploadReport, BouldyError> {
+    let mut report = CrashUploadReport { sent: 0, failed: 0 };
+    let Some(ref url) = settings.crash_report_url else {
+        return Ok(report);
+    };
+    let client = network::http_client(&settings.network)?;
+
+    for path in crash_files(&log_dir(app)?) {
+        if path.extension().is_none_or(|extension| extension != "json") {
+            continue;
+        }
+        let Some(mut crash) = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<CrashReport>(&content).ok())
+        else {
+            continue;
+        };
+        anonymize(&mut crash);
+
+        let sent = client
+            .post(url)
+            .json(&crash)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        match sent {
+            Ok(_) => {
+                let mut sent_path = path.clone().into_os_string();
+                sent_path.push(SENT_SUFFIX);
+                let _ = fs::rename(&path, sent_path);
+                report.sent += 1;
+            }
+            Err(e) => {
+                tracing::warn!("Failed to upload crash report - {}", e);
+                report.failed += 1;
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Upload pending crash reports in the background, if enabled.
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let Ok(app_settings) = settings::load_settings(&app) else {
+            return;
+        };
+        if let Err(e) = upload_crash_reports(&app, &app_settings).await {
+            tracing::warn!("Crash report upload failed - {}", e);
+        }
+    });
+}
+
+pub fn validate_report_url(url: &str) -> Result<(), BouldyError> {
+    let parsed = tauri::Url::parse(url).map_err(|e| {
+        BouldyError::invalid_input(format!("Invalid crash report URL '{}': {}", url, e))
+    })?;
+    if parsed.scheme() != "https" {
+        return Err(BouldyError::invalid_input(format!(
+            "Crash report URL must be https: '{}'",
+            url
+        )));
+    }
+    Ok(())
+}
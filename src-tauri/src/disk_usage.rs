@@ -0,0 +1,158 @@
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::BouldyError;
+use crate::{vault, vault_config};
+
+// Largest files reported, across all categories
+const LARGEST_FILES: usize = 20;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Category {
+    #[serde(rename = "notes")]
+    Notes,
+    // The attachments folder, without notes kept there
+    #[serde(rename = "assets")]
+    Assets,
+    // Note versions in .bouldy/history
+    #[serde(rename = "history")]
+    History,
+    // Obsidian's .trash folder, where it can move deleted notes
+    #[serde(rename = "trash")]
+    Trash,
+    // The search index, with SQLite's -wal and -shm files
+    #[serde(rename = "index")]
+    Index,
+    // todo.txt, the rest of .bouldy, .obsidian, .git and anything else
+    #[serde(rename = "other")]
+    Other,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct CategoryUsage {
+    pub bytes: u64,
+    pub files: usize,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct LargeFile {
+    pub path: String,
+    pub size: u64,
+    pub category: Category,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct DiskUsage {
+    pub notes: CategoryUsage,
+    pub assets: CategoryUsage,
+    pub history: CategoryUsage,
+    pub trash: CategoryUsage,
+    pub index: CategoryUsage,
+    pub other: CategoryUsage,
+    pub total: u64,
+    // Biggest first
+    pub largest: Vec<LargeFile>,
+}
+
+impl DiskUsage {
+    fn category_mut(&mut self, category: Category) -> &mut CategoryUsage {
+        match category {
+            Category::Notes => &mut self.notes,
+            Category::Assets => &mut self.assets,
+            Category::History => &mut self.history,
+            Category::Trash => &mut self.trash,
+            Category::Index => &mut self.index,
+            Category::Other => &mut self.other,
+        }
+    }
+}
+
+// Every file under `dir`, hidden ones included. Symlinks aren't followed, so
+// a linked folder isn't counted twice or against this vault.
+fn walk_all(dir: &Path, files: &mut Vec<(PathBuf, u64)>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            walk_all(&entry.path(), files);
+        } else if file_type.is_file() {
+            let size = entry.metadata().map(|m| m.len()).unwrap_or_default();
+            files.push((entry.path(), size));
+        }
+    }
+}
+
+fn categorize(relative: &Path, path: &Path, attachments_dir: &Path) -> Category {
+    let mut components = relative.iter().map(|c| c.to_string_lossy());
+    let first = components.next().unwrap_or_default();
+    if first == ".bouldy" {
+        let second = components.next().unwrap_or_default();
+        return if second == "history" {
+            Category::History
+        } else if second.starts_with("index.db") {
+            Category::Index
+        } else {
+            Category::Other
+        };
+    }
+    if first == ".trash" {
+        return Category::Trash;
+    }
+    // Obsidian's attachments folder may be the vault root, which shouldn't
+    // claim .obsidian or .git
+    let hidden = relative
+        .iter()
+        .any(|c| c.to_string_lossy().starts_with('.'));
+    if vault::is_markdown_file(path) && !hidden {
+        Category::Notes
+    } else if path.starts_with(attachments_dir) && !hidden {
+        Category::Assets
+    } else {
+        Category::Other
+    }
+}
+
+/// How much space the vault takes, by category, and its largest files, so
+/// the user can see what to clean up.
+pub fn get_disk_usage(vault_path: &str) -> Result<DiskUsage, BouldyError> {
+    let vault = Path::new(vault_path);
+    if !vault.is_dir() {
+        return Err(BouldyError::not_found(format!(
+            "Vault not found: {}",
+            vault_path
+        )));
+    }
+    let attachments_dir = vault_config::load(vault_path)?.attachments_dir(vault_path, None);
+
+    let mut files = Vec::new();
+    walk_all(vault, &mut files);
+
+    let mut usage = DiskUsage::default();
+    let mut largest = Vec::with_capacity(files.len());
+    for (path, size) in files {
+        let relative = path.strip_prefix(vault).unwrap_or(&path);
+        let category = categorize(relative, &path, &attachments_dir);
+        let entry = usage.category_mut(category);
+        entry.bytes += size;
+        entry.files += 1;
+        usage.total += size;
+        largest.push((size, path, category));
+    }
+
+    largest.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    usage.largest = largest
+        .into_iter()
+        .take(LARGEST_FILES)
+        .map(|(size, path, category)| LargeFile {
+            path: path.to_string_lossy().to_string(),
+            size,
+            category,
+        })
+        .collect();
+    Ok(usage)
+}
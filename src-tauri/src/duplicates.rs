@@ -0,0 +1,223 @@
+use rayon::prelude::*;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::hash::{Hash, Hasher};
+
+use crate::error::BouldyError;
+use crate::{fsutil, markdown, text, vault};
+
+// Words per shingle; shorter notes can only be exact duplicates
+const SHINGLE_SIZE: usize = 5;
+// Share of shingles two notes need in common to count as near-duplicates
+const NEAR_THRESHOLD: f64 = 0.8;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct DuplicateNote {
+    pub path: String,
+    pub title: String,
+    pub modified: u64,
+    pub size: usize,
+    // Notes elsewhere in the vault linking here
+    pub backlinks: usize,
+    // Shingle overlap with the canonical note, 1.0 for identical content
+    pub similarity: f64,
+}
+
+/// Notes with the same or nearly the same content. `canonical` is the one
+/// to keep when merging: the most linked-to, then the longest, then the
+/// oldest.
+#[derive(Serialize, Clone, Debug)]
+pub struct DuplicateCluster {
+    pub canonical: String,
+    pub exact: bool,
+    pub notes: Vec<DuplicateNote>,
+}
+
+struct Scanned {
+    note: vault::Note,
+    size: usize,
+    hash: [u8; 32],
+    shingles: HashSet<u64>,
+    links: Vec<String>,
+}
+
+// Line endings and trailing spaces don't make a note different
+fn normalize(body: &str) -> String {
+    body.lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+fn shingles(body: &str) -> HashSet<u64> {
+    let folded = text::fold(body);
+    let words: Vec<&str> = folded
+        .split_whitespace()
+        .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()))
+        .filter(|word| !word.is_empty())
+        .collect();
+    words
+        .windows(SHINGLE_SIZE)
+        .map(|window| {
+            let mut hasher = DefaultHasher::new();
+            window.hash(&mut hasher);
+            hasher.finish()
+        })
+        .collect()
+}
+
+fn jaccard(a: &HashSet<u64>, b: &HashSet<u64>) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let shared = a.intersection(b).count();
+    shared as f64 / (a.len() + b.len() - shared) as f64
+}
+
+fn scan(note: vault::Note) -> Option<Scanned> {
+    let content = fs::read_to_string(fsutil::long_path(note.path.as_ref())).ok()?;
+    let body = normalize(markdown::split_frontmatter(&content).1);
+    // Every empty note would otherwise be a duplicate of every other
+    if body.is_empty() {
+        return None;
+    }
+    Some(Scanned {
+        size: content.len(),
+        hash: Sha256::digest(body.as_bytes()).into(),
+        shingles: shingles(&body),
+        links: markdown::extract_links(&content),
+        note,
+    })
+}
+
+fn root(parents: &mut [usize], mut i: usize) -> usize {
+    while parents[i] != i {
+        parents[i] = parents[parents[i]];
+        i = parents[i];
+    }
+    i
+}
+
+fn join(parents: &mut [usize], a: usize, b: usize) {
+    let (a, b) = (root(parents, a), root(parents, b));
+    parents[a.max(b)] = a.min(b);
+}
+
+/// Groups of notes whose bodies (frontmatter aside) are identical or share
+/// most of their word sequences, largest groups first.
+pub fn find_duplicates(vault_path: &str) -> Result<Vec<DuplicateCluster>, BouldyError> {
+    let mut notes: Vec<Scanned> = vault::list_notes(vault_path)?
+        .into_par_iter()
+        .filter_map(scan)
+        .collect();
+    // By shingle count, so each note is only compared with ones close enough
+    // in size to reach the threshold
+    notes.sort_by_key(|scanned| scanned.shingles.len());
+
+    let mut parents: Vec<usize> = (0..notes.len()).collect();
+    let mut by_hash: HashMap<[u8; 32], usize> = HashMap::new();
+    for (i, scanned) in notes.iter().enumerate() {
+        if let Some(&first) = by_hash.get(&scanned.hash) {
+            join(&mut parents, first, i);
+        } else {
+            by_hash.insert(scanned.hash, i);
+        }
+    }
+
+    let pairs: Vec<(usize, usize)> = (0..notes.len())
+        .into_par_iter()
+        .flat_map_iter(|i| {
+            let notes = &notes;
+            let smaller = notes[i].shingles.len();
+            (i + 1..notes.len())
+                .take_while(move |&j| {
+                    smaller as f64 >= notes[j].shingles.len() as f64 * NEAR_THRESHOLD
+                })
+                .filter(move |&j| jaccard(&notes[i].shingles, &notes[j].shingles) >= NEAR_THRESHOLD)
+                .map(move |j| (i, j))
+        })
+        .collect();
+    for (i, j) in pairs {
+        join(&mut parents, i, j);
+    }
+
+    let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+    for i in 0..notes.len() {
+        let group = root(&mut parents, i);
+        groups.entry(group).or_default().push(i);
+    }
+
+    // Incoming links by folded note name
+    let mut incoming: HashMap<String, usize> = HashMap::new();
+    for scanned in &notes {
+        for link in &scanned.links {
+            *incoming.entry(text::fold(link)).or_default() += 1;
+        }
+    }
+    let backlinks = |scanned: &Scanned| {
+        incoming
+            .get(&text::fold(&scanned.note.title))
+            .copied()
+            .unwrap_or_default()
+    };
+
+    let mut clusters: Vec<DuplicateCluster> = groups
+        .into_values()
+        .filter(|members| members.len() > 1)
+        .map(|members| {
+            let canonical = *members
+                .iter()
+                .max_by_key(|&&i| {
+                    let scanned = &notes[i];
+                    (
+                        backlinks(scanned),
+                        scanned.size,
+                        std::cmp::Reverse(scanned.note.modified),
+                    )
+                })
+                .unwrap();
+            let exact = members
+                .iter()
+                .all(|&i| notes[i].hash == notes[canonical].hash);
+
+            let mut members: Vec<DuplicateNote> = members
+                .iter()
+                .map(|&i| {
+                    let scanned = &notes[i];
+                    DuplicateNote {
+                        path: scanned.note.path.clone(),
+                        title: scanned.note.title.clone(),
+                        modified: scanned.note.modified,
+                        size: scanned.size,
+                        backlinks: backlinks(scanned),
+                        similarity: if scanned.hash == notes[canonical].hash {
+                            1.0
+                        } else {
+                            jaccard(&scanned.shingles, &notes[canonical].shingles)
+                        },
+                    }
+                })
+                .collect();
+            members.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+
+            DuplicateCluster {
+                canonical: notes[canonical].note.path.clone(),
+                exact,
+                notes: members,
+            }
+        })
+        .collect();
+
+    clusters.sort_by(|a, b| {
+        b.notes
+            .len()
+            .cmp(&a.notes.len())
+            .then_with(|| a.canonical.cmp(&b.canonical))
+    });
+    Ok(clusters)
+}
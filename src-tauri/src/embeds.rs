@@ -0,0 +1,133 @@
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::error::BouldyError;
+use crate::markdown::{self, OutlineHeading};
+use crate::{fsutil, text, vault};
+
+/// How deep embeds inside embedded notes are expanded by default.
+pub const DEFAULT_DEPTH: usize = 3;
+
+fn embed_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    // ![[Note]], ![[Note#Heading]], ![[Note|label]]
+    REGEX.get_or_init(|| Regex::new(r"!\[\[([^\]|#]+)(?:#([^\]|]*))?(?:\|[^\]]*)?\]\]").unwrap())
+}
+
+// Headings in document order
+fn flatten<'a>(headings: &'a [OutlineHeading], flat: &mut Vec<&'a OutlineHeading>) {
+    for heading in headings {
+        flat.push(heading);
+        flatten(&heading.children, flat);
+    }
+}
+
+/// The part of `content` from `heading` up to the next heading of the same
+/// or a higher level. Obsidian's `#A#B` form picks the last heading named.
+fn heading_section<'a>(content: &'a str, heading: &str) -> Option<&'a str> {
+    let wanted = text::fold(heading.rsplit('#').next().unwrap_or(heading).trim());
+    let outline = markdown::outline(content);
+    let mut flat = Vec::new();
+    flatten(&outline, &mut flat);
+
+    let position = flat
+        .iter()
+        .position(|candidate| text::fold(&candidate.text) == wanted)?;
+    let found = flat[position];
+    let end = flat[position + 1..]
+        .iter()
+        .find(|next| next.level <= found.level)
+        .map(|next| next.start)
+        .unwrap_or(content.len());
+    Some(&content[found.start..end])
+}
+
+struct Expander<'a> {
+    vault_path: &'a str,
+    // Note names already looked up, so repeated embeds don't rescan the vault
+    targets: HashMap<String, Option<PathBuf>>,
+    // Notes being expanded, outermost first
+    stack: Vec<PathBuf>,
+}
+
+impl Expander<'_> {
+    fn find(&mut self, name: &str) -> Option<PathBuf> {
+        let vault_path = self.vault_path;
+        self.targets
+            .entry(text::fold(name))
+            .or_insert_with(|| {
+                vault::find_note_by_title(vault_path, name)
+                    .or_else(|| vault::find_note_by_alias(vault_path, name))
+                    .and_then(|path| path.canonicalize().ok())
+            })
+            .clone()
+    }
+
+    fn expand(&mut self, content: &str, depth: usize) -> String {
+        if depth == 0 {
+            return content.to_string();
+        }
+
+        let mut expanded = String::with_capacity(content.len());
+        let mut in_code_block = false;
+        for line in content.split_inclusive('\n') {
+            if line.trim_start().starts_with("```") {
+                in_code_block = !in_code_block;
+            }
+            if in_code_block || !line.contains("![[") {
+                expanded.push_str(line);
+                continue;
+            }
+            let line = embed_regex().replace_all(line, |capture: &Captures| {
+                self.embed(capture, depth)
+                    .unwrap_or_else(|| capture[0].to_string())
+            });
+            expanded.push_str(&line);
+        }
+        expanded
+    }
+
+    // None leaves the embed as written: attachments, missing notes or
+    // headings, and notes that would end up embedding themselves
+    fn embed(&mut self, capture: &Captures, depth: usize) -> Option<String> {
+        let name = vault::link_target_name(&capture[1]);
+        let path = self.find(name)?;
+        if self.stack.contains(&path) {
+            return None;
+        }
+
+        let content = fs::read_to_string(fsutil::long_path(&path)).ok()?;
+        let part = match capture.get(2) {
+            Some(heading) => heading_section(&content, heading.as_str())?,
+            None => markdown::split_frontmatter(&content).1,
+        };
+
+        self.stack.push(path);
+        let expanded = self.expand(part.trim_end(), depth - 1);
+        self.stack.pop();
+        Some(expanded)
+    }
+}
+
+/// The note's content with `![[Note]]` and `![[Note#Heading]]` embeds
+/// replaced by what they point to, `depth` levels deep. Embeds that can't
+/// be expanded, including ones that would loop back to a note already being
+/// expanded, are left as written.
+pub fn resolve_embeds(vault_path: &str, note: &Path, depth: usize) -> Result<String, BouldyError> {
+    let content = fs::read_to_string(fsutil::long_path(note))
+        .map_err(|e| BouldyError::io("Failed to read note", e))?;
+    Ok(expand_content(vault_path, note, &content, depth))
+}
+
+/// Like `resolve_embeds`, for content already read from `note`.
+pub fn expand_content(vault_path: &str, note: &Path, content: &str, depth: usize) -> String {
+    let mut expander = Expander {
+        vault_path,
+        targets: HashMap::new(),
+        stack: note.canonicalize().into_iter().collect(),
+    };
+    expander.expand(content, depth)
+}
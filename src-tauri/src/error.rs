@@ -0,0 +1,152 @@
+use serde::Serialize;
+use std::fmt;
+use std::io;
+
+/// Error returned by commands. Serialized as `{ type, message, ... }` so the
+/// frontend can branch on `type` instead of matching message text.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum BouldyError {
+    NotFound {
+        message: String,
+    },
+    OutsideVault {
+        message: String,
+    },
+    // The target already exists, or changed underneath us
+    Conflict {
+        message: String,
+    },
+    Io {
+        // std::io::ErrorKind, e.g. "PermissionDenied"
+        kind: String,
+        message: String,
+    },
+    ParseError {
+        line: usize,
+        message: String,
+    },
+    // A file name bouldy can't represent or open, e.g. one that isn't UTF-8
+    InvalidPath {
+        message: String,
+    },
+    // The user dismissed a dialog or otherwise backed out
+    Cancelled {
+        message: String,
+    },
+    // Bad values from the user or a client: a malformed date, an empty
+    // title, a setting out of range
+    InvalidInput {
+        message: String,
+    },
+    // Failures on our side; bad input is InvalidInput
+    Other {
+        message: String,
+    },
+}
+
+impl BouldyError {
+    pub fn not_found(message: impl Into<String>) -> Self {
+        BouldyError::NotFound {
+            message: message.into(),
+        }
+    }
+
+    pub fn outside_vault(message: impl Into<String>) -> Self {
+        BouldyError::OutsideVault {
+            message: message.into(),
+        }
+    }
+
+    pub fn conflict(message: impl Into<String>) -> Self {
+        BouldyError::Conflict {
+            message: message.into(),
+        }
+    }
+
+    pub fn invalid_path(message: impl Into<String>) -> Self {
+        BouldyError::InvalidPath {
+            message: message.into(),
+        }
+    }
+
+    pub fn cancelled(message: impl Into<String>) -> Self {
+        BouldyError::Cancelled {
+            message: message.into(),
+        }
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        BouldyError::InvalidInput {
+            message: message.into(),
+        }
+    }
+
+    /// Wrap an io error with what we were doing, e.g. "Failed to read note".
+    pub fn io(context: &str, e: io::Error) -> Self {
+        let message = format!("{}: {}", context, e);
+        match e.kind() {
+            io::ErrorKind::NotFound => BouldyError::NotFound { message },
+            kind => BouldyError::Io {
+                kind: format!("{:?}", kind),
+                message,
+            },
+        }
+    }
+
+    pub fn parse(context: &str, e: serde_json::Error) -> Self {
+        BouldyError::ParseError {
+            line: e.line(),
+            message: format!("{}: {}", context, e),
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            BouldyError::NotFound { message }
+            | BouldyError::OutsideVault { message }
+            | BouldyError::Conflict { message }
+            | BouldyError::Io { message, .. }
+            | BouldyError::ParseError { message, .. }
+            | BouldyError::InvalidPath { message }
+            | BouldyError::Cancelled { message }
+            | BouldyError::InvalidInput { message }
+            | BouldyError::Other { message } => message,
+        }
+    }
+}
+
+impl fmt::Display for BouldyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for BouldyError {}
+
+impl From<io::Error> for BouldyError {
+    fn from(e: io::Error) -> Self {
+        BouldyError::io("I/O error", e)
+    }
+}
+
+// Plain strings are internal failures; bad input uses invalid_input
+impl From<String> for BouldyError {
+    fn from(message: String) -> Self {
+        BouldyError::Other { message }
+    }
+}
+
+impl From<&str> for BouldyError {
+    fn from(message: &str) -> Self {
+        BouldyError::Other {
+            message: message.to_string(),
+        }
+    }
+}
+
+impl From<BouldyError> for String {
+    fn from(e: BouldyError) -> Self {
+        e.to_string()
+    }
+}
@@ -0,0 +1,156 @@
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::BouldyError;
+use crate::{vault, vault_config, vault_lock};
+
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "svg", "bmp"];
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ImportedAttachment {
+    pub path: String,
+    pub link: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct SkippedFile {
+    pub path: String,
+    pub reason: String,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct ImportReport {
+    pub notes: Vec<String>,
+    pub attachments: Vec<ImportedAttachment>,
+    pub skipped: Vec<SkippedFile>,
+    // The open note the attachment links are relative to; the editor
+    // showing it inserts them, so they don't race its autosave
+    #[serde(rename = "linkedInto")]
+    pub linked_into: Option<String>,
+}
+
+fn is_markdown(path: &Path) -> bool {
+    matches!(
+        path.extension()
+            .and_then(|s| s.to_str())
+            .map(|s| s.to_lowercase())
+            .as_deref(),
+        Some("md") | Some("markdown")
+    )
+}
+
+pub fn is_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|s| s.to_str())
+        .map(|ext| IMAGE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// `dir/name.ext`, or `dir/name (1).ext`, `dir/name (2).ext`... if taken.
+pub fn unique_destination(dir: &Path, file_name: &str) -> PathBuf {
+    let candidate = dir.join(file_name);
+    if !candidate.exists() {
+        return candidate;
+    }
+
+    let original = Path::new(file_name);
+    let stem = original
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = original
+        .extension()
+        .map(|s| format!(".{}", s.to_string_lossy()))
+        .unwrap_or_default();
+
+    (1..)
+        .map(|n| dir.join(format!("{} ({}){}", stem, n, extension)))
+        .find(|path| !path.exists())
+        .unwrap()
+}
+
+/// Import files dropped onto the window: markdown is copied into notes/,
+/// everything else into the vault's attachments folder (assets/ unless
+/// configured otherwise) with a link relative to `target_note` (the note the
+/// user has open), if there is one. The note itself isn't touched.
+pub fn import_dropped_files(
+    vault_path: &str,
+    paths: &[PathBuf],
+    target_note: Option<&Path>,
+) -> Result<ImportReport, BouldyError> {
+    let vault = Path::new(vault_path);
+    let notes_dir = vault.join("notes");
+    let config = vault_config::load(vault_path)?;
+    let assets_dir = config.attachments_dir(vault_path, target_note);
+    // Links are relative to the note they're appended to
+    let link_dir = target_note
+        .and_then(Path::parent)
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| notes_dir.clone());
+
+    let _lock = vault_lock::acquire(vault_path)?;
+
+    let mut report = ImportReport::default();
+
+    for source in paths {
+        let skip = |reason: &str| SkippedFile {
+            path: source.to_string_lossy().to_string(),
+            reason: reason.to_string(),
+        };
+
+        if !source.is_file() {
+            report.skipped.push(skip("Not a file"));
+            continue;
+        }
+
+        // Dropping a file that's already in the vault is a no-op, not a copy
+        if source.starts_with(vault) {
+            report.skipped.push(skip("Already in vault"));
+            continue;
+        }
+
+        let Some(file_name) = source.file_name().and_then(|n| n.to_str()) else {
+            report.skipped.push(skip("Invalid file name"));
+            continue;
+        };
+        // The vault may be opened on Windows later
+        let file_name = vault::sanitize_file_name(file_name);
+
+        if is_markdown(source) {
+            let dest = unique_destination(&notes_dir, &file_name);
+            match fs::copy(source, &dest) {
+                Ok(_) => report.notes.push(dest.to_string_lossy().to_string()),
+                Err(e) => report
+                    .skipped
+                    .push(skip(&format!("Failed to copy file: {}", e))),
+            }
+            continue;
+        }
+
+        fs::create_dir_all(&assets_dir)
+            .map_err(|e| format!("Failed to create attachments directory: {}", e))?;
+
+        let dest = unique_destination(&assets_dir, &file_name);
+        if let Err(e) = fs::copy(source, &dest) {
+            report
+                .skipped
+                .push(skip(&format!("Failed to copy file: {}", e)));
+            continue;
+        }
+
+        let link = config.attachment_link(&dest, &link_dir, is_image(source));
+        report.attachments.push(ImportedAttachment {
+            path: dest.to_string_lossy().to_string(),
+            link,
+        });
+    }
+
+    if let Some(note_path) = target_note {
+        if !report.attachments.is_empty() {
+            report.linked_into = Some(note_path.to_string_lossy().to_string());
+        }
+    }
+
+    Ok(report)
+}
@@ -0,0 +1,268 @@
+use chrono::{Duration, NaiveDate};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::error::BouldyError;
+use crate::{fsutil, markdown, vault, vault_lock};
+
+// Cards are written straight into notes:
+//
+//   Q:: What does SM-2 stand for?
+//   A:: SuperMemo 2, the scheduling
+//   algorithm behind most flashcard apps
+//
+//   The {{c1::mitochondria}} is the {{c2::powerhouse::role}} of the cell.
+//
+// An answer runs until the next blank line. Each cloze number is its own
+// card. Scheduling lives in `.bouldy/srs.json`, keyed by a hash of the
+// card text, so editing a card starts it over.
+const STATE_FILE: &str = "srs.json";
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const DEFAULT_EASE: f64 = 2.5;
+const MIN_EASE: f64 = 1.3;
+pub const MAX_GRADE: u8 = 5;
+// Grades below this are a failed recall
+const PASSING_GRADE: u8 = 3;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Schedule {
+    pub ease: f64,
+    // Days until the next review
+    pub interval: u32,
+    // Successful reviews in a row
+    pub repetitions: u32,
+    pub due: String,
+    #[serde(rename = "lastReviewed")]
+    pub last_reviewed: String,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct SrsState {
+    #[serde(default)]
+    cards: HashMap<String, Schedule>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Flashcard {
+    pub id: String,
+    pub question: String,
+    pub answer: String,
+    #[serde(rename = "notePath")]
+    pub note_path: String,
+    // None until the first review
+    pub schedule: Option<Schedule>,
+}
+
+fn cloze_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    // {{c1::answer}} or {{c1::answer::hint}}
+    REGEX.get_or_init(|| Regex::new(r"\{\{c(\d+)::(.*?)(?:::(.*?))?\}\}").unwrap())
+}
+
+fn state_path(vault_path: &str) -> PathBuf {
+    Path::new(vault_path).join(".bouldy").join(STATE_FILE)
+}
+
+fn load_state(vault_path: &str) -> SrsState {
+    fs::read_to_string(state_path(vault_path))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(vault_path: &str, state: &SrsState) -> Result<(), BouldyError> {
+    let content = serde_json::to_string_pretty(state)
+        .map_err(|e| format!("Failed to serialize flashcard state: {}", e))?;
+    fsutil::write_atomic(&state_path(vault_path), content)
+        .map_err(|e| BouldyError::io("Failed to write flashcard state", e))
+}
+
+fn card_id(text: &str) -> String {
+    let digest = Sha256::digest(text.as_bytes());
+    digest[..6].iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// One card per cloze number: that cloze hidden, the others shown
+fn cloze_cards(line: &str, note_path: &str, cards: &mut Vec<Flashcard>) {
+    let numbers: BTreeSet<&str> = cloze_regex()
+        .captures_iter(line)
+        .filter_map(|captures| captures.get(1))
+        .map(|number| number.as_str())
+        .collect();
+
+    for number in numbers {
+        let question = cloze_regex().replace_all(line, |captures: &regex::Captures| {
+            if &captures[1] == number {
+                format!("[{}]", captures.get(3).map_or("...", |hint| hint.as_str()))
+            } else {
+                captures[2].to_string()
+            }
+        });
+        let answer =
+            cloze_regex().replace_all(line, |captures: &regex::Captures| captures[2].to_string());
+        cards.push(Flashcard {
+            id: card_id(&format!("c{}\0{}", number, line)),
+            question: question.trim().to_string(),
+            answer: answer.trim().to_string(),
+            note_path: note_path.to_string(),
+            schedule: None,
+        });
+    }
+}
+
+fn extract_cards(content: &str, note_path: &str) -> Vec<Flashcard> {
+    let (_, body) = markdown::split_frontmatter(content);
+    let mut cards = Vec::new();
+    let mut lines = body.lines().peekable();
+    let mut in_code = false;
+
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            continue;
+        }
+
+        if let Some(question) = trimmed.strip_prefix("Q::") {
+            let mut answer = Vec::new();
+            if let Some(first) = lines.peek().and_then(|l| l.trim().strip_prefix("A::")) {
+                answer.push(first.trim().to_string());
+                lines.next();
+                while let Some(next) = lines.peek().filter(|l| !l.trim().is_empty()) {
+                    answer.push(next.trim().to_string());
+                    lines.next();
+                }
+            }
+            let question = question.trim();
+            if question.is_empty() || answer.is_empty() {
+                continue;
+            }
+            cards.push(Flashcard {
+                id: card_id(&format!("q\0{}", question)),
+                question: question.to_string(),
+                answer: answer.join("\n"),
+                note_path: note_path.to_string(),
+                schedule: None,
+            });
+        } else if cloze_regex().is_match(trimmed) {
+            cloze_cards(trimmed, note_path, &mut cards);
+        }
+    }
+
+    cards
+}
+
+fn all_cards(vault_path: &str) -> Result<Vec<Flashcard>, BouldyError> {
+    let mut cards = Vec::new();
+    for note in vault::list_notes(vault_path)? {
+        match fs::read_to_string(&note.path) {
+            Ok(content) => cards.extend(extract_cards(&content, &note.path)),
+            Err(e) => tracing::warn!("Failed to read {} - {}", note.path, e),
+        }
+    }
+    Ok(cards)
+}
+
+/// Cards due today or earlier, most overdue first, then cards never
+/// reviewed. `limit` caps how many are returned.
+pub fn get_due_cards(
+    vault_path: &str,
+    limit: Option<usize>,
+) -> Result<Vec<Flashcard>, BouldyError> {
+    let state = load_state(vault_path);
+    let today = chrono::Local::now()
+        .date_naive()
+        .format(DATE_FORMAT)
+        .to_string();
+
+    let mut seen = BTreeSet::new();
+    let mut due: Vec<Flashcard> = all_cards(vault_path)?
+        .into_iter()
+        // The same card copied into two notes is reviewed once
+        .filter(|card| seen.insert(card.id.clone()))
+        .map(|mut card| {
+            card.schedule = state.cards.get(&card.id).cloned();
+            card
+        })
+        .filter(|card| card.schedule.as_ref().is_none_or(|s| s.due <= today))
+        .collect();
+
+    due.sort_by(|a, b| match (&a.schedule, &b.schedule) {
+        (Some(a), Some(b)) => a.due.cmp(&b.due),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    });
+    if let Some(limit) = limit {
+        due.truncate(limit);
+    }
+    Ok(due)
+}
+
+// SM-2: grades 0-5, where 3 and up count as remembered
+fn next_schedule(previous: Option<&Schedule>, grade: u8, today: NaiveDate) -> Schedule {
+    let (ease, interval, repetitions) = previous
+        .map(|s| (s.ease, s.interval, s.repetitions))
+        .unwrap_or((DEFAULT_EASE, 0, 0));
+
+    let (interval, repetitions) = if grade >= PASSING_GRADE {
+        let interval = match repetitions {
+            0 => 1,
+            1 => 6,
+            _ => (f64::from(interval) * ease).round() as u32,
+        };
+        (interval, repetitions + 1)
+    } else {
+        (1, 0)
+    };
+
+    let miss = f64::from(MAX_GRADE - grade);
+    let ease = (ease + 0.1 - miss * (0.08 + miss * 0.02)).max(MIN_EASE);
+
+    Schedule {
+        ease,
+        interval,
+        repetitions,
+        due: (today + Duration::days(interval.into()))
+            .format(DATE_FORMAT)
+            .to_string(),
+        last_reviewed: today.format(DATE_FORMAT).to_string(),
+    }
+}
+
+/// Record a review of card `id` graded 0 (blackout) to 5 (perfect) and
+/// schedule its next one. Unknown ids are NotFound.
+pub fn review_card(vault_path: &str, id: &str, grade: u8) -> Result<Schedule, BouldyError> {
+    if grade > MAX_GRADE {
+        return Err(BouldyError::invalid_input(format!(
+            "Grade must be between 0 and {}",
+            MAX_GRADE
+        )));
+    }
+    // Ids come from the card's text, so a card edited since it was shown is
+    // gone rather than renamed
+    if !all_cards(vault_path)?.iter().any(|card| card.id == id) {
+        return Err(BouldyError::not_found(format!(
+            "No flashcard with id {}",
+            id
+        )));
+    }
+
+    let _lock = vault_lock::acquire(vault_path)?;
+    let mut state = load_state(vault_path);
+    let today = chrono::Local::now().date_naive();
+    let schedule = next_schedule(state.cards.get(id), grade, today);
+    state.cards.insert(id.to_string(), schedule.clone());
+    save_state(vault_path, &state)?;
+
+    Ok(schedule)
+}
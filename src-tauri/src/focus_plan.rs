@@ -0,0 +1,218 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::BouldyError;
+use crate::fsutil;
+use crate::pomodoro::{self, PomodoroSettings};
+use crate::todo_store::TodoStore;
+use crate::todos::{self, TodoItem};
+
+const PLANS_FILE: &str = "focus-plans.json";
+const DATE_FORMAT: &str = "%Y-%m-%d";
+// Pomodoros planned for a day without a daily goal
+const DEFAULT_CAPACITY: u32 = 8;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PlannedTodo {
+    // Planned todos get a uid, so the plan survives edits and reordering
+    pub uid: String,
+    pub title: String,
+    pub planned: u32,
+    #[serde(default)]
+    pub completed: u32,
+    // Checked off or no longer in todo.txt
+    #[serde(default)]
+    pub done: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct FocusPlan {
+    pub date: String,
+    pub capacity: u32,
+    pub todos: Vec<PlannedTodo>,
+    // Work sessions that day, and how many went to todos outside the plan
+    #[serde(default)]
+    pub completed: u32,
+    #[serde(default)]
+    pub unplanned: u32,
+    // Uid of the todo to start the next session on
+    #[serde(default)]
+    pub next: Option<String>,
+}
+
+// Date -> plan
+#[derive(Serialize, Deserialize, Default)]
+struct Plans {
+    plans: BTreeMap<String, FocusPlan>,
+}
+
+fn plans_path(vault_path: &str) -> PathBuf {
+    Path::new(vault_path).join(".bouldy").join(PLANS_FILE)
+}
+
+fn load_plans(vault_path: &str) -> Plans {
+    fs::read_to_string(plans_path(vault_path))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_plans(vault_path: &str, plans: &Plans) -> Result<(), BouldyError> {
+    let content = serde_json::to_string_pretty(plans)
+        .map_err(|e| format!("Failed to serialize focus plans: {}", e))?;
+    fsutil::write_atomic(&plans_path(vault_path), content)
+        .map_err(|e| BouldyError::io("Failed to write focus plans", e))
+}
+
+/// Open todos that belong in the day's plan, most important first: by
+/// priority, then due time, then file order.
+pub fn candidates(todos_list: &[TodoItem], date: NaiveDate) -> Vec<&TodoItem> {
+    let mut candidates: Vec<&TodoItem> = todos_list
+        .iter()
+        .filter(|todo| !todo.completed)
+        .filter(|todo| todo.priority.is_some() || todo.due().is_some_and(|due| due.date() <= date))
+        .collect();
+    candidates.sort_by_key(|todo| {
+        (
+            todo.priority.is_none(),
+            todo.priority.clone(),
+            todo.due().map(todos::Due::deadline),
+            todo.id,
+        )
+    });
+    candidates
+}
+
+// Count the day's finished sessions against the plan. Sessions started on a
+// todo count for it; the rest fill the plan in order.
+fn reconcile(plan: &mut FocusPlan, vault_path: &str, todos_list: &[TodoItem]) {
+    let Ok(date) = NaiveDate::parse_from_str(&plan.date, DATE_FORMAT) else {
+        return;
+    };
+    let sessions: Vec<_> = pomodoro::load_work_sessions(vault_path)
+        .into_iter()
+        .filter(|session| session.start.date() == date)
+        .collect();
+
+    for planned in &mut plan.todos {
+        planned.completed = 0;
+        planned.done = todos_list
+            .iter()
+            .find(|todo| todo.uid.as_deref() == Some(planned.uid.as_str()))
+            .is_none_or(|todo| todo.completed);
+    }
+    plan.completed = sessions.len() as u32;
+    plan.unplanned = 0;
+
+    let mut unlinked = 0;
+    for session in &sessions {
+        match session.todo_uid {
+            Some(ref uid) => match plan.todos.iter_mut().find(|p| &p.uid == uid) {
+                Some(planned) => planned.completed += 1,
+                None => plan.unplanned += 1,
+            },
+            None => unlinked += 1,
+        }
+    }
+    for planned in &mut plan.todos {
+        let open = planned.planned.saturating_sub(planned.completed);
+        let filled = open.min(unlinked);
+        planned.completed += filled;
+        unlinked -= filled;
+    }
+    plan.unplanned += unlinked;
+
+    plan.next = plan
+        .todos
+        .iter()
+        .find(|planned| !planned.done && planned.completed < planned.planned)
+        .map(|planned| planned.uid.clone());
+}
+
+/// Spread `sessions` pomodoros (the daily goal by default) over the day's
+/// prioritized and due todos, each getting its `est:` estimate or one, and
+/// save the plan, replacing any earlier plan for that day.
+pub fn plan_sessions(
+    store: &TodoStore,
+    vault_path: &str,
+    settings: &PomodoroSettings,
+    date: NaiveDate,
+    sessions: Option<u32>,
+) -> Result<FocusPlan, BouldyError> {
+    let capacity = sessions.or(settings.daily_goal).unwrap_or(DEFAULT_CAPACITY);
+    if capacity == 0 {
+        return Err(BouldyError::invalid_input("Plan at least one pomodoro"));
+    }
+
+    let todos_list = store.mutate_if_changed(vault_path, |todos_list| {
+        let ids: Vec<usize> = candidates(todos_list, date)
+            .iter()
+            .map(|todo| todo.id)
+            .collect();
+        let mut changed = false;
+        for todo in todos_list.iter_mut().filter(|todo| ids.contains(&todo.id)) {
+            if todo.uid.is_none() {
+                todo.uid = Some(todos::new_uid());
+                changed = true;
+            }
+        }
+        Ok((todos_list.clone(), changed))
+    })?;
+
+    let mut remaining = capacity;
+    let mut planned = Vec::new();
+    for todo in candidates(&todos_list, date) {
+        if remaining == 0 {
+            break;
+        }
+        let count = todo.estimate.unwrap_or(1).min(remaining);
+        remaining -= count;
+        planned.push(PlannedTodo {
+            uid: todo.uid.clone().unwrap_or_default(),
+            title: todo.title.clone(),
+            planned: count,
+            completed: 0,
+            done: false,
+        });
+    }
+
+    let mut plan = FocusPlan {
+        date: date.format(DATE_FORMAT).to_string(),
+        capacity,
+        todos: planned,
+        completed: 0,
+        unplanned: 0,
+        next: None,
+    };
+    reconcile(&mut plan, vault_path, &todos_list);
+
+    let mut plans = load_plans(vault_path);
+    plans.plans.insert(plan.date.clone(), plan.clone());
+    save_plans(vault_path, &plans)?;
+    Ok(plan)
+}
+
+/// The saved plan for `date` with progress from the pomodoro log.
+pub fn get_plan(
+    vault_path: &str,
+    todos_list: &[TodoItem],
+    date: NaiveDate,
+) -> Result<Option<FocusPlan>, BouldyError> {
+    let mut plans = load_plans(vault_path);
+    let key = date.format(DATE_FORMAT).to_string();
+    let Some(plan) = plans.plans.get_mut(&key) else {
+        return Ok(None);
+    };
+
+    let before = serde_json::to_value(&*plan).ok();
+    reconcile(plan, vault_path, todos_list);
+    let plan = plan.clone();
+    // Keep progress with the plan, so past days still show it
+    if serde_json::to_value(&plan).ok() != before {
+        save_plans(vault_path, &plans)?;
+    }
+    Ok(Some(plan))
+}
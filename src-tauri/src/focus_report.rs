@@ -0,0 +1,170 @@
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::error::BouldyError;
+use crate::pomodoro::{self, WorkSession};
+use crate::timesheet::TimeRange;
+use crate::todos::TodoItem;
+
+// Sessions with nothing to group them by
+const UNGROUPED: &str = "(none)";
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub enum FocusGroupBy {
+    // `+project` of the session, or of the todo it was started on
+    #[serde(rename = "project")]
+    Project,
+    // `@context` and `#tag` words
+    #[serde(rename = "tag")]
+    Tag,
+    #[serde(rename = "note")]
+    Note,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct FocusGroup {
+    pub key: String,
+    pub sessions: usize,
+    pub minutes: i64,
+    // Minutes per day of the range, oldest first
+    pub daily: Vec<i64>,
+    // Minutes in the same number of days just before the range
+    #[serde(rename = "previousMinutes")]
+    pub previous_minutes: i64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct FocusReport {
+    pub from: String,
+    pub to: String,
+    pub sessions: usize,
+    #[serde(rename = "totalMinutes")]
+    pub total_minutes: i64,
+    #[serde(rename = "previousMinutes")]
+    pub previous_minutes: i64,
+    // Largest first. A session with several projects or tags counts in each.
+    pub groups: Vec<FocusGroup>,
+}
+
+fn minutes(session: &WorkSession) -> i64 {
+    (session.end - session.start).num_minutes().max(0)
+}
+
+fn group_keys(
+    session: &WorkSession,
+    group_by: FocusGroupBy,
+    todos_by_uid: &HashMap<&str, &TodoItem>,
+) -> Vec<String> {
+    let todo = session
+        .todo_uid
+        .as_deref()
+        .and_then(|uid| todos_by_uid.get(uid));
+    let keys: Vec<String> = match group_by {
+        FocusGroupBy::Project => {
+            let logged: Vec<String> = session
+                .tags
+                .iter()
+                .filter_map(|tag| tag.strip_prefix('+'))
+                .map(str::to_string)
+                .collect();
+            // Older sessions only have the todo to go on
+            if logged.is_empty() {
+                todo.map(|todo| todo.projects.clone()).unwrap_or_default()
+            } else {
+                logged
+            }
+        }
+        FocusGroupBy::Tag => {
+            let logged: Vec<String> = session
+                .tags
+                .iter()
+                .filter(|tag| tag.starts_with(['@', '#']))
+                .cloned()
+                .collect();
+            if logged.is_empty() {
+                todo.map(|todo| todo.contexts.iter().map(|c| format!("@{}", c)).collect())
+                    .unwrap_or_default()
+            } else {
+                logged
+            }
+        }
+        FocusGroupBy::Note => session.note.iter().cloned().collect(),
+    };
+
+    if keys.is_empty() {
+        vec![UNGROUPED.to_string()]
+    } else {
+        keys
+    }
+}
+
+/// Completed work pomodoros between two dates (inclusive), grouped by
+/// project, tag or note, with a per-day series and the previous period for
+/// comparison.
+pub fn focus_report(
+    vault_path: &str,
+    todos_list: &[TodoItem],
+    range: &TimeRange,
+    group_by: FocusGroupBy,
+) -> Result<FocusReport, BouldyError> {
+    let (from, to) = range.dates()?;
+    let days = (to - from).num_days() + 1;
+    let previous_from = from - Duration::days(days);
+
+    let todos_by_uid: HashMap<&str, &TodoItem> = todos_list
+        .iter()
+        .filter_map(|todo| Some((todo.uid.as_deref()?, todo)))
+        .collect();
+
+    let mut report = FocusReport {
+        from: range.from.trim().to_string(),
+        to: range.to.trim().to_string(),
+        sessions: 0,
+        total_minutes: 0,
+        previous_minutes: 0,
+        groups: Vec::new(),
+    };
+    let mut groups: BTreeMap<String, FocusGroup> = BTreeMap::new();
+
+    for session in pomodoro::load_work_sessions(vault_path) {
+        let day: NaiveDate = session.start.date();
+        if day < previous_from || day > to {
+            continue;
+        }
+        let session_minutes = minutes(&session);
+        let in_range = day >= from;
+        if in_range {
+            report.sessions += 1;
+            report.total_minutes += session_minutes;
+        } else {
+            report.previous_minutes += session_minutes;
+        }
+
+        for key in group_keys(&session, group_by, &todos_by_uid) {
+            let group = groups.entry(key.clone()).or_insert_with(|| FocusGroup {
+                key,
+                sessions: 0,
+                minutes: 0,
+                daily: vec![0; days as usize],
+                previous_minutes: 0,
+            });
+            if in_range {
+                group.sessions += 1;
+                group.minutes += session_minutes;
+                group.daily[(day - from).num_days() as usize] += session_minutes;
+            } else {
+                group.previous_minutes += session_minutes;
+            }
+        }
+    }
+
+    report.groups = groups
+        .into_values()
+        .filter(|group| group.sessions > 0 || group.previous_minutes > 0)
+        .collect();
+    report
+        .groups
+        .sort_by(|a, b| b.minutes.cmp(&a.minutes).then_with(|| a.key.cmp(&b.key)));
+    Ok(report)
+}
@@ -0,0 +1,170 @@
+use std::borrow::Cow;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// Tells apart temp files of concurrent writes from this process
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Paths past Windows' 260-character MAX_PATH (deep OneDrive folders, say)
+/// only work in extended-length `\\?\` form. Elsewhere, and for short
+/// paths, this is the path unchanged.
+#[cfg(windows)]
+pub fn long_path(path: &Path) -> Cow<'_, Path> {
+    use std::ffi::OsString;
+    use std::path::{Component, Prefix};
+
+    // Leave headroom for the temp file name write_atomic puts beside it
+    const LONG_PATH_THRESHOLD: usize = 200;
+
+    if path.as_os_str().len() < LONG_PATH_THRESHOLD {
+        return Cow::Borrowed(path);
+    }
+
+    let mut components = path.components();
+    let mut extended = match components.next() {
+        Some(Component::Prefix(prefix)) => match prefix.kind() {
+            Prefix::Disk(_) => {
+                let mut s = OsString::from(r"\\?\");
+                s.push(prefix.as_os_str());
+                s
+            }
+            Prefix::UNC(server, share) => {
+                let mut s = OsString::from(r"\\?\UNC\");
+                s.push(server);
+                s.push(r"\");
+                s.push(share);
+                s
+            }
+            // Already extended, or a device path
+            _ => return Cow::Borrowed(path),
+        },
+        // Relative paths can't be extended
+        _ => return Cow::Borrowed(path),
+    };
+
+    for component in components {
+        match component {
+            Component::RootDir => {}
+            Component::Normal(part) => {
+                extended.push(r"\");
+                extended.push(part);
+            }
+            // Extended paths aren't normalized, so "." and ".." would be
+            // taken literally
+            _ => return Cow::Borrowed(path),
+        }
+    }
+
+    Cow::Owned(PathBuf::from(extended))
+}
+
+#[cfg(not(windows))]
+pub fn long_path(path: &Path) -> Cow<'_, Path> {
+    Cow::Borrowed(path)
+}
+
+// Symlinked notes are written through to their target; renaming over the
+// link itself would replace it with a regular file
+fn resolve_target(path: &Path) -> io::Result<PathBuf> {
+    if path.is_symlink() {
+        fs::canonicalize(path)
+    } else {
+        Ok(path.to_path_buf())
+    }
+}
+
+/// Replace `path` with `contents` so readers (and a crash) only ever see the
+/// old or the new file, never a truncated one: write a temp file beside it,
+/// fsync, then rename over the original.
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let target = resolve_target(&long_path(path))?;
+    let dir = target
+        .parent()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Path has no parent"))?;
+    let file_name = target
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Path has no file name"))?;
+
+    // Same directory, so the rename never crosses filesystems
+    let mut temp_name = std::ffi::OsString::from(".");
+    temp_name.push(file_name);
+    temp_name.push(format!(
+        ".{}.{}.tmp",
+        std::process::id(),
+        TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let temp_path = dir.join(temp_name);
+
+    let result = (|| {
+        let mut file = File::create(&temp_path)?;
+        file.write_all(contents.as_ref())?;
+        file.sync_all()?;
+
+        if let Ok(metadata) = fs::metadata(&target) {
+            fs::set_permissions(&temp_path, metadata.permissions())?;
+        }
+
+        fs::rename(&temp_path, &target)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+        return result;
+    }
+
+    // Persist the rename itself. Directories can't be opened this way on
+    // Windows, where the rename is already durable enough.
+    #[cfg(unix)]
+    if let Ok(dir) = File::open(dir) {
+        let _ = dir.sync_all();
+    }
+
+    Ok(())
+}
+
+// Line-ending conventions of an existing text file
+#[derive(Clone, Copy, Debug)]
+struct TextFormat {
+    crlf: bool,
+    final_newline: bool,
+}
+
+impl TextFormat {
+    fn detect(text: &str) -> Self {
+        Self {
+            crlf: text.contains("\r\n"),
+            final_newline: text.is_empty() || text.ends_with('\n'),
+        }
+    }
+
+    fn apply(self, text: &str) -> String {
+        let mut text = text.replace("\r\n", "\n");
+
+        if self.final_newline {
+            if !text.is_empty() && !text.ends_with('\n') {
+                text.push('\n');
+            }
+        } else if text.ends_with('\n') {
+            text.pop();
+        }
+
+        if self.crlf {
+            text = text.replace('\n', "\r\n");
+        }
+        text
+    }
+}
+
+/// Like `write_atomic`, but keeps the line endings (CRLF vs LF) and
+/// final-newline presence of the file being replaced, so a rewrite doesn't
+/// show up as every line changed in a git-tracked vault. New files are
+/// written as given.
+pub fn write_text_preserving(path: &Path, contents: &str) -> io::Result<()> {
+    match fs::read_to_string(long_path(path)) {
+        Ok(existing) => write_atomic(path, TextFormat::detect(&existing).apply(contents)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => write_atomic(path, contents),
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,361 @@
+use chrono::{Duration, NaiveDate, Weekday};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::BouldyError;
+use crate::{fsutil, locale, vault_lock};
+
+// Next to todo.txt, in the same spirit:
+//
+//   Meditate
+//   Exercise goal:3/week
+//   x 2025-01-06 Meditate
+//   x 2025-01-06 Exercise
+//
+// One habit per line, check-ins as `x <date> <habit>`, `#` for comments.
+pub const HABITS_FILE: &str = "habits.txt";
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Period {
+    #[serde(rename = "day")]
+    Day,
+    #[serde(rename = "week")]
+    Week,
+}
+
+impl Period {
+    // The first day of the period `date` falls in
+    fn start(self, date: NaiveDate, week_start: Weekday) -> NaiveDate {
+        match self {
+            Period::Day => date,
+            Period::Week => locale::start_of_week(date, week_start),
+        }
+    }
+
+    fn previous(self, start: NaiveDate) -> NaiveDate {
+        match self {
+            Period::Day => start - Duration::days(1),
+            Period::Week => start - Duration::days(7),
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Habit {
+    pub name: String,
+    // Check-ins needed per period, e.g. 3 a week
+    pub target: u32,
+    pub period: Period,
+    #[serde(rename = "checkedToday")]
+    pub checked_today: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct HabitStats {
+    pub name: String,
+    // In periods (days or weeks) that met the target; the current period
+    // doesn't break a streak until it's over
+    #[serde(rename = "currentStreak")]
+    pub current_streak: usize,
+    #[serde(rename = "longestStreak")]
+    pub longest_streak: usize,
+    // Share of periods since the first check-in that met the target
+    #[serde(rename = "completionRate")]
+    pub completion_rate: f64,
+    #[serde(rename = "totalCheckIns")]
+    pub total_check_ins: usize,
+}
+
+struct Definition {
+    name: String,
+    target: u32,
+    period: Period,
+}
+
+enum Line {
+    Habit(Definition),
+    CheckIn(NaiveDate, String),
+    Other,
+}
+
+fn habits_path(vault_path: &str) -> PathBuf {
+    Path::new(vault_path).join(HABITS_FILE)
+}
+
+fn parse_date(date: &str) -> Result<NaiveDate, BouldyError> {
+    NaiveDate::parse_from_str(date.trim(), DATE_FORMAT).map_err(|_| {
+        BouldyError::invalid_input(format!("Invalid date '{}'. Expected YYYY-MM-DD", date))
+    })
+}
+
+// `goal:3/week`, `goal:2/day`; a bare count is per day
+fn parse_goal(value: &str) -> Option<(u32, Period)> {
+    let (count, period) = value.split_once('/').unwrap_or((value, "day"));
+    let period = match period {
+        "day" | "d" => Period::Day,
+        "week" | "w" => Period::Week,
+        _ => return None,
+    };
+    count
+        .parse()
+        .ok()
+        .filter(|count| *count > 0)
+        .map(|count| (count, period))
+}
+
+fn parse_line(line: &str) -> Line {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Line::Other;
+    }
+
+    if let Some(rest) = line.strip_prefix("x ") {
+        let (date, name) = rest.trim_start().split_once(' ').unwrap_or((rest, ""));
+        return match NaiveDate::parse_from_str(date, DATE_FORMAT) {
+            Ok(date) if !name.trim().is_empty() => Line::CheckIn(date, name.trim().to_string()),
+            _ => Line::Other,
+        };
+    }
+
+    let mut goal = (1, Period::Day);
+    let mut words = Vec::new();
+    for word in line.split_whitespace() {
+        match word.strip_prefix("goal:").and_then(parse_goal) {
+            Some(parsed) => goal = parsed,
+            None => words.push(word),
+        }
+    }
+    if words.is_empty() {
+        return Line::Other;
+    }
+    Line::Habit(Definition {
+        name: words.join(" "),
+        target: goal.0,
+        period: goal.1,
+    })
+}
+
+fn same_name(a: &str, b: &str) -> bool {
+    a.to_lowercase() == b.to_lowercase()
+}
+
+fn read_lines(vault_path: &str) -> Result<Vec<String>, BouldyError> {
+    match fs::read_to_string(habits_path(vault_path)) {
+        Ok(content) => Ok(content.lines().map(|line| line.to_string()).collect()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(BouldyError::io("Failed to read habits", e)),
+    }
+}
+
+fn write_lines(vault_path: &str, lines: &[String]) -> Result<(), BouldyError> {
+    let mut content = lines.join("\n");
+    content.push('\n');
+    fsutil::write_atomic(&habits_path(vault_path), content)
+        .map_err(|e| BouldyError::io("Failed to write habits", e))
+}
+
+fn definitions(lines: &[String]) -> Vec<Definition> {
+    lines
+        .iter()
+        .filter_map(|line| match parse_line(line) {
+            Line::Habit(definition) => Some(definition),
+            _ => None,
+        })
+        .collect()
+}
+
+fn check_ins(lines: &[String], name: &str) -> BTreeSet<NaiveDate> {
+    lines
+        .iter()
+        .filter_map(|line| match parse_line(line) {
+            Line::CheckIn(date, habit) if same_name(&habit, name) => Some(date),
+            _ => None,
+        })
+        .collect()
+}
+
+fn find<'a>(definitions: &'a [Definition], habit: &str) -> Result<&'a Definition, BouldyError> {
+    definitions
+        .iter()
+        .find(|definition| same_name(&definition.name, habit))
+        .ok_or_else(|| BouldyError::not_found(format!("No habit named '{}'", habit)))
+}
+
+pub fn list_habits(vault_path: &str) -> Result<Vec<Habit>, BouldyError> {
+    let lines = read_lines(vault_path)?;
+    let today = chrono::Local::now().date_naive();
+
+    Ok(definitions(&lines)
+        .into_iter()
+        .map(|definition| Habit {
+            checked_today: check_ins(&lines, &definition.name).contains(&today),
+            name: definition.name,
+            target: definition.target,
+            period: definition.period,
+        })
+        .collect())
+}
+
+/// Define a new habit, e.g. `add_habit("Exercise", Some("3/week"))`.
+pub fn add_habit(vault_path: &str, name: &str, goal: Option<&str>) -> Result<Habit, BouldyError> {
+    let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+    if name.is_empty() || name.starts_with('#') || name.starts_with("x ") {
+        return Err(BouldyError::invalid_path(format!(
+            "Invalid habit name '{}'",
+            name
+        )));
+    }
+    let (target, period) = match goal {
+        Some(goal) => parse_goal(goal.trim()).ok_or_else(|| {
+            BouldyError::invalid_input(format!(
+                "Invalid goal '{}'. Expected e.g. 1/day or 3/week",
+                goal
+            ))
+        })?,
+        None => (1, Period::Day),
+    };
+
+    let _lock = vault_lock::acquire(vault_path)?;
+    let mut lines = read_lines(vault_path)?;
+    if find(&definitions(&lines), &name).is_ok() {
+        return Err(BouldyError::conflict(format!(
+            "Habit '{}' already exists",
+            name
+        )));
+    }
+
+    let mut line = name.clone();
+    if (target, period) != (1, Period::Day) {
+        let period = if period == Period::Week {
+            "week"
+        } else {
+            "day"
+        };
+        line.push_str(&format!(" goal:{}/{}", target, period));
+    }
+    // Definitions stay together above the check-ins
+    let position = lines
+        .iter()
+        .rposition(|l| matches!(parse_line(l), Line::Habit(_)))
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    lines.insert(position, line);
+    write_lines(vault_path, &lines)?;
+
+    Ok(Habit {
+        name,
+        target,
+        period,
+        checked_today: false,
+    })
+}
+
+/// Record (or with `done: false`, remove) a check-in for `habit` on `date`.
+pub fn check_in(
+    vault_path: &str,
+    habit: &str,
+    date: &str,
+    done: bool,
+    week_start: Weekday,
+) -> Result<HabitStats, BouldyError> {
+    let date = parse_date(date)?;
+
+    let _lock = vault_lock::acquire(vault_path)?;
+    let mut lines = read_lines(vault_path)?;
+    let name = find(&definitions(&lines), habit)?.name.clone();
+    let checked = check_ins(&lines, &name).contains(&date);
+
+    if done && !checked {
+        lines.push(format!("x {} {}", date.format(DATE_FORMAT), name));
+        write_lines(vault_path, &lines)?;
+    } else if !done && checked {
+        lines.retain(|line| {
+            !matches!(parse_line(line), Line::CheckIn(d, ref h) if d == date && same_name(h, &name))
+        });
+        write_lines(vault_path, &lines)?;
+    }
+
+    Ok(stats_for(
+        &lines,
+        find(&definitions(&lines), &name)?,
+        week_start,
+    ))
+}
+
+fn stats_for(lines: &[String], definition: &Definition, week_start: Weekday) -> HabitStats {
+    let dates = check_ins(lines, &definition.name);
+    let period = definition.period;
+    let target = definition.target as usize;
+    let met = |start: NaiveDate| {
+        let end = match period {
+            Period::Day => start,
+            Period::Week => start + Duration::days(6),
+        };
+        dates.range(start..=end).count() >= target
+    };
+
+    let mut stats = HabitStats {
+        name: definition.name.clone(),
+        current_streak: 0,
+        longest_streak: 0,
+        completion_rate: 0.0,
+        total_check_ins: dates.len(),
+    };
+    let Some(&first) = dates.first() else {
+        return stats;
+    };
+
+    let current = period.start(chrono::Local::now().date_naive(), week_start);
+    let first = period.start(first, week_start);
+
+    // Walk back from the current period to the first one with a check-in
+    let mut periods = 0;
+    let mut met_periods = 0;
+    let mut run = 0;
+    let mut start = current;
+    let mut in_current_run = true;
+    while start >= first {
+        let ok = met(start);
+        // An unfinished current period counts only once it's met
+        if start == current && !ok {
+            start = period.previous(start);
+            continue;
+        }
+        periods += 1;
+        if ok {
+            met_periods += 1;
+            run += 1;
+            stats.longest_streak = stats.longest_streak.max(run);
+        } else {
+            if in_current_run {
+                stats.current_streak = run;
+                in_current_run = false;
+            }
+            run = 0;
+        }
+        start = period.previous(start);
+    }
+    if in_current_run {
+        stats.current_streak = run;
+    }
+    if periods > 0 {
+        stats.completion_rate = met_periods as f64 / periods as f64;
+    }
+
+    stats
+}
+
+pub fn get_habit_stats(
+    vault_path: &str,
+    week_start: Weekday,
+) -> Result<Vec<HabitStats>, BouldyError> {
+    let lines = read_lines(vault_path)?;
+    Ok(definitions(&lines)
+        .iter()
+        .map(|definition| stats_for(&lines, definition, week_start))
+        .collect())
+}
@@ -0,0 +1,357 @@
+use kuchikiki::traits::TendrilSink;
+use kuchikiki::NodeRef;
+use reqwest::Url;
+
+// Never worth keeping in a note
+const SKIPPED_ELEMENTS: &[&str] = &[
+    "head", "script", "style", "noscript", "template", "svg", "iframe", "form", "button", "input",
+    "select", "textarea", "nav",
+];
+
+const BLOCK_ELEMENTS: &[&str] = &[
+    "p",
+    "div",
+    "section",
+    "article",
+    "main",
+    "header",
+    "footer",
+    "aside",
+    "figure",
+    "figcaption",
+    "details",
+    "summary",
+    "dl",
+    "dt",
+    "dd",
+    "address",
+    "center",
+];
+
+struct Converter<'a> {
+    base: Option<&'a Url>,
+}
+
+/// Convert an HTML document or fragment to markdown. Relative links and
+/// image sources are resolved against `base`.
+pub fn convert(html: &str, base: Option<&Url>) -> String {
+    let document = kuchikiki::parse_html().one(html).document_node;
+    convert_node(&document, base)
+}
+
+/// Convert an already parsed node and its descendants.
+pub fn convert_node(node: &NodeRef, base: Option<&Url>) -> String {
+    let converter = Converter { base };
+    let mut output = String::new();
+    converter.children(node, &mut output);
+    tidy(&output)
+}
+
+// Drop trailing spaces and runs of blank lines
+fn tidy(markdown: &str) -> String {
+    let mut output = String::new();
+    let mut blank_lines = 0;
+    for line in markdown.lines() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            blank_lines += 1;
+            continue;
+        }
+        if !output.is_empty() {
+            output.push_str(if blank_lines > 0 { "\n\n" } else { "\n" });
+        }
+        output.push_str(line);
+        blank_lines = 0;
+    }
+    output
+}
+
+// Start a new block: a blank line unless at the very start
+fn block_break(output: &mut String) {
+    let trimmed = output.trim_end_matches([' ', '\n']).len();
+    output.truncate(trimmed);
+    if !output.is_empty() {
+        output.push_str("\n\n");
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '`' | '[' | ']') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+// Inline text with its whitespace collapsed the way a browser would
+fn push_text(output: &mut String, text: &str) {
+    let mut words = text.split_whitespace().peekable();
+    let leading_space = text.starts_with(char::is_whitespace);
+    let trailing_space = text.ends_with(char::is_whitespace);
+
+    if words.peek().is_none() {
+        if !text.is_empty() && !output.is_empty() && !output.ends_with([' ', '\n']) {
+            output.push(' ');
+        }
+        return;
+    }
+    if leading_space && !output.is_empty() && !output.ends_with([' ', '\n']) {
+        output.push(' ');
+    }
+    let words: Vec<String> = words.map(escape).collect();
+    output.push_str(&words.join(" "));
+    if trailing_space {
+        output.push(' ');
+    }
+}
+
+// Link destinations with spaces or parentheses need angle brackets
+fn destination(url: &str) -> String {
+    if url.contains([' ', '(', ')']) {
+        format!("<{}>", url)
+    } else {
+        url.to_string()
+    }
+}
+
+impl Converter<'_> {
+    fn resolve(&self, url: &str) -> String {
+        match self.base {
+            Some(base) => base
+                .join(url)
+                .map(|url| url.to_string())
+                .unwrap_or_else(|_| url.to_string()),
+            None => url.to_string(),
+        }
+    }
+
+    fn children(&self, node: &NodeRef, output: &mut String) {
+        for child in node.children() {
+            self.node(&child, output);
+        }
+    }
+
+    fn inline(&self, node: &NodeRef) -> String {
+        let mut inner = String::new();
+        self.children(node, &mut inner);
+        inner.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    fn node(&self, node: &NodeRef, output: &mut String) {
+        if let Some(text) = node.as_text() {
+            push_text(output, &text.borrow());
+            return;
+        }
+        let Some(element) = node.as_element() else {
+            // Documents and other containers
+            if node.as_comment().is_none() {
+                self.children(node, output);
+            }
+            return;
+        };
+
+        let name: &str = &element.name.local;
+        let attribute = |key: &str| {
+            element
+                .attributes
+                .borrow()
+                .get(key)
+                .map(|value| value.trim().to_string())
+        };
+
+        match name {
+            _ if SKIPPED_ELEMENTS.contains(&name) => {}
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = name[1..].parse().unwrap_or(1);
+                let text = self.inline(node);
+                if !text.is_empty() {
+                    block_break(output);
+                    output.push_str(&format!("{} {}", "#".repeat(level), text));
+                    block_break(output);
+                }
+            }
+            "br" => output.push_str("\\\n"),
+            "hr" => {
+                block_break(output);
+                output.push_str("---");
+                block_break(output);
+            }
+            "strong" | "b" => self.wrap(node, output, "**"),
+            "em" | "i" => self.wrap(node, output, "*"),
+            "del" | "s" | "strike" => self.wrap(node, output, "~~"),
+            "code" => {
+                let code = node.text_contents();
+                let code = code.split_whitespace().collect::<Vec<_>>().join(" ");
+                if code.contains('`') {
+                    output.push_str(&format!("`` {} ``", code));
+                } else if !code.is_empty() {
+                    output.push_str(&format!("`{}`", code));
+                }
+            }
+            "pre" => {
+                let code = node.text_contents();
+                let fence = if code.contains("```") { "~~~" } else { "```" };
+                // Highlighters put the language on the inner <code>
+                let language = node
+                    .descendants()
+                    .filter_map(|child| {
+                        let class = child
+                            .as_element()?
+                            .attributes
+                            .borrow()
+                            .get("class")?
+                            .to_string();
+                        class
+                            .split_whitespace()
+                            .find_map(|c| c.strip_prefix("language-").map(|l| l.to_string()))
+                    })
+                    .next()
+                    .unwrap_or_default();
+                block_break(output);
+                output.push_str(&format!(
+                    "{}{}\n{}\n{}",
+                    fence,
+                    language,
+                    code.trim_end_matches('\n'),
+                    fence
+                ));
+                block_break(output);
+            }
+            "a" => {
+                let href = attribute("href").unwrap_or_default();
+                if href.is_empty() || href.starts_with("javascript:") || href.starts_with('#') {
+                    self.children(node, output);
+                    return;
+                }
+                let text = self.inline(node);
+                if !text.is_empty() {
+                    output.push_str(&format!(
+                        "[{}]({})",
+                        text,
+                        destination(&self.resolve(&href))
+                    ));
+                }
+            }
+            "img" => {
+                let Some(src) = attribute("src").filter(|src| !src.starts_with("data:")) else {
+                    return;
+                };
+                let alt = attribute("alt").unwrap_or_default();
+                output.push_str(&format!(
+                    "![{}]({})",
+                    escape(&alt),
+                    destination(&self.resolve(&src))
+                ));
+            }
+            "ul" | "ol" => {
+                block_break(output);
+                self.list(node, output, name == "ol");
+                block_break(output);
+            }
+            "blockquote" => {
+                let mut inner = String::new();
+                self.children(node, &mut inner);
+                block_break(output);
+                let quoted: Vec<String> = tidy(&inner)
+                    .lines()
+                    .map(|line| format!("> {}", line).trim_end().to_string())
+                    .collect();
+                output.push_str(&quoted.join("\n"));
+                block_break(output);
+            }
+            "table" => {
+                block_break(output);
+                self.table(node, output);
+                block_break(output);
+            }
+            _ if BLOCK_ELEMENTS.contains(&name) => {
+                block_break(output);
+                self.children(node, output);
+                block_break(output);
+            }
+            _ => self.children(node, output),
+        }
+    }
+
+    fn wrap(&self, node: &NodeRef, output: &mut String, marker: &str) {
+        let text = self.inline(node);
+        if !text.is_empty() {
+            if !output.is_empty() && !output.ends_with([' ', '\n']) {
+                output.push(' ');
+            }
+            output.push_str(&format!("{}{}{}", marker, text, marker));
+        }
+    }
+
+    fn list(&self, node: &NodeRef, output: &mut String, ordered: bool) {
+        let items = node
+            .children()
+            .filter(|child| child.as_element().is_some_and(|e| &*e.name.local == "li"));
+
+        for (index, item) in items.enumerate() {
+            let mut inner = String::new();
+            self.children(&item, &mut inner);
+            let marker = if ordered {
+                format!("{}. ", index + 1)
+            } else {
+                "- ".to_string()
+            };
+            let indent = " ".repeat(marker.len());
+
+            // Keep the list tight: nested blocks become continuation lines
+            let mut lines = tidy(&inner)
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| line.to_string())
+                .collect::<Vec<_>>()
+                .into_iter();
+            output.push_str(&marker);
+            output.push_str(&lines.next().unwrap_or_default());
+            for line in lines {
+                output.push('\n');
+                output.push_str(&indent);
+                output.push_str(&line);
+            }
+            output.push('\n');
+        }
+    }
+
+    fn table(&self, node: &NodeRef, output: &mut String) {
+        let rows: Vec<Vec<String>> = node
+            .descendants()
+            .filter(|child| child.as_element().is_some_and(|e| &*e.name.local == "tr"))
+            .map(|row| {
+                row.children()
+                    .filter(|cell| {
+                        cell.as_element()
+                            .is_some_and(|e| matches!(&*e.name.local, "td" | "th"))
+                    })
+                    .map(|cell| self.inline(&cell).replace('|', "\\|"))
+                    .collect()
+            })
+            .filter(|cells: &Vec<String>| !cells.is_empty())
+            .collect();
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+        if columns == 0 {
+            return;
+        }
+
+        let render = |cells: &[String]| {
+            let mut cells = cells.to_vec();
+            cells.resize(columns, String::new());
+            format!("| {} |", cells.join(" | "))
+        };
+        let mut lines = Vec::new();
+        for (index, row) in rows.iter().enumerate() {
+            lines.push(render(row));
+            // The first row is the header
+            if index == 0 {
+                lines.push(format!("|{}", " --- |".repeat(columns)));
+            }
+        }
+        output.push_str(&lines.join("\n"));
+    }
+}
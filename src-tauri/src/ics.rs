@@ -0,0 +1,152 @@
+use chrono::{Duration, Utc};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::BouldyError;
+use crate::fsutil;
+use crate::todos::{self, Due, TodoItem};
+
+// Calendar apps subscribe to this file (file:// or a synced folder) to show
+// due todos. Subscriptions are read-only, so completion flows one way:
+// completing a todo in bouldy drops it from the calendar on the next refresh.
+pub const DEFAULT_CALENDAR_FILE: &str = "bouldy-todos.ics";
+
+pub fn default_calendar_path(vault_path: &str) -> PathBuf {
+    Path::new(vault_path)
+        .join(".bouldy")
+        .join(DEFAULT_CALENDAR_FILE)
+}
+
+// FNV-1a, so UIDs stay stable across builds (std's hasher makes no such promise)
+fn stable_hash(input: &str) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in input.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+// Line numbers shift as todos are added and removed, so the UID is derived
+// from content that stays put for the life of a todo
+fn todo_uid(todo: &TodoItem) -> String {
+    let key = format!(
+        "{}|{}",
+        todo.created_date.as_deref().unwrap_or_default(),
+        todo.title
+    );
+    format!("{:016x}@bouldy", stable_hash(&key))
+}
+
+pub fn escape_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+// RFC 5545 lines are limited to 75 octets; longer ones continue on lines
+// starting with a space. Splits only on char boundaries.
+pub fn fold_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut line_len = 0;
+
+    for ch in line.chars() {
+        let ch_len = ch.len_utf8();
+        if line_len + ch_len > 75 {
+            folded.push_str("\r\n ");
+            line_len = 1;
+        }
+        folded.push(ch);
+        line_len += ch_len;
+    }
+
+    folded.push_str("\r\n");
+    folded
+}
+
+fn todo_event(todo: &TodoItem, stamp: &str) -> Option<String> {
+    let mut lines = vec![
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}", todo_uid(todo)),
+        format!("DTSTAMP:{}", stamp),
+    ];
+
+    match todo.due()? {
+        Due::Date(date) => {
+            let end = date + Duration::days(1);
+            lines.push(format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")));
+            lines.push(format!("DTEND;VALUE=DATE:{}", end.format("%Y%m%d")));
+        }
+        // Floating local time, matching how due times are written
+        Due::DateTime(at) => {
+            lines.push(format!("DTSTART:{}", at.format("%Y%m%dT%H%M%S")));
+        }
+    }
+    lines.push(format!("SUMMARY:{}", escape_text(&todo.title)));
+
+    let categories: Vec<String> = todo
+        .projects
+        .iter()
+        .chain(todo.contexts.iter())
+        .map(|tag| escape_text(tag))
+        .collect();
+    if !categories.is_empty() {
+        lines.push(format!("CATEGORIES:{}", categories.join(",")));
+    }
+
+    if let Some(ref priority) = todo.priority {
+        // (A) -> 1, (B) -> 2, ... capped at the lowest iCalendar priority
+        let rank = priority
+            .bytes()
+            .next()
+            .map(|b| (b - b'A' + 1).min(9))
+            .unwrap_or(0);
+        lines.push(format!("PRIORITY:{}", rank));
+    }
+
+    lines.push("END:VEVENT".to_string());
+
+    Some(lines.iter().map(|line| fold_line(line)).collect())
+}
+
+/// Render open, due-dated todos as all-day events, or timed ones for todos
+/// with a due time.
+pub fn render_calendar(todos: &[TodoItem]) -> String {
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+
+    let mut calendar = String::new();
+    for line in [
+        "BEGIN:VCALENDAR",
+        "VERSION:2.0",
+        "PRODID:-//bouldy//todos//EN",
+        "CALSCALE:GREGORIAN",
+        "X-WR-CALNAME:Bouldy todos",
+    ] {
+        calendar.push_str(&fold_line(line));
+    }
+
+    for todo in todos.iter().filter(|t| !t.completed) {
+        if let Some(event) = todo_event(todo, &stamp) {
+            calendar.push_str(&event);
+        }
+    }
+
+    calendar.push_str(&fold_line("END:VCALENDAR"));
+    calendar
+}
+
+pub fn export_calendar(vault_path: &str, output_path: &Path) -> Result<PathBuf, BouldyError> {
+    let todos_list = todos::load_todos(vault_path)?;
+
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create calendar directory: {}", e))?;
+    }
+
+    fsutil::write_atomic(output_path, render_calendar(&todos_list))
+        .map_err(|e| format!("Failed to write calendar: {}", e))?;
+
+    Ok(output_path.to_path_buf())
+}
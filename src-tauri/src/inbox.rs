@@ -0,0 +1,259 @@
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::BouldyError;
+use crate::locale::LocaleInfo;
+use crate::todo_store::TodoStore;
+use crate::todos::{self, TodoItem};
+use crate::{fsutil, vault, vault_lock};
+
+// Unprocessed captures, one per line with any continuation lines indented.
+// Each starts with the day it was captured, like a todo.txt creation date.
+pub const INBOX_FILE: &str = "inbox.txt";
+const CONTINUATION_INDENT: &str = "  ";
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct InboxItem {
+    // Line number of the item's first line
+    pub id: usize,
+    pub text: String,
+    pub captured: Option<String>,
+}
+
+/// What to do with an inbox item during processing.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(tag = "action")]
+pub enum InboxAction {
+    #[serde(rename = "todo")]
+    Todo {
+        project: Option<String>,
+        due: Option<String>,
+    },
+    // The first line becomes the title unless one is given
+    #[serde(rename = "note")]
+    Note { title: Option<String> },
+    // A waiting-for todo
+    #[serde(rename = "delegate")]
+    Delegate { person: String, due: Option<String> },
+    #[serde(rename = "discard")]
+    Discard,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(tag = "kind")]
+pub enum Processed {
+    #[serde(rename = "todo")]
+    Todo { todo: Box<TodoItem> },
+    #[serde(rename = "note")]
+    Note { path: String },
+    #[serde(rename = "discarded")]
+    Discarded,
+}
+
+fn inbox_path(vault_path: &str) -> PathBuf {
+    Path::new(vault_path).join(INBOX_FILE)
+}
+
+fn parse_inbox(content: &str) -> Vec<InboxItem> {
+    let mut items: Vec<InboxItem> = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if line.starts_with([' ', '\t']) {
+            if let Some(item) = items.last_mut() {
+                item.text.push('\n');
+                item.text.push_str(line.trim());
+                continue;
+            }
+        }
+
+        let line = line.trim();
+        let (captured, text) = match line.split_once(' ') {
+            Some((date, rest)) if chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok() => {
+                (Some(date.to_string()), rest.trim_start())
+            }
+            _ => (None, line),
+        };
+        items.push(InboxItem {
+            id: index + 1,
+            text: text.to_string(),
+            captured,
+        });
+    }
+    items
+}
+
+fn serialize_inbox(items: &[InboxItem]) -> String {
+    let mut content = String::new();
+    for item in items {
+        let mut lines = item.text.lines();
+        if let Some(ref date) = item.captured {
+            content.push_str(date);
+            content.push(' ');
+        }
+        content.push_str(lines.next().unwrap_or_default());
+        content.push('\n');
+        for line in lines {
+            content.push_str(CONTINUATION_INDENT);
+            content.push_str(line);
+            content.push('\n');
+        }
+    }
+    content
+}
+
+pub fn list_inbox(vault_path: &str) -> Result<Vec<InboxItem>, BouldyError> {
+    match fs::read_to_string(inbox_path(vault_path)) {
+        Ok(content) => Ok(parse_inbox(&content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(BouldyError::io("Failed to read inbox", e)),
+    }
+}
+
+fn save_inbox(vault_path: &str, items: &[InboxItem]) -> Result<(), BouldyError> {
+    fsutil::write_text_preserving(&inbox_path(vault_path), &serialize_inbox(items))
+        .map_err(|e| BouldyError::io("Failed to write inbox", e))
+}
+
+/// Append `text` to the inbox as a single item, blank lines dropped.
+pub fn capture(vault_path: &str, text: &str) -> Result<InboxItem, BouldyError> {
+    let text = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    if text.is_empty() {
+        return Err(BouldyError::invalid_input("Nothing to capture"));
+    }
+
+    let _lock = vault_lock::acquire(vault_path)?;
+    let mut items = list_inbox(vault_path)?;
+    items.push(InboxItem {
+        id: 0,
+        text,
+        captured: Some(Local::now().format("%Y-%m-%d").to_string()),
+    });
+    save_inbox(vault_path, &items)?;
+
+    // Re-read for the id the item got
+    let item = list_inbox(vault_path)?.pop().ok_or("Failed to capture")?;
+    Ok(item)
+}
+
+// The item as a todo: the first line with its tags, the rest as description.
+// The due date can be typed the way the locale writes dates, or as a word.
+fn to_todo(
+    item: &InboxItem,
+    project: Option<&str>,
+    due: Option<&str>,
+    delegated: Option<&str>,
+    locale: &LocaleInfo,
+) -> Result<TodoItem, BouldyError> {
+    let due = due
+        .map(|due| locale.parse_due(due, todos::local_now().date()))
+        .transpose()?;
+    let mut lines = item.text.lines();
+    let mut line = lines.next().unwrap_or_default().to_string();
+    if let Some(project) = project.map(str::trim).filter(|p| !p.is_empty()) {
+        line.push_str(&format!(" +{}", project.trim_start_matches('+')));
+    }
+    if let Some(ref due) = due {
+        line.push_str(&format!(" due:{}", due));
+    }
+
+    let mut todo = todos::parse_todos(&line)?
+        .pop()
+        .ok_or_else(|| BouldyError::invalid_input("Inbox item is not a valid todo"))?;
+    todo.description = todos::clean_description(&lines.collect::<Vec<_>>().join("\n"));
+    if let Some(person) = delegated {
+        // Stored as a single `delegated:` word
+        let person = person.split_whitespace().collect::<Vec<_>>().join("_");
+        if person.is_empty() {
+            return Err(BouldyError::invalid_input("Delegate needs a person"));
+        }
+        todo.delegated = Some(person);
+    }
+    if todo.created_date.is_none() {
+        todo.created_date = item
+            .captured
+            .clone()
+            .or_else(|| Some(Local::now().format("%Y-%m-%d").to_string()));
+    }
+    Ok(todo)
+}
+
+fn note_content(item: &InboxItem, title: &str) -> String {
+    let mut lines = item.text.lines();
+    // A first line used as the title isn't repeated in the body
+    if lines.clone().next() == Some(title) {
+        lines.next();
+    }
+    let body = lines.collect::<Vec<_>>().join("\n");
+    if body.is_empty() {
+        format!("# {}\n", title)
+    } else {
+        format!("# {}\n\n{}\n", title, body)
+    }
+}
+
+/// Move inbox item `id` to where it belongs. The destination is written
+/// before the item leaves the inbox, all under the vault lock, so a failure
+/// can leave a duplicate but never lose the text.
+pub fn process_item(
+    store: &TodoStore,
+    vault_path: &str,
+    id: usize,
+    action: InboxAction,
+    locale: &LocaleInfo,
+) -> Result<Processed, BouldyError> {
+    store.with_file(vault_path, || {
+        let mut items = list_inbox(vault_path)?;
+        let index = items
+            .iter()
+            .position(|item| item.id == id)
+            .ok_or_else(|| BouldyError::not_found(format!("Inbox item not found: {}", id)))?;
+        let item = &items[index];
+
+        let processed = match action {
+            InboxAction::Todo { project, due } => {
+                let todo = to_todo(item, project.as_deref(), due.as_deref(), None, locale)?;
+                append_to_todos(vault_path, todo)?
+            }
+            InboxAction::Delegate { person, due } => {
+                let todo = to_todo(item, None, due.as_deref(), Some(&person), locale)?;
+                append_to_todos(vault_path, todo)?
+            }
+            InboxAction::Note { title } => {
+                let title = title
+                    .unwrap_or_else(|| item.text.lines().next().unwrap_or_default().to_string());
+                let path = vault::create_note(vault_path, &title, &note_content(item, &title))?;
+                Processed::Note {
+                    path: path.to_string_lossy().to_string(),
+                }
+            }
+            InboxAction::Discard => Processed::Discarded,
+        };
+
+        items.remove(index);
+        save_inbox(vault_path, &items)?;
+        Ok(processed)
+    })
+}
+
+fn append_to_todos(vault_path: &str, todo: TodoItem) -> Result<Processed, BouldyError> {
+    let mut todos_list = todos::load_todos(vault_path)?;
+    todos_list.push(todo);
+    todos::save_todos(vault_path, &todos_list)?;
+
+    // Re-read for the id the todo got
+    let todo = todos::load_todos(vault_path)?
+        .pop()
+        .ok_or("Failed to add todo")?;
+    Ok(Processed::Todo {
+        todo: Box::new(todo),
+    })
+}
@@ -0,0 +1,770 @@
+use rayon::prelude::*;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::BouldyError;
+use crate::fsutil;
+use crate::markdown;
+use crate::shutdown;
+use crate::switcher::QuickSwitcher;
+use crate::text;
+use crate::vault::{self, Note, Page, SearchMatch};
+
+const INDEX_FILE: &str = "index.db";
+const MAX_SEARCH_RESULTS: i64 = 200;
+
+// Notes read in parallel per batch; progress is reported and cancellation
+// checked between batches
+const SCAN_BATCH_SIZE: usize = 64;
+
+// Bump when the schema changes; older index files are dropped and rebuilt
+// from the notes, which remain the source of truth. Also bumped when what
+// gets stored changes (2: NFC-normalized full text, 3: aliases).
+const INDEX_SCHEMA_VERSION: i64 = 3;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS notes (
+        path TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        title TEXT NOT NULL,
+        modified INTEGER NOT NULL,
+        size INTEGER NOT NULL,
+        is_symlink INTEGER NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS tags (
+        path TEXT NOT NULL,
+        tag TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS tags_by_path ON tags(path);
+    CREATE INDEX IF NOT EXISTS tags_by_tag ON tags(tag);
+    CREATE TABLE IF NOT EXISTS links (
+        source TEXT NOT NULL,
+        target TEXT NOT NULL COLLATE NOCASE
+    );
+    CREATE INDEX IF NOT EXISTS links_by_source ON links(source);
+    CREATE INDEX IF NOT EXISTS links_by_target ON links(target);
+    CREATE TABLE IF NOT EXISTS aliases (
+        path TEXT NOT NULL,
+        alias TEXT NOT NULL COLLATE NOCASE
+    );
+    CREATE INDEX IF NOT EXISTS aliases_by_path ON aliases(path);
+    CREATE VIRTUAL TABLE IF NOT EXISTS notes_fts USING fts5(
+        path UNINDEXED,
+        title,
+        content
+    );
+";
+
+fn db_err(e: rusqlite::Error) -> BouldyError {
+    format!("Index error: {}", e).into()
+}
+
+// Turn free text into an FTS5 query: every word must appear, as a prefix,
+// and quoting keeps FTS syntax characters in user input from erroring
+fn fts_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"*", text::nfc(term).replace('"', "\"\"")))
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+// One match per line of a note FTS found, the way vault::search_notes
+// reports them: the title as line 0, then every line holding all of the
+// query's words. `terms` are already folded; FTS matches them as prefixes,
+// so a substring check finds the same words. FTS matches words anywhere in
+// the note, so when no single line holds them all the note is still listed
+// once, at the first line with any of them, with FTS's own snippet.
+fn line_matches(
+    path: String,
+    title: String,
+    content: &str,
+    fts_snippet: String,
+    terms: &[String],
+) -> Vec<SearchMatch> {
+    let contains_all = |text: &str| {
+        let text = text::fold(text);
+        terms.iter().all(|term| text.contains(term.as_str()))
+    };
+    let mut matches = Vec::new();
+
+    if contains_all(&title) {
+        matches.push(SearchMatch {
+            path: path.clone(),
+            title: title.clone(),
+            line: 0,
+            snippet: title.clone(),
+        });
+    }
+    for (index, line) in content.lines().enumerate() {
+        if contains_all(line) {
+            matches.push(SearchMatch {
+                path: path.clone(),
+                title: title.clone(),
+                line: index + 1,
+                snippet: line.trim().to_string(),
+            });
+        }
+    }
+
+    if matches.is_empty() {
+        let line = content
+            .lines()
+            .position(|line| {
+                let line = text::fold(line);
+                terms.iter().any(|term| line.contains(term.as_str()))
+            })
+            .map_or(0, |index| index + 1);
+        matches.push(SearchMatch {
+            path,
+            title,
+            line,
+            snippet: fts_snippet,
+        });
+    }
+    matches
+}
+
+/// Everything indexed about one note. Reading and parsing is the expensive
+/// part of indexing and needs no database access, so the initial scan builds
+/// records in parallel and only writes them sequentially.
+pub struct NoteRecord {
+    path: PathBuf,
+    name: String,
+    title: String,
+    modified: u64,
+    size: u64,
+    is_symlink: bool,
+    tags: Vec<String>,
+    links: Vec<String>,
+    aliases: Vec<String>,
+    content: String,
+}
+
+impl NoteRecord {
+    pub fn read(path: &Path) -> Option<Self> {
+        let metadata = fs::metadata(fsutil::long_path(path)).ok()?;
+        let content = fs::read_to_string(fsutil::long_path(path)).unwrap_or_default();
+
+        Some(Self {
+            path: path.to_path_buf(),
+            name: path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            title: vault::extract_title_from_filename(path),
+            modified: metadata
+                .modified()
+                .ok()
+                .and_then(|m| m.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            size: metadata.len(),
+            is_symlink: path.is_symlink(),
+            tags: markdown::extract_tags(&content),
+            links: markdown::extract_links(&content),
+            aliases: markdown::parse_frontmatter(&content)
+                .map(|fm| markdown::frontmatter_aliases(&fm))
+                .unwrap_or_default(),
+            content,
+        })
+    }
+}
+
+/// SQLite index of note metadata, tags, links and full text for one vault.
+/// Lives in `.bouldy/index.db` and can be deleted at any time.
+pub struct VaultIndex {
+    vault_path: String,
+    conn: Connection,
+}
+
+impl VaultIndex {
+    pub fn open(vault_path: &str) -> Result<Self, BouldyError> {
+        let bouldy_dir = Path::new(vault_path).join(".bouldy");
+        fs::create_dir_all(&bouldy_dir)
+            .map_err(|e| BouldyError::io("Failed to create .bouldy directory", e))?;
+
+        let conn = Connection::open(bouldy_dir.join(INDEX_FILE)).map_err(db_err)?;
+
+        let version: i64 = conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .map_err(db_err)?;
+        if version != INDEX_SCHEMA_VERSION {
+            conn.execute_batch(
+                "DROP TABLE IF EXISTS notes;
+                 DROP TABLE IF EXISTS tags;
+                 DROP TABLE IF EXISTS links;
+                 DROP TABLE IF EXISTS aliases;
+                 DROP TABLE IF EXISTS notes_fts;",
+            )
+            .map_err(db_err)?;
+        }
+
+        conn.execute_batch(SCHEMA).map_err(db_err)?;
+        conn.pragma_update(None, "user_version", INDEX_SCHEMA_VERSION)
+            .map_err(db_err)?;
+
+        Ok(Self {
+            vault_path: vault_path.to_string(),
+            conn,
+        })
+    }
+
+    pub fn vault_path(&self) -> &str {
+        &self.vault_path
+    }
+
+    /// Forget everything, so the next scan re-reads every note.
+    pub fn clear(&mut self) -> Result<(), BouldyError> {
+        self.conn
+            .execute_batch(
+                "DELETE FROM notes;
+                 DELETE FROM tags;
+                 DELETE FROM links;
+                 DELETE FROM aliases;
+                 DELETE FROM notes_fts;",
+            )
+            .map_err(db_err)
+    }
+
+    /// Compare the notes directory with the index by mtime and size only,
+    /// returning notes to (re)index and indexed paths that no longer exist.
+    pub fn stale_notes(&self) -> Result<(Vec<PathBuf>, Vec<PathBuf>), BouldyError> {
+        let notes_dir = vault::notes_dir(&self.vault_path);
+        let entries = fs::read_dir(&notes_dir)
+            .map_err(|e| BouldyError::io("Failed to read notes directory", e))?;
+
+        let mut indexed: HashMap<String, (u64, u64)> = {
+            let mut stmt = self
+                .conn
+                .prepare("SELECT path, modified, size FROM notes")
+                .map_err(db_err)?;
+            let rows = stmt
+                .query_map([], |row| Ok((row.get(0)?, (row.get(1)?, row.get(2)?))))
+                .map_err(db_err)?;
+            rows.collect::<Result<_, _>>().map_err(db_err)?
+        };
+
+        // Stat in parallel - on large vaults this pass dominates a no-op sync
+        let on_disk: Vec<(PathBuf, (u64, u64))> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| vault::is_markdown_file(path))
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .filter_map(|path| {
+                let metadata = fs::metadata(&path).ok()?;
+                let modified = metadata
+                    .modified()
+                    .ok()
+                    .and_then(|m| m.duration_since(std::time::UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0);
+                Some((path, (modified, metadata.len())))
+            })
+            .collect();
+
+        let mut changed = Vec::new();
+        for (path, stamp) in on_disk {
+            let known = indexed.remove(path.to_string_lossy().as_ref());
+            if known != Some(stamp) {
+                changed.push(path);
+            }
+        }
+
+        // Anything left in `indexed` wasn't found on disk
+        let removed = indexed.into_keys().map(PathBuf::from).collect();
+
+        Ok((changed, removed))
+    }
+
+    /// Bring the index in line with the notes directory, re-reading only
+    /// notes whose mtime or size changed since they were indexed.
+    pub fn sync_all(&mut self) -> Result<(), BouldyError> {
+        let (changed, removed) = self.stale_notes()?;
+
+        for path in &changed {
+            self.upsert_note(path)?;
+        }
+        for path in &removed {
+            self.remove_note(path)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn upsert_note(&mut self, path: &Path) -> Result<(), BouldyError> {
+        match NoteRecord::read(path) {
+            Some(record) => self.write_record(&record),
+            // Gone (or a broken symlink) by the time we got here
+            None => self.remove_note(path),
+        }
+    }
+
+    pub fn write_record(&mut self, record: &NoteRecord) -> Result<(), BouldyError> {
+        let path_str = record.path.to_string_lossy().to_string();
+
+        let tx = self.conn.transaction().map_err(db_err)?;
+        for table in ["tags", "links", "aliases", "notes_fts"] {
+            let column = if table == "links" { "source" } else { "path" };
+            tx.execute(
+                &format!("DELETE FROM {} WHERE {} = ?1", table, column),
+                params![path_str],
+            )
+            .map_err(db_err)?;
+        }
+
+        tx.execute(
+            "INSERT OR REPLACE INTO notes (path, name, title, modified, size, is_symlink)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                path_str,
+                record.name,
+                record.title,
+                record.modified,
+                record.size,
+                record.is_symlink
+            ],
+        )
+        .map_err(db_err)?;
+
+        for tag in &record.tags {
+            tx.execute(
+                "INSERT INTO tags (path, tag) VALUES (?1, ?2)",
+                params![path_str, tag],
+            )
+            .map_err(db_err)?;
+        }
+
+        for target in &record.links {
+            tx.execute(
+                "INSERT INTO links (source, target) VALUES (?1, ?2)",
+                params![path_str, target],
+            )
+            .map_err(db_err)?;
+        }
+
+        for alias in &record.aliases {
+            tx.execute(
+                "INSERT INTO aliases (path, alias) VALUES (?1, ?2)",
+                params![path_str, alias],
+            )
+            .map_err(db_err)?;
+        }
+
+        tx.execute(
+            "INSERT INTO notes_fts (path, title, content) VALUES (?1, ?2, ?3)",
+            params![path_str, record.title, text::nfc(&record.content)],
+        )
+        .map_err(db_err)?;
+
+        tx.commit().map_err(db_err)
+    }
+
+    pub fn remove_note(&mut self, path: &Path) -> Result<(), BouldyError> {
+        let path_str = path.to_string_lossy().to_string();
+
+        let tx = self.conn.transaction().map_err(db_err)?;
+        tx.execute("DELETE FROM notes WHERE path = ?1", params![path_str])
+            .map_err(db_err)?;
+        tx.execute("DELETE FROM tags WHERE path = ?1", params![path_str])
+            .map_err(db_err)?;
+        tx.execute("DELETE FROM links WHERE source = ?1", params![path_str])
+            .map_err(db_err)?;
+        tx.execute("DELETE FROM aliases WHERE path = ?1", params![path_str])
+            .map_err(db_err)?;
+        tx.execute("DELETE FROM notes_fts WHERE path = ?1", params![path_str])
+            .map_err(db_err)?;
+        tx.commit().map_err(db_err)
+    }
+
+    fn query_notes(
+        &self,
+        sql: &str,
+        args: &[&dyn rusqlite::ToSql],
+    ) -> Result<Vec<Note>, BouldyError> {
+        let mut stmt = self.conn.prepare(sql).map_err(db_err)?;
+        let rows = stmt
+            .query_map(args, |row| {
+                Ok(Note {
+                    path: row.get(0)?,
+                    name: row.get(1)?,
+                    title: row.get(2)?,
+                    modified: row.get(3)?,
+                    is_symlink: row.get(4)?,
+                })
+            })
+            .map_err(db_err)?;
+
+        rows.collect::<Result<_, _>>().map_err(db_err)
+    }
+
+    /// All notes, newest first.
+    pub fn list_notes(&self) -> Result<Vec<Note>, BouldyError> {
+        self.query_notes(
+            "SELECT path, name, title, modified, is_symlink FROM notes ORDER BY modified DESC",
+            &[],
+        )
+    }
+
+    /// A window of the note list, newest first, without loading the rest.
+    pub fn list_notes_page(
+        &self,
+        offset: Option<usize>,
+        limit: Option<usize>,
+    ) -> Result<Page<Note>, BouldyError> {
+        let total: i64 = self
+            .conn
+            .query_row("SELECT COUNT(*) FROM notes", [], |row| row.get(0))
+            .map_err(db_err)?;
+
+        let offset = offset.unwrap_or(0) as i64;
+        // SQLite treats a negative LIMIT as no limit
+        let limit = limit.map(|l| l as i64).unwrap_or(-1);
+
+        let items = self.query_notes(
+            "SELECT path, name, title, modified, is_symlink FROM notes
+             ORDER BY modified DESC LIMIT ?1 OFFSET ?2",
+            &[&limit, &offset],
+        )?;
+
+        Ok(Page {
+            items,
+            total: total as usize,
+            offset: (offset as usize).min(total as usize),
+        })
+    }
+
+    /// Notes linking to the note at `path` by title or any of its aliases,
+    /// newest first.
+    pub fn backlinks(&self, path: &Path) -> Result<Vec<Note>, BouldyError> {
+        let target = vault::extract_title_from_filename(path);
+        self.query_notes(
+            "SELECT DISTINCT n.path, n.name, n.title, n.modified, n.is_symlink
+             FROM links l JOIN notes n ON n.path = l.source
+             WHERE (l.target = ?1
+                    OR l.target IN (SELECT alias FROM aliases WHERE path = ?2))
+               AND l.source != ?2
+             ORDER BY n.modified DESC",
+            &[&target, &path.to_string_lossy().to_string()],
+        )
+    }
+
+    /// Every note's aliases, keyed by path.
+    pub fn aliases(&self) -> Result<HashMap<String, Vec<String>>, BouldyError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT path, alias FROM aliases")
+            .map_err(db_err)?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(db_err)?;
+
+        let mut aliases: HashMap<String, Vec<String>> = HashMap::new();
+        for row in rows {
+            let (path, alias) = row.map_err(db_err)?;
+            aliases.entry(path).or_default().push(alias);
+        }
+        Ok(aliases)
+    }
+
+    /// The note that lists `alias` among its aliases, ignoring case.
+    pub fn find_by_alias(&self, alias: &str) -> Result<Option<String>, BouldyError> {
+        self.conn
+            .query_row(
+                "SELECT path FROM aliases WHERE alias = ?1 ORDER BY path LIMIT 1",
+                params![alias],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(db_err)
+    }
+
+    /// Full-text search over titles and content: best matching notes first,
+    /// each with its matching lines in order.
+    pub fn search(&self, query: &str) -> Result<Vec<SearchMatch>, BouldyError> {
+        let Some(fts) = fts_query(query) else {
+            return Ok(Vec::new());
+        };
+
+        let terms: Vec<String> = query.split_whitespace().map(text::fold).collect();
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT path, title, snippet(notes_fts, 2, '', '', '…', 12), content
+                 FROM notes_fts WHERE notes_fts MATCH ?1
+                 ORDER BY rank LIMIT ?2",
+            )
+            .map_err(db_err)?;
+        let rows = stmt
+            .query_map(params![fts, MAX_SEARCH_RESULTS], |row| {
+                let content: String = row.get(3)?;
+                Ok(line_matches(
+                    row.get(0)?,
+                    row.get(1)?,
+                    &content,
+                    row.get(2)?,
+                    &terms,
+                ))
+            })
+            .map_err(db_err)?;
+
+        let notes: Vec<Vec<SearchMatch>> = rows.collect::<Result<_, _>>().map_err(db_err)?;
+        Ok(notes.into_iter().flatten().collect())
+    }
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct IndexStatus {
+    pub ready: bool,
+    pub indexed: usize,
+    pub total: usize,
+    // Why the last scan stopped short, if it did
+    pub error: Option<String>,
+}
+
+/// Shared flag checked between units of work by long-running vault scans.
+#[derive(Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    fn same(&self, other: &CancelToken) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// Managed state holding the index of the vault the watcher is running on.
+#[derive(Default)]
+pub struct IndexState {
+    index: Mutex<Option<VaultIndex>>,
+    status: Mutex<IndexStatus>,
+    scan: Mutex<Option<CancelToken>>,
+}
+
+impl IndexState {
+    /// Stop the running background scan, if any. The index keeps taking
+    /// incremental updates but isn't marked ready.
+    pub fn cancel_scan(&self) {
+        if let Some(token) = self.scan.lock().unwrap().take() {
+            token.cancel();
+        }
+    }
+
+    pub fn status(&self) -> IndexStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    // Record how the scan for `token` ended: ready, cancelled or failed.
+    // None if another scan or vault has taken over since, whose status this
+    // must not touch. The scan lock is held throughout, so a scan starting
+    // meanwhile can't have its status overwritten.
+    fn finish_scan(
+        &self,
+        token: &CancelToken,
+        vault_path: &str,
+        outcome: Result<(), BouldyError>,
+    ) -> Option<IndexStatus> {
+        let scan = self.scan.lock().unwrap();
+        if scan.as_ref().is_some_and(|current| !current.same(token)) {
+            return None;
+        }
+        let open = self.index.lock().unwrap();
+        if open.as_ref().map(VaultIndex::vault_path) != Some(vault_path) {
+            return None;
+        }
+
+        let mut status = self.status.lock().unwrap();
+        if token.is_cancelled() {
+            status.error = Some("Indexing was cancelled".to_string());
+        } else if let Err(e) = outcome {
+            status.error = Some(e.to_string());
+        } else {
+            status.ready = true;
+            status.error = None;
+        }
+        Some(status.clone())
+    }
+
+    /// Stop any scan and close the index, returning the vault it was for.
+    pub fn close(&self) -> Option<String> {
+        self.cancel_scan();
+        let index = self.index.lock().unwrap().take()?;
+        let vault_path = index.vault_path().to_string();
+        // Lets SQLite refresh its statistics while the data is at hand
+        if let Err(e) = index.conn.execute_batch("PRAGMA optimize;") {
+            tracing::warn!("Failed to optimize index - {}", e);
+        }
+        Some(vault_path)
+    }
+
+    /// Run `f` against the index if it's open for `vault_path` and the
+    /// initial scan has finished. Until then callers fall back to the
+    /// filesystem or report that indexing is in progress.
+    pub fn with_vault<T>(
+        &self,
+        vault_path: &str,
+        f: impl FnOnce(&mut VaultIndex) -> Result<T, BouldyError>,
+    ) -> Option<Result<T, BouldyError>> {
+        if !self.status.lock().unwrap().ready {
+            return None;
+        }
+        self.update(vault_path, f)
+    }
+
+    /// Like `with_vault`, but also while the initial scan is running. Used
+    /// for incremental updates, which must not be lost mid-scan.
+    pub fn update<T>(
+        &self,
+        vault_path: &str,
+        f: impl FnOnce(&mut VaultIndex) -> Result<T, BouldyError>,
+    ) -> Option<Result<T, BouldyError>> {
+        let mut guard = self.index.lock().unwrap();
+        match guard.as_mut() {
+            Some(index) if index.vault_path() == vault_path => Some(f(index)),
+            _ => None,
+        }
+    }
+}
+
+// Catch the index up with the notes directory. Returns early, without an
+// error, when cancelled.
+fn scan(app: &AppHandle, vault_path: &str, cancel: &CancelToken) -> Result<(), BouldyError> {
+    let state = app.state::<IndexState>();
+
+    let (changed, removed) = state
+        .update(vault_path, |index| index.stale_notes())
+        .unwrap_or_else(|| Err("Index was closed".into()))?;
+
+    let total = changed.len();
+    state.status.lock().unwrap().total = total;
+
+    let mut indexed = 0;
+    for chunk in changed.chunks(SCAN_BATCH_SIZE) {
+        if cancel.is_cancelled() {
+            return Ok(());
+        }
+
+        let records: Vec<(&PathBuf, Option<NoteRecord>)> = chunk
+            .par_iter()
+            .map(|path| (path, NoteRecord::read(path)))
+            .collect();
+
+        for (path, record) in records {
+            let result = state.update(vault_path, |index| match record {
+                Some(ref record) => index.write_record(record),
+                None => index.remove_note(path),
+            });
+            if let Some(Err(e)) = result {
+                tracing::warn!("{}", e);
+            }
+        }
+
+        indexed += chunk.len();
+        state.status.lock().unwrap().indexed = indexed;
+        let _ = app.emit("index:progress", state.status());
+    }
+
+    for path in &removed {
+        if let Some(Err(e)) = state.update(vault_path, |index| index.remove_note(path)) {
+            tracing::warn!("{}", e);
+        }
+    }
+    Ok(())
+}
+
+#[derive(Serialize, Clone)]
+struct IndexReadyPayload {
+    #[serde(rename = "vaultPath")]
+    vault_path: String,
+    total: usize,
+}
+
+/// Open the index for `vault_path` and catch it up on a background thread,
+/// emitting `index:progress` as notes are indexed and `index:ready` when done,
+/// or `index:cancelled` / `index:error` if it stops short.
+/// The lock is taken per note so watcher updates and commands aren't blocked
+/// for the length of the scan.
+pub fn start_background(app: &AppHandle, vault_path: &str) -> Result<(), BouldyError> {
+    let mut vault_index = VaultIndex::open(vault_path)?;
+    // After a crash the mtime and size check may miss writes that didn't
+    // make it into the index, so start over
+    if !shutdown::take_clean_marker(vault_path) {
+        tracing::info!("Previous session did not shut down cleanly; re-indexing");
+        vault_index.clear()?;
+    }
+    let state = app.state::<IndexState>();
+
+    // A scan of a previously opened vault is no longer wanted
+    let cancel = CancelToken::default();
+    if let Some(previous) = state.scan.lock().unwrap().replace(cancel.clone()) {
+        previous.cancel();
+    }
+
+    *state.index.lock().unwrap() = Some(vault_index);
+    *state.status.lock().unwrap() = IndexStatus::default();
+
+    let app = app.clone();
+    let vault_path = vault_path.to_string();
+
+    thread::spawn(move || {
+        let state = app.state::<IndexState>();
+        let outcome = scan(&app, &vault_path, &cancel);
+        if let Err(ref e) = outcome {
+            tracing::warn!("Failed to index vault: {}", e);
+        }
+
+        let Some(status) = state.finish_scan(&cancel, &vault_path, outcome) else {
+            // Superseded by a scan of another vault, which owns the status now
+            let _ = app.emit("index:cancelled", state.status());
+            return;
+        };
+        if !status.ready {
+            let event = if cancel.is_cancelled() {
+                "index:cancelled"
+            } else {
+                "index:error"
+            };
+            let _ = app.emit(event, status);
+            return;
+        }
+
+        app.state::<QuickSwitcher>().invalidate_notes();
+        let note_count = state
+            .update(&vault_path, |index| index.list_notes_page(Some(0), Some(0)))
+            .and_then(Result::ok)
+            .map(|page| page.total)
+            .unwrap_or(0);
+
+        let _ = app.emit(
+            "index:ready",
+            IndexReadyPayload {
+                vault_path,
+                total: note_count,
+            },
+        );
+    });
+
+    Ok(())
+}
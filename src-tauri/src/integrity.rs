@@ -0,0 +1,236 @@
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::BouldyError;
+use crate::{fsutil, vault, vault_lock};
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+struct ManifestEntry {
+    sha256: String,
+    size: u64,
+    modified: u64,
+}
+
+// Vault-relative path with forward slashes -> last known good state
+#[derive(Serialize, Deserialize, Default)]
+struct Manifest {
+    files: BTreeMap<String, ManifestEntry>,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IssueKind {
+    // Changed by something other than bouldy, e.g. a sync client
+    #[serde(rename = "modified")]
+    Modified,
+    // Shorter than before and cut off: empty, ending in a partial UTF-8
+    // character, or padded with NUL bytes
+    #[serde(rename = "truncated")]
+    Truncated,
+    // Content changed while size and modification time stayed the same
+    #[serde(rename = "corrupted")]
+    Corrupted,
+    #[serde(rename = "missing")]
+    Missing,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct IntegrityIssue {
+    pub path: String,
+    pub kind: IssueKind,
+    #[serde(rename = "expectedSize")]
+    pub expected_size: u64,
+    #[serde(rename = "actualSize")]
+    pub actual_size: Option<u64>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct IntegrityReport {
+    pub checked: usize,
+    // Files seen for the first time, now added to the manifest
+    pub added: usize,
+    pub issues: Vec<IntegrityIssue>,
+}
+
+fn manifest_path(vault_path: &str) -> PathBuf {
+    Path::new(vault_path).join(".bouldy").join(MANIFEST_FILE)
+}
+
+fn load_manifest(vault_path: &str) -> Manifest {
+    fs::read_to_string(manifest_path(vault_path))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_manifest(vault_path: &str, manifest: &Manifest) -> Result<(), BouldyError> {
+    let content = serde_json::to_string(manifest)
+        .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+    fsutil::write_atomic(&manifest_path(vault_path), content)
+        .map_err(|e| BouldyError::io("Failed to write manifest", e))
+}
+
+fn relative_key(vault: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(vault).ok()?;
+    let parts: Vec<&str> = relative
+        .components()
+        .map(|part| part.as_os_str().to_str())
+        .collect::<Option<_>>()?;
+    Some(parts.join("/"))
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+// What a file looks like now, and whether it looks cut off
+fn entry_for(path: &Path) -> Option<(ManifestEntry, bool)> {
+    let bytes = fs::read(fsutil::long_path(path)).ok()?;
+    let metadata = fs::metadata(fsutil::long_path(path)).ok()?;
+    let entry = ManifestEntry {
+        sha256: sha256_hex(&bytes),
+        size: bytes.len() as u64,
+        modified: metadata
+            .modified()
+            .map(vault::unix_secs)
+            .unwrap_or_default(),
+    };
+    Some((entry, looks_truncated(path, &bytes)))
+}
+
+fn looks_truncated(path: &Path, bytes: &[u8]) -> bool {
+    if bytes.is_empty() || bytes.ends_with(&[0]) {
+        return true;
+    }
+    // Text cut mid-character no longer decodes
+    vault::is_markdown_file(path)
+        && std::str::from_utf8(bytes).is_err_and(|e| e.error_len().is_none())
+}
+
+fn classify(known: &ManifestEntry, current: &ManifestEntry, cut_off: bool) -> IssueKind {
+    if current.size < known.size && cut_off {
+        IssueKind::Truncated
+    } else if current.size == known.size && current.modified == known.modified {
+        IssueKind::Corrupted
+    } else {
+        IssueKind::Modified
+    }
+}
+
+/// Record `path` as just written by bouldy, so the save isn't reported as
+/// an outside change. The caller holds the vault lock.
+pub fn record(vault_path: &str, path: &Path) -> Result<(), BouldyError> {
+    let vault = Path::new(vault_path)
+        .canonicalize()
+        .map_err(|e| BouldyError::io("Invalid vault path", e))?;
+    let canonical = path
+        .canonicalize()
+        .map_err(|e| BouldyError::io("Failed to read note", e))?;
+    let (Some(key), Some((entry, _))) = (relative_key(&vault, &canonical), entry_for(&canonical))
+    else {
+        return Ok(());
+    };
+
+    let mut manifest = load_manifest(vault_path);
+    if manifest.files.get(&key) == Some(&entry) {
+        return Ok(());
+    }
+    manifest.files.insert(key, entry);
+    save_manifest(vault_path, &manifest)
+}
+
+/// Hash every file in the vault and compare against the manifest of
+/// content hashes kept as bouldy saves. Files not in the manifest yet are
+/// added; files with issues keep their old entry until accepted.
+pub fn verify(vault_path: &str) -> Result<IntegrityReport, BouldyError> {
+    let vault = Path::new(vault_path)
+        .canonicalize()
+        .map_err(|e| BouldyError::io("Invalid vault path", e))?;
+    let _lock = vault_lock::acquire(vault_path)?;
+    let mut manifest = load_manifest(vault_path);
+
+    let mut files = Vec::new();
+    vault::walk_files(&vault, &mut files);
+    let current: Vec<(String, PathBuf, ManifestEntry, bool)> = files
+        .into_par_iter()
+        .filter_map(|path| {
+            let key = relative_key(&vault, &path)?;
+            let (entry, cut_off) = entry_for(&path)?;
+            Some((key, path, entry, cut_off))
+        })
+        .collect();
+
+    let mut report = IntegrityReport {
+        checked: current.len(),
+        added: 0,
+        issues: Vec::new(),
+    };
+    let mut seen = std::collections::HashSet::new();
+    for (key, path, entry, cut_off) in current {
+        seen.insert(key.clone());
+        match manifest.files.get(&key) {
+            None => {
+                manifest.files.insert(key, entry);
+                report.added += 1;
+            }
+            Some(known) if known.sha256 == entry.sha256 => {}
+            Some(known) => report.issues.push(IntegrityIssue {
+                path: vault::path_to_string(&path).unwrap_or(key),
+                kind: classify(known, &entry, cut_off),
+                expected_size: known.size,
+                actual_size: Some(entry.size),
+            }),
+        }
+    }
+    for (key, known) in &manifest.files {
+        if !seen.contains(key) {
+            report.issues.push(IntegrityIssue {
+                path: vault.join(key).to_string_lossy().to_string(),
+                kind: IssueKind::Missing,
+                expected_size: known.size,
+                actual_size: None,
+            });
+        }
+    }
+
+    if report.added > 0 {
+        save_manifest(vault_path, &manifest)?;
+    }
+    report.issues.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(report)
+}
+
+/// Take the current state of `paths` as correct, or forget them if they
+/// no longer exist, after the user has reviewed the issues.
+pub fn accept(vault_path: &str, paths: &[String]) -> Result<(), BouldyError> {
+    let vault = Path::new(vault_path)
+        .canonicalize()
+        .map_err(|e| BouldyError::io("Invalid vault path", e))?;
+    let _lock = vault_lock::acquire(vault_path)?;
+    let mut manifest = load_manifest(vault_path);
+
+    for path in paths {
+        // Missing files can't be canonicalized
+        let path = Path::new(path);
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let Some(key) = relative_key(&vault, &path) else {
+            return Err(BouldyError::outside_vault(format!(
+                "Path is outside vault: {}",
+                path.display()
+            )));
+        };
+        match entry_for(&path) {
+            Some((entry, _)) => manifest.files.insert(key, entry),
+            None => manifest.files.remove(&key),
+        };
+    }
+    save_manifest(vault_path, &manifest)
+}
@@ -0,0 +1,345 @@
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::error::BouldyError;
+use crate::locale::LocaleInfo;
+use crate::{fsutil, markdown, vault, vault_lock};
+
+pub const JOURNAL_DIR: &str = "journal";
+
+// Mood and energy are rated 1 (low) to 5 (high)
+pub const MIN_RATING: u8 = 1;
+pub const MAX_RATING: u8 = 5;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+const DEFAULT_PROMPTS: &[&str] = &[
+    "What am I grateful for today?",
+    "What would make today great?",
+    "What did I learn?",
+];
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct JournalSettings {
+    // Inserted as headings into each new entry
+    #[serde(default = "default_prompts")]
+    pub prompts: Vec<String>,
+    // Rotate through the prompts this many a day instead of using them all
+    #[serde(rename = "promptsPerEntry", default)]
+    pub prompts_per_entry: Option<usize>,
+}
+
+impl Default for JournalSettings {
+    fn default() -> Self {
+        Self {
+            prompts: default_prompts(),
+            prompts_per_entry: None,
+        }
+    }
+}
+
+fn default_prompts() -> Vec<String> {
+    DEFAULT_PROMPTS.iter().map(|p| p.to_string()).collect()
+}
+
+impl JournalSettings {
+    pub fn validate(&self) -> Result<(), BouldyError> {
+        if self.prompts.iter().any(|prompt| prompt.trim().is_empty()) {
+            return Err(BouldyError::invalid_input(
+                "Journal prompts cannot be empty",
+            ));
+        }
+        if self.prompts_per_entry == Some(0) {
+            return Err(BouldyError::invalid_input(
+                "Prompts per entry must be at least 1",
+            ));
+        }
+        Ok(())
+    }
+
+    // The same day always gets the same prompts
+    fn prompts_for(&self, date: NaiveDate) -> Vec<&str> {
+        let count = self.prompts.len();
+        match self.prompts_per_entry {
+            Some(per_entry) if per_entry < count => {
+                let start = date.num_days_from_ce() as usize * per_entry % count;
+                (0..per_entry)
+                    .map(|i| self.prompts[(start + i) % count].as_str())
+                    .collect()
+            }
+            _ => self.prompts.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct JournalEntry {
+    pub path: String,
+    pub date: String,
+    // False when the entry already existed
+    pub created: bool,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct JournalStreak {
+    // Consecutive days up to today; not broken until today is over
+    pub current: usize,
+    pub longest: usize,
+    pub total: usize,
+    #[serde(rename = "writtenToday")]
+    pub written_today: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct JournalDay {
+    pub date: String,
+    pub mood: Option<u8>,
+    pub energy: Option<u8>,
+    // Words written, not counting the prompt headings
+    pub words: usize,
+}
+
+pub struct EntryLookup {
+    pub path: PathBuf,
+    // None if there's no entry for the day yet
+    pub day: Option<JournalDay>,
+}
+
+impl JournalDay {
+    // An entry opened but left as the blank template doesn't count
+    pub fn is_written(&self) -> bool {
+        self.words > 0 || self.mood.is_some() || self.energy.is_some()
+    }
+}
+
+fn journal_dir(vault_path: &str) -> PathBuf {
+    vault::notes_dir(vault_path).join(JOURNAL_DIR)
+}
+
+fn entry_path(vault_path: &str, date: NaiveDate) -> PathBuf {
+    journal_dir(vault_path).join(format!("{}.md", date.format(DATE_FORMAT)))
+}
+
+fn parse_date(date: &str) -> Result<NaiveDate, BouldyError> {
+    NaiveDate::parse_from_str(date.trim(), DATE_FORMAT).map_err(|_| {
+        BouldyError::invalid_input(format!("Invalid date '{}'. Expected YYYY-MM-DD", date))
+    })
+}
+
+fn validate_rating(name: &str, rating: Option<u8>) -> Result<(), BouldyError> {
+    match rating {
+        Some(value) if !(MIN_RATING..=MAX_RATING).contains(&value) => {
+            Err(BouldyError::invalid_input(format!(
+                "{} must be between {} and {}",
+                name, MIN_RATING, MAX_RATING
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn read_day(date: NaiveDate, content: &str) -> JournalDay {
+    let frontmatter = markdown::parse_frontmatter(content).unwrap_or_default();
+    let rating = |key: &str| {
+        frontmatter
+            .get(key)
+            .and_then(serde_yaml::Value::as_u64)
+            .and_then(|value| u8::try_from(value).ok())
+            .filter(|value| (MIN_RATING..=MAX_RATING).contains(value))
+    };
+
+    let (_, body) = markdown::split_frontmatter(content);
+    let words = body
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .map(|line| line.split_whitespace().count())
+        .sum();
+
+    JournalDay {
+        date: date.format(DATE_FORMAT).to_string(),
+        mood: rating("mood"),
+        energy: rating("energy"),
+        words,
+    }
+}
+
+// Every entry in the journal folder, keyed by date
+fn read_days(vault_path: &str) -> Result<BTreeMap<NaiveDate, JournalDay>, BouldyError> {
+    let entries = match fs::read_dir(journal_dir(vault_path)) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+        Err(e) => return Err(BouldyError::io("Failed to read journal folder", e)),
+    };
+
+    let mut days = BTreeMap::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !vault::is_markdown_file(&path) {
+            continue;
+        }
+        let Some(date) = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| NaiveDate::parse_from_str(stem, DATE_FORMAT).ok())
+        else {
+            continue;
+        };
+        match fs::read_to_string(&path) {
+            Ok(content) => {
+                days.insert(date, read_day(date, &content));
+            }
+            Err(e) => tracing::warn!("Failed to read {} - {}", path.display(), e),
+        }
+    }
+    Ok(days)
+}
+
+fn new_entry(settings: &JournalSettings, date: NaiveDate, locale: &LocaleInfo) -> String {
+    let mut content = format!(
+        "---\ndate: {}\nmood:\nenergy:\ntags: [\"{}\"]\n---\n\n# {}\n",
+        date.format(DATE_FORMAT),
+        JOURNAL_DIR,
+        locale.format_long(date)
+    );
+    for prompt in settings.prompts_for(date) {
+        content.push_str(&format!("\n## {}\n\n", prompt.trim()));
+    }
+    content
+}
+
+/// The entry for `date`, without creating it.
+pub fn find_entry(vault_path: &str, date: NaiveDate) -> EntryLookup {
+    let path = entry_path(vault_path, date);
+    let day = fs::read_to_string(&path)
+        .ok()
+        .map(|content| read_day(date, &content));
+    EntryLookup { path, day }
+}
+
+/// Open the entry for `date` (today by default), creating it with the
+/// configured prompts if it doesn't exist yet.
+pub fn open_entry(
+    vault_path: &str,
+    settings: &JournalSettings,
+    date: Option<&str>,
+    locale: &LocaleInfo,
+) -> Result<JournalEntry, BouldyError> {
+    let date = match date {
+        Some(date) => parse_date(date)?,
+        None => chrono::Local::now().date_naive(),
+    };
+    let path = entry_path(vault_path, date);
+
+    let _lock = vault_lock::acquire(vault_path)?;
+    let created = !path.exists();
+    if created {
+        fs::create_dir_all(journal_dir(vault_path))
+            .map_err(|e| BouldyError::io("Failed to create journal folder", e))?;
+        fsutil::write_atomic(&path, new_entry(settings, date, locale))
+            .map_err(|e| BouldyError::io("Failed to create journal entry", e))?;
+    }
+
+    Ok(JournalEntry {
+        path: path.to_string_lossy().to_string(),
+        date: date.format(DATE_FORMAT).to_string(),
+        created,
+    })
+}
+
+/// Record mood and energy (1-5, `None` clears) in the entry's frontmatter,
+/// creating the entry if needed.
+pub fn set_mood(
+    vault_path: &str,
+    settings: &JournalSettings,
+    date: &str,
+    mood: Option<u8>,
+    energy: Option<u8>,
+    locale: &LocaleInfo,
+) -> Result<JournalDay, BouldyError> {
+    validate_rating("Mood", mood)?;
+    validate_rating("Energy", energy)?;
+    let entry = open_entry(vault_path, settings, Some(date), locale)?;
+    let date = parse_date(&entry.date)?;
+
+    let _lock = vault_lock::acquire(vault_path)?;
+    let content = fs::read_to_string(&entry.path)
+        .map_err(|e| BouldyError::io("Failed to read journal entry", e))?;
+    let rating = |value: Option<u8>| value.map(|v| v.to_string()).unwrap_or_default();
+    let content = markdown::set_frontmatter_field(&content, "mood", &rating(mood));
+    let content = markdown::set_frontmatter_field(&content, "energy", &rating(energy));
+    fsutil::write_atomic(&PathBuf::from(&entry.path), &content)
+        .map_err(|e| BouldyError::io("Failed to write journal entry", e))?;
+
+    Ok(read_day(date, &content))
+}
+
+pub fn get_streak(vault_path: &str) -> Result<JournalStreak, BouldyError> {
+    let written: Vec<NaiveDate> = read_days(vault_path)?
+        .into_values()
+        .filter(JournalDay::is_written)
+        .filter_map(|day| NaiveDate::parse_from_str(&day.date, DATE_FORMAT).ok())
+        .collect();
+
+    let mut streak = JournalStreak {
+        total: written.len(),
+        ..Default::default()
+    };
+
+    let mut run = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for &date in &written {
+        run = match previous {
+            Some(previous) if date - previous == Duration::days(1) => run + 1,
+            _ => 1,
+        };
+        streak.longest = streak.longest.max(run);
+        previous = Some(date);
+    }
+
+    // The run ending today, or yesterday while today can still be written
+    let today = chrono::Local::now().date_naive();
+    streak.written_today = written.last() == Some(&today);
+    if let Some(&last) = written.last() {
+        if last == today || last == today - Duration::days(1) {
+            streak.current = run;
+        }
+    }
+
+    Ok(streak)
+}
+
+/// The entries written in `month` (`YYYY-MM`), with their mood and energy.
+pub fn get_calendar(vault_path: &str, month: &str) -> Result<Vec<JournalDay>, BouldyError> {
+    let first =
+        NaiveDate::parse_from_str(&format!("{}-01", month.trim()), DATE_FORMAT).map_err(|_| {
+            BouldyError::invalid_input(format!("Invalid month '{}'. Expected YYYY-MM", month))
+        })?;
+
+    Ok(read_days(vault_path)?
+        .into_iter()
+        .filter(|(date, day)| {
+            date.year() == first.year() && date.month() == first.month() && day.is_written()
+        })
+        .map(|(_, day)| day)
+        .collect())
+}
+
+/// Written entries from `from` to `to` (inclusive) with their text.
+pub fn entries_between(
+    vault_path: &str,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Vec<(JournalDay, String)> {
+    from.iter_days()
+        .take_while(|date| *date <= to)
+        .filter_map(|date| {
+            let content = fs::read_to_string(entry_path(vault_path, date)).ok()?;
+            let day = read_day(date, &content);
+            let (_, body) = markdown::split_frontmatter(&content);
+            day.is_written().then(|| (day, body.to_string()))
+        })
+        .collect()
+}
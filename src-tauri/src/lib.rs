@@ -1,22 +1,98 @@
+use error::BouldyError;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Mutex;
-use tauri::{AppHandle, Emitter, Manager};
-use tauri_plugin_store::StoreExt;
-
-mod todos;
+use tauri::{AppHandle, Emitter, Listener, Manager};
+use vault::Note;
+
+mod agenda;
+mod attachments;
+mod audio_memo;
+mod automation;
+mod badge;
+mod boards;
+mod bookmarks;
+mod breaks;
+mod caldav;
+mod calendar;
+mod canvas;
+mod capture;
+mod clipboard;
+mod contexts;
+mod deeplink;
+mod diagnostics;
+mod disk_usage;
+mod duplicates;
+mod embeds;
+pub mod error;
+mod file_drop;
+mod flashcards;
+mod focus_plan;
+mod focus_report;
+mod fsutil;
+mod habits;
+mod html_markdown;
+mod ics;
+mod inbox;
+mod index;
+mod integrity;
+mod journal;
+mod llm;
+mod local_api;
+mod local_server;
+mod locale;
+mod logseq_import;
+mod mail_capture;
+mod markdown;
+mod migration;
+mod network;
+mod note_ai;
+mod note_cache;
+mod note_diff;
+mod note_history;
+mod notifications;
+mod plugins;
+mod pomodoro;
+mod prompt_estimate;
+mod prompt_palette;
+mod prompt_share;
+mod prompt_tests;
+mod read_later;
+mod reminders;
+mod remote_tasks;
+mod rollover;
+mod secrets;
+mod session;
+mod settings;
+mod shortcuts;
+mod shutdown;
+mod site_export;
+mod snippets;
+mod someday;
+mod startup_metrics;
+mod switcher;
+mod task_import;
+mod task_sync;
+mod taskwarrior;
+mod text;
+mod timesheet;
+mod todo_export;
+mod todo_store;
+pub mod todos;
+mod updates;
+pub mod vault;
+mod vault_config;
+mod vault_discovery;
+pub mod vault_lock;
+mod waiting;
 mod watcher;
-
-#[derive(Serialize, Deserialize)]
-struct Note {
-    path: String,
-    name: String,
-    title: String,
-    modified: u64,
-    is_symlink: bool,
-}
+mod web_clipper;
+mod weekly_review;
+mod widget;
+mod windows;
+mod writing_stats;
 
 #[derive(Serialize, Deserialize)]
 struct NoteMetadata {
@@ -45,6 +121,31 @@ struct PromptStats {
     last_used: Option<u64>,
     #[serde(rename = "useCount")]
     use_count: u64,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    favorite: bool,
+    // 1 to 5 stars
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rating: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tests: Option<Vec<prompt_tests::PromptTestCase>>,
+}
+
+const MAX_PROMPT_RATING: u8 = 5;
+
+// How list_prompts orders prompts. Favorites always come first.
+#[derive(Deserialize, Clone, Copy, Debug, Default)]
+enum PromptSort {
+    // Most recently used, then by title
+    #[default]
+    #[serde(rename = "recent")]
+    Recent,
+    // Highest rated, unrated last
+    #[serde(rename = "rating")]
+    Rating,
+    #[serde(rename = "uses")]
+    Uses,
+    #[serde(rename = "title")]
+    Title,
 }
 
 // What React sends when creating/updating a prompt
@@ -59,6 +160,21 @@ struct PromptInput {
     variables: Vec<String>,
 }
 
+// Everything in Prompt except the content, for list views
+#[derive(Serialize, Deserialize, Clone)]
+struct PromptSummary {
+    id: String,
+    title: String,
+    tags: Vec<String>,
+    category: Option<String>,
+    variables: Vec<String>,
+    last_used: Option<u64>,
+    use_count: u64,
+    favorite: bool,
+    rating: Option<u8>,
+    path: String,
+}
+
 // What React sees - combined view
 #[derive(Serialize, Deserialize, Clone)]
 struct Prompt {
@@ -70,236 +186,646 @@ struct Prompt {
     variables: Vec<String>,
     last_used: Option<u64>,
     use_count: u64,
+    favorite: bool,
+    rating: Option<u8>,
+    tests: Vec<prompt_tests::PromptTestCase>,
     created: u64,
     modified: u64,
     path: String,
 }
 
 #[tauri::command]
-async fn select_vault_folder(app: tauri::AppHandle) -> Result<String, String> {
+async fn select_vault_folder(app: tauri::AppHandle) -> Result<String, BouldyError> {
     use tauri_plugin_dialog::DialogExt;
 
     let folder = app.dialog().file().blocking_pick_folder();
 
     match folder {
         Some(path) => Ok(path.to_string()),
-        None => Err("No folder selected".to_string()),
+        None => Err(BouldyError::cancelled("No folder selected")),
     }
 }
 
+// Likely vaults to offer on first run, before the user picks a folder
+#[tauri::command]
+async fn discover_vaults() -> Result<Vec<vault_discovery::VaultCandidate>, BouldyError> {
+    blocking(|| Ok(vault_discovery::discover_vaults())).await
+}
+
 #[tauri::command]
-async fn save_vault_path(app: tauri::AppHandle, path: String) -> Result<(), String> {
-    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+async fn save_vault_path(app: tauri::AppHandle, path: String) -> Result<(), BouldyError> {
+    blocking(move || {
+        // Note windows belong to the vault they were opened from
+        if settings::load_settings(&app)?.vault_path.as_deref() != Some(path.as_str()) {
+            windows::close_note_windows(&app);
+        }
 
-    store.set("vaultPath", path);
-    store.save().map_err(|e| e.to_string())?;
+        settings::update_settings(
+            &app,
+            settings::SettingsPatch {
+                vault_path: Some(path),
+                ..Default::default()
+            },
+        )?;
 
-    Ok(())
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+fn get_vault_path(app: tauri::AppHandle) -> Result<Option<String>, BouldyError> {
+    Ok(settings::load_settings(&app)?.vault_path)
 }
 
 #[tauri::command]
-fn get_vault_path(app: tauri::AppHandle) -> Result<Option<String>, String> {
-    let store = app.store("settings.json").map_err(|e| e.to_string())?;
+fn get_settings(app: AppHandle) -> Result<settings::Settings, BouldyError> {
+    settings::load_settings(&app)
+}
+
+#[tauri::command]
+async fn update_settings(
+    app: AppHandle,
+    patch: settings::SettingsPatch,
+) -> Result<settings::Settings, BouldyError> {
+    blocking(move || {
+        let updated = settings::update_settings(&app, patch)?;
 
-    Ok(store
-        .get("vaultPath")
-        .and_then(|v| v.as_str().map(String::from)))
+        let _ = app.emit("settings:changed", updated.clone());
+
+        Ok(updated)
+    })
+    .await
 }
 
+// Week start, date order and names for the locale setting (or the system's)
 #[tauri::command]
-async fn check_vault_exists(path: String) -> Result<bool, String> {
-    Ok(std::path::Path::new(&path).exists())
+fn get_locale_info(app: AppHandle) -> Result<locale::LocaleInfo, BouldyError> {
+    Ok(settings::load_settings(&app)?.locale_info())
 }
 
-fn validate_path_in_vault(vault_path: &str, file_path: &str) -> Result<PathBuf, String> {
-    let vault = Path::new(vault_path)
-        .canonicalize()
-        .map_err(|e| format!("Invalid vault path: {}", e))?;
+// Where the user left off in a vault, restored when switching back to it
+#[tauri::command]
+fn load_session_state(
+    app: AppHandle,
+    vault_path: String,
+) -> Result<session::SessionState, BouldyError> {
+    session::load(&app, &vault_path)
+}
 
-    let file_path_buf = Path::new(file_path);
+#[tauri::command]
+fn save_session_state(
+    app: AppHandle,
+    vault_path: String,
+    state: session::SessionState,
+) -> Result<(), BouldyError> {
+    session::save(&app, &vault_path, &state)
+}
 
-    // Check if the path is a symlink
-    // For symlinks, validate the symlink location itself, not where it points
-    let file = if file_path_buf.is_symlink() {
-        // For symlinks, check that the symlink itself is within the vault
-        let parent = file_path_buf
-            .parent()
-            .ok_or("Invalid file path")?
-            .canonicalize()
-            .map_err(|e| format!("Invalid parent path: {}", e))?;
+// Passed by the OS when the app is launched at login, so setup() can tell an
+// autostart launch apart from the user opening the app
+const AUTOSTART_ARG: &str = "--autostart";
 
-        if !parent.starts_with(&vault) {
-            return Err("Path is outside vault".to_string());
-        }
+#[tauri::command]
+fn get_autostart_enabled(app: AppHandle) -> Result<bool, BouldyError> {
+    use tauri_plugin_autostart::ManagerExt;
 
-        // Return the original path (the symlink), not the resolved target
-        file_path_buf.to_path_buf()
-    } else {
-        // For regular files, canonicalize as before
-        file_path_buf
-            .canonicalize()
-            .map_err(|e| format!("Invalid file path: {}", e))?
-    };
+    app.autolaunch()
+        .is_enabled()
+        .map_err(|e| e.to_string().into())
+}
 
-    if !file.is_symlink() && !file.starts_with(&vault) {
-        return Err("Path is outside vault".to_string());
-    }
+#[tauri::command]
+async fn set_autostart_enabled(app: AppHandle, enabled: bool) -> Result<(), BouldyError> {
+    use tauri_plugin_autostart::ManagerExt;
+
+    blocking(move || {
+        let autostart_manager = app.autolaunch();
+        if enabled {
+            autostart_manager.enable().map_err(|e| e.to_string())?;
+        } else {
+            autostart_manager.disable().map_err(|e| e.to_string())?;
+        }
 
-    Ok(file)
+        settings::update_settings(
+            &app,
+            settings::SettingsPatch {
+                autostart_enabled: Some(enabled),
+                ..Default::default()
+            },
+        )?;
+
+        Ok(())
+    })
+    .await
 }
 
-fn extract_title_from_filename(path: &Path) -> String {
-    // Extract title from filename (without .md extension)
-    path.file_stem()
-        .and_then(|s| s.to_str())
-        .map(|s| s.to_string())
-        .unwrap_or_else(|| "Untitled".to_string())
+#[tauri::command]
+fn get_network_config(app: AppHandle) -> Result<network::NetworkConfig, BouldyError> {
+    Ok(settings::load_settings(&app)?.network)
 }
 
 #[tauri::command]
-async fn list_vault_files(vault_path: String) -> Result<Vec<Note>, String> {
-    let vault = Path::new(&vault_path);
-    let notes_dir = vault.join("notes");
+async fn set_network_config(
+    app: AppHandle,
+    config: network::NetworkConfig,
+) -> Result<network::NetworkConfig, BouldyError> {
+    blocking(move || {
+        let updated = settings::update_settings(
+            &app,
+            settings::SettingsPatch {
+                network: Some(config),
+                ..Default::default()
+            },
+        )?;
+
+        Ok(updated.network)
+    })
+    .await
+}
 
-    // Use notes/ folder if it exists, otherwise fall back to vault root
-    let read_dir = if notes_dir.exists() {
-        &notes_dir
-    } else {
-        vault
-    };
+#[tauri::command]
+async fn test_network_connection(app: AppHandle, url: String) -> Result<u16, BouldyError> {
+    let config = settings::load_settings(&app)?.network;
+    network::test_connection(&config, &url).await
+}
 
-    if !read_dir.exists() {
-        return Err("Notes directory does not exist".to_string());
-    }
+// Filesystem work runs on the blocking pool so slow disks or large vaults
+// don't stall the async runtime every other command shares
+async fn blocking<T, F>(f: F) -> Result<T, BouldyError>
+where
+    F: FnOnce() -> Result<T, BouldyError> + Send + 'static,
+    T: Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(f)
+        .await
+        .map_err(|e| format!("Background task failed: {}", e))?
+}
 
-    let mut notes = Vec::new();
+// For commands that mix network requests with vault or keyring access: the
+// whole command runs on the blocking pool, driving its requests from there
+async fn blocking_async<T, F, Fut>(f: F) -> Result<T, BouldyError>
+where
+    F: FnOnce() -> Fut + Send + 'static,
+    Fut: std::future::Future<Output = Result<T, BouldyError>>,
+    T: Send + 'static,
+{
+    blocking(move || tauri::async_runtime::block_on(f())).await
+}
 
-    let entries =
-        fs::read_dir(read_dir).map_err(|e| format!("Failed to read notes directory: {}", e))?;
+#[tauri::command]
+async fn check_vault_exists(path: String) -> Result<bool, BouldyError> {
+    blocking(move || Ok(Path::new(&path).exists())).await
+}
 
-    for entry in entries {
-        let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
-        let path = entry.path();
+#[tauri::command]
+async fn list_vault_files(
+    app: AppHandle,
+    vault_path: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<vault::Page<Note>, BouldyError> {
+    blocking(move || {
+        let started = std::time::Instant::now();
+
+        // The index only exists once the watcher is running on this vault
+        let page = app
+            .state::<index::IndexState>()
+            .with_vault(&vault_path, |index| index.list_notes_page(offset, limit))
+            .unwrap_or_else(|| {
+                app.state::<note_cache::NoteCache>()
+                    .list(&vault_path)
+                    .map(|notes| vault::Page::from_vec(notes, offset, limit))
+            })?;
+
+        startup_metrics::record(
+            &app,
+            startup_metrics::Phase::FirstListing,
+            started.elapsed(),
+        );
+        Ok(page)
+    })
+    .await
+}
 
-        if path.extension().and_then(|s| s.to_str()) == Some("md") {
-            // Try to get metadata - if it fails (broken symlink), skip this file
-            let metadata = match fs::metadata(&path) {
-                Ok(m) => m,
-                Err(e) => {
-                    eprintln!("Warning: Skipping {} - {}", path.display(), e);
-                    continue;
-                }
-            };
+#[tauri::command]
+async fn resync_note_list(app: AppHandle, vault_path: String) -> Result<(), BouldyError> {
+    blocking(move || {
+        let notes = app
+            .state::<index::IndexState>()
+            .with_vault(&vault_path, |index| {
+                index.sync_all()?;
+                index.list_notes()
+            })
+            .unwrap_or_else(|| vault::list_notes(&vault_path))?;
+
+        let payload = notes
+            .into_iter()
+            .map(|note| watcher::NoteEventPayload {
+                path: note.path,
+                name: note.name,
+                title: Some(note.title),
+                modified: Some(note.modified),
+            })
+            .collect();
+
+        watcher::emit_note_list_updated(&app, payload);
+        Ok(())
+    })
+    .await
+}
 
-            let modified = match metadata.modified() {
-                Ok(m) => m.duration_since(std::time::UNIX_EPOCH).unwrap().as_secs(),
-                Err(e) => {
-                    eprintln!("Warning: Skipping {} - {}", path.display(), e);
-                    continue;
-                }
-            };
+#[tauri::command]
+async fn search_notes(
+    app: AppHandle,
+    vault_path: String,
+    query: String,
+) -> Result<Vec<vault::SearchMatch>, BouldyError> {
+    blocking(move || {
+        app.state::<index::IndexState>()
+            .with_vault(&vault_path, |index| index.search(&query))
+            .unwrap_or_else(|| vault::search_notes(&vault_path, &query))
+    })
+    .await
+}
 
-            let title = extract_title_from_filename(&path);
-            let is_symlink = path.is_symlink();
+#[tauri::command]
+async fn get_backlinks(
+    app: AppHandle,
+    vault_path: String,
+    note_path: String,
+) -> Result<Vec<Note>, BouldyError> {
+    blocking(move || {
+        app.state::<index::IndexState>()
+            .with_vault(&vault_path, |index| index.backlinks(Path::new(&note_path)))
+            .unwrap_or_else(|| Err("Vault is still being indexed".into()))
+    })
+    .await
+}
 
-            notes.push(Note {
+// Titles win over aliases, so a new note can take over a renamed one's old name
+#[tauri::command]
+async fn resolve_note(
+    app: AppHandle,
+    name: String,
+) -> Result<Option<vault::ResolvedNote>, BouldyError> {
+    blocking(move || {
+        let vault_path = settings::require_vault_path(&app)?;
+        let name = vault::link_target_name(&name);
+        if name.is_empty() {
+            return Ok(None);
+        }
+        if let Some(path) = vault::find_note_by_title(&vault_path, name) {
+            return Ok(Some(vault::ResolvedNote {
+                title: vault::extract_title_from_filename(&path),
                 path: path.to_string_lossy().to_string(),
-                name: path.file_name().unwrap().to_string_lossy().to_string(),
-                title,
-                modified,
-                is_symlink,
-            });
+                alias: None,
+            }));
         }
-    }
 
-    // Sort by modified time (newest first)
-    notes.sort_by(|a, b| b.modified.cmp(&a.modified));
+        let path = match app
+            .state::<index::IndexState>()
+            .with_vault(&vault_path, |index| index.find_by_alias(name))
+        {
+            Some(found) => found?.map(PathBuf::from),
+            None => vault::find_note_by_alias(&vault_path, name),
+        };
+        Ok(path.map(|path| vault::ResolvedNote {
+            title: vault::extract_title_from_filename(&path),
+            path: path.to_string_lossy().to_string(),
+            alias: Some(name.to_string()),
+        }))
+    })
+    .await
+}
 
-    Ok(notes)
+#[tauri::command]
+async fn find_duplicate_notes(
+    vault_path: String,
+) -> Result<Vec<duplicates::DuplicateCluster>, BouldyError> {
+    blocking(move || duplicates::find_duplicates(&vault_path)).await
 }
 
 #[tauri::command]
-async fn read_note(path: String) -> Result<NoteMetadata, String> {
-    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read note: {}", e))?;
+async fn find_orphaned_attachments(
+    vault_path: String,
+) -> Result<attachments::OrphanReport, BouldyError> {
+    blocking(move || attachments::find_orphaned(&vault_path)).await
+}
 
-    let path_obj = Path::new(&path);
-    let title = extract_title_from_filename(path_obj);
+#[tauri::command]
+async fn find_broken_attachments(
+    vault_path: String,
+    path: String,
+) -> Result<Vec<String>, BouldyError> {
+    blocking(move || {
+        let note = vault::validate_path_in_vault(&vault_path, &path)?;
+        attachments::broken_attachments(&vault_path, &note)
+    })
+    .await
+}
 
-    Ok(NoteMetadata { title, content })
+#[tauri::command]
+async fn delete_orphaned_attachments(
+    vault_path: String,
+    paths: Vec<String>,
+) -> Result<attachments::CleanupReport, BouldyError> {
+    blocking(move || attachments::delete_orphaned(&vault_path, &paths)).await
 }
 
 #[tauri::command]
-async fn write_note(
+async fn get_vault_disk_usage(vault_path: String) -> Result<disk_usage::DiskUsage, BouldyError> {
+    blocking(move || disk_usage::get_disk_usage(&vault_path)).await
+}
+
+#[tauri::command]
+async fn verify_vault_integrity(
+    vault_path: String,
+) -> Result<integrity::IntegrityReport, BouldyError> {
+    blocking(move || integrity::verify(&vault_path)).await
+}
+
+#[tauri::command]
+async fn accept_integrity_changes(
+    vault_path: String,
+    paths: Vec<String>,
+) -> Result<(), BouldyError> {
+    blocking(move || integrity::accept(&vault_path, &paths)).await
+}
+
+#[tauri::command]
+fn get_index_status(state: tauri::State<index::IndexState>) -> index::IndexStatus {
+    state.status()
+}
+
+#[tauri::command]
+async fn fuzzy_find(
+    app: AppHandle,
+    vault_path: String,
+    query: String,
+    scope: Option<switcher::SwitcherScope>,
+) -> Result<Vec<switcher::SwitcherMatch>, BouldyError> {
+    blocking(move || {
+        Ok(app.state::<switcher::QuickSwitcher>().find(
+            &app.state::<index::IndexState>(),
+            &vault_path,
+            &query,
+            scope.unwrap_or(switcher::SwitcherScope::All),
+        ))
+    })
+    .await
+}
+
+#[tauri::command]
+async fn read_note(app: AppHandle, path: String) -> Result<NoteMetadata, BouldyError> {
+    blocking(move || {
+        let vault_path = settings::require_vault_path(&app)?;
+        let file = vault::validate_path_in_vault(&vault_path, &path)?;
+
+        let content =
+            fs::read_to_string(&file).map_err(|e| BouldyError::io("Failed to read note", e))?;
+        let title = vault::extract_title_from_filename(&file);
+
+        Ok(NoteMetadata { title, content })
+    })
+    .await
+}
+
+#[tauri::command]
+async fn read_note_metadata(
     app: AppHandle,
     path: String,
-    content: String,
-    title: String,
-) -> Result<Note, String> {
-    fs::write(&path, &content).map_err(|e| format!("Failed to write note: {}", e))?;
+) -> Result<vault::NoteHeader, BouldyError> {
+    blocking(move || {
+        let vault_path = settings::require_vault_path(&app)?;
+        let path = vault::validate_path_in_vault(&vault_path, &path)?;
+        vault::read_note_header(&path)
+    })
+    .await
+}
 
-    let metadata = fs::metadata(&path).map_err(|e| format!("Failed to read metadata: {}", e))?;
+#[tauri::command]
+async fn get_note_outline(
+    app: AppHandle,
+    path: String,
+) -> Result<Vec<markdown::OutlineHeading>, BouldyError> {
+    blocking(move || {
+        let vault_path = settings::require_vault_path(&app)?;
+        let file = vault::validate_path_in_vault(&vault_path, &path)?;
+        let content =
+            fs::read_to_string(&file).map_err(|e| BouldyError::io("Failed to read note", e))?;
+        Ok(markdown::outline(&content))
+    })
+    .await
+}
 
-    let modified = metadata
-        .modified()
-        .map_err(|e| format!("Failed to get modified time: {}", e))?
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_secs();
+#[tauri::command]
+async fn resolve_embeds(
+    app: AppHandle,
+    path: String,
+    depth: Option<usize>,
+) -> Result<String, BouldyError> {
+    blocking(move || {
+        let vault_path = settings::require_vault_path(&app)?;
+        let file = vault::validate_path_in_vault(&vault_path, &path)?;
+        embeds::resolve_embeds(&vault_path, &file, depth.unwrap_or(embeds::DEFAULT_DEPTH))
+    })
+    .await
+}
 
-    let path_obj = Path::new(&path);
-    let is_symlink = path_obj.is_symlink();
+#[tauri::command]
+async fn get_writing_stats(
+    app: AppHandle,
+    range: writing_stats::StatsRange,
+) -> Result<writing_stats::WritingStats, BouldyError> {
+    blocking(move || {
+        let settings = settings::load_settings(&app)?;
+        let week_start = settings.locale_info().week_start.weekday();
+        let vault_path = settings.vault_path.ok_or("No vault configured")?;
+        writing_stats::get_stats(&vault_path, &settings.writing_goals, range, week_start)
+    })
+    .await
+}
 
-    let note = Note {
-        path: path.clone(),
-        name: path_obj.file_name().unwrap().to_string_lossy().to_string(),
-        title: title.clone(),
-        modified,
-        is_symlink,
-    };
+#[tauri::command]
+async fn diff_notes(
+    app: AppHandle,
+    path_a: String,
+    path_b: String,
+) -> Result<note_diff::NoteDiff, BouldyError> {
+    blocking(move || {
+        let vault_path = settings::require_vault_path(&app)?;
+        let read = |path: &str| {
+            let file = vault::validate_path_in_vault(&vault_path, path)?;
+            fs::read_to_string(&file).map_err(|e| BouldyError::io("Failed to read note", e))
+        };
+        Ok(note_diff::diff_text(&read(&path_a)?, &read(&path_b)?))
+    })
+    .await
+}
 
-    // Emit event after successful save
-    let _ = app.emit(
-        "note:saved",
-        watcher::NoteEventPayload {
-            path: path.clone(),
-            name: note.name.clone(),
-            title: Some(title),
-            modified: Some(modified),
-        },
-    );
+#[tauri::command]
+async fn list_note_versions(
+    app: AppHandle,
+    path: String,
+) -> Result<Vec<note_history::NoteVersion>, BouldyError> {
+    blocking(move || {
+        let vault_path = settings::require_vault_path(&app)?;
+        let file = vault::validate_path_in_vault(&vault_path, &path)?;
+        note_history::list_versions(&vault_path, &file)
+    })
+    .await
+}
 
-    Ok(note)
+#[tauri::command]
+async fn diff_note_version(
+    app: AppHandle,
+    path: String,
+    version: String,
+) -> Result<note_diff::NoteDiff, BouldyError> {
+    blocking(move || {
+        let vault_path = settings::require_vault_path(&app)?;
+        let file = vault::validate_path_in_vault(&vault_path, &path)?;
+        let old = note_history::read_version(&vault_path, &file, &version)?;
+        let current =
+            fs::read_to_string(&file).map_err(|e| BouldyError::io("Failed to read note", e))?;
+        Ok(note_diff::diff_text(&old, &current))
+    })
+    .await
 }
 
 #[tauri::command]
-async fn delete_note(app: AppHandle, vault_path: String, path: String) -> Result<(), String> {
-    // Validate path is within vault
-    validate_path_in_vault(&vault_path, &path)?;
+async fn write_note(
+    app: AppHandle,
+    window: tauri::WebviewWindow,
+    path: String,
+    content: String,
+    title: String,
+) -> Result<Note, BouldyError> {
+    save_note(app, window.label().to_string(), path, content, title).await
+}
+
+// `source` names who saved: a window label, or the local API
+async fn save_note(
+    app: AppHandle,
+    source: String,
+    path: String,
+    content: String,
+    title: String,
+) -> Result<Note, BouldyError> {
+    blocking(move || {
+        // The webview only knows note paths, so check against the configured vault
+        let vault_path = settings::require_vault_path(&app)?;
+        let file = vault::validate_path_in_vault(&vault_path, &path)?;
+
+        // Existing notes keep whatever name they have; new ones must be
+        // creatable on every platform
+        if !file.exists() {
+            vault::validate_file_stem(&vault::extract_title_from_filename(&file))?;
+        }
 
-    let path_obj = Path::new(&path);
-    let name = path_obj
-        .file_name()
-        .map(|n| n.to_string_lossy().to_string())
-        .unwrap_or_default();
+        let _lock = vault_lock::acquire(&vault_path)?;
+        // Keep what's being overwritten so it can be compared against later
+        let previous = fs::read_to_string(&file).ok();
+        if let Some(ref previous) = previous {
+            if *previous != content {
+                if let Err(e) = note_history::record(&vault_path, &file, previous) {
+                    tracing::warn!("Failed to save version of {} - {}", path, e);
+                }
+            }
+        }
+        if let Err(e) = writing_stats::record_save(
+            &vault_path,
+            previous.as_deref().unwrap_or_default(),
+            &content,
+        ) {
+            tracing::warn!("Failed to update writing stats - {}", e);
+        }
+        fsutil::write_text_preserving(&file, &content)
+            .map_err(|e| BouldyError::io("Failed to write note", e))?;
+        // Cached under the path the listing handed out, not the canonical one
+        app.state::<note_cache::NoteCache>()
+            .invalidate(Path::new(&path));
+        if let Err(e) = integrity::record(&vault_path, &file) {
+            tracing::warn!("Failed to update vault manifest - {}", e);
+        }
+
+        let metadata =
+            fs::metadata(&file).map_err(|e| BouldyError::io("Failed to read metadata", e))?;
 
-    fs::remove_file(&path).map_err(|e| format!("Failed to delete note: {}", e))?;
+        let modified = vault::unix_secs(
+            metadata
+                .modified()
+                .map_err(|e| BouldyError::io("Failed to get modified time", e))?,
+        );
 
-    // Emit event after successful deletion
-    let _ = app.emit(
-        "note:deleted",
-        watcher::NoteEventPayload {
+        let is_symlink = file.is_symlink();
+
+        let note = Note {
             path: path.clone(),
-            name,
-            title: None,
-            modified: None,
-        },
-    );
+            name: vault::file_name_to_string(&file)?,
+            title: title.clone(),
+            modified,
+            is_symlink,
+        };
+
+        // Emit event after successful save
+        let _ = app.emit(
+            "note:saved",
+            watcher::NoteSavedPayload {
+                note: watcher::NoteEventPayload {
+                    path: path.clone(),
+                    name: note.name.clone(),
+                    title: Some(title),
+                    modified: Some(modified),
+                },
+                window: source,
+            },
+        );
+        plugins::notify(&app, "note_saved", serde_json::json!({ "path": path }));
+        automation::note_saved(&app, &vault_path, &path);
+
+        Ok(note)
+    })
+    .await
+}
 
-    Ok(())
+#[tauri::command]
+async fn delete_note(app: AppHandle, path: String) -> Result<(), BouldyError> {
+    blocking(move || {
+        // The webview only knows note paths, so check against the configured vault
+        let vault_path = settings::require_vault_path(&app)?;
+        let file = vault::validate_path_in_vault(&vault_path, &path)?;
+
+        let name = file
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let _lock = vault_lock::acquire(&vault_path)?;
+        fs::remove_file(fsutil::long_path(&file))
+            .map_err(|e| BouldyError::io("Failed to delete note", e))?;
+        app.state::<note_cache::NoteCache>()
+            .invalidate(Path::new(&path));
+
+        // Emit event after successful deletion
+        let _ = app.emit(
+            "note:deleted",
+            watcher::NoteEventPayload {
+                path: path.clone(),
+                name,
+                title: None,
+                modified: None,
+            },
+        );
+
+        Ok(())
+    })
+    .await
 }
 
 #[tauri::command]
-async fn pick_markdown_file(app: tauri::AppHandle) -> Result<Option<String>, String> {
+async fn pick_markdown_file(app: tauri::AppHandle) -> Result<Option<String>, BouldyError> {
     use tauri_plugin_dialog::DialogExt;
 
     let file_path = app
@@ -313,7 +839,7 @@ async fn pick_markdown_file(app: tauri::AppHandle) -> Result<Option<String>, Str
             if let Some(path_ref) = path.as_path() {
                 Ok(Some(path_ref.to_string_lossy().to_string()))
             } else {
-                Err("Failed to get file path".to_string())
+                Err("Failed to get file path".into())
             }
         }
         None => Ok(None),
@@ -326,63 +852,106 @@ async fn import_note(
     source_path: String,
     import_type: String,
     app: tauri::AppHandle,
-) -> Result<String, String> {
-    use std::path::Path;
-    use std::fs;
-
-    let source = Path::new(&source_path);
-    if !source.exists() {
-        return Err("Source file does not exist".to_string());
-    }
-
-    // Get filename from source
-    let filename = source
-        .file_name()
-        .ok_or("Invalid filename")?
-        .to_str()
-        .ok_or("Invalid UTF-8 in filename")?;
+) -> Result<String, BouldyError> {
+    blocking(move || {
+        use std::fs;
+        use std::path::Path;
+
+        let source = Path::new(&source_path);
+        if !source.exists() {
+            return Err("Source file does not exist".into());
+        }
 
-    let dest_path = Path::new(&vault_path).join("notes").join(filename);
+        // Get filename from source
+        let filename = vault::file_name_to_string(source)?;
 
-    // Check if file already exists
-    if dest_path.exists() {
-        return Err(format!("Note '{}' already exists in vault", filename));
-    }
+        let dest_path = Path::new(&vault_path).join("notes").join(&filename);
 
-    match import_type.as_str() {
-        "copy" => {
-            fs::copy(&source, &dest_path)
-                .map_err(|e| format!("Failed to copy file: {}", e))?;
+        // Check if file (or one differing only in case/normalization) already exists
+        let title = vault::extract_title_from_filename(&dest_path);
+        vault::validate_file_stem(&title)?;
+        if dest_path.exists() || vault::find_note_by_title(&vault_path, &title).is_some() {
+            return Err(BouldyError::conflict(format!(
+                "Note '{}' already exists in vault",
+                filename
+            )));
         }
-        "symlink" => {
-            #[cfg(unix)]
-            {
-                std::os::unix::fs::symlink(&source, &dest_path)
-                    .map_err(|e| format!("Failed to create symlink: {}", e))?;
+
+        match import_type.as_str() {
+            "copy" => {
+                fs::copy(source, &dest_path)
+                    .map_err(|e| BouldyError::io("Failed to copy file", e))?;
             }
-            #[cfg(windows)]
-            {
-                std::os::windows::fs::symlink_file(&source, &dest_path)
-                    .map_err(|e| {
+            "symlink" => {
+                #[cfg(unix)]
+                {
+                    std::os::unix::fs::symlink(source, &dest_path)
+                        .map_err(|e| BouldyError::io("Failed to create symlink", e))?;
+                }
+                #[cfg(windows)]
+                {
+                    std::os::windows::fs::symlink_file(source, &dest_path).map_err(|e| {
                         format!(
                             "Failed to create symlink: {}. Try running as administrator.",
                             e
                         )
                     })?;
+                }
+            }
+            _ => {
+                return Err(BouldyError::invalid_input(
+                    "Invalid import type. Use 'copy' or 'symlink'",
+                ))
             }
         }
-        _ => return Err("Invalid import type. Use 'copy' or 'symlink'".to_string()),
-    }
 
-    // Emit event to refresh notes list
-    app.emit("note:list-updated", ()).ok();
+        // Emit event to refresh notes list
+        app.emit("note:list-updated", ()).ok();
+
+        vault::path_to_string(&dest_path)
+    })
+    .await
+}
+
+fn handle_file_drop(app: &AppHandle, paths: &[PathBuf]) -> Result<(), BouldyError> {
+    let app_settings = settings::load_settings(app)?;
+    let vault_path = app_settings
+        .vault_path
+        .ok_or_else(|| "No vault configured".to_string())?;
+
+    // Written by the webview, so only trusted once it's a note in the vault
+    let target_note = app_settings
+        .last_opened_note_path
+        .filter(|path| {
+            vault::validate_path_in_vault(&vault_path, path).is_ok_and(|file| file.is_file())
+        })
+        .map(PathBuf::from);
+    let report = file_drop::import_dropped_files(&vault_path, paths, target_note.as_deref())?;
+
+    let _ = app.emit("import:completed", report);
 
-    Ok(dest_path.to_string_lossy().to_string())
+    Ok(())
+}
+
+#[tauri::command]
+async fn load_todos(
+    app: AppHandle,
+    vault_path: String,
+) -> Result<Vec<todos::TodoItem>, BouldyError> {
+    blocking(move || app.state::<todo_store::TodoStore>().load(&vault_path)).await
 }
 
 #[tauri::command]
-async fn load_todos(vault_path: String) -> Result<Vec<todos::TodoItem>, String> {
-    todos::load_todos(&vault_path)
+async fn load_todos_by_due(
+    app: AppHandle,
+    vault_path: String,
+) -> Result<Vec<todos::TodoItem>, BouldyError> {
+    blocking(move || {
+        let mut todos = app.state::<todo_store::TodoStore>().load(&vault_path)?;
+        todos::sort_by_due(&mut todos);
+        Ok(todos)
+    })
+    .await
 }
 
 #[tauri::command]
@@ -394,27 +963,59 @@ async fn create_todo(
     priority: Option<String>,
     projects: Vec<String>,
     contexts: Vec<String>,
-) -> Result<todos::TodoItem, String> {
-    let mut todos_list = todos::load_todos(&vault_path)?;
-
-    let new_todo = todos::TodoItem {
-        id: todos_list.len() + 1, // Use line number as ID
-        title,
-        completed: false,
-        due_date,
-        priority,
-        projects,
-        contexts,
-        created_date: Some(chrono::Local::now().format("%Y-%m-%d").to_string()),
-    };
+) -> Result<todos::TodoItem, BouldyError> {
+    let due_date = todos::normalize_due_date(due_date)?;
+
+    blocking(move || {
+        let new_todo = app
+            .state::<todo_store::TodoStore>()
+            .mutate(&vault_path, |todos_list| {
+                let new_todo = todos::TodoItem {
+                    id: 0,
+                    title,
+                    completed: false,
+                    due_date,
+                    priority,
+                    projects,
+                    contexts,
+                    created_date: Some(chrono::Local::now().format("%Y-%m-%d").to_string()),
+                    uid: None,
+                    parent: None,
+                    delegated: None,
+                    estimate: None,
+                    description: None,
+                };
+
+                Ok(todos::push(todos_list, new_todo))
+            })?;
+
+        // Emit event for external change detection
+        let _ = app.emit("todos_changed", ());
+
+        Ok(new_todo)
+    })
+    .await
+}
 
-    todos_list.push(new_todo.clone());
-    todos::save_todos(&vault_path, &todos_list)?;
+// Apply `f` to one todo through the store and notify listeners
+fn update_todo_with(
+    app: &AppHandle,
+    vault_path: &str,
+    id: usize,
+    f: impl FnOnce(&mut todos::TodoItem),
+) -> Result<todos::TodoItem, BouldyError> {
+    let result = app
+        .state::<todo_store::TodoStore>()
+        .mutate(vault_path, |todos_list| {
+            let todo = todos::find_todo_mut(todos_list, id)
+                .ok_or_else(|| BouldyError::not_found(format!("Todo not found: {}", id)))?;
+            f(todo);
+            Ok(todo.clone())
+        })?;
 
-    // Emit event for external change detection
     let _ = app.emit("todos_changed", ());
 
-    Ok(new_todo)
+    Ok(result)
 }
 
 #[tauri::command]
@@ -423,31 +1024,24 @@ async fn update_todo(
     vault_path: String,
     id: usize,
     title: String,
-) -> Result<todos::TodoItem, String> {
-    let mut todos_list = todos::load_todos(&vault_path)?;
-
-    let todo = todos::find_todo_mut(&mut todos_list, id)
-        .ok_or_else(|| format!("Todo not found: {}", id))?;
-
-    todo.title = title;
-    let result = todo.clone();
-
-    todos::save_todos(&vault_path, &todos_list)?;
-    let _ = app.emit("todos_changed", ());
-
-    Ok(result)
+) -> Result<todos::TodoItem, BouldyError> {
+    blocking(move || update_todo_with(&app, &vault_path, id, |todo| todo.title = title)).await
 }
 
 #[tauri::command]
-async fn delete_todo(app: AppHandle, vault_path: String, id: usize) -> Result<(), String> {
-    let mut todos_list = todos::load_todos(&vault_path)?;
+async fn delete_todo(app: AppHandle, vault_path: String, id: usize) -> Result<(), BouldyError> {
+    blocking(move || {
+        app.state::<todo_store::TodoStore>()
+            .mutate(&vault_path, |todos_list| {
+                todos_list.retain(|t| t.id != id);
+                Ok(())
+            })?;
 
-    todos_list.retain(|t| t.id != id);
-
-    todos::save_todos(&vault_path, &todos_list)?;
-    let _ = app.emit("todos_changed", ());
+        let _ = app.emit("todos_changed", ());
 
-    Ok(())
+        Ok(())
+    })
+    .await
 }
 
 #[tauri::command]
@@ -455,19 +1049,18 @@ async fn toggle_todo(
     app: AppHandle,
     vault_path: String,
     id: usize,
-) -> Result<todos::TodoItem, String> {
-    let mut todos_list = todos::load_todos(&vault_path)?;
-
-    let todo = todos::find_todo_mut(&mut todos_list, id)
-        .ok_or_else(|| format!("Todo not found: {}", id))?;
-
-    todo.completed = !todo.completed;
-    let result = todo.clone();
-
-    todos::save_todos(&vault_path, &todos_list)?;
-    let _ = app.emit("todos_changed", ());
-
-    Ok(result)
+) -> Result<todos::TodoItem, BouldyError> {
+    blocking(move || {
+        let todo = update_todo_with(&app, &vault_path, id, |todo| {
+            todo.completed = !todo.completed
+        })?;
+        if todo.completed {
+            plugins::notify(&app, "todo_completed", serde_json::json!({ "todo": todo }));
+            automation::todo_completed(&app, &vault_path, &todo);
+        }
+        Ok(todo)
+    })
+    .await
 }
 
 #[tauri::command]
@@ -476,19 +1069,14 @@ async fn update_todo_due_date(
     vault_path: String,
     id: usize,
     due_date: Option<String>,
-) -> Result<todos::TodoItem, String> {
-    let mut todos_list = todos::load_todos(&vault_path)?;
-
-    let todo = todos::find_todo_mut(&mut todos_list, id)
-        .ok_or_else(|| format!("Todo not found: {}", id))?;
-
-    todo.due_date = due_date;
-    let result = todo.clone();
-
-    todos::save_todos(&vault_path, &todos_list)?;
-    let _ = app.emit("todos_changed", ());
-
-    Ok(result)
+) -> Result<todos::TodoItem, BouldyError> {
+    // Typed dates ("tomorrow", "12/03") are read the user's way
+    let locale = settings::load_settings(&app)?.locale_info();
+    let due_date = due_date
+        .map(|due| locale.parse_due(&due, todos::local_now().date()))
+        .transpose()?;
+
+    blocking(move || update_todo_with(&app, &vault_path, id, |todo| todo.due_date = due_date)).await
 }
 
 #[tauri::command]
@@ -499,21 +1087,174 @@ async fn update_todo_metadata(
     priority: Option<String>,
     projects: Vec<String>,
     contexts: Vec<String>,
-) -> Result<todos::TodoItem, String> {
-    let mut todos_list = todos::load_todos(&vault_path)?;
+) -> Result<todos::TodoItem, BouldyError> {
+    blocking(move || {
+        update_todo_with(&app, &vault_path, id, |todo| {
+            todo.priority = priority;
+            todo.projects = projects;
+            todo.contexts = contexts;
+        })
+    })
+    .await
+}
 
-    let todo = todos::find_todo_mut(&mut todos_list, id)
-        .ok_or_else(|| format!("Todo not found: {}", id))?;
+#[tauri::command]
+async fn set_todo_description(
+    app: AppHandle,
+    vault_path: String,
+    id: usize,
+    text: String,
+) -> Result<todos::TodoItem, BouldyError> {
+    blocking(move || {
+        update_todo_with(&app, &vault_path, id, |todo| {
+            todo.description = todos::clean_description(&text)
+        })
+    })
+    .await
+}
 
-    todo.priority = priority;
-    todo.projects = projects;
-    todo.contexts = contexts;
-    let result = todo.clone();
+#[tauri::command]
+async fn set_todo_delegate(
+    app: AppHandle,
+    vault_path: String,
+    id: usize,
+    person: Option<String>,
+) -> Result<todos::TodoItem, BouldyError> {
+    // Stored as a single `delegated:` word
+    let person = person
+        .map(|person| person.split_whitespace().collect::<Vec<_>>().join("_"))
+        .filter(|person| !person.is_empty());
+    blocking(move || update_todo_with(&app, &vault_path, id, |todo| todo.delegated = person)).await
+}
 
-    todos::save_todos(&vault_path, &todos_list)?;
-    let _ = app.emit("todos_changed", ());
+#[tauri::command]
+async fn set_todo_estimate(
+    app: AppHandle,
+    vault_path: String,
+    id: usize,
+    pomodoros: Option<u32>,
+) -> Result<todos::TodoItem, BouldyError> {
+    let estimate = pomodoros.filter(|&pomodoros| pomodoros > 0);
+    blocking(move || update_todo_with(&app, &vault_path, id, |todo| todo.estimate = estimate)).await
+}
 
-    Ok(result)
+#[tauri::command]
+async fn list_waiting_for(
+    app: AppHandle,
+    vault_path: String,
+) -> Result<Vec<waiting::WaitingGroup>, BouldyError> {
+    blocking(move || {
+        let todos = app.state::<todo_store::TodoStore>().load(&vault_path)?;
+        waiting::list_waiting_for(&vault_path, &todos)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn list_someday(vault_path: String) -> Result<Vec<todos::TodoItem>, BouldyError> {
+    blocking(move || someday::list_someday(&vault_path)).await
+}
+
+#[tauri::command]
+async fn move_to_someday(
+    app: AppHandle,
+    vault_path: String,
+    id: usize,
+) -> Result<todos::TodoItem, BouldyError> {
+    blocking(move || {
+        let todo =
+            someday::move_to_someday(&app.state::<todo_store::TodoStore>(), &vault_path, id)?;
+        let _ = app.emit("todos_changed", ());
+        let _ = app.emit("someday_changed", ());
+        Ok(todo)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn activate_someday(
+    app: AppHandle,
+    vault_path: String,
+    id: usize,
+) -> Result<todos::TodoItem, BouldyError> {
+    blocking(move || {
+        let todo =
+            someday::activate_someday(&app.state::<todo_store::TodoStore>(), &vault_path, id)?;
+        let _ = app.emit("todos_changed", ());
+        let _ = app.emit("someday_changed", ());
+        Ok(todo)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn get_review_queue(
+    vault_path: String,
+    weeks: Option<u32>,
+) -> Result<Vec<someday::ReviewItem>, BouldyError> {
+    blocking(move || {
+        someday::review_queue(&vault_path, weeks.unwrap_or(someday::DEFAULT_REVIEW_WEEKS))
+    })
+    .await
+}
+
+#[tauri::command]
+async fn mark_someday_reviewed(vault_path: String, ids: Vec<usize>) -> Result<(), BouldyError> {
+    blocking(move || someday::mark_reviewed(&vault_path, &ids)).await
+}
+
+#[tauri::command]
+async fn get_todos_for_context(
+    app: AppHandle,
+    vault_path: String,
+    context: String,
+) -> Result<Vec<todos::TodoItem>, BouldyError> {
+    blocking(move || {
+        let todos = app.state::<todo_store::TodoStore>().load(&vault_path)?;
+        Ok(contexts::todos_for_context(&todos, &context))
+    })
+    .await
+}
+
+#[tauri::command]
+async fn list_todo_contexts(
+    app: AppHandle,
+    vault_path: String,
+) -> Result<Vec<String>, BouldyError> {
+    blocking(move || {
+        let todos = app.state::<todo_store::TodoStore>().load(&vault_path)?;
+        Ok(contexts::list_contexts(&todos))
+    })
+    .await
+}
+
+#[tauri::command]
+async fn set_current_context(app: AppHandle, context: Option<String>) -> Result<(), BouldyError> {
+    blocking(move || {
+        let mut context_settings = settings::load_settings(&app)?.contexts;
+        context_settings.current = context
+            .map(|context| contexts::normalize(&context))
+            .filter(|context| !context.is_empty());
+
+        settings::update_settings(
+            &app,
+            settings::SettingsPatch {
+                contexts: Some(context_settings),
+                ..Default::default()
+            },
+        )?;
+
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+async fn apply_rollover(
+    app: AppHandle,
+    vault_path: String,
+) -> Result<rollover::RolloverSummary, BouldyError> {
+    blocking(move || rollover::run(&app, &vault_path)).await
 }
 
 #[tauri::command]
@@ -522,185 +1263,1419 @@ async fn reorder_todo(
     vault_path: String,
     old_index: usize,
     new_index: usize,
-) -> Result<(), String> {
-    todos::reorder_todo(&vault_path, old_index, new_index)?;
-    let _ = app.emit("todos_changed", ());
+) -> Result<(), BouldyError> {
+    blocking(move || {
+        app.state::<todo_store::TodoStore>()
+            .with_file(&vault_path, || {
+                todos::reorder_todo(&vault_path, old_index, new_index)
+            })?;
+        let _ = app.emit("todos_changed", ());
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+async fn get_todo_stats(vault_path: String) -> Result<todos::TodoStats, BouldyError> {
+    blocking(move || {
+        let metadata = todos::load_metadata(&vault_path)?;
+        Ok(metadata.stats)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn get_todo_metadata(vault_path: String) -> Result<todos::TodoMetadata, BouldyError> {
+    blocking(move || todos::load_metadata(&vault_path)).await
+}
+
+#[tauri::command]
+async fn set_daily_limit(vault_path: String, limit: usize) -> Result<(), BouldyError> {
+    blocking(move || {
+        let _lock = vault_lock::acquire(&vault_path)?;
+        let mut metadata = todos::load_metadata(&vault_path)?;
+        metadata.daily_limit = limit;
+        todos::save_metadata(&vault_path, &metadata)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn archive_completed_todos(app: AppHandle, vault_path: String) -> Result<usize, BouldyError> {
+    blocking(move || {
+        let count = app
+            .state::<todo_store::TodoStore>()
+            .with_file(&vault_path, || todos::archive_completed_todos(&vault_path))?;
+        let _ = app.emit("todos_changed", ());
+        Ok(count)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn load_archived_todos(
+    vault_path: String,
+    month: String,
+) -> Result<Vec<todos::ArchivedTodo>, BouldyError> {
+    blocking(move || todos::load_archived_todos(&vault_path, &month)).await
+}
+
+#[tauri::command]
+async fn list_archive_months(vault_path: String) -> Result<Vec<String>, BouldyError> {
+    blocking(move || todos::list_archive_months(&vault_path)).await
+}
+
+#[tauri::command]
+async fn list_projects(app: AppHandle, vault_path: String) -> Result<Vec<String>, BouldyError> {
+    blocking(move || {
+        let todos = app.state::<todo_store::TodoStore>().load(&vault_path)?;
+
+        let projects: std::collections::HashSet<String> = todos
+            .iter()
+            .flat_map(|t| t.projects.iter().cloned())
+            .collect();
+
+        let mut project_list: Vec<String> = projects.into_iter().collect();
+        project_list.sort();
+
+        Ok(project_list)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn list_contexts(app: AppHandle, vault_path: String) -> Result<Vec<String>, BouldyError> {
+    blocking(move || {
+        let todos = app.state::<todo_store::TodoStore>().load(&vault_path)?;
+
+        let contexts: std::collections::HashSet<String> = todos
+            .iter()
+            .flat_map(|t| t.contexts.iter().cloned())
+            .collect();
+
+        let mut context_list: Vec<String> = contexts.into_iter().collect();
+        context_list.sort();
+
+        Ok(context_list)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn list_priorities(app: AppHandle, vault_path: String) -> Result<Vec<String>, BouldyError> {
+    blocking(move || {
+        let todos = app.state::<todo_store::TodoStore>().load(&vault_path)?;
+
+        let priorities: std::collections::HashSet<String> =
+            todos.iter().filter_map(|t| t.priority.clone()).collect();
+
+        let mut priority_list: Vec<String> = priorities.into_iter().collect();
+        priority_list.sort();
+
+        Ok(priority_list)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn bulk_update_due_dates(
+    app: AppHandle,
+    vault_path: String,
+    updates: Vec<(usize, Option<String>)>,
+) -> Result<(), BouldyError> {
+    let updates = updates
+        .into_iter()
+        .map(|(id, due_date)| Ok((id, todos::normalize_due_date(due_date)?)))
+        .collect::<Result<Vec<_>, BouldyError>>()?;
+
+    blocking(move || {
+        app.state::<todo_store::TodoStore>()
+            .with_file(&vault_path, || {
+                todos::bulk_update_due_dates(&vault_path, updates)
+            })?;
+        let _ = app.emit("todos_changed", ());
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+async fn quick_capture_note(app: AppHandle, text: String) -> Result<String, BouldyError> {
+    blocking(move || {
+        let vault_path = settings::require_vault_path(&app)?;
+        let inbox_path = capture::capture_note(&vault_path, &text)?;
+
+        Ok(inbox_path.to_string_lossy().to_string())
+    })
+    .await
+}
+
+#[tauri::command]
+async fn quick_capture_todo(app: AppHandle, text: String) -> Result<todos::TodoItem, BouldyError> {
+    blocking(move || {
+        let vault_path = settings::require_vault_path(&app)?;
+        let todo = capture::capture_todo(&app, &vault_path, &text)?;
+
+        let _ = app.emit("todos_changed", ());
+
+        Ok(todo)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn capture_to_inbox(app: AppHandle, text: String) -> Result<inbox::InboxItem, BouldyError> {
+    blocking(move || {
+        let vault_path = settings::require_vault_path(&app)?;
+        let item = inbox::capture(&vault_path, &text)?;
+
+        let _ = app.emit("inbox_changed", ());
+
+        Ok(item)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn list_inbox(vault_path: String) -> Result<Vec<inbox::InboxItem>, BouldyError> {
+    blocking(move || inbox::list_inbox(&vault_path)).await
+}
+
+#[tauri::command]
+async fn process_inbox_item(
+    app: AppHandle,
+    vault_path: String,
+    id: usize,
+    action: inbox::InboxAction,
+) -> Result<inbox::Processed, BouldyError> {
+    blocking(move || {
+        let processed = inbox::process_item(
+            &app.state::<todo_store::TodoStore>(),
+            &vault_path,
+            id,
+            action,
+            &settings::load_settings(&app)?.locale_info(),
+        )?;
+        if matches!(processed, inbox::Processed::Todo { .. }) {
+            let _ = app.emit("todos_changed", ());
+        }
+        let _ = app.emit("inbox_changed", ());
+        Ok(processed)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn capture_url(
+    app: AppHandle,
+    url: String,
+) -> Result<read_later::ReadLaterSaved, BouldyError> {
+    blocking_async(move || async move {
+        let app_settings = settings::load_settings(&app)?;
+        let vault_path = settings::require_vault_path(&app)?;
+        let saved = read_later::capture_url(&app_settings.network, &vault_path, &url).await?;
+
+        let _ = app.emit("read-later:saved", &saved);
+
+        Ok(saved)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn get_web_clipper_token() -> Result<String, BouldyError> {
+    blocking(web_clipper::token).await
+}
+
+#[tauri::command]
+async fn reset_web_clipper_token() -> Result<String, BouldyError> {
+    blocking(web_clipper::reset_token).await
+}
+
+#[tauri::command]
+async fn set_web_clipper_enabled(
+    app: AppHandle,
+    enabled: bool,
+    port: Option<u16>,
+) -> Result<(), BouldyError> {
+    let updated = settings::update_settings(
+        &app,
+        settings::SettingsPatch {
+            web_clipper_enabled: Some(enabled),
+            web_clipper_port: port,
+            ..Default::default()
+        },
+    )?;
+
+    blocking(move || {
+        if enabled {
+            web_clipper::start(&app, updated.web_clipper_port)?;
+        } else {
+            web_clipper::stop(&app);
+        }
+
+        let _ = app.emit("web-clipper:toggled", enabled);
+
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+async fn get_api_token() -> Result<String, BouldyError> {
+    blocking(local_api::token).await
+}
+
+#[tauri::command]
+async fn reset_api_token() -> Result<String, BouldyError> {
+    blocking(local_api::reset_token).await
+}
+
+#[tauri::command]
+async fn check_for_updates(app: AppHandle) -> Result<Option<updates::UpdateInfo>, BouldyError> {
+    updates::check(&app).await
+}
+
+#[tauri::command]
+fn set_update_channel(app: AppHandle, channel: updates::UpdateChannel) -> Result<(), BouldyError> {
+    settings::update_settings(
+        &app,
+        settings::SettingsPatch {
+            update_channel: Some(channel),
+            ..Default::default()
+        },
+    )?;
+    updates::clear_pending(&app);
     Ok(())
 }
 
 #[tauri::command]
-async fn get_todo_stats(vault_path: String) -> Result<todos::TodoStats, String> {
-    let metadata = todos::load_metadata(&vault_path)?;
-    Ok(metadata.stats)
+async fn install_update_and_restart(app: AppHandle) -> Result<(), BouldyError> {
+    updates::install_and_restart(&app).await
 }
 
 #[tauri::command]
-async fn get_todo_metadata(vault_path: String) -> Result<todos::TodoMetadata, String> {
-    todos::load_metadata(&vault_path)
+async fn set_api_server_enabled(
+    app: AppHandle,
+    enabled: bool,
+    port: Option<u16>,
+) -> Result<(), BouldyError> {
+    let updated = settings::update_settings(
+        &app,
+        settings::SettingsPatch {
+            api_server_enabled: Some(enabled),
+            api_server_port: port,
+            ..Default::default()
+        },
+    )?;
+
+    blocking(move || {
+        if enabled {
+            local_api::start(&app, updated.api_server_port)?;
+        } else {
+            local_api::stop(&app);
+        }
+
+        let _ = app.emit("api-server:toggled", enabled);
+
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+async fn show_quick_capture(app: AppHandle) -> Result<(), BouldyError> {
+    capture::show_capture_window(&app)
+}
+
+#[tauri::command]
+fn get_clipboard_capture_enabled(state: tauri::State<clipboard::ClipboardCapture>) -> bool {
+    state.enabled.load(std::sync::atomic::Ordering::Relaxed)
+}
+
+#[tauri::command]
+async fn set_clipboard_capture_enabled(app: AppHandle, enabled: bool) -> Result<(), BouldyError> {
+    blocking(move || {
+        settings::update_settings(
+            &app,
+            settings::SettingsPatch {
+                clipboard_capture_enabled: Some(enabled),
+                ..Default::default()
+            },
+        )?;
+
+        app.state::<clipboard::ClipboardCapture>()
+            .enabled
+            .store(enabled, std::sync::atomic::Ordering::Relaxed);
+
+        let _ = app.emit("clipboard:capture-toggled", enabled);
+
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+async fn send_notification(
+    app: AppHandle,
+    category: notifications::NotificationCategory,
+    title: String,
+    body: String,
+) -> Result<bool, BouldyError> {
+    blocking(move || notifications::notify(&app, category, &title, &body)).await
+}
+
+#[tauri::command]
+async fn open_deep_link(app: AppHandle, url: String) -> Result<(), BouldyError> {
+    let url = tauri::Url::parse(&url)
+        .map_err(|e| BouldyError::invalid_input(format!("Invalid link: {}", e)))?;
+    blocking(move || deeplink::handle(&app, &url)).await
+}
+
+#[tauri::command]
+fn take_launch_deep_links(state: tauri::State<deeplink::LaunchDeepLinks>) -> Vec<String> {
+    std::mem::take(&mut *state.0.lock().unwrap())
 }
 
 #[tauri::command]
-async fn set_daily_limit(vault_path: String, limit: usize) -> Result<(), String> {
-    let mut metadata = todos::load_metadata(&vault_path)?;
-    metadata.daily_limit = limit;
-    todos::save_metadata(&vault_path, &metadata)
+async fn refresh_badge(app: AppHandle, vault_path: String) -> Result<usize, BouldyError> {
+    blocking(move || badge::refresh(&app, &vault_path)).await
+}
+
+#[tauri::command]
+async fn export_todos_calendar(app: AppHandle, vault_path: String) -> Result<String, BouldyError> {
+    blocking(move || {
+        let app_settings = settings::load_settings(&app)?;
+        let output_path = match app_settings.calendar_export_path {
+            Some(path) => PathBuf::from(path),
+            None => ics::default_calendar_path(&vault_path),
+        };
+
+        let path = ics::export_calendar(&vault_path, &output_path)?;
+        Ok(path.to_string_lossy().to_string())
+    })
+    .await
 }
 
 #[tauri::command]
-async fn archive_completed_todos(
+async fn export_todos(
     app: AppHandle,
     vault_path: String,
-) -> Result<usize, String> {
-    let count = todos::archive_completed_todos(&vault_path)?;
-    let _ = app.emit("todos_changed", ());
-    Ok(count)
+    format: todo_export::ExportFormat,
+    filter: Option<todo_export::TodoFilter>,
+) -> Result<String, BouldyError> {
+    blocking(move || {
+        let todos_list = app.state::<todo_store::TodoStore>().load(&vault_path)?;
+        todo_export::export_todos(&todos_list, format, &filter.unwrap_or_default())
+    })
+    .await
 }
 
 #[tauri::command]
-async fn load_archived_todos(
+async fn export_site(
     vault_path: String,
-    month: String,
-) -> Result<Vec<todos::ArchivedTodo>, String> {
-    todos::load_archived_todos(&vault_path, &month)
+    output_dir: String,
+    options: Option<site_export::SiteOptions>,
+) -> Result<site_export::SiteReport, BouldyError> {
+    blocking(move || {
+        site_export::export_site(&vault_path, &output_dir, &options.unwrap_or_default())
+    })
+    .await
 }
 
 #[tauri::command]
-async fn list_archive_months(vault_path: String) -> Result<Vec<String>, String> {
-    todos::list_archive_months(&vault_path)
+async fn set_secret(
+    app: AppHandle,
+    key: secrets::SecretKey,
+    value: String,
+) -> Result<(), BouldyError> {
+    blocking(move || {
+        let (service, account) = key.locate(&settings::load_settings(&app)?)?;
+        secrets::set(service, &account, &value)
+    })
+    .await
 }
 
 #[tauri::command]
-async fn list_projects(vault_path: String) -> Result<Vec<String>, String> {
-    let todos = todos::load_todos(&vault_path)?;
+async fn get_secret(
+    app: AppHandle,
+    key: secrets::SecretKey,
+) -> Result<Option<String>, BouldyError> {
+    blocking(move || {
+        let (service, account) = key.locate(&settings::load_settings(&app)?)?;
+        secrets::get(service, &account)
+    })
+    .await
+}
 
-    let projects: std::collections::HashSet<String> = todos
-        .iter()
-        .flat_map(|t| t.projects.iter().cloned())
-        .collect();
+#[tauri::command]
+async fn delete_secret(app: AppHandle, key: secrets::SecretKey) -> Result<(), BouldyError> {
+    blocking(move || {
+        let (service, account) = key.locate(&settings::load_settings(&app)?)?;
+        secrets::delete(service, &account)
+    })
+    .await
+}
 
-    let mut project_list: Vec<String> = projects.into_iter().collect();
-    project_list.sort();
+#[tauri::command]
+async fn sync_caldav(
+    app: AppHandle,
+    vault_path: String,
+) -> Result<caldav::CaldavSyncReport, BouldyError> {
+    blocking_async(move || async move {
+        let app_settings = settings::load_settings(&app)?;
+        let report = caldav::sync(
+            &app.state::<todo_store::TodoStore>(),
+            &vault_path,
+            &app_settings.caldav,
+            &app_settings.network,
+        )
+        .await?;
+
+        if report.pulled > 0 || report.removed > 0 {
+            let _ = app.emit("todos_changed", ());
+        }
 
-    Ok(project_list)
+        Ok(report)
+    })
+    .await
 }
 
 #[tauri::command]
-async fn list_contexts(vault_path: String) -> Result<Vec<String>, String> {
-    let todos = todos::load_todos(&vault_path)?;
+async fn authorize_remote_tasks(app: AppHandle) -> Result<(), BouldyError> {
+    use tauri_plugin_opener::OpenerExt;
+
+    blocking_async(move || async move {
+        let app_settings = settings::load_settings(&app)?;
+        remote_tasks::authorize(&app_settings.remote_tasks, &app_settings.network, |url| {
+            app.opener()
+                .open_url(url, None::<&str>)
+                .map_err(|e| format!("Failed to open browser: {}", e).into())
+        })
+        .await
+    })
+    .await
+}
 
-    let contexts: std::collections::HashSet<String> = todos
-        .iter()
-        .flat_map(|t| t.contexts.iter().cloned())
-        .collect();
+#[tauri::command]
+async fn sign_out_remote_tasks(app: AppHandle) -> Result<(), BouldyError> {
+    let app_settings = settings::load_settings(&app)?;
+    blocking(move || remote_tasks::sign_out(&app_settings.remote_tasks)).await
+}
 
-    let mut context_list: Vec<String> = contexts.into_iter().collect();
-    context_list.sort();
+#[tauri::command]
+async fn list_remote_task_lists(
+    app: AppHandle,
+) -> Result<Vec<remote_tasks::RemoteList>, BouldyError> {
+    blocking_async(move || async move {
+        let app_settings = settings::load_settings(&app)?;
+        remote_tasks::list_remote_lists(&app_settings.remote_tasks, &app_settings.network).await
+    })
+    .await
+}
 
-    Ok(context_list)
+#[tauri::command]
+async fn sync_remote_tasks(
+    app: AppHandle,
+    vault_path: String,
+) -> Result<remote_tasks::RemoteSyncReport, BouldyError> {
+    blocking_async(move || async move { remote_tasks::sync_and_notify(&app, &vault_path).await })
+        .await
+}
+
+#[tauri::command]
+async fn fetch_mail_captures(
+    app: AppHandle,
+    vault_path: String,
+) -> Result<mail_capture::MailCaptureReport, BouldyError> {
+    blocking(move || mail_capture::fetch_and_notify(&app, &vault_path)).await
+}
+
+#[tauri::command]
+async fn summarize_note(app: AppHandle, path: String) -> Result<String, BouldyError> {
+    blocking_async(move || async move {
+        let app_settings = settings::load_settings(&app)?;
+        let vault_path = settings::require_vault_path(&app)?;
+        let file = vault::validate_path_in_vault(&vault_path, &path)?;
+        note_ai::summarize_note(&app_settings.network, &app_settings.llm, &file).await
+    })
+    .await
+}
+
+#[tauri::command]
+async fn suggest_tags(app: AppHandle, path: String) -> Result<Vec<String>, BouldyError> {
+    blocking_async(move || async move {
+        let app_settings = settings::load_settings(&app)?;
+        let vault_path = settings::require_vault_path(&app)?;
+        let file = vault::validate_path_in_vault(&vault_path, &path)?;
+        note_ai::suggest_tags(&app_settings.network, &app_settings.llm, &vault_path, &file).await
+    })
+    .await
+}
+
+#[tauri::command]
+async fn accept_note_summary(
+    app: AppHandle,
+    path: String,
+    summary: String,
+) -> Result<(), BouldyError> {
+    blocking(move || {
+        let vault_path = settings::require_vault_path(&app)?;
+        let file = vault::validate_path_in_vault(&vault_path, &path)?;
+        note_ai::accept_summary(&vault_path, &file, &summary)?;
+        app.state::<note_cache::NoteCache>().invalidate(&file);
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+async fn accept_note_tags(
+    app: AppHandle,
+    path: String,
+    tags: Vec<String>,
+) -> Result<(), BouldyError> {
+    blocking(move || {
+        let vault_path = settings::require_vault_path(&app)?;
+        let file = vault::validate_path_in_vault(&vault_path, &path)?;
+        note_ai::accept_tags(&vault_path, &file, &tags)?;
+        app.state::<note_cache::NoteCache>().invalidate(&file);
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+async fn start_audio_memo(
+    app: AppHandle,
+    vault_path: String,
+    note_path: Option<String>,
+) -> Result<String, BouldyError> {
+    blocking(move || {
+        let note = note_path
+            .map(|path| vault::validate_path_in_vault(&vault_path, &path))
+            .transpose()?;
+        app.state::<audio_memo::AudioRecorder>()
+            .start(&vault_path, note.as_deref())
+    })
+    .await
+}
+
+#[tauri::command]
+async fn stop_audio_memo(
+    app: AppHandle,
+    vault_path: String,
+    transcribe: bool,
+    note_path: Option<String>,
+) -> Result<audio_memo::AudioMemo, BouldyError> {
+    let state = app.clone();
+    let (audio, duration_seconds) =
+        blocking(move || state.state::<audio_memo::AudioRecorder>().stop()).await?;
+    let note = note_path
+        .map(|path| vault::validate_path_in_vault(&vault_path, &path))
+        .transpose()?;
+
+    // A failed transcription still keeps the recording and its link
+    let transcript = if transcribe {
+        let app_settings = settings::load_settings(&app)?;
+        match audio_memo::transcribe(&app_settings.network, &app_settings.transcription, &audio)
+            .await
+        {
+            Ok(text) => Some(text),
+            Err(e) => {
+                tracing::warn!("Failed to transcribe audio memo - {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    if let Some(ref note) = note {
+        let (vault, note, audio, text) = (
+            vault_path.clone(),
+            note.clone(),
+            audio.clone(),
+            transcript.clone(),
+        );
+        let cache = app.clone();
+        blocking(move || {
+            audio_memo::append_to_note(&vault, &note, &audio, text.as_deref())?;
+            cache.state::<note_cache::NoteCache>().invalidate(&note);
+            Ok(())
+        })
+        .await?;
+    }
+
+    Ok(audio_memo::AudioMemo {
+        path: audio.to_string_lossy().to_string(),
+        duration_seconds,
+        transcript,
+        note_path: note.map(|note| note.to_string_lossy().to_string()),
+    })
+}
+
+#[tauri::command]
+async fn open_journal_entry(
+    app: AppHandle,
+    vault_path: String,
+    date: Option<String>,
+) -> Result<journal::JournalEntry, BouldyError> {
+    let app_settings = settings::load_settings(&app)?;
+    let locale = app_settings.locale_info();
+    blocking(move || {
+        journal::open_entry(&vault_path, &app_settings.journal, date.as_deref(), &locale)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn set_journal_mood(
+    app: AppHandle,
+    vault_path: String,
+    date: String,
+    mood: Option<u8>,
+    energy: Option<u8>,
+) -> Result<journal::JournalDay, BouldyError> {
+    let app_settings = settings::load_settings(&app)?;
+    let locale = app_settings.locale_info();
+    blocking(move || {
+        journal::set_mood(
+            &vault_path,
+            &app_settings.journal,
+            &date,
+            mood,
+            energy,
+            &locale,
+        )
+    })
+    .await
+}
+
+#[tauri::command]
+async fn get_journal_streak(vault_path: String) -> Result<journal::JournalStreak, BouldyError> {
+    blocking(move || journal::get_streak(&vault_path)).await
+}
+
+#[tauri::command]
+async fn get_journal_calendar(
+    vault_path: String,
+    month: String,
+) -> Result<Vec<journal::JournalDay>, BouldyError> {
+    blocking(move || journal::get_calendar(&vault_path, &month)).await
+}
+
+#[tauri::command]
+async fn generate_weekly_review(
+    app: AppHandle,
+    vault_path: String,
+    week: Option<String>,
+) -> Result<weekly_review::WeeklyReview, BouldyError> {
+    blocking(move || {
+        weekly_review::generate(
+            &app.state::<todo_store::TodoStore>(),
+            &vault_path,
+            week.as_deref(),
+            &settings::load_settings(&app)?.locale_info(),
+        )
+    })
+    .await
+}
+
+#[tauri::command]
+async fn get_calendar(
+    app: AppHandle,
+    vault_path: String,
+    month: String,
+) -> Result<Vec<calendar::CalendarDay>, BouldyError> {
+    blocking(move || {
+        calendar::get_calendar(&app.state::<todo_store::TodoStore>(), &vault_path, &month)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn list_habits(vault_path: String) -> Result<Vec<habits::Habit>, BouldyError> {
+    blocking(move || habits::list_habits(&vault_path)).await
+}
+
+#[tauri::command]
+async fn add_habit(
+    app: AppHandle,
+    vault_path: String,
+    name: String,
+    goal: Option<String>,
+) -> Result<habits::Habit, BouldyError> {
+    blocking(move || {
+        let habit = habits::add_habit(&vault_path, &name, goal.as_deref())?;
+
+        let _ = app.emit("habits_changed", ());
+
+        Ok(habit)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn check_in(
+    app: AppHandle,
+    vault_path: String,
+    habit: String,
+    date: String,
+    done: Option<bool>,
+) -> Result<habits::HabitStats, BouldyError> {
+    blocking(move || {
+        let week_start = settings::load_settings(&app)?
+            .locale_info()
+            .week_start
+            .weekday();
+        let stats = habits::check_in(&vault_path, &habit, &date, done.unwrap_or(true), week_start)?;
+
+        let _ = app.emit("habits_changed", ());
+
+        Ok(stats)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn get_habit_stats(
+    app: AppHandle,
+    vault_path: String,
+) -> Result<Vec<habits::HabitStats>, BouldyError> {
+    blocking(move || {
+        let week_start = settings::load_settings(&app)?
+            .locale_info()
+            .week_start
+            .weekday();
+        habits::get_habit_stats(&vault_path, week_start)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn start_tracking(
+    app: AppHandle,
+    vault_path: String,
+    label_or_todo_id: String,
+) -> Result<timesheet::ActiveTimer, BouldyError> {
+    blocking(move || {
+        let timer = timesheet::start_tracking(
+            &app.state::<todo_store::TodoStore>(),
+            &vault_path,
+            &label_or_todo_id,
+        )?;
+
+        let _ = app.emit("tracking_changed", Some(&timer));
+
+        Ok(timer)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn stop_tracking(
+    app: AppHandle,
+    vault_path: String,
+) -> Result<timesheet::ActiveTimer, BouldyError> {
+    blocking(move || {
+        let timer = timesheet::stop_tracking(&vault_path)?;
+
+        let _ = app.emit("tracking_changed", None::<&timesheet::ActiveTimer>);
+
+        Ok(timer)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn get_tracking_status(
+    vault_path: String,
+) -> Result<Option<timesheet::ActiveTimer>, BouldyError> {
+    blocking(move || timesheet::tracking_status(&vault_path)).await
+}
+
+#[tauri::command]
+async fn get_time_report(
+    app: AppHandle,
+    vault_path: String,
+    range: timesheet::TimeRange,
+    group_by: timesheet::GroupBy,
+) -> Result<timesheet::TimeReport, BouldyError> {
+    blocking(move || {
+        let week_start = settings::load_settings(&app)?
+            .locale_info()
+            .week_start
+            .weekday();
+        timesheet::time_report(&vault_path, &range, group_by, week_start)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn list_boards(vault_path: String) -> Result<Vec<boards::BoardSummary>, BouldyError> {
+    blocking(move || boards::list_boards(&vault_path)).await
+}
+
+#[tauri::command]
+async fn get_board(vault_path: String, name: String) -> Result<boards::Board, BouldyError> {
+    blocking(move || boards::get_board(&vault_path, &name)).await
+}
+
+#[tauri::command]
+async fn create_board(
+    app: AppHandle,
+    vault_path: String,
+    name: String,
+    columns: Option<Vec<String>>,
+) -> Result<boards::Board, BouldyError> {
+    blocking(move || {
+        let board = boards::create_board(&vault_path, &name, columns)?;
+
+        let _ = app.emit("boards_changed", &board.path);
+
+        Ok(board)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn list_canvases(vault_path: String) -> Result<Vec<canvas::CanvasSummary>, BouldyError> {
+    blocking(move || canvas::list_canvases(&vault_path)).await
+}
+
+#[tauri::command]
+async fn read_canvas(vault_path: String, name: String) -> Result<canvas::CanvasFile, BouldyError> {
+    blocking(move || canvas::read_canvas(&vault_path, &name)).await
+}
+
+#[tauri::command]
+async fn write_canvas(
+    app: AppHandle,
+    vault_path: String,
+    name: String,
+    canvas: canvas::Canvas,
+) -> Result<canvas::CanvasFile, BouldyError> {
+    blocking(move || {
+        let file = canvas::write_canvas(&vault_path, &name, canvas)?;
+
+        let _ = app.emit("canvas_changed", &file.path);
+
+        Ok(file)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn move_card(
+    app: AppHandle,
+    vault_path: String,
+    board: String,
+    card_id: String,
+    to_column: String,
+    position: Option<usize>,
+) -> Result<boards::Board, BouldyError> {
+    blocking(move || {
+        let board = boards::move_card(&vault_path, &board, &card_id, &to_column, position)?;
+
+        let _ = app.emit("boards_changed", &board.path);
+
+        Ok(board)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn add_card(
+    app: AppHandle,
+    vault_path: String,
+    board: String,
+    column: String,
+    text: String,
+) -> Result<boards::Board, BouldyError> {
+    blocking(move || {
+        let board = boards::add_card(&vault_path, &board, &column, &text)?;
+
+        let _ = app.emit("boards_changed", &board.path);
+
+        Ok(board)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn add_bookmark(
+    app: AppHandle,
+    vault_path: String,
+    url: String,
+    tags: Option<Vec<String>>,
+    notes: Option<String>,
+) -> Result<bookmarks::Bookmark, BouldyError> {
+    blocking_async(move || async move {
+        let app_settings = settings::load_settings(&app)?;
+        let bookmark = bookmarks::add_bookmark(
+            &app_settings.network,
+            &vault_path,
+            &url,
+            tags.unwrap_or_default(),
+            notes,
+        )
+        .await?;
+
+        let _ = app.emit("bookmarks_changed", ());
+
+        Ok(bookmark)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn list_bookmarks(
+    vault_path: String,
+    filter: Option<String>,
+) -> Result<Vec<bookmarks::Bookmark>, BouldyError> {
+    blocking(move || bookmarks::list_bookmarks(&vault_path, filter.as_deref())).await
+}
+
+#[tauri::command]
+async fn remove_bookmark(
+    app: AppHandle,
+    vault_path: String,
+    url: String,
+) -> Result<(), BouldyError> {
+    blocking(move || {
+        bookmarks::remove_bookmark(&vault_path, &url)?;
+
+        let _ = app.emit("bookmarks_changed", ());
+
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+async fn check_bookmark_links(
+    app: AppHandle,
+    vault_path: String,
+) -> Result<bookmarks::LinkCheckReport, BouldyError> {
+    blocking_async(move || async move {
+        let app_settings = settings::load_settings(&app)?;
+        let report = bookmarks::check_links(&app_settings.network, &vault_path).await?;
+
+        let _ = app.emit("bookmarks_changed", ());
+
+        Ok(report)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn get_due_cards(
+    vault_path: String,
+    limit: Option<usize>,
+) -> Result<Vec<flashcards::Flashcard>, BouldyError> {
+    blocking(move || flashcards::get_due_cards(&vault_path, limit)).await
+}
+
+#[tauri::command]
+async fn review_card(
+    vault_path: String,
+    id: String,
+    grade: u8,
+) -> Result<flashcards::Schedule, BouldyError> {
+    blocking(move || flashcards::review_card(&vault_path, &id, grade)).await
+}
+
+#[tauri::command]
+async fn load_plugins(
+    app: AppHandle,
+    vault_path: String,
+) -> Result<Vec<plugins::PluginInfo>, BouldyError> {
+    blocking(move || app.state::<plugins::PluginHost>().load(&app, &vault_path)).await
+}
+
+#[tauri::command]
+fn list_plugins(app: AppHandle) -> Vec<plugins::PluginInfo> {
+    app.state::<plugins::PluginHost>().list()
+}
+
+#[tauri::command]
+async fn run_plugin_command(
+    app: AppHandle,
+    plugin: String,
+    command: String,
+) -> Result<(), BouldyError> {
+    blocking(move || {
+        app.state::<plugins::PluginHost>()
+            .run_command(&plugin, &command)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn load_scripts(
+    app: AppHandle,
+    vault_path: String,
+) -> Result<Vec<automation::ScriptInfo>, BouldyError> {
+    blocking(move || {
+        app.state::<automation::ScriptHost>()
+            .load(&app, &vault_path)
+    })
+    .await
+}
+
+#[tauri::command]
+fn list_scripts(app: AppHandle) -> Vec<automation::ScriptInfo> {
+    app.state::<automation::ScriptHost>().list()
+}
+
+// A `YYYY-MM-DD` date from the frontend, today if not given
+fn parse_day(date: Option<String>) -> Result<chrono::NaiveDate, BouldyError> {
+    match date {
+        Some(date) => chrono::NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").map_err(|_| {
+            BouldyError::invalid_input(format!("Invalid date '{}'. Expected YYYY-MM-DD", date))
+        }),
+        None => Ok(todos::local_now().date()),
+    }
+}
+
+#[tauri::command]
+async fn get_agenda(
+    app: AppHandle,
+    vault_path: String,
+    date: Option<String>,
+) -> Result<agenda::Agenda, BouldyError> {
+    let date = parse_day(date)?;
+
+    blocking(move || {
+        let pomodoro = settings::load_settings(&app)?.pomodoro;
+        let todos = app.state::<todo_store::TodoStore>().load(&vault_path)?;
+        agenda::get_agenda(&vault_path, &todos, &pomodoro, date)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn plan_focus_sessions(
+    app: AppHandle,
+    vault_path: String,
+    date: Option<String>,
+    sessions: Option<u32>,
+) -> Result<focus_plan::FocusPlan, BouldyError> {
+    let date = parse_day(date)?;
+
+    blocking(move || {
+        let pomodoro = settings::load_settings(&app)?.pomodoro;
+        let plan = focus_plan::plan_sessions(
+            &app.state::<todo_store::TodoStore>(),
+            &vault_path,
+            &pomodoro,
+            date,
+            sessions,
+        )?;
+        // Planned todos may have been given a uid
+        let _ = app.emit("todos_changed", ());
+        Ok(plan)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn get_focus_plan(
+    app: AppHandle,
+    vault_path: String,
+    date: Option<String>,
+) -> Result<Option<focus_plan::FocusPlan>, BouldyError> {
+    let date = parse_day(date)?;
+
+    blocking(move || {
+        let todos = app.state::<todo_store::TodoStore>().load(&vault_path)?;
+        focus_plan::get_plan(&vault_path, &todos, date)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn get_focus_report(
+    app: AppHandle,
+    vault_path: String,
+    group_by: focus_report::FocusGroupBy,
+    range: timesheet::TimeRange,
+) -> Result<focus_report::FocusReport, BouldyError> {
+    blocking(move || {
+        let todos = app.state::<todo_store::TodoStore>().load(&vault_path)?;
+        focus_report::focus_report(&vault_path, &todos, &range, group_by)
+    })
+    .await
+}
+
+#[tauri::command]
+fn get_timer_config(app: AppHandle) -> Result<pomodoro::TimerConfig, BouldyError> {
+    Ok(settings::load_settings(&app)?.pomodoro.timer)
+}
+
+#[tauri::command]
+fn set_timer_config(
+    app: AppHandle,
+    config: pomodoro::TimerConfig,
+) -> Result<pomodoro::TimerConfig, BouldyError> {
+    let mut pomodoro = settings::load_settings(&app)?.pomodoro;
+    pomodoro.timer = config;
+    let updated = settings::update_settings(
+        &app,
+        settings::SettingsPatch {
+            pomodoro: Some(pomodoro),
+            ..Default::default()
+        },
+    )?;
+
+    let _ = app.emit("pomodoro:timer-config", &updated.pomodoro.timer);
+    Ok(updated.pomodoro.timer)
+}
+
+// Sent by the main window's timer on every change, so the widget can show it
+#[tauri::command]
+fn report_timer_state(app: AppHandle, state: Option<widget::TimerReport>) {
+    app.state::<widget::TimerState>().report(state);
+}
+
+#[tauri::command]
+async fn show_widget(app: AppHandle) -> Result<(), BouldyError> {
+    widget::show_widget_window(&app)
+}
+
+#[tauri::command]
+fn get_widget_data(app: AppHandle) -> Result<widget::WidgetData, BouldyError> {
+    widget::widget_data(&app)
+}
+
+#[tauri::command]
+async fn start_break(
+    app: AppHandle,
+    vault_path: String,
+    kind: breaks::BreakKind,
+) -> Result<breaks::BreakStart, BouldyError> {
+    blocking(move || {
+        let pomodoro = settings::load_settings(&app)?.pomodoro;
+        breaks::start_break(&app, &vault_path, &pomodoro, kind)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn end_break(vault_path: String, skipped: bool) -> Result<breaks::BreakDay, BouldyError> {
+    blocking(move || breaks::end_break(&vault_path, skipped)).await
+}
+
+#[tauri::command]
+async fn get_break_stats(
+    vault_path: String,
+    days: Option<u32>,
+) -> Result<breaks::BreakStats, BouldyError> {
+    blocking(move || Ok(breaks::get_stats(&vault_path, days.unwrap_or(7)))).await
+}
+
+#[tauri::command]
+async fn read_pomodoros(vault_path: String) -> Result<String, BouldyError> {
+    blocking(move || {
+        let pomodoro_path = Path::new(&vault_path).join(".pomodoros.md");
+
+        if !pomodoro_path.exists() {
+            // Return empty string if file doesn't exist yet
+            return Ok(String::new());
+        }
+
+        fs::read_to_string(&pomodoro_path)
+            .map_err(|e| BouldyError::io("Failed to read pomodoros", e))
+    })
+    .await
+}
+
+#[tauri::command]
+async fn write_pomodoros(vault_path: String, content: String) -> Result<(), BouldyError> {
+    blocking(move || {
+        let pomodoro_path = Path::new(&vault_path).join(".pomodoros.md");
+
+        fsutil::write_atomic(&pomodoro_path, content)
+            .map_err(|e| BouldyError::io("Failed to write pomodoros", e))
+    })
+    .await
+}
+
+#[tauri::command]
+async fn migrate_vault_structure(
+    vault_path: String,
+    dry_run: Option<bool>,
+) -> Result<migration::MigrationReport, BouldyError> {
+    blocking(move || migration::migrate(&vault_path, dry_run.unwrap_or(false))).await
+}
+
+#[tauri::command]
+async fn rollback_migration(vault_path: String) -> Result<usize, BouldyError> {
+    blocking(move || migration::rollback(&vault_path)).await
+}
+
+#[tauri::command]
+async fn bulk_add_todos(
+    app: AppHandle,
+    vault_path: String,
+    text: String,
+) -> Result<task_import::BulkAdd, BouldyError> {
+    blocking(move || {
+        let added =
+            task_import::bulk_add(&app.state::<todo_store::TodoStore>(), &vault_path, &text)?;
+
+        if !added.todos.is_empty() {
+            let _ = app.emit("todos_changed", ());
+        }
+
+        Ok(added)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn import_tasks(
+    app: AppHandle,
+    vault_path: String,
+    path: String,
+    format: task_import::TaskFormat,
+    dry_run: Option<bool>,
+) -> Result<task_import::TaskImport, BouldyError> {
+    let dry_run = dry_run.unwrap_or(false);
+
+    blocking(move || {
+        let import = task_import::import_tasks(
+            &app.state::<todo_store::TodoStore>(),
+            &vault_path,
+            &path,
+            format,
+            dry_run,
+        )?;
+
+        if !dry_run && !import.todos.is_empty() {
+            let _ = app.emit("todos_changed", ());
+        }
+
+        Ok(import)
+    })
+    .await
 }
 
 #[tauri::command]
-async fn list_priorities(vault_path: String) -> Result<Vec<String>, String> {
-    let todos = todos::load_todos(&vault_path)?;
-
-    let priorities: std::collections::HashSet<String> = todos
-        .iter()
-        .filter_map(|t| t.priority.clone())
-        .collect();
+async fn import_taskwarrior(
+    app: AppHandle,
+    vault_path: String,
+    export_json: String,
+) -> Result<taskwarrior::TaskwarriorImport, BouldyError> {
+    blocking(move || {
+        let import = taskwarrior::import_taskwarrior(
+            &app.state::<todo_store::TodoStore>(),
+            &vault_path,
+            &export_json,
+        )?;
+
+        if import.added + import.updated > 0 {
+            let _ = app.emit("todos_changed", ());
+        }
 
-    let mut priority_list: Vec<String> = priorities.into_iter().collect();
-    priority_list.sort();
+        Ok(import)
+    })
+    .await
+}
 
-    Ok(priority_list)
+#[tauri::command]
+async fn export_taskwarrior(app: AppHandle, vault_path: String) -> Result<String, BouldyError> {
+    blocking(move || {
+        taskwarrior::export_taskwarrior(&app.state::<todo_store::TodoStore>(), &vault_path)
+    })
+    .await
 }
 
 #[tauri::command]
-async fn bulk_update_due_dates(
+async fn import_logseq(
     app: AppHandle,
+    graph_path: String,
     vault_path: String,
-    updates: Vec<(usize, Option<String>)>,
-) -> Result<(), String> {
-    todos::bulk_update_due_dates(&vault_path, updates)?;
-    let _ = app.emit("todos_changed", ());
-    Ok(())
+) -> Result<logseq_import::LogseqImport, BouldyError> {
+    blocking(move || {
+        let import = logseq_import::import_logseq(
+            &app.state::<todo_store::TodoStore>(),
+            &graph_path,
+            &vault_path,
+        )?;
+
+        if import.todos > 0 {
+            let _ = app.emit("todos_changed", ());
+        }
+
+        Ok(import)
+    })
+    .await
 }
 
 #[tauri::command]
-async fn read_pomodoros(vault_path: String) -> Result<String, String> {
-    let pomodoro_path = Path::new(&vault_path).join(".pomodoros.md");
-
-    if !pomodoro_path.exists() {
-        // Return empty string if file doesn't exist yet
-        return Ok(String::new());
-    }
-
-    fs::read_to_string(&pomodoro_path).map_err(|e| format!("Failed to read pomodoros: {}", e))
+fn get_vault_config(vault_path: String) -> Result<vault_config::VaultConfig, BouldyError> {
+    vault_config::load(&vault_path)
 }
 
 #[tauri::command]
-async fn write_pomodoros(vault_path: String, content: String) -> Result<(), String> {
-    let pomodoro_path = Path::new(&vault_path).join(".pomodoros.md");
-
-    fs::write(&pomodoro_path, content).map_err(|e| format!("Failed to write pomodoros: {}", e))
+fn update_vault_config(
+    vault_path: String,
+    config: vault_config::VaultConfig,
+) -> Result<vault_config::VaultConfig, BouldyError> {
+    vault_config::save(&vault_path, &config)?;
+    Ok(config)
 }
 
 #[tauri::command]
-async fn migrate_vault_structure(vault_path: String) -> Result<(), String> {
-    let vault = Path::new(&vault_path);
-    let notes_dir = vault.join("notes");
-
-    // Create notes directory if it doesn't exist
-    if !notes_dir.exists() {
-        fs::create_dir(&notes_dir)
-            .map_err(|e| format!("Failed to create notes directory: {}", e))?;
-
-        // Move all .md files from vault root to notes/
-        let entries =
-            fs::read_dir(vault).map_err(|e| format!("Failed to read vault directory: {}", e))?;
+async fn start_vault_watcher(app: AppHandle, vault_path: String) -> Result<(), BouldyError> {
+    blocking(move || {
+        let started = std::time::Instant::now();
+        let debounce_ms = settings::load_settings(&app)?.watcher_debounce_ms;
 
-        for entry in entries {
-            let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
-            let path = entry.path();
-
-            // Only move .md files
-            if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("md") {
-                let file_name = path
-                    .file_name()
-                    .ok_or_else(|| "Failed to get file name".to_string())?;
-                let dest_path = notes_dir.join(file_name);
-
-                fs::rename(&path, &dest_path).map_err(|e| format!("Failed to move file: {}", e))?;
-            }
-        }
-    }
+        // Index on a background thread; listings and search use the filesystem
+        // until index:ready
+        index::start_background(&app, &vault_path)?;
 
-    Ok(())
-}
+        // Set up file watcher
+        let debouncer = watcher::setup_watcher(app.clone(), vault_path, debounce_ms)?;
 
-#[tauri::command]
-async fn start_vault_watcher(app: AppHandle, vault_path: String) -> Result<(), String> {
-    // Set up file watcher
-    let debouncer = watcher::setup_watcher(app.clone(), vault_path)?;
+        // Store the debouncer in app state to keep it alive
+        app.manage(Mutex::new(Some(debouncer)));
 
-    // Store the debouncer in app state to keep it alive
-    app.manage(Mutex::new(Some(debouncer)));
+        startup_metrics::record(
+            &app,
+            startup_metrics::Phase::WatcherSetup,
+            started.elapsed(),
+        );
 
-    Ok(())
+        Ok(())
+    })
+    .await
 }
 
 // Prompt helper functions
 // Ensure .bouldy directory exists
-fn ensure_bouldy_dir(vault_path: &str) -> Result<PathBuf, String> {
+fn ensure_bouldy_dir(vault_path: &str) -> Result<PathBuf, BouldyError> {
     let bouldy_dir = Path::new(vault_path).join(".bouldy");
     if !bouldy_dir.exists() {
         fs::create_dir(&bouldy_dir)
-            .map_err(|e| format!("Failed to create .bouldy directory: {}", e))?;
+            .map_err(|e| BouldyError::io("Failed to create .bouldy directory", e))?;
     }
     Ok(bouldy_dir)
 }
 
+// Prompt ids come from the webview and become file names, so they must
+// not be able to name anything outside prompts/
+fn prompt_path(vault_path: &str, id: &str) -> Result<PathBuf, BouldyError> {
+    vault::validate_file_stem(id)?;
+
+    vault::validate_path_in_vault(vault_path, &format!("prompts/{}.md", id))
+}
+
 // Parse clean markdown prompt file
-fn parse_prompt_content(content: &str) -> Result<PromptContent, String> {
+fn parse_prompt_content(content: &str) -> Result<PromptContent, BouldyError> {
     let lines: Vec<&str> = content.lines().collect();
     
     if lines.is_empty() {
@@ -737,7 +2712,7 @@ fn serialize_prompt_content(prompt: &PromptContent) -> String {
 }
 
 // Load all metadata from .bouldy/prompt-metadata.json
-fn load_all_prompt_stats(vault_path: &str) -> Result<HashMap<String, PromptStats>, String> {
+fn load_all_prompt_stats(vault_path: &str) -> Result<HashMap<String, PromptStats>, BouldyError> {
     let bouldy_dir = Path::new(vault_path).join(".bouldy");
     let metadata_file = bouldy_dir.join("prompt-metadata.json");
     
@@ -746,47 +2721,52 @@ fn load_all_prompt_stats(vault_path: &str) -> Result<HashMap<String, PromptStats
     }
     
     let content = fs::read_to_string(&metadata_file)
-        .map_err(|e| format!("Failed to read prompt metadata: {}", e))?;
+        .map_err(|e| BouldyError::io("Failed to read prompt metadata", e))?;
     
     serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse prompt metadata: {}", e))
+        .map_err(|e| BouldyError::parse("Failed to parse prompt metadata", e))
 }
 
 // Save all metadata to .bouldy/prompt-metadata.json
-fn save_all_prompt_stats(vault_path: &str, stats: &std::collections::HashMap<String, PromptStats>) -> Result<(), String> {
+fn save_all_prompt_stats(
+    vault_path: &str,
+    stats: &std::collections::HashMap<String, PromptStats>,
+) -> Result<(), BouldyError> {
     let bouldy_dir = ensure_bouldy_dir(vault_path)?;
     let metadata_file = bouldy_dir.join("prompt-metadata.json");
     
     let content = serde_json::to_string_pretty(stats)
         .map_err(|e| format!("Failed to serialize prompt metadata: {}", e))?;
     
-    fs::write(&metadata_file, content)
-        .map_err(|e| format!("Failed to write prompt metadata: {}", e))
+    fsutil::write_atomic(&metadata_file, content)
+        .map_err(|e| BouldyError::io("Failed to write prompt metadata", e))
 }
 
 // Extract full Prompt from file + metadata
-fn extract_prompt_from_file(path: &Path, id: &str, all_stats: &std::collections::HashMap<String, PromptStats>) -> Result<Prompt, String> {
-    let content = fs::read_to_string(path)
-        .map_err(|e| format!("Failed to read prompt: {}", e))?;
-    
+fn extract_prompt_from_file(
+    path: &Path,
+    id: &str,
+    all_stats: &std::collections::HashMap<String, PromptStats>,
+) -> Result<Prompt, BouldyError> {
+    let content =
+        fs::read_to_string(path).map_err(|e| BouldyError::io("Failed to read prompt", e))?;
+
     let prompt_content = parse_prompt_content(&content)?;
+
+    let file_metadata =
+        fs::metadata(path).map_err(|e| BouldyError::io("Failed to read file metadata", e))?;
+
+    let modified = vault::unix_secs(
+        file_metadata
+            .modified()
+            .map_err(|e| BouldyError::io("Failed to get modified time", e))?,
+    );
     
-    let file_metadata = fs::metadata(path)
-        .map_err(|e| format!("Failed to read file metadata: {}", e))?;
-    
-    let modified = file_metadata
-        .modified()
-        .map_err(|e| format!("Failed to get modified time: {}", e))?
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_secs();
-    
+    // Not every filesystem records creation time
     let created = file_metadata
         .created()
-        .unwrap_or_else(|_| file_metadata.modified().unwrap())
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_secs();
+        .map(vault::unix_secs)
+        .unwrap_or(modified);
     
     let stats = all_stats.get(id).cloned().unwrap_or_default();
     
@@ -799,70 +2779,136 @@ fn extract_prompt_from_file(path: &Path, id: &str, all_stats: &std::collections:
         variables: stats.variables.unwrap_or_default(),
         last_used: stats.last_used,
         use_count: stats.use_count,
+        favorite: stats.favorite,
+        rating: stats.rating,
+        tests: stats.tests.unwrap_or_default(),
         created,
         modified,
-        path: path.to_string_lossy().to_string(),
+        path: vault::path_to_string(path)?,
     })
 }
 
-#[tauri::command]
-async fn list_prompts(vault_path: String) -> Result<Vec<Prompt>, String> {
-    let vault = Path::new(&vault_path);
-    let prompts_dir = vault.join("prompts");
+fn sort_prompts(prompts: &mut [Prompt], sort: PromptSort) {
+    prompts.sort_by(|a, b| {
+        let by = match sort {
+            PromptSort::Recent => match (a.last_used, b.last_used) {
+                (Some(a_used), Some(b_used)) => b_used.cmp(&a_used),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            },
+            PromptSort::Rating => b.rating.cmp(&a.rating),
+            PromptSort::Uses => b.use_count.cmp(&a.use_count),
+            PromptSort::Title => std::cmp::Ordering::Equal,
+        };
+        b.favorite
+            .cmp(&a.favorite)
+            .then(by)
+            .then_with(|| a.title.cmp(&b.title))
+    });
+}
 
-    // Create prompts directory if it doesn't exist
-    if !prompts_dir.exists() {
-        fs::create_dir(&prompts_dir)
-            .map_err(|e| format!("Failed to create prompts directory: {}", e))?;
-        return Ok(vec![]);
-    }
+#[tauri::command]
+async fn list_prompts(
+    vault_path: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    sort: Option<PromptSort>,
+    favorites_only: Option<bool>,
+    min_rating: Option<u8>,
+) -> Result<vault::Page<Prompt>, BouldyError> {
+    blocking(move || {
+        let vault = Path::new(&vault_path);
+        let prompts_dir = vault.join("prompts");
+
+        // Create prompts directory if it doesn't exist
+        if !prompts_dir.exists() {
+            fs::create_dir(&prompts_dir)
+                .map_err(|e| BouldyError::io("Failed to create prompts directory", e))?;
+            return Ok(vault::Page::from_vec(vec![], offset, limit));
+        }
 
-    // Load all metadata
-    let all_stats = load_all_prompt_stats(&vault_path)?;
+        // Load all metadata
+        let all_stats = load_all_prompt_stats(&vault_path)?;
 
-    let mut prompts = Vec::new();
+        let mut prompts = Vec::new();
 
-    let entries = fs::read_dir(&prompts_dir)
-        .map_err(|e| format!("Failed to read prompts directory: {}", e))?;
+        let entries = fs::read_dir(&prompts_dir)
+            .map_err(|e| BouldyError::io("Failed to read prompts directory", e))?;
 
-    for entry in entries {
-        let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
-        let path = entry.path();
+        for entry in entries {
+            let entry = entry.map_err(|e| BouldyError::io("Failed to read entry", e))?;
+            let path = entry.path();
 
-        if path.extension().and_then(|s| s.to_str()) == Some("md") {
-            let id = path
-                .file_stem()
-                .and_then(|s| s.to_str())
-                .unwrap_or("untitled");
+            if vault::is_markdown_file(&path) {
+                let id = path
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("untitled");
             
-            match extract_prompt_from_file(&path, id, &all_stats) {
-                Ok(prompt) => prompts.push(prompt),
-                Err(_) => {
-                    // Skip invalid prompts silently
+                match extract_prompt_from_file(&path, id, &all_stats) {
+                    Ok(prompt) => {
+                        let wanted = (!favorites_only.unwrap_or(false) || prompt.favorite)
+                            && min_rating.is_none_or(|min| prompt.rating.is_some_and(|r| r >= min));
+                        if wanted {
+                            prompts.push(prompt);
+                        }
+                    }
+                    Err(_) => {
+                        // Skip invalid prompts silently
+                    }
                 }
             }
         }
-    }
 
-    // Sort by lastUsed (recent first), then by title
-    prompts.sort_by(|a, b| match (a.last_used, b.last_used) {
-        (Some(a_used), Some(b_used)) => b_used.cmp(&a_used),
-        (Some(_), None) => std::cmp::Ordering::Less,
-        (None, Some(_)) => std::cmp::Ordering::Greater,
-        (None, None) => a.title.cmp(&b.title),
-    });
+        sort_prompts(&mut prompts, sort.unwrap_or_default());
+
+        Ok(vault::Page::from_vec(prompts, offset, limit))
+    })
+    .await
+}
+
+#[tauri::command]
+async fn read_prompt(vault_path: String, id: String) -> Result<Prompt, BouldyError> {
+    blocking(move || {
+        let file_path = prompt_path(&vault_path, &id)?;
 
-    Ok(prompts)
+        let all_stats = load_all_prompt_stats(&vault_path)?;
+        extract_prompt_from_file(&file_path, &id, &all_stats)
+    })
+    .await
 }
 
 #[tauri::command]
-async fn read_prompt(vault_path: String, id: String) -> Result<Prompt, String> {
-    let vault = Path::new(&vault_path);
-    let prompts_dir = vault.join("prompts");
-    let file_path = prompts_dir.join(format!("{}.md", id));
+async fn read_prompt_metadata(
+    vault_path: String,
+    id: String,
+) -> Result<PromptSummary, BouldyError> {
+    blocking(move || {
+        let file_path = prompt_path(&vault_path, &id)?;
+
+        // The title is the first line, so the head is plenty
+        let head = vault::read_head(&file_path)?;
+        let title = parse_prompt_content(&head)?.title;
 
-    let all_stats = load_all_prompt_stats(&vault_path)?;
-    extract_prompt_from_file(&file_path, &id, &all_stats)
+        let stats = load_all_prompt_stats(&vault_path)?
+            .remove(&id)
+            .unwrap_or_default();
+
+        Ok(PromptSummary {
+            id,
+            title,
+            tags: stats.tags.unwrap_or_default(),
+            category: stats.category,
+            variables: stats.variables.unwrap_or_default(),
+            last_used: stats.last_used,
+            use_count: stats.use_count,
+            favorite: stats.favorite,
+            rating: stats.rating,
+            path: file_path.to_string_lossy().to_string(),
+        })
+    })
+    .await
 }
 
 #[tauri::command]
@@ -871,128 +2917,541 @@ async fn write_prompt(
     vault_path: String,
     id: String,
     input: PromptInput,
-) -> Result<Prompt, String> {
-    let vault = Path::new(&vault_path);
-    let prompts_dir = vault.join("prompts");
-
-    // Create prompts directory if it doesn't exist
-    if !prompts_dir.exists() {
-        fs::create_dir(&prompts_dir)
-            .map_err(|e| format!("Failed to create prompts directory: {}", e))?;
+) -> Result<Prompt, BouldyError> {
+    blocking(move || {
+        let vault = Path::new(&vault_path);
+        let prompts_dir = vault.join("prompts");
+
+        // Create prompts directory if it doesn't exist
+        if !prompts_dir.exists() {
+            fs::create_dir(&prompts_dir)
+                .map_err(|e| BouldyError::io("Failed to create prompts directory", e))?;
+        }
+
+        // Write clean markdown file (just title + content)
+        let prompt_content = PromptContent {
+            title: input.title,
+            content: input.content,
+        };
+        let file_path = prompt_path(&vault_path, &id)?;
+        let serialized = serialize_prompt_content(&prompt_content);
+        fsutil::write_atomic(&file_path, serialized)
+            .map_err(|e| BouldyError::io("Failed to write prompt", e))?;
+
+        // Update metadata in .bouldy/prompt-metadata.json
+        let mut all_stats = load_all_prompt_stats(&vault_path)?;
+        // Favorites, ratings and test cases are set separately and survive
+        // edits
+        let previous = all_stats.remove(&id).unwrap_or_default();
+        all_stats.insert(
+            id.clone(),
+            PromptStats {
+                tags: if input.tags.is_empty() {
+                    None
+                } else {
+                    Some(input.tags)
+                },
+                category: input.category,
+                variables: if input.variables.is_empty() {
+                    None
+                } else {
+                    Some(input.variables)
+                },
+                last_used: None,
+                use_count: 0,
+                favorite: previous.favorite,
+                rating: previous.rating,
+                tests: previous.tests,
+            },
+        );
+        save_all_prompt_stats(&vault_path, &all_stats)?;
+
+        // Load and return the full prompt
+        let all_stats = load_all_prompt_stats(&vault_path)?;
+        let prompt = extract_prompt_from_file(&file_path, &id, &all_stats)?;
+
+        // Emit event after successful save
+        let _ = app.emit("prompt:saved", prompt.clone());
+
+        Ok(prompt)
+    })
+    .await
+}
+
+#[tauri::command]
+async fn delete_prompt(app: AppHandle, vault_path: String, id: String) -> Result<(), BouldyError> {
+    blocking(move || {
+        let file_path = prompt_path(&vault_path, &id)?;
+
+        // Delete the prompt file
+        fs::remove_file(&file_path).map_err(|e| BouldyError::io("Failed to delete prompt", e))?;
+
+        // Remove from metadata
+        let mut all_stats = load_all_prompt_stats(&vault_path)?;
+        all_stats.remove(&id);
+        save_all_prompt_stats(&vault_path, &all_stats)?;
+        prompt_tests::delete_runs(&vault_path, &id);
+
+        // Emit event after successful deletion
+        #[derive(Clone, Serialize)]
+        struct PromptDeletedPayload {
+            path: String,
+            id: String,
+        }
+
+        let _ = app.emit(
+            "prompt:deleted",
+            PromptDeletedPayload {
+                path: file_path.to_string_lossy().to_string(),
+                id,
+            },
+        );
+
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+async fn get_prompt_palette(
+    vault_path: String,
+    limit: Option<usize>,
+) -> Result<Vec<prompt_palette::PaletteEntry>, BouldyError> {
+    let prompts = list_prompts(vault_path, None, None, None, None, None).await?;
+    let now = vault::unix_secs(std::time::SystemTime::now());
+    let entries = prompts
+        .items
+        .into_iter()
+        .map(|prompt| prompt_palette::PaletteEntry {
+            score: prompt_palette::score(prompt.use_count, prompt.last_used, prompt.favorite, now),
+            id: prompt.id,
+            title: prompt.title,
+            variables: prompt.variables,
+            favorite: prompt.favorite,
+        })
+        .collect();
+    Ok(prompt_palette::rank(
+        entries,
+        limit.unwrap_or(prompt_palette::DEFAULT_PALETTE_SIZE),
+    ))
+}
+
+// Open a note in its own window, sized as it was last closed
+#[tauri::command]
+async fn open_note_window(app: AppHandle, path: String) -> Result<String, BouldyError> {
+    blocking(move || windows::open_note_window(&app, &path)).await
+}
+
+// Which note a note window shows; None for other windows
+#[tauri::command]
+fn get_window_note(app: AppHandle, window: tauri::WebviewWindow) -> Option<String> {
+    windows::window_note(&app, window.label())
+}
+
+#[tauri::command]
+async fn show_prompt_palette(app: AppHandle) -> Result<(), BouldyError> {
+    prompt_palette::show_palette_window(&app)
+}
+
+// Render the prompt and paste it into the app that was focused before the
+// palette opened
+#[tauri::command]
+async fn paste_prompt(
+    app: AppHandle,
+    vault_path: String,
+    id: String,
+    variables: Option<HashMap<String, String>>,
+) -> Result<prompt_palette::PastedPrompt, BouldyError> {
+    let prompt = read_prompt(vault_path.clone(), id.clone()).await?;
+    let text = prompt_tests::render(&prompt.content, &variables.unwrap_or_default());
+    let missing = prompt_estimate::unfilled_variables(&text);
+    if !missing.is_empty() {
+        return Err(BouldyError::invalid_input(format!(
+            "Fill in {}",
+            missing.join(", ")
+        )));
     }
 
-    // Write clean markdown file (just title + content)
-    let prompt_content = PromptContent {
-        title: input.title,
-        content: input.content,
-    };
-    let file_path = prompts_dir.join(format!("{}.md", id));
-    let serialized = serialize_prompt_content(&prompt_content);
-    fs::write(&file_path, serialized).map_err(|e| format!("Failed to write prompt: {}", e))?;
-
-    // Update metadata in .bouldy/prompt-metadata.json
-    let mut all_stats = load_all_prompt_stats(&vault_path)?;
-    all_stats.insert(
-        id.clone(),
-        PromptStats {
-            tags: if input.tags.is_empty() { None } else { Some(input.tags) },
-            category: input.category,
-            variables: if input.variables.is_empty() { None } else { Some(input.variables) },
-            last_used: None,
-            use_count: 0,
-        },
-    );
-    save_all_prompt_stats(&vault_path, &all_stats)?;
+    track_prompt_usage(app.clone(), vault_path, id).await?;
+    blocking(move || prompt_palette::paste(&app, text)).await
+}
+
+#[tauri::command]
+fn list_shortcuts(app: AppHandle) -> Result<Vec<shortcuts::ShortcutBinding>, BouldyError> {
+    Ok(shortcuts::list(&settings::load_settings(&app)?))
+}
+
+// `binding` None restores the default, "" unbinds
+#[tauri::command]
+async fn set_shortcut(
+    app: AppHandle,
+    id: String,
+    binding: Option<String>,
+) -> Result<Vec<shortcuts::ShortcutBinding>, BouldyError> {
+    blocking(move || {
+        let updated = shortcuts::set_shortcut(&app, &id, binding)?;
+        let _ = app.emit("shortcuts:changed", &updated);
+        Ok(updated)
+    })
+    .await
+}
 
-    // Load and return the full prompt
-    let all_stats = load_all_prompt_stats(&vault_path)?;
-    let prompt = extract_prompt_from_file(&file_path, &id, &all_stats)?;
+#[tauri::command]
+async fn list_snippets(vault_path: String) -> Result<Vec<snippets::Snippet>, BouldyError> {
+    blocking(move || snippets::list_snippets(&vault_path)).await
+}
 
-    // Emit event after successful save
-    let _ = app.emit("prompt:saved", prompt.clone());
+#[tauri::command]
+async fn write_snippet(
+    vault_path: String,
+    name: String,
+    abbr: String,
+    text: String,
+) -> Result<snippets::Snippet, BouldyError> {
+    blocking(move || snippets::write_snippet(&vault_path, &name, &abbr, &text)).await
+}
 
-    Ok(prompt)
+#[tauri::command]
+async fn delete_snippet(vault_path: String, name: String) -> Result<(), BouldyError> {
+    blocking(move || snippets::delete_snippet(&vault_path, &name)).await
 }
 
 #[tauri::command]
-async fn delete_prompt(app: AppHandle, vault_path: String, id: String) -> Result<(), String> {
-    let vault = Path::new(&vault_path);
-    let prompts_dir = vault.join("prompts");
-    let file_path = prompts_dir.join(format!("{}.md", id));
+async fn expand_snippet(vault_path: String, abbr: String) -> Result<String, BouldyError> {
+    blocking(move || snippets::expand_snippet(&vault_path, &abbr)).await
+}
 
-    // Delete the prompt file
-    fs::remove_file(&file_path).map_err(|e| format!("Failed to delete prompt: {}", e))?;
+// Expand and paste into the focused app, like a prompt from the palette
+#[tauri::command]
+async fn paste_snippet(
+    app: AppHandle,
+    vault_path: String,
+    abbr: String,
+) -> Result<prompt_palette::PastedPrompt, BouldyError> {
+    blocking(move || {
+        let text = snippets::expand_snippet(&vault_path, &abbr)?;
+        prompt_palette::paste(&app, text)
+    })
+    .await
+}
 
-    // Remove from metadata
-    let mut all_stats = load_all_prompt_stats(&vault_path)?;
-    all_stats.remove(&id);
-    save_all_prompt_stats(&vault_path, &all_stats)?;
+#[tauri::command]
+async fn track_prompt_usage(
+    _app: AppHandle,
+    vault_path: String,
+    id: String,
+) -> Result<(), BouldyError> {
+    blocking(move || {
+        // Load all metadata
+        let mut all_stats = load_all_prompt_stats(&vault_path)?;
+
+        // Update usage tracking for this prompt
+        let stats = all_stats.entry(id).or_insert_with(PromptStats::default);
+        stats.use_count += 1;
+        stats.last_used = Some(
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+        );
+
+        // Save back
+        save_all_prompt_stats(&vault_path, &all_stats)?;
+
+        Ok(())
+    })
+    .await
+}
 
-    // Emit event after successful deletion
-    #[derive(Clone, Serialize)]
-    struct PromptDeletedPayload {
-        path: String,
-        id: String,
+// Change one prompt's metadata, for prompts that exist
+fn update_prompt_stats(
+    vault_path: &str,
+    id: &str,
+    update: impl FnOnce(&mut PromptStats),
+) -> Result<(), BouldyError> {
+    let file_path = prompt_path(vault_path, id)?;
+    if !file_path.is_file() {
+        return Err(BouldyError::not_found(format!("Prompt not found: {}", id)));
     }
 
-    let _ = app.emit(
-        "prompt:deleted",
-        PromptDeletedPayload {
-            path: file_path.to_string_lossy().to_string(),
-            id,
-        },
-    );
+    let mut all_stats = load_all_prompt_stats(vault_path)?;
+    update(all_stats.entry(id.to_string()).or_default());
+    save_all_prompt_stats(vault_path, &all_stats)
+}
 
-    Ok(())
+#[tauri::command]
+async fn set_prompt_favorite(
+    app: AppHandle,
+    vault_path: String,
+    id: String,
+    favorite: bool,
+) -> Result<(), BouldyError> {
+    blocking(move || {
+        update_prompt_stats(&vault_path, &id, |stats| stats.favorite = favorite)?;
+        let _ = app.emit("prompt:metadata-changed", id);
+        Ok(())
+    })
+    .await
+}
+
+// Zero stars clears the rating
+#[tauri::command]
+async fn rate_prompt(
+    app: AppHandle,
+    vault_path: String,
+    id: String,
+    stars: u8,
+) -> Result<(), BouldyError> {
+    if stars > MAX_PROMPT_RATING {
+        return Err(BouldyError::invalid_input(format!(
+            "Rating must be between 0 and {} stars",
+            MAX_PROMPT_RATING
+        )));
+    }
+    blocking(move || {
+        update_prompt_stats(&vault_path, &id, |stats| {
+            stats.rating = (stars > 0).then_some(stars)
+        })?;
+        let _ = app.emit("prompt:metadata-changed", id);
+        Ok(())
+    })
+    .await
 }
 
 #[tauri::command]
-async fn track_prompt_usage(_app: AppHandle, vault_path: String, id: String) -> Result<(), String> {
-    // Load all metadata
-    let mut all_stats = load_all_prompt_stats(&vault_path)?;
+async fn set_prompt_tests(
+    app: AppHandle,
+    vault_path: String,
+    id: String,
+    tests: Vec<prompt_tests::PromptTestCase>,
+) -> Result<(), BouldyError> {
+    prompt_tests::validate_cases(&tests)?;
+    blocking(move || {
+        update_prompt_stats(&vault_path, &id, |stats| {
+            stats.tests = (!tests.is_empty()).then_some(tests)
+        })?;
+        let _ = app.emit("prompt:metadata-changed", id);
+        Ok(())
+    })
+    .await
+}
 
-    // Update usage tracking for this prompt
-    let stats = all_stats.entry(id).or_insert_with(PromptStats::default);
-    stats.use_count += 1;
-    stats.last_used = Some(
-        std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs(),
-    );
+#[tauri::command]
+async fn run_prompt_tests(
+    app: AppHandle,
+    vault_path: String,
+    id: String,
+    provider: Option<llm::LlmProvider>,
+) -> Result<prompt_tests::PromptTestRun, BouldyError> {
+    let app_settings = settings::load_settings(&app)?;
+    let prompt = {
+        let (vault_path, id) = (vault_path.clone(), id.clone());
+        blocking(move || {
+            let all_stats = load_all_prompt_stats(&vault_path)?;
+            extract_prompt_from_file(&prompt_path(&vault_path, &id)?, &id, &all_stats)
+        })
+        .await?
+    };
 
-    // Save back
-    save_all_prompt_stats(&vault_path, &all_stats)?;
+    prompt_tests::run_tests(
+        &app_settings.network,
+        &app_settings.llm,
+        provider,
+        &vault_path,
+        &id,
+        &prompt.content,
+        &prompt.tests,
+    )
+    .await
+}
 
-    Ok(())
+#[tauri::command]
+async fn get_prompt_test_runs(
+    vault_path: String,
+    id: String,
+) -> Result<Vec<prompt_tests::PromptTestRun>, BouldyError> {
+    blocking(move || {
+        prompt_path(&vault_path, &id)?;
+        Ok(prompt_tests::load_runs(&vault_path, &id))
+    })
+    .await
 }
 
 #[tauri::command]
-async fn get_saved_theme(app: tauri::AppHandle) -> Result<String, String> {
-    match app.store("settings.json") {
-        Ok(store) => {
-            match store.get("theme") {
-                Some(v) => {
-                    if let Some(theme_str) = v.as_str() {
-                        Ok(theme_str.to_string())
-                    } else {
-                        Ok("midnight".to_string())
-                    }
-                }
-                None => {
-                    Ok("midnight".to_string())
-                }
-            }
-        }
-        Err(_) => {
-            Ok("midnight".to_string())
+async fn estimate_prompt(
+    app: AppHandle,
+    vault_path: String,
+    id: String,
+    variables: Option<HashMap<String, String>>,
+    model: Option<String>,
+) -> Result<prompt_estimate::PromptEstimate, BouldyError> {
+    let llm_settings = settings::load_settings(&app)?.llm;
+    blocking(move || {
+        let content = fs::read_to_string(prompt_path(&vault_path, &id)?)
+            .map_err(|e| BouldyError::io("Failed to read prompt", e))?;
+        let prompt = parse_prompt_content(&content)?;
+        Ok(prompt_estimate::estimate(
+            &prompt.content,
+            &variables.unwrap_or_default(),
+            model.as_deref().map(str::trim).filter(|m| !m.is_empty()),
+            &llm_settings,
+        ))
+    })
+    .await
+}
+
+#[tauri::command]
+async fn share_prompt(
+    app: AppHandle,
+    vault_path: String,
+    id: String,
+    target: prompt_share::ShareTarget,
+) -> Result<prompt_share::SharedLink, BouldyError> {
+    let prompt = read_prompt(vault_path, id.clone()).await?;
+    let shared = prompt_share::SharedPrompt {
+        title: prompt.title,
+        content: prompt.content,
+        tags: prompt.tags,
+        category: prompt.category,
+        variables: prompt.variables,
+    };
+    let markdown = prompt_share::to_markdown(&shared)?;
+
+    let url = match target {
+        prompt_share::ShareTarget::Markdown => None,
+        prompt_share::ShareTarget::Gist { public } => {
+            let file_name = format!("{}.md", vault::sanitize_file_name(&shared.title));
+            let markdown = markdown.clone();
+            let url = blocking_async(move || async move {
+                let network = settings::load_settings(&app)?.network;
+                prompt_share::create_gist(&network, &file_name, &shared, &markdown, public).await
+            })
+            .await?;
+            Some(url)
         }
+    };
+    Ok(prompt_share::SharedLink { markdown, url })
+}
+
+// Accepts a gist or other URL serving the markdown, or the markdown itself
+#[tauri::command]
+async fn import_shared_prompt(
+    app: AppHandle,
+    vault_path: String,
+    url_or_text: String,
+) -> Result<Prompt, BouldyError> {
+    let source = url_or_text.trim();
+    let text = if source.starts_with("http://") || source.starts_with("https://") {
+        let app = app.clone();
+        let url = source.to_string();
+        blocking_async(move || async move {
+            let network = settings::load_settings(&app)?.network;
+            prompt_share::fetch(&network, &url).await
+        })
+        .await?
+    } else {
+        source.to_string()
+    };
+    let shared = prompt_share::parse_shared(&text)?;
+
+    // Same ids as prompts created in the app, moved along if taken
+    let id = {
+        let vault_path = vault_path.clone();
+        blocking(move || {
+            let mut id = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_millis();
+            while prompt_path(&vault_path, &id.to_string())?.exists() {
+                id += 1;
+            }
+            Ok(id)
+        })
+        .await?
+    };
+
+    let input = PromptInput {
+        title: shared.title,
+        content: shared.content,
+        tags: shared.tags,
+        category: shared.category,
+        variables: shared.variables,
+    };
+    write_prompt(app, vault_path, id.to_string(), input).await
+}
+
+// Called by the frontend once React has mounted. Nothing is kept unless the
+// user opted in, since the log lives outside the vault.
+#[tauri::command]
+async fn log_startup_metrics(
+    app: AppHandle,
+    theme_init_ms: f64,
+    react_mount_ms: f64,
+    total_ms: f64,
+    first_paint_ms: Option<f64>,
+) -> Result<(), BouldyError> {
+    if !settings::load_settings(&app)?.startup_metrics_enabled {
+        return Ok(());
     }
+
+    let metrics = startup_metrics::StartupMetrics {
+        timestamp: chrono::Local::now().to_rfc3339(),
+        version: app.package_info().version.to_string(),
+        theme_init_ms,
+        react_mount_ms,
+        total_ms,
+        first_paint_ms,
+        backend: startup_metrics::backend_timings(&app),
+    };
+
+    blocking(move || startup_metrics::append(&app, &metrics)).await
+}
+
+#[tauri::command]
+async fn get_startup_metrics_history(
+    app: AppHandle,
+) -> Result<Vec<startup_metrics::StartupMetrics>, BouldyError> {
+    blocking(move || startup_metrics::load_history(&app)).await
+}
+
+#[tauri::command]
+async fn get_recent_logs(app: AppHandle, lines: Option<usize>) -> Result<Vec<String>, BouldyError> {
+    blocking(move || diagnostics::recent_logs(&app, lines)).await
+}
+
+#[tauri::command]
+async fn export_diagnostics_bundle(app: AppHandle, output_path: String) -> Result<(), BouldyError> {
+    blocking(move || diagnostics::export_bundle(&app, Path::new(&output_path))).await
+}
+
+#[tauri::command]
+async fn get_saved_theme(app: tauri::AppHandle) -> Result<String, BouldyError> {
+    blocking(move || match settings::load_settings(&app) {
+        Ok(settings) => Ok(settings.theme),
+        Err(_) => Ok(settings::DEFAULT_THEME.to_string()),
+    })
+    .await
 }
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tauri::Builder::default()
-        .plugin(tauri_plugin_autostart::Builder::new().build())
+    let mut builder = tauri::Builder::default();
+
+    // Must be registered first. A second launch exits immediately and hands
+    // its arguments (including deep links) to the running instance instead
+    // of starting another watcher on the same vault.
+    #[cfg(desktop)]
+    {
+        builder = builder.plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
+            windows::focus_main_window(app);
+        }));
+    }
+
+    builder
+        .plugin(
+            tauri_plugin_autostart::Builder::new()
+                .arg(AUTOSTART_ARG)
+                .build(),
+        )
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_window_state::Builder::default().build())
         .plugin(tauri_plugin_process::init())
@@ -1000,36 +3459,205 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_store::Builder::new().build())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .manage(switcher::QuickSwitcher::default())
+        .manage(index::IndexState::default())
+        .manage(note_cache::NoteCache::default())
+        .manage(todo_store::TodoStore::default())
+        .manage(plugins::PluginHost::default())
+        .manage(automation::ScriptHost::default())
+        .manage(audio_memo::AudioRecorder::default())
+        .manage(updates::PendingUpdate::default())
+        .manage(startup_metrics::StartupTimings::default())
+        .manage(windows::NoteWindows::default())
+        .manage(widget::TimerState::default())
         .setup(|app| {
+            diagnostics::init(app.handle());
+
             #[cfg(desktop)]
             {
                 use tauri_plugin_autostart::ManagerExt;
 
-                // Get the autostart manager
+                let started = std::time::Instant::now();
+                let app_settings = settings::load_settings(app.handle())?;
+                startup_metrics::record(
+                    app.handle(),
+                    startup_metrics::Phase::StoreLoad,
+                    started.elapsed(),
+                );
+
+                app.handle()
+                    .plugin(tauri_plugin_global_shortcut::Builder::new().build())?;
+
+                shortcuts::register_all(app.handle(), &app_settings);
+
+                // Keep the OS login item in sync with the user's choice
                 let autostart_manager = app.autolaunch();
-                // Enable autostart
-                let _ = autostart_manager.enable();
+                if app_settings.autostart_enabled {
+                    let _ = autostart_manager.enable();
+                } else {
+                    let _ = autostart_manager.disable();
+                }
+
+                // There is no tray yet, so "start minimized" minimizes the main window
+                let launched_at_login = std::env::args().any(|arg| arg == AUTOSTART_ARG);
+                if launched_at_login && app_settings.start_minimized {
+                    if let Some(window) = app.get_webview_window(windows::MAIN_WINDOW) {
+                        let _ = window.minimize();
+                    }
+                }
+            }
+
+            {
+                use tauri_plugin_deep_link::DeepLinkExt;
+
+                // Linux and Windows only pick up the scheme once it's registered at runtime
+                #[cfg(any(target_os = "linux", target_os = "windows"))]
+                if let Err(e) = app.deep_link().register_all() {
+                    tracing::warn!("Failed to register deep link scheme - {}", e);
+                }
+
+                let handle = app.handle().clone();
+                app.deep_link().on_open_url(move |event| {
+                    deeplink::handle_all(&handle, event.urls());
+                });
+
+                let launch_urls = app
+                    .deep_link()
+                    .get_current()
+                    .ok()
+                    .flatten()
+                    .unwrap_or_default();
+                app.manage(deeplink::LaunchDeepLinks(Mutex::new(
+                    launch_urls.iter().map(|url| url.to_string()).collect(),
+                )));
+            }
+
+            // Another bouldy window or the CLI is mid-write on this vault
+            let handle = app.handle().clone();
+            vault_lock::on_contention(move |vault_path| {
+                let _ = handle.emit("vault:locked-by-other", vault_path.to_string());
+            });
+
+            reminders::start(app.handle().clone());
+            remote_tasks::start(app.handle().clone());
+            mail_capture::start(app.handle().clone());
+            automation::start(app.handle().clone());
+            weekly_review::start(app.handle().clone());
+            diagnostics::start(app.handle().clone());
+            todo_store::start_flusher(app.handle().clone());
+
+            // The frontend can abandon a long initial index (e.g. a huge vault
+            // opened by mistake); listings keep working off the filesystem
+            let handle = app.handle().clone();
+            app.listen("index:cancel", move |_| {
+                handle.state::<index::IndexState>().cancel_scan();
+            });
+
+            let clipboard_capture = clipboard::ClipboardCapture::default();
+            if settings::load_settings(app.handle())?.clipboard_capture_enabled {
+                clipboard_capture
+                    .enabled
+                    .store(true, std::sync::atomic::Ordering::Relaxed);
+            }
+            app.manage(clipboard_capture);
+            clipboard::start(app.handle().clone());
+
+            app.manage(web_clipper::WebClipper::default());
+            let app_settings = settings::load_settings(app.handle())?;
+            if app_settings.web_clipper_enabled {
+                if let Err(e) = web_clipper::start(app.handle(), app_settings.web_clipper_port) {
+                    tracing::warn!("Failed to start web clipper - {}", e);
+                }
+            }
+
+            app.manage(local_api::LocalApi::default());
+            if app_settings.api_server_enabled {
+                if let Err(e) = local_api::start(app.handle(), app_settings.api_server_port) {
+                    tracing::warn!("Failed to start API server - {}", e);
+                }
             }
+
             Ok(())
         })
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) = event {
+                let app = window.app_handle().clone();
+                let paths = paths.clone();
+
+                // Copying can take a while for big files; keep the event loop free
+                std::thread::spawn(move || {
+                    if let Err(e) = handle_file_drop(&app, &paths) {
+                        tracing::warn!("File drop import failed - {}", e);
+                    }
+                });
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             select_vault_folder,
+            discover_vaults,
             save_vault_path,
             get_vault_path,
+            get_settings,
+            update_settings,
+            get_locale_info,
+            load_session_state,
+            save_session_state,
+            get_autostart_enabled,
+            set_autostart_enabled,
+            get_network_config,
+            set_network_config,
+            test_network_connection,
             check_vault_exists,
             list_vault_files,
+            resync_note_list,
+            search_notes,
+            get_backlinks,
+            find_duplicate_notes,
+            find_orphaned_attachments,
+            find_broken_attachments,
+            delete_orphaned_attachments,
+            get_vault_disk_usage,
+            verify_vault_integrity,
+            accept_integrity_changes,
+            get_index_status,
+            fuzzy_find,
             read_note,
+            read_note_metadata,
+            get_note_outline,
+            resolve_embeds,
+            resolve_note,
+            get_writing_stats,
+            diff_notes,
+            list_note_versions,
+            diff_note_version,
             write_note,
             delete_note,
             pick_markdown_file,
             import_note,
             load_todos,
+            load_todos_by_due,
             create_todo,
             update_todo,
             delete_todo,
             toggle_todo,
             update_todo_due_date,
             update_todo_metadata,
+            set_todo_description,
+            set_todo_delegate,
+            set_todo_estimate,
+            list_waiting_for,
+            get_todos_for_context,
+            list_todo_contexts,
+            set_current_context,
+            apply_rollover,
+            list_someday,
+            move_to_someday,
+            activate_someday,
+            get_review_queue,
+            mark_someday_reviewed,
             reorder_todo,
             get_todo_stats,
             get_todo_metadata,
@@ -1041,17 +3669,142 @@ pub fn run() {
             list_contexts,
             list_priorities,
             bulk_update_due_dates,
+            quick_capture_note,
+            quick_capture_todo,
+            capture_to_inbox,
+            list_inbox,
+            process_inbox_item,
+            show_quick_capture,
+            get_clipboard_capture_enabled,
+            capture_url,
+            get_web_clipper_token,
+            reset_web_clipper_token,
+            set_web_clipper_enabled,
+            get_api_token,
+            reset_api_token,
+            set_api_server_enabled,
+            check_for_updates,
+            set_update_channel,
+            install_update_and_restart,
+            set_clipboard_capture_enabled,
+            send_notification,
+            export_todos_calendar,
+            export_todos,
+            export_site,
+            set_secret,
+            get_secret,
+            delete_secret,
+            sync_caldav,
+            authorize_remote_tasks,
+            sign_out_remote_tasks,
+            list_remote_task_lists,
+            sync_remote_tasks,
+            summarize_note,
+            suggest_tags,
+            accept_note_summary,
+            accept_note_tags,
+            start_audio_memo,
+            stop_audio_memo,
+            fetch_mail_captures,
+            refresh_badge,
+            open_deep_link,
+            take_launch_deep_links,
+            open_journal_entry,
+            set_journal_mood,
+            get_journal_streak,
+            get_journal_calendar,
+            get_calendar,
+            generate_weekly_review,
+            list_habits,
+            add_habit,
+            check_in,
+            get_habit_stats,
+            start_tracking,
+            stop_tracking,
+            get_tracking_status,
+            get_time_report,
+            list_boards,
+            get_board,
+            create_board,
+            list_canvases,
+            read_canvas,
+            write_canvas,
+            move_card,
+            add_card,
+            add_bookmark,
+            list_bookmarks,
+            remove_bookmark,
+            check_bookmark_links,
+            get_due_cards,
+            review_card,
+            load_plugins,
+            list_plugins,
+            run_plugin_command,
+            load_scripts,
+            list_scripts,
+            get_agenda,
+            plan_focus_sessions,
+            get_focus_plan,
+            get_focus_report,
+            get_timer_config,
+            set_timer_config,
+            report_timer_state,
+            show_widget,
+            get_widget_data,
+            start_break,
+            end_break,
+            get_break_stats,
             read_pomodoros,
             write_pomodoros,
             migrate_vault_structure,
+            rollback_migration,
+            import_tasks,
+            bulk_add_todos,
+            import_taskwarrior,
+            export_taskwarrior,
+            import_logseq,
+            get_vault_config,
+            update_vault_config,
             start_vault_watcher,
             list_prompts,
+            set_prompt_favorite,
+            rate_prompt,
+            set_prompt_tests,
+            run_prompt_tests,
+            get_prompt_test_runs,
+            estimate_prompt,
+            share_prompt,
+            import_shared_prompt,
+            get_prompt_palette,
+            open_note_window,
+            get_window_note,
+            show_prompt_palette,
+            paste_prompt,
+            list_shortcuts,
+            set_shortcut,
+            list_snippets,
+            write_snippet,
+            delete_snippet,
+            expand_snippet,
+            paste_snippet,
             read_prompt,
+            read_prompt_metadata,
             write_prompt,
             delete_prompt,
             track_prompt_usage,
+            log_startup_metrics,
+            get_startup_metrics_history,
+            get_recent_logs,
+            export_diagnostics_bundle,
             get_saved_theme
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // Don't lose todo changes still waiting on the write debounce,
+            // and tell the next start whether the index can be trusted
+            if let tauri::RunEvent::Exit = event {
+                shutdown::run(app);
+            }
+        });
 }
@@ -0,0 +1,185 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::error::BouldyError;
+use crate::network::{self, NetworkConfig};
+use crate::secrets;
+
+pub const SECRET_SERVICE: &str = "bouldy-llm";
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+pub const MAX_OUTPUT_TOKENS: u32 = 1024;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LlmProvider {
+    // Also any OpenAI-compatible server, via `baseUrl`
+    #[serde(rename = "openai")]
+    OpenAi,
+    #[serde(rename = "anthropic")]
+    Anthropic,
+    #[serde(rename = "ollama")]
+    Ollama,
+}
+
+impl LlmProvider {
+    pub fn name(self) -> &'static str {
+        match self {
+            LlmProvider::OpenAi => "openai",
+            LlmProvider::Anthropic => "anthropic",
+            LlmProvider::Ollama => "ollama",
+        }
+    }
+
+    fn default_base_url(self) -> &'static str {
+        match self {
+            LlmProvider::OpenAi => "https://api.openai.com/v1",
+            LlmProvider::Anthropic => "https://api.anthropic.com/v1",
+            LlmProvider::Ollama => "http://localhost:11434",
+        }
+    }
+
+    pub fn default_model(self) -> &'static str {
+        match self {
+            LlmProvider::OpenAi => "gpt-4o-mini",
+            LlmProvider::Anthropic => "claude-3-5-haiku-latest",
+            LlmProvider::Ollama => "llama3.2",
+        }
+    }
+
+    // Local Ollama servers don't authenticate
+    fn needs_key(self) -> bool {
+        self != LlmProvider::Ollama
+    }
+}
+
+/// Which model the AI features call. API keys live in the OS keyring, never
+/// in settings.json, and requests are only ever made from the backend.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct LlmSettings {
+    #[serde(default)]
+    pub provider: Option<LlmProvider>,
+    // Unset uses the provider's default
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(rename = "baseUrl", default)]
+    pub base_url: Option<String>,
+}
+
+impl LlmSettings {
+    pub fn validate(&self) -> Result<(), BouldyError> {
+        if let Some(ref base_url) = self.base_url {
+            let url = tauri::Url::parse(base_url).map_err(|e| {
+                BouldyError::invalid_input(format!("Invalid LLM base URL '{}': {}", base_url, e))
+            })?;
+            if !matches!(url.scheme(), "http" | "https") {
+                return Err(BouldyError::invalid_input(format!(
+                    "LLM base URL must be http or https: '{}'",
+                    base_url
+                )));
+            }
+        }
+        if self
+            .model
+            .as_deref()
+            .is_some_and(|model| model.trim().is_empty())
+        {
+            return Err(BouldyError::invalid_input("LLM model cannot be empty"));
+        }
+        Ok(())
+    }
+
+    fn provider(&self) -> Result<LlmProvider, BouldyError> {
+        self.provider
+            .ok_or_else(|| BouldyError::not_found("No LLM provider configured"))
+    }
+}
+
+pub fn api_key(provider: LlmProvider) -> Result<String, BouldyError> {
+    secrets::get(SECRET_SERVICE, provider.name())?
+        .ok_or_else(|| BouldyError::not_found(format!("No API key saved for {}", provider.name())))
+}
+
+/// Send one system + user message to the configured model and return its
+/// reply text.
+pub async fn complete(
+    network_config: &NetworkConfig,
+    settings: &LlmSettings,
+    system: &str,
+    prompt: &str,
+) -> Result<String, BouldyError> {
+    let provider = settings.provider()?;
+    let key = if provider.needs_key() {
+        Some(api_key(provider)?)
+    } else {
+        None
+    };
+    let model = settings
+        .model
+        .as_deref()
+        .unwrap_or(provider.default_model());
+    let base_url = settings
+        .base_url
+        .as_deref()
+        .unwrap_or(provider.default_base_url())
+        .trim_end_matches('/');
+
+    let client = network::http_client(network_config)?;
+    let request = match provider {
+        LlmProvider::OpenAi => client
+            .post(format!("{}/chat/completions", base_url))
+            .bearer_auth(key.unwrap_or_default())
+            .json(&json!({
+                "model": model,
+                "messages": [
+                    { "role": "system", "content": system },
+                    { "role": "user", "content": prompt },
+                ],
+                "max_tokens": MAX_OUTPUT_TOKENS,
+            })),
+        LlmProvider::Anthropic => client
+            .post(format!("{}/messages", base_url))
+            .header("x-api-key", key.unwrap_or_default())
+            .header("anthropic-version", ANTHROPIC_VERSION)
+            .json(&json!({
+                "model": model,
+                "system": system,
+                "messages": [{ "role": "user", "content": prompt }],
+                "max_tokens": MAX_OUTPUT_TOKENS,
+            })),
+        LlmProvider::Ollama => client.post(format!("{}/api/chat", base_url)).json(&json!({
+            "model": model,
+            "stream": false,
+            "messages": [
+                { "role": "system", "content": system },
+                { "role": "user", "content": prompt },
+            ],
+        })),
+    };
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach {}: {}", provider.name(), e))?;
+    let status = response.status();
+    let text = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read response from {}: {}", provider.name(), e))?;
+    let body: Value = serde_json::from_str(&text).unwrap_or(Value::Null);
+    if !status.is_success() {
+        // All three put a readable message at error.message or error
+        let message = body["error"]["message"]
+            .as_str()
+            .or(body["error"].as_str())
+            .unwrap_or(text.trim());
+        return Err(format!("{} returned {}: {}", provider.name(), status, message).into());
+    }
+
+    let text = match provider {
+        LlmProvider::OpenAi => body["choices"][0]["message"]["content"].as_str(),
+        LlmProvider::Anthropic => body["content"][0]["text"].as_str(),
+        LlmProvider::Ollama => body["message"]["content"].as_str(),
+    };
+    text.map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty())
+        .ok_or_else(|| format!("Empty response from {}", provider.name()).into())
+}
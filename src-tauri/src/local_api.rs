@@ -0,0 +1,286 @@
+use percent_encoding::percent_decode_str;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::path::Path;
+use tauri::async_runtime::block_on;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::BouldyError;
+use crate::local_server::{LocalServer, Request, Response, ServerConfig};
+use crate::{capture, note_cache, secrets, settings, todo_store, todos, vault};
+
+// A JSON API on 127.0.0.1 for scripts and editor integrations. Every request
+// needs `Authorization: Bearer <token>`. Note paths are relative to the
+// notes folder and percent-encoded in the URL.
+//
+//   GET    /status
+//   GET    /notes                 GET /notes/{path}
+//   PUT    /notes/{path}          {"content"}
+//   DELETE /notes/{path}
+//   GET    /search?q=...
+//   GET    /todos                 POST /todos {"text"}
+//   PATCH  /todos/{id}            {"title", "completed", "dueDate"}
+//   DELETE /todos/{id}
+//   GET    /prompts               GET /prompts/{id}
+//   PUT    /prompts/{id}          same body as write_prompt
+//   DELETE /prompts/{id}
+pub const DEFAULT_PORT: u16 = 27125;
+
+const SECRET_SERVICE: &str = "bouldy-api";
+const SECRET_ACCOUNT: &str = "token";
+
+/// Managed state: the listener, while it runs.
+#[derive(Default)]
+pub struct LocalApi {
+    server: LocalServer,
+}
+
+#[derive(Deserialize)]
+struct NoteBody {
+    content: String,
+}
+
+#[derive(Deserialize)]
+struct TodoBody {
+    text: String,
+}
+
+#[derive(Deserialize)]
+struct TodoPatch {
+    title: Option<String>,
+    completed: Option<bool>,
+    // An empty string clears the due date
+    #[serde(rename = "dueDate")]
+    due_date: Option<String>,
+}
+
+// === Token ===
+
+/// The token clients must send as `Authorization: Bearer <token>`, created
+/// on first use.
+pub fn token() -> Result<String, BouldyError> {
+    match secrets::get(SECRET_SERVICE, SECRET_ACCOUNT)? {
+        Some(token) => Ok(token),
+        None => reset_token(),
+    }
+}
+
+/// Replace the token, locking out clients set up with the old one.
+pub fn reset_token() -> Result<String, BouldyError> {
+    let token = uuid::Uuid::new_v4().simple().to_string();
+    secrets::set(SECRET_SERVICE, SECRET_ACCOUNT, &token)?;
+    Ok(token)
+}
+
+// === Routes ===
+
+fn status_for(error: &BouldyError) -> &'static str {
+    match error {
+        BouldyError::NotFound { .. } => "404 Not Found",
+        BouldyError::OutsideVault { .. } | BouldyError::InvalidPath { .. } => "400 Bad Request",
+        BouldyError::ParseError { .. } | BouldyError::InvalidInput { .. } => "400 Bad Request",
+        BouldyError::Conflict { .. } => "409 Conflict",
+        _ => "500 Internal Server Error",
+    }
+}
+
+fn parse_body<T: for<'de> Deserialize<'de>>(body: &[u8]) -> Result<T, BouldyError> {
+    serde_json::from_slice(body).map_err(|e| BouldyError::parse("Invalid request body", e))
+}
+
+fn to_json<T: serde::Serialize>(value: T) -> Result<Value, BouldyError> {
+    serde_json::to_value(value).map_err(|e| format!("Failed to serialize response: {}", e).into())
+}
+
+fn decode(segment: &str) -> Result<String, BouldyError> {
+    percent_decode_str(segment)
+        .decode_utf8()
+        .map(|decoded| decoded.to_string())
+        .map_err(|_| BouldyError::invalid_path(format!("Invalid path: {}", segment)))
+}
+
+fn query_param(query: &str, name: &str) -> Option<String> {
+    query.split('&').find_map(|pair| {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        (key == name).then(|| {
+            percent_decode_str(&value.replace('+', " "))
+                .decode_utf8_lossy()
+                .to_string()
+        })
+    })
+}
+
+// The absolute path of a note given relative to the notes folder. Checked
+// against the notes folder, not just the vault, so `..%2F` can't reach
+// todo.txt or bouldy's own state.
+fn note_file(vault_path: &str, path: &str) -> Result<String, BouldyError> {
+    let notes_dir = vault::notes_dir(vault_path);
+    let file = vault::validate_path_in_vault(
+        vault_path,
+        &notes_dir.join(decode(path)?).to_string_lossy(),
+    )?;
+    let inside_notes = notes_dir
+        .canonicalize()
+        .is_ok_and(|notes_dir| file.starts_with(notes_dir));
+    if !inside_notes || !vault::is_markdown_file(&file) {
+        return Err(BouldyError::invalid_path(format!("Not a note: {}", path)));
+    }
+    Ok(file.to_string_lossy().to_string())
+}
+
+fn parse_todo_id(id: &str) -> Result<usize, BouldyError> {
+    id.parse()
+        .map_err(|_| BouldyError::not_found(format!("Todo not found: {}", id)))
+}
+
+// Returns the status line and body for a request, or None for unknown routes
+fn route(
+    app: &AppHandle,
+    method: &str,
+    path: &str,
+    query: &str,
+    body: &[u8],
+) -> Option<Result<(&'static str, Value), BouldyError>> {
+    if (method, path.trim_matches('/')) == ("GET", "status") {
+        return Some(Ok(("200 OK", json!({ "app": "bouldy" }))));
+    }
+    let vault_path = match settings::require_vault_path(app) {
+        Ok(vault_path) => vault_path,
+        Err(e) => return Some(Err(e)),
+    };
+    let segments: Vec<&str> = path.trim_matches('/').splitn(2, '/').collect();
+    let ok = |value: Result<Value, BouldyError>| value.map(|value| ("200 OK", value));
+
+    let result = match (method, segments.as_slice()) {
+        ("GET", ["notes"]) => ok(app
+            .state::<note_cache::NoteCache>()
+            .list(&vault_path)
+            .and_then(to_json)),
+        ("GET", ["notes", note]) => ok(note_file(&vault_path, note).and_then(|file| {
+            let note = block_on(crate::read_note(app.clone(), file.clone()))?;
+            Ok(json!({ "path": file, "title": note.title, "content": note.content }))
+        })),
+        ("PUT", ["notes", note]) => (|| {
+            let file = note_file(&vault_path, note)?;
+            let existed = Path::new(&file).exists();
+            let NoteBody { content } = parse_body(body)?;
+            let title = vault::extract_title_from_filename(Path::new(&file));
+            let note = block_on(crate::save_note(
+                app.clone(),
+                "local-api".into(),
+                file,
+                content,
+                title,
+            ))?;
+            let status = if existed { "200 OK" } else { "201 Created" };
+            Ok((status, to_json(note)?))
+        })(),
+        ("DELETE", ["notes", note]) => ok(note_file(&vault_path, note).and_then(|file| {
+            block_on(crate::delete_note(app.clone(), file))?;
+            Ok(Value::Null)
+        })),
+        ("GET", ["search"]) => ok(block_on(crate::search_notes(
+            app.clone(),
+            vault_path.clone(),
+            query_param(query, "q").unwrap_or_default(),
+        ))
+        .and_then(to_json)),
+
+        ("GET", ["todos"]) => ok(app
+            .state::<todo_store::TodoStore>()
+            .load(&vault_path)
+            .and_then(to_json)),
+        ("POST", ["todos"]) => (|| {
+            let TodoBody { text } = parse_body(body)?;
+            let todo = capture::capture_todo(app, &vault_path, &text)?;
+            let _ = app.emit("todos_changed", ());
+            Ok(("201 Created", to_json(todo)?))
+        })(),
+        ("PATCH", ["todos", id]) => ok((|| {
+            let id = parse_todo_id(id)?;
+            let patch: TodoPatch = parse_body(body)?;
+            let due_date = patch
+                .due_date
+                .map(|due| {
+                    todos::normalize_due_date(Some(due).filter(|due| !due.trim().is_empty()))
+                })
+                .transpose()?;
+            let todo = crate::update_todo_with(app, &vault_path, id, |todo| {
+                if let Some(title) = patch.title {
+                    todo.title = title;
+                }
+                if let Some(completed) = patch.completed {
+                    todo.completed = completed;
+                }
+                if let Some(due_date) = due_date {
+                    todo.due_date = due_date;
+                }
+            })?;
+            to_json(todo)
+        })()),
+        ("DELETE", ["todos", id]) => ok(parse_todo_id(id).and_then(|id| {
+            block_on(crate::delete_todo(app.clone(), vault_path.clone(), id))?;
+            Ok(Value::Null)
+        })),
+
+        ("GET", ["prompts"]) => ok(block_on(crate::list_prompts(
+            vault_path.clone(),
+            None,
+            None,
+            None,
+            None,
+            None,
+        ))
+        .and_then(to_json)),
+        ("GET", ["prompts", id]) => ok(decode(id)
+            .and_then(|id| to_json(block_on(crate::read_prompt(vault_path.clone(), id))?))),
+        ("PUT", ["prompts", id]) => ok((|| {
+            let input = parse_body(body)?;
+            let prompt = block_on(crate::write_prompt(
+                app.clone(),
+                vault_path.clone(),
+                decode(id)?,
+                input,
+            ))?;
+            to_json(prompt)
+        })()),
+        ("DELETE", ["prompts", id]) => ok(decode(id).and_then(|id| {
+            block_on(crate::delete_prompt(app.clone(), vault_path.clone(), id))?;
+            Ok(Value::Null)
+        })),
+
+        _ => return None,
+    };
+    Some(result)
+}
+
+// === Server ===
+
+fn handle_request(app: &AppHandle, request: Request) -> Response {
+    let (path, query) = request
+        .path
+        .split_once('?')
+        .unwrap_or((request.path.as_str(), ""));
+    match route(app, &request.method, path, query, &request.body) {
+        Some(Ok((status, body))) => Response::json(status, body),
+        Some(Err(e)) => Response::error(status_for(&e), e.message()),
+        None => Response::error("404 Not Found", "Not found"),
+    }
+}
+
+/// Listen on `127.0.0.1:port` until `stop` is called.
+pub fn start(app: &AppHandle, port: u16) -> Result<(), BouldyError> {
+    let config = ServerConfig {
+        name: "API server",
+        token,
+        cors: false,
+    };
+    app.state::<LocalApi>()
+        .server
+        .start(app, port, config, handle_request)
+}
+
+/// Stop the listener, if it's running.
+pub fn stop(app: &AppHandle) {
+    app.state::<LocalApi>().server.stop();
+}
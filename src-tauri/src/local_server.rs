@@ -0,0 +1,285 @@
+use serde_json::{json, Value};
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::AppHandle;
+
+use crate::error::BouldyError;
+
+// The plumbing shared by the web clipper and the local API: a loopback-only
+// HTTP listener that checks a bearer token and hands each request to the
+// feature's handler on a thread of its own.
+
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_HEADER_BYTES: usize = 16 * 1024;
+// Web clips of whole pages with inline images can get large
+const MAX_BODY_BYTES: usize = 10 * 1024 * 1024;
+
+pub struct Request {
+    pub method: String,
+    // Including any query string
+    pub path: String,
+    pub authorization: Option<String>,
+    pub body: Vec<u8>,
+}
+
+pub struct Response {
+    pub status: &'static str,
+    // None or null sends an empty body
+    pub body: Option<Value>,
+}
+
+impl Response {
+    pub fn json(status: &'static str, body: Value) -> Self {
+        Response {
+            status,
+            body: Some(body),
+        }
+    }
+
+    pub fn error(status: &'static str, message: &str) -> Self {
+        Response::json(status, json!({ "error": message }))
+    }
+}
+
+/// How a server identifies itself and who may call it.
+pub struct ServerConfig {
+    // For logs and errors, e.g. "web clipper"
+    pub name: &'static str,
+    // The token callers must send as `Authorization: Bearer <token>`
+    pub token: fn() -> Result<String, BouldyError>,
+    // Browser extensions call from their own origin and need CORS headers;
+    // without them browsers keep web pages from reading the responses
+    pub cors: bool,
+}
+
+type Handler = fn(&AppHandle, Request) -> Response;
+
+/// Managed state: the running listener's stop flag, if any.
+#[derive(Default)]
+pub struct LocalServer {
+    running: Mutex<Option<Arc<AtomicBool>>>,
+}
+
+impl LocalServer {
+    /// Listen on `127.0.0.1:port` until `stop` is called. Only loopback is
+    /// bound, so the server is never reachable from other machines.
+    pub fn start(
+        &self,
+        app: &AppHandle,
+        port: u16,
+        config: ServerConfig,
+        handler: Handler,
+    ) -> Result<(), BouldyError> {
+        self.stop();
+
+        // A listener being stopped holds the port until its next poll
+        let mut attempts = 0;
+        let listener = loop {
+            match TcpListener::bind(("127.0.0.1", port)) {
+                Ok(listener) => break listener,
+                Err(e) if e.kind() == std::io::ErrorKind::AddrInUse && attempts < 5 => {
+                    attempts += 1;
+                    thread::sleep(POLL_INTERVAL);
+                }
+                Err(e) => {
+                    return Err(BouldyError::io(
+                        &format!("Failed to listen on port {}", port),
+                        e,
+                    ))
+                }
+            }
+        };
+        listener.set_nonblocking(true).map_err(|e| {
+            BouldyError::io(&format!("Failed to configure {} listener", config.name), e)
+        })?;
+        // Make sure a token exists before a client asks for it
+        (config.token)()?;
+
+        let stopped = Arc::new(AtomicBool::new(false));
+        *self.running.lock().unwrap_or_else(|e| e.into_inner()) = Some(stopped.clone());
+
+        let app = app.clone();
+        let config = Arc::new(config);
+        thread::spawn(move || {
+            while !stopped.load(Ordering::Relaxed) {
+                match listener.accept() {
+                    // Each request on its own thread, so a slow or stalled
+                    // client doesn't hold up the rest
+                    Ok((stream, _)) => {
+                        let app = app.clone();
+                        let config = config.clone();
+                        thread::spawn(move || handle_connection(&app, &config, handler, stream));
+                    }
+                    Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                        thread::sleep(POLL_INTERVAL)
+                    }
+                    Err(e) => {
+                        tracing::warn!("Connection to {} failed - {}", config.name, e);
+                        thread::sleep(POLL_INTERVAL);
+                    }
+                }
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Stop the listener, if it's running. The port is released within a poll.
+    pub fn stop(&self) {
+        let running = self
+            .running
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        if let Some(stopped) = running {
+            stopped.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+// Compare without bailing out at the first differing byte
+fn token_matches(expected: &str, given: &str) -> bool {
+    expected.len() == given.len()
+        && expected
+            .bytes()
+            .zip(given.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn read_request(stream: &mut TcpStream) -> Result<Request, String> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+
+    let header_end = loop {
+        if let Some(end) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            break end + 4;
+        }
+        if buffer.len() > MAX_HEADER_BYTES {
+            return Err("Headers too large".to_string());
+        }
+        let read = stream.read(&mut chunk).map_err(|e| e.to_string())?;
+        if read == 0 {
+            return Err("Connection closed".to_string());
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default().to_string();
+    let path = request_line.next().unwrap_or_default().to_string();
+
+    let mut content_length = 0;
+    let mut authorization = None;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        match name.trim().to_ascii_lowercase().as_str() {
+            "content-length" => content_length = value.trim().parse().unwrap_or(0),
+            "authorization" => authorization = Some(value.trim().to_string()),
+            _ => {}
+        }
+    }
+    if content_length > MAX_BODY_BYTES {
+        return Err("Body too large".to_string());
+    }
+
+    let mut body = buffer[header_end..].to_vec();
+    while body.len() < content_length {
+        let read = stream.read(&mut chunk).map_err(|e| e.to_string())?;
+        if read == 0 {
+            break;
+        }
+        body.extend_from_slice(&chunk[..read]);
+    }
+    body.truncate(content_length);
+
+    Ok(Request {
+        method,
+        path,
+        authorization,
+        body,
+    })
+}
+
+fn respond(stream: &mut TcpStream, config: &ServerConfig, response: Response) {
+    let body = response
+        .body
+        .filter(|body| !body.is_null())
+        .map(|body| body.to_string())
+        .unwrap_or_default();
+    let cors = if config.cors {
+        "Access-Control-Allow-Origin: *\r\n\
+         Access-Control-Allow-Methods: GET, POST, OPTIONS\r\n\
+         Access-Control-Allow-Headers: Authorization, Content-Type\r\n"
+    } else {
+        ""
+    };
+    let response = format!(
+        "HTTP/1.1 {}\r\n\
+         {}\
+         Content-Type: application/json\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\r\n{}",
+        response.status,
+        cors,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes());
+}
+
+fn handle_connection(
+    app: &AppHandle,
+    config: &ServerConfig,
+    handler: Handler,
+    mut stream: TcpStream,
+) {
+    let _ = stream.set_nonblocking(false);
+    let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+
+    let request = match read_request(&mut stream) {
+        Ok(request) => request,
+        Err(e) => {
+            respond(&mut stream, config, Response::error("400 Bad Request", &e));
+            return;
+        }
+    };
+
+    // Preflight requests carry no token
+    if config.cors && request.method == "OPTIONS" {
+        let response = Response {
+            status: "204 No Content",
+            body: None,
+        };
+        respond(&mut stream, config, response);
+        return;
+    }
+
+    let authorized = (config.token)().is_ok_and(|token| {
+        request
+            .authorization
+            .as_deref()
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|given| token_matches(&token, given.trim()))
+    });
+    if !authorized {
+        respond(
+            &mut stream,
+            config,
+            Response::error("401 Unauthorized", "Invalid token"),
+        );
+        return;
+    }
+
+    let response = handler(app, request);
+    respond(&mut stream, config, response);
+}
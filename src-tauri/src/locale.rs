@@ -0,0 +1,550 @@
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::Serialize;
+
+use crate::error::BouldyError;
+use crate::todos::Due;
+
+// Used when neither the setting nor the system names a locale
+pub const DEFAULT_LOCALE: &str = "en-US";
+
+// Regions whose calendars start the week on Sunday
+const SUNDAY_REGIONS: &[&str] = &["US", "CA", "MX", "BR", "JP", "KR", "TW", "IL", "PH", "IN"];
+const MDY_REGIONS: &[&str] = &["US", "PH"];
+const YMD_REGIONS: &[&str] = &["CN", "JP", "KR", "TW", "HU", "LT"];
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WeekStart {
+    #[serde(rename = "monday")]
+    Monday,
+    #[serde(rename = "sunday")]
+    Sunday,
+}
+
+impl WeekStart {
+    pub fn weekday(self) -> Weekday {
+        match self {
+            WeekStart::Monday => Weekday::Mon,
+            WeekStart::Sunday => Weekday::Sun,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DateOrder {
+    #[serde(rename = "dmy")]
+    Dmy,
+    #[serde(rename = "mdy")]
+    Mdy,
+    #[serde(rename = "ymd")]
+    Ymd,
+}
+
+// Names and words for one language. Patterns fill in {weekday}, {day},
+// {month} and {year}.
+struct Language {
+    code: &'static str,
+    months: [&'static str; 12],
+    // Monday first
+    weekdays: [&'static str; 7],
+    today: &'static str,
+    tomorrow: &'static str,
+    yesterday: &'static str,
+    long_date: &'static str,
+    month_day: &'static str,
+    date: &'static str,
+}
+
+const LANGUAGES: &[Language] = &[
+    Language {
+        code: "en",
+        months: [
+            "January",
+            "February",
+            "March",
+            "April",
+            "May",
+            "June",
+            "July",
+            "August",
+            "September",
+            "October",
+            "November",
+            "December",
+        ],
+        weekdays: [
+            "Monday",
+            "Tuesday",
+            "Wednesday",
+            "Thursday",
+            "Friday",
+            "Saturday",
+            "Sunday",
+        ],
+        today: "today",
+        tomorrow: "tomorrow",
+        yesterday: "yesterday",
+        long_date: "{weekday}, {month} {day}, {year}",
+        month_day: "{month} {day}",
+        date: "{month} {day}, {year}",
+    },
+    Language {
+        code: "de",
+        months: [
+            "Januar",
+            "Februar",
+            "März",
+            "April",
+            "Mai",
+            "Juni",
+            "Juli",
+            "August",
+            "September",
+            "Oktober",
+            "November",
+            "Dezember",
+        ],
+        weekdays: [
+            "Montag",
+            "Dienstag",
+            "Mittwoch",
+            "Donnerstag",
+            "Freitag",
+            "Samstag",
+            "Sonntag",
+        ],
+        today: "heute",
+        tomorrow: "morgen",
+        yesterday: "gestern",
+        long_date: "{weekday}, {day}. {month} {year}",
+        month_day: "{day}. {month}",
+        date: "{day}. {month} {year}",
+    },
+    Language {
+        code: "fr",
+        months: [
+            "janvier",
+            "février",
+            "mars",
+            "avril",
+            "mai",
+            "juin",
+            "juillet",
+            "août",
+            "septembre",
+            "octobre",
+            "novembre",
+            "décembre",
+        ],
+        weekdays: [
+            "lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche",
+        ],
+        today: "aujourd'hui",
+        tomorrow: "demain",
+        yesterday: "hier",
+        long_date: "{weekday} {day} {month} {year}",
+        month_day: "{day} {month}",
+        date: "{day} {month} {year}",
+    },
+    Language {
+        code: "es",
+        months: [
+            "enero",
+            "febrero",
+            "marzo",
+            "abril",
+            "mayo",
+            "junio",
+            "julio",
+            "agosto",
+            "septiembre",
+            "octubre",
+            "noviembre",
+            "diciembre",
+        ],
+        weekdays: [
+            "lunes",
+            "martes",
+            "miércoles",
+            "jueves",
+            "viernes",
+            "sábado",
+            "domingo",
+        ],
+        today: "hoy",
+        tomorrow: "mañana",
+        yesterday: "ayer",
+        long_date: "{weekday}, {day} de {month} de {year}",
+        month_day: "{day} de {month}",
+        date: "{day} de {month} de {year}",
+    },
+    Language {
+        code: "pt",
+        months: [
+            "janeiro",
+            "fevereiro",
+            "março",
+            "abril",
+            "maio",
+            "junho",
+            "julho",
+            "agosto",
+            "setembro",
+            "outubro",
+            "novembro",
+            "dezembro",
+        ],
+        weekdays: [
+            "segunda-feira",
+            "terça-feira",
+            "quarta-feira",
+            "quinta-feira",
+            "sexta-feira",
+            "sábado",
+            "domingo",
+        ],
+        today: "hoje",
+        tomorrow: "amanhã",
+        yesterday: "ontem",
+        long_date: "{weekday}, {day} de {month} de {year}",
+        month_day: "{day} de {month}",
+        date: "{day} de {month} de {year}",
+    },
+    Language {
+        code: "it",
+        months: [
+            "gennaio",
+            "febbraio",
+            "marzo",
+            "aprile",
+            "maggio",
+            "giugno",
+            "luglio",
+            "agosto",
+            "settembre",
+            "ottobre",
+            "novembre",
+            "dicembre",
+        ],
+        weekdays: [
+            "lunedì",
+            "martedì",
+            "mercoledì",
+            "giovedì",
+            "venerdì",
+            "sabato",
+            "domenica",
+        ],
+        today: "oggi",
+        tomorrow: "domani",
+        yesterday: "ieri",
+        long_date: "{weekday} {day} {month} {year}",
+        month_day: "{day} {month}",
+        date: "{day} {month} {year}",
+    },
+    Language {
+        code: "nl",
+        months: [
+            "januari",
+            "februari",
+            "maart",
+            "april",
+            "mei",
+            "juni",
+            "juli",
+            "augustus",
+            "september",
+            "oktober",
+            "november",
+            "december",
+        ],
+        weekdays: [
+            "maandag",
+            "dinsdag",
+            "woensdag",
+            "donderdag",
+            "vrijdag",
+            "zaterdag",
+            "zondag",
+        ],
+        today: "vandaag",
+        tomorrow: "morgen",
+        yesterday: "gisteren",
+        long_date: "{weekday} {day} {month} {year}",
+        month_day: "{day} {month}",
+        date: "{day} {month} {year}",
+    },
+];
+
+/// How dates are read and written for the configured locale.
+#[derive(Serialize, Clone, Debug)]
+pub struct LocaleInfo {
+    // Normalized, e.g. "en-GB"
+    pub locale: String,
+    // The language names and words come from; English if not translated
+    pub language: String,
+    #[serde(rename = "weekStart")]
+    pub week_start: WeekStart,
+    #[serde(rename = "dateOrder")]
+    pub date_order: DateOrder,
+    // How numeric dates are shown, e.g. "DD/MM/YYYY"
+    #[serde(rename = "datePattern")]
+    pub date_pattern: String,
+    #[serde(rename = "monthNames")]
+    pub month_names: Vec<String>,
+    // In week order, starting with `weekStart`
+    #[serde(rename = "weekdayNames")]
+    pub weekday_names: Vec<String>,
+}
+
+/// The first day of the week `date` falls in, for weeks starting on
+/// `week_start`.
+pub fn start_of_week(date: NaiveDate, week_start: Weekday) -> NaiveDate {
+    date - Duration::days(date.weekday().days_since(week_start).into())
+}
+
+// "en_GB.UTF-8", "en-gb" and "en-GB" are the same locale
+fn normalize(tag: &str) -> Option<(String, Option<String>)> {
+    let tag = tag.split(['.', '@']).next().unwrap_or_default();
+    let mut parts = tag.split(['-', '_']);
+    let language = parts.next()?.to_lowercase();
+    if !(2..=3).contains(&language.len()) || !language.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let region = match parts.next() {
+        Some(region) if region.len() == 2 && region.chars().all(|c| c.is_ascii_alphabetic()) => {
+            Some(region.to_uppercase())
+        }
+        Some(_) => return None,
+        None => None,
+    };
+    Some((language, region))
+}
+
+/// Reject locale settings that aren't a language with an optional region.
+pub fn validate(tag: &str) -> Result<(), BouldyError> {
+    normalize(tag).map(|_| ()).ok_or_else(|| {
+        BouldyError::invalid_input(format!(
+            "Invalid locale '{}'. Expected e.g. en-US or de",
+            tag
+        ))
+    })
+}
+
+// The system locale from the environment, as on Unix; elsewhere the
+// default until the user picks one
+fn system_locale() -> Option<String> {
+    ["LC_ALL", "LC_TIME", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty() && value != "C" && value != "POSIX")
+}
+
+fn language(code: &str) -> &'static Language {
+    LANGUAGES
+        .iter()
+        .find(|language| language.code == code)
+        .unwrap_or(&LANGUAGES[0])
+}
+
+/// Locale info for the `locale` setting, or the system locale if unset.
+pub fn resolve(setting: Option<&str>) -> LocaleInfo {
+    let (code, region) = setting
+        .map(str::to_string)
+        .or_else(system_locale)
+        .and_then(|tag| normalize(&tag))
+        .or_else(|| normalize(DEFAULT_LOCALE))
+        .unwrap_or_default();
+    // A bare "en" means American English
+    let region = region.or_else(|| (code == "en").then(|| "US".to_string()));
+    let in_regions = |regions: &[&str]| region.as_deref().is_some_and(|r| regions.contains(&r));
+
+    let week_start = if in_regions(SUNDAY_REGIONS) {
+        WeekStart::Sunday
+    } else {
+        WeekStart::Monday
+    };
+    let date_order = if in_regions(MDY_REGIONS) {
+        DateOrder::Mdy
+    } else if in_regions(YMD_REGIONS) {
+        DateOrder::Ymd
+    } else {
+        DateOrder::Dmy
+    };
+    let date_pattern = match date_order {
+        DateOrder::Dmy if code == "de" => "DD.MM.YYYY",
+        DateOrder::Dmy => "DD/MM/YYYY",
+        DateOrder::Mdy => "MM/DD/YYYY",
+        DateOrder::Ymd => "YYYY-MM-DD",
+    };
+
+    let names = language(&code);
+    let weekday_names = (0..7)
+        .map(|offset| {
+            let day = week_start.weekday().num_days_from_monday() as usize + offset;
+            names.weekdays[day % 7].to_string()
+        })
+        .collect();
+    LocaleInfo {
+        locale: match region {
+            Some(ref region) => format!("{}-{}", code, region),
+            None => code.clone(),
+        },
+        language: names.code.to_string(),
+        week_start,
+        date_order,
+        date_pattern: date_pattern.to_string(),
+        month_names: names.months.iter().map(|m| m.to_string()).collect(),
+        weekday_names,
+    }
+}
+
+impl LocaleInfo {
+    fn names(&self) -> &'static Language {
+        language(&self.language)
+    }
+
+    // English outside the US writes the day first
+    fn patterns(&self) -> [&'static str; 3] {
+        let names = self.names();
+        if names.code == "en" && self.date_order != DateOrder::Mdy {
+            [
+                "{weekday} {day} {month} {year}",
+                "{day} {month}",
+                "{day} {month} {year}",
+            ]
+        } else {
+            [names.long_date, names.month_day, names.date]
+        }
+    }
+
+    fn fill(&self, pattern: &str, date: NaiveDate) -> String {
+        let names = self.names();
+        pattern
+            .replace(
+                "{weekday}",
+                names.weekdays[date.weekday().num_days_from_monday() as usize],
+            )
+            .replace("{month}", names.months[date.month0() as usize])
+            .replace("{day}", &date.day().to_string())
+            .replace("{year}", &date.year().to_string())
+    }
+
+    pub fn start_of_week(&self, date: NaiveDate) -> NaiveDate {
+        start_of_week(date, self.week_start.weekday())
+    }
+
+    /// E.g. "Monday, March 9, 2026" or "Montag, 9. März 2026".
+    pub fn format_long(&self, date: NaiveDate) -> String {
+        self.fill(self.patterns()[0], date)
+    }
+
+    /// E.g. "March 9" or "9 March".
+    pub fn format_month_day(&self, date: NaiveDate) -> String {
+        self.fill(self.patterns()[1], date)
+    }
+
+    /// E.g. "March 9, 2026" or "9 March 2026".
+    pub fn format_date(&self, date: NaiveDate) -> String {
+        self.fill(self.patterns()[2], date)
+    }
+
+    pub fn weekday_name(&self, date: NaiveDate) -> String {
+        self.names().weekdays[date.weekday().num_days_from_monday() as usize].to_string()
+    }
+
+    // "12/03/2026", "12.03." or "3-12" in the locale's order; a missing year
+    // is this year's
+    fn parse_numeric(&self, text: &str, today: NaiveDate) -> Option<NaiveDate> {
+        let parts: Vec<u32> = text
+            .split(['/', '.', '-'])
+            .filter(|part| !part.is_empty())
+            .map(|part| part.parse().ok())
+            .collect::<Option<_>>()?;
+        let (year, month, day) = match (self.date_order, parts.as_slice()) {
+            (DateOrder::Ymd, [y, m, d]) => (*y as i32, *m, *d),
+            (DateOrder::Ymd, [m, d]) => (today.year(), *m, *d),
+            (DateOrder::Dmy, [d, m, y]) => (*y as i32, *m, *d),
+            (DateOrder::Dmy, [d, m]) => (today.year(), *m, *d),
+            (DateOrder::Mdy, [m, d, y]) => (*y as i32, *m, *d),
+            (DateOrder::Mdy, [m, d]) => (today.year(), *m, *d),
+            _ => return None,
+        };
+        // Two-digit years are this century's
+        let year = if year < 100 { year + 2000 } else { year };
+        NaiveDate::from_ymd_opt(year, month, day)
+    }
+
+    fn weekday_named(&self, word: &str) -> Option<Weekday> {
+        [self.names(), &LANGUAGES[0]].iter().find_map(|names| {
+            names
+                .weekdays
+                .iter()
+                .position(|name| {
+                    let name = name.to_lowercase();
+                    // Full names and three-letter abbreviations
+                    name == word || (word.chars().count() >= 3 && name.starts_with(word))
+                })
+                .and_then(|day| Weekday::try_from(day as u8).ok())
+        })
+    }
+
+    /// Read a date the way a user would type it: ISO, numeric in the
+    /// locale's order, today/tomorrow/yesterday and weekday names in the
+    /// locale's language or English, "next week", and "in 3 days".
+    pub fn parse_date(&self, text: &str, today: NaiveDate) -> Option<NaiveDate> {
+        let text = text.trim().to_lowercase();
+        if let Ok(date) = NaiveDate::parse_from_str(&text, "%Y-%m-%d") {
+            return Some(date);
+        }
+        if text.starts_with(|c: char| c.is_ascii_digit()) {
+            return self.parse_numeric(&text, today);
+        }
+
+        let names = self.names();
+        let english = &LANGUAGES[0];
+        for language in [names, english] {
+            if text == language.today {
+                return Some(today);
+            }
+            if text == language.tomorrow {
+                return Some(today + Duration::days(1));
+            }
+            if text == language.yesterday {
+                return Some(today - Duration::days(1));
+            }
+        }
+        if text == "next week" {
+            return Some(self.start_of_week(today) + Duration::days(7));
+        }
+        if let Some(rest) = text.strip_prefix("in ") {
+            let (count, unit) = rest.split_once(' ')?;
+            let count: i64 = count.parse().ok()?;
+            return match unit.trim_end_matches('s') {
+                "day" => Some(today + Duration::days(count)),
+                "week" => Some(today + Duration::weeks(count)),
+                _ => None,
+            };
+        }
+
+        // The next such day, today included
+        let weekday = self.weekday_named(text.trim_start_matches("next "))?;
+        Some(today + Duration::days(weekday.days_since(today.weekday()).into()))
+    }
+
+    /// A `due:` value from user input: anything `Due::parse` accepts is kept
+    /// as is, anything `parse_date` understands becomes a plain date.
+    pub fn parse_due(&self, text: &str, today: NaiveDate) -> Result<String, BouldyError> {
+        if let Some(due) = Due::parse(text.trim()) {
+            return Ok(due.format());
+        }
+        self.parse_date(text, today)
+            .map(|date| Due::Date(date).format())
+            .ok_or_else(|| {
+                format!(
+                    "Invalid due date '{}'. Expected a date like {} or a word like tomorrow",
+                    text, self.date_pattern
+                )
+                .into()
+            })
+    }
+}
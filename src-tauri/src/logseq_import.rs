@@ -0,0 +1,422 @@
+use chrono::NaiveDate;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::error::BouldyError;
+use crate::file_drop::{unique_destination, SkippedFile};
+use crate::task_import;
+use crate::todo_store::TodoStore;
+use crate::todos::{self, TodoItem};
+use crate::{markdown, vault, vault_config};
+
+const TASK_MARKERS: &[&str] = &[
+    "TODO",
+    "DOING",
+    "NOW",
+    "LATER",
+    "WAITING",
+    "DONE",
+    "CANCELED",
+    "CANCELLED",
+];
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct LogseqImport {
+    pub notes: Vec<String>,
+    pub todos: usize,
+    pub attachments: usize,
+    pub skipped: Vec<SkippedFile>,
+}
+
+fn property_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    // key:: value
+    REGEX.get_or_init(|| Regex::new(r"^([A-Za-z][\w-]*)::\s*(.*)$").unwrap())
+}
+
+fn block_ref_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"\(\(([0-9a-f-]{36})\)\)").unwrap())
+}
+
+fn page_ref_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"\[\[([^\]]+)\]\]").unwrap())
+}
+
+fn priority_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"\[#([A-Z])\]\s*").unwrap())
+}
+
+// One outline block: its nesting depth and lines with the bullet and
+// indentation removed
+struct Block {
+    depth: usize,
+    lines: Vec<String>,
+}
+
+// Tabs are one level each; Logseq configured for spaces uses two per level
+fn indent_depth(indent: &str) -> usize {
+    indent.chars().filter(|c| *c == '\t').count() + indent.chars().filter(|c| *c == ' ').count() / 2
+}
+
+fn parse_blocks(content: &str) -> Vec<Block> {
+    let mut blocks: Vec<Block> = Vec::new();
+
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        let indent = &line[..line.len() - trimmed.len()];
+
+        if let Some(text) = trimmed
+            .strip_prefix("- ")
+            .or((trimmed == "-").then_some(""))
+        {
+            blocks.push(Block {
+                depth: indent_depth(indent),
+                lines: vec![text.to_string()],
+            });
+        } else if let Some(block) = blocks.last_mut() {
+            block.lines.push(trimmed.to_string());
+        } else if !trimmed.is_empty() {
+            // Text before the first bullet, e.g. page properties
+            blocks.push(Block {
+                depth: 0,
+                lines: vec![trimmed.to_string()],
+            });
+        }
+    }
+
+    blocks
+}
+
+// Logseq file names encode namespaces ("a___b" or "a%2Fb" for "a/b")
+fn page_title(path: &Path) -> String {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let stem = stem.replace("___", "/");
+    percent_encoding::percent_decode_str(&stem)
+        .decode_utf8_lossy()
+        .to_string()
+}
+
+// A `title::` page property overrides the file name
+fn title_property(blocks: &[Block]) -> Option<String> {
+    blocks.first()?.lines.iter().find_map(|line| {
+        let captures = property_regex().captures(line.trim())?;
+        (&captures[1] == "title").then(|| captures[2].trim().to_string())
+    })
+}
+
+fn property_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|item| {
+            item.trim()
+                .trim_start_matches('#')
+                .trim_start_matches("[[")
+                .trim_end_matches("]]")
+                .to_string()
+        })
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+struct Page {
+    source: PathBuf,
+    title: String,
+    blocks: Vec<Block>,
+}
+
+struct Converted {
+    content: String,
+    todos: Vec<TodoItem>,
+}
+
+/// Turn a page's outline into markdown: page properties become
+/// frontmatter, top-level blocks paragraphs, nested blocks list items, and
+/// task blocks checkboxes (collected as todos too).
+fn convert_page(page: &Page, block_text: &HashMap<String, String>) -> Converted {
+    let mut frontmatter = Vec::new();
+    let mut body = Vec::new();
+    let mut todos = Vec::new();
+
+    let mut blocks = page.blocks.iter().peekable();
+    // Nesting level that maps to an unindented list item: children of a
+    // top-level paragraph start at 1, children of a top-level task at 0
+    let mut list_base = 1;
+
+    // The first block holds page properties if every line is one
+    if let Some(first) = blocks.peek() {
+        let all_properties = first
+            .lines
+            .iter()
+            .all(|line| line.trim().is_empty() || property_regex().is_match(line.trim()));
+        if first.depth == 0 && all_properties {
+            for line in &first.lines {
+                let Some(captures) = property_regex().captures(line.trim()) else {
+                    continue;
+                };
+                let (key, value) = (&captures[1], captures[2].trim());
+                match key {
+                    "alias" | "tags" => {
+                        let key = if key == "alias" { "aliases" } else { "tags" };
+                        let items: Vec<String> = property_list(value)
+                            .iter()
+                            .map(|i| markdown::yaml_string(i))
+                            .collect();
+                        frontmatter.push(format!("{}: [{}]", key, items.join(", ")));
+                    }
+                    // Used for the note's name instead
+                    "title" => {}
+                    _ => frontmatter.push(format!("{}: {}", key, markdown::yaml_string(value))),
+                }
+            }
+            blocks.next();
+        }
+    }
+
+    for block in blocks {
+        let mut lines: Vec<String> = block
+            .lines
+            .iter()
+            .filter(|line| !property_regex().is_match(line.trim()))
+            .map(|line| {
+                block_ref_regex()
+                    .replace_all(line, |captures: &regex::Captures| {
+                        block_text
+                            .get(&captures[1])
+                            .cloned()
+                            .unwrap_or_else(|| captures[0].to_string())
+                    })
+                    .to_string()
+            })
+            .collect();
+
+        // Planning lines belong to the task, not the text
+        let mut due = None;
+        lines.retain(|line| {
+            let planning: Vec<_> = task_import::org_planning_regex()
+                .captures_iter(line)
+                .map(|c| {
+                    (
+                        &c[1] == "DEADLINE",
+                        task_import::org_due(&c[2], c.get(3).map(|t| t.as_str())),
+                    )
+                })
+                .collect();
+            if planning.is_empty() {
+                return true;
+            }
+            for (deadline, date) in planning {
+                if deadline || due.is_none() {
+                    due = Some(date);
+                }
+            }
+            false
+        });
+
+        let Some(first) = lines.first().cloned() else {
+            continue;
+        };
+        let mut first = first;
+
+        let marker = TASK_MARKERS
+            .iter()
+            .find(|marker| first.starts_with(&format!("{} ", marker)));
+        if let Some(marker) = marker {
+            let text = first[marker.len() + 1..].to_string();
+            let priority = priority_regex()
+                .captures(&text)
+                .map(|captures| captures[1].to_string());
+            let text = priority_regex().replace(&text, "").to_string();
+            let completed = matches!(*marker, "DONE" | "CANCELED" | "CANCELLED");
+
+            let plain = page_ref_regex().replace_all(&text, "$1");
+            let mut todo = task_import::todo_from_text(&plain);
+            todo.completed = completed;
+            if priority.is_some() {
+                todo.priority = priority;
+            }
+            if due.is_some() {
+                todo.due_date = due.take();
+            }
+            if !todo.title.is_empty() {
+                todos.push(todo);
+            }
+
+            first = format!("[{}] {}", if completed { "x" } else { " " }, text);
+        }
+
+        if block.depth == 0 && marker.is_none() {
+            // A top-level block is a paragraph of its own
+            body.push(String::new());
+            body.push(first);
+            body.extend(lines.into_iter().skip(1));
+            list_base = 1;
+        } else {
+            if block.depth == 0 {
+                body.push(String::new());
+                list_base = 0;
+            }
+            let indent = "  ".repeat(block.depth.saturating_sub(list_base));
+            body.push(format!("{}- {}", indent, first));
+            for line in lines.into_iter().skip(1) {
+                body.push(format!("{}  {}", indent, line));
+            }
+        }
+    }
+
+    let mut content = String::new();
+    if !frontmatter.is_empty() {
+        content.push_str(&format!("---\n{}\n---\n", frontmatter.join("\n")));
+    }
+    content.push_str(&format!("# {}\n", page.title));
+    content.push_str(&body.join("\n"));
+    content.push('\n');
+
+    Converted { content, todos }
+}
+
+fn markdown_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| vault::is_markdown_file(path))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Convert a Logseq graph into bouldy notes. Journal pages are named by
+/// date, block references are inlined, task blocks become checkboxes and
+/// are appended to todo.txt, and the graph's assets/ are copied into the
+/// vault's attachments folder. Pages whose name is already taken are
+/// skipped.
+pub fn import_logseq(
+    store: &TodoStore,
+    graph_path: &str,
+    vault_path: &str,
+) -> Result<LogseqImport, BouldyError> {
+    let graph = Path::new(graph_path);
+    let journals_dir = graph.join("journals");
+    let pages_dir = graph.join("pages");
+    if !journals_dir.is_dir() && !pages_dir.is_dir() {
+        return Err(BouldyError::invalid_path(format!(
+            "'{}' is not a Logseq graph (no journals/ or pages/ folder)",
+            graph_path
+        )));
+    }
+
+    let mut pages = Vec::new();
+    for path in markdown_files(&journals_dir) {
+        // 2024_01_15.md -> 2024-01-15
+        let stem = page_title(&path);
+        let title = NaiveDate::parse_from_str(&stem, "%Y_%m_%d")
+            .map(|date| date.format("%Y-%m-%d").to_string())
+            .unwrap_or(stem);
+        pages.push((path, title));
+    }
+    for path in markdown_files(&pages_dir) {
+        let title = page_title(&path);
+        pages.push((path, title));
+    }
+
+    let mut report = LogseqImport::default();
+    let mut parsed = Vec::new();
+    for (source, title) in pages {
+        match fs::read_to_string(&source) {
+            Ok(content) => {
+                let blocks = parse_blocks(&content);
+                parsed.push(Page {
+                    title: title_property(&blocks).unwrap_or(title),
+                    blocks,
+                    source,
+                })
+            }
+            Err(e) => report.skipped.push(SkippedFile {
+                path: source.to_string_lossy().to_string(),
+                reason: format!("Failed to read file: {}", e),
+            }),
+        }
+    }
+
+    // Block ids, so ((uuid)) references can be inlined
+    let mut block_text = HashMap::new();
+    for page in &parsed {
+        for block in &page.blocks {
+            let id = block.lines.iter().find_map(|line| {
+                let captures = property_regex().captures(line.trim())?;
+                (&captures[1] == "id").then(|| captures[2].trim().to_string())
+            });
+            if let (Some(id), Some(text)) = (id, block.lines.first()) {
+                block_text.insert(id, text.clone());
+            }
+        }
+    }
+
+    let mut todos = Vec::new();
+    for page in &parsed {
+        let converted = convert_page(page, &block_text);
+        // Namespaced pages ("a/b") can't keep the slash in a file name
+        let file_name = vault::sanitize_file_name(&format!("{}.md", page.title));
+        let title = file_name.trim_end_matches(".md");
+
+        match vault::create_note(vault_path, title, &converted.content) {
+            Ok(path) => {
+                report.notes.push(path.to_string_lossy().to_string());
+                todos.extend(converted.todos);
+            }
+            Err(e) => report.skipped.push(SkippedFile {
+                path: page.source.to_string_lossy().to_string(),
+                reason: e.to_string(),
+            }),
+        }
+    }
+
+    report.todos = todos.len();
+    if !todos.is_empty() {
+        store.mutate(vault_path, |existing| {
+            for todo in todos {
+                todos::push(existing, todo);
+            }
+            Ok(())
+        })?;
+    }
+
+    // Pages link to ../assets/..., which matches bouldy's default layout
+    let assets = graph.join("assets");
+    if let Ok(entries) = fs::read_dir(&assets) {
+        let config = vault_config::load(vault_path)?;
+        let target = config.attachments_dir(vault_path, None);
+        fs::create_dir_all(&target)
+            .map_err(|e| BouldyError::io("Failed to create attachments directory", e))?;
+
+        for entry in entries.flatten() {
+            let source = entry.path();
+            if !source.is_file() {
+                continue;
+            }
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let dest = unique_destination(&target, &file_name);
+            match fs::copy(&source, &dest) {
+                Ok(_) => report.attachments += 1,
+                Err(e) => report.skipped.push(SkippedFile {
+                    path: source.to_string_lossy().to_string(),
+                    reason: format!("Failed to copy file: {}", e),
+                }),
+            }
+        }
+    }
+
+    Ok(report)
+}
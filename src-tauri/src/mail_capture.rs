@@ -0,0 +1,735 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use rustls::pki_types::ServerName;
+use rustls::{ClientConfig, ClientConnection, RootCertStore, StreamOwned};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::BouldyError;
+use crate::file_drop::{self, unique_destination};
+use crate::todo_store::TodoStore;
+use crate::{
+    fsutil, html_markdown, markdown, secrets, settings, task_import, todos, vault, vault_config,
+    vault_lock,
+};
+
+pub const SECRET_SERVICE: &str = "bouldy-mail";
+pub const INBOX_DIR: &str = "inbox";
+pub const DEFAULT_IMAP_PORT: u16 = 993;
+pub const MIN_POLL_INTERVAL_MINUTES: u32 = 5;
+
+const IO_TIMEOUT: Duration = Duration::from_secs(30);
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+// Enough for a busy label; the rest are picked up next time
+const MAX_MESSAGES_PER_FETCH: usize = 50;
+
+// The manual trigger and the poller must not capture the same message twice
+static FETCHING: Mutex<()> = Mutex::new(());
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CaptureAs {
+    // One note per email in `inbox/`, attachments alongside
+    #[default]
+    #[serde(rename = "note")]
+    Note,
+    // The subject becomes a todo
+    #[serde(rename = "todo")]
+    Todo,
+}
+
+/// IMAP account settings for email capture. The password lives in the OS
+/// keyring, never in settings.json.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MailCaptureSettings {
+    #[serde(default)]
+    pub host: Option<String>,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    #[serde(default)]
+    pub username: Option<String>,
+    // Folder to watch; on Gmail a label is a folder too
+    #[serde(default = "default_mailbox")]
+    pub mailbox: String,
+    // Only capture mail sent to this address, e.g. a `you+bouldy@` alias
+    #[serde(default)]
+    pub recipient: Option<String>,
+    #[serde(rename = "captureAs", default)]
+    pub capture_as: CaptureAs,
+    // Background polling period; unset leaves fetching to the command
+    #[serde(rename = "pollIntervalMinutes", default)]
+    pub poll_interval_minutes: Option<u32>,
+}
+
+impl Default for MailCaptureSettings {
+    fn default() -> Self {
+        Self {
+            host: None,
+            port: default_port(),
+            username: None,
+            mailbox: default_mailbox(),
+            recipient: None,
+            capture_as: CaptureAs::default(),
+            poll_interval_minutes: None,
+        }
+    }
+}
+
+fn default_port() -> u16 {
+    DEFAULT_IMAP_PORT
+}
+
+fn default_mailbox() -> String {
+    "INBOX".to_string()
+}
+
+impl MailCaptureSettings {
+    pub fn validate(&self) -> Result<(), BouldyError> {
+        if let Some(minutes) = self.poll_interval_minutes {
+            if minutes < MIN_POLL_INTERVAL_MINUTES {
+                return Err(BouldyError::invalid_input(format!(
+                    "Mail poll interval must be at least {} minutes",
+                    MIN_POLL_INTERVAL_MINUTES
+                )));
+            }
+        }
+        if self.mailbox.trim().is_empty() {
+            return Err(BouldyError::invalid_input("Mailbox cannot be empty"));
+        }
+        Ok(())
+    }
+
+    fn account(&self) -> Result<(&str, &str), BouldyError> {
+        match (&self.host, &self.username) {
+            (Some(host), Some(username)) => Ok((host, username)),
+            _ => Err("Email capture is not configured".into()),
+        }
+    }
+
+    /// The keyring account the IMAP password is saved under.
+    pub fn secret_account(&self) -> Result<String, BouldyError> {
+        let (host, username) = self.account()?;
+        Ok(format!("{}@{}", username, host))
+    }
+}
+
+fn password(config: &MailCaptureSettings) -> Result<String, BouldyError> {
+    secrets::get(SECRET_SERVICE, &config.secret_account()?)?
+        .ok_or_else(|| BouldyError::not_found("No mail password saved"))
+}
+
+// === IMAP ===
+
+// One untagged response, with any literals pulled out of the line
+struct Response {
+    line: String,
+    literals: Vec<Vec<u8>>,
+}
+
+struct Imap {
+    stream: BufReader<StreamOwned<ClientConnection, TcpStream>>,
+    next_tag: u32,
+}
+
+// IMAP quoted string
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+impl Imap {
+    fn connect(host: &str, port: u16) -> Result<Self, BouldyError> {
+        let roots = RootCertStore::from_iter(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+        let config =
+            ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+                .with_safe_default_protocol_versions()
+                .map_err(|e| format!("Failed to set up TLS: {}", e))?
+                .with_root_certificates(roots)
+                .with_no_client_auth();
+
+        let server_name = ServerName::try_from(host.to_string()).map_err(|e| {
+            BouldyError::invalid_input(format!("Invalid mail server '{}': {}", host, e))
+        })?;
+        let connection = ClientConnection::new(Arc::new(config), server_name)
+            .map_err(|e| format!("Failed to set up TLS: {}", e))?;
+
+        let socket = TcpStream::connect((host, port))
+            .map_err(|e| BouldyError::io("Failed to connect to mail server", e))?;
+        socket
+            .set_read_timeout(Some(IO_TIMEOUT))
+            .and_then(|()| socket.set_write_timeout(Some(IO_TIMEOUT)))
+            .map_err(|e| BouldyError::io("Failed to connect to mail server", e))?;
+
+        let mut imap = Self {
+            stream: BufReader::new(StreamOwned::new(connection, socket)),
+            next_tag: 1,
+        };
+        // Server greeting
+        let greeting = imap.read_line()?;
+        if !greeting.starts_with("* OK") && !greeting.starts_with("* PREAUTH") {
+            return Err(format!("Unexpected mail server greeting: {}", greeting.trim()).into());
+        }
+        Ok(imap)
+    }
+
+    fn read_line(&mut self) -> Result<String, BouldyError> {
+        let mut line = Vec::new();
+        self.stream
+            .read_until(b'\n', &mut line)
+            .map_err(|e| BouldyError::io("Failed to read from mail server", e))?;
+        if line.is_empty() {
+            return Err("Mail server closed the connection".into());
+        }
+        Ok(String::from_utf8_lossy(&line).into_owned())
+    }
+
+    // A line ending in `{n}` is followed by n bytes of literal data
+    fn literal_size(line: &str) -> Option<usize> {
+        let line = line.trim_end();
+        let start = line.rfind('{')?;
+        line.strip_suffix('}')?[start + 1..].parse().ok()
+    }
+
+    /// Send a command and collect its untagged responses until the tagged
+    /// completion, which must be OK.
+    fn command(&mut self, command: &str) -> Result<Vec<Response>, BouldyError> {
+        let tag = format!("A{:04}", self.next_tag);
+        self.next_tag += 1;
+
+        let stream = self.stream.get_mut();
+        stream
+            .write_all(format!("{} {}\r\n", tag, command).as_bytes())
+            .and_then(|()| stream.flush())
+            .map_err(|e| BouldyError::io("Failed to write to mail server", e))?;
+
+        let mut responses = Vec::new();
+        loop {
+            let mut line = self.read_line()?;
+
+            if let Some(status) = line.strip_prefix(&format!("{} ", tag)) {
+                if status.starts_with("OK") {
+                    return Ok(responses);
+                }
+                // Don't echo the command back; LOGIN carries the password
+                let verb = command.split_whitespace().next().unwrap_or_default();
+                return Err(format!("Mail server rejected {}: {}", verb, status.trim()).into());
+            }
+
+            let mut response = Response {
+                line: String::new(),
+                literals: Vec::new(),
+            };
+            while let Some(size) = Self::literal_size(&line) {
+                let mut literal = vec![0; size];
+                self.stream
+                    .read_exact(&mut literal)
+                    .map_err(|e| BouldyError::io("Failed to read from mail server", e))?;
+                response.line.push_str(&line);
+                response.literals.push(literal);
+                line = self.read_line()?;
+            }
+            response.line.push_str(&line);
+            responses.push(response);
+        }
+    }
+
+    fn login(&mut self, username: &str, password: &str) -> Result<(), BouldyError> {
+        self.command(&format!("LOGIN {} {}", quote(username), quote(password)))
+            .map(|_| ())
+    }
+
+    /// UIDs of the unread messages in `mailbox`, oldest first.
+    fn unseen(&mut self, mailbox: &str, recipient: Option<&str>) -> Result<Vec<u32>, BouldyError> {
+        self.command(&format!("SELECT {}", quote(mailbox)))?;
+
+        let mut search = "UID SEARCH UNSEEN".to_string();
+        if let Some(recipient) = recipient {
+            search.push_str(&format!(" TO {}", quote(recipient)));
+        }
+        let mut uids: Vec<u32> = self
+            .command(&search)?
+            .iter()
+            .filter_map(|response| response.line.strip_prefix("* SEARCH"))
+            .flat_map(|ids| {
+                ids.split_whitespace()
+                    .filter_map(|id| id.parse().ok())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        uids.sort_unstable();
+        Ok(uids)
+    }
+
+    /// The raw message, without marking it read.
+    fn fetch(&mut self, uid: u32) -> Result<Vec<u8>, BouldyError> {
+        self.command(&format!("UID FETCH {} BODY.PEEK[]", uid))?
+            .into_iter()
+            .find_map(|response| response.literals.into_iter().next())
+            .ok_or_else(|| BouldyError::not_found(format!("Message {} not found", uid)))
+    }
+
+    fn mark_seen(&mut self, uid: u32) -> Result<(), BouldyError> {
+        self.command(&format!("UID STORE {} +FLAGS.SILENT (\\Seen)", uid))
+            .map(|_| ())
+    }
+
+    fn logout(mut self) {
+        let _ = self.command("LOGOUT");
+    }
+}
+
+// === MIME ===
+
+struct Part<'a> {
+    headers: Vec<(String, String)>,
+    body: &'a [u8],
+}
+
+impl Part<'_> {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    // The media type, lowercased, and the full header for its parameters
+    fn content_type(&self) -> (String, &str) {
+        let header = self.header("content-type").unwrap_or("text/plain");
+        let media_type = header
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_lowercase();
+        (media_type, header)
+    }
+
+    fn decoded_body(&self) -> Vec<u8> {
+        match self
+            .header("content-transfer-encoding")
+            .map(|encoding| encoding.trim().to_lowercase())
+            .as_deref()
+        {
+            Some("base64") => {
+                let compact: Vec<u8> = self
+                    .body
+                    .iter()
+                    .copied()
+                    .filter(|b| !b.is_ascii_whitespace())
+                    .collect();
+                STANDARD.decode(compact).unwrap_or_default()
+            }
+            Some("quoted-printable") => decode_quoted_printable(self.body, false),
+            _ => self.body.to_vec(),
+        }
+    }
+
+    fn text(&self) -> String {
+        let (_, header) = self.content_type();
+        decode_charset(
+            &self.decoded_body(),
+            &header_param(header, "charset").unwrap_or_default(),
+        )
+    }
+
+    fn file_name(&self) -> Option<String> {
+        self.header("content-disposition")
+            .and_then(|disposition| header_param(disposition, "filename"))
+            .or_else(|| {
+                let (_, header) = self.content_type();
+                header_param(header, "name")
+            })
+            .map(|name| decode_words(&name))
+            .filter(|name| !name.trim().is_empty())
+    }
+}
+
+fn parse_part(raw: &[u8]) -> Part<'_> {
+    let (head, body) = match raw.windows(4).position(|w| w == b"\r\n\r\n") {
+        Some(end) => (&raw[..end], &raw[end + 4..]),
+        None => match raw.windows(2).position(|w| w == b"\n\n") {
+            Some(end) => (&raw[..end], &raw[end + 2..]),
+            None => (raw, &raw[raw.len()..]),
+        },
+    };
+
+    // Unfold continuation lines
+    let mut headers: Vec<(String, String)> = Vec::new();
+    for line in String::from_utf8_lossy(head).lines() {
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+        }
+    }
+
+    Part { headers, body }
+}
+
+// `name=value` or `name="value"` from a structured header
+fn header_param(header: &str, name: &str) -> Option<String> {
+    header.split(';').skip(1).find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        let key = key.trim().to_lowercase();
+        // RFC 2231 `filename*=utf-8''...`
+        if key == format!("{}*", name) {
+            let value = value.trim().trim_matches('"');
+            let encoded = value.splitn(3, '\'').nth(2).unwrap_or(value);
+            return Some(
+                percent_encoding::percent_decode_str(encoded)
+                    .decode_utf8_lossy()
+                    .into_owned(),
+            );
+        }
+        (key == name).then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+fn decode_quoted_printable(input: &[u8], header: bool) -> Vec<u8> {
+    let mut output = Vec::with_capacity(input.len());
+    let mut i = 0;
+    while i < input.len() {
+        match input[i] {
+            b'=' if input.get(i + 1) == Some(&b'\r') && input.get(i + 2) == Some(&b'\n') => i += 3,
+            b'=' if input.get(i + 1) == Some(&b'\n') => i += 2,
+            b'=' if i + 2 < input.len() => {
+                let hex = std::str::from_utf8(&input[i + 1..i + 3]).ok();
+                match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    Some(byte) => {
+                        output.push(byte);
+                        i += 3;
+                    }
+                    None => {
+                        output.push(b'=');
+                        i += 1;
+                    }
+                }
+            }
+            // Encoded words use `_` for spaces
+            b'_' if header => {
+                output.push(b' ');
+                i += 1;
+            }
+            byte => {
+                output.push(byte);
+                i += 1;
+            }
+        }
+    }
+    output
+}
+
+// UTF-8 and Latin-1 cover nearly all mail; anything else is read as UTF-8
+fn decode_charset(bytes: &[u8], charset: &str) -> String {
+    match charset.trim().to_lowercase().as_str() {
+        "iso-8859-1" | "latin1" | "windows-1252" | "cp1252" => {
+            bytes.iter().map(|&b| b as char).collect()
+        }
+        _ => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+/// Decode RFC 2047 `=?charset?B|Q?text?=` words in a header value.
+fn decode_words(value: &str) -> String {
+    let mut output = String::new();
+    let mut rest = value;
+    let mut after_word = false;
+
+    while let Some(start) = rest.find("=?") {
+        let decoded = rest[start + 2..].splitn(3, '?').collect::<Vec<_>>();
+        let (Some(charset), Some(encoding), Some(tail)) =
+            (decoded.first(), decoded.get(1), decoded.get(2))
+        else {
+            break;
+        };
+        let Some(end) = tail.find("?=") else {
+            break;
+        };
+        let text = &tail[..end];
+        let bytes = match encoding.to_ascii_uppercase().as_str() {
+            "B" => STANDARD.decode(text).unwrap_or_default(),
+            "Q" => decode_quoted_printable(text.as_bytes(), true),
+            _ => break,
+        };
+
+        // Whitespace between two encoded words is dropped
+        let between = &rest[..start];
+        if !(after_word && between.trim().is_empty()) {
+            output.push_str(between);
+        }
+        output.push_str(&decode_charset(&bytes, charset));
+        after_word = true;
+
+        let consumed = start + 2 + charset.len() + 1 + encoding.len() + 1 + end + 2;
+        rest = &rest[consumed..];
+    }
+
+    output.push_str(rest);
+    output
+}
+
+struct Attachment {
+    name: String,
+    data: Vec<u8>,
+}
+
+#[derive(Default)]
+struct Message {
+    subject: String,
+    from: String,
+    date: Option<String>,
+    plain: Option<String>,
+    html: Option<String>,
+    attachments: Vec<Attachment>,
+}
+
+fn walk(part: &Part, message: &mut Message) {
+    let (media_type, header) = part.content_type();
+
+    if media_type.starts_with("multipart/") {
+        let Some(boundary) = header_param(header, "boundary") else {
+            return;
+        };
+        let delimiter = format!("--{}", boundary);
+        let body = String::from_utf8_lossy(part.body);
+        // Offsets into the original bytes, so binary parts stay intact
+        let mut offsets = Vec::new();
+        let mut offset = 0;
+        for line in body.split_inclusive('\n') {
+            if line.trim_end().starts_with(&delimiter) {
+                offsets.push((offset, offset + line.len(), line.trim_end().ends_with("--")));
+            }
+            offset += line.len();
+        }
+        for pair in offsets.windows(2) {
+            let (_, start, closing) = pair[0];
+            let (end, _, _) = pair[1];
+            if closing || start > end || end > part.body.len() {
+                continue;
+            }
+            let raw = &part.body[start..end];
+            let raw = raw
+                .strip_suffix(b"\r\n")
+                .or_else(|| raw.strip_suffix(b"\n"))
+                .unwrap_or(raw);
+            walk(&parse_part(raw), message);
+        }
+        return;
+    }
+
+    if media_type == "message/rfc822" {
+        walk(&parse_part(part.body), message);
+        return;
+    }
+
+    let attached = part
+        .header("content-disposition")
+        .is_some_and(|d| d.trim().to_lowercase().starts_with("attachment"));
+    match (media_type.as_str(), part.file_name()) {
+        ("text/plain", None) if !attached && message.plain.is_none() => {
+            message.plain = Some(part.text())
+        }
+        ("text/html", None) if !attached && message.html.is_none() => {
+            message.html = Some(part.text())
+        }
+        (_, Some(name)) => message.attachments.push(Attachment {
+            name,
+            data: part.decoded_body(),
+        }),
+        _ => {}
+    }
+}
+
+fn parse_message(raw: &[u8]) -> Message {
+    let top = parse_part(raw);
+    let mut message = Message {
+        subject: decode_words(top.header("subject").unwrap_or_default())
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" "),
+        from: decode_words(top.header("from").unwrap_or_default()),
+        date: top.header("date").map(|date| date.to_string()),
+        ..Default::default()
+    };
+    walk(&top, &mut message);
+    message
+}
+
+// === Capture ===
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct MailCaptureReport {
+    pub notes: Vec<String>,
+    pub todos: usize,
+    pub attachments: usize,
+    pub errors: Vec<String>,
+}
+
+fn message_body(message: &Message) -> String {
+    match (&message.plain, &message.html) {
+        (Some(plain), _) if !plain.trim().is_empty() => {
+            plain.replace("\r\n", "\n").trim().to_string()
+        }
+        (_, Some(html)) => html_markdown::convert(html, None),
+        _ => String::new(),
+    }
+}
+
+fn save_note(
+    vault_path: &str,
+    message: &Message,
+    report: &mut MailCaptureReport,
+) -> Result<PathBuf, BouldyError> {
+    let title = if message.subject.is_empty() {
+        format!("Email {}", chrono::Local::now().format("%Y-%m-%d %H%M"))
+    } else {
+        message.subject.clone()
+    };
+
+    let dir = vault::notes_dir(vault_path).join(INBOX_DIR);
+    let config = vault_config::load(vault_path)?;
+    let _lock = vault_lock::acquire(vault_path)?;
+    fs::create_dir_all(&dir).map_err(|e| BouldyError::io("Failed to create inbox folder", e))?;
+    let path = unique_destination(&dir, &vault::sanitize_file_name(&format!("{}.md", title)));
+
+    let mut links = Vec::new();
+    if !message.attachments.is_empty() {
+        let assets_dir = config.attachments_dir(vault_path, Some(&path));
+        fs::create_dir_all(&assets_dir)
+            .map_err(|e| BouldyError::io("Failed to create attachments folder", e))?;
+        for attachment in &message.attachments {
+            let dest =
+                unique_destination(&assets_dir, &vault::sanitize_file_name(&attachment.name));
+            if let Err(e) = fs::write(&dest, &attachment.data) {
+                report.errors.push(format!("{}: {}", attachment.name, e));
+                continue;
+            }
+            links.push(config.attachment_link(&dest, &dir, file_drop::is_image(&dest)));
+            report.attachments += 1;
+        }
+    }
+
+    let mut content = format!(
+        "---\ntitle: {}\nfrom: {}\n",
+        markdown::yaml_string(&title),
+        markdown::yaml_string(&message.from)
+    );
+    if let Some(ref date) = message.date {
+        content.push_str(&format!("sent: {}\n", markdown::yaml_string(date)));
+    }
+    content.push_str(&format!(
+        "captured: {}\ntags: [\"email\"]\n---\n\n{}\n",
+        chrono::Local::now().to_rfc3339(),
+        message_body(message)
+    ));
+    if !links.is_empty() {
+        content.push_str(&format!("\n## Attachments\n\n{}\n", links.join("\n")));
+    }
+
+    fsutil::write_atomic(&path, content).map_err(|e| BouldyError::io("Failed to write note", e))?;
+    Ok(path)
+}
+
+/// Capture the unread mail in the configured folder (sent to the
+/// configured address, if set) as inbox notes or todos. Each captured
+/// message is marked read so it isn't captured again; a message that fails
+/// stays unread for the next run.
+pub fn fetch_captures(
+    store: &TodoStore,
+    vault_path: &str,
+    config: &MailCaptureSettings,
+) -> Result<MailCaptureReport, BouldyError> {
+    let _fetching = FETCHING.lock().unwrap_or_else(|e| e.into_inner());
+    let (host, username) = config.account()?;
+
+    let mut imap = Imap::connect(host, config.port)?;
+    imap.login(username, &password(config)?)?;
+    let uids = imap.unseen(&config.mailbox, config.recipient.as_deref())?;
+
+    let mut report = MailCaptureReport::default();
+    for uid in uids.into_iter().take(MAX_MESSAGES_PER_FETCH) {
+        let message = parse_message(&imap.fetch(uid)?);
+
+        let captured = match config.capture_as {
+            CaptureAs::Note => save_note(vault_path, &message, &mut report)
+                .map(|path| report.notes.push(path.to_string_lossy().to_string())),
+            CaptureAs::Todo if message.subject.is_empty() => Err("Email has no subject".into()),
+            CaptureAs::Todo => store
+                .mutate(vault_path, |todos| {
+                    todos::push(todos, task_import::todo_from_text(&message.subject));
+                    Ok(())
+                })
+                .map(|()| report.todos += 1),
+        };
+
+        match captured {
+            Ok(()) => imap.mark_seen(uid)?,
+            Err(e) => report.errors.push(format!("{}: {}", message.subject, e)),
+        }
+    }
+
+    imap.logout();
+    Ok(report)
+}
+
+/// Fetch using the current settings and tell the frontend what arrived.
+pub fn fetch_and_notify(
+    app: &AppHandle,
+    vault_path: &str,
+) -> Result<MailCaptureReport, BouldyError> {
+    let app_settings = settings::load_settings(app)?;
+    let report = fetch_captures(
+        &app.state::<TodoStore>(),
+        vault_path,
+        &app_settings.mail_capture,
+    )?;
+
+    if report.todos > 0 {
+        let _ = app.emit("todos_changed", ());
+    }
+    if !report.notes.is_empty() || report.todos > 0 {
+        let _ = app.emit("mail:captured", &report);
+    }
+
+    Ok(report)
+}
+
+/// Start the background poller. Settings are re-read on every tick, so
+/// changing the interval needs no restart.
+pub fn start(app: AppHandle) {
+    thread::spawn(move || {
+        let mut last_fetch: Option<Instant> = None;
+
+        loop {
+            thread::sleep(CHECK_INTERVAL);
+
+            let Ok(app_settings) = settings::load_settings(&app) else {
+                continue;
+            };
+            let (Some(minutes), Some(vault_path)) = (
+                app_settings.mail_capture.poll_interval_minutes,
+                app_settings.vault_path,
+            ) else {
+                continue;
+            };
+            let interval = Duration::from_secs(u64::from(minutes) * 60);
+            if last_fetch.is_some_and(|at| at.elapsed() < interval) {
+                continue;
+            }
+
+            last_fetch = Some(Instant::now());
+            if let Err(e) = fetch_and_notify(&app, &vault_path) {
+                tracing::warn!("Mail capture failed - {}", e);
+            }
+        }
+    });
+}
@@ -0,0 +1,239 @@
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+use regex::Regex;
+use serde::Serialize;
+use std::sync::OnceLock;
+
+/// Split a note into its YAML frontmatter block (without the `---` fences)
+/// and the body. Notes without frontmatter return `None` and the full text.
+pub fn split_frontmatter(content: &str) -> (Option<&str>, &str) {
+    let Some(rest) = content
+        .strip_prefix("---\n")
+        .or_else(|| content.strip_prefix("---\r\n"))
+    else {
+        return (None, content);
+    };
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            let yaml = &rest[..offset];
+            let body = &rest[offset + line.len()..];
+            return (Some(yaml), body);
+        }
+        offset += line.len();
+    }
+
+    // Unterminated fence: treat the whole thing as body
+    (None, content)
+}
+
+/// Replace `key:` in the frontmatter, adding it (and the frontmatter) if
+/// missing. A block value under the old line (`- item`s) goes with it; other
+/// lines are kept as written.
+pub fn set_frontmatter_field(content: &str, key: &str, value: &str) -> String {
+    let line = if value.is_empty() {
+        format!("{}:", key)
+    } else {
+        format!("{}: {}", key, value)
+    };
+    let prefix = format!("{}:", key);
+
+    let (yaml, body) = split_frontmatter(content);
+    let Some(yaml) = yaml else {
+        return format!("---\n{}\n---\n\n{}", line, content);
+    };
+
+    let mut lines: Vec<String> = yaml.lines().map(|l| l.to_string()).collect();
+    match lines.iter().position(|l| l.starts_with(&prefix)) {
+        Some(index) => {
+            let continued = lines[index + 1..]
+                .iter()
+                .take_while(|l| l.starts_with(' ') || l.starts_with('\t') || l.starts_with('-'))
+                .count();
+            lines.splice(index..=index + continued, [line]);
+        }
+        None => lines.push(line),
+    }
+    format!("---\n{}\n---\n{}", lines.join("\n"), body)
+}
+
+/// Quote a value for a frontmatter line; JSON strings are valid YAML.
+pub fn yaml_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+pub fn parse_frontmatter(content: &str) -> Option<serde_yaml::Mapping> {
+    let (yaml, _) = split_frontmatter(content);
+    match serde_yaml::from_str(yaml?).ok()? {
+        serde_yaml::Value::Mapping(mapping) => Some(mapping),
+        _ => None,
+    }
+}
+
+/// Read a frontmatter field that may be written as a list (`tags: [a, b]`)
+/// or a single string (`tags: a`).
+pub fn frontmatter_list(frontmatter: &serde_yaml::Mapping, key: &str) -> Vec<String> {
+    match frontmatter.get(key) {
+        Some(serde_yaml::Value::Sequence(items)) => items
+            .iter()
+            .filter_map(|item| item.as_str())
+            .map(|item| item.trim().to_string())
+            .filter(|item| !item.is_empty())
+            .collect(),
+        Some(serde_yaml::Value::String(item)) => item
+            .split(',')
+            .map(|item| item.trim().to_string())
+            .filter(|item| !item.is_empty())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Other names a note goes by, from Obsidian's `aliases:` (or the older
+/// `alias:`) frontmatter field.
+pub fn frontmatter_aliases(frontmatter: &serde_yaml::Mapping) -> Vec<String> {
+    let mut aliases = frontmatter_list(frontmatter, "aliases");
+    aliases.extend(frontmatter_list(frontmatter, "alias"));
+    aliases
+}
+
+fn inline_tag_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"(?:^|\s)#([\w][\w/-]*)").unwrap())
+}
+
+fn wikilink_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    // [[Target]], [[Target#Heading]], [[Target|alias]]
+    REGEX.get_or_init(|| Regex::new(r"\[\[([^\]|#]+)(?:#[^\]|]*)?(?:\|[^\]]*)?\]\]").unwrap())
+}
+
+fn markdown_link_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"\]\(<?([^)<>]+?\.md)>?\)").unwrap())
+}
+
+/// Tags from the `tags:` frontmatter field plus inline `#tags` in the body,
+/// deduplicated in order of first appearance.
+pub fn extract_tags(content: &str) -> Vec<String> {
+    let (_, body) = split_frontmatter(content);
+    let mut tags = parse_frontmatter(content)
+        .map(|fm| frontmatter_list(&fm, "tags"))
+        .unwrap_or_default();
+
+    let mut in_code_block = false;
+    for line in body.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+        for capture in inline_tag_regex().captures_iter(line) {
+            tags.push(capture[1].to_string());
+        }
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    tags.retain(|tag| seen.insert(tag.clone()));
+    tags
+}
+
+/// Names of notes linked from `content`, via `[[wikilinks]]` or markdown
+/// links to `.md` files. Returned as note names without the extension.
+pub fn extract_links(content: &str) -> Vec<String> {
+    // Obsidian resolves [[folder/Note]] and [[Note.md]] by file name too
+    let mut links: Vec<String> = wikilink_regex()
+        .captures_iter(content)
+        .map(|capture| {
+            let target = capture[1].trim();
+            let name = target.rsplit('/').next().unwrap_or(target);
+            name.trim_end_matches(".md").to_string()
+        })
+        .collect();
+
+    for capture in markdown_link_regex().captures_iter(content) {
+        let target = &capture[1];
+        if target.contains("://") {
+            continue;
+        }
+        let name = target.rsplit('/').next().unwrap_or(target);
+        let name = percent_encoding::percent_decode_str(name).decode_utf8_lossy();
+        links.push(name.trim_end_matches(".md").to_string());
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    links.retain(|link| !link.is_empty() && seen.insert(link.clone()));
+    links
+}
+
+/// A heading in a note's outline, with the headings nested under it.
+#[derive(Serialize, Clone, Debug)]
+pub struct OutlineHeading {
+    pub level: u8,
+    pub text: String,
+    // Byte range of the heading line(s) in the whole file
+    pub start: usize,
+    pub end: usize,
+    // 1-indexed
+    pub line: usize,
+    pub children: Vec<OutlineHeading>,
+}
+
+// Attach `heading` under the last open heading of a lower level
+fn push_heading(stack: &mut Vec<OutlineHeading>, outline: &mut Vec<OutlineHeading>) {
+    let Some(heading) = stack.pop() else {
+        return;
+    };
+    match stack.last_mut() {
+        Some(parent) => parent.children.push(heading),
+        None => outline.push(heading),
+    }
+}
+
+/// The note's headings as a tree. A `###` straight under a `#` nests under
+/// it; skipped levels don't add empty parents.
+pub fn outline(content: &str) -> Vec<OutlineHeading> {
+    // Without metadata blocks the closing `---` of frontmatter would turn
+    // its last line into a heading
+    let parser = Parser::new_ext(content, Options::ENABLE_YAML_STYLE_METADATA_BLOCKS);
+
+    let mut outline = Vec::new();
+    let mut stack: Vec<OutlineHeading> = Vec::new();
+    let mut current: Option<OutlineHeading> = None;
+    for (event, range) in parser.into_offset_iter() {
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => {
+                current = Some(OutlineHeading {
+                    level: level as u8,
+                    text: String::new(),
+                    start: range.start,
+                    end: range.end,
+                    line: content[..range.start].matches('\n').count() + 1,
+                    children: Vec::new(),
+                });
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some(ref mut heading) = current {
+                    heading.text.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                let Some(mut heading) = current.take() else {
+                    continue;
+                };
+                heading.text = heading.text.trim().to_string();
+                while stack.last().is_some_and(|open| open.level >= heading.level) {
+                    push_heading(&mut stack, &mut outline);
+                }
+                stack.push(heading);
+            }
+            _ => {}
+        }
+    }
+    while !stack.is_empty() {
+        push_heading(&mut stack, &mut outline);
+    }
+    outline
+}
@@ -0,0 +1,221 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::BouldyError;
+use crate::{fsutil, vault, vault_lock};
+
+const MIGRATIONS_DIR: &str = "migrations";
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// One file the migration moves. Paths are relative to the vault so a
+/// manifest still applies after the vault folder itself is moved.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PlannedMove {
+    pub from: String,
+    pub to: String,
+}
+
+/// Written to `.bouldy/migrations/<timestamp>/` before anything moves,
+/// alongside a copy of every file being moved.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MigrationManifest {
+    pub timestamp: String,
+    pub moves: Vec<PlannedMove>,
+    #[serde(rename = "createdNotesDir")]
+    pub created_notes_dir: bool,
+    #[serde(rename = "rolledBack", default)]
+    pub rolled_back: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct MigrationReport {
+    pub moves: Vec<PlannedMove>,
+    #[serde(rename = "dryRun")]
+    pub dry_run: bool,
+    // Where the backup and manifest went, if anything was migrated
+    #[serde(rename = "backupDir")]
+    pub backup_dir: Option<String>,
+}
+
+fn migrations_dir(vault_path: &str) -> PathBuf {
+    Path::new(vault_path).join(".bouldy").join(MIGRATIONS_DIR)
+}
+
+/// Markdown files in the vault root that belong in notes/. Only vaults
+/// without a notes/ folder are migrated.
+pub fn plan(vault_path: &str) -> Result<Vec<PlannedMove>, BouldyError> {
+    let vault = Path::new(vault_path);
+    if vault::notes_dir(vault_path).exists() {
+        return Ok(Vec::new());
+    }
+
+    let entries =
+        fs::read_dir(vault).map_err(|e| BouldyError::io("Failed to read vault directory", e))?;
+
+    let mut moves = Vec::new();
+    for entry in entries {
+        let entry = entry.map_err(|e| BouldyError::io("Failed to read entry", e))?;
+        let path = entry.path();
+        if !path.is_file() || !vault::is_markdown_file(&path) {
+            continue;
+        }
+
+        let file_name = vault::file_name_to_string(&path)?;
+        moves.push(PlannedMove {
+            from: file_name.clone(),
+            to: format!("notes/{}", file_name),
+        });
+    }
+    moves.sort_by(|a, b| a.from.cmp(&b.from));
+
+    Ok(moves)
+}
+
+fn write_manifest(dir: &Path, manifest: &MigrationManifest) -> Result<(), BouldyError> {
+    let content = serde_json::to_string_pretty(manifest)
+        .map_err(|e| format!("Failed to serialize migration manifest: {}", e))?;
+    fsutil::write_atomic(&dir.join(MANIFEST_FILE), content)
+        .map_err(|e| BouldyError::io("Failed to write migration manifest", e))
+}
+
+/// Move root-level notes into notes/. With `dry_run` only the plan is
+/// returned. Otherwise every file is copied into a timestamped backup with a
+/// manifest first, so `rollback` can undo it.
+pub fn migrate(vault_path: &str, dry_run: bool) -> Result<MigrationReport, BouldyError> {
+    let _lock = vault_lock::acquire(vault_path)?;
+
+    let moves = plan(vault_path)?;
+    let notes_dir = vault::notes_dir(vault_path);
+    if dry_run || notes_dir.exists() {
+        return Ok(MigrationReport {
+            moves,
+            dry_run,
+            backup_dir: None,
+        });
+    }
+
+    // Nothing to move, so nothing to back up
+    if moves.is_empty() {
+        fs::create_dir(&notes_dir)
+            .map_err(|e| BouldyError::io("Failed to create notes directory", e))?;
+        return Ok(MigrationReport {
+            moves,
+            dry_run,
+            backup_dir: None,
+        });
+    }
+
+    let vault = Path::new(vault_path);
+    let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let backup_dir = migrations_dir(vault_path).join(&timestamp);
+    fs::create_dir_all(&backup_dir)
+        .map_err(|e| BouldyError::io("Failed to create migration backup", e))?;
+
+    for planned in &moves {
+        fs::copy(vault.join(&planned.from), backup_dir.join(&planned.from))
+            .map_err(|e| BouldyError::io(&format!("Failed to back up {}", planned.from), e))?;
+    }
+
+    write_manifest(
+        &backup_dir,
+        &MigrationManifest {
+            timestamp,
+            moves: moves.clone(),
+            created_notes_dir: true,
+            rolled_back: false,
+        },
+    )?;
+
+    fs::create_dir(&notes_dir)
+        .map_err(|e| BouldyError::io("Failed to create notes directory", e))?;
+
+    for (done, planned) in moves.iter().enumerate() {
+        if let Err(e) = fs::rename(vault.join(&planned.from), vault.join(&planned.to)) {
+            // Put back what already moved rather than leave a half-migrated vault
+            for undo in &moves[..done] {
+                let _ = fs::rename(vault.join(&undo.to), vault.join(&undo.from));
+            }
+            let _ = fs::remove_dir(&notes_dir);
+            return Err(BouldyError::io(
+                &format!("Failed to move {}", planned.from),
+                e,
+            ));
+        }
+    }
+
+    Ok(MigrationReport {
+        moves,
+        dry_run: false,
+        backup_dir: Some(vault::path_to_string(&backup_dir)?),
+    })
+}
+
+// The newest migration that hasn't been rolled back yet
+fn latest_manifest(vault_path: &str) -> Result<(PathBuf, MigrationManifest), BouldyError> {
+    let mut dirs: Vec<PathBuf> = fs::read_dir(migrations_dir(vault_path))
+        .map_err(|e| BouldyError::io("No migration to roll back", e))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.join(MANIFEST_FILE).is_file())
+        .collect();
+    // Timestamped names sort chronologically
+    dirs.sort();
+
+    for dir in dirs.into_iter().rev() {
+        let content = fs::read_to_string(dir.join(MANIFEST_FILE))
+            .map_err(|e| BouldyError::io("Failed to read migration manifest", e))?;
+        let manifest: MigrationManifest = serde_json::from_str(&content)
+            .map_err(|e| BouldyError::parse("Failed to parse migration manifest", e))?;
+        if !manifest.rolled_back {
+            return Ok((dir, manifest));
+        }
+    }
+
+    Err(BouldyError::not_found("No migration to roll back"))
+}
+
+/// Undo the most recent migration using its manifest. Files are moved back
+/// to where they were; any that have since gone missing are restored from
+/// the backup. Returns how many files were put back.
+pub fn rollback(vault_path: &str) -> Result<usize, BouldyError> {
+    let _lock = vault_lock::acquire(vault_path)?;
+
+    let (backup_dir, mut manifest) = latest_manifest(vault_path)?;
+    let vault = Path::new(vault_path);
+
+    // Check everything first so a conflict doesn't leave a partial rollback
+    if let Some(planned) = manifest
+        .moves
+        .iter()
+        .find(|planned| vault.join(&planned.from).exists())
+    {
+        return Err(BouldyError::conflict(format!(
+            "Can't roll back: {} exists again in the vault root",
+            planned.from
+        )));
+    }
+
+    let mut restored = 0;
+    for planned in &manifest.moves {
+        let original = vault.join(&planned.from);
+        let moved = vault.join(&planned.to);
+        let result = if moved.exists() {
+            fs::rename(&moved, &original)
+        } else {
+            fs::copy(backup_dir.join(&planned.from), &original).map(|_| ())
+        };
+        result.map_err(|e| BouldyError::io(&format!("Failed to restore {}", planned.from), e))?;
+        restored += 1;
+    }
+
+    // Only remove notes/ if nothing new was written there since
+    if manifest.created_notes_dir {
+        let _ = fs::remove_dir(vault::notes_dir(vault_path));
+    }
+
+    manifest.rolled_back = true;
+    write_manifest(&backup_dir, &manifest)?;
+
+    Ok(restored)
+}
@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::time::Duration;
+
+use crate::error::BouldyError;
+
+const USER_AGENT: &str = concat!("bouldy/", env!("CARGO_PKG_VERSION"));
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Proxy and TLS settings shared by every outgoing request the backend makes
+/// (updater, sync, LLM providers).
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct NetworkConfig {
+    #[serde(rename = "proxyUrl", default)]
+    pub proxy_url: Option<String>,
+    #[serde(rename = "noProxy", default)]
+    pub no_proxy: Option<String>,
+    #[serde(rename = "caCertPath", default)]
+    pub ca_cert_path: Option<String>,
+}
+
+impl NetworkConfig {
+    pub fn validate(&self) -> Result<(), BouldyError> {
+        if let Some(ref proxy_url) = self.proxy_url {
+            let url = tauri::Url::parse(proxy_url).map_err(|e| {
+                BouldyError::invalid_input(format!("Invalid proxy URL '{}': {}", proxy_url, e))
+            })?;
+
+            if !matches!(url.scheme(), "http" | "https" | "socks5" | "socks5h") {
+                return Err(BouldyError::invalid_input(format!(
+                    "Unsupported proxy scheme '{}'. Use http, https or socks5",
+                    url.scheme()
+                )));
+            }
+        }
+
+        if let Some(ref ca_cert_path) = self.ca_cert_path {
+            load_ca_certificates(ca_cert_path)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn load_ca_certificates(path: &str) -> Result<Vec<reqwest::Certificate>, BouldyError> {
+    let pem = fs::read(path).map_err(|e| format!("Failed to read CA certificate: {}", e))?;
+
+    let certs = reqwest::Certificate::from_pem_bundle(&pem)
+        .map_err(|e| BouldyError::invalid_input(format!("Invalid CA certificate: {}", e)))?;
+
+    if certs.is_empty() {
+        return Err(BouldyError::invalid_input(
+            "CA certificate file contains no certificates",
+        ));
+    }
+
+    Ok(certs)
+}
+
+/// Apply proxy and custom CA settings to a client builder.
+pub fn configure_client(
+    mut builder: reqwest::ClientBuilder,
+    config: &NetworkConfig,
+) -> Result<reqwest::ClientBuilder, BouldyError> {
+    if let Some(ref proxy_url) = config.proxy_url {
+        let no_proxy = config
+            .no_proxy
+            .as_deref()
+            .and_then(reqwest::NoProxy::from_string);
+
+        let proxy = reqwest::Proxy::all(proxy_url)
+            .map_err(|e| format!("Invalid proxy URL '{}': {}", proxy_url, e))?
+            .no_proxy(no_proxy);
+
+        builder = builder.proxy(proxy);
+    }
+
+    if let Some(ref ca_cert_path) = config.ca_cert_path {
+        for cert in load_ca_certificates(ca_cert_path)? {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+
+    Ok(builder)
+}
+
+/// Build the HTTP client every network feature should use.
+pub fn http_client(config: &NetworkConfig) -> Result<reqwest::Client, BouldyError> {
+    let builder = reqwest::Client::builder()
+        .user_agent(USER_AGENT)
+        .timeout(REQUEST_TIMEOUT);
+
+    configure_client(builder, config)?
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e).into())
+}
+
+/// Issue a HEAD request through the configured proxy, returning the status code.
+pub async fn test_connection(config: &NetworkConfig, url: &str) -> Result<u16, BouldyError> {
+    let client = http_client(config)?;
+
+    let response = client
+        .head(url)
+        .send()
+        .await
+        .map_err(|e| format!("Connection failed: {}", e))?;
+
+    Ok(response.status().as_u16())
+}
@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::error::BouldyError;
+use crate::llm::{self, LlmSettings};
+use crate::network::NetworkConfig;
+use crate::{fsutil, markdown, vault, vault_lock};
+
+// Long notes are cut to keep requests fast and cheap
+const MAX_NOTE_CHARS: usize = 12_000;
+const MAX_SUGGESTED_TAGS: usize = 5;
+// The vault's most used tags, offered so suggestions reuse them
+const MAX_KNOWN_TAGS: usize = 100;
+
+const SUMMARY_PROMPT: &str = "You summarize personal notes. Reply with a summary of \
+    one to three sentences in the note's own language, as plain text without \
+    markdown or a preamble.";
+const TAGS_PROMPT: &str = "You tag personal notes. Reply with only a JSON array of \
+    at most 5 short lowercase tags (no # and no spaces; use hyphens). Prefer tags \
+    from the existing list when they fit.";
+
+fn read_body(path: &Path) -> Result<(String, Vec<String>), BouldyError> {
+    let content =
+        fs::read_to_string(path).map_err(|e| BouldyError::io("Failed to read note", e))?;
+    let tags = markdown::extract_tags(&content);
+    let (_, body) = markdown::split_frontmatter(&content);
+    let body: String = body.trim().chars().take(MAX_NOTE_CHARS).collect();
+    if body.is_empty() {
+        return Err(BouldyError::invalid_input("Note is empty"));
+    }
+    Ok((body, tags))
+}
+
+fn note_prompt(path: &Path, body: &str) -> String {
+    format!(
+        "Title: {}\n\n{}",
+        vault::extract_title_from_filename(path),
+        body
+    )
+}
+
+/// Ask the configured model for a short summary of the note at `path`.
+/// Nothing is written until the user accepts it with `accept_summary`.
+pub async fn summarize_note(
+    network_config: &NetworkConfig,
+    settings: &LlmSettings,
+    path: &Path,
+) -> Result<String, BouldyError> {
+    let (body, _) = read_body(path)?;
+    let summary = llm::complete(
+        network_config,
+        settings,
+        SUMMARY_PROMPT,
+        &note_prompt(path, &body),
+    )
+    .await?;
+    // It goes on one frontmatter line
+    Ok(summary.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+fn known_tags(vault_path: &str) -> Vec<String> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for note in vault::list_notes(vault_path).unwrap_or_default() {
+        if let Ok(content) = fs::read_to_string(&note.path) {
+            for tag in markdown::extract_tags(&content) {
+                *counts.entry(tag).or_default() += 1;
+            }
+        }
+    }
+    let mut tags: Vec<(String, usize)> = counts.into_iter().collect();
+    tags.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    tags.into_iter()
+        .take(MAX_KNOWN_TAGS)
+        .map(|(tag, _)| tag)
+        .collect()
+}
+
+fn normalize_tag(tag: &str) -> String {
+    tag.trim()
+        .trim_start_matches('#')
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_lowercase()
+}
+
+// A JSON array is asked for, but models wrap it in prose or code fences
+fn parse_tags(reply: &str) -> Vec<String> {
+    let array = reply
+        .find('[')
+        .zip(reply.rfind(']'))
+        .and_then(|(start, end)| reply.get(start..=end))
+        .and_then(|array| serde_json::from_str::<Vec<String>>(array).ok());
+    array.unwrap_or_else(|| {
+        reply
+            .split([',', '\n'])
+            .map(|tag| tag.trim_start_matches(['-', '*', ' ']).to_string())
+            .collect()
+    })
+}
+
+/// Ask the configured model for tags that fit the note at `path`, leaving
+/// out ones it already has.
+pub async fn suggest_tags(
+    network_config: &NetworkConfig,
+    settings: &LlmSettings,
+    vault_path: &str,
+    path: &Path,
+) -> Result<Vec<String>, BouldyError> {
+    let (body, existing) = read_body(path)?;
+    let known = known_tags(vault_path);
+    let prompt = format!(
+        "Existing tags: {}\n\n{}",
+        known.join(", "),
+        note_prompt(path, &body)
+    );
+    let reply = llm::complete(network_config, settings, TAGS_PROMPT, &prompt).await?;
+
+    let mut tags: Vec<String> = Vec::new();
+    for tag in parse_tags(&reply) {
+        let tag = normalize_tag(&tag);
+        if !tag.is_empty()
+            && !tags.contains(&tag)
+            && !existing.iter().any(|e| e.eq_ignore_ascii_case(&tag))
+        {
+            tags.push(tag);
+        }
+    }
+    tags.truncate(MAX_SUGGESTED_TAGS);
+    Ok(tags)
+}
+
+fn update_note(
+    vault_path: &str,
+    path: &Path,
+    update: impl FnOnce(&str) -> String,
+) -> Result<(), BouldyError> {
+    let _lock = vault_lock::acquire(vault_path)?;
+    let content =
+        fs::read_to_string(path).map_err(|e| BouldyError::io("Failed to read note", e))?;
+    fsutil::write_text_preserving(path, &update(&content))
+        .map_err(|e| BouldyError::io("Failed to write note", e))
+}
+
+/// Write an accepted summary into the note's `summary:` frontmatter field.
+pub fn accept_summary(vault_path: &str, path: &Path, summary: &str) -> Result<(), BouldyError> {
+    let summary = summary.split_whitespace().collect::<Vec<_>>().join(" ");
+    if summary.is_empty() {
+        return Err("Summary is empty".into());
+    }
+    update_note(vault_path, path, |content| {
+        markdown::set_frontmatter_field(content, "summary", &markdown::yaml_string(&summary))
+    })
+}
+
+/// Add accepted tags to the note's `tags:` frontmatter list.
+pub fn accept_tags(vault_path: &str, path: &Path, tags: &[String]) -> Result<(), BouldyError> {
+    update_note(vault_path, path, |content| {
+        let mut all = markdown::parse_frontmatter(content)
+            .map(|frontmatter| markdown::frontmatter_list(&frontmatter, "tags"))
+            .unwrap_or_default();
+        for tag in tags.iter().map(|tag| normalize_tag(tag)) {
+            if !tag.is_empty() && !all.contains(&tag) {
+                all.push(tag);
+            }
+        }
+        let list: Vec<String> = all.iter().map(|tag| markdown::yaml_string(tag)).collect();
+        markdown::set_frontmatter_field(content, "tags", &format!("[{}]", list.join(", ")))
+    })
+}
@@ -0,0 +1,58 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::error::BouldyError;
+use crate::vault::{self, Note};
+
+/// Listing metadata (mtime, title, symlink flag) keyed by path. A listing is
+/// one readdir pass that only stats files not already cached; the watcher
+/// evicts entries as files change, so cached entries are trusted as-is.
+#[derive(Default)]
+pub struct NoteCache {
+    notes: Mutex<HashMap<PathBuf, Note>>,
+}
+
+impl NoteCache {
+    pub fn list(&self, vault_path: &str) -> Result<Vec<Note>, BouldyError> {
+        let read_dir = vault::notes_dir(vault_path);
+        let entries = fs::read_dir(&read_dir)
+            .map_err(|e| format!("Failed to read notes directory: {}", e))?;
+
+        let mut cache = self.notes.lock().unwrap();
+        let mut seen = HashMap::with_capacity(cache.len());
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !vault::is_markdown_file(&path) {
+                continue;
+            }
+
+            let note = match cache.remove(&path) {
+                Some(note) => note,
+                None => match vault::read_note_entry(&path) {
+                    Ok(note) => note,
+                    Err(e) => {
+                        tracing::warn!("Skipping {} - {}", path.display(), e);
+                        continue;
+                    }
+                },
+            };
+            seen.insert(path, note);
+        }
+
+        // Whatever wasn't seen in this pass no longer exists (or belongs to
+        // a previously opened vault)
+        *cache = seen;
+
+        let mut notes: Vec<Note> = cache.values().cloned().collect();
+        notes.sort_by_key(|n| std::cmp::Reverse(n.modified));
+
+        Ok(notes)
+    }
+
+    pub fn invalidate(&self, path: &Path) {
+        self.notes.lock().unwrap().remove(path);
+    }
+}
@@ -0,0 +1,88 @@
+use serde::Serialize;
+use similar::{ChangeTag, TextDiff};
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiffKind {
+    #[serde(rename = "equal")]
+    Equal,
+    #[serde(rename = "insert")]
+    Insert,
+    #[serde(rename = "delete")]
+    Delete,
+}
+
+/// Part of a line; `changed` marks the words that differ from the line it
+/// replaced.
+#[derive(Serialize, Clone, Debug)]
+pub struct DiffSpan {
+    pub text: String,
+    pub changed: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct DiffLine {
+    pub kind: DiffKind,
+    // 1-indexed; `None` on the side the line isn't in
+    #[serde(rename = "oldLine")]
+    pub old_line: Option<usize>,
+    #[serde(rename = "newLine")]
+    pub new_line: Option<usize>,
+    pub spans: Vec<DiffSpan>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct NoteDiff {
+    pub lines: Vec<DiffLine>,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+/// Line diff of `old` against `new`, with word-level highlights inside
+/// lines that were changed rather than added or removed outright.
+pub fn diff_text(old: &str, new: &str) -> NoteDiff {
+    let diff = TextDiff::from_lines(old, new);
+    let mut result = NoteDiff {
+        lines: Vec::new(),
+        insertions: 0,
+        deletions: 0,
+    };
+
+    for op in diff.ops() {
+        for change in diff.iter_inline_changes(op) {
+            let kind = match change.tag() {
+                ChangeTag::Equal => DiffKind::Equal,
+                ChangeTag::Insert => {
+                    result.insertions += 1;
+                    DiffKind::Insert
+                }
+                ChangeTag::Delete => {
+                    result.deletions += 1;
+                    DiffKind::Delete
+                }
+            };
+            let mut spans: Vec<DiffSpan> = change
+                .iter_strings_lossy()
+                .map(|(changed, text)| DiffSpan {
+                    text: text.to_string(),
+                    changed,
+                })
+                .collect();
+            // The line break isn't part of what's shown
+            if let Some(last) = spans.last_mut() {
+                let trimmed = last.text.trim_end_matches(['\n', '\r']).len();
+                last.text.truncate(trimmed);
+                if last.text.is_empty() && spans.len() > 1 {
+                    spans.pop();
+                }
+            }
+
+            result.lines.push(DiffLine {
+                kind,
+                old_line: change.old_index().map(|index| index + 1),
+                new_line: change.new_index().map(|index| index + 1),
+                spans,
+            });
+        }
+    }
+    result
+}
@@ -0,0 +1,116 @@
+use chrono::{Local, NaiveDateTime, TimeZone};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::BouldyError;
+use crate::fsutil;
+
+const HISTORY_DIR: &str = "history";
+const VERSION_FORMAT: &str = "%Y%m%d-%H%M%S";
+// Autosave writes often; one version per stretch of editing is enough
+const MIN_VERSION_GAP_MINUTES: i64 = 10;
+const MAX_VERSIONS: usize = 50;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct NoteVersion {
+    pub version: String,
+    // RFC 3339
+    pub created: String,
+    pub size: u64,
+}
+
+// `.bouldy/history/<path in vault>/`, one file per version. `note` is a
+// path from `validate_path_in_vault`, so it's under the canonical vault.
+fn history_dir(vault_path: &str, note: &Path) -> Result<PathBuf, BouldyError> {
+    let vault = Path::new(vault_path)
+        .canonicalize()
+        .map_err(|e| BouldyError::io("Invalid vault path", e))?;
+    let relative = note.strip_prefix(&vault).map_err(|_| {
+        BouldyError::outside_vault(format!("Path is outside vault: {}", note.display()))
+    })?;
+    Ok(vault
+        .join(".bouldy")
+        .join(HISTORY_DIR)
+        .join(relative.with_extension("")))
+}
+
+fn parse_version(version: &str) -> Result<NaiveDateTime, BouldyError> {
+    NaiveDateTime::parse_from_str(version, VERSION_FORMAT)
+        .map_err(|_| BouldyError::not_found(format!("No version '{}'", version)))
+}
+
+// Oldest first
+fn versions(dir: &Path) -> Vec<(NaiveDateTime, PathBuf)> {
+    let mut versions: Vec<(NaiveDateTime, PathBuf)> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| {
+                    let path = entry.path();
+                    let stem = path.file_stem()?.to_str()?;
+                    Some((parse_version(stem).ok()?, path))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    versions.sort();
+    versions
+}
+
+/// Keep `previous` (the note's content before a save) as a version, unless
+/// one was kept in the last few minutes or it matches the newest one.
+pub fn record(vault_path: &str, note: &Path, previous: &str) -> Result<(), BouldyError> {
+    let dir = history_dir(vault_path, note)?;
+    let now = Local::now().naive_local();
+    let existing = versions(&dir);
+    if let Some((at, path)) = existing.last() {
+        if (now - *at).num_minutes() < MIN_VERSION_GAP_MINUTES
+            || fs::read_to_string(path).is_ok_and(|latest| latest == previous)
+        {
+            return Ok(());
+        }
+    }
+
+    fs::create_dir_all(&dir).map_err(|e| BouldyError::io("Failed to create history folder", e))?;
+    let path = dir.join(format!("{}.md", now.format(VERSION_FORMAT)));
+    fsutil::write_atomic(&path, previous)
+        .map_err(|e| BouldyError::io("Failed to save note version", e))?;
+
+    let excess = (existing.len() + 1).saturating_sub(MAX_VERSIONS);
+    for (_, old) in existing.iter().take(excess) {
+        let _ = fs::remove_file(old);
+    }
+    Ok(())
+}
+
+/// Saved versions of `note`, newest first.
+pub fn list_versions(vault_path: &str, note: &Path) -> Result<Vec<NoteVersion>, BouldyError> {
+    let dir = history_dir(vault_path, note)?;
+    Ok(versions(&dir)
+        .into_iter()
+        .rev()
+        .map(|(at, path)| NoteVersion {
+            version: at.format(VERSION_FORMAT).to_string(),
+            created: Local
+                .from_local_datetime(&at)
+                .earliest()
+                .map(|at| at.to_rfc3339())
+                .unwrap_or_default(),
+            size: fs::metadata(&path).map(|m| m.len()).unwrap_or_default(),
+        })
+        .collect())
+}
+
+pub fn read_version(vault_path: &str, note: &Path, version: &str) -> Result<String, BouldyError> {
+    // Parsing first keeps the id from naming anything outside the folder
+    let at = parse_version(version)?;
+    let path = history_dir(vault_path, note)?.join(format!("{}.md", at.format(VERSION_FORMAT)));
+    match fs::read_to_string(&path) {
+        Ok(content) => Ok(content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            Err(BouldyError::not_found(format!("No version '{}'", version)))
+        }
+        Err(e) => Err(BouldyError::io("Failed to read note version", e)),
+    }
+}
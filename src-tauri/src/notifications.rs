@@ -0,0 +1,147 @@
+use chrono::{Local, NaiveTime};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+use crate::error::BouldyError;
+use crate::settings;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NotificationCategory {
+    #[serde(rename = "dueReminders")]
+    DueReminders,
+    #[serde(rename = "pomodoro")]
+    Pomodoro,
+    #[serde(rename = "sync")]
+    Sync,
+}
+
+// Times are local "HH:MM"; a range like 22:00-07:00 wraps past midnight
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct QuietHours {
+    pub start: String,
+    pub end: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NotificationSettings {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(rename = "dueReminders", default = "default_true")]
+    pub due_reminders: bool,
+    #[serde(default = "default_true")]
+    pub pomodoro: bool,
+    #[serde(default = "default_true")]
+    pub sync: bool,
+    #[serde(rename = "quietHours", default)]
+    pub quiet_hours: Option<QuietHours>,
+    // Remind about waiting-for todos unchanged this many days; 0 turns it off
+    #[serde(
+        rename = "waitingFollowUpDays",
+        default = "default_waiting_follow_up_days"
+    )]
+    pub waiting_follow_up_days: u32,
+    // Minutes before a todo's due time to remind about it
+    #[serde(
+        rename = "dueTimeLeadMinutes",
+        default = "default_due_time_lead_minutes"
+    )]
+    pub due_time_lead_minutes: u32,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            due_reminders: true,
+            pomodoro: true,
+            sync: true,
+            quiet_hours: None,
+            waiting_follow_up_days: default_waiting_follow_up_days(),
+            due_time_lead_minutes: default_due_time_lead_minutes(),
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_waiting_follow_up_days() -> u32 {
+    7
+}
+
+fn default_due_time_lead_minutes() -> u32 {
+    10
+}
+
+pub fn parse_time(value: &str) -> Result<NaiveTime, BouldyError> {
+    NaiveTime::parse_from_str(value, "%H:%M").map_err(|_| {
+        BouldyError::invalid_input(format!("Invalid time '{}'. Expected HH:MM", value))
+    })
+}
+
+impl QuietHours {
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        let (Ok(start), Ok(end)) = (parse_time(&self.start), parse_time(&self.end)) else {
+            return false;
+        };
+
+        if start <= end {
+            time >= start && time < end
+        } else {
+            time >= start || time < end
+        }
+    }
+}
+
+impl NotificationSettings {
+    pub fn validate(&self) -> Result<(), BouldyError> {
+        if let Some(ref quiet_hours) = self.quiet_hours {
+            parse_time(&quiet_hours.start)?;
+            parse_time(&quiet_hours.end)?;
+        }
+        Ok(())
+    }
+
+    pub fn is_category_enabled(&self, category: NotificationCategory) -> bool {
+        self.enabled
+            && match category {
+                NotificationCategory::DueReminders => self.due_reminders,
+                NotificationCategory::Pomodoro => self.pomodoro,
+                NotificationCategory::Sync => self.sync,
+            }
+    }
+
+    pub fn allows(&self, category: NotificationCategory, now: NaiveTime) -> bool {
+        self.is_category_enabled(category)
+            && !self
+                .quiet_hours
+                .as_ref()
+                .is_some_and(|quiet_hours| quiet_hours.contains(now))
+    }
+}
+
+/// Show a native notification if its category is enabled and we're outside
+/// quiet hours. Returns whether the notification was actually shown.
+pub fn notify(
+    app: &AppHandle,
+    category: NotificationCategory,
+    title: &str,
+    body: &str,
+) -> Result<bool, BouldyError> {
+    let notification_settings = settings::load_settings(app)?.notifications;
+
+    if !notification_settings.allows(category, Local::now().time()) {
+        return Ok(false);
+    }
+
+    app.notification()
+        .builder()
+        .title(title)
+        .body(body)
+        .show()
+        .map_err(|e| format!("Failed to show notification: {}", e))?;
+
+    Ok(true)
+}
@@ -0,0 +1,546 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use wasmtime::{
+    AsContextMut, Caller, Config, Engine, Instance, Linker, Memory, Module, Store, StoreLimits,
+    StoreLimitsBuilder, TypedFunc,
+};
+
+use crate::error::BouldyError;
+use crate::{todo_store, todos, vault};
+
+// Each plugin is a folder under `<vault>/.bouldy/plugins/`:
+//
+//   plugin.json   {"name": "...", "version": "...", "capabilities": [...]}
+//   plugin.wasm
+//
+// Plugins run without WASI, so they can't touch the filesystem or network;
+// everything goes through the `bouldy` imports below, each gated by a
+// capability from the manifest. Strings cross the boundary as (ptr, len)
+// pairs of UTF-8 in the plugin's exported `memory`; the host hands strings
+// back by calling the plugin's `alloc(len) -> ptr` and returns them packed
+// as `ptr << 32 | len`.
+//
+// Imports (module "bouldy"):
+//   log(ptr, len)
+//   list_notes() -> i64            read_notes; JSON [{"path","title"}]
+//   read_note(ptr, len) -> i64     read_notes; path relative to notes/
+//   add_todo(ptr, len) -> i32      add_todos; todo.txt syntax
+//   register_command(ptr, len) -> i32   commands; JSON {"id","title"}
+//
+// Exports, all optional besides `memory`:
+//   alloc(len) -> ptr
+//   on_event(ptr, len)      JSON {"event", ...}: load, unload, note_saved,
+//                           todo_completed
+//   run_command(ptr, len)   the id of a registered command
+pub const PLUGINS_DIR: &str = "plugins";
+const MANIFEST_FILE: &str = "plugin.json";
+const MODULE_FILE: &str = "plugin.wasm";
+const IMPORT_MODULE: &str = "bouldy";
+
+// Roughly a second of work per call before the plugin is stopped
+const FUEL_PER_CALL: u64 = 500_000_000;
+const MAX_MEMORY_BYTES: usize = 64 * 1024 * 1024;
+// Longest string a plugin can pass to the host
+const MAX_MESSAGE_BYTES: usize = 1024 * 1024;
+
+// Import results; packed strings are never negative
+const OK: i32 = 0;
+const DENIED: i32 = -1;
+const FAILED: i32 = -2;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Capability {
+    #[serde(rename = "read_notes")]
+    ReadNotes,
+    #[serde(rename = "add_todos")]
+    AddTodos,
+    #[serde(rename = "commands")]
+    Commands,
+}
+
+#[derive(Deserialize)]
+struct Manifest {
+    name: String,
+    #[serde(default)]
+    version: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    capabilities: BTreeSet<Capability>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PluginCommand {
+    pub id: String,
+    pub title: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PluginInfo {
+    // The plugin's folder name
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    pub description: String,
+    pub capabilities: BTreeSet<Capability>,
+    pub commands: Vec<PluginCommand>,
+    // Why the plugin isn't running, if it isn't
+    pub error: Option<String>,
+}
+
+struct HostState {
+    app: AppHandle,
+    plugin: String,
+    vault_path: String,
+    capabilities: BTreeSet<Capability>,
+    commands: Vec<PluginCommand>,
+    limits: StoreLimits,
+}
+
+struct Runtime {
+    store: Store<HostState>,
+    instance: Instance,
+}
+
+struct LoadedPlugin {
+    info: PluginInfo,
+    runtime: Option<Runtime>,
+}
+
+/// Managed state: the plugins of the open vault.
+#[derive(Default)]
+pub struct PluginHost {
+    plugins: Mutex<Vec<LoadedPlugin>>,
+}
+
+pub fn plugins_dir(vault_path: &str) -> PathBuf {
+    Path::new(vault_path).join(".bouldy").join(PLUGINS_DIR)
+}
+
+// === Guest memory ===
+
+fn guest_memory(caller: &mut Caller<'_, HostState>) -> Option<Memory> {
+    caller
+        .get_export("memory")
+        .and_then(|export| export.into_memory())
+}
+
+fn read_string(caller: &mut Caller<'_, HostState>, ptr: i32, len: i32) -> Option<String> {
+    let len = usize::try_from(len)
+        .ok()
+        .filter(|len| *len <= MAX_MESSAGE_BYTES)?;
+    let ptr = usize::try_from(ptr).ok()?;
+    let memory = guest_memory(caller)?;
+    let mut buffer = vec![0; len];
+    memory.read(&*caller, ptr, &mut buffer).ok()?;
+    String::from_utf8(buffer).ok()
+}
+
+// Copy `data` into memory the plugin allocated for it
+fn copy_in(
+    mut store: impl AsContextMut<Data = HostState>,
+    memory: Memory,
+    alloc: TypedFunc<i32, i32>,
+    data: &[u8],
+) -> wasmtime::Result<(i32, i32)> {
+    let len = i32::try_from(data.len())?;
+    let ptr = alloc.call(&mut store, len)?;
+    memory.write(&mut store, usize::try_from(ptr)?, data)?;
+    Ok((ptr, len))
+}
+
+fn return_string(caller: &mut Caller<'_, HostState>, data: &str) -> i64 {
+    let alloc = caller
+        .get_export("alloc")
+        .and_then(|export| export.into_func())
+        .and_then(|func| func.typed::<i32, i32>(&*caller).ok());
+    let (Some(memory), Some(alloc)) = (guest_memory(caller), alloc) else {
+        return FAILED.into();
+    };
+    match copy_in(&mut *caller, memory, alloc, data.as_bytes()) {
+        Ok((ptr, len)) => (i64::from(ptr) << 32) | i64::from(len),
+        Err(e) => {
+            tracing::warn!("Plugin {} alloc failed - {}", caller.data().plugin, e);
+            FAILED.into()
+        }
+    }
+}
+
+fn allowed(caller: &Caller<'_, HostState>, capability: Capability) -> bool {
+    let state = caller.data();
+    if state.capabilities.contains(&capability) {
+        return true;
+    }
+    tracing::warn!(
+        "Plugin {} used {:?} without declaring it",
+        state.plugin,
+        capability
+    );
+    false
+}
+
+// === Host API ===
+
+fn host_list_notes(caller: &mut Caller<'_, HostState>) -> Result<String, BouldyError> {
+    let vault_path = caller.data().vault_path.clone();
+    let notes_dir = vault::notes_dir(&vault_path);
+    let notes: Vec<_> = vault::list_notes(&vault_path)?
+        .into_iter()
+        .map(|note| {
+            let path = Path::new(&note.path)
+                .strip_prefix(&notes_dir)
+                .map(|relative| relative.to_string_lossy().to_string())
+                .unwrap_or(note.name);
+            json!({ "path": path, "title": note.title })
+        })
+        .collect();
+    serde_json::to_string(&notes).map_err(|e| BouldyError::parse("Failed to list notes", e))
+}
+
+fn host_read_note(caller: &mut Caller<'_, HostState>, path: &str) -> Result<String, BouldyError> {
+    let vault_path = caller.data().vault_path.clone();
+    let requested = vault::notes_dir(&vault_path).join(path);
+    let file = vault::validate_path_in_vault(&vault_path, &requested.to_string_lossy())?;
+    if !vault::is_markdown_file(&file) {
+        return Err(BouldyError::invalid_path(format!("Not a note: {}", path)));
+    }
+    fs::read_to_string(&file).map_err(|e| BouldyError::io("Failed to read note", e))
+}
+
+fn host_add_todo(caller: &mut Caller<'_, HostState>, text: &str) -> Result<(), BouldyError> {
+    let state = caller.data();
+    state
+        .app
+        .state::<todo_store::TodoStore>()
+        .with_file(&state.vault_path, || {
+            todos::append_todo(&state.vault_path, text)
+        })?;
+    let _ = state.app.emit("todos_changed", ());
+    Ok(())
+}
+
+fn host_register_command(caller: &mut Caller<'_, HostState>, command: &str) -> Result<(), String> {
+    let command: PluginCommand =
+        serde_json::from_str(command).map_err(|e| format!("Invalid command: {}", e))?;
+    if command.id.trim().is_empty() {
+        return Err("Command id is empty".to_string());
+    }
+    let commands = &mut caller.data_mut().commands;
+    commands.retain(|existing| existing.id != command.id);
+    commands.push(command);
+    Ok(())
+}
+
+fn report<T, E: std::fmt::Display>(
+    caller: &Caller<'_, HostState>,
+    call: &str,
+    result: Result<T, E>,
+) -> Option<T> {
+    result
+        .map_err(|e| tracing::warn!("Plugin {} {} failed - {}", caller.data().plugin, call, e))
+        .ok()
+}
+
+fn linker(engine: &Engine) -> wasmtime::Result<Linker<HostState>> {
+    let mut linker = Linker::new(engine);
+
+    linker.func_wrap(
+        IMPORT_MODULE,
+        "log",
+        |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| {
+            if let Some(message) = read_string(&mut caller, ptr, len) {
+                tracing::info!("[plugin {}] {}", caller.data().plugin, message);
+            }
+        },
+    )?;
+
+    linker.func_wrap(
+        IMPORT_MODULE,
+        "list_notes",
+        |mut caller: Caller<'_, HostState>| -> i64 {
+            if !allowed(&caller, Capability::ReadNotes) {
+                return DENIED.into();
+            }
+            let result = host_list_notes(&mut caller);
+            match report(&caller, "list_notes", result) {
+                Some(notes) => return_string(&mut caller, &notes),
+                None => FAILED.into(),
+            }
+        },
+    )?;
+
+    linker.func_wrap(
+        IMPORT_MODULE,
+        "read_note",
+        |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| -> i64 {
+            if !allowed(&caller, Capability::ReadNotes) {
+                return DENIED.into();
+            }
+            let Some(path) = read_string(&mut caller, ptr, len) else {
+                return FAILED.into();
+            };
+            let result = host_read_note(&mut caller, &path);
+            match report(&caller, "read_note", result) {
+                Some(content) => return_string(&mut caller, &content),
+                None => FAILED.into(),
+            }
+        },
+    )?;
+
+    linker.func_wrap(
+        IMPORT_MODULE,
+        "add_todo",
+        |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| -> i32 {
+            if !allowed(&caller, Capability::AddTodos) {
+                return DENIED;
+            }
+            let Some(text) = read_string(&mut caller, ptr, len) else {
+                return FAILED;
+            };
+            let result = host_add_todo(&mut caller, &text);
+            report(&caller, "add_todo", result).map_or(FAILED, |_| OK)
+        },
+    )?;
+
+    linker.func_wrap(
+        IMPORT_MODULE,
+        "register_command",
+        |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| -> i32 {
+            if !allowed(&caller, Capability::Commands) {
+                return DENIED;
+            }
+            let Some(command) = read_string(&mut caller, ptr, len) else {
+                return FAILED;
+            };
+            let result = host_register_command(&mut caller, &command);
+            report(&caller, "register_command", result).map_or(FAILED, |_| OK)
+        },
+    )?;
+
+    Ok(linker)
+}
+
+fn engine() -> wasmtime::Result<Engine> {
+    let mut config = Config::new();
+    config.consume_fuel(true);
+    Engine::new(&config)
+}
+
+// === Lifecycle ===
+
+impl Runtime {
+    // Call an export taking one string, if the plugin has it
+    fn call(&mut self, export: &str, argument: &str) -> Result<bool, String> {
+        let Ok(func) = self
+            .instance
+            .get_typed_func::<(i32, i32), ()>(&mut self.store, export)
+        else {
+            return Ok(false);
+        };
+        let memory = self
+            .instance
+            .get_memory(&mut self.store, "memory")
+            .ok_or("Plugin exports no memory")?;
+        let alloc = self
+            .instance
+            .get_typed_func::<i32, i32>(&mut self.store, "alloc")
+            .map_err(|_| "Plugin exports no alloc")?;
+
+        self.store
+            .set_fuel(FUEL_PER_CALL)
+            .map_err(|e| e.to_string())?;
+        let (ptr, len) = copy_in(&mut self.store, memory, alloc, argument.as_bytes())
+            .map_err(|e| e.to_string())?;
+        // {:#} keeps the cause, e.g. running out of fuel
+        func.call(&mut self.store, (ptr, len))
+            .map_err(|e| format!("{:#}", e))?;
+        Ok(true)
+    }
+
+    fn send_event(&mut self, event: &serde_json::Value) {
+        if let Err(e) = self.call("on_event", &event.to_string()) {
+            tracing::warn!(
+                "Plugin {} failed handling {} - {}",
+                self.store.data().plugin,
+                event["event"],
+                e
+            );
+        }
+    }
+}
+
+fn load_plugin(
+    app: &AppHandle,
+    engine: &Engine,
+    linker: &Linker<HostState>,
+    vault_path: &str,
+    id: &str,
+    dir: &Path,
+) -> LoadedPlugin {
+    let mut info = PluginInfo {
+        id: id.to_string(),
+        name: id.to_string(),
+        version: String::new(),
+        description: String::new(),
+        capabilities: BTreeSet::new(),
+        commands: Vec::new(),
+        error: None,
+    };
+
+    let manifest: Manifest = match fs::read_to_string(dir.join(MANIFEST_FILE))
+        .map_err(|e| e.to_string())
+        .and_then(|content| serde_json::from_str(&content).map_err(|e| e.to_string()))
+    {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            info.error = Some(format!("Invalid {}: {}", MANIFEST_FILE, e));
+            return LoadedPlugin {
+                info,
+                runtime: None,
+            };
+        }
+    };
+    info.name = manifest.name;
+    info.version = manifest.version;
+    info.description = manifest.description;
+    info.capabilities = manifest.capabilities;
+
+    let state = HostState {
+        app: app.clone(),
+        plugin: id.to_string(),
+        vault_path: vault_path.to_string(),
+        capabilities: info.capabilities.clone(),
+        commands: Vec::new(),
+        limits: StoreLimitsBuilder::new()
+            .memory_size(MAX_MEMORY_BYTES)
+            .build(),
+    };
+    let mut store = Store::new(engine, state);
+    store.limiter(|state| &mut state.limits);
+
+    let instance = Module::from_file(engine, dir.join(MODULE_FILE)).and_then(|module| {
+        store.set_fuel(FUEL_PER_CALL)?;
+        linker.instantiate(&mut store, &module)
+    });
+    let mut runtime = match instance {
+        Ok(instance) => Runtime { store, instance },
+        Err(e) => {
+            info.error = Some(format!("Failed to load {}: {}", MODULE_FILE, e));
+            return LoadedPlugin {
+                info,
+                runtime: None,
+            };
+        }
+    };
+
+    runtime.send_event(&json!({ "event": "load" }));
+    LoadedPlugin {
+        info,
+        runtime: Some(runtime),
+    }
+}
+
+impl LoadedPlugin {
+    fn info(&self) -> PluginInfo {
+        let mut info = self.info.clone();
+        if let Some(runtime) = &self.runtime {
+            info.commands = runtime.store.data().commands.clone();
+        }
+        info
+    }
+}
+
+impl PluginHost {
+    /// Unload the current plugins and load those of `vault_path`. A plugin
+    /// that fails to load is listed with its error rather than failing the rest.
+    pub fn load(&self, app: &AppHandle, vault_path: &str) -> Result<Vec<PluginInfo>, BouldyError> {
+        let mut plugins = self.plugins.lock().unwrap();
+        for plugin in plugins.iter_mut() {
+            if let Some(runtime) = plugin.runtime.as_mut() {
+                runtime.send_event(&json!({ "event": "unload" }));
+            }
+        }
+        plugins.clear();
+
+        let dir = plugins_dir(vault_path);
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(BouldyError::io("Failed to read plugins folder", e)),
+        };
+        let mut folders: Vec<(String, PathBuf)> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .filter_map(|path| Some((path.file_name()?.to_str()?.to_string(), path)))
+            .collect();
+        folders.sort();
+
+        let engine = engine().map_err(|e| format!("Failed to start plugin engine: {}", e))?;
+        let linker = linker(&engine).map_err(|e| format!("Failed to set up plugin API: {}", e))?;
+        for (id, path) in folders {
+            let plugin = load_plugin(app, &engine, &linker, vault_path, &id, &path);
+            if let Some(error) = &plugin.info.error {
+                tracing::warn!("Plugin {} not loaded - {}", id, error);
+            }
+            plugins.push(plugin);
+        }
+
+        Ok(plugins.iter().map(LoadedPlugin::info).collect())
+    }
+
+    pub fn list(&self) -> Vec<PluginInfo> {
+        let plugins = self.plugins.lock().unwrap();
+        plugins.iter().map(LoadedPlugin::info).collect()
+    }
+
+    /// Send a lifecycle event to every running plugin.
+    pub fn dispatch(&self, event: &str, mut payload: serde_json::Value) {
+        payload["event"] = json!(event);
+        let mut plugins = self.plugins.lock().unwrap();
+        for runtime in plugins.iter_mut().filter_map(|p| p.runtime.as_mut()) {
+            runtime.send_event(&payload);
+        }
+    }
+
+    pub fn run_command(&self, plugin: &str, command: &str) -> Result<(), BouldyError> {
+        let mut plugins = self.plugins.lock().unwrap();
+        let runtime = plugins
+            .iter_mut()
+            .find(|p| p.info.id == plugin)
+            .and_then(|p| p.runtime.as_mut())
+            .ok_or_else(|| BouldyError::not_found(format!("Plugin not running: {}", plugin)))?;
+        if !runtime
+            .store
+            .data()
+            .commands
+            .iter()
+            .any(|c| c.id == command)
+        {
+            return Err(BouldyError::not_found(format!(
+                "Plugin {} has no command {}",
+                plugin, command
+            )));
+        }
+
+        match runtime.call("run_command", command) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(format!("Plugin {} exports no run_command", plugin).into()),
+            Err(e) => Err(format!("Plugin command failed: {}", e).into()),
+        }
+    }
+}
+
+/// Dispatch `event` off the calling thread, so a slow plugin doesn't hold up
+/// the command that triggered it.
+pub fn notify(app: &AppHandle, event: &'static str, payload: serde_json::Value) {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        app.state::<PluginHost>().dispatch(event, payload);
+    });
+}
@@ -0,0 +1,192 @@
+use chrono::{DateTime, Local, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::error::BouldyError;
+
+// Written by the timer in the frontend, one session per line:
+//
+//   id|type|seconds|start|end|completed|todo uid|tags|note
+//
+// Times are RFC 3339. The last three are optional: the todo the session was
+// started on, its `+project`/`@context`/`#tag` words separated by spaces,
+// and the vault-relative path of the note being worked on.
+pub const POMODORO_FILE: &str = ".pomodoros.md";
+
+const DEFAULT_BREAK_ACTIVITIES: &[&str] = &[
+    "Stand up and stretch",
+    "Get a glass of water",
+    "Look at something far away",
+    "Take a short walk",
+    "Breathe slowly for a minute",
+];
+
+// Longest session the timer accepts, in minutes
+const MAX_SESSION_MINUTES: u32 = 240;
+
+/// The work/break cycle the timer runs.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TimerConfig {
+    #[serde(rename = "workMinutes")]
+    pub work_minutes: u32,
+    #[serde(rename = "shortBreakMinutes")]
+    pub short_break_minutes: u32,
+    #[serde(rename = "longBreakMinutes")]
+    pub long_break_minutes: u32,
+    // Every this many work sessions in a day, the break is a long one
+    #[serde(rename = "sessionsPerLongBreak")]
+    pub sessions_per_long_break: u32,
+    // Start the next session when one finishes, instead of waiting
+    #[serde(rename = "autoStartNext", default = "default_true")]
+    pub auto_start_next: bool,
+}
+
+impl Default for TimerConfig {
+    fn default() -> Self {
+        Self {
+            work_minutes: 25,
+            short_break_minutes: 5,
+            long_break_minutes: 15,
+            sessions_per_long_break: 4,
+            auto_start_next: true,
+        }
+    }
+}
+
+impl TimerConfig {
+    pub fn validate(&self) -> Result<(), BouldyError> {
+        for (name, minutes) in [
+            ("Work session", self.work_minutes),
+            ("Short break", self.short_break_minutes),
+            ("Long break", self.long_break_minutes),
+        ] {
+            if minutes == 0 || minutes > MAX_SESSION_MINUTES {
+                return Err(BouldyError::invalid_input(format!(
+                    "{} must be between 1 and {} minutes",
+                    name, MAX_SESSION_MINUTES
+                )));
+            }
+        }
+        if self.sessions_per_long_break == 0 {
+            return Err(BouldyError::invalid_input(
+                "Sessions per long break must be at least 1",
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PomodoroSettings {
+    #[serde(default)]
+    pub timer: TimerConfig,
+    // Work sessions to aim for each day
+    #[serde(rename = "dailyGoal", default)]
+    pub daily_goal: Option<u32>,
+    // Suggested in turn at the start of each break
+    #[serde(rename = "breakActivities", default = "default_break_activities")]
+    pub break_activities: Vec<String>,
+    #[serde(rename = "breakNotifications", default = "default_true")]
+    pub break_notifications: bool,
+    // Ask the frontend to dim the window while on a break
+    #[serde(rename = "dimOnBreak", default)]
+    pub dim_on_break: bool,
+    // Breaks can't be dismissed from the UI; skipping one is recorded
+    #[serde(rename = "strictBreaks", default)]
+    pub strict_breaks: bool,
+}
+
+impl Default for PomodoroSettings {
+    fn default() -> Self {
+        Self {
+            timer: TimerConfig::default(),
+            daily_goal: None,
+            break_activities: default_break_activities(),
+            break_notifications: true,
+            dim_on_break: false,
+            strict_breaks: false,
+        }
+    }
+}
+
+fn default_break_activities() -> Vec<String> {
+    DEFAULT_BREAK_ACTIVITIES
+        .iter()
+        .map(|activity| activity.to_string())
+        .collect()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl PomodoroSettings {
+    pub fn validate(&self) -> Result<(), BouldyError> {
+        self.timer.validate()?;
+        if self.daily_goal == Some(0) {
+            return Err(BouldyError::invalid_input(
+                "Daily pomodoro goal must be at least 1",
+            ));
+        }
+        if self
+            .break_activities
+            .iter()
+            .any(|activity| activity.trim().is_empty())
+        {
+            return Err(BouldyError::invalid_input(
+                "Break activities cannot be empty",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A finished work session, in local time.
+#[derive(Clone, Debug)]
+pub struct WorkSession {
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+    pub todo_uid: Option<String>,
+    pub tags: Vec<String>,
+    pub note: Option<String>,
+}
+
+pub fn load_work_sessions(vault_path: &str) -> Vec<WorkSession> {
+    let Ok(content) = fs::read_to_string(Path::new(vault_path).join(POMODORO_FILE)) else {
+        return Vec::new();
+    };
+    let local = |value: &str| {
+        DateTime::parse_from_rfc3339(value)
+            .ok()
+            .map(|at| at.with_timezone(&Local).naive_local())
+    };
+
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.trim().split('|').collect();
+            match parts.as_slice() {
+                [_, "work", _, start, end, "true", rest @ ..] => {
+                    let optional = |index: usize| {
+                        rest.get(index)
+                            .map(|value| value.trim())
+                            .filter(|value| !value.is_empty())
+                            .map(str::to_string)
+                    };
+                    Some(WorkSession {
+                        start: local(start)?,
+                        end: local(end)?,
+                        todo_uid: optional(0),
+                        tags: optional(1)
+                            .map(|tags| tags.split_whitespace().map(str::to_string).collect())
+                            .unwrap_or_default(),
+                        note: optional(2),
+                    })
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
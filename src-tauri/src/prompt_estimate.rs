@@ -0,0 +1,141 @@
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::llm::{self, LlmProvider, LlmSettings};
+use crate::prompt_tests;
+
+// USD per million input and output tokens, matched by model name prefix
+// (longest first wins). Prices change; these are the providers' list prices
+// at the time of writing, and unknown models get no cost.
+const MODEL_PRICES: &[(&str, f64, f64)] = &[
+    ("gpt-4o-mini", 0.15, 0.60),
+    ("gpt-4o", 2.50, 10.00),
+    ("gpt-4.1-nano", 0.10, 0.40),
+    ("gpt-4.1-mini", 0.40, 1.60),
+    ("gpt-4.1", 2.00, 8.00),
+    ("o4-mini", 1.10, 4.40),
+    ("o3", 2.00, 8.00),
+    ("claude-3-5-haiku", 0.80, 4.00),
+    ("claude-haiku-4", 1.00, 5.00),
+    ("claude-3-5-sonnet", 3.00, 15.00),
+    ("claude-3-7-sonnet", 3.00, 15.00),
+    ("claude-sonnet-4", 3.00, 15.00),
+    ("claude-opus-4", 15.00, 75.00),
+];
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PromptEstimate {
+    pub model: String,
+    pub characters: usize,
+    // An approximation of OpenAI's cl100k tokenizer; other models split
+    // text differently, so treat it as a ballpark
+    pub tokens: usize,
+    // Unknown for models without a listed price, zero for local models
+    #[serde(rename = "inputCost")]
+    pub input_cost: Option<f64>,
+    // If the reply used the whole output budget
+    #[serde(rename = "maxOutputCost")]
+    pub max_output_cost: Option<f64>,
+    // `{{variables}}` still in the prompt, which the estimate doesn't cover
+    #[serde(rename = "missingVariables")]
+    pub missing_variables: Vec<String>,
+}
+
+fn pretokenize_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    // tiktoken's split pattern without the lookaheads: contractions, words
+    // with their leading space, numbers in groups of three, punctuation runs
+    // and whitespace
+    REGEX.get_or_init(|| {
+        Regex::new(r"(?i:'s|'t|'re|'ve|'m|'ll|'d)| ?\p{L}+| ?\p{N}{1,3}| ?[^\s\p{L}\p{N}]+|\s+")
+            .unwrap()
+    })
+}
+
+fn variable_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"\{\{\s*([^{}]+?)\s*\}\}").unwrap())
+}
+
+// Tokens for one pre-tokenized piece. Common words are a single token and
+// long ones split every few letters; scripts outside ASCII take about a
+// token per character.
+fn piece_tokens(piece: &str) -> usize {
+    let word = piece.trim_start_matches(' ');
+    if word.is_empty() || word.chars().all(char::is_whitespace) {
+        return 1;
+    }
+    if !word.is_ascii() {
+        return word.chars().count();
+    }
+    if word.chars().all(char::is_alphabetic) {
+        1 + word.len().saturating_sub(1) / 5
+    } else {
+        word.len().div_ceil(2)
+    }
+}
+
+pub fn count_tokens(text: &str) -> usize {
+    pretokenize_regex()
+        .find_iter(text)
+        .map(|piece| piece_tokens(piece.as_str()))
+        .sum()
+}
+
+fn price(model: &str) -> Option<(f64, f64)> {
+    MODEL_PRICES
+        .iter()
+        .filter(|(prefix, _, _)| model.starts_with(prefix))
+        .max_by_key(|(prefix, _, _)| prefix.len())
+        .map(|&(_, input, output)| (input, output))
+}
+
+/// `{{variables}}` left in `text`, sorted and without duplicates.
+pub fn unfilled_variables(text: &str) -> Vec<String> {
+    let mut variables: Vec<String> = variable_regex()
+        .captures_iter(text)
+        .map(|captures| captures[1].to_string())
+        .collect();
+    variables.sort();
+    variables.dedup();
+    variables
+}
+
+/// Render `content` with `variables` and estimate its size and cost for
+/// `model`, or the configured model when none is given.
+pub fn estimate(
+    content: &str,
+    variables: &HashMap<String, String>,
+    model: Option<&str>,
+    settings: &LlmSettings,
+) -> PromptEstimate {
+    let rendered = prompt_tests::render(content, variables);
+    let missing_variables = unfilled_variables(&rendered);
+
+    let local = model.is_none() && settings.provider == Some(LlmProvider::Ollama);
+    let model = model
+        .map(str::to_string)
+        .or_else(|| settings.model.clone())
+        .or_else(|| settings.provider.map(|p| p.default_model().to_string()))
+        .unwrap_or_else(|| LlmProvider::OpenAi.default_model().to_string());
+
+    let tokens = count_tokens(&rendered);
+    let per_million = |usd: f64, tokens: usize| usd * tokens as f64 / 1_000_000.0;
+    let prices = if local {
+        Some((0.0, 0.0))
+    } else {
+        price(&model)
+    };
+
+    PromptEstimate {
+        characters: rendered.chars().count(),
+        tokens,
+        input_cost: prices.map(|(input, _)| per_million(input, tokens)),
+        max_output_cost: prices
+            .map(|(_, output)| per_million(output, llm::MAX_OUTPUT_TOKENS as usize)),
+        missing_variables,
+        model,
+    }
+}
@@ -0,0 +1,206 @@
+use serde::Serialize;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+
+use crate::clipboard;
+use crate::error::BouldyError;
+
+pub const PROMPT_PALETTE_WINDOW: &str = "prompt-palette";
+pub const DEFAULT_PROMPT_PALETTE_SHORTCUT: &str = "CommandOrControl+Alt+P";
+pub const DEFAULT_PALETTE_SIZE: usize = 10;
+
+// Time for the OS to give focus back to the previous app before pasting
+const REFOCUS_DELAY: Duration = Duration::from_millis(200);
+// Time for that app to read the clipboard before the user's contents go back
+const RESTORE_DELAY: Duration = Duration::from_millis(500);
+const DAY_SECS: u64 = 24 * 60 * 60;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PaletteEntry {
+    pub id: String,
+    pub title: String,
+    // The palette asks for these before pasting
+    pub variables: Vec<String>,
+    pub favorite: bool,
+    pub score: f64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PastedPrompt {
+    pub text: String,
+    // False when the keystroke couldn't be sent; the text is still on the
+    // clipboard for a manual paste
+    pub pasted: bool,
+}
+
+/// Frecency: uses weighted by how recently the prompt was last used, the
+/// way browsers rank history. Favorites count as recently used.
+pub fn score(use_count: u64, last_used: Option<u64>, favorite: bool, now: u64) -> f64 {
+    let weight = match last_used.map(|used| now.saturating_sub(used) / DAY_SECS) {
+        _ if favorite => 100.0,
+        None => return 0.0,
+        Some(0..=3) => 100.0,
+        Some(4..=13) => 70.0,
+        Some(14..=30) => 50.0,
+        Some(31..=90) => 30.0,
+        Some(_) => 10.0,
+    };
+    (use_count.max(1)) as f64 * weight
+}
+
+/// The best `limit` entries, highest score first.
+pub fn rank(mut entries: Vec<PaletteEntry>, limit: usize) -> Vec<PaletteEntry> {
+    entries.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.title.cmp(&b.title))
+    });
+    entries.truncate(limit);
+    entries
+}
+
+/// Show the palette window, creating it on first use.
+pub fn show_palette_window(app: &AppHandle) -> Result<(), BouldyError> {
+    if let Some(window) = app.get_webview_window(PROMPT_PALETTE_WINDOW) {
+        window.show().map_err(|e| e.to_string())?;
+        window.set_focus().map_err(|e| e.to_string())?;
+        return Ok(());
+    }
+
+    WebviewWindowBuilder::new(
+        app,
+        PROMPT_PALETTE_WINDOW,
+        WebviewUrl::App("index.html?window=prompt-palette".into()),
+    )
+    .title("Prompts")
+    .inner_size(560.0, 380.0)
+    .resizable(false)
+    .decorations(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .center()
+    .focused(true)
+    .build()
+    .map_err(|e| format!("Failed to open prompt palette: {}", e))?;
+
+    Ok(())
+}
+
+/// Keys pressed in whatever app has focus.
+#[derive(Clone, Copy, Debug)]
+pub enum Keystroke {
+    Paste,
+    Copy,
+    // Extend the selection to the start of the word before the cursor
+    SelectWordBack,
+    // Drop the selection, leaving the cursor where it ends
+    Right,
+}
+
+// Ask the OS to press `keystroke` in whatever app has focus. There's no
+// portable way, so each platform uses its own scripting tool.
+pub fn send_keystroke(keystroke: Keystroke) -> bool {
+    use std::process::Command;
+
+    #[cfg(target_os = "macos")]
+    let attempts = {
+        let script = match keystroke {
+            Keystroke::Paste => "keystroke \"v\" using command down",
+            Keystroke::Copy => "keystroke \"c\" using command down",
+            Keystroke::SelectWordBack => "key code 123 using {option down, shift down}",
+            Keystroke::Right => "key code 124",
+        };
+        [(
+            "osascript",
+            vec![
+                "-e".to_string(),
+                format!("tell application \"System Events\" to {}", script),
+            ],
+        )]
+    };
+    #[cfg(target_os = "windows")]
+    let attempts = {
+        let keys = match keystroke {
+            Keystroke::Paste => "^v",
+            Keystroke::Copy => "^c",
+            Keystroke::SelectWordBack => "^+{LEFT}",
+            Keystroke::Right => "{RIGHT}",
+        };
+        [(
+            "powershell",
+            vec![
+                "-NoProfile".to_string(),
+                "-Command".to_string(),
+                format!(
+                    "Add-Type -AssemblyName System.Windows.Forms; [System.Windows.Forms.SendKeys]::SendWait('{}')",
+                    keys
+                ),
+            ],
+        )]
+    };
+    // wtype on Wayland, xdotool on X11
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let attempts = {
+        let (wtype, xdotool): (&[&str], &str) = match keystroke {
+            Keystroke::Paste => (&["-M", "ctrl", "v", "-m", "ctrl"], "ctrl+v"),
+            Keystroke::Copy => (&["-M", "ctrl", "c", "-m", "ctrl"], "ctrl+c"),
+            Keystroke::SelectWordBack => (
+                &[
+                    "-M", "ctrl", "-M", "shift", "-k", "Left", "-m", "shift", "-m", "ctrl",
+                ],
+                "ctrl+shift+Left",
+            ),
+            Keystroke::Right => (&["-k", "Right"], "Right"),
+        };
+        [
+            ("wtype", wtype.to_vec()),
+            ("xdotool", vec!["key", "--clearmodifiers", xdotool]),
+        ]
+    };
+
+    attempts.iter().any(|(program, args)| {
+        Command::new(program)
+            .args(args)
+            .status()
+            .is_ok_and(|status| status.success())
+    })
+}
+
+/// Press paste and, once it has landed, put `saved` back on the clipboard.
+/// False when the keystroke couldn't be sent; the clipboard is then left as
+/// it is for a manual paste.
+pub fn paste_and_restore(app: &AppHandle, saved: clipboard::Saved) -> bool {
+    if !send_keystroke(Keystroke::Paste) {
+        return false;
+    }
+    let app = app.clone();
+    thread::spawn(move || {
+        thread::sleep(RESTORE_DELAY);
+        if let Err(e) = clipboard::restore(&app, saved) {
+            tracing::warn!("{}", e);
+        }
+    });
+    true
+}
+
+/// Put `text` on the clipboard, hide the palette so the previous app gets
+/// focus back, and paste into it. Also used for snippets. The user's own
+/// clipboard contents are put back once the paste has landed.
+pub fn paste(app: &AppHandle, text: String) -> Result<PastedPrompt, BouldyError> {
+    let saved = clipboard::save(app);
+    clipboard::write_text(app, &text)?;
+    if let Some(window) = app
+        .get_webview_window(PROMPT_PALETTE_WINDOW)
+        .filter(|window| window.is_visible().unwrap_or(false))
+    {
+        window.hide().map_err(|e| e.to_string())?;
+    }
+
+    thread::sleep(REFOCUS_DELAY);
+    let pasted = paste_and_restore(app, saved);
+    if !pasted {
+        tracing::warn!("Could not send paste keystroke; prompt left on the clipboard");
+    }
+    Ok(PastedPrompt { text, pasted })
+}
@@ -0,0 +1,220 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::error::BouldyError;
+use crate::markdown;
+use crate::network::{self, NetworkConfig};
+use crate::secrets;
+
+pub const SECRET_SERVICE: &str = "bouldy-github";
+pub const SECRET_ACCOUNT: &str = "gist";
+const GIST_API: &str = "https://api.github.com/gists";
+// Marks frontmatter as a shared prompt rather than any markdown file
+const SHARED_KIND: &str = "prompt";
+// Shared prompts are small; anything bigger isn't one
+const MAX_SHARED_BYTES: usize = 256 * 1024;
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(tag = "target")]
+pub enum ShareTarget {
+    // Secret gists are unlisted, not private: anyone with the link sees them
+    #[serde(rename = "gist")]
+    Gist {
+        #[serde(default)]
+        public: bool,
+    },
+    // Just the markdown, for the user to copy anywhere
+    #[serde(rename = "markdown")]
+    Markdown,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct SharedLink {
+    pub markdown: String,
+    pub url: Option<String>,
+}
+
+/// A prompt with everything needed to recreate it elsewhere. Usage counts,
+/// favorites and ratings are personal and stay behind.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct SharedPrompt {
+    pub title: String,
+    #[serde(skip)]
+    pub content: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variables: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Frontmatter {
+    bouldy: String,
+    #[serde(flatten)]
+    prompt: SharedPrompt,
+}
+
+/// The prompt as one markdown document: frontmatter with its metadata, then
+/// the same `# Title` and body as the prompt file.
+pub fn to_markdown(prompt: &SharedPrompt) -> Result<String, BouldyError> {
+    let frontmatter = serde_yaml::to_string(&Frontmatter {
+        bouldy: SHARED_KIND.to_string(),
+        prompt: prompt.clone(),
+    })
+    .map_err(|e| format!("Failed to serialize prompt: {}", e))?;
+    Ok(format!(
+        "---\n{}---\n\n# {}\n\n{}\n",
+        frontmatter,
+        prompt.title,
+        prompt.content.trim()
+    ))
+}
+
+/// Read a document made by `to_markdown`, checking its frontmatter.
+pub fn parse_shared(text: &str) -> Result<SharedPrompt, BouldyError> {
+    if text.len() > MAX_SHARED_BYTES {
+        return Err(BouldyError::invalid_input("Shared prompt is too large"));
+    }
+    let text = text.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+    let (Some(yaml), body) = markdown::split_frontmatter(text.trim_start()) else {
+        return Err(BouldyError::invalid_input(
+            "Not a shared prompt: frontmatter is missing",
+        ));
+    };
+    let frontmatter: Frontmatter = serde_yaml::from_str(yaml).map_err(|e| {
+        BouldyError::invalid_input(format!("Invalid shared prompt frontmatter: {}", e))
+    })?;
+    if frontmatter.bouldy != SHARED_KIND {
+        return Err(BouldyError::invalid_input(format!(
+            "Not a shared prompt: bouldy is '{}'",
+            frontmatter.bouldy
+        )));
+    }
+
+    let mut prompt = frontmatter.prompt;
+    prompt.title = prompt.title.trim().to_string();
+    if prompt.title.is_empty() {
+        return Err(BouldyError::invalid_input("Shared prompt has no title"));
+    }
+    if let Some(variable) = prompt
+        .variables
+        .iter()
+        .find(|v| v.is_empty() || v.contains(['{', '}']) || v.trim() != v.as_str())
+    {
+        return Err(BouldyError::invalid_input(format!(
+            "Invalid variable name '{}'",
+            variable
+        )));
+    }
+    if prompt.tags.iter().any(|tag| tag.trim().is_empty()) {
+        return Err(BouldyError::invalid_input("Shared prompt has an empty tag"));
+    }
+
+    // The heading repeats the title
+    let body = body.trim_start();
+    let body = match body.split_once('\n') {
+        Some((first, rest)) if first.starts_with("# ") => rest,
+        None if body.starts_with("# ") => "",
+        _ => body,
+    };
+    prompt.content = body.trim().to_string();
+    Ok(prompt)
+}
+
+fn github_token() -> Result<String, BouldyError> {
+    secrets::get(SECRET_SERVICE, SECRET_ACCOUNT)?
+        .ok_or_else(|| BouldyError::not_found("No GitHub token saved for gists"))
+}
+
+/// Upload the markdown as a gist with a single file and return its page.
+pub async fn create_gist(
+    network_config: &NetworkConfig,
+    file_name: &str,
+    prompt: &SharedPrompt,
+    markdown: &str,
+    public: bool,
+) -> Result<String, BouldyError> {
+    let token = github_token()?;
+    let response = network::http_client(network_config)?
+        .post(GIST_API)
+        .bearer_auth(token)
+        .header("Accept", "application/vnd.github+json")
+        .json(&json!({
+            "description": prompt.title,
+            "public": public,
+            "files": { file_name: { "content": markdown } },
+        }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach GitHub: {}", e))?;
+
+    let status = response.status();
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to read response from GitHub: {}", e))?;
+    if !status.is_success() {
+        let message = body["message"].as_str().unwrap_or_default();
+        return Err(format!("GitHub returned {}: {}", status, message).into());
+    }
+    body["html_url"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| "GitHub did not return a gist URL".into())
+}
+
+// https://gist.github.com/user/<id> -> the gist's API URL
+fn gist_api_url(url: &tauri::Url) -> Option<String> {
+    if url.host_str() != Some("gist.github.com") {
+        return None;
+    }
+    let id = url.path_segments()?.rfind(|segment| !segment.is_empty())?;
+    Some(format!("{}/{}", GIST_API, id))
+}
+
+/// Download a shared prompt. Gist pages are read through the API, taking
+/// the first markdown file; other URLs must serve the markdown itself.
+pub async fn fetch(network_config: &NetworkConfig, url: &str) -> Result<String, BouldyError> {
+    let parsed = tauri::Url::parse(url)
+        .map_err(|e| BouldyError::invalid_input(format!("Invalid URL '{}': {}", url, e)))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(BouldyError::invalid_input(format!(
+            "URL must be http or https: '{}'",
+            url
+        )));
+    }
+
+    let client = network::http_client(network_config)?;
+    let gist = gist_api_url(&parsed);
+    let response = client
+        .get(gist.as_deref().unwrap_or(url))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch {}: {}", url, e))?;
+    if !response.status().is_success() {
+        return Err(format!("{} returned {}", url, response.status()).into());
+    }
+    let text = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", url, e))?;
+    if gist.is_none() {
+        return Ok(text);
+    }
+
+    let body: Value = serde_json::from_str(&text)
+        .map_err(|e| BouldyError::parse("Invalid response from GitHub", e))?;
+    body["files"]
+        .as_object()
+        .and_then(|files| {
+            files
+                .iter()
+                .find(|(name, _)| name.ends_with(".md"))
+                .or_else(|| files.iter().next())
+        })
+        .and_then(|(_, file)| file["content"].as_str())
+        .map(str::to_string)
+        .ok_or_else(|| "Gist has no prompt file".into())
+}
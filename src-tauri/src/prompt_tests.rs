@@ -0,0 +1,165 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::BouldyError;
+use crate::fsutil;
+use crate::llm::{self, LlmProvider, LlmSettings};
+use crate::network::NetworkConfig;
+
+// One file per prompt, so runs of different prompts don't contend
+const RUNS_DIR: &str = "prompt-tests";
+// Older runs are dropped
+const MAX_RUNS: usize = 20;
+const SYSTEM_PROMPT: &str = "You are a helpful assistant.";
+
+/// Example inputs for a prompt's `{{variables}}` and what a good answer
+/// should do, kept with the prompt's metadata.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct PromptTestCase {
+    pub name: String,
+    #[serde(default)]
+    pub inputs: HashMap<String, String>,
+    // Free-form, e.g. "mentions the deadline"; shown next to the output
+    #[serde(default)]
+    pub expect: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PromptTestResult {
+    pub case: String,
+    pub rendered: String,
+    pub expect: Vec<String>,
+    #[serde(default)]
+    pub output: Option<String>,
+    // A failed call is recorded instead of failing the whole run
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PromptTestRun {
+    #[serde(rename = "ranAt")]
+    pub ran_at: String,
+    pub provider: LlmProvider,
+    pub model: Option<String>,
+    pub results: Vec<PromptTestResult>,
+}
+
+pub fn validate_cases(cases: &[PromptTestCase]) -> Result<(), BouldyError> {
+    for (index, case) in cases.iter().enumerate() {
+        if case.name.trim().is_empty() {
+            return Err(BouldyError::invalid_input(format!(
+                "Test case {} needs a name",
+                index + 1
+            )));
+        }
+        if cases[..index].iter().any(|other| other.name == case.name) {
+            return Err(BouldyError::invalid_input(format!(
+                "Duplicate test case name '{}'",
+                case.name
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// The prompt with `{{name}}` replaced by the case's inputs. Variables the
+/// case doesn't set are left as they are.
+pub fn render(content: &str, inputs: &HashMap<String, String>) -> String {
+    inputs
+        .iter()
+        .fold(content.to_string(), |rendered, (name, value)| {
+            rendered.replace(&format!("{{{{{}}}}}", name), value)
+        })
+}
+
+fn runs_path(vault_path: &str, id: &str) -> PathBuf {
+    Path::new(vault_path)
+        .join(".bouldy")
+        .join(RUNS_DIR)
+        .join(format!("{}.json", id))
+}
+
+/// Past runs for a prompt, newest first.
+pub fn load_runs(vault_path: &str, id: &str) -> Vec<PromptTestRun> {
+    fs::read_to_string(runs_path(vault_path, id))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn delete_runs(vault_path: &str, id: &str) {
+    let _ = fs::remove_file(runs_path(vault_path, id));
+}
+
+fn save_run(vault_path: &str, id: &str, run: &PromptTestRun) -> Result<(), BouldyError> {
+    let mut runs = load_runs(vault_path, id);
+    runs.insert(0, run.clone());
+    runs.truncate(MAX_RUNS);
+
+    let path = runs_path(vault_path, id);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| BouldyError::io("Failed to create prompt test directory", e))?;
+    }
+    let content = serde_json::to_string_pretty(&runs)
+        .map_err(|e| format!("Failed to serialize prompt test runs: {}", e))?;
+    fsutil::write_atomic(&path, content)
+        .map_err(|e| BouldyError::io("Failed to write prompt test runs", e))
+}
+
+/// Render every case, send it to `provider` (the configured one by default)
+/// and keep the outputs next to the expectations for comparison.
+pub async fn run_tests(
+    network_config: &NetworkConfig,
+    settings: &LlmSettings,
+    provider: Option<LlmProvider>,
+    vault_path: &str,
+    id: &str,
+    content: &str,
+    cases: &[PromptTestCase],
+) -> Result<PromptTestRun, BouldyError> {
+    if cases.is_empty() {
+        return Err(BouldyError::invalid_input("Prompt has no test cases"));
+    }
+
+    let mut settings = settings.clone();
+    // The configured model and server belong to the configured provider
+    if provider.is_some_and(|provider| settings.provider != Some(provider)) {
+        settings = LlmSettings {
+            provider,
+            ..Default::default()
+        };
+    }
+    let provider = settings
+        .provider
+        .ok_or_else(|| BouldyError::not_found("No LLM provider configured"))?;
+
+    let mut results = Vec::new();
+    for case in cases {
+        let rendered = render(content, &case.inputs);
+        let reply = llm::complete(network_config, &settings, SYSTEM_PROMPT, &rendered).await;
+        let (output, error) = match reply {
+            Ok(output) => (Some(output), None),
+            Err(e) => (None, Some(e.message().to_string())),
+        };
+        results.push(PromptTestResult {
+            case: case.name.clone(),
+            rendered,
+            expect: case.expect.clone(),
+            output,
+            error,
+        });
+    }
+
+    let run = PromptTestRun {
+        ran_at: chrono::Local::now().to_rfc3339(),
+        provider,
+        model: settings.model.clone(),
+        results,
+    };
+    save_run(vault_path, id, &run)?;
+    Ok(run)
+}
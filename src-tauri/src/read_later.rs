@@ -0,0 +1,300 @@
+use kuchikiki::traits::TendrilSink;
+use kuchikiki::NodeRef;
+use regex::Regex;
+use reqwest::Url;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use crate::error::BouldyError;
+use crate::file_drop::unique_destination;
+use crate::network::{self, NetworkConfig};
+use crate::{fsutil, html_markdown, markdown, vault, vault_lock};
+
+pub const READING_DIR: &str = "reading";
+
+// Paragraphs shorter than this are usually captions, bylines or buttons
+const MIN_PARAGRAPH_CHARS: usize = 25;
+
+fn positive_hint_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(r"(?i)article|body|content|entry|main|page|post|text|blog|story").unwrap()
+    })
+}
+
+fn negative_hint_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(
+            r"(?i)comment|meta|footer|footnote|sidebar|share|social|related|promo|sponsor|advert|\bad\b|nav|menu|widget|banner|subscribe|newsletter|cookie|popup|modal",
+        )
+        .unwrap()
+    })
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ReadLaterSaved {
+    pub path: String,
+    pub title: String,
+    pub url: String,
+}
+
+struct Article {
+    title: String,
+    author: Option<String>,
+    published: Option<String>,
+    body: String,
+}
+
+fn attribute(node: &NodeRef, name: &str) -> Option<String> {
+    node.as_element()?
+        .attributes
+        .borrow()
+        .get(name)
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// `<meta property|name="key" content="...">`
+fn meta(document: &NodeRef, keys: &[&str]) -> Option<String> {
+    keys.iter().find_map(|key| {
+        document
+            .select(&format!("meta[property=\"{0}\"], meta[name=\"{0}\"]", key))
+            .ok()?
+            .find_map(|node| attribute(node.as_node(), "content"))
+    })
+}
+
+// Class and id say a lot about what a container holds
+fn hint_weight(node: &NodeRef) -> f64 {
+    let hints = format!(
+        "{} {}",
+        attribute(node, "class").unwrap_or_default(),
+        attribute(node, "id").unwrap_or_default()
+    );
+    let mut weight = 0.0;
+    if positive_hint_regex().is_match(&hints) {
+        weight += 25.0;
+    }
+    if negative_hint_regex().is_match(&hints) {
+        weight -= 25.0;
+    }
+    weight
+}
+
+// Share of the text that sits inside links; high for menus and link lists
+fn link_density(node: &NodeRef) -> f64 {
+    let total = node.text_contents().chars().count();
+    if total == 0 {
+        return 0.0;
+    }
+    let linked: usize = node
+        .select("a")
+        .map(|links| links.map(|a| a.text_contents().chars().count()).sum())
+        .unwrap_or(0);
+    linked as f64 / total as f64
+}
+
+/// Find the element holding the article text: every paragraph scores its
+/// parent fully and its grandparent by half, adjusted for class/id hints
+/// and link density.
+fn main_content(document: &NodeRef) -> Option<NodeRef> {
+    let body = document.select_first("body").ok()?.as_node().clone();
+    let mut scores: Vec<(NodeRef, f64)> = Vec::new();
+    let mut index: HashMap<*const kuchikiki::Node, usize> = HashMap::new();
+
+    let mut add = |node: NodeRef, score: f64| {
+        let key = &*node as *const kuchikiki::Node;
+        match index.get(&key) {
+            Some(&i) => scores[i].1 += score,
+            None => {
+                index.insert(key, scores.len());
+                let base = hint_weight(&node);
+                scores.push((node, base + score));
+            }
+        }
+    };
+
+    for paragraph in body.select("p, pre, td").ok()? {
+        let paragraph = paragraph.as_node();
+        let text = collapse(&paragraph.text_contents());
+        let length = text.chars().count();
+        if length < MIN_PARAGRAPH_CHARS {
+            continue;
+        }
+        let score = 1.0 + text.matches(',').count() as f64 + (length / 100).min(3) as f64;
+
+        if let Some(parent) = paragraph.parent() {
+            if let Some(grandparent) = parent.parent().filter(|g| g.as_element().is_some()) {
+                add(grandparent, score / 2.0);
+            }
+            add(parent, score);
+        }
+    }
+
+    scores
+        .into_iter()
+        .map(|(node, score)| {
+            let score = score * (1.0 - link_density(&node));
+            (node, score)
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .filter(|(_, score)| *score > 0.0)
+        .map(|(node, _)| node)
+        // Pages without real paragraphs: an explicit <article>, else everything
+        .or_else(|| {
+            body.select_first("article, main, [role=main]")
+                .ok()
+                .map(|node| node.as_node().clone())
+        })
+        .or(Some(body))
+}
+
+// Drop sharing bars, related-post lists and the like nested in the article
+fn strip_clutter(content: &NodeRef) {
+    let clutter: Vec<NodeRef> = content
+        .descendants()
+        .filter(|node| {
+            let Some(element) = node.as_element() else {
+                return false;
+            };
+            let name: &str = &element.name.local;
+            if matches!(name, "aside" | "footer" | "nav") {
+                return true;
+            }
+            // Link lists are fine in the article itself, link-heavy boxes aren't
+            match name {
+                "div" | "section" => hint_weight(node) < 0.0 || link_density(node) > 0.5,
+                "ul" | "header" => hint_weight(node) < 0.0,
+                _ => false,
+            }
+        })
+        .collect();
+    for node in clutter {
+        node.detach();
+    }
+}
+
+fn extract(html: &str, url: &Url) -> Article {
+    let document = kuchikiki::parse_html().one(html).document_node;
+
+    let title = meta(&document, &["og:title", "twitter:title"])
+        .or_else(|| {
+            document
+                .select_first("title")
+                .ok()
+                .map(|title| title.text_contents())
+        })
+        .map(|title| collapse(&title))
+        .filter(|title| !title.is_empty())
+        .unwrap_or_else(|| url.host_str().unwrap_or("Article").to_string());
+
+    let author = meta(&document, &["author", "article:author", "twitter:creator"])
+        .map(|author| collapse(&author));
+    let published = meta(&document, &["article:published_time", "date", "pubdate"]);
+
+    let body = match main_content(&document) {
+        Some(content) => {
+            strip_clutter(&content);
+            html_markdown::convert_node(&content, Some(url))
+        }
+        None => String::new(),
+    };
+
+    Article {
+        title,
+        author,
+        published,
+        body,
+    }
+}
+
+fn save(vault_path: &str, url: &Url, article: Article) -> Result<ReadLaterSaved, BouldyError> {
+    let mut content = format!(
+        "---\ntitle: {}\nsource: {}\n",
+        markdown::yaml_string(&article.title),
+        markdown::yaml_string(url.as_str())
+    );
+    if let Some(ref author) = article.author {
+        content.push_str(&format!("author: {}\n", markdown::yaml_string(author)));
+    }
+    if let Some(ref published) = article.published {
+        content.push_str(&format!(
+            "published: {}\n",
+            markdown::yaml_string(published)
+        ));
+    }
+    content.push_str(&format!(
+        "saved: {}\ntags: [\"{}\"]\n---\n\n{}\n",
+        chrono::Local::now().to_rfc3339(),
+        READING_DIR,
+        if article.body.is_empty() {
+            format!("<{}>", url)
+        } else {
+            article.body
+        }
+    ));
+
+    let dir = vault::notes_dir(vault_path).join(READING_DIR);
+    let _lock = vault_lock::acquire(vault_path)?;
+    fs::create_dir_all(&dir).map_err(|e| BouldyError::io("Failed to create reading folder", e))?;
+    let path: PathBuf = unique_destination(
+        &dir,
+        &vault::sanitize_file_name(&format!("{}.md", article.title)),
+    );
+    fsutil::write_atomic(&path, content)
+        .map_err(|e| BouldyError::io("Failed to write article", e))?;
+
+    Ok(ReadLaterSaved {
+        path: path.to_string_lossy().to_string(),
+        title: article.title,
+        url: url.to_string(),
+    })
+}
+
+/// Download a web page, keep just the article text and save it as a
+/// markdown note in `reading/`, with the source URL and save date in its
+/// frontmatter.
+pub async fn capture_url(
+    network_config: &NetworkConfig,
+    vault_path: &str,
+    url: &str,
+) -> Result<ReadLaterSaved, BouldyError> {
+    let url = Url::parse(url.trim())
+        .ok()
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+        .ok_or_else(|| BouldyError::invalid_path(format!("Not a web page: '{}'", url)))?;
+
+    let response = network::http_client(network_config)?
+        .get(url.clone())
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Failed to fetch '{}': {}", url, e))?;
+
+    let is_html = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_none_or(|value| value.contains("html"));
+    if !is_html {
+        return Err(format!("'{}' is not an HTML page", url).into());
+    }
+
+    // Redirects may land on another site; links resolve against that
+    let url = response.url().clone();
+    let html = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read '{}': {}", url, e))?;
+
+    let article = extract(&html, &url);
+    save(vault_path, &url, article)
+}
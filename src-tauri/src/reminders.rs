@@ -0,0 +1,200 @@
+use chrono::Timelike;
+use std::collections::HashSet;
+use std::thread;
+use std::time::Duration;
+use tauri::AppHandle;
+
+use crate::error::BouldyError;
+use crate::notifications::{self, NotificationCategory};
+use crate::{badge, rollover, settings, todos, waiting};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+// How long after its due time a timed todo is still worth a reminder, e.g.
+// when quiet hours held it back or the app was closed
+const DUE_TIME_GRACE_MINUTES: i64 = 60;
+
+/// Start the background reminder loop. It re-reads the vault path from
+/// settings on every tick, so switching vaults needs no restart.
+pub fn start(app: AppHandle) {
+    thread::spawn(move || {
+        let mut last_notified_day: Option<String> = None;
+        let mut last_waiting_day: Option<String> = None;
+        let mut reminded: HashSet<String> = HashSet::new();
+        let mut last_rollover_day: Option<String> = None;
+
+        loop {
+            // First, so the due summary sees rescheduled todos
+            if let Err(e) = check_rollover(&app, &mut last_rollover_day) {
+                tracing::warn!("Overdue rollover failed - {}", e);
+            }
+            if let Err(e) = check_due_todos(&app, &mut last_notified_day) {
+                tracing::warn!("Reminder check failed - {}", e);
+            }
+            if let Err(e) = check_due_times(&app, &mut reminded) {
+                tracing::warn!("Due time check failed - {}", e);
+            }
+            if let Err(e) = check_waiting_todos(&app, &mut last_waiting_day) {
+                tracing::warn!("Waiting-for check failed - {}", e);
+            }
+            // Wake at the start of each minute so due times fire on time
+            let into_minute = Duration::from_secs(todos::local_now().second() as u64);
+            thread::sleep(CHECK_INTERVAL.saturating_sub(into_minute));
+        }
+    });
+}
+
+// Rolls overdue todos over once a day: on the first tick after startup and
+// again after midnight
+fn check_rollover(
+    app: &AppHandle,
+    last_rollover_day: &mut Option<String>,
+) -> Result<(), BouldyError> {
+    let Some(vault_path) = settings::load_settings(app)?.vault_path else {
+        return Ok(());
+    };
+    let today = todos::local_now().date().format("%Y-%m-%d").to_string();
+    if last_rollover_day.as_deref() == Some(today.as_str()) {
+        return Ok(());
+    }
+
+    rollover::run(app, &vault_path)?;
+    *last_rollover_day = Some(today);
+    Ok(())
+}
+
+// Refreshes the badge (so it rolls over at midnight) and sends at most one
+// "due today/overdue" summary per day. A summary held back by quiet hours
+// goes out on the first check after they end.
+fn check_due_todos(
+    app: &AppHandle,
+    last_notified_day: &mut Option<String>,
+) -> Result<(), BouldyError> {
+    let app_settings = settings::load_settings(app)?;
+    let Some(vault_path) = app_settings.vault_path else {
+        return Ok(());
+    };
+
+    let now = todos::local_now();
+    let today = now.date().format("%Y-%m-%d").to_string();
+
+    let todos_list = todos::load_todos(&vault_path)?;
+    let due = todos::due_or_overdue(&todos_list, now);
+
+    badge::set_due_count(app, due.len());
+
+    // The badge counts everything; reminders only what can be done here and now
+    let due: Vec<_> = due
+        .into_iter()
+        .filter(|todo| app_settings.contexts.is_relevant(todo, now.time()))
+        .collect();
+
+    if due.is_empty() || last_notified_day.as_deref() == Some(today.as_str()) {
+        return Ok(());
+    }
+
+    let overdue = due.iter().filter(|t| todos::is_overdue(t, now)).count();
+    let body = if due.len() == 1 {
+        due[0].title.clone()
+    } else if overdue == 0 {
+        format!("{} todos are due today", due.len())
+    } else {
+        format!(
+            "{} todos are due today or overdue ({} overdue)",
+            due.len(),
+            overdue
+        )
+    };
+
+    if notifications::notify(app, NotificationCategory::DueReminders, "Todos due", &body)? {
+        *last_notified_day = Some(today);
+    }
+
+    Ok(())
+}
+
+// One reminder per timed todo, `dueTimeLeadMinutes` before it's due.
+// `reminded` holds fingerprints, so changing the due time re-arms it.
+fn check_due_times(app: &AppHandle, reminded: &mut HashSet<String>) -> Result<(), BouldyError> {
+    let app_settings = settings::load_settings(app)?;
+    let Some(vault_path) = app_settings.vault_path else {
+        return Ok(());
+    };
+    let lead = chrono::Duration::minutes(app_settings.notifications.due_time_lead_minutes as i64);
+    let grace = chrono::Duration::minutes(DUE_TIME_GRACE_MINUTES);
+    let now = todos::local_now();
+
+    let todos_list = todos::load_todos(&vault_path)?;
+    let mut pending = HashSet::new();
+    for todo in todos_list.iter().filter(|todo| !todo.completed) {
+        let Some(todos::Due::DateTime(at)) = todo.due() else {
+            continue;
+        };
+        if now < at - lead || now >= at + grace {
+            continue;
+        }
+        if !app_settings.contexts.is_relevant(todo, now.time()) {
+            continue;
+        }
+
+        let key = todos::fingerprint(todo);
+        pending.insert(key.clone());
+        if reminded.contains(&key) {
+            continue;
+        }
+
+        let body = if at > now {
+            format!("{} (due at {})", todo.title, at.format("%H:%M"))
+        } else {
+            format!("{} (was due at {})", todo.title, at.format("%H:%M"))
+        };
+        if notifications::notify(app, NotificationCategory::DueReminders, "Todo due", &body)? {
+            reminded.insert(key);
+        }
+    }
+    // Forget todos that are done, edited or past the window
+    reminded.retain(|key| pending.contains(key));
+
+    Ok(())
+}
+
+// At most one nudge a day about waiting-for todos that haven't moved in
+// `waitingFollowUpDays`, so they can be chased up
+fn check_waiting_todos(
+    app: &AppHandle,
+    last_notified_day: &mut Option<String>,
+) -> Result<(), BouldyError> {
+    let app_settings = settings::load_settings(app)?;
+    let days = app_settings.notifications.waiting_follow_up_days;
+    let Some(vault_path) = app_settings.vault_path else {
+        return Ok(());
+    };
+
+    let today = todos::local_now().date().format("%Y-%m-%d").to_string();
+    if days == 0 || last_notified_day.as_deref() == Some(today.as_str()) {
+        return Ok(());
+    }
+
+    let todos_list = todos::load_todos(&vault_path)?;
+    let stalled = waiting::stalled(&vault_path, &todos_list, days)?;
+    if stalled.is_empty() {
+        return Ok(());
+    }
+
+    let body = match stalled.as_slice() {
+        [item] => match item.todo.delegated {
+            Some(ref person) => format!("{} (waiting on {})", item.todo.title, person),
+            None => item.todo.title.clone(),
+        },
+        items => format!(
+            "{} waiting-for todos haven't moved in {} days",
+            items.len(),
+            days
+        ),
+    };
+
+    if notifications::notify(app, NotificationCategory::DueReminders, "Follow up", &body)? {
+        *last_notified_day = Some(today);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,1136 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use reqwest::{Client, Method, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::async_runtime::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::caldav::ConflictPolicy;
+use crate::error::BouldyError;
+use crate::network::{self, NetworkConfig};
+use crate::todo_store::TodoStore;
+use crate::todos::{self, TodoItem};
+use crate::{fsutil, secrets, settings};
+
+const SECRET_SERVICE: &str = "bouldy-remote-tasks";
+const STATE_FILE: &str = "remote-tasks-sync.json";
+
+// How long to wait for the browser to come back from the consent screen
+const AUTH_TIMEOUT: Duration = Duration::from_secs(300);
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+pub const MIN_SYNC_INTERVAL_MINUTES: u32 = 5;
+
+// The manual command and the background loop share the state file
+static SYNCING: Mutex<()> = Mutex::const_new(());
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Provider {
+    #[serde(rename = "google")]
+    Google,
+    #[serde(rename = "microsoft")]
+    Microsoft,
+}
+
+impl Provider {
+    fn auth_url(self) -> &'static str {
+        match self {
+            Provider::Google => "https://accounts.google.com/o/oauth2/v2/auth",
+            Provider::Microsoft => "https://login.microsoftonline.com/common/oauth2/v2.0/authorize",
+        }
+    }
+
+    fn token_url(self) -> &'static str {
+        match self {
+            Provider::Google => "https://oauth2.googleapis.com/token",
+            Provider::Microsoft => "https://login.microsoftonline.com/common/oauth2/v2.0/token",
+        }
+    }
+
+    fn scope(self) -> &'static str {
+        match self {
+            Provider::Google => "https://www.googleapis.com/auth/tasks",
+            Provider::Microsoft => "offline_access Tasks.ReadWrite",
+        }
+    }
+
+    fn api_base(self) -> &'static str {
+        match self {
+            Provider::Google => "https://tasks.googleapis.com/tasks/v1",
+            Provider::Microsoft => "https://graph.microsoft.com/v1.0/me/todo",
+        }
+    }
+
+    // Google wants the loopback IP, Microsoft only allows any port on localhost
+    fn redirect_host(self) -> &'static str {
+        match self {
+            Provider::Google => "127.0.0.1",
+            Provider::Microsoft => "localhost",
+        }
+    }
+}
+
+/// Google Tasks / Microsoft To Do account settings. OAuth tokens live in
+/// the OS keyring, never in settings.json.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct RemoteTasksSettings {
+    #[serde(default)]
+    pub provider: Option<Provider>,
+    // The user's own OAuth app registration
+    #[serde(rename = "clientId", default)]
+    pub client_id: Option<String>,
+    // Google issues desktop clients a secret that isn't confidential
+    #[serde(rename = "clientSecret", default)]
+    pub client_secret: Option<String>,
+    #[serde(rename = "listId", default)]
+    pub list_id: Option<String>,
+    // Only todos in this project are mirrored, and pulled tasks join it
+    #[serde(default)]
+    pub project: Option<String>,
+    #[serde(rename = "conflictPolicy", default)]
+    pub conflict_policy: ConflictPolicy,
+    // Background sync period; unset leaves syncing to the command
+    #[serde(rename = "syncIntervalMinutes", default)]
+    pub sync_interval_minutes: Option<u32>,
+}
+
+impl RemoteTasksSettings {
+    pub fn validate(&self) -> Result<(), BouldyError> {
+        if let Some(minutes) = self.sync_interval_minutes {
+            if minutes < MIN_SYNC_INTERVAL_MINUTES {
+                return Err(BouldyError::invalid_input(format!(
+                    "Remote task sync interval must be at least {} minutes",
+                    MIN_SYNC_INTERVAL_MINUTES
+                )));
+            }
+        }
+        if let Some(ref project) = self.project {
+            if project.is_empty() || project.contains(char::is_whitespace) {
+                return Err(BouldyError::invalid_input(format!(
+                    "Invalid project name '{}'",
+                    project
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn account(&self) -> Result<(Provider, &str), BouldyError> {
+        match (self.provider, &self.client_id) {
+            (Some(provider), Some(client_id)) => Ok((provider, client_id)),
+            _ => Err("Remote task sync is not configured".into()),
+        }
+    }
+
+    fn list(&self) -> Result<&str, BouldyError> {
+        self.list_id
+            .as_deref()
+            .ok_or_else(|| "No remote task list chosen".into())
+    }
+
+    fn secret_account(&self) -> Result<String, BouldyError> {
+        let (provider, client_id) = self.account()?;
+        let provider = serde_json::to_value(provider).unwrap_or_default();
+        Ok(format!(
+            "{}:{}",
+            provider.as_str().unwrap_or_default(),
+            client_id
+        ))
+    }
+
+    fn mirrors(&self, todo: &TodoItem) -> bool {
+        self.project
+            .as_ref()
+            .is_none_or(|project| todo.projects.contains(project))
+    }
+}
+
+// === OAuth ===
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct StoredToken {
+    refresh_token: String,
+    access_token: String,
+    // Unix seconds
+    expires_at: i64,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: Option<i64>,
+}
+
+fn load_token(config: &RemoteTasksSettings) -> Result<StoredToken, BouldyError> {
+    let token = secrets::get(SECRET_SERVICE, &config.secret_account()?)?
+        .ok_or_else(|| BouldyError::not_found("Not signed in to the remote task service"))?;
+    serde_json::from_str(&token)
+        .map_err(|e| BouldyError::parse("Invalid stored remote task token", e))
+}
+
+fn save_token(config: &RemoteTasksSettings, token: &StoredToken) -> Result<(), BouldyError> {
+    let token = serde_json::to_string(token)
+        .map_err(|e| format!("Failed to serialize remote task token: {}", e))?;
+    secrets::set(SECRET_SERVICE, &config.secret_account()?, &token)
+}
+
+/// Forget the stored tokens, e.g. before switching accounts.
+pub fn sign_out(config: &RemoteTasksSettings) -> Result<(), BouldyError> {
+    secrets::delete(SECRET_SERVICE, &config.secret_account()?)
+}
+
+async fn request_token(
+    client: &Client,
+    config: &RemoteTasksSettings,
+    params: &[(&str, &str)],
+    previous_refresh: Option<&str>,
+) -> Result<StoredToken, BouldyError> {
+    let (provider, client_id) = config.account()?;
+    let mut form = vec![("client_id", client_id)];
+    if let Some(ref secret) = config.client_secret {
+        form.push(("client_secret", secret));
+    }
+    form.extend_from_slice(params);
+
+    let response = client
+        .post(provider.token_url())
+        .form(&form)
+        .send()
+        .await
+        .map_err(|e| format!("Token request failed: {}", e))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("Token request returned {}: {}", status, body.trim()).into());
+    }
+    let token: TokenResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid token response: {}", e))?;
+
+    // Refreshing may or may not rotate the refresh token
+    let refresh_token = token
+        .refresh_token
+        .or_else(|| previous_refresh.map(|refresh| refresh.to_string()))
+        .ok_or("The token response had no refresh token")?;
+
+    Ok(StoredToken {
+        refresh_token,
+        access_token: token.access_token,
+        expires_at: chrono::Utc::now().timestamp() + token.expires_in.unwrap_or(3600),
+    })
+}
+
+async fn access_token(
+    client: &Client,
+    config: &RemoteTasksSettings,
+) -> Result<String, BouldyError> {
+    let token = load_token(config)?;
+    // A minute of slack so the token doesn't expire mid-sync
+    if token.expires_at > chrono::Utc::now().timestamp() + 60 {
+        return Ok(token.access_token);
+    }
+
+    let refreshed = request_token(
+        client,
+        config,
+        &[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", &token.refresh_token),
+        ],
+        Some(&token.refresh_token),
+    )
+    .await?;
+    save_token(config, &refreshed)?;
+    Ok(refreshed.access_token)
+}
+
+// Serve one redirect back from the consent screen and return its code
+fn wait_for_redirect(listener: TcpListener, state: &str) -> Result<String, BouldyError> {
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| BouldyError::io("Failed to listen for sign-in", e))?;
+    let deadline = Instant::now() + AUTH_TIMEOUT;
+
+    loop {
+        let mut stream = match listener.accept() {
+            Ok((stream, _)) => stream,
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                if Instant::now() > deadline {
+                    return Err(BouldyError::cancelled("Sign-in timed out"));
+                }
+                thread::sleep(Duration::from_millis(200));
+                continue;
+            }
+            Err(e) => return Err(BouldyError::io("Failed to accept sign-in redirect", e)),
+        };
+        let _ = stream.set_nonblocking(false);
+        let _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+
+        let mut buffer = [0u8; 8192];
+        let read = stream.read(&mut buffer).unwrap_or(0);
+        let request = String::from_utf8_lossy(&buffer[..read]);
+        let target = request
+            .lines()
+            .next()
+            .and_then(|line| line.split_whitespace().nth(1))
+            .unwrap_or("/");
+        let Ok(url) = Url::parse(&format!("http://localhost{}", target)) else {
+            continue;
+        };
+        let params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+
+        // Browsers also ask for /favicon.ico and the like
+        if !params.contains_key("state") {
+            let _ = stream.write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n");
+            continue;
+        }
+
+        let page =
+            "<html><body><p>You can close this window and return to bouldy.</p></body></html>";
+        let _ = stream.write_all(
+            format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                page.len(),
+                page
+            )
+            .as_bytes(),
+        );
+
+        if params.get("state").map(String::as_str) != Some(state) {
+            return Err("Sign-in redirect had an unexpected state".into());
+        }
+        if let Some(error) = params.get("error") {
+            return Err(BouldyError::cancelled(format!(
+                "Sign-in was denied: {}",
+                error
+            )));
+        }
+        return params
+            .get("code")
+            .cloned()
+            .ok_or_else(|| "Sign-in redirect had no code".into());
+    }
+}
+
+/// Sign in with the provider's consent screen (authorization code flow
+/// with PKCE and a loopback redirect), keeping the tokens in the keyring.
+/// `open_url` shows the consent screen in the user's browser.
+pub async fn authorize(
+    config: &RemoteTasksSettings,
+    network_config: &NetworkConfig,
+    open_url: impl FnOnce(&str) -> Result<(), BouldyError>,
+) -> Result<(), BouldyError> {
+    let (provider, client_id) = config.account()?;
+
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .map_err(|e| BouldyError::io("Failed to listen for sign-in", e))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| BouldyError::io("Failed to listen for sign-in", e))?
+        .port();
+    let redirect_uri = format!("http://{}:{}", provider.redirect_host(), port);
+
+    let verifier = format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    );
+    let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+    let state = uuid::Uuid::new_v4().simple().to_string();
+
+    let mut params = vec![
+        ("client_id", client_id),
+        ("response_type", "code"),
+        ("redirect_uri", redirect_uri.as_str()),
+        ("scope", provider.scope()),
+        ("state", state.as_str()),
+        ("code_challenge", challenge.as_str()),
+        ("code_challenge_method", "S256"),
+    ];
+    if provider == Provider::Google {
+        // Needed for a refresh token
+        params.extend([("access_type", "offline"), ("prompt", "consent")]);
+    }
+    let auth_url = Url::parse_with_params(provider.auth_url(), &params)
+        .map_err(|e| format!("Invalid authorization URL: {}", e))?;
+
+    open_url(auth_url.as_str())?;
+
+    let code = tauri::async_runtime::spawn_blocking(move || wait_for_redirect(listener, &state))
+        .await
+        .map_err(|e| format!("Sign-in task failed: {}", e))??;
+
+    let client = network::http_client(network_config)?;
+    let token = request_token(
+        &client,
+        config,
+        &[
+            ("grant_type", "authorization_code"),
+            ("code", &code),
+            ("code_verifier", &verifier),
+            ("redirect_uri", &redirect_uri),
+        ],
+        None,
+    )
+    .await?;
+    save_token(config, &token)
+}
+
+// === Tasks ===
+
+/// The parts of a task that are synced, in the form the provider can hold
+/// them, compared against the last synced copy to tell which side changed.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+struct TaskFields {
+    title: String,
+    completed: bool,
+    // YYYY-MM-DD; neither service keeps a due time
+    due: Option<String>,
+    priority: Option<String>,
+}
+
+impl TaskFields {
+    // `subtask` for Microsoft checklist items, which have only a title and
+    // a checkbox
+    fn of(todo: &TodoItem, provider: Provider, subtask: bool) -> Self {
+        let due = todo
+            .due_date
+            .as_ref()
+            .map(|due| due.chars().take(10).collect());
+        // To Do's importance is high/normal/low; Google has no priority
+        let priority = match (provider, todo.priority.as_deref()) {
+            (Provider::Microsoft, Some("A")) => Some("A".to_string()),
+            (Provider::Microsoft, Some("B") | None) => None,
+            (Provider::Microsoft, Some(_)) => Some("C".to_string()),
+            (Provider::Google, _) => None,
+        };
+
+        if subtask && provider == Provider::Microsoft {
+            return Self {
+                title: todo.title.clone(),
+                completed: todo.completed,
+                due: None,
+                priority: None,
+            };
+        }
+        Self {
+            title: todo.title.clone(),
+            completed: todo.completed,
+            due,
+            priority,
+        }
+    }
+
+    // Only overwrite what the provider could tell apart, so a due time or a
+    // (D) priority survives a pull
+    fn apply(&self, todo: &mut TodoItem, provider: Provider, subtask: bool) {
+        let current = Self::of(todo, provider, subtask);
+        todo.title = self.title.clone();
+        todo.completed = self.completed;
+        if current.due != self.due {
+            todo.due_date = self.due.clone();
+        }
+        if current.priority != self.priority {
+            todo.priority = self.priority.clone();
+        }
+    }
+}
+
+struct RemoteTask {
+    id: String,
+    // The remote id of the task this is a subtask of
+    parent: Option<String>,
+    fields: TaskFields,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct RemoteList {
+    pub id: String,
+    pub title: String,
+}
+
+struct Api {
+    client: Client,
+    provider: Provider,
+    token: String,
+}
+
+impl Api {
+    async fn connect(
+        config: &RemoteTasksSettings,
+        network_config: &NetworkConfig,
+    ) -> Result<Self, BouldyError> {
+        let (provider, _) = config.account()?;
+        let client = network::http_client(network_config)?;
+        let token = access_token(&client, config).await?;
+        Ok(Self {
+            client,
+            provider,
+            token,
+        })
+    }
+
+    fn endpoint(&self, segments: &[&str]) -> Url {
+        let mut url = Url::parse(self.provider.api_base()).expect("valid API base URL");
+        url.path_segments_mut()
+            .expect("API base URL has a path")
+            .extend(segments);
+        url
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        url: Url,
+        body: Option<Value>,
+    ) -> Result<Value, BouldyError> {
+        let request = self.client.request(method, url).bearer_auth(&self.token);
+        let request = match body {
+            Some(body) => request.json(&body),
+            None => request,
+        };
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Remote task request failed: {}", e))?;
+
+        match response.status() {
+            StatusCode::NOT_FOUND => Err(BouldyError::not_found("Remote task not found")),
+            status if !status.is_success() => {
+                Err(format!("Remote task service returned {}", status).into())
+            }
+            StatusCode::NO_CONTENT => Ok(Value::Null),
+            _ => response
+                .json()
+                .await
+                .map_err(|e| format!("Invalid remote task response: {}", e).into()),
+        }
+    }
+
+    async fn fetch_lists(&self) -> Result<Vec<RemoteList>, BouldyError> {
+        let (url, items, title) = match self.provider {
+            Provider::Google => (self.endpoint(&["users", "@me", "lists"]), "items", "title"),
+            Provider::Microsoft => (self.endpoint(&["lists"]), "value", "displayName"),
+        };
+        let body = self.send(Method::GET, url, None).await?;
+
+        Ok(body[items]
+            .as_array()
+            .map(|lists| {
+                lists
+                    .iter()
+                    .filter_map(|list| {
+                        Some(RemoteList {
+                            id: list["id"].as_str()?.to_string(),
+                            title: list[title].as_str().unwrap_or_default().to_string(),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    async fn fetch_tasks(&self, list_id: &str) -> Result<Vec<RemoteTask>, BouldyError> {
+        let mut tasks = Vec::new();
+        let mut next = Some(match self.provider {
+            Provider::Google => {
+                let mut url = self.endpoint(&["lists", list_id, "tasks"]);
+                url.query_pairs_mut()
+                    .append_pair("showCompleted", "true")
+                    .append_pair("showHidden", "true")
+                    .append_pair("maxResults", "100");
+                url
+            }
+            Provider::Microsoft => {
+                let mut url = self.endpoint(&["lists", list_id, "tasks"]);
+                url.query_pairs_mut()
+                    .append_pair("$expand", "checklistItems");
+                url
+            }
+        });
+
+        while let Some(url) = next.take() {
+            let body = self.send(Method::GET, url.clone(), None).await?;
+            match self.provider {
+                Provider::Google => {
+                    for item in body["items"].as_array().into_iter().flatten() {
+                        if let Some(task) = google_task(item) {
+                            tasks.push(task);
+                        }
+                    }
+                    if let Some(page) = body["nextPageToken"].as_str() {
+                        let mut url = url;
+                        let pairs: Vec<(String, String)> = url
+                            .query_pairs()
+                            .into_owned()
+                            .filter(|(key, _)| key != "pageToken")
+                            .collect();
+                        url.query_pairs_mut()
+                            .clear()
+                            .extend_pairs(pairs)
+                            .append_pair("pageToken", page);
+                        next = Some(url);
+                    }
+                }
+                Provider::Microsoft => {
+                    for item in body["value"].as_array().into_iter().flatten() {
+                        tasks.extend(microsoft_tasks(item));
+                    }
+                    if let Some(link) = body["@odata.nextLink"].as_str() {
+                        next = Some(
+                            Url::parse(link)
+                                .map_err(|e| format!("Invalid next page link: {}", e))?,
+                        );
+                    }
+                }
+            }
+        }
+
+        Ok(tasks)
+    }
+
+    fn task_url(&self, list_id: &str, id: &str, parent: Option<&str>) -> Url {
+        match (self.provider, parent) {
+            (Provider::Microsoft, Some(parent)) => {
+                self.endpoint(&["lists", list_id, "tasks", parent, "checklistItems", id])
+            }
+            _ => self.endpoint(&["lists", list_id, "tasks", id]),
+        }
+    }
+
+    /// Create a task, as a subtask of `parent` if given. Returns its id.
+    async fn create_task(
+        &self,
+        list_id: &str,
+        fields: &TaskFields,
+        parent: Option<&str>,
+    ) -> Result<String, BouldyError> {
+        let url = match (self.provider, parent) {
+            (Provider::Google, parent) => {
+                let mut url = self.endpoint(&["lists", list_id, "tasks"]);
+                if let Some(parent) = parent {
+                    url.query_pairs_mut().append_pair("parent", parent);
+                }
+                url
+            }
+            (Provider::Microsoft, Some(parent)) => {
+                self.endpoint(&["lists", list_id, "tasks", parent, "checklistItems"])
+            }
+            (Provider::Microsoft, None) => self.endpoint(&["lists", list_id, "tasks"]),
+        };
+
+        let body = self
+            .send(
+                Method::POST,
+                url,
+                Some(task_body(self.provider, fields, parent.is_some())),
+            )
+            .await?;
+        body["id"]
+            .as_str()
+            .map(|id| id.to_string())
+            .ok_or_else(|| "Created task has no id".into())
+    }
+
+    async fn update_task(
+        &self,
+        list_id: &str,
+        id: &str,
+        fields: &TaskFields,
+        parent: Option<&str>,
+    ) -> Result<(), BouldyError> {
+        let url = self.task_url(list_id, id, parent);
+        self.send(
+            Method::PATCH,
+            url,
+            Some(task_body(self.provider, fields, parent.is_some())),
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+fn google_task(item: &Value) -> Option<RemoteTask> {
+    let title = item["title"].as_str().unwrap_or_default().trim();
+    // Blank rows the Google Tasks UI leaves behind
+    if item["deleted"].as_bool() == Some(true) || title.is_empty() {
+        return None;
+    }
+    Some(RemoteTask {
+        id: item["id"].as_str()?.to_string(),
+        parent: item["parent"].as_str().map(|parent| parent.to_string()),
+        fields: TaskFields {
+            title: title.to_string(),
+            completed: item["status"].as_str() == Some("completed"),
+            due: item["due"]
+                .as_str()
+                .map(|due| due.chars().take(10).collect()),
+            priority: None,
+        },
+    })
+}
+
+// A To Do task and its checklist items
+fn microsoft_tasks(item: &Value) -> Vec<RemoteTask> {
+    let Some(id) = item["id"].as_str() else {
+        return Vec::new();
+    };
+
+    let priority = match item["importance"].as_str() {
+        Some("high") => Some("A".to_string()),
+        Some("low") => Some("C".to_string()),
+        _ => None,
+    };
+    let mut tasks = vec![RemoteTask {
+        id: id.to_string(),
+        parent: None,
+        fields: TaskFields {
+            title: item["title"]
+                .as_str()
+                .unwrap_or_default()
+                .trim()
+                .to_string(),
+            completed: item["status"].as_str() == Some("completed"),
+            due: item["dueDateTime"]["dateTime"]
+                .as_str()
+                .map(|due| due.chars().take(10).collect()),
+            priority,
+        },
+    }];
+
+    for checklist_item in item["checklistItems"].as_array().into_iter().flatten() {
+        let Some(child_id) = checklist_item["id"].as_str() else {
+            continue;
+        };
+        tasks.push(RemoteTask {
+            id: child_id.to_string(),
+            parent: Some(id.to_string()),
+            fields: TaskFields {
+                title: checklist_item["displayName"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string(),
+                completed: checklist_item["isChecked"].as_bool() == Some(true),
+                due: None,
+                priority: None,
+            },
+        });
+    }
+
+    tasks
+}
+
+fn task_body(provider: Provider, fields: &TaskFields, subtask: bool) -> Value {
+    match provider {
+        Provider::Google => {
+            let mut body = json!({
+                "title": fields.title,
+                "status": if fields.completed { "completed" } else { "needsAction" },
+                "due": fields.due.as_ref().map(|due| format!("{}T00:00:00.000Z", due)),
+            });
+            // Reopening a task needs its completion time cleared too
+            if !fields.completed {
+                body["completed"] = Value::Null;
+            }
+            body
+        }
+        Provider::Microsoft if subtask => json!({
+            "displayName": fields.title,
+            "isChecked": fields.completed,
+        }),
+        Provider::Microsoft => json!({
+            "title": fields.title,
+            "status": if fields.completed { "completed" } else { "notStarted" },
+            "importance": match fields.priority.as_deref() {
+                Some("A") => "high",
+                Some(_) => "low",
+                None => "normal",
+            },
+            "dueDateTime": fields.due.as_ref().map(|due| json!({
+                "dateTime": format!("{}T00:00:00", due),
+                "timeZone": "UTC",
+            })),
+        }),
+    }
+}
+
+// === Sync ===
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct SyncedTask {
+    remote_id: String,
+    // Remote id of the parent task; subtasks keep the parent they were
+    // created under
+    #[serde(default)]
+    parent: Option<String>,
+    fields: TaskFields,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+struct SyncState {
+    // "provider:listId" the state belongs to; choosing another list starts over
+    list: String,
+    // Keyed by todo uid
+    tasks: HashMap<String, SyncedTask>,
+}
+
+fn state_path(vault_path: &str) -> PathBuf {
+    Path::new(vault_path).join(".bouldy").join(STATE_FILE)
+}
+
+fn load_state(vault_path: &str, list: &str) -> SyncState {
+    fs::read_to_string(state_path(vault_path))
+        .ok()
+        .and_then(|content| serde_json::from_str::<SyncState>(&content).ok())
+        .filter(|state| state.list == list)
+        .unwrap_or_else(|| SyncState {
+            list: list.to_string(),
+            tasks: HashMap::new(),
+        })
+}
+
+fn save_state(vault_path: &str, state: &SyncState) -> Result<(), BouldyError> {
+    let content = serde_json::to_string_pretty(state)
+        .map_err(|e| format!("Failed to serialize remote task sync state: {}", e))?;
+    fsutil::write_atomic(&state_path(vault_path), content)
+        .map_err(|e| BouldyError::io("Failed to write remote task sync state", e))
+}
+
+/// A task edited on both sides since the last sync.
+#[derive(Serialize, Clone, Debug)]
+pub struct RemoteTaskConflict {
+    pub uid: String,
+    pub title: String,
+    // Which side's version was kept: "local" or "remote"
+    pub kept: String,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct RemoteSyncReport {
+    pub pulled: usize,
+    pub pushed: usize,
+    pub removed: usize,
+    pub conflicts: Vec<RemoteTaskConflict>,
+    pub errors: Vec<String>,
+}
+
+struct Push {
+    uid: String,
+    // None for a task the service doesn't have yet
+    remote_id: Option<String>,
+    // Remote parent for an update, local parent uid for a new task
+    parent: Option<String>,
+    fields: TaskFields,
+}
+
+/// Merge the remote tasks into `todos` and work out what to push, the way
+/// CalDAV sync does: whichever side changed since the last sync wins, and
+/// `policy` decides when both did.
+fn reconcile(
+    todos: &mut Vec<TodoItem>,
+    remote: &[RemoteTask],
+    state: &mut SyncState,
+    config: &RemoteTasksSettings,
+    provider: Provider,
+    report: &mut RemoteSyncReport,
+) -> (Vec<Push>, bool) {
+    let mut pushes = Vec::new();
+    let mut changed = false;
+
+    let mut uids: HashMap<String, String> = state
+        .tasks
+        .iter()
+        .map(|(uid, synced)| (synced.remote_id.clone(), uid.clone()))
+        .collect();
+    for task in remote {
+        uids.entry(task.id.clone()).or_insert_with(todos::new_uid);
+    }
+
+    for task in remote {
+        let uid = uids[task.id.as_str()].clone();
+        let subtask = task.parent.is_some();
+        let synced = state.tasks.get(&uid);
+        let remote_changed = synced.is_none_or(|synced| synced.fields != task.fields);
+
+        match todos
+            .iter_mut()
+            .find(|todo| todo.uid.as_deref() == Some(uid.as_str()))
+        {
+            Some(todo) => {
+                let local = TaskFields::of(todo, provider, subtask);
+                let local_changed = synced.is_none_or(|synced| synced.fields != local);
+                let take_remote = local == task.fields
+                    || (remote_changed
+                        && (!local_changed
+                            || config.conflict_policy == ConflictPolicy::PreferRemote));
+
+                if local != task.fields && remote_changed && local_changed {
+                    report.conflicts.push(RemoteTaskConflict {
+                        uid: uid.clone(),
+                        title: todo.title.clone(),
+                        kept: if take_remote { "remote" } else { "local" }.to_string(),
+                    });
+                }
+
+                if take_remote {
+                    if local != task.fields {
+                        task.fields.apply(todo, provider, subtask);
+                        report.pulled += 1;
+                        changed = true;
+                    }
+                } else if local_changed {
+                    pushes.push(Push {
+                        uid,
+                        remote_id: Some(task.id.clone()),
+                        parent: task.parent.clone(),
+                        fields: local,
+                    });
+                    continue;
+                }
+            }
+            // Deleted or archived here since the last sync; the remote copy
+            // is left alone unless it changes again
+            None if !remote_changed => continue,
+            None => {
+                let mut todo = TodoItem {
+                    id: 0,
+                    title: String::new(),
+                    completed: false,
+                    due_date: None,
+                    priority: None,
+                    projects: config.project.iter().cloned().collect(),
+                    contexts: Vec::new(),
+                    created_date: Some(chrono::Local::now().format("%Y-%m-%d").to_string()),
+                    uid: Some(uid.clone()),
+                    parent: task
+                        .parent
+                        .as_deref()
+                        .and_then(|parent| uids.get(parent))
+                        .cloned(),
+                    delegated: None,
+                    estimate: None,
+                    description: None,
+                };
+                task.fields.apply(&mut todo, provider, subtask);
+                todos::push(todos, todo);
+                report.pulled += 1;
+                changed = true;
+            }
+        }
+
+        state.tasks.insert(
+            uid,
+            SyncedTask {
+                remote_id: task.id.clone(),
+                parent: task.parent.clone(),
+                fields: task.fields.clone(),
+            },
+        );
+    }
+
+    // Mirrored todos the service doesn't have
+    let remote_ids: HashSet<&str> = remote.iter().map(|task| task.id.as_str()).collect();
+    let mirrored: HashSet<String> = todos
+        .iter()
+        .filter(|todo| config.mirrors(todo))
+        .filter_map(|todo| todo.uid.clone())
+        .collect();
+    let mut removed = HashSet::new();
+    for todo in todos.iter_mut().filter(|todo| config.mirrors(todo)) {
+        let uid = match todo.uid.clone() {
+            Some(uid) => uid,
+            None => {
+                let uid = todos::new_uid();
+                todo.uid = Some(uid.clone());
+                changed = true;
+                uid
+            }
+        };
+        let synced = state.tasks.get(&uid);
+        if synced.is_some_and(|synced| remote_ids.contains(synced.remote_id.as_str())) {
+            continue;
+        }
+
+        // Only one level of subtasks, under a task that is mirrored too
+        let parent = todo
+            .parent
+            .clone()
+            .filter(|parent| mirrored.contains(parent));
+        let local = TaskFields::of(todo, provider, parent.is_some());
+
+        // Deleted remotely; follow unless it was edited here since
+        if synced.is_some_and(|synced| synced.fields == local) {
+            removed.insert(uid);
+            continue;
+        }
+
+        pushes.push(Push {
+            uid,
+            remote_id: None,
+            parent,
+            fields: local,
+        });
+    }
+
+    if !removed.is_empty() {
+        todos.retain(|todo| !todo.uid.as_deref().is_some_and(|uid| removed.contains(uid)));
+        for uid in &removed {
+            state.tasks.remove(uid);
+        }
+        report.removed += removed.len();
+        changed = true;
+    }
+
+    (pushes, changed)
+}
+
+/// Two-way sync of the chosen project's todos with the chosen Google Tasks
+/// or Microsoft To Do list. Subtasks map to `parent:` todos (checklist
+/// items on To Do); a subtask keeps the parent it was created under.
+pub async fn sync(
+    store: &TodoStore,
+    vault_path: &str,
+    config: &RemoteTasksSettings,
+    network_config: &NetworkConfig,
+) -> Result<RemoteSyncReport, BouldyError> {
+    let _syncing = SYNCING.lock().await;
+
+    let (provider, _) = config.account()?;
+    let list_id = config.list()?;
+    let api = Api::connect(config, network_config).await?;
+    let remote = api.fetch_tasks(list_id).await?;
+
+    let provider_name = serde_json::to_value(provider).unwrap_or_default();
+    let mut state = load_state(
+        vault_path,
+        &format!("{}:{}", provider_name.as_str().unwrap_or_default(), list_id),
+    );
+    let mut report = RemoteSyncReport::default();
+    let mut pushes = store.mutate_if_changed(vault_path, |todos| {
+        Ok(reconcile(
+            todos,
+            &remote,
+            &mut state,
+            config,
+            provider,
+            &mut report,
+        ))
+    })?;
+
+    // Parents first, so new subtasks can be created under them
+    pushes.sort_by_key(|push| push.remote_id.is_none() && push.parent.is_some());
+
+    for push in pushes {
+        let result = match &push.remote_id {
+            Some(remote_id) => api
+                .update_task(list_id, remote_id, &push.fields, push.parent.as_deref())
+                .await
+                .map(|()| (remote_id.clone(), push.parent.clone())),
+            None => {
+                let parent = push
+                    .parent
+                    .as_ref()
+                    .and_then(|uid| state.tasks.get(uid))
+                    .map(|synced| synced.remote_id.clone());
+                api.create_task(list_id, &push.fields, parent.as_deref())
+                    .await
+                    .map(|remote_id| (remote_id, parent))
+            }
+        };
+
+        match result {
+            Ok((remote_id, parent)) => {
+                report.pushed += 1;
+                state.tasks.insert(
+                    push.uid,
+                    SyncedTask {
+                        remote_id,
+                        parent,
+                        fields: push.fields,
+                    },
+                );
+            }
+            Err(e) => report.errors.push(format!("{}: {}", push.fields.title, e)),
+        }
+    }
+
+    save_state(vault_path, &state)?;
+    Ok(report)
+}
+
+/// The signed-in account's task lists, to choose one to mirror.
+pub async fn list_remote_lists(
+    config: &RemoteTasksSettings,
+    network_config: &NetworkConfig,
+) -> Result<Vec<RemoteList>, BouldyError> {
+    Api::connect(config, network_config)
+        .await?
+        .fetch_lists()
+        .await
+}
+
+/// Sync using the current settings and tell the frontend what happened.
+pub async fn sync_and_notify(
+    app: &AppHandle,
+    vault_path: &str,
+) -> Result<RemoteSyncReport, BouldyError> {
+    let app_settings = settings::load_settings(app)?;
+    let report = sync(
+        &app.state::<TodoStore>(),
+        vault_path,
+        &app_settings.remote_tasks,
+        &app_settings.network,
+    )
+    .await?;
+
+    if report.pulled > 0 || report.removed > 0 {
+        let _ = app.emit("todos_changed", ());
+    }
+    if !report.conflicts.is_empty() {
+        let _ = app.emit("remote-tasks:conflicts", &report.conflicts);
+    }
+
+    Ok(report)
+}
+
+/// Start the background sync loop. Settings are re-read on every tick, so
+/// changing the interval or the list needs no restart.
+pub fn start(app: AppHandle) {
+    thread::spawn(move || {
+        let mut last_sync: Option<Instant> = None;
+
+        loop {
+            thread::sleep(CHECK_INTERVAL);
+
+            let Ok(app_settings) = settings::load_settings(&app) else {
+                continue;
+            };
+            let (Some(minutes), Some(vault_path)) = (
+                app_settings.remote_tasks.sync_interval_minutes,
+                app_settings.vault_path,
+            ) else {
+                continue;
+            };
+            let interval = Duration::from_secs(u64::from(minutes) * 60);
+            if last_sync.is_some_and(|at| at.elapsed() < interval) {
+                continue;
+            }
+
+            last_sync = Some(Instant::now());
+            if let Err(e) = tauri::async_runtime::block_on(sync_and_notify(&app, &vault_path)) {
+                tracing::warn!("Remote task sync failed - {}", e);
+            }
+        }
+    });
+}
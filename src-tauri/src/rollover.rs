@@ -0,0 +1,101 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::BouldyError;
+use crate::settings;
+use crate::todo_store::TodoStore;
+use crate::todos::{self, Due, TodoItem};
+
+// Context added to flagged todos, so they can be listed like any other
+pub const OVERDUE_CONTEXT: &str = "overdue";
+
+/// What happens to todos whose due day has passed.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RolloverPolicy {
+    #[default]
+    #[serde(rename = "leave")]
+    Leave,
+    // Move the due date to today, keeping any time of day
+    #[serde(rename = "reschedule")]
+    Reschedule,
+    // Tag them `@overdue`
+    #[serde(rename = "flag")]
+    Flag,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct RolloverSummary {
+    pub policy: RolloverPolicy,
+    pub date: String,
+    // The todos as they are after the rollover
+    pub changed: Vec<TodoItem>,
+}
+
+fn roll(todo: &mut TodoItem, policy: RolloverPolicy, today: NaiveDate) -> bool {
+    let Some(due) = todo
+        .due()
+        .filter(|due| !todo.completed && due.date() < today)
+    else {
+        return false;
+    };
+    match policy {
+        RolloverPolicy::Leave => false,
+        RolloverPolicy::Reschedule => {
+            let rolled = match due {
+                Due::Date(_) => Due::Date(today),
+                Due::DateTime(at) => Due::DateTime(today.and_time(at.time())),
+            };
+            todo.due_date = Some(rolled.format());
+            true
+        }
+        RolloverPolicy::Flag => {
+            if todo
+                .contexts
+                .iter()
+                .any(|context| context.eq_ignore_ascii_case(OVERDUE_CONTEXT))
+            {
+                return false;
+            }
+            todo.contexts.push(OVERDUE_CONTEXT.to_string());
+            true
+        }
+    }
+}
+
+/// Apply `policy` to every open todo due before `today`.
+pub fn apply(
+    store: &TodoStore,
+    vault_path: &str,
+    policy: RolloverPolicy,
+    today: NaiveDate,
+) -> Result<RolloverSummary, BouldyError> {
+    let changed = store.mutate_if_changed(vault_path, |todos_list| {
+        let changed: Vec<TodoItem> = todos_list
+            .iter_mut()
+            .filter_map(|todo| roll(todo, policy, today).then(|| todo.clone()))
+            .collect();
+        let any = !changed.is_empty();
+        Ok((changed, any))
+    })?;
+
+    Ok(RolloverSummary {
+        policy,
+        date: today.format("%Y-%m-%d").to_string(),
+        changed,
+    })
+}
+
+/// Apply the configured policy and tell the frontend what changed. Run at
+/// startup and after midnight, and on demand.
+pub fn run(app: &AppHandle, vault_path: &str) -> Result<RolloverSummary, BouldyError> {
+    let policy = settings::load_settings(app)?.overdue_rollover;
+    let today = todos::local_now().date();
+    let summary = apply(&app.state::<TodoStore>(), vault_path, policy, today)?;
+
+    if !summary.changed.is_empty() {
+        let _ = app.emit("todos_changed", ());
+        let _ = app.emit("todos_rolled_over", &summary);
+    }
+    Ok(summary)
+}
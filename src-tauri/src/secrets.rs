@@ -0,0 +1,80 @@
+use serde::Deserialize;
+
+use crate::error::BouldyError;
+use crate::llm::LlmProvider;
+use crate::settings::Settings;
+use crate::{caldav, llm, mail_capture, prompt_share};
+
+// Passwords, API keys and tokens all live in the OS keychain, under a
+// `service` per feature and an `account` within it, never in settings.json.
+
+fn entry(service: &str, account: &str) -> Result<keyring::Entry, BouldyError> {
+    keyring::Entry::new(service, account)
+        .map_err(|e| format!("Failed to access keyring: {}", e).into())
+}
+
+/// The stored secret, or `None` if nothing was saved.
+pub fn get(service: &str, account: &str) -> Result<Option<String>, BouldyError> {
+    match entry(service, account)?.get_password() {
+        Ok(secret) => Ok(Some(secret)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to read secret from keyring: {}", e).into()),
+    }
+}
+
+/// Store a secret, or remove it when `secret` is empty.
+pub fn set(service: &str, account: &str, secret: &str) -> Result<(), BouldyError> {
+    if secret.is_empty() {
+        return delete(service, account);
+    }
+    entry(service, account)?
+        .set_password(secret)
+        .map_err(|e| format!("Failed to save secret to keyring: {}", e).into())
+}
+
+/// Remove a secret. Removing one that isn't there is not an error.
+pub fn delete(service: &str, account: &str) -> Result<(), BouldyError> {
+    match entry(service, account)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("Failed to remove secret from keyring: {}", e).into()),
+    }
+}
+
+/// The secrets the settings UI can manage. Tokens the app creates itself
+/// (API and clipper tokens, OAuth logins) have their own commands.
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(tag = "kind")]
+pub enum SecretKey {
+    #[serde(rename = "llmApiKey")]
+    LlmApiKey { provider: LlmProvider },
+    // For the configured account, so it must be set up first
+    #[serde(rename = "caldavPassword")]
+    CaldavPassword,
+    #[serde(rename = "mailPassword")]
+    MailPassword,
+    // Needs the gist scope, for sharing prompts
+    #[serde(rename = "githubToken")]
+    GithubToken,
+}
+
+impl SecretKey {
+    /// Where the secret is stored, given the current settings.
+    pub fn locate(self, settings: &Settings) -> Result<(&'static str, String), BouldyError> {
+        match self {
+            SecretKey::LlmApiKey { provider } => {
+                Ok((llm::SECRET_SERVICE, provider.name().to_string()))
+            }
+            SecretKey::CaldavPassword => {
+                Ok((caldav::SECRET_SERVICE, settings.caldav.secret_account()?))
+            }
+            SecretKey::MailPassword => Ok((
+                mail_capture::SECRET_SERVICE,
+                settings.mail_capture.secret_account()?,
+            )),
+            SecretKey::GithubToken => Ok((
+                prompt_share::SECRET_SERVICE,
+                prompt_share::SECRET_ACCOUNT.to_string(),
+            )),
+        }
+    }
+}
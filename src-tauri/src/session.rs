@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::Path;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use crate::error::BouldyError;
+
+// Beside settings.json rather than in the vault: where you were is about
+// this machine, and a synced vault shouldn't carry it to others
+const SESSIONS_FILE: &str = "sessions.json";
+
+/// Where the user left off in a vault.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct SessionState {
+    #[serde(rename = "lastNote", default)]
+    pub last_note: Option<String>,
+    // Of the last note's editor
+    #[serde(rename = "scrollTop", default)]
+    pub scroll_top: Option<f64>,
+    #[serde(rename = "openPanels", default)]
+    pub open_panels: Vec<String>,
+    // Anything else the frontend wants back, kept as given
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+// The same vault opened through a different path (a symlink, a trailing
+// slash) shares one session
+fn vault_key(vault_path: &str) -> String {
+    Path::new(vault_path)
+        .canonicalize()
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_else(|_| vault_path.trim_end_matches(['/', '\\']).to_string())
+}
+
+pub fn load(app: &AppHandle, vault_path: &str) -> Result<SessionState, BouldyError> {
+    let store = app.store(SESSIONS_FILE).map_err(|e| e.to_string())?;
+    Ok(store
+        .get(vault_key(vault_path))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default())
+}
+
+pub fn save(app: &AppHandle, vault_path: &str, state: &SessionState) -> Result<(), BouldyError> {
+    let store = app.store(SESSIONS_FILE).map_err(|e| e.to_string())?;
+    let value = serde_json::to_value(state)
+        .map_err(|e| format!("Failed to serialize session state: {}", e))?;
+    store.set(vault_key(vault_path), value);
+    store
+        .save()
+        .map_err(|e| format!("Failed to save session state: {}", e).into())
+}
@@ -0,0 +1,586 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use crate::audio_memo::TranscriptionSettings;
+use crate::caldav::CaldavSettings;
+use crate::contexts::ContextSettings;
+use crate::diagnostics;
+use crate::error::BouldyError;
+use crate::journal::JournalSettings;
+use crate::llm::LlmSettings;
+use crate::locale::{self, LocaleInfo};
+use crate::mail_capture::MailCaptureSettings;
+use crate::network::NetworkConfig;
+use crate::notifications::NotificationSettings;
+use crate::pomodoro::PomodoroSettings;
+use crate::remote_tasks::RemoteTasksSettings;
+use crate::rollover::RolloverPolicy;
+use crate::shortcuts;
+use crate::updates::UpdateChannel;
+use crate::writing_stats::WritingGoals;
+
+pub const SETTINGS_FILE: &str = "settings.json";
+
+/// Bump this whenever the shape or meaning of a setting changes and add a
+/// matching step to `migrate`.
+pub const SCHEMA_VERSION: u32 = 2;
+
+pub const THEMES: &[&str] = &["midnight", "dawn", "forest", "sunset", "clean"];
+pub const DEFAULT_THEME: &str = "midnight";
+
+pub const MIN_WATCHER_DEBOUNCE_MS: u64 = 100;
+pub const MAX_WATCHER_DEBOUNCE_MS: u64 = 5000;
+pub const DEFAULT_WATCHER_DEBOUNCE_MS: u64 = 500;
+
+// Every field maps to a top-level key in settings.json, so the frontend can
+// keep reading individual keys from the store while the backend owns the schema.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Settings {
+    #[serde(rename = "schemaVersion", default)]
+    pub schema_version: u32,
+    #[serde(rename = "vaultPath", default)]
+    pub vault_path: Option<String>,
+    #[serde(default = "default_theme")]
+    pub theme: String,
+    #[serde(rename = "lastOpenedNotePath", default)]
+    pub last_opened_note_path: Option<String>,
+    #[serde(rename = "watcherDebounceMs", default = "default_watcher_debounce_ms")]
+    pub watcher_debounce_ms: u64,
+    #[serde(rename = "autostartEnabled", default = "default_true")]
+    pub autostart_enabled: bool,
+    #[serde(rename = "startMinimized", default)]
+    pub start_minimized: bool,
+    #[serde(default)]
+    pub network: NetworkConfig,
+    // Action id -> binding, for the ones changed from their default; an
+    // empty binding unbinds. See `shortcuts::list` for the actions.
+    #[serde(default)]
+    pub shortcuts: BTreeMap<String, String>,
+    #[serde(default)]
+    pub notifications: NotificationSettings,
+    #[serde(rename = "clipboardCaptureEnabled", default)]
+    pub clipboard_capture_enabled: bool,
+    #[serde(rename = "calendarExportEnabled", default)]
+    pub calendar_export_enabled: bool,
+    #[serde(rename = "calendarExportPath", default)]
+    pub calendar_export_path: Option<String>,
+    #[serde(rename = "startupMetricsEnabled", default)]
+    pub startup_metrics_enabled: bool,
+    #[serde(default)]
+    pub caldav: CaldavSettings,
+    #[serde(rename = "remoteTasks", default)]
+    pub remote_tasks: RemoteTasksSettings,
+    #[serde(rename = "webClipperEnabled", default)]
+    pub web_clipper_enabled: bool,
+    #[serde(rename = "webClipperPort", default = "default_web_clipper_port")]
+    pub web_clipper_port: u16,
+    #[serde(rename = "mailCapture", default)]
+    pub mail_capture: MailCaptureSettings,
+    #[serde(default)]
+    pub journal: JournalSettings,
+    #[serde(rename = "apiServerEnabled", default)]
+    pub api_server_enabled: bool,
+    #[serde(rename = "apiServerPort", default = "default_api_server_port")]
+    pub api_server_port: u16,
+    #[serde(default)]
+    pub llm: LlmSettings,
+    #[serde(default)]
+    pub transcription: TranscriptionSettings,
+    // Write a weekly review note every Sunday evening
+    #[serde(rename = "weeklyReviewEnabled", default)]
+    pub weekly_review_enabled: bool,
+    #[serde(rename = "updateChannel", default)]
+    pub update_channel: UpdateChannel,
+    // Where crash reports are uploaded; unset keeps them local
+    #[serde(rename = "crashReportUrl", default)]
+    pub crash_report_url: Option<String>,
+    #[serde(rename = "writingGoals", default)]
+    pub writing_goals: WritingGoals,
+    #[serde(default)]
+    pub contexts: ContextSettings,
+    #[serde(rename = "overdueRollover", default)]
+    pub overdue_rollover: RolloverPolicy,
+    #[serde(default)]
+    pub pomodoro: PomodoroSettings,
+    // e.g. "en-GB"; unset follows the system
+    #[serde(default)]
+    pub locale: Option<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            vault_path: None,
+            theme: default_theme(),
+            last_opened_note_path: None,
+            watcher_debounce_ms: default_watcher_debounce_ms(),
+            autostart_enabled: true,
+            start_minimized: false,
+            network: NetworkConfig::default(),
+            shortcuts: BTreeMap::new(),
+            notifications: NotificationSettings::default(),
+            clipboard_capture_enabled: false,
+            calendar_export_enabled: false,
+            calendar_export_path: None,
+            startup_metrics_enabled: false,
+            caldav: CaldavSettings::default(),
+            remote_tasks: RemoteTasksSettings::default(),
+            web_clipper_enabled: false,
+            web_clipper_port: default_web_clipper_port(),
+            mail_capture: MailCaptureSettings::default(),
+            journal: JournalSettings::default(),
+            api_server_enabled: false,
+            api_server_port: default_api_server_port(),
+            llm: LlmSettings::default(),
+            transcription: TranscriptionSettings::default(),
+            weekly_review_enabled: false,
+            update_channel: UpdateChannel::default(),
+            crash_report_url: None,
+            writing_goals: WritingGoals::default(),
+            contexts: ContextSettings::default(),
+            overdue_rollover: RolloverPolicy::default(),
+            pomodoro: PomodoroSettings::default(),
+            locale: None,
+        }
+    }
+}
+
+fn default_theme() -> String {
+    DEFAULT_THEME.to_string()
+}
+
+fn default_watcher_debounce_ms() -> u64 {
+    DEFAULT_WATCHER_DEBOUNCE_MS
+}
+
+fn default_web_clipper_port() -> u16 {
+    crate::web_clipper::DEFAULT_PORT
+}
+
+fn default_api_server_port() -> u16 {
+    crate::local_api::DEFAULT_PORT
+}
+
+fn default_true() -> bool {
+    true
+}
+
+// What React sends to update_settings - only the fields being changed. An
+// empty string clears an optional text setting.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct SettingsPatch {
+    #[serde(rename = "vaultPath")]
+    pub vault_path: Option<String>,
+    pub theme: Option<String>,
+    #[serde(rename = "lastOpenedNotePath")]
+    pub last_opened_note_path: Option<String>,
+    #[serde(rename = "watcherDebounceMs")]
+    pub watcher_debounce_ms: Option<u64>,
+    #[serde(rename = "autostartEnabled")]
+    pub autostart_enabled: Option<bool>,
+    #[serde(rename = "startMinimized")]
+    pub start_minimized: Option<bool>,
+    pub network: Option<NetworkConfig>,
+    pub shortcuts: Option<BTreeMap<String, String>>,
+    pub notifications: Option<NotificationSettings>,
+    #[serde(rename = "clipboardCaptureEnabled")]
+    pub clipboard_capture_enabled: Option<bool>,
+    #[serde(rename = "calendarExportEnabled")]
+    pub calendar_export_enabled: Option<bool>,
+    #[serde(rename = "calendarExportPath")]
+    pub calendar_export_path: Option<String>,
+    #[serde(rename = "startupMetricsEnabled")]
+    pub startup_metrics_enabled: Option<bool>,
+    pub caldav: Option<CaldavSettings>,
+    #[serde(rename = "remoteTasks")]
+    pub remote_tasks: Option<RemoteTasksSettings>,
+    #[serde(rename = "webClipperEnabled")]
+    pub web_clipper_enabled: Option<bool>,
+    #[serde(rename = "webClipperPort")]
+    pub web_clipper_port: Option<u16>,
+    #[serde(rename = "mailCapture")]
+    pub mail_capture: Option<MailCaptureSettings>,
+    pub journal: Option<JournalSettings>,
+    #[serde(rename = "apiServerEnabled")]
+    pub api_server_enabled: Option<bool>,
+    #[serde(rename = "apiServerPort")]
+    pub api_server_port: Option<u16>,
+    pub llm: Option<LlmSettings>,
+    pub transcription: Option<TranscriptionSettings>,
+    #[serde(rename = "weeklyReviewEnabled")]
+    pub weekly_review_enabled: Option<bool>,
+    #[serde(rename = "updateChannel")]
+    pub update_channel: Option<UpdateChannel>,
+    // An empty string turns uploading off
+    #[serde(rename = "crashReportUrl")]
+    pub crash_report_url: Option<String>,
+    #[serde(rename = "writingGoals")]
+    pub writing_goals: Option<WritingGoals>,
+    pub contexts: Option<ContextSettings>,
+    #[serde(rename = "overdueRollover")]
+    pub overdue_rollover: Option<RolloverPolicy>,
+    pub pomodoro: Option<PomodoroSettings>,
+    // An empty string follows the system again
+    pub locale: Option<String>,
+}
+
+impl Settings {
+    /// Check the settings `patch` changed, so a value that went bad since it
+    /// was saved (a moved CA file, say) doesn't block unrelated changes.
+    pub fn validate(&self, patch: &SettingsPatch) -> Result<(), BouldyError> {
+        if patch.theme.is_some() && !THEMES.contains(&self.theme.as_str()) {
+            return Err(BouldyError::invalid_input(format!(
+                "Invalid theme '{}'. Expected one of: {}",
+                self.theme,
+                THEMES.join(", ")
+            )));
+        }
+
+        if patch.watcher_debounce_ms.is_some()
+            && !(MIN_WATCHER_DEBOUNCE_MS..=MAX_WATCHER_DEBOUNCE_MS)
+                .contains(&self.watcher_debounce_ms)
+        {
+            return Err(BouldyError::invalid_input(format!(
+                "Watcher debounce must be between {} and {} ms",
+                MIN_WATCHER_DEBOUNCE_MS, MAX_WATCHER_DEBOUNCE_MS
+            )));
+        }
+
+        if let Some(ref vault_path) = patch.vault_path {
+            if !vault_path.is_empty() && vault_path.trim().is_empty() {
+                return Err(BouldyError::invalid_input("Vault path cannot be empty"));
+            }
+        }
+
+        if patch.calendar_export_path.is_some() {
+            if let Some(ref path) = self.calendar_export_path {
+                if !path.ends_with(".ics") {
+                    return Err(BouldyError::invalid_input(
+                        "Calendar export path must end in .ics",
+                    ));
+                }
+            }
+        }
+
+        if patch.network.is_some() {
+            self.network.validate()?;
+        }
+        if patch.notifications.is_some() {
+            self.notifications.validate()?;
+        }
+        if patch.caldav.is_some() {
+            self.caldav.validate()?;
+        }
+        if patch.remote_tasks.is_some() {
+            self.remote_tasks.validate()?;
+        }
+        if patch.mail_capture.is_some() {
+            self.mail_capture.validate()?;
+        }
+        if patch.journal.is_some() {
+            self.journal.validate()?;
+        }
+        if patch.llm.is_some() {
+            self.llm.validate()?;
+        }
+        if patch.transcription.is_some() {
+            self.transcription.validate()?;
+        }
+        if patch.writing_goals.is_some() {
+            self.writing_goals.validate()?;
+        }
+        if patch.contexts.is_some() {
+            self.contexts.validate()?;
+        }
+        if patch.pomodoro.is_some() {
+            self.pomodoro.validate()?;
+        }
+        if patch.crash_report_url.is_some() {
+            if let Some(ref url) = self.crash_report_url {
+                diagnostics::validate_report_url(url)?;
+            }
+        }
+
+        if patch.web_clipper_port.is_some() || patch.api_server_port.is_some() {
+            // Below 1024 needs privileges on most systems
+            if self.web_clipper_port < 1024 {
+                return Err(BouldyError::invalid_input(
+                    "Web clipper port must be 1024 or higher",
+                ));
+            }
+            if self.api_server_port < 1024 {
+                return Err(BouldyError::invalid_input(
+                    "API server port must be 1024 or higher",
+                ));
+            }
+            if self.api_server_port == self.web_clipper_port {
+                return Err(BouldyError::invalid_input(
+                    "API server and web clipper need different ports",
+                ));
+            }
+        }
+
+        if patch.locale.is_some() {
+            if let Some(ref tag) = self.locale {
+                locale::validate(tag)?;
+            }
+        }
+
+        if patch.shortcuts.is_some() {
+            shortcuts::validate(&self.shortcuts)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn apply(&mut self, patch: SettingsPatch) {
+        if let Some(vault_path) = patch.vault_path {
+            self.vault_path = Some(vault_path).filter(|path| !path.is_empty());
+        }
+        if let Some(theme) = patch.theme {
+            self.theme = theme;
+        }
+        if let Some(path) = patch.last_opened_note_path {
+            self.last_opened_note_path = Some(path).filter(|path| !path.is_empty());
+        }
+        if let Some(debounce) = patch.watcher_debounce_ms {
+            self.watcher_debounce_ms = debounce;
+        }
+        if let Some(enabled) = patch.autostart_enabled {
+            self.autostart_enabled = enabled;
+        }
+        if let Some(minimized) = patch.start_minimized {
+            self.start_minimized = minimized;
+        }
+        if let Some(network) = patch.network {
+            self.network = network;
+        }
+        if let Some(shortcuts) = patch.shortcuts {
+            self.shortcuts = shortcuts;
+        }
+        if let Some(notifications) = patch.notifications {
+            self.notifications = notifications;
+        }
+        if let Some(enabled) = patch.clipboard_capture_enabled {
+            self.clipboard_capture_enabled = enabled;
+        }
+        if let Some(enabled) = patch.calendar_export_enabled {
+            self.calendar_export_enabled = enabled;
+        }
+        if let Some(path) = patch.calendar_export_path {
+            self.calendar_export_path = Some(path).filter(|path| !path.is_empty());
+        }
+        if let Some(enabled) = patch.startup_metrics_enabled {
+            self.startup_metrics_enabled = enabled;
+        }
+        if let Some(caldav) = patch.caldav {
+            self.caldav = caldav;
+        }
+        if let Some(remote_tasks) = patch.remote_tasks {
+            self.remote_tasks = remote_tasks;
+        }
+        if let Some(enabled) = patch.web_clipper_enabled {
+            self.web_clipper_enabled = enabled;
+        }
+        if let Some(port) = patch.web_clipper_port {
+            self.web_clipper_port = port;
+        }
+        if let Some(mail_capture) = patch.mail_capture {
+            self.mail_capture = mail_capture;
+        }
+        if let Some(journal) = patch.journal {
+            self.journal = journal;
+        }
+        if let Some(enabled) = patch.api_server_enabled {
+            self.api_server_enabled = enabled;
+        }
+        if let Some(port) = patch.api_server_port {
+            self.api_server_port = port;
+        }
+        if let Some(llm) = patch.llm {
+            self.llm = llm;
+        }
+        if let Some(transcription) = patch.transcription {
+            self.transcription = transcription;
+        }
+        if let Some(enabled) = patch.weekly_review_enabled {
+            self.weekly_review_enabled = enabled;
+        }
+        if let Some(channel) = patch.update_channel {
+            self.update_channel = channel;
+        }
+        if let Some(url) = patch.crash_report_url {
+            self.crash_report_url = Some(url).filter(|url| !url.is_empty());
+        }
+        if let Some(goals) = patch.writing_goals {
+            self.writing_goals = goals;
+        }
+        if let Some(contexts) = patch.contexts {
+            self.contexts = contexts;
+        }
+        if let Some(policy) = patch.overdue_rollover {
+            self.overdue_rollover = policy;
+        }
+        if let Some(pomodoro) = patch.pomodoro {
+            self.pomodoro = pomodoro;
+        }
+        if let Some(tag) = patch.locale {
+            self.locale = Some(tag).filter(|tag| !tag.is_empty());
+        }
+    }
+
+    /// How dates are read and written for the user.
+    pub fn locale_info(&self) -> LocaleInfo {
+        locale::resolve(self.locale.as_deref())
+    }
+
+    /// Where the due-todo calendar is written, if exporting is enabled.
+    pub fn calendar_export_target(&self, vault_path: &str) -> Option<PathBuf> {
+        if !self.calendar_export_enabled {
+            return None;
+        }
+
+        Some(match self.calendar_export_path {
+            Some(ref path) => PathBuf::from(path),
+            None => crate::ics::default_calendar_path(vault_path),
+        })
+    }
+}
+
+/// Bring the raw store written by an older version up to `SCHEMA_VERSION`,
+/// before it is read into `Settings`.
+fn migrate(entries: &mut Map<String, Value>) {
+    let version = entries
+        .get("schemaVersion")
+        .and_then(Value::as_u64)
+        .unwrap_or(0);
+
+    // v0 -> v1: settings were loose keys written directly by the frontend,
+    // so values may be anything. Drop whatever doesn't validate.
+    if version < 1 {
+        let theme_ok = entries
+            .get("theme")
+            .and_then(Value::as_str)
+            .is_some_and(|theme| THEMES.contains(&theme));
+        if !theme_ok {
+            entries.remove("theme");
+        }
+        match entries.get("watcherDebounceMs").and_then(Value::as_u64) {
+            Some(debounce) => {
+                let clamped = debounce.clamp(MIN_WATCHER_DEBOUNCE_MS, MAX_WATCHER_DEBOUNCE_MS);
+                entries.insert("watcherDebounceMs".to_string(), clamped.into());
+            }
+            None => {
+                entries.remove("watcherDebounceMs");
+            }
+        }
+    }
+
+    // v1 -> v2: the quick capture and prompt palette shortcuts moved into
+    // the shortcut registry
+    if version < 2 {
+        let mut shortcuts: BTreeMap<String, String> = entries
+            .get("shortcuts")
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+            .unwrap_or_default();
+        for (id, key) in [
+            ("quick-capture", "quickCaptureShortcut"),
+            ("prompt-palette", "promptPaletteShortcut"),
+        ] {
+            if let Some(Value::String(binding)) = entries.remove(key) {
+                shortcuts.entry(id.to_string()).or_insert(binding);
+            }
+        }
+        if shortcuts::validate(&shortcuts).is_err() {
+            shortcuts.clear();
+        }
+        entries.insert(
+            "shortcuts".to_string(),
+            serde_json::to_value(shortcuts).unwrap_or_default(),
+        );
+    }
+
+    entries.insert("schemaVersion".to_string(), SCHEMA_VERSION.into());
+}
+
+// Read the store into `Settings`, dropping any key whose value doesn't fit
+// its type so one bad key falls back to its default instead of failing
+// every load.
+fn parse(entries: &mut Map<String, Value>) -> Settings {
+    if let Ok(settings) = serde_json::from_value(Value::Object(entries.clone())) {
+        return settings;
+    }
+
+    let mut accepted = Map::new();
+    for (key, value) in std::mem::take(entries) {
+        let mut candidate = accepted.clone();
+        candidate.insert(key.clone(), value.clone());
+        if serde_json::from_value::<Settings>(Value::Object(candidate)).is_ok() {
+            accepted.insert(key, value);
+        } else {
+            tracing::warn!("Ignoring invalid setting '{}'", key);
+        }
+    }
+    *entries = accepted;
+    serde_json::from_value(Value::Object(entries.clone())).unwrap_or_default()
+}
+
+pub fn load_settings(app: &AppHandle) -> Result<Settings, BouldyError> {
+    let store = app.store(SETTINGS_FILE).map_err(|e| e.to_string())?;
+
+    let original: Map<String, Value> = store.entries().into_iter().collect();
+    let mut entries = original.clone();
+    migrate(&mut entries);
+    let settings = parse(&mut entries);
+
+    if entries != original {
+        // Keys that were migrated away or didn't parse
+        for key in original.keys().filter(|key| !entries.contains_key(*key)) {
+            store.delete(key);
+        }
+        save_settings(app, &settings)?;
+    }
+
+    Ok(settings)
+}
+
+/// The configured vault, for commands that run without the main window
+/// (and so without a vault path passed in from React).
+pub fn require_vault_path(app: &AppHandle) -> Result<String, BouldyError> {
+    load_settings(app)?
+        .vault_path
+        .ok_or_else(|| "No vault configured".into())
+}
+
+pub fn save_settings(app: &AppHandle, settings: &Settings) -> Result<(), BouldyError> {
+    let store = app.store(SETTINGS_FILE).map_err(|e| e.to_string())?;
+
+    let value = serde_json::to_value(settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+
+    if let Value::Object(entries) = value {
+        for (key, value) in entries {
+            // Unset optional values are removed rather than stored as null
+            if value.is_null() {
+                store.delete(&key);
+            } else {
+                store.set(key, value);
+            }
+        }
+    }
+
+    store.save().map_err(|e| e.to_string().into())
+}
+
+pub fn update_settings(app: &AppHandle, patch: SettingsPatch) -> Result<Settings, BouldyError> {
+    let mut settings = load_settings(app)?;
+    settings.apply(patch.clone());
+    settings.validate(&patch)?;
+    save_settings(app, &settings)?;
+    Ok(settings)
+}
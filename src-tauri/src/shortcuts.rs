@@ -0,0 +1,280 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tauri::AppHandle;
+
+use crate::capture::{self, DEFAULT_QUICK_CAPTURE_SHORTCUT};
+use crate::error::BouldyError;
+use crate::prompt_palette::{self, DEFAULT_PROMPT_PALETTE_SHORTCUT};
+use crate::settings::{self, Settings, SettingsPatch};
+use crate::{snippets, widget};
+
+/// Where a shortcut works: everywhere, registered with the OS, or only
+/// while the main window has focus, handled by the frontend.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scope {
+    #[serde(rename = "global")]
+    Global,
+    #[serde(rename = "app")]
+    App,
+}
+
+struct Action {
+    id: &'static str,
+    label: &'static str,
+    scope: Scope,
+    default: Option<&'static str>,
+}
+
+const ACTIONS: &[Action] = &[
+    Action {
+        id: "quick-capture",
+        label: "Quick capture",
+        scope: Scope::Global,
+        default: Some(DEFAULT_QUICK_CAPTURE_SHORTCUT),
+    },
+    Action {
+        id: "prompt-palette",
+        label: "Prompt palette",
+        scope: Scope::Global,
+        default: Some(DEFAULT_PROMPT_PALETTE_SHORTCUT),
+    },
+    Action {
+        id: "widget",
+        label: "Show widget",
+        scope: Scope::Global,
+        default: None,
+    },
+    Action {
+        id: "expand-snippet",
+        label: "Expand snippet",
+        scope: Scope::Global,
+        default: None,
+    },
+    Action {
+        id: "command-menu",
+        label: "Command menu",
+        scope: Scope::App,
+        default: Some("CommandOrControl+K"),
+    },
+    Action {
+        id: "panel-notes",
+        label: "Notes panel",
+        scope: Scope::App,
+        default: Some("Alt+1"),
+    },
+    Action {
+        id: "panel-todos",
+        label: "Todos panel",
+        scope: Scope::App,
+        default: Some("Alt+2"),
+    },
+    Action {
+        id: "panel-calendar",
+        label: "Calendar panel",
+        scope: Scope::App,
+        default: Some("Alt+3"),
+    },
+    Action {
+        id: "panel-prompts",
+        label: "Prompts panel",
+        scope: Scope::App,
+        default: Some("Alt+4"),
+    },
+    Action {
+        id: "panel-pomodoro",
+        label: "Pomodoro panel",
+        scope: Scope::App,
+        default: Some("Alt+5"),
+    },
+    Action {
+        id: "panel-settings",
+        label: "Settings panel",
+        scope: Scope::App,
+        default: Some("Alt+6"),
+    },
+];
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ShortcutBinding {
+    pub id: String,
+    pub label: String,
+    pub scope: Scope,
+    // None when unbound
+    pub binding: Option<String>,
+    pub default: Option<String>,
+}
+
+fn find(id: &str) -> Result<&'static Action, BouldyError> {
+    ACTIONS
+        .iter()
+        .find(|action| action.id == id)
+        .ok_or_else(|| BouldyError::not_found(format!("Unknown shortcut action: {}", id)))
+}
+
+// The user's binding, else the default. An empty binding unbinds.
+fn effective(shortcuts: &BTreeMap<String, String>, action: &Action) -> Option<String> {
+    match shortcuts.get(action.id) {
+        Some(binding) => Some(binding.clone()).filter(|binding| !binding.is_empty()),
+        None => action.default.map(str::to_string),
+    }
+}
+
+pub fn list(settings: &Settings) -> Vec<ShortcutBinding> {
+    ACTIONS
+        .iter()
+        .map(|action| ShortcutBinding {
+            id: action.id.to_string(),
+            label: action.label.to_string(),
+            scope: action.scope,
+            binding: effective(&settings.shortcuts, action),
+            default: action.default.map(str::to_string),
+        })
+        .collect()
+}
+
+// Parsed, so "Ctrl+K" and "control+k" are the same combination
+#[cfg(desktop)]
+fn parse(binding: &str) -> Result<String, BouldyError> {
+    use std::str::FromStr;
+    use tauri_plugin_global_shortcut::Shortcut;
+
+    Shortcut::from_str(binding)
+        .map(|shortcut| shortcut.into_string())
+        .map_err(|e| BouldyError::invalid_input(format!("Invalid shortcut '{}': {}", binding, e)))
+}
+
+#[cfg(not(desktop))]
+fn parse(binding: &str) -> Result<String, BouldyError> {
+    Ok(binding.to_lowercase())
+}
+
+/// Reject unknown actions, unparseable bindings and two actions sharing one
+/// combination.
+pub fn validate(shortcuts: &BTreeMap<String, String>) -> Result<(), BouldyError> {
+    for id in shortcuts.keys() {
+        find(id)?;
+    }
+
+    let mut used: Vec<(String, &Action)> = Vec::new();
+    for action in ACTIONS {
+        let Some(binding) = effective(shortcuts, action) else {
+            continue;
+        };
+        let combination = parse(&binding)?;
+        if let Some((_, other)) = used.iter().find(|(used, _)| *used == combination) {
+            return Err(BouldyError::conflict(format!(
+                "{} and {} can't both use {}",
+                other.label, action.label, binding
+            )));
+        }
+        used.push((combination, action));
+    }
+    Ok(())
+}
+
+#[cfg(desktop)]
+fn run(app: &AppHandle, id: &str) -> Result<(), BouldyError> {
+    match id {
+        "quick-capture" => capture::show_capture_window(app),
+        "prompt-palette" => prompt_palette::show_palette_window(app),
+        "widget" => widget::show_widget_window(app),
+        // Reads the clipboard and waits on keystrokes, so off the event loop
+        "expand-snippet" => {
+            let app = app.clone();
+            std::thread::spawn(move || {
+                if let Err(e) = snippets::expand_at_cursor(&app) {
+                    tracing::warn!("{}", e);
+                }
+            });
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(desktop)]
+fn register(app: &AppHandle, id: &'static str, binding: &str) -> Result<(), BouldyError> {
+    use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+    app.global_shortcut()
+        .on_shortcut(binding, move |app, _shortcut, event| {
+            if event.state == ShortcutState::Pressed {
+                if let Err(e) = run(app, id) {
+                    tracing::warn!("{}", e);
+                }
+            }
+        })
+        .map_err(|e| format!("Failed to register shortcut '{}': {}", binding, e).into())
+}
+
+#[cfg(desktop)]
+fn unregister(app: &AppHandle, binding: &str) -> Result<(), BouldyError> {
+    use tauri_plugin_global_shortcut::GlobalShortcutExt;
+
+    app.global_shortcut()
+        .unregister(binding)
+        .map_err(|e| format!("Failed to unregister shortcut '{}': {}", binding, e).into())
+}
+
+/// Register every bound global shortcut with the OS. Another app may already
+/// own a combination; the action still works from the main window, so that
+/// only logs a warning.
+#[cfg(desktop)]
+pub fn register_all(app: &AppHandle, settings: &Settings) {
+    for action in ACTIONS
+        .iter()
+        .filter(|action| action.scope == Scope::Global)
+    {
+        if let Some(binding) = effective(&settings.shortcuts, action) {
+            if let Err(e) = register(app, action.id, &binding) {
+                tracing::warn!("{}", e);
+            }
+        }
+    }
+}
+
+/// Bind `id` to `binding`: None restores the default, an empty string
+/// unbinds. Global shortcuts are re-registered right away: the new one
+/// first, so a shortcut another app holds fails before anything is saved,
+/// and the old one only once the new binding is persisted.
+pub fn set_shortcut(
+    app: &AppHandle,
+    id: &str,
+    binding: Option<String>,
+) -> Result<Vec<ShortcutBinding>, BouldyError> {
+    let action = find(id)?;
+    let mut shortcuts = settings::load_settings(app)?.shortcuts;
+    let previous = effective(&shortcuts, action);
+    match binding {
+        Some(binding) => shortcuts.insert(id.to_string(), binding.trim().to_string()),
+        None => shortcuts.remove(id),
+    };
+    let current = effective(&shortcuts, action);
+    let rebind = cfg!(desktop) && action.scope == Scope::Global && current != previous;
+
+    #[cfg(desktop)]
+    if rebind {
+        if let Some(ref current) = current {
+            register(app, action.id, current)?;
+        }
+    }
+
+    let updated = settings::update_settings(
+        app,
+        SettingsPatch {
+            shortcuts: Some(shortcuts),
+            ..Default::default()
+        },
+    );
+
+    #[cfg(desktop)]
+    if rebind {
+        // Whichever binding didn't end up saved is released
+        let stale = if updated.is_ok() { previous } else { current };
+        if let Some(ref stale) = stale {
+            let _ = unregister(app, stale);
+        }
+    }
+
+    Ok(list(&updated?))
+}
@@ -0,0 +1,54 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::index::IndexState;
+use crate::todo_store::TodoStore;
+use crate::watcher::VaultWatcher;
+
+// Written on a clean exit and removed when the vault's index is opened, so
+// a missing marker means the last session crashed or was killed
+const CLEAN_SHUTDOWN_MARKER: &str = "clean-shutdown";
+
+fn marker_path(vault_path: &str) -> PathBuf {
+    Path::new(vault_path)
+        .join(".bouldy")
+        .join(CLEAN_SHUTDOWN_MARKER)
+}
+
+/// Whether the previous session on this vault shut down cleanly. The marker
+/// is consumed, so a crash in this session is noticed next time.
+pub fn take_clean_marker(vault_path: &str) -> bool {
+    fs::remove_file(marker_path(vault_path)).is_ok()
+}
+
+/// Write everything still pending and release the vault: flush debounced
+/// todo writes, stop the watcher so no half-handled events run during
+/// exit, close the index, then leave the clean-shutdown marker.
+pub fn run(app: &AppHandle) {
+    let flushed = app.state::<TodoStore>().flush();
+    if let Err(ref e) = flushed {
+        tracing::warn!("{}", e);
+    }
+
+    if let Some(watcher) = app
+        .try_state::<Mutex<Option<VaultWatcher>>>()
+        .and_then(|state| state.lock().unwrap().take())
+    {
+        watcher.stop();
+    }
+
+    let Some(vault_path) = app.state::<IndexState>().close() else {
+        return;
+    };
+    // Changes that didn't reach disk may be missing from the index too, so
+    // without the marker the next start checks everything
+    if flushed.is_err() {
+        return;
+    }
+    let stamp = chrono::Local::now().to_rfc3339();
+    if let Err(e) = fs::write(marker_path(&vault_path), stamp) {
+        tracing::warn!("Failed to write clean shutdown marker - {}", e);
+    }
+}
@@ -0,0 +1,443 @@
+use pulldown_cmark::{html, CowStr, Event, LinkType, Options, Parser, Tag, TagEnd};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::BouldyError;
+use crate::file_drop::unique_destination;
+use crate::{embeds, fsutil, markdown, text, vault};
+
+const ASSETS_DIR: &str = "assets";
+
+const STYLE: &str = "
+body { max-width: 46rem; margin: 2rem auto; padding: 0 1rem; font: 16px/1.6 system-ui, sans-serif; color: #222; }
+nav { margin-bottom: 2rem; font-size: 0.9rem; }
+a { color: #2a5db0; }
+img { max-width: 100%; }
+pre { background: #f5f5f5; padding: 0.75rem; overflow-x: auto; }
+code { font-family: ui-monospace, monospace; }
+.tags { color: #666; font-size: 0.85rem; }
+ul.index { list-style: none; padding: 0; }
+ul.index li { margin: 0.4rem 0; }
+";
+
+/// Which notes to publish. With neither set, every note is exported.
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct SiteOptions {
+    // Notes carrying any of these tags
+    #[serde(default)]
+    pub tags: Vec<String>,
+    // Note paths to include regardless of tags
+    #[serde(default)]
+    pub notes: Vec<String>,
+    // Defaults to the vault folder's name
+    pub title: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct SiteReport {
+    pub pages: usize,
+    pub attachments: usize,
+    #[serde(rename = "outputDir")]
+    pub output_dir: String,
+    // Links to notes that weren't exported, rendered as plain text
+    #[serde(rename = "unresolvedLinks")]
+    pub unresolved_links: Vec<String>,
+}
+
+struct Page {
+    source: PathBuf,
+    title: String,
+    slug: String,
+    tags: Vec<String>,
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Lowercase words joined by dashes, for file names and heading anchors
+fn slugify(value: &str) -> String {
+    let mut slug = String::new();
+    for ch in value.chars().flat_map(char::to_lowercase) {
+        if ch.is_alphanumeric() {
+            slug.push(ch);
+        } else if !slug.ends_with('-') && !slug.is_empty() {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+fn is_external(url: &str) -> bool {
+    url.contains("://") || url.starts_with("mailto:") || url.starts_with("data:")
+}
+
+// Every non-markdown file in the vault by file name, for resolving
+// `![[image.png]]` embeds the way Obsidian does
+fn attachment_index(vault: &Path, output_dir: &Path) -> HashMap<String, PathBuf> {
+    let mut index = HashMap::new();
+    let mut pending = vec![vault.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let hidden = entry.file_name().to_string_lossy().starts_with('.');
+            if hidden || path == output_dir {
+                continue;
+            }
+            if path.is_dir() {
+                pending.push(path);
+            } else if !vault::is_markdown_file(&path) {
+                index
+                    .entry(entry.file_name().to_string_lossy().to_string())
+                    .or_insert(path);
+            }
+        }
+    }
+
+    index
+}
+
+struct Site {
+    output_dir: PathBuf,
+    // Folded title, file stem or alias -> index into `pages`
+    lookup: HashMap<String, usize>,
+    pages: Vec<Page>,
+    attachments: HashMap<String, PathBuf>,
+    // Source file -> path relative to the site
+    copied: HashMap<PathBuf, String>,
+    unresolved: HashSet<String>,
+}
+
+impl Site {
+    fn page_url(&self, target: &str) -> Option<String> {
+        let (name, heading) = match target.split_once('#') {
+            Some((name, heading)) => (name, Some(heading)),
+            None => (target, None),
+        };
+        let name = name.rsplit('/').next().unwrap_or(name);
+        let name = name.strip_suffix(".md").unwrap_or(name);
+
+        let page = self.lookup.get(&text::fold(name.trim()))?;
+        let mut url = format!("{}.html", self.pages[*page].slug);
+        if let Some(heading) = heading {
+            url.push('#');
+            url.push_str(&slugify(heading));
+        }
+        Some(url)
+    }
+
+    fn copy_asset(&mut self, source: &Path) -> Result<String, BouldyError> {
+        if let Some(url) = self.copied.get(source) {
+            return Ok(url.clone());
+        }
+
+        let assets_dir = self.output_dir.join(ASSETS_DIR);
+        fs::create_dir_all(&assets_dir)
+            .map_err(|e| BouldyError::io("Failed to create assets directory", e))?;
+        let file_name = vault::file_name_to_string(source)?;
+        let dest = unique_destination(&assets_dir, &file_name);
+        fs::copy(source, &dest).map_err(|e| BouldyError::io("Failed to copy attachment", e))?;
+
+        let url = format!("{}/{}", ASSETS_DIR, vault::file_name_to_string(&dest)?);
+        self.copied.insert(source.to_path_buf(), url.clone());
+        Ok(url)
+    }
+
+    // A local file a link or embed points at: relative to the note, or by
+    // name anywhere in the vault for wikilinks
+    fn find_attachment(&self, url: &str, note_dir: &Path, wikilink: bool) -> Option<PathBuf> {
+        let decoded = percent_encoding::percent_decode_str(url).decode_utf8_lossy();
+        let relative = note_dir.join(decoded.as_ref());
+        if relative.is_file() {
+            return Some(relative);
+        }
+        if wikilink {
+            let name = decoded.rsplit('/').next().unwrap_or(&decoded);
+            return self.attachments.get(name).cloned();
+        }
+        None
+    }
+
+    // Rewrite a link to wherever its target ended up in the site. None means
+    // the target isn't published and the link becomes plain text.
+    fn resolve(
+        &mut self,
+        url: &str,
+        note_dir: &Path,
+        link_type: LinkType,
+    ) -> Result<Option<String>, BouldyError> {
+        if is_external(url) || url.starts_with('#') {
+            return Ok(Some(url.to_string()));
+        }
+
+        let wikilink = matches!(link_type, LinkType::WikiLink { .. });
+        let path_part = url.split('#').next().unwrap_or(url);
+        let is_note = path_part.ends_with(".md") || (wikilink && !path_part.contains('.'));
+
+        if !is_note {
+            if let Some(source) = self.find_attachment(path_part, note_dir, wikilink) {
+                return self.copy_asset(&source).map(Some);
+            }
+            // Plain links to missing files are left as written; a wikilink
+            // may still name a note like "v1.2"
+            if !wikilink {
+                return Ok(Some(url.to_string()));
+            }
+        }
+
+        let decoded = percent_encoding::percent_decode_str(url)
+            .decode_utf8_lossy()
+            .to_string();
+        match self.page_url(&decoded) {
+            Some(page_url) => Ok(Some(page_url)),
+            None => {
+                self.unresolved.insert(decoded);
+                Ok(None)
+            }
+        }
+    }
+
+    fn render(&mut self, content: &str, note_dir: &Path) -> Result<String, BouldyError> {
+        let (_, body) = markdown::split_frontmatter(content);
+        let options = Options::ENABLE_TABLES
+            | Options::ENABLE_STRIKETHROUGH
+            | Options::ENABLE_TASKLISTS
+            | Options::ENABLE_FOOTNOTES
+            | Options::ENABLE_WIKILINKS;
+        let mut events: Vec<Event> = Parser::new_ext(body, options).collect();
+
+        // Anchors for [[Note#Heading]] links
+        for i in 0..events.len() {
+            if !matches!(events[i], Event::Start(Tag::Heading { id: None, .. })) {
+                continue;
+            }
+            let heading: String = events[i + 1..]
+                .iter()
+                .take_while(|event| !matches!(event, Event::End(TagEnd::Heading(_))))
+                .filter_map(|event| match event {
+                    Event::Text(text) | Event::Code(text) => Some(text.as_ref()),
+                    _ => None,
+                })
+                .collect();
+            if let Event::Start(Tag::Heading { id, .. }) = &mut events[i] {
+                *id = Some(CowStr::from(slugify(&heading)));
+            }
+        }
+
+        let mut output = Vec::with_capacity(events.len());
+        // Whether each open link was dropped, so its end is dropped too
+        let mut dropped_links = Vec::new();
+        for event in events {
+            match event {
+                Event::Start(Tag::Link {
+                    link_type,
+                    dest_url,
+                    title,
+                    id,
+                }) => {
+                    let resolved = self.resolve(&dest_url, note_dir, link_type)?;
+                    dropped_links.push(resolved.is_none());
+                    if let Some(url) = resolved {
+                        output.push(Event::Start(Tag::Link {
+                            link_type,
+                            dest_url: url.into(),
+                            title,
+                            id,
+                        }));
+                    }
+                }
+                Event::End(TagEnd::Link) => {
+                    if !dropped_links.pop().unwrap_or(false) {
+                        output.push(event);
+                    }
+                }
+                Event::Start(Tag::Image {
+                    link_type,
+                    dest_url,
+                    title,
+                    id,
+                }) => {
+                    // A missing image keeps its original URL, like a browser would
+                    let url = self
+                        .resolve(&dest_url, note_dir, link_type)?
+                        .unwrap_or_else(|| dest_url.to_string());
+                    output.push(Event::Start(Tag::Image {
+                        link_type,
+                        dest_url: url.into(),
+                        title,
+                        id,
+                    }));
+                }
+                event => output.push(event),
+            }
+        }
+
+        let mut rendered = String::new();
+        html::push_html(&mut rendered, output.into_iter());
+        Ok(rendered)
+    }
+}
+
+fn layout(site_title: &str, page_title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{} - {}</title>\n<style>{}</style>\n</head>\n<body>\n\
+         <nav><a href=\"index.html\">{}</a></nav>\n<main>\n{}</main>\n</body>\n</html>\n",
+        escape_html(page_title),
+        escape_html(site_title),
+        STYLE,
+        escape_html(site_title),
+        body
+    )
+}
+
+fn tag_list(tags: &[String]) -> String {
+    if tags.is_empty() {
+        return String::new();
+    }
+    let tags: Vec<String> = tags
+        .iter()
+        .map(|tag| format!("#{}", escape_html(tag)))
+        .collect();
+    format!("<p class=\"tags\">{}</p>\n", tags.join(" "))
+}
+
+/// Render the selected notes to a static HTML site in `output_dir`: one
+/// page per note plus an index. Links between published notes are
+/// resolved, links to unpublished ones become plain text, and attachments
+/// the notes use are copied into `assets/`.
+pub fn export_site(
+    vault_path: &str,
+    output_dir: &str,
+    options: &SiteOptions,
+) -> Result<SiteReport, BouldyError> {
+    let vault = Path::new(vault_path);
+    let output = PathBuf::from(output_dir);
+    if output.starts_with(vault::notes_dir(vault_path)) {
+        return Err(BouldyError::invalid_path(
+            "Can't export the site into the notes folder",
+        ));
+    }
+
+    let mut pages: Vec<Page> = Vec::new();
+    let mut lookup = HashMap::new();
+    let mut slugs = HashSet::new();
+
+    for note in vault::list_notes(vault_path)? {
+        let path = PathBuf::from(&note.path);
+        let header = vault::read_note_header(&path)?;
+
+        let selected = (options.tags.is_empty() && options.notes.is_empty())
+            || options.notes.contains(&note.path)
+            || header.tags.iter().any(|tag| options.tags.contains(tag));
+        if !selected {
+            continue;
+        }
+
+        let base = match slugify(&header.title) {
+            slug if slug.is_empty() => "note".to_string(),
+            slug => slug,
+        };
+        let slug = (1..)
+            .map(|n| match n {
+                1 => base.clone(),
+                n => format!("{}-{}", base, n),
+            })
+            .find(|slug| slug != "index" && !slugs.contains(slug))
+            .unwrap();
+        slugs.insert(slug.clone());
+
+        let index = pages.len();
+        let stem = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        for name in [&header.title, &stem].into_iter().chain(&header.aliases) {
+            lookup.entry(text::fold(name)).or_insert(index);
+        }
+
+        pages.push(Page {
+            source: path,
+            title: header.title,
+            slug,
+            tags: header.tags,
+        });
+    }
+
+    fs::create_dir_all(&output)
+        .map_err(|e| BouldyError::io("Failed to create output directory", e))?;
+
+    let mut site = Site {
+        attachments: attachment_index(vault, &output),
+        output_dir: output.clone(),
+        lookup,
+        pages,
+        copied: HashMap::new(),
+        unresolved: HashSet::new(),
+    };
+    let site_title = options.title.clone().unwrap_or_else(|| {
+        vault
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "Notes".to_string())
+    });
+
+    for index in 0..site.pages.len() {
+        let source = site.pages[index].source.clone();
+        let content = fs::read_to_string(fsutil::long_path(&source))
+            .map_err(|e| BouldyError::io("Failed to read note", e))?;
+        let note_dir = source.parent().unwrap_or(vault).to_path_buf();
+
+        let content = embeds::expand_content(vault_path, &source, &content, embeds::DEFAULT_DEPTH);
+        let rendered = site.render(&content, &note_dir)?;
+        let page = &site.pages[index];
+        let body = format!("{}{}", tag_list(&page.tags), rendered);
+
+        fsutil::write_atomic(
+            &output.join(format!("{}.html", page.slug)),
+            layout(&site_title, &page.title, &body),
+        )
+        .map_err(|e| BouldyError::io("Failed to write page", e))?;
+    }
+
+    let mut sorted: Vec<&Page> = site.pages.iter().collect();
+    sorted.sort_by_key(|page| text::fold(&page.title));
+    let mut index_body = format!(
+        "<h1>{}</h1>\n<ul class=\"index\">\n",
+        escape_html(&site_title)
+    );
+    for page in sorted {
+        index_body.push_str(&format!(
+            "<li><a href=\"{}.html\">{}</a></li>\n",
+            page.slug,
+            escape_html(&page.title)
+        ));
+    }
+    index_body.push_str("</ul>\n");
+    fsutil::write_atomic(
+        &output.join("index.html"),
+        layout(&site_title, "Index", &index_body),
+    )
+    .map_err(|e| BouldyError::io("Failed to write index", e))?;
+
+    let mut unresolved_links: Vec<String> = site.unresolved.into_iter().collect();
+    unresolved_links.sort();
+
+    Ok(SiteReport {
+        pages: site.pages.len(),
+        attachments: site.copied.len(),
+        output_dir: vault::path_to_string(&output)?,
+        unresolved_links,
+    })
+}
@@ -0,0 +1,193 @@
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+use tauri::AppHandle;
+
+use crate::error::BouldyError;
+use crate::prompt_palette::{self, Keystroke};
+use crate::{clipboard, fsutil, markdown, settings, vault};
+
+// One markdown file per snippet: the abbreviation in frontmatter, the text
+// it expands to as the body
+pub const SNIPPETS_DIR: &str = "snippets";
+
+// Time for the focused app to act on a simulated keystroke, and for the
+// user to let go of the shortcut's own modifiers
+const KEY_DELAY: Duration = Duration::from_millis(150);
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Snippet {
+    // File name without `.md`
+    pub name: String,
+    pub abbr: String,
+    pub text: String,
+    pub path: String,
+}
+
+#[derive(Deserialize)]
+struct SnippetFrontmatter {
+    abbr: String,
+}
+
+fn snippets_dir(vault_path: &str) -> PathBuf {
+    Path::new(vault_path).join(SNIPPETS_DIR)
+}
+
+fn parse_snippet(path: &Path) -> Option<Snippet> {
+    let content = fs::read_to_string(path).ok()?;
+    let (yaml, body) = markdown::split_frontmatter(&content);
+    let frontmatter: SnippetFrontmatter = serde_yaml::from_str(yaml?).ok()?;
+    let abbr = frontmatter.abbr.trim().to_string();
+    if abbr.is_empty() {
+        return None;
+    }
+    Some(Snippet {
+        name: path.file_stem()?.to_string_lossy().to_string(),
+        abbr,
+        // The blank line after the frontmatter isn't part of the text
+        text: body
+            .strip_prefix('\n')
+            .unwrap_or(body)
+            .trim_end()
+            .to_string(),
+        path: path.to_string_lossy().to_string(),
+    })
+}
+
+/// Every snippet with an abbreviation, by abbreviation. Files without one
+/// are skipped.
+pub fn list_snippets(vault_path: &str) -> Result<Vec<Snippet>, BouldyError> {
+    let entries = match fs::read_dir(snippets_dir(vault_path)) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(BouldyError::io("Failed to read snippets directory", e)),
+    };
+
+    let mut snippets: Vec<Snippet> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| vault::is_markdown_file(path))
+        .filter_map(|path| parse_snippet(&path))
+        .collect();
+    snippets.sort_by(|a, b| a.abbr.cmp(&b.abbr).then_with(|| a.name.cmp(&b.name)));
+    Ok(snippets)
+}
+
+fn validate_abbr(abbr: &str) -> Result<(), BouldyError> {
+    if abbr.is_empty() {
+        return Err(BouldyError::invalid_input(
+            "Snippet abbreviation cannot be empty",
+        ));
+    }
+    if abbr.chars().any(char::is_whitespace) {
+        return Err(BouldyError::invalid_input(format!(
+            "Snippet abbreviation '{}' can't contain spaces",
+            abbr
+        )));
+    }
+    Ok(())
+}
+
+/// Create or replace snippet `name`. Abbreviations must be unique.
+pub fn write_snippet(
+    vault_path: &str,
+    name: &str,
+    abbr: &str,
+    text: &str,
+) -> Result<Snippet, BouldyError> {
+    vault::validate_file_stem(name)?;
+    let abbr = abbr.trim();
+    validate_abbr(abbr)?;
+    if let Some(other) = list_snippets(vault_path)?
+        .into_iter()
+        .find(|snippet| snippet.abbr == abbr && snippet.name != name)
+    {
+        return Err(BouldyError::conflict(format!(
+            "'{}' is already the abbreviation for {}",
+            abbr, other.name
+        )));
+    }
+
+    let dir = snippets_dir(vault_path);
+    fs::create_dir_all(&dir)
+        .map_err(|e| BouldyError::io("Failed to create snippets directory", e))?;
+    let path = vault::validate_path_in_vault(vault_path, &format!("{}/{}.md", SNIPPETS_DIR, name))?;
+    let content = format!(
+        "---\nabbr: {}\n---\n\n{}\n",
+        markdown::yaml_string(abbr),
+        text.trim_end()
+    );
+    fsutil::write_atomic(&path, content)
+        .map_err(|e| BouldyError::io("Failed to write snippet", e))?;
+
+    parse_snippet(&path).ok_or_else(|| "Failed to read back snippet".into())
+}
+
+pub fn delete_snippet(vault_path: &str, name: &str) -> Result<(), BouldyError> {
+    vault::validate_file_stem(name)?;
+    let path = vault::validate_path_in_vault(vault_path, &format!("{}/{}.md", SNIPPETS_DIR, name))?;
+    fs::remove_file(&path).map_err(|e| BouldyError::io("Failed to delete snippet", e))
+}
+
+/// The snippet's text with `{{date}}` and `{{time}}` filled in.
+pub fn expand(snippet: &Snippet) -> String {
+    let now = Local::now();
+    snippet
+        .text
+        .replace("{{date}}", &now.format("%Y-%m-%d").to_string())
+        .replace("{{time}}", &now.format("%H:%M").to_string())
+}
+
+/// Expand the snippet with abbreviation `abbr`.
+pub fn expand_snippet(vault_path: &str, abbr: &str) -> Result<String, BouldyError> {
+    let abbr = abbr.trim();
+    list_snippets(vault_path)?
+        .iter()
+        .find(|snippet| snippet.abbr == abbr)
+        .map(expand)
+        .ok_or_else(|| BouldyError::not_found(format!("No snippet for '{}'", abbr)))
+}
+
+/// System-wide expansion, run from the expand-snippet shortcut: select the
+/// word before the cursor in whatever app has focus and, if it's a snippet's
+/// abbreviation, paste the expansion over it. The word is read through the
+/// clipboard, whose contents are put back afterwards. Not for the main
+/// thread, where reading the clipboard can deadlock on Linux.
+pub fn expand_at_cursor(app: &AppHandle) -> Result<(), BouldyError> {
+    let vault_path = settings::require_vault_path(app)?;
+    let saved = clipboard::save(app);
+    // Cleared, so a copy that selected nothing doesn't read the user's text
+    clipboard::restore(app, clipboard::Saved::Empty)?;
+
+    thread::sleep(KEY_DELAY);
+    let selected = prompt_palette::send_keystroke(Keystroke::SelectWordBack);
+    let copied = selected && prompt_palette::send_keystroke(Keystroke::Copy);
+    thread::sleep(KEY_DELAY);
+    let word = match clipboard::save(app) {
+        clipboard::Saved::Text(word) if copied => word,
+        _ => String::new(),
+    };
+
+    match expand_snippet(&vault_path, &word) {
+        Ok(text) => {
+            clipboard::write_text(app, &text)?;
+            if !prompt_palette::paste_and_restore(app, saved) {
+                tracing::warn!("Could not send paste keystroke; snippet left on the clipboard");
+            }
+            Ok(())
+        }
+        Err(e) => {
+            if selected {
+                prompt_palette::send_keystroke(Keystroke::Right);
+            }
+            clipboard::restore(app, saved)?;
+            match e {
+                BouldyError::NotFound { .. } => Ok(()),
+                e => Err(e),
+            }
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::BouldyError;
+use crate::fsutil;
+use crate::todo_store::TodoStore;
+use crate::todos::{self, TodoItem};
+
+// Someday/maybe items live next to todo.txt in the same format, so they can
+// be moved back and forth line for line
+pub const SOMEDAY_FILE: &str = "someday.txt";
+const STATE_FILE: &str = "someday.json";
+const DATE_FORMAT: &str = "%Y-%m-%d";
+pub const DEFAULT_REVIEW_WEEKS: u32 = 4;
+
+// Fingerprint of an item's line -> the day it was last moved, edited or
+// reviewed
+#[derive(Serialize, Deserialize, Default, PartialEq)]
+struct SomedayState {
+    touched: HashMap<String, String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ReviewItem {
+    #[serde(flatten)]
+    pub todo: TodoItem,
+    #[serde(rename = "lastTouched")]
+    pub last_touched: String,
+    #[serde(rename = "weeksUntouched")]
+    pub weeks_untouched: i64,
+}
+
+fn someday_path(vault_path: &str) -> PathBuf {
+    Path::new(vault_path).join(SOMEDAY_FILE)
+}
+
+fn state_path(vault_path: &str) -> PathBuf {
+    Path::new(vault_path).join(".bouldy").join(STATE_FILE)
+}
+
+fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+pub fn list_someday(vault_path: &str) -> Result<Vec<TodoItem>, BouldyError> {
+    match fs::read_to_string(someday_path(vault_path)) {
+        Ok(content) => todos::parse_todos(&content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(BouldyError::io("Failed to read someday list", e)),
+    }
+}
+
+fn save_someday(vault_path: &str, items: &[TodoItem]) -> Result<(), BouldyError> {
+    fsutil::write_text_preserving(&someday_path(vault_path), &todos::serialize_todos(items))
+        .map_err(|e| BouldyError::io("Failed to write someday list", e))
+}
+
+fn load_state(vault_path: &str) -> SomedayState {
+    fs::read_to_string(state_path(vault_path))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+// When each item was last touched, forgetting ones that are gone. Items not
+// seen before count from their creation date, or today if they have none.
+fn touched_dates(
+    vault_path: &str,
+    items: &[TodoItem],
+    reviewed: &[usize],
+) -> Result<Vec<NaiveDate>, BouldyError> {
+    let previous = load_state(vault_path);
+    let today = today();
+
+    let mut state = SomedayState::default();
+    let dates = items
+        .iter()
+        .map(|item| {
+            let key = todos::fingerprint(item);
+            let touched = if reviewed.contains(&item.id) {
+                today
+            } else {
+                previous
+                    .touched
+                    .get(&key)
+                    .or(item.created_date.as_ref())
+                    .and_then(|date| NaiveDate::parse_from_str(date, DATE_FORMAT).ok())
+                    .unwrap_or(today)
+            };
+            state
+                .touched
+                .insert(key, touched.format(DATE_FORMAT).to_string());
+            touched
+        })
+        .collect();
+
+    if state != previous {
+        let content = serde_json::to_string_pretty(&state)
+            .map_err(|e| format!("Failed to serialize someday state: {}", e))?;
+        fsutil::write_atomic(&state_path(vault_path), content)
+            .map_err(|e| BouldyError::io("Failed to write someday state", e))?;
+    }
+    Ok(dates)
+}
+
+fn take(items: &mut Vec<TodoItem>, id: usize, list: &str) -> Result<TodoItem, BouldyError> {
+    let index = items
+        .iter()
+        .position(|item| item.id == id)
+        .ok_or_else(|| BouldyError::not_found(format!("{} item not found: {}", list, id)))?;
+    Ok(items.remove(index))
+}
+
+/// Move a todo out of todo.txt to the end of someday.txt.
+pub fn move_to_someday(
+    store: &TodoStore,
+    vault_path: &str,
+    id: usize,
+) -> Result<TodoItem, BouldyError> {
+    store.with_file(vault_path, || {
+        let mut todos_list = todos::load_todos(vault_path)?;
+        let todo = take(&mut todos_list, id, "Todo")?;
+
+        let mut someday = list_someday(vault_path)?;
+        someday.push(todo.clone());
+        // Someday first, so a failure leaves a duplicate rather than a loss
+        save_someday(vault_path, &someday)?;
+        todos::save_todos(vault_path, &todos_list)?;
+
+        // Ids are positions, so the moved item is the last one
+        todos::renumber(&mut someday);
+        let moved = [someday.len()];
+        touched_dates(vault_path, &someday, &moved)?;
+        Ok(todo)
+    })
+}
+
+/// Move a someday item back to the end of todo.txt.
+pub fn activate_someday(
+    store: &TodoStore,
+    vault_path: &str,
+    id: usize,
+) -> Result<TodoItem, BouldyError> {
+    store.with_file(vault_path, || {
+        let mut someday = list_someday(vault_path)?;
+        let item = take(&mut someday, id, "Someday")?;
+
+        let mut todos_list = todos::load_todos(vault_path)?;
+        let item = todos::push(&mut todos_list, item);
+        todos::save_todos(vault_path, &todos_list)?;
+        save_someday(vault_path, &someday)?;
+
+        touched_dates(vault_path, &list_someday(vault_path)?, &[])?;
+        Ok(item)
+    })
+}
+
+/// Someday items nobody has moved, edited or reviewed in at least `weeks`
+/// weeks, longest untouched first.
+pub fn review_queue(vault_path: &str, weeks: u32) -> Result<Vec<ReviewItem>, BouldyError> {
+    let items = list_someday(vault_path)?;
+    let touched = touched_dates(vault_path, &items, &[])?;
+    let today = today();
+
+    let mut queue: Vec<ReviewItem> = items
+        .into_iter()
+        .zip(touched)
+        .filter(|(_, touched)| (today - *touched).num_weeks() >= weeks as i64)
+        .map(|(todo, touched)| ReviewItem {
+            todo,
+            last_touched: touched.format(DATE_FORMAT).to_string(),
+            weeks_untouched: (today - touched).num_weeks(),
+        })
+        .collect();
+    queue.sort_by(|a, b| a.last_touched.cmp(&b.last_touched));
+    Ok(queue)
+}
+
+/// Keep the given someday items for now, restarting their review clock.
+pub fn mark_reviewed(vault_path: &str, ids: &[usize]) -> Result<(), BouldyError> {
+    let items = list_someday(vault_path)?;
+    touched_dates(vault_path, &items, ids)?;
+    Ok(())
+}
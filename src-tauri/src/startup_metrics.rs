@@ -0,0 +1,132 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::error::BouldyError;
+
+const METRICS_FILE: &str = "startup-metrics.jsonl";
+
+// Older entries are dropped once the log grows past this many launches
+const MAX_ENTRIES: usize = 100;
+
+#[derive(Clone, Copy, Debug)]
+pub enum Phase {
+    StoreLoad,
+    WatcherSetup,
+    FirstListing,
+}
+
+/// Backend timings for the current launch. Each phase keeps its first
+/// measurement, so later vault switches or refreshes don't overwrite it.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct BackendTimings {
+    #[serde(rename = "storeLoadMs")]
+    pub store_load_ms: Option<f64>,
+    #[serde(rename = "watcherSetupMs")]
+    pub watcher_setup_ms: Option<f64>,
+    #[serde(rename = "firstListingMs")]
+    pub first_listing_ms: Option<f64>,
+}
+
+#[derive(Default)]
+pub struct StartupTimings(Mutex<BackendTimings>);
+
+/// One launch as written to the metrics log.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StartupMetrics {
+    pub timestamp: String,
+    pub version: String,
+    #[serde(rename = "themeInitMs")]
+    pub theme_init_ms: f64,
+    #[serde(rename = "reactMountMs")]
+    pub react_mount_ms: f64,
+    #[serde(rename = "totalMs")]
+    pub total_ms: f64,
+    #[serde(rename = "firstPaintMs")]
+    pub first_paint_ms: Option<f64>,
+    #[serde(flatten)]
+    pub backend: BackendTimings,
+}
+
+pub fn record(app: &AppHandle, phase: Phase, elapsed: Duration) {
+    let Some(state) = app.try_state::<StartupTimings>() else {
+        return;
+    };
+    let mut timings = state.0.lock().unwrap();
+
+    let slot = match phase {
+        Phase::StoreLoad => &mut timings.store_load_ms,
+        Phase::WatcherSetup => &mut timings.watcher_setup_ms,
+        Phase::FirstListing => &mut timings.first_listing_ms,
+    };
+    if slot.is_none() {
+        *slot = Some(elapsed.as_secs_f64() * 1000.0);
+    }
+}
+
+pub fn backend_timings(app: &AppHandle) -> BackendTimings {
+    app.try_state::<StartupTimings>()
+        .map(|state| state.0.lock().unwrap().clone())
+        .unwrap_or_default()
+}
+
+fn metrics_path(app: &AppHandle) -> Result<PathBuf, BouldyError> {
+    let log_dir = app
+        .path()
+        .app_log_dir()
+        .map_err(|e| format!("Failed to resolve log directory: {}", e))?;
+    fs::create_dir_all(&log_dir).map_err(|e| format!("Failed to create log directory: {}", e))?;
+
+    Ok(log_dir.join(METRICS_FILE))
+}
+
+pub fn load_history(app: &AppHandle) -> Result<Vec<StartupMetrics>, BouldyError> {
+    let path = metrics_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read startup metrics: {}", e))?;
+
+    // A line cut short by a crash mid-append is skipped, not fatal
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+pub fn append(app: &AppHandle, metrics: &StartupMetrics) -> Result<(), BouldyError> {
+    let path = metrics_path(app)?;
+    let line = serde_json::to_string(metrics)
+        .map_err(|e| format!("Failed to serialize startup metrics: {}", e))?;
+
+    let mut history = load_history(app)?;
+    if history.len() >= MAX_ENTRIES {
+        history.drain(..=history.len() - MAX_ENTRIES);
+        let mut content = String::new();
+        for entry in &history {
+            if let Ok(entry) = serde_json::to_string(entry) {
+                content.push_str(&entry);
+                content.push('\n');
+            }
+        }
+        content.push_str(&line);
+        content.push('\n');
+
+        return crate::fsutil::write_atomic(&path, content)
+            .map_err(|e| BouldyError::io("Failed to write startup metrics", e));
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open startup metrics: {}", e))?;
+
+    writeln!(file, "{}", line).map_err(|e| BouldyError::io("Failed to write startup metrics", e))
+}
@@ -0,0 +1,263 @@
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::index::IndexState;
+use crate::{text, todos, vault};
+
+const MAX_RESULTS: usize = 50;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SwitcherScope {
+    #[serde(rename = "all")]
+    All,
+    #[serde(rename = "notes")]
+    Notes,
+    #[serde(rename = "prompts")]
+    Prompts,
+    #[serde(rename = "todos")]
+    Todos,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EntryKind {
+    #[serde(rename = "note")]
+    Note,
+    #[serde(rename = "prompt")]
+    Prompt,
+    #[serde(rename = "todo")]
+    Todo,
+}
+
+impl SwitcherScope {
+    fn includes(self, kind: EntryKind) -> bool {
+        match self {
+            SwitcherScope::All => true,
+            SwitcherScope::Notes => kind == EntryKind::Note,
+            SwitcherScope::Prompts => kind == EntryKind::Prompt,
+            SwitcherScope::Todos => kind == EntryKind::Todo,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct SwitcherEntry {
+    pub kind: EntryKind,
+    pub id: String, // note path, prompt id, or todo id
+    pub title: String,
+    // Other names a note can be found by, from its `aliases:` frontmatter
+    #[serde(skip)]
+    pub aliases: Vec<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct SwitcherMatch {
+    #[serde(flatten)]
+    pub entry: SwitcherEntry,
+    pub score: i64,
+    pub indices: Vec<usize>, // char positions in the title (or alias) that matched
+    // Set when the query matched one of the note's aliases better than its title
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+}
+
+// Each section is rebuilt independently the next time it's queried after
+// the watcher marks it stale.
+#[derive(Default)]
+struct VaultIndex {
+    vault_path: String,
+    notes: Option<Vec<SwitcherEntry>>,
+    prompts: Option<Vec<SwitcherEntry>>,
+    todos: Option<Vec<SwitcherEntry>>,
+}
+
+/// Managed state holding the in-memory title index for the open vault.
+#[derive(Default)]
+pub struct QuickSwitcher {
+    index: Mutex<VaultIndex>,
+}
+
+fn index_notes(vault_path: &str, vault_index: &IndexState) -> Vec<SwitcherEntry> {
+    let indexed = vault_index.with_vault(vault_path, |index| {
+        Ok((index.list_notes()?, index.aliases()?))
+    });
+    let (notes, mut aliases) = match indexed {
+        Some(Ok(indexed)) => indexed,
+        // Not indexed yet, so aliases come from each note's frontmatter
+        _ => {
+            let notes = vault::list_notes(vault_path).unwrap_or_default();
+            let aliases = notes
+                .iter()
+                .filter_map(|note| {
+                    let header = vault::read_note_header(Path::new(&note.path)).ok()?;
+                    Some((note.path.clone(), header.aliases))
+                })
+                .collect();
+            (notes, aliases)
+        }
+    };
+
+    notes
+        .into_iter()
+        .map(|note| SwitcherEntry {
+            kind: EntryKind::Note,
+            aliases: aliases.remove(&note.path).unwrap_or_default(),
+            id: note.path,
+            title: note.title,
+        })
+        .collect()
+}
+
+// Only the first line is read - prompt files start with their "# Title"
+fn read_prompt_title(path: &Path) -> Option<String> {
+    let mut first_line = String::new();
+    BufReader::new(File::open(path).ok()?)
+        .read_line(&mut first_line)
+        .ok()?;
+
+    let title = first_line.trim().strip_prefix("# ")?.trim();
+    Some(title.to_string())
+}
+
+fn index_prompts(vault_path: &str) -> Vec<SwitcherEntry> {
+    let prompts_dir = Path::new(vault_path).join("prompts");
+    let Ok(entries) = fs::read_dir(&prompts_dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| vault::is_markdown_file(path))
+        .filter_map(|path| {
+            let id = path.file_stem()?.to_str()?.to_string();
+            let title = read_prompt_title(&path).unwrap_or_else(|| id.clone());
+            Some(SwitcherEntry {
+                kind: EntryKind::Prompt,
+                id,
+                title,
+                aliases: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+fn index_todos(vault_path: &str) -> Vec<SwitcherEntry> {
+    todos::load_todos(vault_path)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|todo| !todo.completed)
+        .map(|todo| SwitcherEntry {
+            kind: EntryKind::Todo,
+            id: todo.id.to_string(),
+            title: todo.title,
+            aliases: Vec::new(),
+        })
+        .collect()
+}
+
+impl QuickSwitcher {
+    pub fn invalidate_notes(&self) {
+        self.index.lock().unwrap().notes = None;
+    }
+
+    pub fn invalidate_prompts(&self) {
+        self.index.lock().unwrap().prompts = None;
+    }
+
+    pub fn invalidate_todos(&self) {
+        self.index.lock().unwrap().todos = None;
+    }
+
+    pub fn find(
+        &self,
+        vault_index: &IndexState,
+        vault_path: &str,
+        query: &str,
+        scope: SwitcherScope,
+    ) -> Vec<SwitcherMatch> {
+        let mut index = self.index.lock().unwrap();
+
+        if index.vault_path != vault_path {
+            *index = VaultIndex {
+                vault_path: vault_path.to_string(),
+                ..Default::default()
+            };
+        }
+
+        if scope.includes(EntryKind::Note) && index.notes.is_none() {
+            index.notes = Some(index_notes(vault_path, vault_index));
+        }
+        if scope.includes(EntryKind::Prompt) && index.prompts.is_none() {
+            index.prompts = Some(index_prompts(vault_path));
+        }
+        if scope.includes(EntryKind::Todo) && index.todos.is_none() {
+            index.todos = Some(index_todos(vault_path));
+        }
+
+        let candidates = [&index.notes, &index.prompts, &index.todos]
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|entry| scope.includes(entry.kind));
+
+        let query = query.trim();
+        if query.is_empty() {
+            return candidates
+                .take(MAX_RESULTS)
+                .map(|entry| SwitcherMatch {
+                    entry: entry.clone(),
+                    score: 0,
+                    indices: Vec::new(),
+                    alias: None,
+                })
+                .collect();
+        }
+
+        // Both sides composed so indices line up with the returned title and
+        // decomposed file names match what was typed
+        let query = text::nfc(query);
+        let matcher = SkimMatcherV2::default().ignore_case();
+        let mut matches: Vec<SwitcherMatch> = candidates
+            .filter_map(|entry| {
+                let title = text::nfc(&entry.title);
+                let by_title = matcher
+                    .fuzzy_indices(&title, &query)
+                    .map(|(score, indices)| (score, indices, None));
+                // A renamed note stays reachable under its old name
+                let by_alias = entry.aliases.iter().filter_map(|alias| {
+                    let alias = text::nfc(alias);
+                    let (score, indices) = matcher.fuzzy_indices(&alias, &query)?;
+                    Some((score, indices, Some(alias)))
+                });
+                let (score, indices, alias) = by_title
+                    .into_iter()
+                    .chain(by_alias)
+                    .max_by_key(|(score, _, alias)| (*score, alias.is_none()))?;
+                Some(SwitcherMatch {
+                    entry: SwitcherEntry {
+                        title,
+                        ..entry.clone()
+                    },
+                    score,
+                    indices,
+                    alias,
+                })
+            })
+            .collect();
+
+        // Best score first; shorter titles win ties since they're closer matches
+        matches.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| a.entry.title.len().cmp(&b.entry.title.len()))
+        });
+        matches.truncate(MAX_RESULTS);
+
+        matches
+    }
+}
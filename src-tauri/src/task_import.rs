@@ -0,0 +1,370 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::sync::OnceLock;
+
+use crate::error::BouldyError;
+use crate::todo_store::TodoStore;
+use crate::todos::{self, TodoItem};
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub enum TaskFormat {
+    // `* TODO [#A] Title :tag:` headlines with SCHEDULED/DEADLINE lines
+    #[serde(rename = "org")]
+    Org,
+    // `- [ ] Title` task lists, including Obsidian Tasks' emoji dates
+    #[serde(rename = "markdown")]
+    Markdown,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct SkippedTask {
+    pub line: usize,
+    pub text: String,
+    pub reason: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TaskImport {
+    pub todos: Vec<TodoItem>,
+    pub skipped: Vec<SkippedTask>,
+    #[serde(rename = "dryRun")]
+    pub dry_run: bool,
+}
+
+fn org_headline_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(
+            r"^\*+\s+(TODO|NEXT|WAITING|HOLD|DONE|CANCELLED|CANCELED)\s+(?:\[#([A-Z])\]\s+)?(.*?)(?:\s+(:[^\s:]+(?::[^\s:]+)*:))?\s*$",
+        )
+        .unwrap()
+    })
+}
+
+fn org_other_headline_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"^\*+\s").unwrap())
+}
+
+pub fn org_planning_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    // SCHEDULED: <2025-01-02 Thu> DEADLINE: <2025-01-05 Sun 9:30>
+    REGEX.get_or_init(|| {
+        Regex::new(r"(SCHEDULED|DEADLINE):\s*<(\d{4}-\d{2}-\d{2})(?:\s+[^\s\d>]+)?(?:\s+(\d{1,2}:\d{2}))?[^>]*>")
+            .unwrap()
+    })
+}
+
+fn markdown_task_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"^\s*[-*+]\s+\[([ xX])\]\s+(.+)$").unwrap())
+}
+
+fn list_marker_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    // "- ", "* ", "• ", "1. " and "1) " bullets on pasted lines
+    REGEX.get_or_init(|| Regex::new(r"^\s*(?:[-*+•]|\d+[.)])\s+").unwrap())
+}
+
+// Org timestamp to a todo.txt due value, `YYYY-MM-DD[THH:MM]`
+pub fn org_due(date: &str, time: Option<&str>) -> String {
+    match time {
+        Some(time) if time.len() == 4 => format!("{}T0{}", date, time),
+        Some(time) => format!("{}T{}", date, time),
+        None => date.to_string(),
+    }
+}
+
+/// Split task text into a todo: `+project`, `@context`, `#tag` (as a
+/// project), `due:` and the Obsidian Tasks markers for dates and priority
+/// are pulled out; everything else is the title.
+pub fn todo_from_text(text: &str) -> TodoItem {
+    let mut todo = TodoItem {
+        id: 0,
+        title: String::new(),
+        completed: false,
+        due_date: None,
+        priority: None,
+        projects: Vec::new(),
+        contexts: Vec::new(),
+        created_date: Some(chrono::Local::now().format("%Y-%m-%d").to_string()),
+        uid: None,
+        parent: None,
+        delegated: None,
+        estimate: None,
+        description: None,
+    };
+
+    let mut title = Vec::new();
+    let mut scheduled = None;
+    let mut words = text.split_whitespace();
+    while let Some(word) = words.next() {
+        match word {
+            // The date follows as its own word
+            "📅" | "⏳" => {
+                let date = words.next().map(str::to_string);
+                if word == "📅" {
+                    todo.due_date = date;
+                } else {
+                    scheduled = date;
+                }
+            }
+            // Completion/creation dates and recurrence aren't kept
+            "✅" | "➕" | "🛫" => {
+                words.next();
+            }
+            "🔺" | "⏫" => todo.priority = Some("A".to_string()),
+            "🔼" => todo.priority = Some("B".to_string()),
+            "🔽" | "⏬" => todo.priority = Some("C".to_string()),
+            _ => {
+                if let Some(due) = word.strip_prefix("due:") {
+                    todo.due_date = Some(due.to_string());
+                } else if let Some(estimate) = word
+                    .strip_prefix("est:")
+                    .and_then(|estimate| estimate.parse().ok())
+                    .filter(|&estimate| estimate > 0)
+                {
+                    todo.estimate = Some(estimate);
+                } else if let Some(project) = word.strip_prefix('+').filter(|p| !p.is_empty()) {
+                    todo.projects.push(project.to_string());
+                } else if let Some(tag) = word.strip_prefix('#').filter(|t| !t.is_empty()) {
+                    todo.projects.push(tag.to_string());
+                } else if let Some(context) = word.strip_prefix('@').filter(|c| !c.is_empty()) {
+                    todo.contexts.push(context.to_string());
+                } else {
+                    title.push(word);
+                }
+            }
+        }
+    }
+
+    // todo.txt has no start date, so a scheduled date is the best stand-in
+    if todo.due_date.is_none() {
+        todo.due_date = scheduled;
+    }
+    todo.title = title.join(" ");
+    todo
+}
+
+// (line number, todo) for each task headline
+fn parse_org(content: &str) -> Vec<(usize, TodoItem)> {
+    let mut tasks: Vec<(usize, TodoItem)> = Vec::new();
+    // Whether the last headline was a task, so planning lines apply to it
+    let mut in_task = false;
+    // A DEADLINE wins over a SCHEDULED date regardless of order
+    let mut has_deadline = false;
+
+    for (index, line) in content.lines().enumerate() {
+        if let Some(captures) = org_headline_regex().captures(line) {
+            let mut todo = todo_from_text(&captures[3]);
+            let state = &captures[1];
+            todo.completed = matches!(state, "DONE" | "CANCELLED" | "CANCELED");
+            // Keep intermediate states visible as contexts
+            if matches!(state, "NEXT" | "WAITING" | "HOLD") {
+                todo.contexts.push(state.to_lowercase());
+            }
+            if let Some(priority) = captures.get(2) {
+                todo.priority = Some(priority.as_str().to_string());
+            }
+            // :@home:work: - org's @tags are contexts, the rest projects
+            if let Some(tags) = captures.get(4) {
+                for tag in tags.as_str().split(':').filter(|t| !t.is_empty()) {
+                    match tag.strip_prefix('@') {
+                        Some(context) => todo.contexts.push(context.to_string()),
+                        None => todo.projects.push(tag.to_string()),
+                    }
+                }
+            }
+
+            tasks.push((index + 1, todo));
+            in_task = true;
+            has_deadline = false;
+            continue;
+        }
+
+        if org_other_headline_regex().is_match(line) {
+            in_task = false;
+            continue;
+        }
+
+        let Some((_, todo)) = tasks.last_mut().filter(|_| in_task) else {
+            continue;
+        };
+        for captures in org_planning_regex().captures_iter(line) {
+            let due = org_due(&captures[2], captures.get(3).map(|t| t.as_str()));
+            if &captures[1] == "DEADLINE" {
+                todo.due_date = Some(due);
+                has_deadline = true;
+            } else if !has_deadline {
+                todo.due_date = Some(due);
+            }
+        }
+    }
+
+    tasks
+}
+
+fn parse_markdown(content: &str) -> Vec<(usize, TodoItem)> {
+    let mut tasks = Vec::new();
+    let mut in_code_block = false;
+
+    for (index, line) in content.lines().enumerate() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+
+        if let Some(captures) = markdown_task_regex().captures(line) {
+            let mut todo = todo_from_text(&captures[2]);
+            todo.completed = &captures[1] != " ";
+            tasks.push((index + 1, todo));
+        }
+    }
+
+    tasks
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct BulkAdd {
+    pub todos: Vec<TodoItem>,
+    pub skipped: Vec<SkippedTask>,
+}
+
+// One pasted line as a todo: a markdown checkbox, a bullet or a plain line,
+// optionally starting with a todo.txt priority like "(A)"
+fn todo_from_line(line: &str) -> TodoItem {
+    if let Some(captures) = markdown_task_regex().captures(line) {
+        let mut todo = todo_from_text(&captures[2]);
+        todo.completed = &captures[1] != " ";
+        return todo;
+    }
+
+    let text = list_marker_regex().replace(line, "");
+    let text = text.trim();
+    let priority = text
+        .strip_prefix('(')
+        .and_then(|rest| rest.split_once(") "))
+        .filter(|(p, _)| p.len() == 1 && p.chars().all(|c| c.is_ascii_uppercase()));
+    match priority {
+        Some((priority, rest)) => {
+            let mut todo = todo_from_text(rest);
+            todo.priority = Some(priority.to_string());
+            todo
+        }
+        None => todo_from_text(text),
+    }
+}
+
+/// Add one todo per pasted line to the end of todo.txt in a single write.
+/// Blank lines and headings are ignored; lines without a title or with an
+/// invalid due date are skipped and reported.
+pub fn bulk_add(store: &TodoStore, vault_path: &str, text: &str) -> Result<BulkAdd, BouldyError> {
+    let mut todos_to_add = Vec::new();
+    let mut skipped = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let mut todo = todo_from_line(line);
+        let skip = |reason: &str| SkippedTask {
+            line: index + 1,
+            text: line.trim().to_string(),
+            reason: reason.to_string(),
+        };
+
+        if todo.title.is_empty() {
+            skipped.push(skip("No title"));
+            continue;
+        }
+        match todos::normalize_due_date(todo.due_date.take()) {
+            Ok(due_date) => todo.due_date = due_date,
+            Err(e) => {
+                skipped.push(skip(e.message()));
+                continue;
+            }
+        }
+        todos_to_add.push(todo);
+    }
+
+    if todos_to_add.is_empty() {
+        return Ok(BulkAdd {
+            todos: Vec::new(),
+            skipped,
+        });
+    }
+
+    let todos = store.mutate(vault_path, |existing| {
+        Ok(todos_to_add
+            .into_iter()
+            .map(|todo| todos::push(existing, todo))
+            .collect())
+    })?;
+    Ok(BulkAdd { todos, skipped })
+}
+
+/// Parse tasks from an org-mode or markdown file and append them to
+/// todo.txt. Tasks with an invalid date, or whose title matches a todo that
+/// is already there, are skipped. With `dry_run` nothing is written and the
+/// result is a preview.
+pub fn import_tasks(
+    store: &TodoStore,
+    vault_path: &str,
+    path: &str,
+    format: TaskFormat,
+    dry_run: bool,
+) -> Result<TaskImport, BouldyError> {
+    let content =
+        fs::read_to_string(path).map_err(|e| BouldyError::io("Failed to read task file", e))?;
+
+    let tasks = match format {
+        TaskFormat::Org => parse_org(&content),
+        TaskFormat::Markdown => parse_markdown(&content),
+    };
+
+    let merge = |existing: &mut Vec<TodoItem>| {
+        let mut imported = Vec::new();
+        let mut skipped = Vec::new();
+        for (line, mut todo) in tasks {
+            let skip = |reason: &str| SkippedTask {
+                line,
+                text: todo.title.clone(),
+                reason: reason.to_string(),
+            };
+
+            if todo.title.is_empty() {
+                skipped.push(skip("No title"));
+                continue;
+            }
+            match todos::normalize_due_date(todo.due_date.take()) {
+                Ok(due_date) => todo.due_date = due_date,
+                Err(e) => {
+                    skipped.push(skip(e.message()));
+                    continue;
+                }
+            }
+            if existing.iter().any(|t| t.title == todo.title) {
+                skipped.push(skip("Already in todo.txt"));
+                continue;
+            }
+
+            imported.push(todos::push(existing, todo));
+        }
+
+        TaskImport {
+            todos: imported,
+            skipped,
+            dry_run,
+        }
+    };
+
+    if dry_run {
+        let mut existing = store.load(vault_path)?;
+        Ok(merge(&mut existing))
+    } else {
+        store.mutate(vault_path, |existing| Ok(merge(existing)))
+    }
+}
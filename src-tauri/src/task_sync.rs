@@ -0,0 +1,232 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use crate::error::BouldyError;
+use crate::todo_store::TodoStore;
+use crate::todos::{self, TodoItem};
+use crate::{fsutil, task_import, vault, vault_lock};
+
+/// Frontmatter flag a note sets to have its checkboxes synced with todo.txt.
+pub const SYNC_FLAG: &str = "sync-tasks";
+
+const STATE_FILE: &str = "task-sync.json";
+
+// The watcher can fire for a note and todo.txt at once; syncing one batch at
+// a time keeps the state file consistent
+static SYNCING: Mutex<()> = Mutex::new(());
+
+// What a checkbox and its todo last agreed on, to tell which side changed
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct SyncedTask {
+    title: String,
+    completed: bool,
+}
+
+type SyncState = HashMap<String, SyncedTask>;
+
+fn checkbox_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    // `- [ ] Title ^uid`, where the uid doubles as an Obsidian block id
+    REGEX.get_or_init(|| {
+        Regex::new(r"^(\s*[-*+]\s+\[)([ xX])(\]\s+)(.*?)(?:\s+\^([A-Za-z0-9-]+))?\s*$").unwrap()
+    })
+}
+
+fn state_path(vault_path: &str) -> PathBuf {
+    Path::new(vault_path).join(".bouldy").join(STATE_FILE)
+}
+
+fn load_state(vault_path: &str) -> SyncState {
+    fs::read_to_string(state_path(vault_path))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(vault_path: &str, state: &SyncState) -> Result<(), BouldyError> {
+    let content = serde_json::to_string_pretty(state)
+        .map_err(|e| format!("Failed to serialize task sync state: {}", e))?;
+    fsutil::write_atomic(&state_path(vault_path), content)
+        .map_err(|e| BouldyError::io("Failed to write task sync state", e))
+}
+
+fn is_synced_note(path: &Path) -> bool {
+    vault::read_note_header(path)
+        .map(|header| header.frontmatter.get(SYNC_FLAG) == Some(&serde_json::Value::Bool(true)))
+        .unwrap_or(false)
+}
+
+/// Reconcile one note's checkboxes with `todos`. Completion syncs both ways:
+/// whichever side changed since the last sync wins, the note if both did.
+/// Titles only flow from the note. Checkboxes without a uid get a new todo
+/// and a `^uid` appended. Returns the rewritten note, if it changed, and
+/// whether `todos` changed.
+fn sync_content(
+    content: &str,
+    todos: &mut Vec<TodoItem>,
+    state: &mut SyncState,
+) -> (Option<String>, bool) {
+    let mut output = String::with_capacity(content.len());
+    let mut note_changed = false;
+    let mut todos_changed = false;
+    let mut in_code_block = false;
+    let mut seen = HashSet::new();
+
+    for raw_line in content.split_inclusive('\n') {
+        let line = raw_line.trim_end_matches(['\n', '\r']);
+        let ending = &raw_line[line.len()..];
+
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+        }
+        let captures = match checkbox_regex().captures(line) {
+            Some(captures) if !in_code_block => captures,
+            _ => {
+                output.push_str(raw_line);
+                continue;
+            }
+        };
+
+        let text = &captures[4];
+        let parsed = task_import::todo_from_text(text);
+        let mut checked = &captures[2] != " ";
+
+        // An editor holding an older copy of the note may have saved over
+        // the uid; re-adopt the todo it belonged to rather than duplicate it
+        let uid = captures
+            .get(5)
+            .map(|uid| uid.as_str().to_string())
+            .or_else(|| {
+                state
+                    .iter()
+                    .find(|(uid, synced)| synced.title == parsed.title && !seen.contains(*uid))
+                    .map(|(uid, _)| uid.clone())
+            });
+        let had_uid = captures.get(5).is_some();
+
+        let uid = match uid {
+            Some(uid) => uid,
+            None => todos::new_uid(),
+        };
+        seen.insert(uid.clone());
+
+        match todos
+            .iter_mut()
+            .find(|todo| todo.uid.as_deref() == Some(uid.as_str()))
+        {
+            Some(todo) => {
+                let last = state.get(&uid);
+                if todo.completed != checked {
+                    if last.is_none_or(|last| last.completed != checked) {
+                        todo.completed = checked;
+                        todos_changed = true;
+                    } else {
+                        checked = todo.completed;
+                        note_changed = true;
+                    }
+                }
+                if todo.title != parsed.title && last.is_none_or(|last| last.title != parsed.title)
+                {
+                    todo.title = parsed.title.clone();
+                    todos_changed = true;
+                }
+            }
+            // Deleted or archived from the todo list since; leave the
+            // checkbox as it is
+            None if state.contains_key(&uid) => {
+                state.remove(&uid);
+                output.push_str(raw_line);
+                continue;
+            }
+            None => {
+                let mut todo = parsed.clone();
+                todo.completed = checked;
+                todo.uid = Some(uid.clone());
+                todos::push(todos, todo);
+                todos_changed = true;
+            }
+        }
+
+        state.insert(
+            uid.clone(),
+            SyncedTask {
+                title: parsed.title,
+                completed: checked,
+            },
+        );
+
+        if !had_uid {
+            note_changed = true;
+        }
+        output.push_str(&format!(
+            "{}{}{}{} ^{}{}",
+            &captures[1],
+            if checked { "x" } else { " " },
+            &captures[3],
+            text,
+            uid,
+            ending
+        ));
+    }
+
+    (note_changed.then_some(output), todos_changed)
+}
+
+/// Sync the checkboxes of every note in `paths` that sets `sync-tasks: true`
+/// with todo.txt, rewriting notes whose checkboxes changed. Returns whether
+/// the todo list changed.
+pub fn sync_notes(
+    store: &TodoStore,
+    vault_path: &str,
+    paths: &[PathBuf],
+) -> Result<bool, BouldyError> {
+    let notes: Vec<(&PathBuf, String)> = paths
+        .iter()
+        .filter(|path| is_synced_note(path))
+        .filter_map(|path| Some((path, fs::read_to_string(path).ok()?)))
+        .collect();
+    if notes.is_empty() {
+        return Ok(false);
+    }
+
+    let _syncing = SYNCING.lock().unwrap_or_else(|e| e.into_inner());
+    let mut state = load_state(vault_path);
+
+    let (rewrites, todos_changed) = store.mutate_if_changed(vault_path, |todos| {
+        let mut rewrites = Vec::new();
+        let mut todos_changed = false;
+        for (path, content) in &notes {
+            let (rewritten, changed) = sync_content(content, todos, &mut state);
+            if let Some(rewritten) = rewritten {
+                rewrites.push((*path, rewritten));
+            }
+            todos_changed |= changed;
+        }
+        Ok(((rewrites, todos_changed), todos_changed))
+    })?;
+
+    let _lock = vault_lock::acquire(vault_path)?;
+    for (path, content) in rewrites {
+        fsutil::write_atomic(path, content)
+            .map_err(|e| BouldyError::io("Failed to write synced note", e))?;
+    }
+    save_state(vault_path, &state)?;
+
+    Ok(todos_changed)
+}
+
+/// Sync every opted-in note, e.g. after todo.txt changed on disk.
+pub fn sync_vault(store: &TodoStore, vault_path: &str) -> Result<bool, BouldyError> {
+    let paths: Vec<PathBuf> = fs::read_dir(vault::notes_dir(vault_path))
+        .map_err(|e| BouldyError::io("Failed to read notes directory", e))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| vault::is_markdown_file(path))
+        .collect();
+
+    sync_notes(store, vault_path, &paths)
+}
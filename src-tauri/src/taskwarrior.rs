@@ -0,0 +1,399 @@
+use chrono::{Local, NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::BouldyError;
+use crate::task_import::SkippedTask;
+use crate::todo_store::TodoStore;
+use crate::todos::{self, Due, TodoItem};
+use crate::{fsutil, vault_lock};
+
+const STATE_FILE: &str = "taskwarrior.json";
+
+// Taskwarrior's UTC timestamp format, e.g. 20250101T120000Z
+const TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+// Task attributes mapped onto todo.txt fields. Everything else on a task
+// (annotations, wait, UDAs, ...) is kept in the state file and written back
+// on export. `id` and `urgency` are computed by Taskwarrior and dropped.
+const OWNED_ATTRIBUTES: &[&str] = &[
+    "uuid",
+    "description",
+    "status",
+    "project",
+    "priority",
+    "due",
+    "entry",
+    "tags",
+    "modified",
+    "id",
+    "urgency",
+];
+
+// What todo.txt can't hold about one task
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+struct TaskState {
+    // The todo's `uid:` when it isn't the UUID itself, e.g. one given by a
+    // synced note before the todo ever reached Taskwarrior
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    uid: Option<String>,
+    #[serde(default)]
+    attributes: Map<String, Value>,
+}
+
+// Keyed by Taskwarrior UUID
+type BridgeState = HashMap<String, TaskState>;
+
+// A task from `task export` and its 1-based position there
+type ExportedTask = (usize, Map<String, Value>);
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct TaskwarriorImport {
+    pub added: usize,
+    pub updated: usize,
+    // `line` is the task's position in the export
+    pub skipped: Vec<SkippedTask>,
+}
+
+fn state_path(vault_path: &str) -> PathBuf {
+    Path::new(vault_path).join(".bouldy").join(STATE_FILE)
+}
+
+fn load_state(vault_path: &str) -> BridgeState {
+    fs::read_to_string(state_path(vault_path))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(vault_path: &str, state: &BridgeState) -> Result<(), BouldyError> {
+    let content = serde_json::to_string_pretty(state)
+        .map_err(|e| format!("Failed to serialize Taskwarrior state: {}", e))?;
+    let _lock = vault_lock::acquire(vault_path)?;
+    fsutil::write_atomic(&state_path(vault_path), content)
+        .map_err(|e| BouldyError::io("Failed to write Taskwarrior state", e))
+}
+
+fn parse_timestamp(value: &str) -> Option<NaiveDateTime> {
+    let utc = NaiveDateTime::parse_from_str(value, TIMESTAMP_FORMAT).ok()?;
+    Some(
+        Utc.from_utc_datetime(&utc)
+            .with_timezone(&Local)
+            .naive_local(),
+    )
+}
+
+fn format_timestamp(local: NaiveDateTime) -> String {
+    let at = Local
+        .from_local_datetime(&local)
+        .earliest()
+        .map(|at| at.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&local));
+    at.format(TIMESTAMP_FORMAT).to_string()
+}
+
+// Taskwarrior has H/M/L; todo.txt priorities below (C) export as L
+fn priority_from_taskwarrior(priority: &str) -> Option<String> {
+    match priority {
+        "H" => Some("A".to_string()),
+        "M" => Some("B".to_string()),
+        "L" => Some("C".to_string()),
+        _ => None,
+    }
+}
+
+fn priority_to_taskwarrior(priority: &str) -> &'static str {
+    match priority {
+        "A" => "H",
+        "B" => "M",
+        _ => "L",
+    }
+}
+
+// todo.txt tags end at whitespace
+fn tag_word(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join("-")
+}
+
+/// `task export` prints a JSON array; older versions print one object per
+/// line.
+fn parse_export(export_json: &str) -> Result<Vec<ExportedTask>, BouldyError> {
+    if let Ok(tasks) = serde_json::from_str::<Vec<Map<String, Value>>>(export_json) {
+        return Ok(tasks
+            .into_iter()
+            .enumerate()
+            .map(|(i, t)| (i + 1, t))
+            .collect());
+    }
+
+    let mut tasks = Vec::new();
+    for (index, line) in export_json.lines().enumerate() {
+        let line = line.trim().trim_end_matches(',');
+        if line.is_empty() || line == "[" || line == "]" {
+            continue;
+        }
+        let task = serde_json::from_str(line)
+            .map_err(|e| BouldyError::parse("Invalid Taskwarrior export", e))?;
+        tasks.push((index + 1, task));
+    }
+    Ok(tasks)
+}
+
+fn todo_from_task(task: &Map<String, Value>) -> Result<TodoItem, &'static str> {
+    let text = |key: &str| task.get(key).and_then(Value::as_str);
+
+    let title = text("description")
+        .map(|description| description.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|title| !title.is_empty())
+        .ok_or("No description")?;
+
+    let completed = match text("status").unwrap_or("pending") {
+        "pending" | "waiting" => false,
+        "completed" => true,
+        "deleted" => return Err("Deleted in Taskwarrior"),
+        // The template; its pending instances are imported instead
+        "recurring" => return Err("Recurring template"),
+        _ => return Err("Unknown status"),
+    };
+
+    // Dates entered without a time sit at local midnight
+    let due_date = match text("due") {
+        Some(due) => {
+            let at = parse_timestamp(due).ok_or("Invalid due date")?;
+            if at.time() == chrono::NaiveTime::MIN {
+                Some(at.format("%Y-%m-%d").to_string())
+            } else {
+                Some(at.format("%Y-%m-%dT%H:%M").to_string())
+            }
+        }
+        None => None,
+    };
+
+    let contexts = task
+        .get("tags")
+        .and_then(Value::as_array)
+        .map(|tags| {
+            tags.iter()
+                .filter_map(Value::as_str)
+                .map(tag_word)
+                .filter(|tag| !tag.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(TodoItem {
+        id: 0,
+        title,
+        completed,
+        due_date,
+        priority: text("priority").and_then(priority_from_taskwarrior),
+        projects: text("project").map(tag_word).into_iter().collect(),
+        contexts,
+        created_date: text("entry")
+            .and_then(parse_timestamp)
+            .map(|at| at.format("%Y-%m-%d").to_string()),
+        uid: None,
+        parent: None,
+        delegated: None,
+        estimate: None,
+        description: None,
+    })
+}
+
+/// Merge a `task export` into todo.txt. Tasks are matched by UUID, so
+/// importing again updates the todos from the previous import instead of
+/// duplicating them. The project becomes a `+project`, tags `@contexts`,
+/// H/M/L priorities (A)/(B)/(C); annotations and other attributes are kept
+/// aside for `export_taskwarrior`.
+pub fn import_taskwarrior(
+    store: &TodoStore,
+    vault_path: &str,
+    export_json: &str,
+) -> Result<TaskwarriorImport, BouldyError> {
+    let tasks = parse_export(export_json)?;
+    let mut state = load_state(vault_path);
+
+    let import = store.mutate_if_changed(vault_path, |existing| {
+        let mut import = TaskwarriorImport::default();
+
+        for (line, task) in tasks {
+            let uuid = task
+                .get("uuid")
+                .and_then(Value::as_str)
+                .map(|uuid| uuid.to_lowercase());
+            let parsed = uuid
+                .ok_or("No UUID")
+                .and_then(|uuid| Ok((uuid, todo_from_task(&task)?)));
+            let (uuid, mut todo) = match parsed {
+                Ok(parsed) => parsed,
+                Err(reason) => {
+                    import.skipped.push(SkippedTask {
+                        line,
+                        text: task
+                            .get("description")
+                            .and_then(Value::as_str)
+                            .unwrap_or_default()
+                            .to_string(),
+                        reason: reason.to_string(),
+                    });
+                    continue;
+                }
+            };
+
+            let entry = state.entry(uuid.clone()).or_default();
+            entry.attributes = task
+                .into_iter()
+                .filter(|(key, _)| !OWNED_ATTRIBUTES.contains(&key.as_str()))
+                .collect();
+            let uid = entry.uid.clone().unwrap_or(uuid);
+
+            match existing
+                .iter_mut()
+                .find(|t| t.uid.as_deref() == Some(uid.as_str()))
+            {
+                Some(current) => {
+                    todo.id = current.id;
+                    todo.uid = current.uid.clone();
+                    if *current != todo {
+                        *current = todo;
+                        import.updated += 1;
+                    }
+                }
+                None => {
+                    todo.uid = Some(uid);
+                    todos::push(existing, todo);
+                    import.added += 1;
+                }
+            }
+        }
+
+        let changed = import.added + import.updated > 0;
+        Ok((import, changed))
+    })?;
+
+    save_state(vault_path, &state)?;
+    Ok(import)
+}
+
+fn task_from_todo(uuid: &str, todo: &TodoItem, state: Option<&TaskState>) -> Map<String, Value> {
+    let now = todos::local_now();
+    let mut task = state
+        .map(|state| state.attributes.clone())
+        .unwrap_or_default();
+
+    task.insert("uuid".into(), uuid.into());
+    task.insert("description".into(), todo.title.clone().into());
+    task.insert(
+        "status".into(),
+        if todo.completed {
+            "completed"
+        } else {
+            "pending"
+        }
+        .into(),
+    );
+    if todo.completed {
+        task.entry("end")
+            .or_insert_with(|| format_timestamp(now).into());
+    } else {
+        task.remove("end");
+    }
+
+    let entry = todo
+        .created_date
+        .as_deref()
+        .and_then(|date| chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+        .map(|date| date.and_time(chrono::NaiveTime::MIN))
+        .unwrap_or(now);
+    task.insert("entry".into(), format_timestamp(entry).into());
+    task.insert("modified".into(), format_timestamp(now).into());
+
+    // Taskwarrior has one project per task; any further ones become tags
+    let mut projects = todo.projects.iter();
+    if let Some(project) = projects.next() {
+        task.insert("project".into(), project.clone().into());
+    }
+    let tags: Vec<Value> = todo
+        .contexts
+        .iter()
+        .chain(projects)
+        .map(|tag| tag.clone().into())
+        .collect();
+    if !tags.is_empty() {
+        task.insert("tags".into(), tags.into());
+    }
+
+    if let Some(priority) = &todo.priority {
+        task.insert("priority".into(), priority_to_taskwarrior(priority).into());
+    }
+    if let Some(due) = todo.due() {
+        let at = match due {
+            Due::Date(date) => date.and_time(chrono::NaiveTime::MIN),
+            Due::DateTime(at) => at,
+        };
+        task.insert("due".into(), format_timestamp(at).into());
+    }
+
+    task
+}
+
+/// Render todo.txt as a JSON array for `task import`. Todos without a
+/// `uid:` are given a UUID first, so later exports update the same tasks.
+pub fn export_taskwarrior(store: &TodoStore, vault_path: &str) -> Result<String, BouldyError> {
+    let mut state = load_state(vault_path);
+    let before = state.len();
+
+    let tasks = store.mutate_if_changed(vault_path, |todos| {
+        let mut changed = false;
+        let mut tasks = Vec::new();
+
+        for todo in todos.iter_mut() {
+            let uid = match &todo.uid {
+                Some(uid) => uid.clone(),
+                None => {
+                    let uid = uuid::Uuid::new_v4().to_string();
+                    todo.uid = Some(uid.clone());
+                    changed = true;
+                    uid
+                }
+            };
+
+            let uuid = if uuid::Uuid::parse_str(&uid).is_ok() {
+                uid.to_lowercase()
+            } else {
+                // Short uids from synced notes keep their own UUID
+                match state
+                    .iter()
+                    .find(|(_, task)| task.uid.as_deref() == Some(uid.as_str()))
+                {
+                    Some((uuid, _)) => uuid.clone(),
+                    None => {
+                        let uuid = uuid::Uuid::new_v4().to_string();
+                        state.insert(
+                            uuid.clone(),
+                            TaskState {
+                                uid: Some(uid),
+                                ..Default::default()
+                            },
+                        );
+                        uuid
+                    }
+                }
+            };
+
+            tasks.push(Value::Object(task_from_todo(&uuid, todo, state.get(&uuid))));
+        }
+
+        Ok((tasks, changed))
+    })?;
+
+    if state.len() != before {
+        save_state(vault_path, &state)?;
+    }
+
+    serde_json::to_string_pretty(&tasks)
+        .map_err(|e| format!("Failed to serialize Taskwarrior export: {}", e).into())
+}
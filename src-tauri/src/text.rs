@@ -0,0 +1,16 @@
+use caseless::Caseless;
+use unicode_normalization::UnicodeNormalization;
+
+/// Canonical composed form. macOS hands back file names decomposed
+/// ("Cafe\u{301}"), so titles from disk are composed before display or
+/// comparison with what the user typed.
+pub fn nfc(s: &str) -> String {
+    s.nfc().collect()
+}
+
+/// Key for caseless matching: "Café", "café" and "CAFE\u{301}" all fold to
+/// the same string. Only for comparisons; offsets into the result don't map
+/// back to the original.
+pub fn fold(s: &str) -> String {
+    s.chars().nfd().default_case_fold().nfd().collect()
+}
@@ -0,0 +1,410 @@
+use chrono::{Duration, NaiveDate, NaiveDateTime, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::error::BouldyError;
+use crate::todo_store::TodoStore;
+use crate::todos::{self, TodoItem};
+use crate::{locale, pomodoro, vault_lock};
+
+// Timeclock format, so hledger/ledger can turn it into invoices as is:
+//
+//   i 2025-01-06 09:00:00 clientx  Fix login bug uid:k3j9x2
+//   o 2025-01-06 10:30:00
+//
+// The account (first +project of a todo, or the label) and the description
+// are separated by two spaces.
+pub const TIMESHEET_FILE: &str = "timesheet.txt";
+
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+// Completed work pomodoros nobody clocked in for
+const POMODORO_GROUP: &str = "(pomodoro)";
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ActiveTimer {
+    pub account: String,
+    pub description: String,
+    #[serde(rename = "todoUid")]
+    pub todo_uid: Option<String>,
+    pub started: String,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub enum GroupBy {
+    #[serde(rename = "day")]
+    Day,
+    #[serde(rename = "week")]
+    Week,
+    #[serde(rename = "project")]
+    Project,
+    #[serde(rename = "task")]
+    Task,
+}
+
+/// Inclusive range of dates, `YYYY-MM-DD`.
+#[derive(Deserialize, Clone, Debug)]
+pub struct TimeRange {
+    pub from: String,
+    pub to: String,
+}
+
+impl TimeRange {
+    pub fn dates(&self) -> Result<(NaiveDate, NaiveDate), BouldyError> {
+        let parse_date = |value: &str| {
+            NaiveDate::parse_from_str(value.trim(), DATE_FORMAT).map_err(|_| {
+                BouldyError::invalid_input(format!("Invalid date '{}'. Expected YYYY-MM-DD", value))
+            })
+        };
+        let from = parse_date(&self.from)?;
+        let to = parse_date(&self.to)?;
+        if to < from {
+            return Err(BouldyError::invalid_input(
+                "Report range ends before it starts",
+            ));
+        }
+        Ok((from, to))
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TimeGroup {
+    pub key: String,
+    pub seconds: i64,
+    // Completed work pomodoros within this group's tracked time
+    pub pomodoros: usize,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TimeReport {
+    pub from: String,
+    pub to: String,
+    #[serde(rename = "totalSeconds")]
+    pub total_seconds: i64,
+    pub groups: Vec<TimeGroup>,
+}
+
+struct Entry {
+    account: String,
+    description: String,
+    start: NaiveDateTime,
+    // None while the clock is running
+    end: Option<NaiveDateTime>,
+}
+
+fn timesheet_path(vault_path: &str) -> PathBuf {
+    Path::new(vault_path).join(TIMESHEET_FILE)
+}
+
+fn parse_time(value: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value, TIME_FORMAT).ok()
+}
+
+// `<date> <time> rest`
+fn split_stamp(rest: &str) -> Option<(NaiveDateTime, &str)> {
+    let mut parts = rest.trim_start().splitn(3, ' ');
+    let date = parts.next()?;
+    let time = parts.next()?;
+    let at = parse_time(&format!("{} {}", date, time))?;
+    Some((at, parts.next().unwrap_or_default().trim()))
+}
+
+fn parse_entries(content: &str) -> Vec<Entry> {
+    let mut entries: Vec<Entry> = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let Some((kind, rest)) = line.trim_end().split_once(' ') else {
+            continue;
+        };
+        let Some((at, rest)) = split_stamp(rest) else {
+            if matches!(kind, "i" | "o") {
+                tracing::warn!("Skipping timesheet line {}", index + 1);
+            }
+            continue;
+        };
+
+        match kind {
+            "i" => {
+                // A clock-in without a clock-out ends where the next begins
+                if let Some(open) = entries.last_mut().filter(|entry| entry.end.is_none()) {
+                    open.end = Some(at);
+                }
+                let (account, description) = rest.split_once("  ").unwrap_or((rest, ""));
+                entries.push(Entry {
+                    account: account.trim().to_string(),
+                    description: description.trim().to_string(),
+                    start: at,
+                    end: None,
+                });
+            }
+            "o" => {
+                if let Some(open) = entries.last_mut().filter(|entry| entry.end.is_none()) {
+                    open.end = Some(at.max(open.start));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    entries
+}
+
+fn load_entries(vault_path: &str) -> Result<Vec<Entry>, BouldyError> {
+    match fs::read_to_string(timesheet_path(vault_path)) {
+        Ok(content) => Ok(parse_entries(&content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(BouldyError::io("Failed to read timesheet", e)),
+    }
+}
+
+fn append_line(vault_path: &str, line: &str) -> Result<(), BouldyError> {
+    let path = timesheet_path(vault_path);
+    // Keep the previous line intact if it wasn't newline-terminated
+    let needs_newline = fs::read(&path)
+        .map(|content| !content.is_empty() && !content.ends_with(b"\n"))
+        .unwrap_or(false);
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| BouldyError::io("Failed to open timesheet", e))?;
+    let prefix = if needs_newline { "\n" } else { "" };
+    writeln!(file, "{}{}", prefix, line)
+        .map_err(|e| BouldyError::io("Failed to write timesheet", e))
+}
+
+fn active(entries: &[Entry]) -> Option<ActiveTimer> {
+    let entry = entries.last().filter(|entry| entry.end.is_none())?;
+    let (description, todo_uid) = split_uid(&entry.description);
+    Some(ActiveTimer {
+        account: entry.account.clone(),
+        description,
+        todo_uid,
+        started: entry.start.format(TIME_FORMAT).to_string(),
+    })
+}
+
+// The description minus its `uid:` token, and the uid
+fn split_uid(description: &str) -> (String, Option<String>) {
+    let mut uid = None;
+    let words: Vec<&str> = description
+        .split_whitespace()
+        .filter(|word| match word.strip_prefix("uid:") {
+            Some(value) => {
+                uid = Some(value.to_string());
+                false
+            }
+            None => true,
+        })
+        .collect();
+    (words.join(" "), uid)
+}
+
+pub fn tracking_status(vault_path: &str) -> Result<Option<ActiveTimer>, BouldyError> {
+    Ok(active(&load_entries(vault_path)?))
+}
+
+// Give the todo a uid so the timesheet survives it being renamed or moved
+fn todo_for_tracking(
+    store: &TodoStore,
+    vault_path: &str,
+    id: usize,
+) -> Result<Option<TodoItem>, BouldyError> {
+    store.mutate_if_changed(vault_path, |todos| {
+        let Some(todo) = todos::find_todo_mut(todos, id) else {
+            return Ok((None, false));
+        };
+        let changed = todo.uid.is_none();
+        if changed {
+            todo.uid = Some(todos::new_uid());
+        }
+        Ok((Some(todo.clone()), changed))
+    })
+}
+
+/// Clock in on a todo (by id) or a free-form label, clocking out of
+/// whatever was running first.
+pub fn start_tracking(
+    store: &TodoStore,
+    vault_path: &str,
+    label_or_todo_id: &str,
+) -> Result<ActiveTimer, BouldyError> {
+    let target = label_or_todo_id
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if target.is_empty() {
+        return Err(BouldyError::invalid_input("Nothing to track"));
+    }
+
+    let todo = match target.parse::<usize>() {
+        Ok(id) => Some(
+            todo_for_tracking(store, vault_path, id)?
+                .ok_or_else(|| BouldyError::not_found(format!("Todo {} not found", id)))?,
+        ),
+        Err(_) => None,
+    };
+    let line_target = match todo {
+        Some(ref todo) => {
+            let account = todo.projects.first().map(String::as_str).unwrap_or("todo");
+            let uid = todo.uid.as_deref().unwrap_or_default();
+            format!("{}  {} uid:{}", account, todo.title, uid)
+        }
+        None => target,
+    };
+
+    let now = todos::local_now();
+    let _lock = vault_lock::acquire(vault_path)?;
+    if active(&load_entries(vault_path)?).is_some() {
+        append_line(vault_path, &format!("o {}", now.format(TIME_FORMAT)))?;
+    }
+    append_line(
+        vault_path,
+        &format!("i {} {}", now.format(TIME_FORMAT), line_target),
+    )?;
+
+    active(&load_entries(vault_path)?).ok_or_else(|| "Failed to start tracking".into())
+}
+
+/// Clock out, returning what was being tracked.
+pub fn stop_tracking(vault_path: &str) -> Result<ActiveTimer, BouldyError> {
+    let _lock = vault_lock::acquire(vault_path)?;
+    let timer = active(&load_entries(vault_path)?)
+        .ok_or_else(|| BouldyError::not_found("Not tracking anything"))?;
+    append_line(
+        vault_path,
+        &format!("o {}", todos::local_now().format(TIME_FORMAT)),
+    )?;
+    Ok(timer)
+}
+
+// Finished work sessions from the pomodoro timer, in local time
+pub fn load_pomodoros(vault_path: &str) -> Vec<(NaiveDateTime, NaiveDateTime)> {
+    pomodoro::load_work_sessions(vault_path)
+        .into_iter()
+        .map(|session| (session.start, session.end))
+        .collect()
+}
+
+// Day and week groups are keyed by their first day
+fn date_key(group_by: GroupBy, day: NaiveDate, week_start: Weekday) -> Option<String> {
+    let start = match group_by {
+        GroupBy::Day => day,
+        GroupBy::Week => locale::start_of_week(day, week_start),
+        GroupBy::Project | GroupBy::Task => return None,
+    };
+    Some(start.format(DATE_FORMAT).to_string())
+}
+
+fn group_key(group_by: GroupBy, entry: &Entry, day: NaiveDate, week_start: Weekday) -> String {
+    if let Some(key) = date_key(group_by, day, week_start) {
+        return key;
+    }
+    match group_by {
+        GroupBy::Project => entry.account.clone(),
+        _ => {
+            let (description, _) = split_uid(&entry.description);
+            if description.is_empty() {
+                entry.account.clone()
+            } else {
+                description
+            }
+        }
+    }
+}
+
+/// Tracked time between two dates (inclusive), grouped by day, week,
+/// project or task. A running clock counts up to now. Completed work
+/// pomodoros are counted against the entries they fall in; those outside
+/// any entry are reported as their own group so focus time isn't lost.
+pub fn time_report(
+    vault_path: &str,
+    range: &TimeRange,
+    group_by: GroupBy,
+    week_start: Weekday,
+) -> Result<TimeReport, BouldyError> {
+    let (from, to) = range.dates()?;
+    let range_start = from.and_time(chrono::NaiveTime::MIN);
+    let range_end = (to + Duration::days(1)).and_time(chrono::NaiveTime::MIN);
+
+    let now = todos::local_now();
+    let entries = load_entries(vault_path)?;
+    let pomodoros = load_pomodoros(vault_path);
+
+    let mut groups: HashMap<String, TimeGroup> = HashMap::new();
+    let mut add = |key: String, seconds: i64, pomodoros: usize| {
+        let group = groups.entry(key.clone()).or_insert(TimeGroup {
+            key,
+            seconds: 0,
+            pomodoros: 0,
+        });
+        group.seconds += seconds;
+        group.pomodoros += pomodoros;
+    };
+
+    let overlaps = |start: NaiveDateTime,
+                    end: NaiveDateTime,
+                    (p_start, p_end): &(NaiveDateTime, NaiveDateTime)| {
+        *p_start < end && *p_end > start
+    };
+
+    for entry in &entries {
+        let start = entry.start.max(range_start);
+        let end = entry.end.unwrap_or(now).min(range_end);
+        if start >= end {
+            continue;
+        }
+
+        // Split at midnight so day and week groups add up
+        let mut day_start = start;
+        while day_start < end {
+            let next_midnight =
+                (day_start.date() + Duration::days(1)).and_time(chrono::NaiveTime::MIN);
+            let day_end = end.min(next_midnight);
+            // Each pomodoro is credited to the slice it started in
+            let finished = pomodoros
+                .iter()
+                .filter(|p| p.0 >= day_start && p.0 < day_end)
+                .count();
+            add(
+                group_key(group_by, entry, day_start.date(), week_start),
+                (day_end - day_start).num_seconds(),
+                finished,
+            );
+            day_start = day_end;
+        }
+    }
+
+    for pomodoro in &pomodoros {
+        let (p_start, p_end) = *pomodoro;
+        let untracked = !entries
+            .iter()
+            .any(|entry| overlaps(entry.start, entry.end.unwrap_or(now), pomodoro));
+        if !untracked || p_start < range_start || p_start >= range_end || p_end <= p_start {
+            continue;
+        }
+        let key = date_key(group_by, p_start.date(), week_start)
+            .unwrap_or_else(|| POMODORO_GROUP.to_string());
+        add(key, (p_end - p_start).num_seconds(), 1);
+    }
+
+    let mut groups: Vec<TimeGroup> = groups.into_values().collect();
+    match group_by {
+        GroupBy::Day | GroupBy::Week => groups.sort_by(|a, b| a.key.cmp(&b.key)),
+        GroupBy::Project | GroupBy::Task => {
+            groups.sort_by(|a, b| b.seconds.cmp(&a.seconds).then_with(|| a.key.cmp(&b.key)))
+        }
+    }
+
+    Ok(TimeReport {
+        from: from.format(DATE_FORMAT).to_string(),
+        to: to.format(DATE_FORMAT).to_string(),
+        total_seconds: groups.iter().map(|group| group.seconds).sum(),
+        groups,
+    })
+}
@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::BouldyError;
+use crate::todos::{self, TodoItem};
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub enum ExportFormat {
+    #[serde(rename = "csv")]
+    Csv,
+    #[serde(rename = "json")]
+    Json,
+}
+
+/// Which todos to export. Unset fields match everything; lists match a todo
+/// carrying any of the given projects or contexts.
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct TodoFilter {
+    pub completed: Option<bool>,
+    #[serde(default)]
+    pub projects: Vec<String>,
+    #[serde(default)]
+    pub contexts: Vec<String>,
+    pub priority: Option<String>,
+    // Inclusive, YYYY-MM-DD
+    #[serde(rename = "dueBefore")]
+    pub due_before: Option<String>,
+}
+
+impl TodoFilter {
+    fn matches(&self, todo: &TodoItem) -> bool {
+        let due_before = self
+            .due_before
+            .as_deref()
+            .and_then(|date| chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok());
+
+        self.completed
+            .is_none_or(|completed| todo.completed == completed)
+            && (self.projects.is_empty() || todo.projects.iter().any(|p| self.projects.contains(p)))
+            && (self.contexts.is_empty() || todo.contexts.iter().any(|c| self.contexts.contains(c)))
+            && (self.priority.is_none() || todo.priority == self.priority)
+            && due_before.is_none_or(|limit| todo.due().is_some_and(|due| due.date() <= limit))
+    }
+}
+
+#[derive(Serialize)]
+struct JsonExport<'a> {
+    #[serde(rename = "exportedAt")]
+    exported_at: String,
+    todos: Vec<&'a TodoItem>,
+}
+
+const CSV_COLUMNS: &[&str] = &[
+    "id",
+    "completed",
+    "priority",
+    "title",
+    "projects",
+    "contexts",
+    "created",
+    "due",
+    "overdue",
+    "uid",
+    "parent",
+    "delegated",
+    "estimate",
+    "description",
+];
+
+// RFC 4180: quote fields containing separators, quotes or line breaks
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn render_csv(todos: &[&TodoItem]) -> String {
+    let now = todos::local_now();
+    let mut output = CSV_COLUMNS.join(",");
+    output.push_str("\r\n");
+
+    for todo in todos {
+        let row = [
+            todo.id.to_string(),
+            todo.completed.to_string(),
+            todo.priority.clone().unwrap_or_default(),
+            todo.title.clone(),
+            // Several values in one cell, as most task managers import them
+            todo.projects.join(";"),
+            todo.contexts.join(";"),
+            todo.created_date.clone().unwrap_or_default(),
+            todo.due_date.clone().unwrap_or_default(),
+            todos::is_overdue(todo, now).to_string(),
+            todo.uid.clone().unwrap_or_default(),
+            // uid of the todo this is a subtask of
+            todo.parent.clone().unwrap_or_default(),
+            todo.delegated.clone().unwrap_or_default(),
+            todo.estimate.map(|e| e.to_string()).unwrap_or_default(),
+            // Multi-line, kept in one quoted cell
+            todo.description.clone().unwrap_or_default(),
+        ];
+        let fields: Vec<String> = row.iter().map(|field| csv_field(field)).collect();
+        output.push_str(&fields.join(","));
+        output.push_str("\r\n");
+    }
+
+    output
+}
+
+/// Render the todos matching `filter` as CSV (one row per todo, with a
+/// header) or JSON (`{ exportedAt, todos }` using the app's todo shape).
+/// Both carry every field a todo has, subtask parents and descriptions
+/// included.
+pub fn export_todos(
+    todos: &[TodoItem],
+    format: ExportFormat,
+    filter: &TodoFilter,
+) -> Result<String, BouldyError> {
+    let selected: Vec<&TodoItem> = todos.iter().filter(|todo| filter.matches(todo)).collect();
+
+    match format {
+        ExportFormat::Csv => Ok(render_csv(&selected)),
+        ExportFormat::Json => serde_json::to_string_pretty(&JsonExport {
+            exported_at: chrono::Local::now().to_rfc3339(),
+            todos: selected,
+        })
+        .map_err(|e| format!("Failed to serialize todos: {}", e).into()),
+    }
+}
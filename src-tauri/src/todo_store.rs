@@ -0,0 +1,311 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+use crate::error::BouldyError;
+use crate::todos::{self, TodoItem};
+use crate::vault_lock;
+
+// Quiet period after the last change before todo.txt is rewritten, so a
+// burst of toggles becomes a single write
+const FLUSH_DELAY: Duration = Duration::from_millis(300);
+
+// Beside todo.txt, where our versions of todos that were also edited
+// outside the app are kept for the user to sort out
+pub const CONFLICTS_FILE: &str = "todo.conflicts.txt";
+
+/// Unsaved changes that clashed with an edit made to todo.txt outside the
+/// app. The file's version was kept; ours were appended to `path`.
+#[derive(Serialize, Clone, Debug)]
+pub struct TodoConflict {
+    pub todos: Vec<TodoItem>,
+    pub path: String,
+}
+
+struct CachedTodos {
+    vault_path: String,
+    todos: Vec<TodoItem>,
+    // todo.txt as last read or written, to tell our changes from external ones
+    base: Vec<TodoItem>,
+    // Time of the most recent change not yet written to disk
+    dirty_since: Option<Instant>,
+}
+
+impl CachedTodos {
+    fn load(vault_path: &str) -> Result<Self, BouldyError> {
+        let mut todos = todos::load_todos(vault_path)?;
+        todos::renumber(&mut todos);
+        Ok(Self {
+            vault_path: vault_path.to_string(),
+            base: todos.clone(),
+            todos,
+            dirty_since: None,
+        })
+    }
+
+    // Caller holds the vault lock
+    fn save(&mut self) -> Result<(), BouldyError> {
+        if self.dirty_since.is_some() {
+            todos::save_todos(&self.vault_path, &self.todos)?;
+            self.base = self.todos.clone();
+            self.dirty_since = None;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), BouldyError> {
+        if self.dirty_since.is_none() {
+            return Ok(());
+        }
+        let _lock = vault_lock::acquire(&self.vault_path)?;
+        self.save()
+    }
+}
+
+/// Managed state holding the parsed todo.txt. Commands mutate it in memory
+/// and a background thread writes it out once changes settle.
+#[derive(Default)]
+pub struct TodoStore {
+    cached: Mutex<Option<CachedTodos>>,
+    changed: Condvar,
+}
+
+impl TodoStore {
+    // `f` returns its result and whether it changed anything
+    fn access<T>(
+        &self,
+        vault_path: &str,
+        f: impl FnOnce(&mut Vec<TodoItem>) -> Result<(T, bool), BouldyError>,
+    ) -> Result<T, BouldyError> {
+        let mut guard = self.cached.lock().unwrap();
+
+        if guard.as_ref().map(|c| c.vault_path.as_str()) != Some(vault_path) {
+            // Switching vaults - don't lose pending changes to the old one
+            if let Some(ref mut previous) = *guard {
+                previous.flush()?;
+            }
+            *guard = Some(CachedTodos::load(vault_path)?);
+        }
+
+        let cached = guard.as_mut().unwrap();
+        let (result, changed) = f(&mut cached.todos)?;
+
+        if changed {
+            // Ids are positions in the list, so removals and insertions
+            // move the todos after them along
+            todos::renumber(&mut cached.todos);
+            cached.dirty_since = Some(Instant::now());
+            self.changed.notify_one();
+        }
+
+        Ok(result)
+    }
+
+    pub fn load(&self, vault_path: &str) -> Result<Vec<TodoItem>, BouldyError> {
+        self.access(vault_path, |todos| Ok((todos.clone(), false)))
+    }
+
+    /// Apply `f` to the in-memory list and schedule a write. New todos go
+    /// in with `todos::push`; ids are reassigned afterwards.
+    pub fn mutate<T>(
+        &self,
+        vault_path: &str,
+        f: impl FnOnce(&mut Vec<TodoItem>) -> Result<T, BouldyError>,
+    ) -> Result<T, BouldyError> {
+        self.access(vault_path, |todos| f(todos).map(|result| (result, true)))
+    }
+
+    /// Like `mutate`, but only schedules a write when `f` reports a change.
+    pub fn mutate_if_changed<T>(
+        &self,
+        vault_path: &str,
+        f: impl FnOnce(&mut Vec<TodoItem>) -> Result<(T, bool), BouldyError>,
+    ) -> Result<T, BouldyError> {
+        self.access(vault_path, f)
+    }
+
+    /// For operations that edit todo.txt directly: pending changes are
+    /// written first and the file is re-read on next access. The vault lock
+    /// is held throughout so another process can't write in between.
+    pub fn with_file<T>(
+        &self,
+        vault_path: &str,
+        f: impl FnOnce() -> Result<T, BouldyError>,
+    ) -> Result<T, BouldyError> {
+        let mut guard = self.cached.lock().unwrap();
+        let _lock = vault_lock::acquire(vault_path)?;
+
+        if let Some(ref mut cached) = *guard {
+            cached.save()?;
+        }
+
+        let result = f();
+        *guard = None;
+        result
+    }
+
+    /// Called when todo.txt changes on disk. Without unsaved changes the
+    /// file is re-read on next access. Otherwise the external edit is merged
+    /// with them; where both changed the same todo the file's version is
+    /// kept and ours is set aside in `CONFLICTS_FILE`.
+    pub fn reload(&self) -> Result<Option<TodoConflict>, BouldyError> {
+        let mut guard = self.cached.lock().unwrap();
+        let Some(ref mut cached) = *guard else {
+            return Ok(None);
+        };
+        if cached.dirty_since.is_none() {
+            *guard = None;
+            return Ok(None);
+        }
+
+        let theirs = todos::load_todos(&cached.vault_path)?;
+        if same_todos(&theirs, &cached.base) {
+            return Ok(None);
+        }
+        let (mut merged, conflicts) = merge(&cached.base, &cached.todos, &theirs);
+        todos::renumber(&mut merged);
+        cached.todos = merged;
+        cached.base = theirs;
+        // Still dirty, so the merge is written out
+        cached.dirty_since = Some(Instant::now());
+        self.changed.notify_one();
+
+        if conflicts.is_empty() {
+            return Ok(None);
+        }
+        let path = save_conflicts(&cached.vault_path, &conflicts)?;
+        Ok(Some(TodoConflict {
+            todos: conflicts,
+            path,
+        }))
+    }
+
+    pub fn flush(&self) -> Result<(), BouldyError> {
+        match *self.cached.lock().unwrap() {
+            Some(ref mut cached) => cached.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+fn same_todos(a: &[TodoItem], b: &[TodoItem]) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .zip(b)
+            .all(|(a, b)| todos::fingerprint(a) == todos::fingerprint(b))
+}
+
+// Todos in `from` with no match in `other`, compared by how they're
+// written, with their positions
+fn missing_from<'a>(from: &'a [TodoItem], other: &[TodoItem]) -> Vec<(usize, &'a TodoItem)> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for todo in other {
+        *counts.entry(todos::fingerprint(todo)).or_default() += 1;
+    }
+    from.iter()
+        .enumerate()
+        .filter(
+            |(_, todo)| match counts.get_mut(&todos::fingerprint(todo)) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    false
+                }
+                _ => true,
+            },
+        )
+        .collect()
+}
+
+// Three-way merge: our removals and additions since `base` are replayed on
+// `theirs`. An edit is a removal plus an addition, paired by uid or else by
+// position. If they changed or removed a todo we edited, our version is
+// returned as a conflict instead of applied.
+fn merge(
+    base: &[TodoItem],
+    ours: &[TodoItem],
+    theirs: &[TodoItem],
+) -> (Vec<TodoItem>, Vec<TodoItem>) {
+    let removed = missing_from(base, ours);
+    let mut added = missing_from(ours, base);
+    let mut merged = theirs.to_vec();
+    let mut conflicts = Vec::new();
+
+    for (position, old) in removed {
+        let pair = added
+            .iter()
+            .position(|(_, new)| old.uid.is_some() && new.uid == old.uid)
+            .or_else(|| added.iter().position(|(index, _)| *index == position));
+        let replacement = pair.map(|pair| added.remove(pair).1);
+
+        let old_key = todos::fingerprint(old);
+        match merged.iter().position(|t| todos::fingerprint(t) == old_key) {
+            Some(index) => {
+                merged.remove(index);
+                if let Some(new) = replacement {
+                    merged.insert(index, new.clone());
+                }
+            }
+            // Changed or removed outside the app too: theirs stands
+            None => conflicts.extend(replacement.cloned()),
+        }
+    }
+
+    for (_, new) in added {
+        let key = todos::fingerprint(new);
+        if !merged.iter().any(|t| todos::fingerprint(t) == key) {
+            merged.push(new.clone());
+        }
+    }
+    (merged, conflicts)
+}
+
+fn save_conflicts(vault_path: &str, conflicts: &[TodoItem]) -> Result<String, BouldyError> {
+    let path = Path::new(vault_path).join(CONFLICTS_FILE);
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| BouldyError::io("Failed to open todo conflicts", e))?;
+    file.write_all(todos::serialize_todos(conflicts).as_bytes())
+        .map_err(|e| BouldyError::io("Failed to write todo conflicts", e))?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Write pending todo changes once they've been quiet for `FLUSH_DELAY`.
+pub fn start_flusher(app: AppHandle) {
+    thread::spawn(move || {
+        let store = app.state::<TodoStore>();
+        let mut guard = store.cached.lock().unwrap();
+
+        loop {
+            let dirty_since = guard.as_ref().and_then(|cached| cached.dirty_since);
+
+            guard = match dirty_since {
+                None => store.changed.wait(guard).unwrap(),
+                Some(since) if since.elapsed() >= FLUSH_DELAY => {
+                    if let Some(ref mut cached) = *guard {
+                        if let Err(e) = cached.flush() {
+                            tracing::warn!("{}", e);
+                            // Retry after another delay rather than spinning
+                            cached.dirty_since = Some(Instant::now());
+                        }
+                    }
+                    guard
+                }
+                Some(since) => {
+                    store
+                        .changed
+                        .wait_timeout(guard, FLUSH_DELAY.saturating_sub(since.elapsed()))
+                        .unwrap()
+                        .0
+                }
+            };
+        }
+    });
+}
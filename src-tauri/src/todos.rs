@@ -1,11 +1,28 @@
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::hash_map::RandomState;
 use std::collections::HashMap;
 use std::fs;
+use std::hash::{BuildHasher, Hasher};
 use std::path::Path;
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+use crate::error::BouldyError;
+use crate::fsutil;
+
+// A todo's description sits on the lines under it, indented and marked
+// with `|` so indented todos, which plain todo.txt allows, stay todos:
+//
+//   (A) Write report +work due:2024-05-01
+//     | Outline first, then the numbers.
+//     |
+//     | Ask Sam for the Q1 figures.
+const DESCRIPTION_PREFIX: &str = "  | ";
+const DESCRIPTION_MARKER: char = '|';
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct TodoItem {
-    pub id: usize, // Line number in the file (1-indexed)
+    pub id: usize, // Position in the list (1-indexed), see renumber
     pub title: String,
     pub completed: bool,
     #[serde(rename = "dueDate")]
@@ -15,6 +32,21 @@ pub struct TodoItem {
     pub contexts: Vec<String>,     // @ContextName tags
     #[serde(rename = "createdDate")]
     pub created_date: Option<String>,  // YYYY-MM-DD
+    // Stable `uid:` tag linking the todo to a checkbox in a synced note
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uid: Option<String>,
+    // `parent:` tag holding the uid of the todo this one is a subtask of
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    // `delegated:` tag naming who the todo is waiting on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delegated: Option<String>,
+    // `est:` tag, how many pomodoros the todo should take
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<u32>,
+    // Detail text, stored as marked lines under the todo
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
@@ -55,32 +87,66 @@ pub struct ArchivedTodo {
 }
 
 /// Parse todo.txt file into TodoItem array
-pub fn parse_todos(content: &str) -> Result<Vec<TodoItem>, String> {
+pub fn parse_todos(content: &str) -> Result<Vec<TodoItem>, BouldyError> {
     if content.trim().is_empty() {
         return Ok(Vec::new());
     }
 
     let mut todos: Vec<TodoItem> = Vec::new();
-    let mut line_num = 0;
+    // Whether description lines belong to the last todo
+    let mut in_description = false;
 
     for line in content.lines() {
-        line_num += 1;
+        // Indented, marked lines are the description of the todo above
+        let marked = line
+            .starts_with([' ', '\t'])
+            .then(|| line.trim_start().strip_prefix(DESCRIPTION_MARKER))
+            .flatten();
+        if let Some(text) = marked.filter(|_| in_description) {
+            if let Some(todo) = todos.last_mut() {
+                let text = text.strip_prefix(' ').unwrap_or(text);
+                let description = todo.description.get_or_insert_with(String::new);
+                if !description.is_empty() {
+                    description.push('\n');
+                }
+                description.push_str(text.trim_end());
+            }
+            continue;
+        }
 
         if line.trim().is_empty() {
+            in_description = false;
             continue;
         }
 
         // Parse each line as a todo item
-        if let Ok(todo) = parse_todo_line(line.trim(), line_num) {
+        in_description = false;
+        if let Ok(todo) = parse_todo_line(line.trim()) {
             todos.push(todo);
+            in_description = true;
         }
     }
 
+    // Blank description lines only separate paragraphs
+    for todo in &mut todos {
+        todo.description = todo.description.take().and_then(|d| clean_description(&d));
+    }
+    // Ids count todos, not lines, so descriptions don't shift them
+    renumber(&mut todos);
+
     Ok(todos)
 }
 
+/// Normalize description text for storage: no trailing spaces or blank
+/// lines at either end, and `None` when nothing is left.
+pub fn clean_description(text: &str) -> Option<String> {
+    let lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+    let description = lines.join("\n").trim_matches('\n').to_string();
+    Some(description).filter(|description| !description.trim().is_empty())
+}
+
 /// Parse a single todo line
-fn parse_todo_line(line: &str, line_num: usize) -> Result<TodoItem, String> {
+fn parse_todo_line(line: &str) -> Result<TodoItem, BouldyError> {
     let mut content = line.to_string();
 
     // 1. Check for completion marker
@@ -100,6 +166,10 @@ fn parse_todo_line(line: &str, line_num: usize) -> Result<TodoItem, String> {
 
     // 4. Extract metadata tags
     let due_date = extract_due_date(&content);
+    let uid = extract_uid(&content);
+    let parent = extract_parent(&content);
+    let delegated = extract_delegated(&content);
+    let estimate = extract_estimate(&content);
     let projects = extract_projects(&content);
     let contexts = extract_contexts(&content);
 
@@ -111,9 +181,25 @@ fn parse_todo_line(line: &str, line_num: usize) -> Result<TodoItem, String> {
         title = title.replace(&format!("due:{}", due), "");
     }
 
-    // Remove creation date
+    if let Some(ref uid) = uid {
+        title = title.replace(&format!("uid:{}", uid), "");
+    }
+
+    if let Some(ref parent) = parent {
+        title = title.replace(&format!("parent:{}", parent), "");
+    }
+
+    if let Some(ref delegated) = delegated {
+        title = title.replace(&format!("delegated:{}", delegated), "");
+    }
+
+    if let Some(estimate) = estimate {
+        title = title.replace(&format!("est:{}", estimate), "");
+    }
+
+    // Remove creation date, which leads the line
     if let Some(ref created) = created_date {
-        title = title.replace(created, "");
+        title = title.trim_start().replacen(created.as_str(), "", 1);
     }
 
     // Remove all project tags
@@ -130,7 +216,7 @@ fn parse_todo_line(line: &str, line_num: usize) -> Result<TodoItem, String> {
     title = title.split_whitespace().collect::<Vec<_>>().join(" ");
 
     Ok(TodoItem {
-        id: line_num,
+        id: 0,
         title,
         completed,
         due_date,
@@ -138,20 +224,54 @@ fn parse_todo_line(line: &str, line_num: usize) -> Result<TodoItem, String> {
         projects,
         contexts,
         created_date,
+        uid,
+        parent,
+        delegated,
+        estimate,
+        description: None,
     })
 }
 
 /// Extract due date from line (e.g., "due:2025-12-25")
 fn extract_due_date(content: &str) -> Option<String> {
+    content
+        .split_whitespace()
+        .find_map(|word| word.strip_prefix("due:").map(|due| due.to_string()))
+}
+
+/// Extract sync id from line (e.g., "uid:3fa9c2d1")
+fn extract_uid(content: &str) -> Option<String> {
+    content
+        .split_whitespace()
+        .find_map(|word| word.strip_prefix("uid:").map(|uid| uid.to_string()))
+}
+
+/// Extract parent todo's uid from line (e.g., "parent:3fa9c2d1")
+fn extract_parent(content: &str) -> Option<String> {
     content.split_whitespace().find_map(|word| {
-        if word.starts_with("due:") {
-            Some(word[4..].to_string())
-        } else {
-            None
-        }
+        word.strip_prefix("parent:")
+            .map(|parent| parent.to_string())
     })
 }
 
+/// Extract who a todo is delegated to (e.g., "delegated:Sam")
+fn extract_delegated(content: &str) -> Option<String> {
+    content
+        .split_whitespace()
+        .find_map(|word| word.strip_prefix("delegated:"))
+        .filter(|name| !name.is_empty())
+        .map(|name| name.to_string())
+}
+
+/// Extract pomodoro estimate from line (e.g., "est:3")
+fn extract_estimate(content: &str) -> Option<u32> {
+    content
+        .split_whitespace()
+        .find_map(|word| word.strip_prefix("est:"))
+        .and_then(|estimate| estimate.parse().ok())
+        .filter(|&estimate| estimate > 0)
+}
+
 /// Extract priority from start of line (e.g., "(A)")
 fn extract_priority(content: &str) -> Option<String> {
     let trimmed = content.trim();
@@ -196,15 +316,15 @@ fn extract_contexts(content: &str) -> Vec<String> {
         .collect()
 }
 
-/// Extract creation date (first date in line after priority)
+/// Extract creation date: the first word once completion and priority are
+/// removed, if it's a YYYY-MM-DD date. Dates further on, such as the one in
+/// `due:`, are not creation dates.
 fn extract_created_date(content: &str) -> Option<String> {
-    // After removing priority, first YYYY-MM-DD is creation date
-    use regex::Regex;
-    if let Ok(date_regex) = Regex::new(r"\d{4}-\d{2}-\d{2}") {
-        date_regex.find(content).map(|m| m.as_str().to_string())
-    } else {
-        None
-    }
+    let first = content.split_whitespace().next()?;
+    NaiveDate::parse_from_str(first, "%Y-%m-%d")
+        .ok()
+        .filter(|_| first.len() == 10)
+        .map(|_| first.to_string())
 }
 
 /// Serialize TodoItem array to todo.txt format
@@ -247,14 +367,67 @@ pub fn serialize_todos(todos: &[TodoItem]) -> String {
             parts.push(format!("due:{}", due));
         }
 
+        // 8. Sync id (extension)
+        if let Some(ref uid) = todo.uid {
+            parts.push(format!("uid:{}", uid));
+        }
+
+        // 9. Parent todo (extension)
+        if let Some(ref parent) = todo.parent {
+            parts.push(format!("parent:{}", parent));
+        }
+
+        // 10. Delegated to (extension)
+        if let Some(ref delegated) = todo.delegated {
+            parts.push(format!("delegated:{}", delegated));
+        }
+
+        // 11. Pomodoro estimate (extension)
+        if let Some(estimate) = todo.estimate {
+            parts.push(format!("est:{}", estimate));
+        }
+
         result.push_str(&parts.join(" "));
         result.push('\n');
+
+        // 12. Description, marked lines under the todo
+        if let Some(ref description) = todo.description {
+            for line in description.lines() {
+                let line = format!("{}{}", DESCRIPTION_PREFIX, line);
+                result.push_str(line.trim_end());
+                result.push('\n');
+            }
+        }
     }
 
     result
 }
 
-pub fn load_todos(vault_path: &str) -> Result<Vec<TodoItem>, String> {
+/// Give every todo its position in the list (1-indexed) as its id.
+pub fn renumber(todos: &mut [TodoItem]) {
+    for (index, todo) in todos.iter_mut().enumerate() {
+        todo.id = index + 1;
+    }
+}
+
+/// Append `todo` with the next id and return it as stored. The todo store
+/// keeps its list numbered by position, so that is where it lands.
+pub fn push(todos: &mut Vec<TodoItem>, mut todo: TodoItem) -> TodoItem {
+    todo.id = todos.len() + 1;
+    todos.push(todo.clone());
+    todo
+}
+
+/// Short hash of how the todo is written, for noticing when it changes.
+pub fn fingerprint(todo: &TodoItem) -> String {
+    let line = serialize_todos(std::slice::from_ref(todo));
+    Sha256::digest(line.as_bytes())[..8]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+pub fn load_todos(vault_path: &str) -> Result<Vec<TodoItem>, BouldyError> {
     let todo_path = Path::new(vault_path).join("todo.txt");
 
     if !todo_path.exists() {
@@ -262,29 +435,195 @@ pub fn load_todos(vault_path: &str) -> Result<Vec<TodoItem>, String> {
     }
 
     let content =
-        fs::read_to_string(&todo_path).map_err(|e| format!("Failed to read todos: {}", e))?;
+        fs::read_to_string(&todo_path).map_err(|e| BouldyError::io("Failed to read todos", e))?;
 
     parse_todos(&content)
 }
 
-pub fn save_todos(vault_path: &str, todos: &[TodoItem]) -> Result<(), String> {
+pub fn save_todos(vault_path: &str, todos: &[TodoItem]) -> Result<(), BouldyError> {
     let todo_path = Path::new(vault_path).join("todo.txt");
     let serialized = serialize_todos(todos);
 
-    fs::write(&todo_path, serialized).map_err(|e| format!("Failed to write todos: {}", e))?;
+    fsutil::write_text_preserving(&todo_path, &serialized)
+        .map_err(|e| BouldyError::io("Failed to write todos", e))?;
 
     Ok(())
 }
 
+/// Parse free text (so "+project @context due:..." work) into a new todo and
+/// append it to the end of todo.txt. Only the first non-empty line is used.
+pub fn append_todo(vault_path: &str, text: &str) -> Result<TodoItem, BouldyError> {
+    let line = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .ok_or_else(|| BouldyError::invalid_input("Nothing to add"))?;
+
+    let mut todos = load_todos(vault_path)?;
+
+    let mut new_todo = parse_todo_line(line)?;
+    new_todo.due_date = normalize_due_date(new_todo.due_date.take())?;
+    if new_todo.created_date.is_none() {
+        new_todo.created_date = Some(chrono::Local::now().format("%Y-%m-%d").to_string());
+    }
+
+    let new_todo = push(&mut todos, new_todo);
+    save_todos(vault_path, &todos)?;
+
+    Ok(new_todo)
+}
+
+// === Due Dates ===
+
+const DUE_DATE_FORMAT: &str = "%Y-%m-%d";
+const DUE_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M";
+
+/// A parsed `due:` value, either `YYYY-MM-DD` or `YYYY-MM-DDTHH:MM`. Both are
+/// local wall-clock values; a plain date is due by the end of that day.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Due {
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+}
+
+impl Due {
+    /// Also accepts seconds, and a UTC offset (`Z`, `+02:00`) from other
+    /// tools, which is converted to local time.
+    pub fn parse(value: &str) -> Option<Self> {
+        for format in [DUE_TIME_FORMAT, "%Y-%m-%dT%H:%M:%S"] {
+            if let Ok(at) = NaiveDateTime::parse_from_str(value, format) {
+                return Some(Due::DateTime(at));
+            }
+        }
+        let offset_value = value
+            .strip_suffix('Z')
+            .map(|rest| format!("{}+00:00", rest))
+            .unwrap_or_else(|| value.to_string());
+        for format in ["%Y-%m-%dT%H:%M%#z", "%Y-%m-%dT%H:%M:%S%#z"] {
+            if let Ok(at) = DateTime::parse_from_str(&offset_value, format) {
+                return Some(Due::DateTime(at.with_timezone(&Local).naive_local()));
+            }
+        }
+        NaiveDate::parse_from_str(value, DUE_DATE_FORMAT)
+            .ok()
+            .map(Due::Date)
+    }
+
+    pub fn date(self) -> NaiveDate {
+        match self {
+            Due::Date(date) => date,
+            Due::DateTime(at) => at.date(),
+        }
+    }
+
+    /// The moment the todo becomes overdue; the end of the day for a plain
+    /// date, so timed todos sort before it.
+    pub fn deadline(self) -> NaiveDateTime {
+        match self {
+            Due::Date(date) => date.and_time(NaiveTime::from_hms_opt(23, 59, 59).unwrap()),
+            Due::DateTime(at) => at,
+        }
+    }
+
+    /// How the value is written in todo.txt.
+    pub fn format(self) -> String {
+        match self {
+            Due::Date(date) => date.format(DUE_DATE_FORMAT).to_string(),
+            Due::DateTime(at) => at.format(DUE_TIME_FORMAT).to_string(),
+        }
+    }
+}
+
+impl TodoItem {
+    pub fn due(&self) -> Option<Due> {
+        self.due_date.as_deref().and_then(Due::parse)
+    }
+}
+
+/// A fresh `uid:` value for a todo that needs a stable identity.
+pub fn new_uid() -> String {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default(),
+    );
+    format!("{:08x}", hasher.finish() as u32)
+}
+
+/// Reject `due:` values that `Due::parse` wouldn't understand.
+pub fn validate_due_date(due_date: Option<&str>) -> Result<(), BouldyError> {
+    match due_date {
+        Some(value) if Due::parse(value).is_none() => Err(BouldyError::invalid_input(format!(
+            "Invalid due date '{}'. Expected YYYY-MM-DD or YYYY-MM-DDTHH:MM",
+            value
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Validate a `due:` value from the UI or an import and rewrite it in the
+/// canonical local form, so offsets and seconds never reach todo.txt.
+pub fn normalize_due_date(due_date: Option<String>) -> Result<Option<String>, BouldyError> {
+    validate_due_date(due_date.as_deref())?;
+    Ok(due_date.as_deref().and_then(Due::parse).map(Due::format))
+}
+
+/// The user's current local date and time, which due dates are relative to.
+pub fn local_now() -> NaiveDateTime {
+    chrono::Local::now().naive_local()
+}
+
+/// Whether an open todo is past its due date (or due time) as of `now`.
+pub fn is_overdue(todo: &TodoItem, now: NaiveDateTime) -> bool {
+    if todo.completed {
+        return false;
+    }
+    match todo.due() {
+        Some(Due::Date(date)) => date < now.date(),
+        Some(Due::DateTime(at)) => at < now,
+        None => false,
+    }
+}
+
+/// Calendar days from `today` until the todo is due: 0 for today, negative
+/// once the day has passed.
+pub fn days_until(todo: &TodoItem, today: NaiveDate) -> Option<i64> {
+    todo.due().map(|due| (due.date() - today).num_days())
+}
+
+/// Open todos due today or overdue as of `now`.
+pub fn due_or_overdue(todos: &[TodoItem], now: NaiveDateTime) -> Vec<&TodoItem> {
+    todos
+        .iter()
+        .filter(|t| !t.completed)
+        .filter(|t| days_until(t, now.date()).is_some_and(|days| days <= 0))
+        .collect()
+}
+
+/// Order by when each todo is due, to the minute. Undated todos go last and
+/// ties keep their file order.
+pub fn sort_by_due(todos: &mut [TodoItem]) {
+    todos.sort_by_key(|todo| {
+        let due = todo.due();
+        (due.is_none(), due.map(Due::deadline))
+    });
+}
+
 pub fn find_todo_mut(todos: &mut [TodoItem], id: usize) -> Option<&mut TodoItem> {
     todos.iter_mut().find(|t| t.id == id)
 }
 
-pub fn reorder_todo(vault_path: &str, old_index: usize, new_index: usize) -> Result<(), String> {
+pub fn reorder_todo(
+    vault_path: &str,
+    old_index: usize,
+    new_index: usize,
+) -> Result<(), BouldyError> {
     let mut todos = load_todos(vault_path)?;
 
     if old_index >= todos.len() || new_index >= todos.len() {
-        return Err("Invalid index for reordering".to_string());
+        return Err(BouldyError::invalid_input("Invalid index for reordering"));
     }
 
     if old_index == new_index {
@@ -313,7 +652,7 @@ fn get_archives_dir(vault_path: &str) -> std::path::PathBuf {
     Path::new(vault_path).join(".bouldy").join("archives")
 }
 
-pub fn load_metadata(vault_path: &str) -> Result<TodoMetadata, String> {
+pub fn load_metadata(vault_path: &str) -> Result<TodoMetadata, BouldyError> {
     let metadata_path = get_metadata_path(vault_path);
 
     if !metadata_path.exists() {
@@ -321,26 +660,25 @@ pub fn load_metadata(vault_path: &str) -> Result<TodoMetadata, String> {
     }
 
     let content = fs::read_to_string(&metadata_path)
-        .map_err(|e| format!("Failed to read metadata: {}", e))?;
+        .map_err(|e| BouldyError::io("Failed to read metadata", e))?;
 
-    serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse metadata: {}", e))
+    serde_json::from_str(&content).map_err(|e| BouldyError::parse("Failed to parse metadata", e))
 }
 
-pub fn save_metadata(vault_path: &str, metadata: &TodoMetadata) -> Result<(), String> {
+pub fn save_metadata(vault_path: &str, metadata: &TodoMetadata) -> Result<(), BouldyError> {
     let metadata_path = get_metadata_path(vault_path);
 
     // Ensure .bouldy directory exists
     if let Some(parent) = metadata_path.parent() {
         fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create .bouldy directory: {}", e))?;
+            .map_err(|e| BouldyError::io("Failed to create .bouldy directory", e))?;
     }
 
     let content = serde_json::to_string_pretty(metadata)
-        .map_err(|e| format!("Failed to serialize metadata: {}", e))?;
+        .map_err(|e| BouldyError::from(format!("Failed to serialize metadata: {}", e)))?;
 
-    fs::write(&metadata_path, content)
-        .map_err(|e| format!("Failed to write metadata: {}", e))?;
+    fsutil::write_atomic(&metadata_path, content)
+        .map_err(|e| BouldyError::io("Failed to write metadata", e))?;
 
     Ok(())
 }
@@ -406,7 +744,7 @@ fn calculate_streak(completions_by_day: &HashMap<String, usize>) -> usize {
     streak
 }
 
-pub fn archive_completed_todos(vault_path: &str) -> Result<usize, String> {
+pub fn archive_completed_todos(vault_path: &str) -> Result<usize, BouldyError> {
     let todos = load_todos(vault_path)?;
     let mut metadata = load_metadata(vault_path)?;
 
@@ -441,12 +779,12 @@ pub fn archive_completed_todos(vault_path: &str) -> Result<usize, String> {
     // Write to archive file
     let archives_dir = get_archives_dir(vault_path);
     fs::create_dir_all(&archives_dir)
-        .map_err(|e| format!("Failed to create archives directory: {}", e))?;
+        .map_err(|e| BouldyError::io("Failed to create archives directory", e))?;
 
     let archive_file = archives_dir.join(format!("done-{}.txt", current_month));
     let mut archive_content = if archive_file.exists() {
         fs::read_to_string(&archive_file)
-            .map_err(|e| format!("Failed to read archive file: {}", e))?
+            .map_err(|e| BouldyError::io("Failed to read archive file", e))?
     } else {
         String::new()
     };
@@ -456,8 +794,8 @@ pub fn archive_completed_todos(vault_path: &str) -> Result<usize, String> {
         archive_content.push_str(&format!("[{}] {}\n", today, todo.title));
     }
 
-    fs::write(&archive_file, archive_content)
-        .map_err(|e| format!("Failed to write archive file: {}", e))?;
+    fsutil::write_atomic(&archive_file, archive_content)
+        .map_err(|e| BouldyError::io("Failed to write archive file", e))?;
 
     // Remove completed todos from active list
     let remaining_todos: Vec<TodoItem> = todos.into_iter()
@@ -469,7 +807,10 @@ pub fn archive_completed_todos(vault_path: &str) -> Result<usize, String> {
     Ok(count)
 }
 
-pub fn load_archived_todos(vault_path: &str, month: &str) -> Result<Vec<ArchivedTodo>, String> {
+pub fn load_archived_todos(
+    vault_path: &str,
+    month: &str,
+) -> Result<Vec<ArchivedTodo>, BouldyError> {
     let archives_dir = get_archives_dir(vault_path);
     let archive_file = archives_dir.join(format!("done-{}.txt", month));
 
@@ -478,7 +819,7 @@ pub fn load_archived_todos(vault_path: &str, month: &str) -> Result<Vec<Archived
     }
 
     let content = fs::read_to_string(&archive_file)
-        .map_err(|e| format!("Failed to read archive file: {}", e))?;
+        .map_err(|e| BouldyError::io("Failed to read archive file", e))?;
 
     let mut archived_todos = Vec::new();
 
@@ -499,7 +840,7 @@ pub fn load_archived_todos(vault_path: &str, month: &str) -> Result<Vec<Archived
     Ok(archived_todos)
 }
 
-pub fn list_archive_months(vault_path: &str) -> Result<Vec<String>, String> {
+pub fn list_archive_months(vault_path: &str) -> Result<Vec<String>, BouldyError> {
     let archives_dir = get_archives_dir(vault_path);
 
     if !archives_dir.exists() {
@@ -507,16 +848,14 @@ pub fn list_archive_months(vault_path: &str) -> Result<Vec<String>, String> {
     }
 
     let entries = fs::read_dir(&archives_dir)
-        .map_err(|e| format!("Failed to read archives directory: {}", e))?;
+        .map_err(|e| BouldyError::io("Failed to read archives directory", e))?;
 
     let mut months = Vec::new();
-    for entry in entries {
-        if let Ok(entry) = entry {
-            if let Some(name) = entry.file_name().to_str() {
-                if name.starts_with("done-") && name.ends_with(".txt") {
-                    let month = name[5..name.len()-4].to_string(); // Extract YYYY-MM
-                    months.push(month);
-                }
+    for entry in entries.flatten() {
+        if let Some(name) = entry.file_name().to_str() {
+            if name.starts_with("done-") && name.ends_with(".txt") {
+                let month = name[5..name.len() - 4].to_string(); // Extract YYYY-MM
+                months.push(month);
             }
         }
     }
@@ -530,7 +869,7 @@ pub fn list_archive_months(vault_path: &str) -> Result<Vec<String>, String> {
 pub fn bulk_update_due_dates(
     vault_path: &str,
     updates: Vec<(usize, Option<String>)>,
-) -> Result<(), String> {
+) -> Result<(), BouldyError> {
     let mut todos = load_todos(vault_path)?;
 
     for (id, new_due_date) in updates {
@@ -0,0 +1,123 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, Url};
+use tauri_plugin_updater::{Update, UpdaterExt};
+
+use crate::error::BouldyError;
+use crate::settings;
+
+const STABLE_ENDPOINT: &str =
+    "https://github.com/LudeeD/bouldy/releases/latest/download/latest.json";
+// Pre-releases are published under a rolling `beta` tag, since GitHub's
+// "latest" release skips them
+const BETA_ENDPOINT: &str = "https://github.com/LudeeD/bouldy/releases/download/beta/latest.json";
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UpdateChannel {
+    #[default]
+    #[serde(rename = "stable")]
+    Stable,
+    #[serde(rename = "beta")]
+    Beta,
+}
+
+impl UpdateChannel {
+    fn endpoint(self) -> &'static str {
+        match self {
+            UpdateChannel::Stable => STABLE_ENDPOINT,
+            UpdateChannel::Beta => BETA_ENDPOINT,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct UpdateInfo {
+    pub version: String,
+    #[serde(rename = "currentVersion")]
+    pub current_version: String,
+    pub notes: Option<String>,
+    // RFC 3339
+    pub date: Option<String>,
+    pub channel: UpdateChannel,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct DownloadProgress {
+    pub downloaded: u64,
+    // Unknown when the server sends no Content-Length
+    pub total: Option<u64>,
+}
+
+/// Managed state: the update found by the last check, installed as-is so
+/// the user gets the version they were shown.
+#[derive(Default)]
+pub struct PendingUpdate(Mutex<Option<Update>>);
+
+/// Ask the current channel's endpoint for a newer version.
+pub async fn check(app: &AppHandle) -> Result<Option<UpdateInfo>, BouldyError> {
+    let settings = settings::load_settings(app)?;
+    let channel = settings.update_channel;
+    let endpoint = Url::parse(channel.endpoint()).map_err(|e| e.to_string())?;
+    let mut builder = app
+        .updater_builder()
+        .endpoints(vec![endpoint])
+        .map_err(|e| format!("Failed to set up updater: {}", e))?;
+    // The updater builds its own client, so it gets the proxy separately
+    if let Some(ref proxy_url) = settings.network.proxy_url {
+        let proxy = Url::parse(proxy_url)
+            .map_err(|e| format!("Invalid proxy URL '{}': {}", proxy_url, e))?;
+        builder = builder.proxy(proxy);
+    }
+    let update = builder
+        .build()
+        .map_err(|e| format!("Failed to set up updater: {}", e))?
+        .check()
+        .await
+        .map_err(|e| format!("Failed to check for updates: {}", e))?;
+
+    let info = update.as_ref().map(|update| UpdateInfo {
+        version: update.version.clone(),
+        current_version: update.current_version.clone(),
+        notes: update.body.clone(),
+        date: update.raw_json["pub_date"].as_str().map(str::to_string),
+        channel,
+    });
+    *app.state::<PendingUpdate>().0.lock().unwrap() = update;
+    Ok(info)
+}
+
+/// Forget a checked update, e.g. after switching channels.
+pub fn clear_pending(app: &AppHandle) {
+    app.state::<PendingUpdate>().0.lock().unwrap().take();
+}
+
+/// Download and install the update found by `check`, reporting progress as
+/// `updater:progress` events, then restart into the new version.
+pub async fn install_and_restart(app: &AppHandle) -> Result<(), BouldyError> {
+    let update = app
+        .state::<PendingUpdate>()
+        .0
+        .lock()
+        .unwrap()
+        .take()
+        .ok_or_else(|| BouldyError::not_found("No update to install. Check for updates first"))?;
+
+    let mut downloaded: u64 = 0;
+    let bytes = update
+        .download(
+            |chunk, total| {
+                downloaded += chunk as u64;
+                let _ = app.emit("updater:progress", DownloadProgress { downloaded, total });
+            },
+            || {
+                let _ = app.emit("updater:downloaded", ());
+            },
+        )
+        .await
+        .map_err(|e| format!("Failed to download update: {}", e))?;
+
+    update
+        .install(bytes)
+        .map_err(|e| format!("Failed to install update: {}", e))?;
+    app.restart()
+}
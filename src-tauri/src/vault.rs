@@ -0,0 +1,492 @@
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::error::BouldyError;
+use crate::fsutil;
+use crate::text;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Note {
+    pub path: String,
+    pub name: String,
+    pub title: String,
+    pub modified: u64,
+    pub is_symlink: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SearchMatch {
+    pub path: String,
+    pub title: String,
+    pub line: usize, // 1-indexed, 0 when only the title matched
+    pub snippet: String,
+}
+
+// How much of a file is read when only its frontmatter and title are needed
+const HEAD_BYTES: u64 = 8 * 1024;
+
+// Names Windows won't create as files, with or without an extension
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+// Not allowed in Windows file names (plus control characters)
+const INVALID_NAME_CHARS: &[char] = &['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+
+/// A note's title and frontmatter, read without loading the whole file.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NoteHeader {
+    pub path: String,
+    pub title: String,
+    pub frontmatter: serde_json::Value,
+    pub tags: Vec<String>,
+    pub aliases: Vec<String>,
+}
+
+/// Read at most the first `HEAD_BYTES` of a file, cut back to the last
+/// complete UTF-8 character.
+pub fn read_head(path: &Path) -> Result<String, BouldyError> {
+    let mut bytes = Vec::new();
+    File::open(fsutil::long_path(path))
+        .and_then(|file| file.take(HEAD_BYTES).read_to_end(&mut bytes))
+        .map_err(|e| BouldyError::io(&format!("Failed to read {}", path.display()), e))?;
+
+    match String::from_utf8(bytes) {
+        Ok(head) => Ok(head),
+        Err(e) => {
+            let valid = e.utf8_error().valid_up_to();
+            let mut bytes = e.into_bytes();
+            bytes.truncate(valid);
+            Ok(String::from_utf8(bytes).unwrap_or_default())
+        }
+    }
+}
+
+/// Frontmatter and title from the head of a note. Frontmatter longer than
+/// the head is treated as absent rather than read in full.
+pub fn read_note_header(path: &Path) -> Result<NoteHeader, BouldyError> {
+    let head = read_head(path)?;
+    let frontmatter = crate::markdown::parse_frontmatter(&head).unwrap_or_default();
+
+    let title = frontmatter
+        .get("title")
+        .and_then(|title| title.as_str())
+        .map(|title| title.trim().to_string())
+        .filter(|title| !title.is_empty())
+        .unwrap_or_else(|| extract_title_from_filename(path));
+
+    Ok(NoteHeader {
+        path: path.to_string_lossy().to_string(),
+        title,
+        tags: crate::markdown::frontmatter_list(&frontmatter, "tags"),
+        aliases: crate::markdown::frontmatter_aliases(&frontmatter),
+        frontmatter: serde_json::to_value(&frontmatter).unwrap_or_default(),
+    })
+}
+
+/// One window of a listing plus the full count, so the frontend can load
+/// large vaults lazily instead of receiving every entry at once.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: usize,
+    pub offset: usize,
+}
+
+impl<T> Page<T> {
+    /// Slice an already-loaded listing. No limit returns everything from `offset`.
+    pub fn from_vec(items: Vec<T>, offset: Option<usize>, limit: Option<usize>) -> Self {
+        let total = items.len();
+        let offset = offset.unwrap_or(0).min(total);
+        let items = items
+            .into_iter()
+            .skip(offset)
+            .take(limit.unwrap_or(usize::MAX))
+            .collect();
+
+        Self {
+            items,
+            total,
+            offset,
+        }
+    }
+}
+
+/// Every file under `dir`, skipping hidden files and folders like .bouldy/.
+pub fn walk_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        if path.is_dir() {
+            walk_files(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+/// A markdown file bouldy can list. Paths reach the webview as strings, so a
+/// file whose path isn't valid UTF-8 couldn't be opened from there and is
+/// left out rather than shown under a mangled name.
+pub fn is_markdown_file(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "md") && path.to_str().is_some()
+}
+
+/// The path as sent to the webview.
+pub fn path_to_string(path: &Path) -> Result<String, BouldyError> {
+    path.to_str().map(str::to_string).ok_or_else(|| {
+        BouldyError::invalid_path(format!("File name is not valid UTF-8: {}", path.display()))
+    })
+}
+
+pub fn file_name_to_string(path: &Path) -> Result<String, BouldyError> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| BouldyError::invalid_path(format!("No file name: {}", path.display())))?;
+    path_to_string(Path::new(file_name))
+}
+
+/// Whether `path` exists under exactly this name. macOS and Windows volumes
+/// usually ignore case, so after renaming `Note.md` to `note.md` the old
+/// path still opens the file; only the directory listing says which name
+/// is real.
+pub fn exists_with_exact_case(path: &Path) -> bool {
+    if !path.exists() {
+        return false;
+    }
+    if !cfg!(any(target_os = "macos", target_os = "windows")) {
+        return true;
+    }
+    let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+        return true;
+    };
+    fs::read_dir(parent).is_ok_and(|mut entries| {
+        entries.any(|entry| entry.is_ok_and(|entry| entry.file_name() == name))
+    })
+}
+
+/// Whether two paths name the same file on this platform's usual
+/// filesystem, i.e. ignoring case on macOS and Windows.
+pub fn same_file_path(a: &Path, b: &Path) -> bool {
+    if cfg!(any(target_os = "macos", target_os = "windows")) {
+        a.to_string_lossy().to_lowercase() == b.to_string_lossy().to_lowercase()
+    } else {
+        a == b
+    }
+}
+
+/// Seconds since the epoch, clamping clock oddities (pre-1970 mtimes) to 0.
+pub fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn is_invalid_name_char(c: char) -> bool {
+    INVALID_NAME_CHARS.contains(&c) || c.is_control()
+}
+
+// "con", "CON.md" and "con.backup.txt" are all reserved
+fn is_reserved_name(name: &str) -> bool {
+    let base = name.split('.').next().unwrap_or(name).trim_end();
+    RESERVED_NAMES
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(base))
+}
+
+/// Check a note or prompt name (without `.md`) can be a file on every
+/// platform. Vaults are synced between machines, so Windows rules apply
+/// everywhere.
+pub fn validate_file_stem(name: &str) -> Result<(), BouldyError> {
+    if name.trim().is_empty() {
+        return Err(BouldyError::invalid_path("Name cannot be empty"));
+    }
+    if name.starts_with('.') {
+        return Err(BouldyError::invalid_path(format!(
+            "'{}' can't start with '.'",
+            name
+        )));
+    }
+    if let Some(c) = name.chars().find(|&c| is_invalid_name_char(c)) {
+        return Err(BouldyError::invalid_path(format!(
+            "'{}' can't contain '{}'",
+            name,
+            c.escape_default()
+        )));
+    }
+    if name.ends_with(['.', ' ']) {
+        return Err(BouldyError::invalid_path(format!(
+            "'{}' can't end with a dot or space",
+            name
+        )));
+    }
+    if is_reserved_name(name) {
+        return Err(BouldyError::invalid_path(format!(
+            "'{}' is a reserved file name on Windows",
+            name
+        )));
+    }
+    Ok(())
+}
+
+/// Turn an arbitrary file name into one that passes `validate_file_stem`
+/// (extension kept): invalid characters become '-', reserved names get a
+/// trailing '_'.
+pub fn sanitize_file_name(file_name: &str) -> String {
+    let cleaned: String = file_name
+        .chars()
+        .map(|c| if is_invalid_name_char(c) { '-' } else { c })
+        .collect();
+
+    let (stem, extension) = match cleaned.rfind('.') {
+        Some(dot) if dot > 0 => cleaned.split_at(dot),
+        _ => (cleaned.as_str(), ""),
+    };
+
+    let mut stem = stem
+        .trim_start_matches('.')
+        .trim_end_matches(['.', ' '])
+        .to_string();
+    if stem.is_empty() {
+        stem = "Untitled".to_string();
+    }
+    if is_reserved_name(&stem) {
+        stem.push('_');
+    }
+
+    format!("{}{}", stem, extension)
+}
+
+pub fn extract_title_from_filename(path: &Path) -> String {
+    // Extract title from filename (without .md extension)
+    path.file_stem()
+        .and_then(|s| s.to_str())
+        .map(text::nfc)
+        .unwrap_or_else(|| "Untitled".to_string())
+}
+
+/// An existing note whose title matches `title` ignoring case and Unicode
+/// normalization, so "Café" and "café" aren't created side by side.
+pub fn find_note_by_title(vault_path: &str, title: &str) -> Option<PathBuf> {
+    let wanted = text::fold(title);
+    fs::read_dir(notes_dir(vault_path))
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| is_markdown_file(path))
+        .find(|path| text::fold(&extract_title_from_filename(path)) == wanted)
+}
+
+/// The note a name or `[[wikilink]]` target refers to. `alias` is set when
+/// it was found through the note's `aliases:` rather than its title.
+#[derive(Serialize, Clone, Debug)]
+pub struct ResolvedNote {
+    pub path: String,
+    pub title: String,
+    pub alias: Option<String>,
+}
+
+/// A `[[wikilink]]` target reduced to the note name it refers to: no
+/// `#heading`, `|label`, folder or `.md` extension.
+pub fn link_target_name(target: &str) -> &str {
+    let target = target.split(['#', '|']).next().unwrap_or_default().trim();
+    let name = target.rsplit('/').next().unwrap_or(target);
+    name.strip_suffix(".md").unwrap_or(name)
+}
+
+/// The note an alias in its `aliases:` frontmatter points to, ignoring case
+/// and Unicode normalization. Reads every note's header, so callers should
+/// ask the index first.
+pub fn find_note_by_alias(vault_path: &str, alias: &str) -> Option<PathBuf> {
+    let wanted = text::fold(alias);
+    let mut notes = list_notes(vault_path).ok()?;
+    // Same answer as the index when two notes claim an alias
+    notes.sort_by(|a, b| a.path.cmp(&b.path));
+    notes
+        .into_iter()
+        .map(|note| PathBuf::from(note.path))
+        .find(|path| {
+            read_note_header(path).is_ok_and(|header| {
+                header
+                    .aliases
+                    .iter()
+                    .any(|candidate| text::fold(candidate) == wanted)
+            })
+        })
+}
+
+/// The directory notes live in: notes/ if it exists, otherwise the vault root
+/// (vaults created before the notes/ migration).
+pub fn notes_dir(vault_path: &str) -> PathBuf {
+    let vault = Path::new(vault_path);
+    let notes_dir = vault.join("notes");
+
+    if notes_dir.exists() {
+        notes_dir
+    } else {
+        vault.to_path_buf()
+    }
+}
+
+/// Resolve a path coming from the webview and make sure it stays inside the
+/// vault. Relative paths are taken relative to the vault. Symlinks and files
+/// that don't exist yet are checked by where they live (their parent
+/// directory), not by where a link points.
+pub fn validate_path_in_vault(vault_path: &str, file_path: &str) -> Result<PathBuf, BouldyError> {
+    let vault = Path::new(vault_path)
+        .canonicalize()
+        .map_err(|e| BouldyError::io("Invalid vault path", e))?;
+
+    let requested = Path::new(file_path);
+    let requested = if requested.is_relative() {
+        vault.join(requested)
+    } else {
+        requested.to_path_buf()
+    };
+
+    let file = if requested.is_symlink() || !requested.exists() {
+        // file_name() is None for paths ending in "..", so no escaping that way
+        let file_name = requested.file_name().ok_or("Invalid file path")?;
+        let parent = requested
+            .parent()
+            .ok_or("Invalid file path")?
+            .canonicalize()
+            .map_err(|e| BouldyError::io("Invalid parent path", e))?;
+
+        parent.join(file_name)
+    } else {
+        requested
+            .canonicalize()
+            .map_err(|e| BouldyError::io("Invalid file path", e))?
+    };
+
+    if !file.starts_with(&vault) {
+        return Err(BouldyError::outside_vault(format!(
+            "Path is outside vault: {}",
+            file_path
+        )));
+    }
+
+    Ok(file)
+}
+
+/// Stat a single note file into its listing entry.
+pub fn read_note_entry(path: &Path) -> Result<Note, BouldyError> {
+    let metadata = fs::metadata(fsutil::long_path(path))?;
+
+    Ok(Note {
+        path: path_to_string(path)?,
+        name: file_name_to_string(path)?,
+        title: extract_title_from_filename(path),
+        modified: unix_secs(metadata.modified()?),
+        is_symlink: path.is_symlink(),
+    })
+}
+
+pub fn list_notes(vault_path: &str) -> Result<Vec<Note>, BouldyError> {
+    let read_dir = notes_dir(vault_path);
+
+    if !read_dir.exists() {
+        return Err(BouldyError::not_found("Notes directory does not exist"));
+    }
+
+    let mut notes = Vec::new();
+
+    let entries = fs::read_dir(&read_dir)
+        .map_err(|e| BouldyError::io("Failed to read notes directory", e))?;
+
+    for entry in entries {
+        let entry = entry.map_err(|e| BouldyError::io("Failed to read entry", e))?;
+        let path = entry.path();
+
+        if is_markdown_file(&path) {
+            // Broken symlinks and the like are skipped rather than failing the listing
+            match read_note_entry(&path) {
+                Ok(note) => notes.push(note),
+                Err(e) => tracing::warn!("Skipping {} - {}", path.display(), e),
+            }
+        }
+    }
+
+    // Sort by modified time (newest first)
+    notes.sort_by_key(|n| std::cmp::Reverse(n.modified));
+
+    Ok(notes)
+}
+
+/// Create a new note named after its title. Fails rather than overwriting.
+pub fn create_note(vault_path: &str, title: &str, content: &str) -> Result<PathBuf, BouldyError> {
+    let title = text::nfc(title.trim());
+    validate_file_stem(&title)?;
+
+    let path = notes_dir(vault_path).join(format!("{}.md", title));
+    if path.exists() || find_note_by_title(vault_path, &title).is_some() {
+        return Err(BouldyError::conflict(format!(
+            "Note '{}' already exists in vault",
+            title
+        )));
+    }
+
+    fsutil::write_atomic(&path, content).map_err(|e| BouldyError::io("Failed to write note", e))?;
+
+    Ok(path)
+}
+
+/// Caseless, normalization-insensitive substring search over note titles
+/// and contents. `needle` is already folded.
+/// Returns one match per matching line, plus a title match if the title hits.
+fn search_note(note: &Note, needle: &str) -> Vec<SearchMatch> {
+    let mut matches = Vec::new();
+
+    if text::fold(&note.title).contains(needle) {
+        matches.push(SearchMatch {
+            path: note.path.clone(),
+            title: note.title.clone(),
+            line: 0,
+            snippet: note.title.clone(),
+        });
+    }
+
+    // Unreadable notes (e.g. broken symlinks, binary files) are skipped
+    let Ok(content) = fs::read_to_string(fsutil::long_path(Path::new(&note.path))) else {
+        return matches;
+    };
+
+    for (index, line) in content.lines().enumerate() {
+        if text::fold(line).contains(needle) {
+            matches.push(SearchMatch {
+                path: note.path.clone(),
+                title: note.title.clone(),
+                line: index + 1,
+                snippet: line.trim().to_string(),
+            });
+        }
+    }
+
+    matches
+}
+
+pub fn search_notes(vault_path: &str, query: &str) -> Result<Vec<SearchMatch>, BouldyError> {
+    let needle = text::fold(query.trim());
+    if needle.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Notes are scanned in parallel; collecting keeps them in listing order
+    let matches = list_notes(vault_path)?
+        .par_iter()
+        .flat_map_iter(|note| search_note(note, &needle))
+        .collect();
+
+    Ok(matches)
+}
@@ -0,0 +1,134 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::error::BouldyError;
+use crate::{fsutil, vault, vault_lock};
+
+const CONFIG_FILE: &str = "config.json";
+
+pub const DEFAULT_ATTACHMENTS_DIR: &str = "assets";
+
+/// Whose conventions to follow when writing into the vault.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Compatibility {
+    #[default]
+    #[serde(rename = "bouldy")]
+    Bouldy,
+    // Wikilink embeds and Obsidian's attachment folder, so the vault can be
+    // opened in both apps
+    #[serde(rename = "obsidian")]
+    Obsidian,
+}
+
+/// Settings stored with the vault in `.bouldy/config.json` rather than in
+/// the app's settings, so they follow the vault to other machines.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct VaultConfig {
+    #[serde(default)]
+    pub compatibility: Compatibility,
+    // Relative to the vault, or to the note's folder when it starts with
+    // "./" as in Obsidian. Unset uses the mode's default.
+    #[serde(
+        rename = "attachmentsFolder",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub attachments_folder: Option<String>,
+}
+
+fn config_path(vault_path: &str) -> PathBuf {
+    Path::new(vault_path).join(".bouldy").join(CONFIG_FILE)
+}
+
+pub fn load(vault_path: &str) -> Result<VaultConfig, BouldyError> {
+    let content = match fs::read_to_string(config_path(vault_path)) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(VaultConfig::default()),
+        Err(e) => return Err(BouldyError::io("Failed to read vault config", e)),
+    };
+    serde_json::from_str(&content)
+        .map_err(|e| BouldyError::parse("Failed to parse vault config", e))
+}
+
+pub fn save(vault_path: &str, config: &VaultConfig) -> Result<(), BouldyError> {
+    let content = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Failed to serialize vault config: {}", e))?;
+
+    let _lock = vault_lock::acquire(vault_path)?;
+    fsutil::write_atomic(&config_path(vault_path), content)
+        .map_err(|e| BouldyError::io("Failed to write vault config", e))
+}
+
+// Obsidian's own "Default location for new attachments" setting
+fn obsidian_attachment_folder(vault_path: &str) -> Option<String> {
+    let path = Path::new(vault_path).join(".obsidian").join("app.json");
+    let app: serde_json::Value = serde_json::from_str(&fs::read_to_string(path).ok()?).ok()?;
+    app.get("attachmentFolderPath")?
+        .as_str()
+        .map(|folder| folder.to_string())
+}
+
+// `target` relative to `from`, with forward slashes so links work everywhere
+fn relative_path(from: &Path, target: &Path) -> String {
+    let from: Vec<Component> = from.components().collect();
+    let target: Vec<Component> = target.components().collect();
+    let common = from.iter().zip(&target).take_while(|(a, b)| a == b).count();
+
+    let mut parts: Vec<String> = vec!["..".to_string(); from.len() - common];
+    parts.extend(
+        target[common..]
+            .iter()
+            .map(|part| part.as_os_str().to_string_lossy().to_string()),
+    );
+    parts.join("/")
+}
+
+impl VaultConfig {
+    /// Folder that files dropped onto `note` are copied into.
+    pub fn attachments_dir(&self, vault_path: &str, note: Option<&Path>) -> PathBuf {
+        let folder = match self.compatibility {
+            Compatibility::Bouldy => self
+                .attachments_folder
+                .clone()
+                .unwrap_or_else(|| DEFAULT_ATTACHMENTS_DIR.to_string()),
+            // Obsidian defaults to the vault root
+            Compatibility::Obsidian => self
+                .attachments_folder
+                .clone()
+                .or_else(|| obsidian_attachment_folder(vault_path))
+                .unwrap_or_else(|| "/".to_string()),
+        };
+
+        if folder == "." || folder.starts_with("./") {
+            let note_dir = note
+                .and_then(Path::parent)
+                .map(Path::to_path_buf)
+                .unwrap_or_else(|| vault::notes_dir(vault_path));
+            return note_dir.join(folder.strip_prefix("./").unwrap_or_default());
+        }
+
+        Path::new(vault_path).join(folder.trim_start_matches('/'))
+    }
+
+    /// Link to `attachment` for appending to a note in `note_dir`. Obsidian
+    /// resolves `![[name]]` embeds by file name; bouldy uses a relative
+    /// markdown link, angle-bracketed so spaces survive.
+    pub fn attachment_link(&self, attachment: &Path, note_dir: &Path, image: bool) -> String {
+        let name = attachment
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let embed = if image { "!" } else { "" };
+
+        match self.compatibility {
+            Compatibility::Obsidian => format!("{}[[{}]]", embed, name),
+            Compatibility::Bouldy => format!(
+                "{}[{}](<{}>)",
+                embed,
+                name,
+                relative_path(note_dir, attachment)
+            ),
+        }
+    }
+}
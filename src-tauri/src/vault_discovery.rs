@@ -0,0 +1,198 @@
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::vault;
+
+// How far below each search root vaults are looked for, e.g.
+// ~/Documents/Notes/Work is two levels down
+const MAX_DEPTH: usize = 2;
+// Directories looked at in total, so a huge home folder can't stall onboarding
+const MAX_DIRECTORIES: usize = 5000;
+// Counting stops here; enough to tell a real vault from a stray folder
+const MAX_NOTES_COUNTED: usize = 1000;
+
+// Never vaults, and slow to walk
+const SKIPPED_DIRS: &[&str] = &[
+    "node_modules",
+    "target",
+    "Library",
+    "AppData",
+    "Applications",
+    "Program Files",
+    "Pictures",
+    "Music",
+    "Movies",
+    "Videos",
+];
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum VaultKind {
+    // Has .bouldy, so bouldy has opened it before
+    #[serde(rename = "bouldy")]
+    Bouldy,
+    #[serde(rename = "obsidian")]
+    Obsidian,
+    // A notes/ folder or todo.txt without either app's folder
+    #[serde(rename = "plain")]
+    Plain,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct VaultCandidate {
+    pub path: String,
+    pub name: String,
+    pub kind: VaultKind,
+    // What made it look like a vault: ".bouldy", ".obsidian", "notes",
+    // "todo.txt"
+    pub markers: Vec<String>,
+    // Markdown files in the vault root and notes/, up to a cap
+    pub notes: usize,
+    // Seconds since the epoch of the most recent note
+    pub modified: Option<u64>,
+}
+
+// Home, Documents and Desktop, plus the usual places sync clients keep
+// their folders
+fn search_roots() -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = [dirs::document_dir(), dirs::desktop_dir()]
+        .into_iter()
+        .flatten()
+        .collect();
+    if let Some(home) = dirs::home_dir() {
+        for folder in [
+            "Dropbox",
+            "OneDrive",
+            "Google Drive",
+            "Nextcloud",
+            "Sync",
+            "Library/Mobile Documents/iCloud~md~obsidian/Documents",
+            "Library/Mobile Documents/com~apple~CloudDocs",
+        ] {
+            roots.push(home.join(folder));
+        }
+        roots.push(home);
+    }
+    if let Ok(onedrive) = std::env::var("OneDrive") {
+        roots.push(PathBuf::from(onedrive));
+    }
+    roots.into_iter().filter(|root| root.is_dir()).collect()
+}
+
+fn markdown_files(dir: &Path) -> impl Iterator<Item = fs::DirEntry> {
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "md"))
+}
+
+fn inspect(dir: &Path) -> Option<VaultCandidate> {
+    let mut markers = Vec::new();
+    for marker in [".bouldy", ".obsidian"] {
+        if dir.join(marker).is_dir() {
+            markers.push(marker.to_string());
+        }
+    }
+
+    let notes_dir = dir.join("notes");
+    let mut notes = 0;
+    let mut modified = None;
+    for folder in [dir, notes_dir.as_path()] {
+        for entry in markdown_files(folder) {
+            if notes >= MAX_NOTES_COUNTED {
+                break;
+            }
+            notes += 1;
+            let mtime = entry
+                .metadata()
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .map(vault::unix_secs);
+            modified = modified.max(mtime);
+        }
+    }
+    // An empty notes/ folder is too common to mean anything
+    if notes_dir.is_dir() && markdown_files(&notes_dir).next().is_some() {
+        markers.push("notes".to_string());
+    }
+    if dir.join("todo.txt").is_file() {
+        markers.push("todo.txt".to_string());
+    }
+    if markers.is_empty() {
+        return None;
+    }
+
+    let kind = if markers.iter().any(|m| m == ".bouldy") {
+        VaultKind::Bouldy
+    } else if markers.iter().any(|m| m == ".obsidian") {
+        VaultKind::Obsidian
+    } else {
+        VaultKind::Plain
+    };
+    Some(VaultCandidate {
+        path: dir.to_string_lossy().to_string(),
+        name: dir
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        kind,
+        markers,
+        notes,
+        modified,
+    })
+}
+
+fn is_skipped(name: &str) -> bool {
+    name.starts_with('.') || SKIPPED_DIRS.contains(&name)
+}
+
+/// Folders under the usual places (home, Documents, Desktop, sync folders)
+/// that look like a bouldy or Obsidian vault, for the onboarding screen.
+/// Folders bouldy has opened come first, then Obsidian vaults, then the
+/// ones with the most notes. Vaults inside vaults aren't reported.
+pub fn discover_vaults() -> Vec<VaultCandidate> {
+    let mut candidates = Vec::new();
+    let mut seen: HashSet<PathBuf> = HashSet::new();
+    let mut visited = 0;
+
+    for root in search_roots() {
+        let mut queue = vec![(root, 0)];
+        while let Some((dir, depth)) = queue.pop() {
+            if visited >= MAX_DIRECTORIES {
+                break;
+            }
+            visited += 1;
+            // Documents is under home, and sync folders are often linked
+            let Ok(canonical) = dir.canonicalize() else {
+                continue;
+            };
+            if !seen.insert(canonical) {
+                continue;
+            }
+
+            if let Some(candidate) = inspect(&dir) {
+                candidates.push(candidate);
+                continue;
+            }
+            if depth >= MAX_DEPTH {
+                continue;
+            }
+            for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if !is_skipped(&name) && entry.file_type().is_ok_and(|t| t.is_dir()) {
+                    queue.push((entry.path(), depth + 1));
+                }
+            }
+        }
+    }
+
+    candidates.sort_by(|a, b| {
+        a.kind
+            .cmp(&b.kind)
+            .then_with(|| b.notes.cmp(&a.notes))
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    candidates
+}
@@ -0,0 +1,87 @@
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::error::BouldyError;
+
+pub const LOCK_FILE: &str = "vault.lock";
+
+// How long a writer waits for another process before giving up
+const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+const RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+// File locks are per open file, so two threads of the same process would
+// also contend on vault.lock. Serializing in-process writers first means a
+// failed try_lock really is another process.
+static IN_PROCESS: Mutex<()> = Mutex::new(());
+
+type ContentionHandler = Box<dyn Fn(&str) + Send + Sync>;
+static CONTENTION_HANDLER: OnceLock<ContentionHandler> = OnceLock::new();
+
+/// Called with the vault path whenever a write has to wait for another
+/// process (another bouldy window, or the CLI) holding the vault lock.
+pub fn on_contention(handler: impl Fn(&str) + Send + Sync + 'static) {
+    let _ = CONTENTION_HANDLER.set(Box::new(handler));
+}
+
+/// Advisory lock on a vault, held for the duration of a write and released
+/// on drop. Only bouldy processes honor it.
+pub struct VaultLock {
+    file: File,
+    _guard: MutexGuard<'static, ()>,
+}
+
+impl Drop for VaultLock {
+    fn drop(&mut self) {
+        let _ = self.file.unlock();
+    }
+}
+
+pub fn acquire(vault_path: &str) -> Result<VaultLock, BouldyError> {
+    let guard = IN_PROCESS.lock().unwrap_or_else(|e| e.into_inner());
+
+    let bouldy_dir = Path::new(vault_path).join(".bouldy");
+    fs::create_dir_all(&bouldy_dir)
+        .map_err(|e| format!("Failed to create .bouldy directory: {}", e))?;
+
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(bouldy_dir.join(LOCK_FILE))
+        .map_err(|e| format!("Failed to open vault lock: {}", e))?;
+
+    let deadline = Instant::now() + LOCK_TIMEOUT;
+    let mut reported = false;
+
+    loop {
+        match file.try_lock() {
+            Ok(()) => {
+                return Ok(VaultLock {
+                    file,
+                    _guard: guard,
+                })
+            }
+            Err(TryLockError::WouldBlock) => {}
+            Err(TryLockError::Error(e)) => {
+                return Err(BouldyError::io("Failed to lock vault", e));
+            }
+        }
+
+        if !reported {
+            if let Some(handler) = CONTENTION_HANDLER.get() {
+                handler(vault_path);
+            }
+            reported = true;
+        }
+
+        if Instant::now() >= deadline {
+            return Err(BouldyError::conflict(
+                "Vault is locked by another bouldy instance",
+            ));
+        }
+        thread::sleep(RETRY_INTERVAL);
+    }
+}
@@ -0,0 +1,151 @@
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::BouldyError;
+use crate::fsutil;
+use crate::todos::{self, TodoItem};
+
+const STATE_FILE: &str = "waiting.json";
+const DATE_FORMAT: &str = "%Y-%m-%d";
+pub const WAITING_CONTEXT: &str = "waiting";
+
+// Fingerprint of a waiting todo's line -> the day it was first seen like
+// that. Any edit to the todo counts as movement and starts the clock again.
+#[derive(Serialize, Deserialize, Default, PartialEq)]
+struct WaitingState {
+    since: HashMap<String, String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct WaitingItem {
+    #[serde(flatten)]
+    pub todo: TodoItem,
+    // Last day the todo changed
+    pub since: String,
+    #[serde(rename = "daysWaiting")]
+    pub days_waiting: i64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct WaitingGroup {
+    // None for `@waiting` todos not delegated to anyone
+    pub person: Option<String>,
+    pub items: Vec<WaitingItem>,
+}
+
+/// Open todos marked `@waiting` or delegated to someone.
+pub fn is_waiting(todo: &TodoItem) -> bool {
+    !todo.completed
+        && (todo.delegated.is_some()
+            || todo
+                .contexts
+                .iter()
+                .any(|context| context.eq_ignore_ascii_case(WAITING_CONTEXT)))
+}
+
+fn state_path(vault_path: &str) -> PathBuf {
+    Path::new(vault_path).join(".bouldy").join(STATE_FILE)
+}
+
+// When each waiting todo last changed, recording today for ones not seen
+// before and forgetting ones that are gone
+fn waiting_since(
+    vault_path: &str,
+    todos: &[&TodoItem],
+    today: NaiveDate,
+) -> Result<Vec<NaiveDate>, BouldyError> {
+    let path = state_path(vault_path);
+    let previous: WaitingState = fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+
+    let mut state = WaitingState::default();
+    let dates = todos
+        .iter()
+        .map(|todo| {
+            let key = todos::fingerprint(todo);
+            let since = previous
+                .since
+                .get(&key)
+                .and_then(|date| NaiveDate::parse_from_str(date, DATE_FORMAT).ok())
+                .unwrap_or(today);
+            state
+                .since
+                .insert(key, since.format(DATE_FORMAT).to_string());
+            since
+        })
+        .collect();
+
+    if state != previous {
+        let content = serde_json::to_string_pretty(&state)
+            .map_err(|e| format!("Failed to serialize waiting state: {}", e))?;
+        fsutil::write_atomic(&path, content)
+            .map_err(|e| BouldyError::io("Failed to write waiting state", e))?;
+    }
+    Ok(dates)
+}
+
+fn waiting_items(vault_path: &str, todos: &[TodoItem]) -> Result<Vec<WaitingItem>, BouldyError> {
+    let today = Local::now().date_naive();
+    let waiting: Vec<&TodoItem> = todos.iter().filter(|todo| is_waiting(todo)).collect();
+    let since = waiting_since(vault_path, &waiting, today)?;
+
+    Ok(waiting
+        .into_iter()
+        .zip(since)
+        .map(|(todo, since)| WaitingItem {
+            todo: todo.clone(),
+            since: since.format(DATE_FORMAT).to_string(),
+            days_waiting: (today - since).num_days(),
+        })
+        .collect())
+}
+
+/// Waiting-for todos grouped by who they're delegated to, people in
+/// alphabetical order and the undelegated ones last. Within a group the
+/// longest waiting come first.
+pub fn list_waiting_for(
+    vault_path: &str,
+    todos: &[TodoItem],
+) -> Result<Vec<WaitingGroup>, BouldyError> {
+    let mut by_person: BTreeMap<(bool, String), WaitingGroup> = BTreeMap::new();
+    for item in waiting_items(vault_path, todos)? {
+        let person = item.todo.delegated.clone();
+        let key = (
+            person.is_none(),
+            person.as_deref().unwrap_or_default().to_lowercase(),
+        );
+        by_person
+            .entry(key)
+            .or_insert_with(|| WaitingGroup {
+                person,
+                items: Vec::new(),
+            })
+            .items
+            .push(item);
+    }
+
+    let mut groups: Vec<WaitingGroup> = by_person.into_values().collect();
+    for group in &mut groups {
+        group
+            .items
+            .sort_by_key(|item| std::cmp::Reverse(item.days_waiting));
+    }
+    Ok(groups)
+}
+
+/// Waiting todos that haven't changed in at least `days` days.
+pub fn stalled(
+    vault_path: &str,
+    todos: &[TodoItem],
+    days: u32,
+) -> Result<Vec<WaitingItem>, BouldyError> {
+    Ok(waiting_items(vault_path, todos)?
+        .into_iter()
+        .filter(|item| item.days_waiting >= days as i64)
+        .collect())
+}
@@ -1,11 +1,25 @@
+use notify::event::{ModifyKind, RenameMode};
 use notify::{RecommendedWatcher, RecursiveMode};
 use notify_debouncer_full::{new_debouncer, DebounceEventResult, Debouncer, RecommendedCache};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::BouldyError;
+use crate::index::IndexState;
+use crate::note_cache::NoteCache;
+use crate::switcher::QuickSwitcher;
+use crate::todo_store::TodoStore;
+use crate::{
+    badge, boards, bookmarks, canvas, habits, ics, inbox, pomodoro, settings, snippets, someday,
+    task_sync, vault, vault_config, windows,
+};
+
+pub type VaultWatcher = Debouncer<RecommendedWatcher, RecommendedCache>;
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct NoteEventPayload {
@@ -20,66 +34,208 @@ pub struct NoteListPayload {
     pub notes: Vec<NoteEventPayload>,
 }
 
-fn extract_title_from_filename(path: &Path) -> String {
-    // Extract title from filename (without .md extension)
-    path.file_stem()
-        .and_then(|s| s.to_str())
-        .map(|s| s.to_string())
-        .unwrap_or_else(|| "Untitled".to_string())
+// A save from one of the app's windows; the others reload the note, the
+// one that saved it doesn't
+#[derive(Clone, Serialize, Deserialize)]
+pub struct NoteSavedPayload {
+    #[serde(flatten)]
+    pub note: NoteEventPayload,
+    pub window: String,
+}
+
+// A note moved or renamed within the notes directory, including case-only
+// renames
+#[derive(Clone, Serialize, Deserialize)]
+pub struct NoteRenamedPayload {
+    pub from: String,
+    pub to: NoteEventPayload,
+    #[serde(rename = "caseOnly")]
+    pub case_only: bool,
+}
+
+// A file added to or removed from the attachments folder
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AssetEventPayload {
+    pub path: String,
+    pub name: String,
+}
+
+// Above this many note and asset events in one debounced batch (a git
+// checkout, a sync pulling in a folder), they are replaced by a single
+// `vault:bulk-change` and the frontend reloads once
+const BULK_EVENT_THRESHOLD: usize = 50;
+
+#[derive(Clone, Serialize, Deserialize, Default)]
+pub struct BulkChangePayload {
+    pub created: usize,
+    pub updated: usize,
+    pub deleted: usize,
+    pub renamed: usize,
+    #[serde(rename = "assetsCreated")]
+    pub assets_created: usize,
+    #[serde(rename = "assetsDeleted")]
+    pub assets_deleted: usize,
+}
+
+// Per-file events, held until the end of the batch to decide between
+// sending them one by one or summarized
+enum FileEvent {
+    NoteCreated(NoteEventPayload),
+    NoteUpdated(NoteEventPayload),
+    NoteDeleted(NoteEventPayload),
+    NoteRenamed(NoteRenamedPayload),
+    AssetCreated(AssetEventPayload),
+    AssetDeleted(AssetEventPayload),
+}
+
+impl FileEvent {
+    fn emit(self, app: &AppHandle) {
+        let _ = match self {
+            FileEvent::NoteCreated(payload) => app.emit("note:created", payload),
+            FileEvent::NoteUpdated(payload) => app.emit("note:updated", payload),
+            FileEvent::NoteDeleted(payload) => app.emit("note:deleted", payload),
+            FileEvent::NoteRenamed(payload) => app.emit("note:renamed", payload),
+            FileEvent::AssetCreated(payload) => app.emit("asset:created", payload),
+            FileEvent::AssetDeleted(payload) => app.emit("asset:deleted", payload),
+        };
+    }
+}
+
+fn summarize(events: &[FileEvent]) -> BulkChangePayload {
+    let mut summary = BulkChangePayload::default();
+    for event in events {
+        let count = match event {
+            FileEvent::NoteCreated(_) => &mut summary.created,
+            FileEvent::NoteUpdated(_) => &mut summary.updated,
+            FileEvent::NoteDeleted(_) => &mut summary.deleted,
+            FileEvent::NoteRenamed(_) => &mut summary.renamed,
+            FileEvent::AssetCreated(_) => &mut summary.assets_created,
+            FileEvent::AssetDeleted(_) => &mut summary.assets_deleted,
+        };
+        *count += 1;
+    }
+    summary
+}
+
+// Net change to the note list over one debounced batch of events
+#[derive(Clone, Serialize, Deserialize, Default)]
+pub struct NoteListDiffPayload {
+    pub created: Vec<NoteEventPayload>,
+    pub updated: Vec<NoteEventPayload>,
+    pub removed: Vec<String>,
 }
 
 fn get_note_metadata(path: &Path) -> Option<NoteEventPayload> {
-    if path.extension().and_then(|s| s.to_str()) != Some("md") {
+    // The old name of a case-only rename still resolves on case-insensitive
+    // filesystems, but is gone as far as the list is concerned
+    if !vault::is_markdown_file(path) || !vault::exists_with_exact_case(path) {
         return None;
     }
 
     let metadata = fs::metadata(path).ok()?;
-    let modified = metadata
-        .modified()
-        .ok()?
-        .duration_since(std::time::UNIX_EPOCH)
-        .ok()?
-        .as_secs();
+    let modified = vault::unix_secs(metadata.modified().ok()?);
 
-    let title = extract_title_from_filename(path);
+    let title = vault::extract_title_from_filename(path);
 
     Some(NoteEventPayload {
-        path: path.to_string_lossy().to_string(),
-        name: path.file_name()?.to_string_lossy().to_string(),
+        path: vault::path_to_string(path).ok()?,
+        name: vault::file_name_to_string(path).ok()?,
         title: Some(title),
         modified: Some(modified),
     })
 }
 
-fn emit_note_list_updated(app: &AppHandle, notes_dir: &Path) {
-    if let Ok(entries) = fs::read_dir(notes_dir) {
-        let mut notes = Vec::new();
+/// Emit the full note list. Only used when the frontend asks for a resync;
+/// the watcher itself sends `note:list-diff`.
+pub fn emit_note_list_updated(app: &AppHandle, notes: Vec<NoteEventPayload>) {
+    let _ = app.emit("note:list-updated", NoteListPayload { notes });
+}
 
-        for entry in entries.filter_map(Result::ok) {
-            let path = entry.path();
-            if let Some(note_payload) = get_note_metadata(&path) {
-                notes.push(note_payload);
-            }
+// Collapse every path touched in a batch into its net effect, stat-ing each
+// path once. `created_in_batch` is whether the first event seen for the
+// path was a create, i.e. the frontend didn't know about it before.
+fn emit_note_list_diff(app: &AppHandle, changed: HashMap<PathBuf, bool>) {
+    let mut diff = NoteListDiffPayload::default();
+
+    for (path, created_in_batch) in changed {
+        match (get_note_metadata(&path), created_in_batch) {
+            (Some(payload), true) => diff.created.push(payload),
+            (Some(payload), false) => diff.updated.push(payload),
+            // Created and deleted again within the batch
+            (None, true) => {}
+            (None, false) => diff.removed.push(path.to_string_lossy().to_string()),
         }
+    }
 
-        // Sort by modified time (newest first)
-        notes.sort_by(|a, b| b.modified.unwrap_or(0).cmp(&a.modified.unwrap_or(0)));
+    let _ = app.emit("note:list-diff", diff);
+}
 
-        let _ = app.emit("note:list-updated", NoteListPayload { notes });
+// Push checkbox changes in notes that opt into task sync to todo.txt
+fn sync_note_tasks(app: &AppHandle, vault_path: &str, paths: &[PathBuf]) {
+    match task_sync::sync_notes(&app.state::<TodoStore>(), vault_path, paths) {
+        Ok(true) => {
+            app.state::<QuickSwitcher>().invalidate_todos();
+            let _ = app.emit("todos_changed", ());
+        }
+        Ok(false) => {}
+        Err(e) => tracing::warn!("{}", e),
+    }
+}
+
+// Re-index a changed note, or drop it if it no longer exists
+fn update_index(app: &AppHandle, vault_path: &str, path: &Path) {
+    let result = app.state::<IndexState>().update(vault_path, |index| {
+        if vault::exists_with_exact_case(path) {
+            index.upsert_note(path)
+        } else {
+            index.remove_note(path)
+        }
+    });
+
+    if let Some(Err(e)) = result {
+        tracing::warn!("{}", e);
+    }
+}
+
+// Keep the subscribed calendar file in step with todo.txt, whoever edited it
+fn refresh_calendar_export(app: &AppHandle, vault_path: &str) {
+    let Ok(app_settings) = settings::load_settings(app) else {
+        return;
+    };
+
+    if let Some(output_path) = app_settings.calendar_export_target(vault_path) {
+        if let Err(e) = ics::export_calendar(vault_path, &output_path) {
+            tracing::warn!("{}", e);
+        }
     }
 }
 
 pub fn setup_watcher(
     app: AppHandle,
     vault_path: String,
-) -> Result<Debouncer<RecommendedWatcher, RecommendedCache>, String> {
+    debounce_ms: u64,
+) -> Result<VaultWatcher, BouldyError> {
     let vault = PathBuf::from(&vault_path);
     let notes_dir = vault.join("notes");
     let prompts_dir = vault.join("prompts");
     let todo_file = vault.join("todo.txt");
+    let habits_file = vault.join(habits::HABITS_FILE);
+    let boards_dir = vault.join(boards::BOARDS_DIR);
+    let canvas_dir = vault.join(canvas::CANVAS_DIR);
+    let bookmarks_file = vault.join(bookmarks::BOOKMARKS_FILE);
+    let someday_file = vault.join(someday::SOMEDAY_FILE);
+    let inbox_file = vault.join(inbox::INBOX_FILE);
+    let pomodoro_file = vault.join(pomodoro::POMODORO_FILE);
+    let snippets_dir = vault.join(snippets::SNIPPETS_DIR);
+    // Obsidian vaults may keep attachments in the root, which is watched
+    // already; only a folder of their own is worth watching for them
+    let assets_dir = vault_config::load(&vault_path)
+        .map(|config| config.attachments_dir(&vault_path, None))
+        .ok()
+        .filter(|dir| dir != &vault && dir != &notes_dir);
 
     if !notes_dir.exists() {
-        return Err("Notes directory does not exist".to_string());
+        return Err(BouldyError::not_found("Notes directory does not exist"));
     }
 
     // Create prompts directory if it doesn't exist
@@ -92,17 +248,73 @@ pub fn setup_watcher(
     let notes_dir_clone = notes_dir.clone();
     let prompts_dir_clone = prompts_dir.clone();
     let todo_file_clone = todo_file.clone();
+    let habits_file_clone = habits_file.clone();
+    let boards_dir_clone = boards_dir.clone();
+    let canvas_dir_clone = canvas_dir.clone();
+    let bookmarks_file_clone = bookmarks_file.clone();
+    let someday_file_clone = someday_file.clone();
+    let inbox_file_clone = inbox_file.clone();
+    let pomodoro_file_clone = pomodoro_file.clone();
+    let snippets_dir_clone = snippets_dir.clone();
+    let assets_dir_clone = assets_dir.clone();
+    let vault_path_clone = vault_path.clone();
 
     let mut debouncer = new_debouncer(
-        Duration::from_millis(500),
+        Duration::from_millis(debounce_ms),
         None,
         move |result: DebounceEventResult| {
             match result {
                 Ok(events) => {
-                    let mut should_update_note_list = false;
+                    let mut changed_notes: HashMap<PathBuf, bool> = HashMap::new();
                     let mut should_update_todos = false;
+                    let mut should_update_habits = false;
+                    let mut should_update_bookmarks = false;
+                    let mut should_update_someday = false;
+                    let mut should_update_inbox = false;
+                    let mut should_update_focus_plan = false;
+                    let mut should_update_snippets = false;
+                    let mut file_events: Vec<FileEvent> = Vec::new();
+                    let mut changed_boards: Vec<PathBuf> = Vec::new();
+                    let mut changed_canvases: Vec<PathBuf> = Vec::new();
 
                     for event in events {
+                        // One event for both names of a rename, so the
+                        // frontend can move the note instead of seeing a
+                        // delete and a create (or, for a case-only rename on
+                        // macOS and Windows, two updates)
+                        if let (
+                            notify::EventKind::Modify(ModifyKind::Name(RenameMode::Both)),
+                            [from, to],
+                        ) = (event.kind, event.paths.as_slice())
+                        {
+                            if from.starts_with(&notes_dir_clone)
+                                && to.starts_with(&notes_dir_clone)
+                                && vault::is_markdown_file(from)
+                                && vault::is_markdown_file(to)
+                            {
+                                for path in [from, to] {
+                                    app_clone.state::<NoteCache>().invalidate(path);
+                                    update_index(&app_clone, &vault_path_clone, path);
+                                }
+                                changed_notes.entry(from.clone()).or_insert(false);
+                                changed_notes.entry(to.clone()).or_insert(true);
+
+                                if let Some(payload) = get_note_metadata(to) {
+                                    windows::note_renamed(
+                                        &app_clone,
+                                        &from.to_string_lossy(),
+                                        &payload.path,
+                                    );
+                                    file_events.push(FileEvent::NoteRenamed(NoteRenamedPayload {
+                                        from: from.to_string_lossy().to_string(),
+                                        to: payload,
+                                        case_only: from != to && vault::same_file_path(from, to),
+                                    }));
+                                }
+                                continue;
+                            }
+                        }
+
                         for path in &event.paths {
                             // Check if this is the todo.txt file
                             if path == &todo_file_clone {
@@ -118,24 +330,104 @@ pub fn setup_watcher(
                                 continue;
                             }
 
+                            // Check-ins from the app or an editor
+                            if path == &habits_file_clone {
+                                should_update_habits = true;
+                                continue;
+                            }
+
+                            if path == &bookmarks_file_clone {
+                                should_update_bookmarks = true;
+                                continue;
+                            }
+
+                            if path == &someday_file_clone {
+                                should_update_someday = true;
+                                continue;
+                            }
+
+                            if path == &inbox_file_clone {
+                                should_update_inbox = true;
+                                continue;
+                            }
+
+                            // A finished session moves the day's focus plan on
+                            if path == &pomodoro_file_clone {
+                                should_update_focus_plan = true;
+                                continue;
+                            }
+
+                            if path.starts_with(&snippets_dir_clone) {
+                                should_update_snippets = true;
+                                continue;
+                            }
+
+                            if assets_dir_clone
+                                .as_ref()
+                                .is_some_and(|dir| path.starts_with(dir))
+                                && !vault::is_markdown_file(path)
+                            {
+                                let created = match event.kind {
+                                    notify::EventKind::Create(_) => true,
+                                    notify::EventKind::Remove(_) => false,
+                                    // Renames come as one event per name
+                                    notify::EventKind::Modify(ModifyKind::Name(_)) => path.exists(),
+                                    _ => continue,
+                                };
+                                let payload = AssetEventPayload {
+                                    path: path.to_string_lossy().to_string(),
+                                    name: path
+                                        .file_name()
+                                        .unwrap_or_default()
+                                        .to_string_lossy()
+                                        .to_string(),
+                                };
+                                file_events.push(if created {
+                                    FileEvent::AssetCreated(payload)
+                                } else {
+                                    FileEvent::AssetDeleted(payload)
+                                });
+                                continue;
+                            }
+
+                            // Canvases are JSON, not markdown
+                            if path.starts_with(&canvas_dir_clone) && canvas::is_canvas_file(path) {
+                                if !changed_canvases.contains(path) {
+                                    changed_canvases.push(path.clone());
+                                }
+                                continue;
+                            }
+
                             // Only process .md files
-                            if path.extension().and_then(|s| s.to_str()) != Some("md") {
+                            if !vault::is_markdown_file(path) {
                                 continue;
                             }
 
                             // Check if this is a notes file
                             if path.starts_with(&notes_dir_clone) {
+                                if matches!(
+                                    event.kind,
+                                    notify::EventKind::Create(_)
+                                        | notify::EventKind::Modify(_)
+                                        | notify::EventKind::Remove(_)
+                                ) {
+                                    app_clone.state::<NoteCache>().invalidate(path);
+                                    update_index(&app_clone, &vault_path_clone, path);
+                                    changed_notes.entry(path.clone()).or_insert(matches!(
+                                        event.kind,
+                                        notify::EventKind::Create(_)
+                                    ));
+                                }
+
                                 match event.kind {
                                     notify::EventKind::Create(_) => {
                                         if let Some(payload) = get_note_metadata(path) {
-                                            let _ = app_clone.emit("note:created", payload);
-                                            should_update_note_list = true;
+                                            file_events.push(FileEvent::NoteCreated(payload));
                                         }
                                     }
                                     notify::EventKind::Modify(_) => {
                                         if let Some(payload) = get_note_metadata(path) {
-                                            let _ = app_clone.emit("note:updated", payload);
-                                            should_update_note_list = true;
+                                            file_events.push(FileEvent::NoteUpdated(payload));
                                         }
                                     }
                                     notify::EventKind::Remove(_) => {
@@ -149,28 +441,112 @@ pub fn setup_watcher(
                                             title: None,
                                             modified: None,
                                         };
-                                        let _ = app_clone.emit("note:deleted", payload);
-                                        should_update_note_list = true;
+                                        file_events.push(FileEvent::NoteDeleted(payload));
                                     }
                                     _ => {}
                                 }
                             }
+                            // Boards are written by the board commands or an editor
+                            else if path.starts_with(&boards_dir_clone) {
+                                if !changed_boards.contains(path) {
+                                    changed_boards.push(path.clone());
+                                }
+                            }
                             // Check if this is a prompts file
                             else if path.starts_with(&prompts_dir_clone) {
                                 // Prompts are handled by write_prompt, delete_prompt commands
-                                // which already emit events, so we don't need to emit here
+                                // which already emit events, so we only refresh the index here
+                                app_clone.state::<QuickSwitcher>().invalidate_prompts();
                             }
                         }
                     }
 
-                    // Emit full list update if any notes changed
-                    if should_update_note_list {
-                        emit_note_list_updated(&app_clone, &notes_dir_clone);
+                    let existing_notes: Vec<PathBuf> = changed_notes
+                        .keys()
+                        .filter(|path| path.exists())
+                        .cloned()
+                        .collect();
+                    if !existing_notes.is_empty() {
+                        sync_note_tasks(&app_clone, &vault_path_clone, &existing_notes);
+                    }
+
+                    if !changed_notes.is_empty() {
+                        app_clone.state::<QuickSwitcher>().invalidate_notes();
+                    }
+                    if file_events.len() > BULK_EVENT_THRESHOLD {
+                        // The frontend reloads everything, so no list diff
+                        let _ = app_clone.emit("vault:bulk-change", summarize(&file_events));
+                    } else {
+                        for event in file_events {
+                            event.emit(&app_clone);
+                        }
+                        // Emit what changed rather than re-reading the whole directory
+                        if !changed_notes.is_empty() {
+                            emit_note_list_diff(&app_clone, changed_notes);
+                        }
                     }
 
                     // Emit todos changed event if todo.txt was modified
                     if should_update_todos {
+                        match app_clone.state::<TodoStore>().reload() {
+                            Ok(Some(conflict)) => {
+                                tracing::warn!(
+                                    "{} todo edits clashed with todo.txt, kept in {}",
+                                    conflict.todos.len(),
+                                    conflict.path
+                                );
+                                let _ = app_clone.emit("todos:conflict", conflict);
+                            }
+                            Ok(None) => {}
+                            Err(e) => tracing::warn!("{}", e),
+                        }
+                        // Carry todo.txt completions back into synced notes
+                        if let Err(e) = task_sync::sync_vault(
+                            &app_clone.state::<TodoStore>(),
+                            &vault_path_clone,
+                        ) {
+                            tracing::warn!("{}", e);
+                        }
+                        app_clone.state::<QuickSwitcher>().invalidate_todos();
                         let _ = app_clone.emit("todos_changed", ());
+                        refresh_calendar_export(&app_clone, &vault_path_clone);
+                        if let Err(e) = badge::refresh(&app_clone, &vault_path_clone) {
+                            tracing::warn!("{}", e);
+                        }
+                    }
+
+                    if should_update_habits {
+                        let _ = app_clone.emit("habits_changed", ());
+                    }
+
+                    if should_update_bookmarks {
+                        let _ = app_clone.emit("bookmarks_changed", ());
+                    }
+
+                    if should_update_someday {
+                        let _ = app_clone.emit("someday_changed", ());
+                    }
+
+                    if should_update_inbox {
+                        let _ = app_clone.emit("inbox_changed", ());
+                    }
+
+                    if should_update_snippets {
+                        let _ = app_clone.emit("snippets_changed", ());
+                    }
+
+                    if should_update_focus_plan {
+                        let _ = app_clone.emit("focus_plan_changed", ());
+                    }
+
+                    for path in changed_boards {
+                        let _ =
+                            app_clone.emit("boards_changed", path.to_string_lossy().to_string());
+                    }
+
+                    for path in changed_canvases {
+                        let _ =
+                            app_clone.emit("canvas_changed", path.to_string_lossy().to_string());
                     }
                 }
                 Err(_errors) => {
@@ -191,6 +567,28 @@ pub fn setup_watcher(
     debouncer
         .watch(&prompts_dir, RecursiveMode::NonRecursive)
         .map_err(|e| format!("Failed to watch prompts directory: {}", e))?;
+    // Only there once the first board is made; picked up on the next open
+    if boards_dir.is_dir() {
+        debouncer
+            .watch(&boards_dir, RecursiveMode::NonRecursive)
+            .map_err(|e| format!("Failed to watch boards directory: {}", e))?;
+    }
+    if canvas_dir.is_dir() {
+        debouncer
+            .watch(&canvas_dir, RecursiveMode::NonRecursive)
+            .map_err(|e| format!("Failed to watch canvas directory: {}", e))?;
+    }
+    // Attachments may be sorted into subfolders
+    if let Some(assets_dir) = assets_dir.filter(|dir| dir.is_dir()) {
+        debouncer
+            .watch(&assets_dir, RecursiveMode::Recursive)
+            .map_err(|e| format!("Failed to watch attachments directory: {}", e))?;
+    }
+    if snippets_dir.is_dir() {
+        debouncer
+            .watch(&snippets_dir, RecursiveMode::NonRecursive)
+            .map_err(|e| format!("Failed to watch snippets directory: {}", e))?;
+    }
 
     Ok(debouncer)
 }
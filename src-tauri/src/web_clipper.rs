@@ -0,0 +1,161 @@
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::BouldyError;
+use crate::file_drop::unique_destination;
+use crate::local_server::{LocalServer, Request, Response, ServerConfig};
+use crate::{fsutil, html_markdown, markdown, secrets, settings, vault, vault_lock};
+
+pub const DEFAULT_PORT: u16 = 27124;
+pub const CLIPPINGS_DIR: &str = "clippings";
+
+const SECRET_SERVICE: &str = "bouldy-web-clipper";
+const SECRET_ACCOUNT: &str = "token";
+
+/// Managed state: the listener, while it runs.
+#[derive(Default)]
+pub struct WebClipper {
+    server: LocalServer,
+}
+
+/// What the browser extension posts to `/clip`.
+#[derive(Deserialize, Clone, Debug)]
+pub struct Clip {
+    pub url: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    // HTML of the selection; empty clips just the link
+    #[serde(default)]
+    pub selection: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ClipSaved {
+    pub path: String,
+    pub title: String,
+    pub url: String,
+}
+
+// === Token ===
+
+/// The token the extension must send as `Authorization: Bearer <token>`,
+/// created on first use.
+pub fn token() -> Result<String, BouldyError> {
+    match secrets::get(SECRET_SERVICE, SECRET_ACCOUNT)? {
+        Some(token) => Ok(token),
+        None => reset_token(),
+    }
+}
+
+/// Replace the token, locking out extensions set up with the old one.
+pub fn reset_token() -> Result<String, BouldyError> {
+    let token = uuid::Uuid::new_v4().simple().to_string();
+    secrets::set(SECRET_SERVICE, SECRET_ACCOUNT, &token)?;
+    Ok(token)
+}
+
+// === Saving ===
+
+/// Convert a clip to markdown and save it as a new note in `clippings/`,
+/// with the source URL and clip time in its frontmatter.
+pub fn save_clip(vault_path: &str, clip: &Clip) -> Result<ClipSaved, BouldyError> {
+    let url = Url::parse(clip.url.trim())
+        .ok()
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+        .ok_or_else(|| BouldyError::invalid_path(format!("Not a web page: '{}'", clip.url)))?;
+
+    let title = clip
+        .title
+        .as_deref()
+        .map(|title| title.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|title| !title.is_empty())
+        .unwrap_or_else(|| url.host_str().unwrap_or("Clipping").to_string());
+
+    let body = clip
+        .selection
+        .as_deref()
+        .map(|selection| html_markdown::convert(selection, Some(&url)))
+        .filter(|body| !body.is_empty())
+        .unwrap_or_else(|| format!("<{}>", url));
+
+    let mut tags = vec![CLIPPINGS_DIR.to_string()];
+    for tag in &clip.tags {
+        let tag = tag.trim().trim_start_matches('#');
+        if !tag.is_empty() && !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+    let tags: Vec<String> = tags.iter().map(|tag| markdown::yaml_string(tag)).collect();
+
+    let content = format!(
+        "---\ntitle: {}\nsource: {}\nclipped: {}\ntags: [{}]\n---\n\n{}\n",
+        markdown::yaml_string(&title),
+        markdown::yaml_string(url.as_str()),
+        chrono::Local::now().to_rfc3339(),
+        tags.join(", "),
+        body
+    );
+
+    let dir = vault::notes_dir(vault_path).join(CLIPPINGS_DIR);
+    let _lock = vault_lock::acquire(vault_path)?;
+    fs::create_dir_all(&dir)
+        .map_err(|e| BouldyError::io("Failed to create clippings folder", e))?;
+    let path: PathBuf =
+        unique_destination(&dir, &vault::sanitize_file_name(&format!("{}.md", title)));
+    fsutil::write_atomic(&path, content)
+        .map_err(|e| BouldyError::io("Failed to write clipping", e))?;
+
+    Ok(ClipSaved {
+        path: path.to_string_lossy().to_string(),
+        title,
+        url: url.to_string(),
+    })
+}
+
+// === Server ===
+
+fn handle_request(app: &AppHandle, request: Request) -> Response {
+    match (request.method.as_str(), request.path.as_str()) {
+        // Lets the extension check its token
+        ("GET", "/status") => Response::json("200 OK", json!({ "app": "bouldy" })),
+        ("POST", "/clip") => {
+            let result = serde_json::from_slice::<Clip>(&request.body)
+                .map_err(|e| BouldyError::parse("Invalid clip", e))
+                .and_then(|clip| {
+                    let vault_path = settings::require_vault_path(app)?;
+                    save_clip(&vault_path, &clip)
+                });
+            match result {
+                Ok(saved) => {
+                    let _ = app.emit("web-clipper:clipped", &saved);
+                    Response::json("201 Created", json!({ "path": saved.path }))
+                }
+                Err(e) => Response::error("422 Unprocessable Entity", e.message()),
+            }
+        }
+        _ => Response::error("404 Not Found", "Not found"),
+    }
+}
+
+/// Listen on `127.0.0.1:port` until `stop` is called.
+pub fn start(app: &AppHandle, port: u16) -> Result<(), BouldyError> {
+    let config = ServerConfig {
+        name: "web clipper",
+        token,
+        cors: true,
+    };
+    app.state::<WebClipper>()
+        .server
+        .start(app, port, config, handle_request)
+}
+
+/// Stop the listener, if it's running.
+pub fn stop(app: &AppHandle) {
+    app.state::<WebClipper>().server.stop();
+}
@@ -0,0 +1,290 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Timelike, Weekday};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::calendar;
+use crate::error::BouldyError;
+use crate::locale::LocaleInfo;
+use crate::todo_store::TodoStore;
+use crate::todos::{self, TodoItem};
+use crate::{fsutil, journal, settings, timesheet, vault, vault_lock};
+
+const CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+// Scheduled reviews wait for Sunday evening so most of the week is in
+const SCHEDULED_HOUR: u32 = 18;
+// Journal highlights are the first line of each entry, cut to this
+const MAX_HIGHLIGHT_CHARS: usize = 200;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct WeeklyReview {
+    pub path: String,
+    pub week: String,
+    pub title: String,
+}
+
+/// A `YYYY-Www` ISO week, Monday to Sunday.
+struct Week {
+    monday: NaiveDate,
+}
+
+impl Week {
+    fn parse(week: &str) -> Result<Self, BouldyError> {
+        let invalid = || format!("Invalid week '{}'. Expected YYYY-Www", week);
+        let (year, number) = week.trim().split_once("-W").ok_or_else(invalid)?;
+        let year: i32 = year.parse().map_err(|_| invalid())?;
+        let number: u32 = number.parse().map_err(|_| invalid())?;
+        let monday = NaiveDate::from_isoywd_opt(year, number, Weekday::Mon).ok_or_else(invalid)?;
+        Ok(Week { monday })
+    }
+
+    fn containing(date: NaiveDate) -> Self {
+        Week {
+            monday: date - Duration::days(date.weekday().num_days_from_monday().into()),
+        }
+    }
+
+    fn sunday(&self) -> NaiveDate {
+        self.monday + Duration::days(6)
+    }
+
+    fn contains(&self, date: NaiveDate) -> bool {
+        (self.monday..=self.sunday()).contains(&date)
+    }
+
+    fn label(&self) -> String {
+        let iso = self.monday.iso_week();
+        format!("{}-W{:02}", iso.year(), iso.week())
+    }
+
+    fn title(&self) -> String {
+        format!("Weekly Review {}", self.label())
+    }
+}
+
+fn review_path(vault_path: &str, week: &Week) -> PathBuf {
+    vault::notes_dir(vault_path).join(format!("{}.md", week.title()))
+}
+
+fn todo_line(todo: &TodoItem) -> String {
+    match todo.due_date {
+        Some(ref due) => format!("- {} (due {})\n", todo.title, due),
+        None => format!("- {}\n", todo.title),
+    }
+}
+
+fn note_section(report: &mut String, heading: &str, titles: &[String]) {
+    report.push_str(&format!("\n### {} ({})\n\n", heading, titles.len()));
+    if titles.is_empty() {
+        report.push_str("None\n");
+    }
+    for title in titles {
+        report.push_str(&format!("- [[{}]]\n", title));
+    }
+}
+
+// The first line written under the prompts, skipping headings
+fn highlight(body: &str) -> Option<String> {
+    let line = body
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))?;
+    let mut highlight: String = line.chars().take(MAX_HIGHLIGHT_CHARS).collect();
+    if highlight.len() < line.len() {
+        highlight.push('…');
+    }
+    Some(highlight)
+}
+
+fn compose(
+    store: &TodoStore,
+    vault_path: &str,
+    week: &Week,
+    locale: &LocaleInfo,
+) -> Result<String, BouldyError> {
+    let now = todos::local_now();
+    let week_end = NaiveDateTime::from(week.sunday().succ_opt().unwrap_or(week.sunday()));
+    let path = review_path(vault_path, week);
+
+    // Notes
+    let notes = if vault::notes_dir(vault_path).exists() {
+        vault::list_notes(vault_path)?
+    } else {
+        Vec::new()
+    };
+    let mut created = Vec::new();
+    let mut edited = Vec::new();
+    for note in notes.iter().filter(|note| Path::new(&note.path) != path) {
+        if calendar::note_created(&note.path).is_some_and(|date| week.contains(date)) {
+            created.push(note.title.clone());
+        } else if chrono::DateTime::from_timestamp(note.modified as i64, 0)
+            .map(|at| at.with_timezone(&chrono::Local).date_naive())
+            .is_some_and(|date| week.contains(date))
+        {
+            edited.push(note.title.clone());
+        }
+    }
+
+    // Todos: archived ones carry their completion date; ones still in
+    // todo.txt don't, so they only count toward the current week
+    let todo_list = store.load(vault_path)?;
+    let mut completed: Vec<String> = Vec::new();
+    let mut months = vec![week.monday.format("%Y-%m").to_string()];
+    if week.sunday().month() != week.monday.month() {
+        months.push(week.sunday().format("%Y-%m").to_string());
+    }
+    for month in &months {
+        for archived in todos::load_archived_todos(vault_path, month)? {
+            if NaiveDate::parse_from_str(&archived.completed_date, "%Y-%m-%d")
+                .is_ok_and(|date| week.contains(date))
+            {
+                completed.push(archived.title);
+            }
+        }
+    }
+    if week.contains(now.date()) {
+        completed.extend(
+            todo_list
+                .iter()
+                .filter(|t| t.completed)
+                .map(|t| t.title.clone()),
+        );
+    }
+    let overdue_at = now.min(week_end);
+    let overdue: Vec<&TodoItem> = todo_list
+        .iter()
+        .filter(|t| todos::is_overdue(t, overdue_at))
+        .collect();
+
+    // Focus time
+    let pomodoros: Vec<i64> = timesheet::load_pomodoros(vault_path)
+        .into_iter()
+        .filter(|(start, _)| week.contains(start.date()))
+        .map(|(start, end)| (end - start).num_minutes().max(0))
+        .collect();
+    let focus_minutes: i64 = pomodoros.iter().sum();
+
+    let journal = journal::entries_between(vault_path, week.monday, week.sunday());
+
+    let mut report = format!(
+        "---\nweek: {}\ncreated: {}\ntags: [\"weekly-review\"]\n---\n\n# {}\n\n{} – {}\n",
+        week.label(),
+        now.format("%Y-%m-%dT%H:%M"),
+        week.title(),
+        locale.format_month_day(week.monday),
+        locale.format_date(week.sunday()),
+    );
+
+    report.push_str("\n## Notes\n");
+    note_section(&mut report, "Created", &created);
+    note_section(&mut report, "Edited", &edited);
+
+    report.push_str(&format!("\n## Completed todos ({})\n\n", completed.len()));
+    if completed.is_empty() {
+        report.push_str("None\n");
+    }
+    for title in &completed {
+        report.push_str(&format!("- [x] {}\n", title));
+    }
+
+    report.push_str(&format!("\n## Overdue ({})\n\n", overdue.len()));
+    if overdue.is_empty() {
+        report.push_str("None\n");
+    }
+    for todo in &overdue {
+        report.push_str(&todo_line(todo));
+    }
+
+    report.push_str(&format!(
+        "\n## Focus\n\n{} pomodoros, {}h {:02}m\n",
+        pomodoros.len(),
+        focus_minutes / 60,
+        focus_minutes % 60
+    ));
+
+    report.push_str("\n## Journal\n\n");
+    if journal.is_empty() {
+        report.push_str("No entries\n");
+    }
+    for (day, body) in &journal {
+        let date = NaiveDate::parse_from_str(&day.date, "%Y-%m-%d")
+            .map(|date| locale.weekday_name(date))
+            .unwrap_or_else(|_| day.date.clone());
+        let mut line = format!("- **{}**", date);
+        if let Some(mood) = day.mood {
+            line.push_str(&format!(" mood {}/{}", mood, journal::MAX_RATING));
+        }
+        if let Some(energy) = day.energy {
+            line.push_str(&format!(" energy {}/{}", energy, journal::MAX_RATING));
+        }
+        if let Some(highlight) = highlight(body) {
+            line.push_str(&format!(": {}", highlight));
+        }
+        report.push_str(&line);
+        report.push('\n');
+    }
+
+    Ok(report)
+}
+
+/// Write the review note for `week` (`YYYY-Www`, this week by default),
+/// replacing an earlier one for the same week.
+pub fn generate(
+    store: &TodoStore,
+    vault_path: &str,
+    week: Option<&str>,
+    locale: &LocaleInfo,
+) -> Result<WeeklyReview, BouldyError> {
+    let week = match week {
+        Some(week) => Week::parse(week)?,
+        None => Week::containing(todos::local_now().date()),
+    };
+    let report = compose(store, vault_path, &week, locale)?;
+    let path = review_path(vault_path, &week);
+
+    let _lock = vault_lock::acquire(vault_path)?;
+    fs::create_dir_all(vault::notes_dir(vault_path))
+        .map_err(|e| BouldyError::io("Failed to create notes folder", e))?;
+    fsutil::write_atomic(&path, report)
+        .map_err(|e| BouldyError::io("Failed to write weekly review", e))?;
+
+    Ok(WeeklyReview {
+        path: path.to_string_lossy().to_string(),
+        week: week.label(),
+        title: week.title(),
+    })
+}
+
+// Runs once per week, from Sunday evening, unless the review already exists
+fn generate_if_due(app: &AppHandle) -> Result<(), BouldyError> {
+    let app_settings = settings::load_settings(app)?;
+    let locale = app_settings.locale_info();
+    let (true, Some(vault_path)) = (app_settings.weekly_review_enabled, app_settings.vault_path)
+    else {
+        return Ok(());
+    };
+    let now = todos::local_now();
+    if now.weekday() != Weekday::Sun || now.hour() < SCHEDULED_HOUR {
+        return Ok(());
+    }
+    if review_path(&vault_path, &Week::containing(now.date())).exists() {
+        return Ok(());
+    }
+
+    let review = generate(&app.state::<TodoStore>(), &vault_path, None, &locale)?;
+    let _ = app.emit("weekly-review:generated", &review);
+    Ok(())
+}
+
+/// Start the background schedule. Settings are re-read on every tick, so
+/// turning it on or off needs no restart.
+pub fn start(app: AppHandle) {
+    thread::spawn(move || loop {
+        if let Err(e) = generate_if_due(&app) {
+            tracing::warn!("Weekly review failed - {}", e);
+        }
+        thread::sleep(CHECK_INTERVAL);
+    });
+}
@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder};
+
+use crate::error::BouldyError;
+use crate::todo_store::TodoStore;
+use crate::todos::{self, TodoItem};
+use crate::{focus_plan, settings};
+
+pub const WIDGET_WINDOW: &str = "widget";
+const TOP_TODOS: usize = 3;
+const UPDATE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The timer as the main window sees it, sent whenever it changes.
+#[derive(Deserialize, Clone, Debug)]
+pub struct TimerReport {
+    // "work", "short-break" or "long-break"
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub running: bool,
+    #[serde(rename = "timeRemaining")]
+    pub time_remaining: u64,
+}
+
+// The last report and when it came, so the widget keeps counting down after
+// the main window is closed
+#[derive(Default)]
+pub struct TimerState(Mutex<Option<(TimerReport, Instant)>>);
+
+impl TimerState {
+    pub fn report(&self, report: Option<TimerReport>) {
+        *self.0.lock().unwrap() = report.map(|report| (report, Instant::now()));
+    }
+
+    fn current(&self) -> Option<WidgetTimer> {
+        let state = self.0.lock().unwrap();
+        let (report, at) = state.as_ref()?;
+        let elapsed = if report.running {
+            at.elapsed().as_secs()
+        } else {
+            0
+        };
+        Some(WidgetTimer {
+            kind: report.kind.clone(),
+            running: report.running,
+            time_remaining: report.time_remaining.saturating_sub(elapsed),
+        })
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct WidgetTimer {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub running: bool,
+    #[serde(rename = "timeRemaining")]
+    pub time_remaining: u64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct WidgetData {
+    // None when no session is running
+    pub timer: Option<WidgetTimer>,
+    // The most important open todos for today, as the focus plan ranks them
+    pub todos: Vec<TodoItem>,
+}
+
+pub fn widget_data(app: &AppHandle) -> Result<WidgetData, BouldyError> {
+    let todos = match settings::load_settings(app)?.vault_path {
+        Some(vault_path) => {
+            let todos_list = app.state::<TodoStore>().load(&vault_path)?;
+            focus_plan::candidates(&todos_list, todos::local_now().date())
+                .into_iter()
+                .take(TOP_TODOS)
+                .cloned()
+                .collect()
+        }
+        None => Vec::new(),
+    };
+    Ok(WidgetData {
+        timer: app.state::<TimerState>().current(),
+        todos,
+    })
+}
+
+/// Open the always-on-top widget, or focus it if it's already open. While
+/// it's open the backend sends it `widget:update` every second, so it needs
+/// nothing from the main window.
+pub fn show_widget_window(app: &AppHandle) -> Result<(), BouldyError> {
+    if let Some(window) = app.get_webview_window(WIDGET_WINDOW) {
+        let _ = window.show();
+        let _ = window.set_focus();
+        return Ok(());
+    }
+
+    WebviewWindowBuilder::new(
+        app,
+        WIDGET_WINDOW,
+        WebviewUrl::App("index.html?window=widget".into()),
+    )
+    .title("Bouldy")
+    .inner_size(280.0, 180.0)
+    .resizable(false)
+    .decorations(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .build()
+    .map_err(|e| format!("Failed to open widget: {}", e))?;
+
+    let app = app.clone();
+    thread::spawn(move || {
+        while app.get_webview_window(WIDGET_WINDOW).is_some() {
+            match widget_data(&app) {
+                Ok(data) => {
+                    let _ = app.emit_to(WIDGET_WINDOW, "widget:update", data);
+                }
+                Err(e) => tracing::warn!("Failed to update widget - {}", e),
+            }
+            thread::sleep(UPDATE_INTERVAL);
+        }
+    });
+
+    Ok(())
+}
@@ -0,0 +1,127 @@
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+
+use crate::error::BouldyError;
+use crate::{settings, vault};
+
+pub const MAIN_WINDOW: &str = "main";
+pub const NOTE_WINDOW_PREFIX: &str = "note-";
+
+/// Bring the main window to the front, restoring it if minimized or hidden.
+pub fn focus_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+// Open note windows: label -> the note each one shows
+#[derive(Default)]
+pub struct NoteWindows(Mutex<HashMap<String, String>>);
+
+// The same note always gets the same label, so the window-state plugin
+// brings back the size and position it was last closed with
+fn note_window_label(path: &Path) -> String {
+    let digest = Sha256::digest(path.to_string_lossy().as_bytes());
+    let hash: String = digest[..8].iter().map(|b| format!("{:02x}", b)).collect();
+    format!("{}{}", NOTE_WINDOW_PREFIX, hash)
+}
+
+/// Open `path` in a window of its own, or focus the one already showing it.
+/// Returns the window's label.
+pub fn open_note_window(app: &AppHandle, path: &str) -> Result<String, BouldyError> {
+    let vault_path = settings::require_vault_path(app)?;
+    let file = vault::validate_path_in_vault(&vault_path, path)?;
+    if !file.is_file() {
+        return Err(BouldyError::not_found(format!("Note not found: {}", path)));
+    }
+
+    let label = note_window_label(&file);
+    if let Some(window) = app.get_webview_window(&label) {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+        return Ok(label);
+    }
+
+    let window = WebviewWindowBuilder::new(
+        app,
+        &label,
+        WebviewUrl::App("index.html?window=note".into()),
+    )
+    .title(vault::extract_title_from_filename(&file))
+    .inner_size(720.0, 800.0)
+    .min_inner_size(360.0, 300.0)
+    .focused(true)
+    .build()
+    .map_err(|e| format!("Failed to open note window: {}", e))?;
+
+    let windows = app.state::<NoteWindows>();
+    windows
+        .0
+        .lock()
+        .unwrap()
+        .insert(label.clone(), file.to_string_lossy().to_string());
+
+    let app_handle = app.clone();
+    let closed = label.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::Destroyed = event {
+            app_handle
+                .state::<NoteWindows>()
+                .0
+                .lock()
+                .unwrap()
+                .remove(&closed);
+        }
+    });
+
+    Ok(label)
+}
+
+/// The note shown in window `label`, if it's a note window.
+pub fn window_note(app: &AppHandle, label: &str) -> Option<String> {
+    app.state::<NoteWindows>()
+        .0
+        .lock()
+        .unwrap()
+        .get(label)
+        .cloned()
+}
+
+/// Keep windows on a renamed note showing it, under its new title. Their
+/// webviews learn the new path from the `note:renamed` event.
+pub fn note_renamed(app: &AppHandle, from: &str, to: &str) {
+    let state = app.state::<NoteWindows>();
+    let mut windows = state.0.lock().unwrap();
+    for (label, path) in windows.iter_mut() {
+        if !vault::same_file_path(Path::new(path.as_str()), Path::new(from)) {
+            continue;
+        }
+        *path = to.to_string();
+        if let Some(window) = app.get_webview_window(label) {
+            let _ = window.set_title(&vault::extract_title_from_filename(Path::new(to)));
+        }
+    }
+}
+
+/// Close every note window, e.g. when switching to another vault.
+pub fn close_note_windows(app: &AppHandle) {
+    let labels: Vec<String> = app
+        .state::<NoteWindows>()
+        .0
+        .lock()
+        .unwrap()
+        .keys()
+        .cloned()
+        .collect();
+    for label in labels {
+        if let Some(window) = app.get_webview_window(&label) {
+            let _ = window.close();
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use chrono::{Datelike, Local, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use similar::{ChangeTag, TextDiff};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::BouldyError;
+use crate::{fsutil, locale, markdown, vault};
+
+const STATS_FILE: &str = "writing-stats.json";
+const DATE_FORMAT: &str = "%Y-%m-%d";
+// Autosave runs on every pause in typing, so huge notes shouldn't stall it
+const DIFF_TIMEOUT_MS: u64 = 100;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct WritingGoals {
+    // Words to add each day, across all notes
+    #[serde(rename = "dailyWords", default)]
+    pub daily_words: Option<u32>,
+    // Target length of individual notes, by path
+    #[serde(rename = "noteWords", default)]
+    pub note_words: HashMap<String, u32>,
+}
+
+impl WritingGoals {
+    pub fn validate(&self) -> Result<(), BouldyError> {
+        if self.daily_words == Some(0) || self.note_words.values().any(|&goal| goal == 0) {
+            return Err(BouldyError::invalid_input(
+                "Writing goals must be at least 1 word",
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
+pub struct DayCounts {
+    pub words: u64,
+    pub characters: u64,
+}
+
+// Date -> what was added that day
+#[derive(Serialize, Deserialize, Default)]
+struct StatsLog {
+    days: BTreeMap<String, DayCounts>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatsRange {
+    #[serde(rename = "today")]
+    Today,
+    #[serde(rename = "week")]
+    Week,
+    #[serde(rename = "month")]
+    Month,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct DayStats {
+    pub date: String,
+    pub words: u64,
+    pub characters: u64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct NoteGoalProgress {
+    pub path: String,
+    pub title: String,
+    pub goal: u32,
+    pub words: usize,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct WritingStats {
+    pub from: String,
+    pub to: String,
+    pub words: u64,
+    pub characters: u64,
+    // Every day in the range, including ones with nothing written
+    pub days: Vec<DayStats>,
+    #[serde(rename = "dailyGoal")]
+    pub daily_goal: Option<u32>,
+    #[serde(rename = "todayWords")]
+    pub today_words: u64,
+    #[serde(rename = "noteGoals")]
+    pub note_goals: Vec<NoteGoalProgress>,
+}
+
+fn stats_path(vault_path: &str) -> PathBuf {
+    Path::new(vault_path).join(".bouldy").join(STATS_FILE)
+}
+
+fn load_log(vault_path: &str) -> StatsLog {
+    fs::read_to_string(stats_path(vault_path))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Words and characters in `new` that weren't in `old`. Deleting text
+/// doesn't count against the day.
+fn added(old: &str, new: &str) -> DayCounts {
+    let diff = TextDiff::configure()
+        .timeout(std::time::Duration::from_millis(DIFF_TIMEOUT_MS))
+        .diff_words(old, new);
+    let mut counts = DayCounts::default();
+    for change in diff.iter_all_changes() {
+        if change.tag() != ChangeTag::Insert {
+            continue;
+        }
+        let value = change.value();
+        counts.characters += value.chars().filter(|c| !c.is_whitespace()).count() as u64;
+        if !value.trim().is_empty() {
+            counts.words += 1;
+        }
+    }
+    counts
+}
+
+/// Add what a save of a note wrote to today's counts. The caller holds
+/// the vault lock.
+pub fn record_save(vault_path: &str, previous: &str, content: &str) -> Result<(), BouldyError> {
+    let counts = added(previous, content);
+    if counts.words == 0 && counts.characters == 0 {
+        return Ok(());
+    }
+
+    let mut log = load_log(vault_path);
+    let today = log
+        .days
+        .entry(Local::now().date_naive().format(DATE_FORMAT).to_string())
+        .or_default();
+    today.words += counts.words;
+    today.characters += counts.characters;
+
+    let content = serde_json::to_string_pretty(&log)
+        .map_err(|e| format!("Failed to serialize writing stats: {}", e))?;
+    fsutil::write_atomic(&stats_path(vault_path), content)
+        .map_err(|e| BouldyError::io("Failed to write writing stats", e))
+}
+
+fn word_count(content: &str) -> usize {
+    markdown::split_frontmatter(content)
+        .1
+        .split_whitespace()
+        .count()
+}
+
+impl StatsRange {
+    // First day of the range
+    fn start(self, today: NaiveDate, week_start: Weekday) -> NaiveDate {
+        match self {
+            StatsRange::Today => today,
+            StatsRange::Week => locale::start_of_week(today, week_start),
+            StatsRange::Month => today.with_day(1).unwrap_or(today),
+        }
+    }
+}
+
+/// Words written per day over `range`, with progress towards the daily
+/// goal and each note's length goal.
+pub fn get_stats(
+    vault_path: &str,
+    goals: &WritingGoals,
+    range: StatsRange,
+    week_start: Weekday,
+) -> Result<WritingStats, BouldyError> {
+    let log = load_log(vault_path);
+    let today = Local::now().date_naive();
+    let from = range.start(today, week_start);
+
+    let days: Vec<DayStats> = from
+        .iter_days()
+        .take_while(|date| *date <= today)
+        .map(|date| {
+            let date = date.format(DATE_FORMAT).to_string();
+            let counts = log.days.get(&date).copied().unwrap_or_default();
+            DayStats {
+                date,
+                words: counts.words,
+                characters: counts.characters,
+            }
+        })
+        .collect();
+
+    let mut note_goals: Vec<NoteGoalProgress> = goals
+        .note_words
+        .iter()
+        .filter_map(|(path, &goal)| {
+            let file = vault::validate_path_in_vault(vault_path, path).ok()?;
+            let content = fs::read_to_string(fsutil::long_path(&file)).ok()?;
+            Some(NoteGoalProgress {
+                path: path.clone(),
+                title: vault::extract_title_from_filename(&file),
+                goal,
+                words: word_count(&content),
+            })
+        })
+        .collect();
+    note_goals.sort_by(|a, b| a.title.cmp(&b.title));
+
+    Ok(WritingStats {
+        from: from.format(DATE_FORMAT).to_string(),
+        to: today.format(DATE_FORMAT).to_string(),
+        words: days.iter().map(|day| day.words).sum(),
+        characters: days.iter().map(|day| day.characters).sum(),
+        today_words: days.last().map(|day| day.words).unwrap_or_default(),
+        days,
+        daily_goal: goals.daily_words,
+        note_goals,
+    })
+}
@@ -17,7 +17,17 @@ import {
   usePomodoro,
 } from "./features/pomodoro";
 import { CommandMenu, useCommandMenu, Command } from "./features/command-menu";
-import { PanelType, PanelState, PinnedState } from "./types";
+import {
+  PanelType,
+  PanelState,
+  PinnedState,
+  ShortcutBinding,
+} from "./types";
+import {
+  matchesShortcut,
+  listShortcuts,
+  listenToShortcutsChanged,
+} from "./utils/shortcuts";
 
 interface AppContentProps {
   onResetVault: () => void;
@@ -321,55 +331,49 @@ function AppContent({ onResetVault, vaultPath }: AppContentProps) {
     executeSelected,
   } = useCommandMenu(commands);
 
-  // Keyboard listener for Cmd/Ctrl+K
+  // App shortcuts come from the backend registry, so they can be rebound
+  const [shortcuts, setShortcuts] = useState<ShortcutBinding[]>([]);
   useEffect(() => {
-    const handleKeyDown = (e: KeyboardEvent) => {
-      if ((e.metaKey || e.ctrlKey) && e.key === "k") {
-        e.preventDefault();
-        openMenu();
-      }
+    listShortcuts()
+      .then(setShortcuts)
+      .catch((err) => console.error("Error loading shortcuts:", err));
+    const unlisten = listenToShortcutsChanged(setShortcuts);
+    return () => {
+      unlisten.then((fn) => fn());
     };
+  }, []);
 
-    window.addEventListener("keydown", handleKeyDown);
-    return () => window.removeEventListener("keydown", handleKeyDown);
-  }, [openMenu]);
-
-  // Keyboard listener for Alt+1/2/3/4 panel shortcuts
   useEffect(() => {
+    const panelActions: Record<string, PanelType> = {
+      "panel-notes": "notes",
+      "panel-todos": "todos",
+      "panel-calendar": "calendar",
+      "panel-prompts": "prompts",
+      "panel-pomodoro": "pomodoro",
+      "panel-settings": "settings",
+    };
+
     const handleKeyDown = (e: KeyboardEvent) => {
-      if (e.altKey) {
-        switch (e.key) {
-          case "1":
-            e.preventDefault();
-            activatePanel("notes");
-            break;
-          case "2":
-            e.preventDefault();
-            activatePanel("todos");
-            break;
-          case "3":
-            e.preventDefault();
-            activatePanel("calendar");
-            break;
-          case "4":
-            e.preventDefault();
-            activatePanel("prompts");
-            break;
-          case "5":
-            e.preventDefault();
-            activatePanel("pomodoro");
-            break;
-          case "6":
-            e.preventDefault();
-            activatePanel("settings");
-            break;
-        }
+      const action = shortcuts.find(
+        (shortcut) =>
+          shortcut.scope === "app" &&
+          shortcut.binding &&
+          matchesShortcut(e, shortcut.binding),
+      );
+      if (!action) return;
+
+      if (action.id === "command-menu") {
+        e.preventDefault();
+        openMenu();
+      } else if (panelActions[action.id]) {
+        e.preventDefault();
+        activatePanel(panelActions[action.id]);
       }
     };
 
     window.addEventListener("keydown", handleKeyDown);
     return () => window.removeEventListener("keydown", handleKeyDown);
-  }, [activatePanel]);
+  }, [shortcuts, openMenu, activatePanel]);
 
   const renderPanel = (type: PanelType) => {
     switch (type) {
@@ -0,0 +1,60 @@
+import { useEffect, useState } from "react";
+import "@mdxeditor/editor/style.css";
+import { invoke } from "@tauri-apps/api/core";
+import { errorMessage } from "../../../types/error";
+import NotesEditorContent from "./NotesEditorContent";
+
+interface LoadedNote {
+  path: string;
+  title: string;
+  content: string;
+}
+
+// A note opened in a window of its own (open_note_window). The backend
+// remembers which note each window shows, so the URL doesn't carry it.
+export default function NoteWindowView() {
+  const [note, setNote] = useState<LoadedNote | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    const loadNote = async () => {
+      try {
+        const path = await invoke<string | null>("get_window_note");
+        if (!path) {
+          setError("This window has no note");
+          return;
+        }
+        const metadata = await invoke<{ title: string; content: string }>(
+          "read_note",
+          { path },
+        );
+        setNote({ path, title: metadata.title, content: metadata.content });
+      } catch (err) {
+        console.error("[NoteWindow] Failed to load note:", err);
+        setError(errorMessage(err));
+      }
+    };
+
+    loadNote();
+  }, []);
+
+  if (!note) {
+    return (
+      <div className="min-h-screen flex items-center justify-center bg-bg">
+        <div className={error ? "text-sm text-text-muted" : "text-primary"}>
+          {error ?? "Loading..."}
+        </div>
+      </div>
+    );
+  }
+
+  return (
+    <div className="w-full h-screen flex flex-col bg-bg-light overflow-hidden min-w-0 min-h-0">
+      <NotesEditorContent
+        notePath={note.path}
+        noteTitle={note.title}
+        initialContent={note.content}
+      />
+    </div>
+  );
+}
@@ -1,6 +1,8 @@
 import { useState, useMemo, useEffect } from "react";
 import { Search, ArrowLeft, Trash2, FolderOpen, Link2 } from "lucide-react";
 import { invoke } from "@tauri-apps/api/core";
+import { Page } from "../../../types/page";
+import { errorMessage } from "../../../types/error";
 import { listenToNoteEvents } from "../../../utils/events";
 import ConfirmDialog from "../../../shared/components/ConfirmDialog";
 import ImportDialog from "./ImportDialog";
@@ -45,9 +47,10 @@ export default function NotesBrowserView({
         const vaultPath = await invoke<string | null>("get_vault_path");
         if (vaultPath) {
           console.log("[NotesBrowser] Loading notes from:", vaultPath);
-          const notesList = await invoke<Note[]>("list_vault_files", {
-            vaultPath,
-          });
+          const { items: notesList } = await invoke<Page<Note>>(
+            "list_vault_files",
+            { vaultPath },
+          );
           console.log(`[NotesBrowser] Loaded ${notesList.length} notes`);
           notesList.forEach(note => {
             console.log(`  - ${note.title} (${note.path})`);
@@ -66,11 +69,13 @@ export default function NotesBrowserView({
 
     listenToNoteEvents({
       onListUpdated: () => loadNotes(),
+      onListDiff: () => loadNotes(),
       onDeleted: (payload) => {
         setNotes((prev) => prev.filter((n) => n.path !== payload.path));
       },
       onCreated: () => loadNotes(),
       onSaved: () => loadNotes(),
+      onBulkChange: () => loadNotes(),
     }).then((listeners) => {
       unlisteners.push(...listeners);
     });
@@ -83,10 +88,7 @@ export default function NotesBrowserView({
   // Delete note handler
   const handleDeleteNote = async (note: Note) => {
     try {
-      const vaultPath = await invoke<string | null>("get_vault_path");
-      if (!vaultPath) return;
-
-      await invoke("delete_note", { vaultPath, path: note.path });
+      await invoke("delete_note", { path: note.path });
 
       // Remove from local state
       setNotes((prev) => prev.filter((n) => n.path !== note.path));
@@ -139,7 +141,7 @@ export default function NotesBrowserView({
       // Notes will auto-refresh via event listener
     } catch (error) {
       console.error("[Import] Failed:", error);
-      alert(`Failed to import: ${error}`);
+      alert(`Failed to import: ${errorMessage(error)}`);
     }
   };
 
@@ -1,6 +1,8 @@
 import { useState, useEffect } from "react";
 import "@mdxeditor/editor/style.css";
 import { invoke } from "@tauri-apps/api/core";
+import { Page } from "../../../types/page";
+import { errorMessage } from "../../../types/error";
 import { Store } from "@tauri-apps/plugin-store";
 import NotesEditorView from "./NotesEditorView";
 import NotesBrowserView from "./NotesBrowserView";
@@ -45,7 +47,7 @@ export default function NotesEditor() {
       }
     } catch (error) {
       console.error("[NotesEditor] Failed to load note:", error);
-      alert(`Failed to load note: ${error}`);
+      alert(`Failed to load note: ${errorMessage(error)}`);
     }
   };
 
@@ -67,10 +69,10 @@ export default function NotesEditor() {
             // Get file stats for modified time
             const vaultPath = await invoke<string | null>("get_vault_path");
             if (vaultPath) {
-              const notes = await invoke<Note[]>("list_vault_files", {
+              const page = await invoke<Page<Note>>("list_vault_files", {
                 vaultPath,
               });
-              const note = notes.find((n) => n.path === lastNotePath);
+              const note = page.items.find((n) => n.path === lastNotePath);
 
               if (note) {
                 setCurrentNote(note);
@@ -115,10 +117,7 @@ export default function NotesEditor() {
       });
 
       // Delete old file
-      await invoke("delete_note", {
-        vaultPath,
-        path: currentNote.path,
-      });
+      await invoke("delete_note", { path: currentNote.path });
 
       // Update current note
       const updatedNote = {
@@ -22,6 +22,7 @@ import {
 } from "@mdxeditor/editor";
 import { invoke } from "@tauri-apps/api/core";
 import { useAutoSave } from "../hooks/useAutoSave";
+import { listenToImportCompleted } from "../../../utils/events";
 
 interface EditorContentProps {
   notePath: string;
@@ -129,6 +130,24 @@ const NotesEditorContent = forwardRef<EditorContentHandle, EditorContentProps>(
       }, 0);
     }, [notePath, initialContent]);
 
+    // Links to files dropped onto the window go at the end of this note;
+    // autosave then writes them with the rest of the edits
+    useEffect(() => {
+      const unlisten = listenToImportCompleted((report) => {
+        if (report.linkedInto !== notePath || report.attachments.length === 0) {
+          return;
+        }
+        const current = editorRef.current?.getMarkdown() ?? markdown;
+        const links = report.attachments.map((a) => a.link).join("\n");
+        const next = `${current.trimEnd()}\n\n${links}\n`;
+        editorRef.current?.setMarkdown(next);
+        setMarkdown(next);
+      });
+      return () => {
+        unlisten.then((fn) => fn());
+      };
+    }, [notePath]);
+
     // Save function used by both auto-save and manual save
     const saveNote = async (content: string, title: string) => {
       try {
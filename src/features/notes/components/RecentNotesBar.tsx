@@ -1,6 +1,7 @@
 import { Plus } from "lucide-react";
 import { useRef, useEffect, useState, useMemo, memo } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { Page } from "../../../types/page";
 import { listenToNoteEvents } from "../../../utils/events";
 
 interface Note {
@@ -17,6 +18,13 @@ interface RecentNotesBarProps {
   onBrowseAll: () => void;
 }
 
+async function fetchNotes(): Promise<{ vaultPath: string; notes: Note[] } | null> {
+  const vaultPath = await invoke<string | null>("get_vault_path");
+  if (!vaultPath) return null;
+  const page = await invoke<Page<Note>>("list_vault_files", { vaultPath });
+  return { vaultPath, notes: page.items };
+}
+
 const RecentNotesBar = memo(function RecentNotesBar({
   activePath,
   onSelectNote,
@@ -31,15 +39,10 @@ const RecentNotesBar = memo(function RecentNotesBar({
   useEffect(() => {
     const loadData = async () => {
       try {
-        // Get vault path
-        const path = await invoke<string | null>("get_vault_path");
-        if (path) {
-          setVaultPath(path);
-          // Load notes
-          const notesList = await invoke<Note[]>("list_vault_files", {
-            vaultPath: path,
-          });
-          setNotes(notesList);
+        const loaded = await fetchNotes();
+        if (loaded) {
+          setVaultPath(loaded.vaultPath);
+          setNotes(loaded.notes);
         }
       } catch (error) {
         console.error("Failed to load notes:", error);
@@ -64,6 +67,36 @@ const RecentNotesBar = memo(function RecentNotesBar({
             })),
           );
         },
+        onListDiff: (payload) => {
+          const toNote = (n: (typeof payload.created)[number]) => ({
+            path: n.path,
+            name: n.name,
+            title: n.title || "Untitled",
+            modified: n.modified || 0,
+            is_symlink: n.is_symlink || false,
+          });
+          const changed = new Map(
+            [...payload.created, ...payload.updated].map((n) => [
+              n.path,
+              toNote(n),
+            ]),
+          );
+          const removed = new Set(payload.removed);
+          setNotes((prev) => [
+            ...prev
+              .filter((note) => !removed.has(note.path))
+              .map((note) => changed.get(note.path) ?? note),
+            ...payload.created
+              .filter((n) => !prev.some((note) => note.path === n.path))
+              .map(toNote),
+          ]);
+        },
+        onBulkChange: () => {
+          // Too many changes to apply one by one
+          fetchNotes()
+            .then((loaded) => loaded && setNotes(loaded.notes))
+            .catch((error) => console.error("Failed to reload notes:", error));
+        },
         onDeleted: (payload) => {
           // Remove deleted note from the list
           setNotes((prev) => prev.filter((note) => note.path !== payload.path));
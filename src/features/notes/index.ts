@@ -1,2 +1,3 @@
 export { default as NotesEditor } from "./components/NotesEditor";
+export { default as NoteWindowView } from "./components/NoteWindowView";
 export { useAutoSave } from "./hooks/useAutoSave";
@@ -0,0 +1,67 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { WidgetData } from "../../../types/pomodoro";
+
+const SESSION_LABELS: Record<string, string> = {
+  work: "Focus",
+  "short-break": "Short Break",
+  "long-break": "Long Break",
+};
+
+const formatTime = (seconds: number): string => {
+  const mins = Math.floor(seconds / 60);
+  const secs = seconds % 60;
+  return `${mins.toString().padStart(2, "0")}:${secs.toString().padStart(2, "0")}`;
+};
+
+// The always-on-top widget window (show_widget). Everything it shows comes
+// from the backend's widget:update, so it keeps working with the main
+// window closed and never runs a timer of its own.
+export default function WidgetView() {
+  const [data, setData] = useState<WidgetData | null>(null);
+
+  useEffect(() => {
+    invoke<WidgetData>("get_widget_data")
+      .then(setData)
+      .catch((err) => console.error("Error loading widget data:", err));
+
+    const unlisten = listen<WidgetData>("widget:update", (event) => {
+      setData(event.payload);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const timer = data?.timer;
+
+  return (
+    <div
+      data-tauri-drag-region
+      className="w-full h-screen flex flex-col gap-2 p-3 bg-bg-light border border-border-muted overflow-hidden cursor-move select-none"
+    >
+      <div data-tauri-drag-region className="flex items-baseline justify-between">
+        <span className="text-xs uppercase tracking-wide text-text-muted">
+          {timer ? SESSION_LABELS[timer.type] : "No session"}
+        </span>
+        <span
+          className={`font-mono text-2xl ${timer?.running ? "text-primary" : "text-text-muted"}`}
+        >
+          {timer ? formatTime(timer.timeRemaining) : "--:--"}
+        </span>
+      </div>
+
+      <ul className="flex flex-col gap-1 min-h-0 overflow-hidden">
+        {(data?.todos ?? []).map((todo) => (
+          <li key={todo.id} className="text-sm text-text truncate">
+            {todo.title}
+          </li>
+        ))}
+        {data && data.todos.length === 0 && (
+          <li className="text-sm text-text-muted">Nothing planned for today</li>
+        )}
+      </ul>
+    </div>
+  );
+}
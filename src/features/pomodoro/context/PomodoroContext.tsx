@@ -8,7 +8,13 @@ import {
   ReactNode,
 } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { PomodoroSession } from "../../../types/pomodoro";
+import { listen } from "@tauri-apps/api/event";
+import { getCurrentWindow } from "@tauri-apps/api/window";
+import {
+  PomodoroSession,
+  TimerConfig,
+  TimerReport,
+} from "../../../types/pomodoro";
 import { serializeSessions, parseSessions } from "../utils/pomodoro-parser";
 
 interface PomodoroContextType {
@@ -41,9 +47,14 @@ interface PomodoroProviderProps {
   vaultPath: string;
 }
 
-const WORK_DURATION = 25 * 60; // 25 minutes
-const SHORT_BREAK_DURATION = 5 * 60; // 5 minutes
-const LONG_BREAK_DURATION = 15 * 60; // 15 minutes
+// Used until the backend config has loaded
+const DEFAULT_TIMER_CONFIG: TimerConfig = {
+  workMinutes: 25,
+  shortBreakMinutes: 5,
+  longBreakMinutes: 15,
+  sessionsPerLongBreak: 4,
+  autoStartNext: true,
+};
 
 export function PomodoroProvider({
   children,
@@ -54,11 +65,27 @@ export function PomodoroProvider({
     null,
   );
   const [isRunning, setIsRunning] = useState(false);
-  const [timeRemaining, setTimeRemaining] = useState(WORK_DURATION);
+  const [config, setConfig] = useState<TimerConfig>(DEFAULT_TIMER_CONFIG);
+  const [timeRemaining, setTimeRemaining] = useState(
+    DEFAULT_TIMER_CONFIG.workMinutes * 60,
+  );
 
   const timerRef = useRef<NodeJS.Timeout | null>(null);
   const saveTimeoutRef = useRef<NodeJS.Timeout | null>(null);
 
+  useEffect(() => {
+    invoke<TimerConfig>("get_timer_config")
+      .then(setConfig)
+      .catch((err) => console.error("Error loading timer config:", err));
+
+    const unlisten = listen<TimerConfig>("pomodoro:timer-config", (event) =>
+      setConfig(event.payload),
+    );
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   const loadSessions = useCallback(async () => {
     try {
       const content = await invoke<string>("read_pomodoros", { vaultPath });
@@ -96,18 +123,19 @@ export function PomodoroProvider({
     [saveSessions],
   );
 
-  const getDurationForType = (
-    type: "work" | "short-break" | "long-break",
-  ): number => {
-    switch (type) {
-      case "work":
-        return WORK_DURATION;
-      case "short-break":
-        return SHORT_BREAK_DURATION;
-      case "long-break":
-        return LONG_BREAK_DURATION;
-    }
-  };
+  const getDurationForType = useCallback(
+    (type: "work" | "short-break" | "long-break"): number => {
+      switch (type) {
+        case "work":
+          return config.workMinutes * 60;
+        case "short-break":
+          return config.shortBreakMinutes * 60;
+        case "long-break":
+          return config.longBreakMinutes * 60;
+      }
+    },
+    [config],
+  );
 
   const startSession = useCallback(
     (type: "work" | "short-break" | "long-break") => {
@@ -124,7 +152,7 @@ export function PomodoroProvider({
       setTimeRemaining(duration);
       setIsRunning(true);
     },
-    [],
+    [getDurationForType],
   );
 
   const pauseSession = useCallback(() => {
@@ -155,15 +183,28 @@ export function PomodoroProvider({
     }).length;
 
     // If we just completed a work session, determine break type
-    if (completedSession.type === "work") {
-      const nextBreak =
-        (todaysWorkSessions + 1) % 4 === 0 ? "long-break" : "short-break";
-      startSession(nextBreak);
+    const next =
+      completedSession.type !== "work"
+        ? "work"
+        : (todaysWorkSessions + 1) % config.sessionsPerLongBreak === 0
+          ? "long-break"
+          : "short-break";
+    if (config.autoStartNext) {
+      startSession(next);
     } else {
-      // After break, start work session
-      startSession("work");
+      setCurrentSession(null);
+      setIsRunning(false);
+      setTimeRemaining(getDurationForType(next));
     }
-  }, [currentSession, sessions, timeRemaining, updateSessions, startSession]);
+  }, [
+    currentSession,
+    sessions,
+    timeRemaining,
+    updateSessions,
+    startSession,
+    getDurationForType,
+    config,
+  ]);
 
   const skipSession = useCallback(() => {
     if (!currentSession) return;
@@ -177,8 +218,8 @@ export function PomodoroProvider({
     updateSessions([...sessions, skippedSession]);
     setCurrentSession(null);
     setIsRunning(false);
-    setTimeRemaining(WORK_DURATION);
-  }, [currentSession, sessions, updateSessions]);
+    setTimeRemaining(config.workMinutes * 60);
+  }, [currentSession, sessions, updateSessions, config]);
 
   const resetSession = useCallback(() => {
     if (!currentSession) return;
@@ -203,6 +244,18 @@ export function PomodoroProvider({
     };
   }, [isRunning, timeRemaining, currentSession, completeSession]);
 
+  // Keep the backend's copy current for the widget window. Only the main
+  // window runs the timer; another window's idle copy would overwrite it.
+  useEffect(() => {
+    if (getCurrentWindow().label !== "main") return;
+    const state: TimerReport | null = currentSession
+      ? { type: currentSession.type, running: isRunning, timeRemaining }
+      : null;
+    invoke("report_timer_state", { state }).catch((err) =>
+      console.error("Error reporting timer state:", err),
+    );
+  }, [currentSession, isRunning, timeRemaining]);
+
   // Cleanup on unmount
   useEffect(() => {
     return () => {
@@ -1,3 +1,4 @@
 export { default as PomodoroPanel } from "./components/PomodoroPanel";
+export { default as WidgetView } from "./components/WidgetView";
 export { PomodoroProvider, usePomodoro } from "./context/PomodoroContext";
 export * from "./utils/pomodoro-parser";
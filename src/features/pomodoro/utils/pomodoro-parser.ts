@@ -8,10 +8,20 @@ export function parseSessions(content: string): PomodoroSession[] {
     const trimmed = line.trim();
     if (!trimmed || trimmed.startsWith("#")) continue;
 
-    // Expected format: [id]|[type]|[duration]|[startTime]|[endTime]|[completed]
+    // Expected format: [id]|[type]|[duration]|[startTime]|[endTime]|[completed]|[todoUid]|[tags]|[note]
     const parts = trimmed.split("|");
     if (parts.length >= 5) {
-      const [id, type, duration, startTime, endTime, completed] = parts;
+      const [
+        id,
+        type,
+        duration,
+        startTime,
+        endTime,
+        completed,
+        todoUid,
+        tags,
+        note,
+      ] = parts;
 
       if (type === "work" || type === "short-break" || type === "long-break") {
         sessions.push({
@@ -21,6 +31,9 @@ export function parseSessions(content: string): PomodoroSession[] {
           startTime,
           endTime: endTime || undefined,
           completed: completed === "true",
+          todoUid: todoUid || undefined,
+          tags: tags ? tags.split(" ").filter(Boolean) : undefined,
+          note: note || undefined,
         });
       }
     }
@@ -41,6 +54,16 @@ export function serializeSessions(sessions: PomodoroSession[]): string {
       session.endTime || "",
       session.completed.toString(),
     ];
+    // Optional columns are positional, so earlier ones stay as empty fields
+    const optional = [
+      session.todoUid || "",
+      session.tags?.join(" ") || "",
+      session.note || "",
+    ];
+    while (optional.length > 0 && optional[optional.length - 1] === "") {
+      optional.pop();
+    }
+    parts.push(...optional);
     lines.push(parts.join("|"));
   }
 
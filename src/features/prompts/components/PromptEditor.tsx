@@ -1,5 +1,6 @@
 import { useState, useEffect } from "react";
 import { Save, X as XIcon } from "lucide-react";
+import { errorMessage } from "../../../types/error";
 
 interface PromptEditorProps {
   initialData?: {
@@ -68,7 +69,7 @@ export default function PromptEditor({
         tags,
       });
     } catch (error) {
-      const errorMsg = error instanceof Error ? error.message : String(error);
+      const errorMsg = errorMessage(error);
       console.error("Failed to save prompt:", error);
       setSaveError(errorMsg);
     } finally {
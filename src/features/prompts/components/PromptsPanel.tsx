@@ -4,6 +4,7 @@ import { useQuery, useMutation, useQueryClient } from "@tanstack/react-query";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import { Prompt } from "../../../types/prompt";
+import { Page } from "../../../types/page";
 import PromptItem from "./PromptItem";
 import PromptEditor from "./PromptEditor";
 import PromptViewer from "./PromptViewer";
@@ -33,7 +34,8 @@ function usePromptsQuery(vaultPath: string) {
   // Main query for fetching prompts
   const promptsQuery = useQuery({
     queryKey: [...PROMPTS_QUERY_KEY, vaultPath],
-    queryFn: async () => invoke<Prompt[]>("list_prompts", { vaultPath }),
+    queryFn: async () =>
+      (await invoke<Page<Prompt>>("list_prompts", { vaultPath })).items,
     staleTime: 1000 * 60 * 5,
     gcTime: 1000 * 60 * 10,
   });
@@ -37,6 +37,18 @@ interface TodoSpaceProps {
 // Query key for todos
 const TODOS_QUERY_KEY = ["todos"];
 
+// Due values may carry a time ("2025-01-02T15:00"); date filters only
+// compare the day
+const dueDay = (dueDate: string) => dueDate.slice(0, 10);
+
+// YYYY-MM-DD in the user's timezone (toISOString would give the UTC day)
+const localDateString = (date: Date) =>
+  [
+    date.getFullYear(),
+    String(date.getMonth() + 1).padStart(2, "0"),
+    String(date.getDate()).padStart(2, "0"),
+  ].join("-");
+
 // Hook to use todos with TanStack Query
 function useTodosQuery(vaultPath: string) {
   const queryClient = useQueryClient();
@@ -338,7 +350,7 @@ function SortableTodoItem({
         ))}
 
         {/* Date Display */}
-        {todo.dueDate && todo.dueDate !== getTodayString() && (
+        {todo.dueDate && dueDay(todo.dueDate) !== getTodayString() && (
           <div className="relative flex-shrink-0 flex items-center gap-1.5 px-1.5 py-0.5 rounded hover:bg-bg transition-colors cursor-pointer">
             <span
               className={`text-xs ${
@@ -347,7 +359,7 @@ function SortableTodoItem({
                   : "text-text-muted"
               }`}
             >
-              {new Date(todo.dueDate + "T00:00:00").toLocaleDateString(
+              {new Date(dueDay(todo.dueDate) + "T00:00:00").toLocaleDateString(
                 "en-US",
                 { month: "short", day: "numeric" },
               )}
@@ -412,22 +424,22 @@ export default function TodoSpace({ vaultPath }: TodoSpaceProps) {
 
   const hasCompletedTodos = todos.some((t) => t.completed);
 
-  const getTodayString = () => {
-    const today = new Date();
-    return today.toISOString().split("T")[0];
-  };
+  const getTodayString = () => localDateString(new Date());
 
   const getTomorrowString = () => {
     const tomorrow = new Date();
     tomorrow.setDate(tomorrow.getDate() + 1);
-    return tomorrow.toISOString().split("T")[0];
+    return localDateString(tomorrow);
   };
 
-  const isPast = (dateString: string) => {
+  // Dates without a time are due by the end of the day
+  const isPast = (dueDate: string) => {
+    if (dueDate.length > 10) {
+      return new Date(dueDate) < new Date();
+    }
     const today = new Date();
     today.setHours(0, 0, 0, 0);
-    const date = new Date(dateString + "T00:00:00");
-    return date < today;
+    return new Date(dueDate + "T00:00:00") < today;
   };
 
   const filteredTodos = todos.filter((todo) => {
@@ -436,8 +448,8 @@ export default function TodoSpace({ vaultPath }: TodoSpaceProps) {
     // Date filter
     const dateMatch =
       activeTab === "today"
-        ? !todo.dueDate || todo.dueDate <= today
-        : todo.dueDate && todo.dueDate > today;
+        ? !todo.dueDate || dueDay(todo.dueDate) <= today
+        : todo.dueDate && dueDay(todo.dueDate) > today;
 
     // Project filter
     const projectMatch =
@@ -457,11 +469,11 @@ export default function TodoSpace({ vaultPath }: TodoSpaceProps) {
   // Planning mode helpers
   const todayTodos = todos.filter((t) => {
     const today = getTodayString();
-    return !t.dueDate || t.dueDate <= today;
+    return !t.dueDate || dueDay(t.dueDate) <= today;
   });
   const availableTodos = todos.filter((t) => {
     const today = getTodayString();
-    return t.dueDate && t.dueDate > today;
+    return t.dueDate && dueDay(t.dueDate) > today;
   });
 
   const moveToToday = (todoId: number) => {
@@ -1,5 +1,6 @@
 import { invoke } from "@tauri-apps/api/core";
 import { useState } from "react";
+import { errorMessage, isBouldyError } from "../../../types/error";
 
 interface VaultSelectorProps {
   onVaultSelected: (path: string) => void;
@@ -22,7 +23,11 @@ export default function VaultSelector({ onVaultSelected }: VaultSelectorProps) {
       // Notify parent component
       onVaultSelected(path);
     } catch (err) {
-      setError(err as string);
+      setError(
+        isBouldyError(err) && err.type === "cancelled"
+          ? "You need to select a folder to continue."
+          : `Error: ${errorMessage(err)}`,
+      );
     } finally {
       setIsSelecting(false);
     }
@@ -52,11 +57,7 @@ export default function VaultSelector({ onVaultSelected }: VaultSelectorProps) {
 
           {error && (
             <div className="border-2 border-danger bg-danger/10 p-4">
-              <p className="text-sm text-danger">
-                {error === "No folder selected"
-                  ? "You need to select a folder to continue."
-                  : `Error: ${error}`}
-              </p>
+              <p className="text-sm text-danger">{error}</p>
             </div>
           )}
 
@@ -1,6 +1,9 @@
 import React from "react";
 import ReactDOM from "react-dom/client";
 import App from "./App";
+import { NoteWindowView } from "./features/notes";
+import { WidgetView } from "./features/pomodoro";
+import "./styles/App.css";
 import { PostHogProvider } from "posthog-js/react";
 import { markReactMountStart, markReactMountEnd, markThemeInitEnd } from "./utils/performance";
 
@@ -21,6 +24,20 @@ const options = {
   autocapture: true,
 } as const;
 
+// Secondary windows load the same page with ?window=<kind>
+const windowKind = new URLSearchParams(window.location.search).get("window");
+
+function rootView() {
+  switch (windowKind) {
+    case "note":
+      return <NoteWindowView />;
+    case "widget":
+      return <WidgetView />;
+    default:
+      return <App />;
+  }
+}
+
 const root = ReactDOM.createRoot(document.getElementById("root") as HTMLElement);
 
 // Render and mark completion
@@ -30,7 +47,7 @@ root.render(
       apiKey={import.meta.env.VITE_PUBLIC_POSTHOG_KEY}
       options={options}
     >
-      <App />
+      {rootView()}
     </PostHogProvider>
   </React.StrictMode>,
 );
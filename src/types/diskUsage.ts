@@ -0,0 +1,18 @@
+export type DiskUsageCategory = "notes" | "assets" | "history" | "trash" | "index" | "other";
+
+export interface CategoryUsage {
+  bytes: number;
+  files: number;
+}
+
+// From get_vault_disk_usage
+export interface DiskUsage {
+  notes: CategoryUsage;
+  assets: CategoryUsage; // the attachments folder
+  history: CategoryUsage; // note versions
+  trash: CategoryUsage; // Obsidian's .trash folder
+  index: CategoryUsage; // the search index
+  other: CategoryUsage;
+  total: number;
+  largest: { path: string; size: number; category: DiskUsageCategory }[]; // biggest first, up to 20
+}
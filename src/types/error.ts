@@ -0,0 +1,26 @@
+// Mirrors BouldyError in src-tauri/src/error.rs
+export type BouldyError =
+  | { type: "notFound"; message: string }
+  | { type: "outsideVault"; message: string }
+  | { type: "conflict"; message: string }
+  | { type: "io"; kind: string; message: string }
+  | { type: "parseError"; line: number; message: string }
+  | { type: "invalidPath"; message: string }
+  | { type: "cancelled"; message: string }
+  | { type: "invalidInput"; message: string }
+  | { type: "other"; message: string };
+
+export function isBouldyError(error: unknown): error is BouldyError {
+  return (
+    typeof error === "object" &&
+    error !== null &&
+    "type" in error &&
+    "message" in error
+  );
+}
+
+export function errorMessage(error: unknown): string {
+  if (isBouldyError(error)) return error.message;
+  if (error instanceof Error) return error.message;
+  return String(error);
+}
@@ -3,3 +3,11 @@ export * from "./note";
 export * from "./todo";
 export * from "./panel";
 export * from "./theme";
+export * from "./page";
+export * from "./error";
+export * from "./vaultConfig";
+export * from "./snippet";
+export * from "./session";
+export * from "./locale";
+export * from "./shortcut";
+export * from "./vaultDiscovery";
@@ -0,0 +1,10 @@
+// From get_locale_info, for the `locale` setting or the system's
+export interface LocaleInfo {
+  locale: string; // e.g. "en-GB"
+  language: string; // where names come from; "en" if not translated
+  weekStart: "monday" | "sunday";
+  dateOrder: "dmy" | "mdy" | "ymd";
+  datePattern: string; // e.g. "DD/MM/YYYY"
+  monthNames: string[];
+  weekdayNames: string[]; // starting with weekStart
+}
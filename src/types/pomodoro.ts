@@ -1,3 +1,5 @@
+import { TodoItem } from "./todo";
+
 export interface PomodoroSession {
   id: string;
   type: "work" | "short-break" | "long-break";
@@ -5,6 +7,18 @@ export interface PomodoroSession {
   startTime: string; // ISO timestamp
   endTime?: string; // ISO timestamp (undefined if session incomplete)
   completed: boolean;
+  todoUid?: string; // uid of the todo the session was started on
+  tags?: string[]; // +project, @context and #tag words of that todo
+  note?: string; // vault-relative path of the note worked on
+}
+
+// Backend timer settings, see get_timer_config
+export interface TimerConfig {
+  workMinutes: number;
+  shortBreakMinutes: number;
+  longBreakMinutes: number;
+  sessionsPerLongBreak: number;
+  autoStartNext: boolean;
 }
 
 export interface PomodoroState {
@@ -17,3 +31,16 @@ export interface PomodoroState {
   longBreakDuration: number; // default 15 minutes
   sessionsUntilLongBreak: number; // default 4
 }
+
+// The timer as reported to the backend with report_timer_state
+export interface TimerReport {
+  type: PomodoroSession["type"];
+  running: boolean;
+  timeRemaining: number; // in seconds
+}
+
+// Sent to the widget window as widget:update, see also get_widget_data
+export interface WidgetData {
+  timer: TimerReport | null;
+  todos: TodoItem[]; // today's top 3
+}
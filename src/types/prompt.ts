@@ -7,11 +7,53 @@ export interface Prompt {
   variables: string[];
   lastUsed?: number;
   useCount: number;
+  favorite: boolean;
+  rating?: number; // 1 to 5 stars
+  tests: PromptTestCase[];
   created: number;
   modified: number;
   path: string;
 }
 
+export interface PromptTestCase {
+  name: string;
+  inputs: Record<string, string>; // variable -> example value
+  expect: string[]; // qualities a good answer has
+}
+
+export interface PromptTestResult {
+  case: string;
+  rendered: string;
+  expect: string[];
+  output?: string;
+  error?: string;
+}
+
+export interface PromptTestRun {
+  ranAt: string;
+  provider: "openai" | "anthropic" | "ollama";
+  model?: string;
+  results: PromptTestResult[];
+}
+
+export interface PromptEstimate {
+  model: string;
+  characters: number;
+  tokens: number; // approximate
+  inputCost?: number; // USD, unknown for unlisted models
+  maxOutputCost?: number;
+  missingVariables: string[];
+}
+
+export type ShareTarget =
+  | { target: "gist"; public?: boolean }
+  | { target: "markdown" };
+
+export interface SharedLink {
+  markdown: string; // self-contained, importable with import_shared_prompt
+  url?: string; // the gist, when shared as one
+}
+
 export interface PromptMetadata {
   title: string;
   content: string;
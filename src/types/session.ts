@@ -0,0 +1,8 @@
+// Where the user left off in a vault, from load_session_state
+export interface SessionState {
+  lastNote?: string | null;
+  scrollTop?: number | null; // of the last note's editor
+  openPanels?: string[];
+  // Any other keys are stored and returned as given
+  [key: string]: unknown;
+}
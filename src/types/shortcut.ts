@@ -0,0 +1,9 @@
+// An action from list_shortcuts; global ones are registered with the OS,
+// app ones are handled while the main window has focus
+export interface ShortcutBinding {
+  id: string;
+  label: string;
+  scope: "global" | "app";
+  binding: string | null; // null when unbound
+  default: string | null;
+}
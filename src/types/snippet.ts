@@ -0,0 +1,7 @@
+// A file in snippets/, expanded with expand_snippet or paste_snippet
+export interface Snippet {
+  name: string; // file name without .md
+  abbr: string;
+  text: string; // may contain {{date}} and {{time}}
+  path: string;
+}
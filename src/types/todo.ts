@@ -7,6 +7,8 @@ export interface TodoItem {
   projects: string[];    // +ProjectName tags
   contexts: string[];    // @ContextName tags
   createdDate?: string;  // YYYY-MM-DD
+  uid?: string;          // Links the todo to a checkbox in a synced note
+  parent?: string;       // uid of the todo this is a subtask of
 }
 
 export interface TodoStats {
@@ -26,3 +28,27 @@ export interface ArchivedTodo {
   title: string;
   completedDate: string;
 }
+
+export type TaskImportFormat = "org" | "markdown";
+
+export interface TaskImport {
+  todos: TodoItem[];
+  skipped: { line: number; text: string; reason: string }[];
+  dryRun: boolean;
+}
+
+export interface TaskwarriorImport {
+  added: number;
+  updated: number;
+  skipped: { line: number; text: string; reason: string }[];
+}
+
+export type TodoExportFormat = "csv" | "json";
+
+export interface TodoExportFilter {
+  completed?: boolean;
+  projects?: string[];
+  contexts?: string[];
+  priority?: string;
+  dueBefore?: string;  // YYYY-MM-DD, inclusive
+}
@@ -0,0 +1,8 @@
+// Mirrors VaultConfig in src-tauri/src/vault_config.rs, stored per vault
+// in .bouldy/config.json
+export type Compatibility = "bouldy" | "obsidian";
+
+export interface VaultConfig {
+  compatibility: Compatibility;
+  attachmentsFolder?: string;
+}
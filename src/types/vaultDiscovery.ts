@@ -0,0 +1,9 @@
+// A folder that looks like a vault, from discover_vaults
+export interface VaultCandidate {
+  path: string;
+  name: string;
+  kind: "bouldy" | "obsidian" | "plain";
+  markers: string[]; // ".bouldy", ".obsidian", "notes", "todo.txt"
+  notes: number; // markdown files found, capped at 1000
+  modified: number | null; // unix seconds of the newest note
+}
@@ -6,12 +6,38 @@ export interface NoteEventPayload {
   title?: string;
   modified?: number;
   is_symlink?: boolean;
+  window?: string; // note:saved only: label of the window that saved it
 }
 
 export interface NoteListPayload {
   notes: NoteEventPayload[];
 }
 
+export interface NoteListDiffPayload {
+  created: NoteEventPayload[];
+  updated: NoteEventPayload[];
+  removed: string[];
+}
+
+// Sent instead of individual note and asset events when a batch is large;
+// reload the note list once
+export interface BulkChangePayload {
+  created: number;
+  updated: number;
+  deleted: number;
+  renamed: number;
+  assetsCreated: number;
+  assetsDeleted: number;
+}
+
+// import:completed, after files are dropped onto the window
+export interface ImportReport {
+  notes: string[];
+  attachments: { path: string; link: string }[];
+  skipped: { path: string; reason: string }[];
+  linkedInto: string | null; // the open note the links are for
+}
+
 export interface NoteSelectionPayload {
   path: string;
   title: string;
@@ -26,6 +52,7 @@ export type NoteEventType =
   | "note:deleted"
   | "note:saved"
   | "note:list-updated"
+  | "note:list-diff"
   | "note:selected";
 
 /**
@@ -51,6 +78,39 @@ export async function listenToNoteListUpdated(
   });
 }
 
+/**
+ * Listen for incremental note list changes from the file watcher
+ */
+export async function listenToNoteListDiff(
+  handler: (payload: NoteListDiffPayload) => void,
+): Promise<UnlistenFn> {
+  return listen<NoteListDiffPayload>("note:list-diff", (event) => {
+    handler(event.payload);
+  });
+}
+
+/**
+ * Listen for large batches of file changes (checkouts, sync pulls)
+ */
+export async function listenToBulkChange(
+  handler: (payload: BulkChangePayload) => void,
+): Promise<UnlistenFn> {
+  return listen<BulkChangePayload>("vault:bulk-change", (event) => {
+    handler(event.payload);
+  });
+}
+
+/**
+ * Listen for files dropped onto the window being imported
+ */
+export async function listenToImportCompleted(
+  handler: (payload: ImportReport) => void,
+): Promise<UnlistenFn> {
+  return listen<ImportReport>("import:completed", (event) => {
+    handler(event.payload);
+  });
+}
+
 /**
  * Listen for note selection events (frontend-only events)
  */
@@ -80,7 +140,9 @@ export async function listenToNoteEvents(handlers: {
   onDeleted?: (payload: NoteEventPayload) => void;
   onSaved?: (payload: NoteEventPayload) => void;
   onListUpdated?: (payload: NoteListPayload) => void;
+  onListDiff?: (payload: NoteListDiffPayload) => void;
   onSelected?: (payload: NoteSelectionPayload) => void;
+  onBulkChange?: (payload: BulkChangePayload) => void;
 }): Promise<UnlistenFn[]> {
   const unlisteners: Promise<UnlistenFn>[] = [];
 
@@ -99,9 +161,15 @@ export async function listenToNoteEvents(handlers: {
   if (handlers.onListUpdated) {
     unlisteners.push(listenToNoteListUpdated(handlers.onListUpdated));
   }
+  if (handlers.onListDiff) {
+    unlisteners.push(listenToNoteListDiff(handlers.onListDiff));
+  }
   if (handlers.onSelected) {
     unlisteners.push(listenToNoteSelected(handlers.onSelected));
   }
+  if (handlers.onBulkChange) {
+    unlisteners.push(listenToBulkChange(handlers.onBulkChange));
+  }
 
   return Promise.all(unlisteners);
 }
@@ -0,0 +1,74 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type { ShortcutBinding } from "../types";
+
+const isMac = navigator.platform.toUpperCase().includes("MAC");
+
+/**
+ * Whether a keydown matches a binding from list_shortcuts, written like
+ * "CommandOrControl+Shift+K" (the global shortcut plugin's syntax)
+ */
+export function matchesShortcut(e: KeyboardEvent, binding: string): boolean {
+  const parts = binding.split("+").map((part) => part.trim().toLowerCase());
+  const key = parts.pop();
+  if (!key) return false;
+
+  let ctrl = false;
+  let meta = false;
+  let alt = false;
+  let shift = false;
+  for (const modifier of parts) {
+    switch (modifier) {
+      case "commandorcontrol":
+      case "cmdorctrl":
+        if (isMac) meta = true;
+        else ctrl = true;
+        break;
+      case "control":
+      case "ctrl":
+        ctrl = true;
+        break;
+      case "command":
+      case "cmd":
+      case "super":
+      case "meta":
+        meta = true;
+        break;
+      case "alt":
+      case "option":
+        alt = true;
+        break;
+      case "shift":
+        shift = true;
+        break;
+      default:
+        return false;
+    }
+  }
+  if (
+    e.ctrlKey !== ctrl ||
+    e.metaKey !== meta ||
+    e.altKey !== alt ||
+    e.shiftKey !== shift
+  ) {
+    return false;
+  }
+
+  // e.key changes with Alt and Shift on some layouts, so digits and letters
+  // are compared by physical key
+  if (/^[0-9]$/.test(key)) return e.code === `Digit${key}`;
+  if (/^[a-z]$/.test(key)) return e.code === `Key${key.toUpperCase()}`;
+  return e.key.toLowerCase() === key;
+}
+
+export async function listShortcuts(): Promise<ShortcutBinding[]> {
+  return invoke<ShortcutBinding[]>("list_shortcuts");
+}
+
+export async function listenToShortcutsChanged(
+  handler: (shortcuts: ShortcutBinding[]) => void,
+): Promise<UnlistenFn> {
+  return listen<ShortcutBinding[]>("shortcuts:changed", (event) =>
+    handler(event.payload),
+  );
+}